@@ -1,7 +1,18 @@
-use clap::{Parser, Subcommand, ValueEnum};
-use docata::{BuildOptions, Error, OutputFormat, QueryOptions, RelationKind};
-use std::io;
-use std::path::Path;
+use clap::{Args, CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use docata::{
+    AdoptionConflict, AdoptionResolutions, Baseline, BuildOptions, CatalogFormat, CatalogStore,
+    CommonOptions, Config, CriticalOptions, DependencyChange, DirectoryStore, DuplicateIdPolicy,
+    Error, ExportFormat, ExportOptions, FindingsExportOptions, GraphEndpoint, HotspotMetric,
+    HotspotOptions, IssueFormat, ListOptions, Locale, LockPolicy, MergeConflict, MetadataFilter,
+    NewDocument, NoProgress, OutputFormat, ProgressEvent, ProgressSink, QueryOptions,
+    RelationCombine, RelationKind, RemoteCatalogSource, RemoteFetchOptions, ReviewQueueFormat,
+    ReviewQueueOptions, ScannedDocuments, SimulatedChange, StaleOptions, StoreError,
+    SubgraphFormat, SubgraphSelection, SummarizeOptions,
+};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Clone, Copy, Debug, ValueEnum)]
 enum CliOutputFormat {
@@ -9,6 +20,8 @@ enum CliOutputFormat {
     Text,
     #[value(name = "json")]
     Json,
+    #[value(name = "yaml")]
+    Yaml,
 }
 
 impl From<CliOutputFormat> for OutputFormat {
@@ -16,6 +29,267 @@ impl From<CliOutputFormat> for OutputFormat {
         match value {
             CliOutputFormat::Text => Self::Text,
             CliOutputFormat::Json => Self::Json,
+            CliOutputFormat::Yaml => Self::Yaml,
+        }
+    }
+}
+
+/// Output formats of commands listing documents.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliListingFormat {
+    #[value(name = "text")]
+    Text,
+    #[value(name = "json")]
+    Json,
+    #[value(name = "yaml")]
+    Yaml,
+    #[value(name = "ndjson")]
+    Ndjson,
+}
+
+impl From<CliListingFormat> for OutputFormat {
+    fn from(value: CliListingFormat) -> Self {
+        match value {
+            CliListingFormat::Text => Self::Text,
+            CliListingFormat::Json => Self::Json,
+            CliListingFormat::Yaml => Self::Yaml,
+            CliListingFormat::Ndjson => Self::Ndjson,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, ValueEnum)]
+enum CliErrorFormat {
+    #[default]
+    #[value(name = "text")]
+    Text,
+    #[value(name = "json")]
+    Json,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliCheckFormat {
+    #[value(name = "text")]
+    Text,
+    #[value(name = "json")]
+    Json,
+    #[value(name = "yaml")]
+    Yaml,
+    #[value(name = "sarif")]
+    Sarif,
+    /// `JUnit` XML: a test suite per rule, a test case per file.
+    #[value(name = "junit")]
+    Junit,
+}
+
+impl From<CliCheckFormat> for OutputFormat {
+    fn from(value: CliCheckFormat) -> Self {
+        match value {
+            CliCheckFormat::Text => Self::Text,
+            CliCheckFormat::Json => Self::Json,
+            CliCheckFormat::Yaml => Self::Yaml,
+            CliCheckFormat::Sarif => Self::Sarif,
+            CliCheckFormat::Junit => Self::Junit,
+        }
+    }
+}
+
+/// Output formats of commands whose output is a graph.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliGraphFormat {
+    #[value(name = "text")]
+    Text,
+    #[value(name = "json")]
+    Json,
+    #[value(name = "yaml")]
+    Yaml,
+    #[value(name = "dot")]
+    Dot,
+}
+
+impl From<CliGraphFormat> for OutputFormat {
+    fn from(value: CliGraphFormat) -> Self {
+        match value {
+            CliGraphFormat::Text => Self::Text,
+            CliGraphFormat::Json => Self::Json,
+            CliGraphFormat::Yaml => Self::Yaml,
+            CliGraphFormat::Dot => Self::Dot,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliCatalogFormat {
+    #[value(name = "json")]
+    Json,
+    #[value(name = "yaml")]
+    Yaml,
+    /// Binary `MessagePack`; needs the `msgpack` feature.
+    #[value(name = "msgpack")]
+    MessagePack,
+    /// A directory of per-node JSON files plus an edges index.
+    #[value(name = "dir")]
+    Directory,
+}
+
+impl From<CliCatalogFormat> for CatalogFormat {
+    fn from(value: CliCatalogFormat) -> Self {
+        match value {
+            CliCatalogFormat::Json | CliCatalogFormat::Directory => Self::Json,
+            CliCatalogFormat::Yaml => Self::Yaml,
+            CliCatalogFormat::MessagePack => Self::MessagePack,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliExportFormat {
+    #[value(name = "graphml")]
+    GraphMl,
+    #[value(name = "dot")]
+    Dot,
+    /// Backstage `catalog-info.yaml` entities.
+    #[value(name = "backstage")]
+    Backstage,
+    /// A self-contained HTML page with an interactive graph viewer.
+    #[value(name = "html")]
+    Html,
+}
+
+impl From<CliExportFormat> for ExportFormat {
+    fn from(value: CliExportFormat) -> Self {
+        match value {
+            CliExportFormat::GraphMl => Self::GraphMl,
+            CliExportFormat::Dot => Self::Dot,
+            CliExportFormat::Backstage => Self::Backstage,
+            CliExportFormat::Html => Self::Html,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliIndexFormat {
+    /// A Docusaurus `sidebars.json`.
+    #[value(name = "docusaurus")]
+    Docusaurus,
+    /// Hugo `_index.md` section pages.
+    #[value(name = "hugo")]
+    Hugo,
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliSubgraphFormat {
+    #[value(name = "json")]
+    Json,
+    #[value(name = "yaml")]
+    Yaml,
+    #[value(name = "dot")]
+    Dot,
+    #[value(name = "graphml")]
+    GraphMl,
+}
+
+impl From<CliSubgraphFormat> for SubgraphFormat {
+    fn from(value: CliSubgraphFormat) -> Self {
+        match value {
+            CliSubgraphFormat::Json => Self::Catalog(CatalogFormat::Json),
+            CliSubgraphFormat::Yaml => Self::Catalog(CatalogFormat::Yaml),
+            CliSubgraphFormat::Dot => Self::Export(ExportFormat::Dot),
+            CliSubgraphFormat::GraphMl => Self::Export(ExportFormat::GraphMl),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliIssueFormat {
+    #[value(name = "github-issues")]
+    GithubIssues,
+    #[value(name = "jira-csv")]
+    JiraCsv,
+}
+
+impl From<CliIssueFormat> for IssueFormat {
+    fn from(value: CliIssueFormat) -> Self {
+        match value {
+            CliIssueFormat::GithubIssues => Self::GithubIssues,
+            CliIssueFormat::JiraCsv => Self::JiraCsv,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliReviewQueueFormat {
+    #[value(name = "markdown")]
+    Markdown,
+    #[value(name = "json")]
+    Json,
+}
+
+impl From<CliReviewQueueFormat> for ReviewQueueFormat {
+    fn from(value: CliReviewQueueFormat) -> Self {
+        match value {
+            CliReviewQueueFormat::Markdown => Self::Markdown,
+            CliReviewQueueFormat::Json => Self::Json,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliDuplicateIdPolicy {
+    #[value(name = "error")]
+    Error,
+    #[value(name = "keep-first")]
+    KeepFirst,
+    #[value(name = "suffix")]
+    Suffix,
+}
+
+impl From<CliDuplicateIdPolicy> for DuplicateIdPolicy {
+    fn from(value: CliDuplicateIdPolicy) -> Self {
+        match value {
+            CliDuplicateIdPolicy::Error => Self::Error,
+            CliDuplicateIdPolicy::KeepFirst => Self::KeepFirst,
+            CliDuplicateIdPolicy::Suffix => Self::Suffix,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliRelationCombine {
+    #[value(name = "union")]
+    Union,
+    #[value(name = "intersection")]
+    Intersection,
+}
+
+impl From<CliRelationCombine> for RelationCombine {
+    fn from(value: CliRelationCombine) -> Self {
+        match value {
+            CliRelationCombine::Union => Self::Union,
+            CliRelationCombine::Intersection => Self::Intersection,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum CliHotspotMetric {
+    #[value(name = "in-degree")]
+    InDegree,
+    #[value(name = "out-degree")]
+    OutDegree,
+    #[value(name = "dependents")]
+    Dependents,
+    #[value(name = "betweenness")]
+    Betweenness,
+}
+
+impl From<CliHotspotMetric> for HotspotMetric {
+    fn from(value: CliHotspotMetric) -> Self {
+        match value {
+            CliHotspotMetric::InDegree => Self::InDegree,
+            CliHotspotMetric::OutDegree => Self::OutDegree,
+            CliHotspotMetric::Dependents => Self::Dependents,
+            CliHotspotMetric::Betweenness => Self::Betweenness,
         }
     }
 }
@@ -25,119 +299,2055 @@ impl From<CliOutputFormat> for OutputFormat {
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    /// Language of validation reports and error messages, e.g. `ja`;
+    /// defaults to `LC_ALL`, `LC_MESSAGES`, or `LANG`.
+    #[arg(long, global = true, value_parser = parse_locale)]
+    lang: Option<Locale>,
+    /// Write JSON output and JSON catalogs on a single line.
+    #[arg(long, global = true)]
+    compact: bool,
+    /// How a failure is reported on stderr: a message, or one JSON object
+    /// with a stable `code` such as `E001`.
+    #[arg(value_enum, long, global = true, default_value_t = CliErrorFormat::Text)]
+    error_format: CliErrorFormat,
+}
+
+fn parse_locale(tag: &str) -> Result<Locale, String> {
+    Locale::from_tag(tag).ok_or_else(|| format!("unsupported language `{tag}`"))
+}
+
+/// Parse an age in days, weeks (`w`), or years (`y`) into days.
+fn parse_age(age: &str) -> Result<u64, String> {
+    let (count, unit_days) = match age.as_bytes().last() {
+        Some(b'd') => (&age[..age.len() - 1], 1),
+        Some(b'w') => (&age[..age.len() - 1], 7),
+        Some(b'y') => (&age[..age.len() - 1], 365),
+        _ => (age, 1),
+    };
+    count
+        .parse::<u64>()
+        .ok()
+        .and_then(|count| count.checked_mul(unit_days))
+        .ok_or_else(|| format!("invalid age `{age}`; expected e.g. `180d`, `26w`, or `1y`"))
+}
+
+/// An error from a command, rendered in the language and `--error-format`
+/// the command ran with.
+pub struct Failure {
+    message: String,
+}
+
+impl Failure {
+    fn new(
+        error: &Error,
+        locale: Locale,
+        format: CliErrorFormat,
+    ) -> Self {
+        let message = match format {
+            CliErrorFormat::Text => docata::error_message(error, locale),
+            CliErrorFormat::Json => docata::error_json(error),
+        };
+        Self { message }
+    }
+}
+
+impl std::fmt::Display for Failure {
+    fn fmt(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+    ) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
 }
 
 #[derive(Subcommand)]
 enum Commands {
-    Build {
-        #[arg(default_value = "./docs")]
-        dir: String,
-        #[arg(default_value = "./docs/catalog.json")]
-        out_dir: String,
+    Build(BuildArgs),
+    Check(CheckArgs),
+    Deps(DepsArgs),
+    Refs(RefsArgs),
+    /// List the dependencies several documents share.
+    Common(CommonArgs),
+    Show(ShowArgs),
+    List(ListArgs),
+    /// List documents no other document depends on.
+    Roots(ListArgs),
+    /// List documents depending on nothing.
+    Leaves(ListArgs),
+    Stats(StatsArgs),
+    /// Report strongly and weakly connected components of the dependency graph.
+    Components(ComponentsArgs),
+    Critical(CriticalArgs),
+    /// Rank documents by how widely their changes ripple.
+    Hotspots(HotspotsArgs),
+    Simulate(SimulateArgs),
+    Diff(DiffArgs),
+    /// Three-way merge catalogs node by node, as a git merge driver.
+    MergeCatalog(MergeCatalogArgs),
+    Path(PathArgs),
+    Freshness(FreshnessArgs),
+    /// List documents not reviewed, or never reviewed and not modified, for
+    /// longer than an age.
+    Stale(StaleArgs),
+    /// Show who answers for a document: owners, git history, and the
+    /// documents depending on it.
+    Blame(BlameArgs),
+    /// List documents whose files changed since a git ref.
+    Changed(ChangedArgs),
+    /// List dependency cycles among documents without failing on them.
+    Cycles(CyclesArgs),
+    Route(RouteArgs),
+    ReviewQueue(ReviewQueueArgs),
+    ExportFindings(ExportFindingsArgs),
+    Export(ExportArgs),
+    /// Write the neighborhood of one document, or one domain, as a catalog or
+    /// graph file.
+    Subgraph(SubgraphArgs),
+    /// Generate site navigation grouping documents by domain, then type.
+    GenerateIndex(GenerateIndexArgs),
+    Adopt(AdoptArgs),
+    /// Create a document from the template of its type.
+    New(NewArgs),
+    /// Add a dependency to a document's frontmatter `deps`.
+    AddDep(DepEditArgs),
+    /// Remove a dependency from a document's frontmatter `deps`.
+    RmDep(DepEditArgs),
+    Fetch(FetchArgs),
+    /// Check or print the nearest docata.toml.
+    #[command(subcommand)]
+    Config(ConfigCommand),
+    /// Run as a git hook.
+    #[command(subcommand)]
+    Hook(HookCommand),
+    /// Print a shell completion script; document ids complete from the
+    /// catalog.
+    Completions(CompletionsArgs),
+}
+
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct BuildArgs {
+    /// Document roots (default `./docs`); ids must be unique across them.
+    /// Without `--out`, a single root may be followed by the catalog to
+    /// write, unless that is an existing directory, which is a second root.
+    paths: Vec<String>,
+    /// Catalog to write (default `./docs/catalog.json`); every positional
+    /// argument is then a document root.
+    #[arg(long)]
+    out: Option<String>,
+    #[arg(long)]
+    with_node_metadata: bool,
+    /// Record on each `deps` edge the file and line declaring it.
+    #[arg(long)]
+    with_provenance: bool,
+    /// Record a SHA-256 of each document's body on its node.
+    #[arg(long)]
+    with_content_hash: bool,
+    /// Record each document's last commit date and top authors from git.
+    #[arg(long)]
+    with_git_metadata: bool,
+    /// Record each document's owners from the repository's CODEOWNERS file.
+    #[arg(long)]
+    with_code_owners: bool,
+    #[arg(value_enum, long, default_value_t = CliDuplicateIdPolicy::Error)]
+    duplicate_ids: CliDuplicateIdPolicy,
+    /// Catalog format; detected from the output extension when omitted.
+    #[arg(value_enum, long)]
+    format: Option<CliCatalogFormat>,
+    /// Wait for other writers of the catalog to finish (default).
+    #[arg(long, overrides_with = "no_wait")]
+    wait: bool,
+    /// Fail immediately if another writer holds the catalog's lock.
+    #[arg(long, overrides_with = "wait")]
+    no_wait: bool,
+    /// Report Markdown files without frontmatter or without an `id` instead
+    /// of dropping or rejecting them.
+    #[arg(long)]
+    report_skipped: bool,
+    /// Leave out documents that fail to scan, e.g. with malformed
+    /// frontmatter, and warn about them instead of failing the build.
+    #[arg(long)]
+    lenient: bool,
+    /// Show on stderr how many documents have been scanned.
+    #[arg(long)]
+    progress: bool,
+    /// Write a query index to `<catalog>.idx` so `deps` and `refs` read only
+    /// the documents they reach. Builds without it leave an existing index
+    /// stale, and queries then ignore it.
+    #[arg(long)]
+    index: bool,
+    /// Print the build report to stdout in this format instead of warnings
+    /// to stderr.
+    #[arg(value_enum, long)]
+    report: Option<CliOutputFormat>,
+}
+
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct CheckArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    #[arg(long)]
+    catalog: Option<String>,
+    /// Regenerate with (or, with `=false`, without) node metadata; detected
+    /// from the catalog when omitted.
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    with_node_metadata: Option<bool>,
+    /// Regenerate with (or, with `=false`, without) edge provenance; detected
+    /// from the catalog when omitted.
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    with_provenance: Option<bool>,
+    /// Regenerate with (or, with `=false`, without) content hashes; detected
+    /// from the catalog when omitted.
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    with_content_hash: Option<bool>,
+    /// Regenerate with (or, with `=false`, without) git metadata; detected
+    /// from the catalog when omitted.
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    with_git_metadata: Option<bool>,
+    /// Regenerate with (or, with `=false`, without) code owners; detected
+    /// from the catalog when omitted.
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    with_code_owners: Option<bool>,
+    /// Duplicate id policy the catalog was built with.
+    #[arg(value_enum, long, default_value_t = CliDuplicateIdPolicy::Error)]
+    duplicate_ids: CliDuplicateIdPolicy,
+    /// Check every `docata.toml` root under `dir` in parallel.
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "catalog",
+            "with_node_metadata",
+            "duplicate_ids",
+            "orphans",
+            "broken_links",
+            "stale_after",
+            "report_skipped",
+            "lenient",
+            "progress",
+            "baseline",
+            "write_baseline",
+            "fix",
+            "stubs",
+        ]
+    )]
+    recursive: bool,
+    /// Flag documents that no other document depends on.
+    #[arg(long)]
+    orphans: bool,
+    /// Document type exempt from `--orphans`, e.g. `index`.
+    #[arg(long = "orphan-root-type", requires = "orphans")]
+    orphan_root_types: Vec<String>,
+    /// Flag relative body links to missing files or non-catalog documents.
+    #[arg(long)]
+    broken_links: bool,
+    /// Flag documents last reviewed longer ago than this age, e.g. `180d`.
+    #[arg(long, value_parser = parse_age)]
+    stale_after: Option<u64>,
+    /// Warn about Markdown files without frontmatter or without an `id`.
+    #[arg(long)]
+    report_skipped: bool,
+    /// Report every document that fails to scan instead of stopping at the
+    /// first.
+    #[arg(long)]
+    lenient: bool,
+    /// Show on stderr how many documents have been scanned and which checks
+    /// are running.
+    #[arg(long)]
+    progress: bool,
+    /// Ignore findings recorded in this baseline file; only new ones fail.
+    #[arg(long)]
+    baseline: Option<String>,
+    /// Record current findings in this baseline file and pass.
+    #[arg(long, conflicts_with = "baseline")]
+    write_baseline: Option<String>,
+    /// Accepted for compatibility; out-of-date nodes and edges are always
+    /// listed on stderr.
+    #[arg(long, requires = "catalog", hide = true)]
+    explain: bool,
+    /// Rewrite an out-of-date catalog with the regenerated content instead of
+    /// failing, or build it when missing.
+    #[arg(long, requires = "catalog")]
+    write: bool,
+    /// Drop duplicate deps, self-dependencies, and deps on `[validation]
+    /// removed_ids` from the documents before checking them.
+    #[arg(long)]
+    fix: bool,
+    /// Create a stub document (`status: stub`) in this directory, relative to
+    /// `dir`, for every unresolved dependency before checking.
+    #[arg(long, value_name = "DIR")]
+    stubs: Option<String>,
+    #[arg(value_enum, long, default_value_t = CliCheckFormat::Text)]
+    format: CliCheckFormat,
+}
+
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct DepsArgs {
+    /// Ids to query. A single id may still be followed by an existing or
+    /// remote catalog, as in `<ID> <CATALOG>`.
+    #[arg(required = true, value_name = "ID")]
+    ids: Vec<String>,
+    /// Catalog to query (default: ./docs/catalog.json).
+    #[arg(long)]
+    catalog: Option<String>,
+    /// Output format [default: json, or text with --tree].
+    #[arg(value_enum, long)]
+    format: Option<CliListingFormat>,
+    /// Show transitive dependencies as an indented tree; ids already shown
+    /// are marked `(*)` and ids closing a cycle `(cycle)`.
+    #[arg(long, conflicts_with = "with_catalogs")]
+    tree: bool,
+    /// Expand the tree at most this many levels.
+    #[arg(long, requires = "tree")]
+    depth: Option<usize>,
+    #[arg(long)]
+    strict: bool,
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Keep items whose metadata matches `field=value` (type, domain, status,
+    /// `source_of_truth`, or tag). Repeat to combine: values of one field match
+    /// any, different fields must all match.
+    #[arg(long = "filter", value_name = "FIELD=VALUE")]
+    filters: Vec<MetadataFilter>,
+    /// Embed each item's tags, type, domain, status, and `source_of_truth` in
+    /// JSON and YAML output.
+    #[arg(long)]
+    with_node_metadata: bool,
+    /// How to merge the results of several ids.
+    #[arg(value_enum, long, default_value_t = CliRelationCombine::Union)]
+    combine: CliRelationCombine,
+    /// Follow edges of this relation kind, declared under `[relations]` in
+    /// docata.toml, instead of `deps`.
+    #[arg(long)]
+    kind: Option<String>,
+    /// Write items as they are resolved instead of after the whole result;
+    /// JSON output becomes JSON Lines ending with a `summary` line. Applies
+    /// to single-id queries.
+    #[arg(long)]
+    stream: bool,
+    /// Follow relations transitively instead of listing direct neighbours.
+    #[arg(long)]
+    transitive: bool,
+    /// Name the file and line declaring each direct relation, for catalogs
+    /// built with `--with-provenance`.
+    #[arg(long)]
+    with_provenance: bool,
+    /// Another catalog, e.g. of another repository, to query as one graph
+    /// with the first; JSON items then name their catalog. Repeatable.
+    #[arg(long = "with-catalog", value_name = "CATALOG")]
+    with_catalogs: Vec<String>,
+}
+
+#[derive(Args)]
+#[allow(clippy::struct_excessive_bools)]
+struct RefsArgs {
+    /// Ids to query. A single id may still be followed by an existing or
+    /// remote catalog, as in `<ID> <CATALOG>`.
+    #[arg(required = true, value_name = "ID")]
+    ids: Vec<String>,
+    /// Catalog to query (default: ./docs/catalog.json).
+    #[arg(long)]
+    catalog: Option<String>,
+    #[arg(value_enum, long, default_value_t = CliListingFormat::Text)]
+    format: CliListingFormat,
+    #[arg(long)]
+    strict: bool,
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Keep items whose metadata matches `field=value` (type, domain, status,
+    /// `source_of_truth`, or tag). Repeat to combine: values of one field match
+    /// any, different fields must all match.
+    #[arg(long = "filter", value_name = "FIELD=VALUE")]
+    filters: Vec<MetadataFilter>,
+    /// Embed each item's tags, type, domain, status, and `source_of_truth` in
+    /// JSON and YAML output.
+    #[arg(long)]
+    with_node_metadata: bool,
+    /// How to merge the results of several ids.
+    #[arg(value_enum, long, default_value_t = CliRelationCombine::Union)]
+    combine: CliRelationCombine,
+    /// Follow edges of this relation kind, declared under `[relations]` in
+    /// docata.toml, instead of `deps`.
+    #[arg(long)]
+    kind: Option<String>,
+    /// Write items as they are resolved instead of after the whole result;
+    /// JSON output becomes JSON Lines ending with a `summary` line. Applies
+    /// to single-id queries.
+    #[arg(long)]
+    stream: bool,
+    /// Follow relations transitively instead of listing direct neighbours.
+    #[arg(long)]
+    transitive: bool,
+    /// Name the file and line declaring each direct relation, for catalogs
+    /// built with `--with-provenance`.
+    #[arg(long)]
+    with_provenance: bool,
+    /// Another catalog, e.g. of another repository, to query as one graph
+    /// with the first; JSON items then name their catalog. Repeatable.
+    #[arg(long = "with-catalog", value_name = "CATALOG")]
+    with_catalogs: Vec<String>,
+}
+
+#[derive(Args)]
+struct CommonArgs {
+    /// Ids to compare.
+    #[arg(required = true, value_name = "ID")]
+    ids: Vec<String>,
+    #[arg(long, default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+    /// Compare direct dependencies instead of transitive ones.
+    #[arg(long)]
+    direct: bool,
+    /// Keep dependencies shared by at least this many ids instead of all.
+    #[arg(long)]
+    min_shared: Option<usize>,
+}
+
+#[derive(Args)]
+struct ShowArgs {
+    id: String,
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct ListArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long, default_value_t = CliListingFormat::Text)]
+    format: CliListingFormat,
+    #[arg(long = "tag")]
+    tags: Vec<String>,
+    /// Keep only documents last committed before this date (`YYYY-MM-DD`);
+    /// needs a catalog built with `--with-git-metadata`.
+    #[arg(long)]
+    modified_before: Option<String>,
+    /// Keep only documents CODEOWNERS assigns to this user or team; needs a
+    /// catalog built with `--with-code-owners`.
+    #[arg(long)]
+    owner: Option<String>,
+}
+
+#[derive(Args)]
+struct StatsArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct ComponentsArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct CriticalArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    /// Also list documents every dependency path from this id passes through.
+    #[arg(long)]
+    root: Option<String>,
+    /// Show at most this many entries per list.
+    #[arg(long)]
+    limit: Option<usize>,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct HotspotsArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    /// Metric to rank by.
+    #[arg(value_enum, long, default_value_t = CliHotspotMetric::InDegree)]
+    by: CliHotspotMetric,
+    /// Also count transitive dependents; slow on large catalogs.
+    #[arg(long)]
+    dependents: bool,
+    /// Also compute betweenness centrality; slow on large catalogs.
+    #[arg(long)]
+    betweenness: bool,
+    /// Show at most this many documents.
+    #[arg(long)]
+    limit: Option<usize>,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct SimulateArgs {
+    #[command(subcommand)]
+    change: SimulateChange,
+    #[arg(long, global = true, default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long, global = true, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Subcommand)]
+enum SimulateChange {
+    /// Delete a document; dependencies on it become unresolved.
+    Remove { id: String },
+    /// Delete a document and point every dependency on it at another.
+    Retarget {
+        id: String,
         #[arg(long)]
-        with_node_metadata: bool,
+        to: String,
     },
+}
+
+impl From<&SimulateChange> for SimulatedChange {
+    fn from(change: &SimulateChange) -> Self {
+        match change {
+            SimulateChange::Remove { id } => SimulatedChange::Remove { id: id.clone() },
+            SimulateChange::Retarget { id, to } => SimulatedChange::Retarget {
+                id: id.clone(),
+                to: to.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Args)]
+struct DiffArgs {
+    /// Catalog to compare from.
+    old: String,
+    /// Catalog to compare to.
+    new: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct MergeCatalogArgs {
+    /// Common ancestor of the catalog (git's `%O`).
+    base: String,
+    /// Our catalog, overwritten with the merge result (git's `%A`).
+    ours: String,
+    /// Their catalog (git's `%B`).
+    theirs: String,
+    /// Path of the catalog in the repository (git's `%P`); its extension
+    /// sets the format, JSON by default.
+    path: Option<String>,
+}
+
+#[derive(Args)]
+struct PathArgs {
+    from: String,
+    to: String,
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    /// Print every shortest path instead of one.
+    #[arg(long)]
+    all: bool,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct FreshnessArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct StaleArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    /// Age such as `180d`, `26w`, or `1y`; a bare number counts days.
+    #[arg(long, value_parser = parse_age)]
+    older_than: u64,
+    /// Only documents with this status, e.g. `published`.
+    #[arg(long = "status")]
+    statuses: Vec<String>,
+    /// Measure ages as of this date (`YYYY-MM-DD`) instead of today.
+    #[arg(long)]
+    today: Option<String>,
+    #[arg(value_enum, long, default_value_t = CliListingFormat::Text)]
+    format: CliListingFormat,
+}
+
+#[derive(Args)]
+struct BlameArgs {
+    id: String,
+    #[arg(default_value = "./docs")]
+    dir: String,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct ChangedArgs {
+    /// Git ref to compare the work tree with, e.g. `main` or `HEAD~3`.
+    #[arg(long)]
+    since: String,
+    /// Catalog whose documents to list; node paths are read relative to the
+    /// current directory.
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    /// Also list documents depending, directly or transitively, on a changed
+    /// document.
+    #[arg(long)]
+    transitive: bool,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Report parse errors and unknown keys.
     Check {
-        #[arg(default_value = "./docs")]
+        #[arg(default_value = ".")]
         dir: String,
-        #[arg(long)]
-        catalog: Option<String>,
-        #[arg(long)]
-        with_node_metadata: bool,
-    },
-    Deps {
-        id: String,
-        #[arg(default_value = "./docs/catalog.json")]
-        catalog: String,
-        #[arg(value_enum, long, default_value_t = CliOutputFormat::Json)]
+        #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
         format: CliOutputFormat,
+    },
+    /// Print the config file.
+    Print {
+        #[arg(default_value = ".")]
+        dir: String,
+        /// Print the effective configuration with defaults filled in.
         #[arg(long)]
-        strict: bool,
+        resolved: bool,
     },
-    Refs {
-        id: String,
-        #[arg(default_value = "./docs/catalog.json")]
+}
+
+#[derive(Subcommand)]
+enum HookCommand {
+    /// Validate only the staged documents, against the catalog, and check the
+    /// catalog is up to date with them.
+    PreCommit {
+        #[arg(default_value = "./docs")]
+        dir: String,
+        #[arg(long, default_value = "./docs/catalog.json")]
         catalog: String,
         #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
         format: CliOutputFormat,
-        #[arg(long)]
-        strict: bool,
     },
 }
 
+#[derive(Args)]
+struct CyclesArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    #[arg(value_enum, long, default_value_t = CliGraphFormat::Text)]
+    format: CliGraphFormat,
+}
+
+#[derive(Args)]
+struct RouteArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    /// Id of a changed document; its dependents get impact findings.
+    #[arg(long = "changed")]
+    changed: Vec<String>,
+    #[arg(value_enum, long, default_value_t = CliOutputFormat::Text)]
+    format: CliOutputFormat,
+}
+
+#[derive(Args)]
+struct ReviewQueueArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    /// Keep only the highest scoring documents.
+    #[arg(long, default_value_t = 20)]
+    limit: usize,
+    /// Upstream changes within this many days count as recent.
+    #[arg(long, default_value_t = 7)]
+    recent_days: u64,
+    /// Score as of this date (`YYYY-MM-DD`) instead of today.
+    #[arg(long)]
+    today: Option<String>,
+    #[arg(value_enum, long, default_value_t = CliReviewQueueFormat::Markdown)]
+    format: CliReviewQueueFormat,
+}
+
+#[derive(Args)]
+struct ExportFindingsArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    #[arg(value_enum, long)]
+    format: CliIssueFormat,
+    /// JSON file of findings exported by earlier runs; skipped and updated.
+    #[arg(long)]
+    state: Option<String>,
+    /// Prefix for file links in issue bodies, e.g. a repository blob URL.
+    #[arg(long)]
+    link_base: Option<String>,
+}
+
+#[derive(Args)]
+struct GenerateIndexArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long)]
+    format: CliIndexFormat,
+    /// File to write the sidebar to instead of stdout, or the documents
+    /// directory to write Hugo pages into.
+    #[arg(long, required_if_eq("format", "hugo"))]
+    out: Option<String>,
+    /// Documents directory that Docusaurus doc ids are relative to.
+    #[arg(long, default_value = "./docs")]
+    docs_dir: String,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    #[arg(value_enum, long)]
+    format: CliExportFormat,
+    /// Write to this file instead of stdout.
+    #[arg(long)]
+    out: Option<String>,
+    /// Collapse low-degree documents into one group node per domain.
+    #[arg(long)]
+    summarize: bool,
+    /// Keep documents with at least this many edges when summarizing.
+    #[arg(long, default_value_t = 2, requires = "summarize")]
+    min_degree: usize,
+    /// Keep at most this many edges when summarizing.
+    #[arg(long, requires = "summarize")]
+    max_edges: Option<usize>,
+}
+
+#[derive(Args)]
+struct SubgraphArgs {
+    #[arg(default_value = "./docs/catalog.json")]
+    catalog: String,
+    /// Keep this document and its neighbors.
+    #[arg(long, required_unless_present = "domain", conflicts_with = "domain")]
+    focus: Option<String>,
+    /// Follow edges in either direction up to this many steps from --focus.
+    #[arg(long, default_value_t = 1, requires = "focus")]
+    depth: usize,
+    /// Keep the documents of this domain.
+    #[arg(long)]
+    domain: Option<String>,
+    #[arg(value_enum, long, default_value_t = CliSubgraphFormat::Json)]
+    format: CliSubgraphFormat,
+    /// Write to this file instead of stdout.
+    #[arg(long)]
+    out: Option<String>,
+}
+
+#[derive(Args)]
+struct AdoptArgs {
+    #[arg(default_value = "./docs")]
+    dir: String,
+    /// Apply every proposal without prompting; fails on unresolved conflicts.
+    #[arg(long)]
+    yes: bool,
+    /// JSON file with recorded conflict decisions; new decisions are saved to it.
+    #[arg(long)]
+    resolutions: Option<String>,
+}
+
+#[derive(Args)]
+struct NewArgs {
+    /// Id of the new document; must not be in use.
+    id: String,
+    /// Documents directory.
+    #[arg(long, default_value = "./docs")]
+    dir: String,
+    /// Document type, selecting the `[types.<type>]` template and required
+    /// fields of docata.toml.
+    #[arg(long = "type", value_name = "TYPE")]
+    node_type: Option<String>,
+    #[arg(long)]
+    title: Option<String>,
+    /// Status [default: the first of `[validation] statuses`, if set].
+    #[arg(long)]
+    status: Option<String>,
+    /// `last_reviewed` date (YYYY-MM-DD) [default: today].
+    #[arg(long)]
+    date: Option<String>,
+    /// File to create, relative to the documents directory [default: <ID>.md].
+    #[arg(long)]
+    path: Option<String>,
+}
+
+#[derive(Args)]
+struct DepEditArgs {
+    /// Id of the document to edit.
+    id: String,
+    /// Id of the dependency.
+    dep: String,
+    /// Documents directory.
+    #[arg(long, default_value = "./docs")]
+    dir: String,
+    /// Validate the documents directory after the edit, as `check` does.
+    #[arg(long)]
+    check: bool,
+}
+
+#[derive(Args)]
+struct CompletionsArgs {
+    #[arg(value_enum)]
+    shell: Shell,
+}
+
+/// `docata __complete-ids`, called by completion scripts. It is parsed apart
+/// from [`Cli`] so that generated scripts do not list it.
+#[derive(Parser)]
+#[command(name = "docata __complete-ids")]
+struct CompleteIdsArgs {
+    /// Start of the id being typed.
+    #[arg(default_value = "")]
+    prefix: String,
+    #[arg(long, default_value = "./docs/catalog.json")]
+    catalog: String,
+}
+
+#[derive(Args)]
+struct FetchArgs {
+    url: String,
+    /// Directory holding downloaded catalogs.
+    #[arg(long, default_value = ".docata/cache")]
+    cache_dir: String,
+    /// Use the cached copy only, without touching the network.
+    #[arg(long)]
+    offline: bool,
+    /// Attempts before falling back to the cached copy.
+    #[arg(long, default_value_t = 4)]
+    attempts: u32,
+}
+
 /// Run the CLI.
 ///
 /// # Errors
 ///
 /// Returns `Error` when reading catalog files, writing catalog files, or
 /// serializing output fails.
-pub fn run() -> Result<(), Error> {
+pub fn run() -> Result<(), Failure> {
+    if std::env::args().nth(1).as_deref() == Some("__complete-ids") {
+        let args = CompleteIdsArgs::parse_from(std::env::args().skip(1));
+        return complete_ids(&args)
+            .map_err(|error| Failure::new(&error, Locale::from_env(), CliErrorFormat::Text));
+    }
     let cli = Cli::parse();
+    let locale = cli.lang.unwrap_or_else(Locale::from_env);
 
-    match cli.command {
-        Commands::Build {
-            dir,
-            out_dir,
-            with_node_metadata,
-        } => {
-            let dir = Path::new(&dir);
-            let out_dir = Path::new(&out_dir);
-            let mut file = std::fs::File::create(out_dir)?;
-            docata::build_catalog_with_options(
-                dir,
-                &mut file,
-                BuildOptions {
-                    include_node_metadata: with_node_metadata,
-                },
-            )
-        },
-        Commands::Check {
-            dir,
-            catalog,
-            with_node_metadata,
-        } => {
-            let dir = Path::new(&dir);
-            let options = BuildOptions {
-                include_node_metadata: with_node_metadata,
-            };
+    run_command(cli.command, locale, cli.compact)
+        .map_err(|error| Failure::new(&error, locale, cli.error_format))
+}
 
-            if let Some(catalog) = catalog {
-                docata::check_catalog(dir, Path::new(&catalog), options)
+fn run_command(
+    command: Commands,
+    locale: Locale,
+    compact: bool,
+) -> Result<(), Error> {
+    match command {
+        Commands::Build(args) => build(&args, compact),
+        Commands::Check(args) => check(&args, locale, compact),
+        Commands::Deps(args) if args.tree => deps_tree(args, compact),
+        Commands::Deps(args) => query_relation(
+            args.ids,
+            args.catalog,
+            &args.with_catalogs,
+            RelationKind::Deps,
+            args.format.unwrap_or(CliListingFormat::Json),
+            &QueryOptions::new()
+                .strict(args.strict)
+                .tags(args.tags)
+                .filters(args.filters)
+                .include_node_metadata(args.with_node_metadata)
+                .combine(args.combine.into())
+                .edge_kind(args.kind)
+                .stream(args.stream)
+                .transitive(args.transitive)
+                .include_provenance(args.with_provenance),
+            compact,
+        ),
+        Commands::Refs(args) => query_relation(
+            args.ids,
+            args.catalog,
+            &args.with_catalogs,
+            RelationKind::Refs,
+            args.format,
+            &QueryOptions::new()
+                .strict(args.strict)
+                .tags(args.tags)
+                .filters(args.filters)
+                .include_node_metadata(args.with_node_metadata)
+                .combine(args.combine.into())
+                .edge_kind(args.kind)
+                .stream(args.stream)
+                .transitive(args.transitive)
+                .include_provenance(args.with_provenance),
+            compact,
+        ),
+        Commands::Common(args) => common(&args, compact),
+        Commands::Show(args) => show(&args, compact),
+        Commands::List(args) => list(args, compact),
+        Commands::Roots(args) => endpoints(args, GraphEndpoint::Roots, compact),
+        Commands::Leaves(args) => endpoints(args, GraphEndpoint::Leaves, compact),
+        Commands::Stats(args) => stats(&args, compact),
+        Commands::Components(args) => components(&args, compact),
+        Commands::Critical(args) => critical(&args, compact),
+        Commands::Hotspots(args) => hotspots(&args, compact),
+        Commands::Simulate(args) => simulate(&args, compact),
+        Commands::Diff(args) => diff(&args, compact),
+        Commands::MergeCatalog(args) => merge_catalog(&args),
+        Commands::Path(args) => path(&args, compact),
+        Commands::Freshness(args) => freshness(&args, compact),
+        Commands::Stale(args) => stale(&args, compact),
+        Commands::Blame(args) => blame(&args, compact),
+        Commands::Changed(args) => changed(&args, compact),
+        Commands::Cycles(args) => cycles(&args, compact),
+        Commands::Route(args) => route(&args, compact),
+        Commands::ReviewQueue(args) => review_queue(&args),
+        Commands::ExportFindings(args) => export_findings(&args),
+        Commands::Export(args) => export(&args),
+        Commands::GenerateIndex(args) => generate_index(&args),
+        Commands::Subgraph(args) => subgraph(&args, compact),
+        Commands::Config(command) => config(&command, compact),
+        Commands::Hook(command) => hook(&command, compact),
+        Commands::Adopt(args) => adopt(&args),
+        Commands::New(args) => new_document(args),
+        Commands::AddDep(args) => edit_dependency(&args, DependencyChange::Add, locale),
+        Commands::RmDep(args) => edit_dependency(&args, DependencyChange::Remove, locale),
+        Commands::Fetch(args) => fetch(&args),
+        Commands::Completions(args) => completions(&args),
+    }
+}
+
+fn build(
+    args: &BuildArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let (roots, out) = build_paths(args);
+    let roots = roots.iter().map(Path::new).collect::<Vec<_>>();
+    let store: Box<dyn CatalogStore> = match args.format {
+        Some(CliCatalogFormat::Directory) => Box::new(DirectoryStore::new(&out)),
+        _ => docata::open_catalog_store(&out)?,
+    };
+    let catalog_format = args.format.map_or_else(|| store.format(), Into::into);
+    let progress = StderrProgress::default();
+    let report = docata::build_merged_catalog_into_with_progress(
+        &roots,
+        &*store,
+        BuildOptions::new()
+            .include_node_metadata(args.with_node_metadata)
+            .duplicate_id_policy(args.duplicate_ids.into())
+            .catalog_format(catalog_format)
+            .lock_policy(if args.no_wait {
+                LockPolicy::NoWait
             } else {
-                docata::check_catalog_structure(dir)
-            }
+                LockPolicy::Wait
+            })
+            .report_skipped(args.report_skipped)
+            .lenient(args.lenient)
+            .compact(compact)
+            .compression(store.compression())
+            .include_provenance(args.with_provenance)
+            .include_content_hash(args.with_content_hash)
+            .include_git_metadata(args.with_git_metadata)
+            .include_code_owners(args.with_code_owners)
+            .write_index(args.index),
+        if args.progress {
+            &progress
+        } else {
+            &NoProgress
+        },
+    )?;
+
+    match args.report {
+        Some(format) => {
+            docata::write_build_report(
+                &report,
+                output_format(format, compact),
+                &mut io::stdout().lock(),
+            )?;
         },
-        Commands::Deps {
+        None => docata::write_build_report(&report, OutputFormat::Text, &mut io::stderr().lock())?,
+    }
+
+    Ok(())
+}
+
+/// Document roots and output catalog of `build`: every positional argument
+/// is a root, written to `--out` or the default catalog, except that a root
+/// followed by something other than an existing directory is the legacy
+/// `<ROOT> <CATALOG>` form.
+fn build_paths(args: &BuildArgs) -> (Vec<String>, String) {
+    let mut roots = args.paths.clone();
+    let out = match (&args.out, roots.as_slice()) {
+        (Some(out), _) => out.clone(),
+        (None, [_, catalog]) if !Path::new(catalog).is_dir() => roots.pop().unwrap_or_default(),
+        (None, _) => "./docs/catalog.json".to_owned(),
+    };
+    if roots.is_empty() {
+        roots.push("./docs".to_owned());
+    }
+    (roots, out)
+}
+
+/// `format` as single-line JSON when `--compact` is given.
+fn output_format(
+    format: impl Into<OutputFormat>,
+    compact: bool,
+) -> OutputFormat {
+    let format = format.into();
+    if compact { format.compact() } else { format }
+}
+
+/// Progress shown by `--progress`: a line per scanned root and check, and a
+/// running count of parsed documents.
+#[derive(Default)]
+struct StderrProgress {
+    discovered: AtomicUsize,
+    parsed: AtomicUsize,
+}
+
+impl ProgressSink for StderrProgress {
+    fn event(
+        &self,
+        event: &ProgressEvent<'_>,
+    ) {
+        match event {
+            ProgressEvent::FilesDiscovered { location, count } => {
+                self.discovered.fetch_add(*count, Ordering::Relaxed);
+                eprintln!("scanning {count} file(s) in {location}");
+            },
+            ProgressEvent::FileParsed { .. } => {
+                let parsed = self.parsed.fetch_add(1, Ordering::Relaxed) + 1;
+                let discovered = self.discovered.load(Ordering::Relaxed);
+                if parsed == discovered {
+                    eprintln!("\rparsed {parsed}/{discovered}");
+                } else if parsed.is_multiple_of(100) {
+                    eprint!("\rparsed {parsed}/{discovered}");
+                }
+            },
+            ProgressEvent::ValidationStarted { phase } => eprintln!("checking {phase}"),
+            _ => {},
+        }
+    }
+}
+
+fn check(
+    args: &CheckArgs,
+    locale: Locale,
+    compact: bool,
+) -> Result<(), Error> {
+    let dir = Path::new(&args.dir);
+
+    let mut stdout = io::stdout().lock();
+    if args.recursive {
+        docata::check_recursive(dir, output_format(args.format, compact), &mut stdout)?;
+        return Ok(());
+    }
+
+    // Rules from the nearest docata.toml apply; flags only add to them.
+    let mut rules = Config::nearest(dir)?
+        .map(|config| config.validation_rules())
+        .unwrap_or_default();
+    rules.orphans |= args.orphans;
+    rules.broken_links |= args.broken_links;
+    if args.stale_after.is_some() {
+        rules.stale_after_days = args.stale_after;
+    }
+    rules.report_skipped |= args.report_skipped;
+    rules.lenient |= args.lenient;
+    rules
+        .orphan_root_types
+        .extend(args.orphan_root_types.iter().cloned());
+    if args.fix {
+        for fix in docata::fix_dependencies(dir, &rules.removed_ids)? {
+            eprintln!(
+                "fixed {}: dropped {} '{}'",
+                fix.path.display(),
+                fix.reason.as_str(),
+                fix.dep
+            );
+        }
+    }
+    if let Some(stubs) = &args.stubs {
+        for path in docata::create_stubs(dir, Path::new(stubs))? {
+            eprintln!("created {}", path.display());
+        }
+    }
+    // With `--catalog`, documents are scanned once as the catalog is built,
+    // for validation and regeneration alike.
+    let store = args
+        .catalog
+        .as_deref()
+        .map(docata::open_catalog_store)
+        .transpose()?;
+    let options = check_build_options(args, store.as_deref())?
+        .report_skipped(rules.report_skipped)
+        .lenient(rules.lenient);
+    let progress = StderrProgress::default();
+    let progress: &dyn ProgressSink = if args.progress {
+        &progress
+    } else {
+        &NoProgress
+    };
+    let documents = ScannedDocuments::scan(dir, options, progress)?;
+    let mut outcome = documents.validate(&rules, progress);
+    let baseline = match (&args.write_baseline, &args.baseline) {
+        (Some(path), _) => {
+            let baseline = Baseline::from_report(&outcome.report);
+            baseline.save(Path::new(path))?;
+            Some(baseline)
+        },
+        (None, Some(path)) => Some(Baseline::load(Path::new(path))?),
+        (None, None) => None,
+    };
+    if let Some(baseline) = &baseline {
+        baseline.suppress(&mut outcome.report);
+    }
+
+    docata::write_localized_validation_report(
+        &outcome.report,
+        output_format(args.format, compact),
+        locale,
+        &mut stdout,
+    )?;
+    if !outcome.is_success() {
+        return Err(Error::CheckFailed {
+            findings: outcome.report.finding_count(),
+        });
+    }
+
+    if let Some(store) = &store {
+        if args.write {
+            if docata::update_scanned_catalog(&documents, &**store)? {
+                eprintln!("updated {}", store.location());
+            }
+            return Ok(());
+        }
+        let result = docata::check_scanned_catalog(&documents, &**store);
+        if let Err(Error::CatalogDiff {
+            delta: Some(delta), ..
+        }) = &result
+        {
+            docata::write_catalog_delta(delta, OutputFormat::Text, &mut io::stderr().lock())?;
+        }
+        result?;
+    }
+
+    Ok(())
+}
+
+/// The options `check` regenerates the catalog with: those given on the
+/// command line, the rest detected from the catalog in `store`. `--write`
+/// builds a missing catalog with the default layout; otherwise checking it
+/// fails once the documents are validated.
+fn check_build_options(
+    args: &CheckArgs,
+    store: Option<&dyn CatalogStore>,
+) -> Result<BuildOptions, Error> {
+    let detected = match store.map(docata::detect_build_options) {
+        None | Some(Err(Error::Store(StoreError::NotFound { .. }))) => BuildOptions::new(),
+        Some(detected) => detected?,
+    };
+    Ok(BuildOptions::new()
+        .include_node_metadata(
+            args.with_node_metadata
+                .unwrap_or(detected.include_node_metadata),
+        )
+        .include_provenance(args.with_provenance.unwrap_or(detected.include_provenance))
+        .include_content_hash(
+            args.with_content_hash
+                .unwrap_or(detected.include_content_hash),
+        )
+        .include_git_metadata(
+            args.with_git_metadata
+                .unwrap_or(detected.include_git_metadata),
+        )
+        .include_code_owners(
+            args.with_code_owners
+                .unwrap_or(detected.include_code_owners),
+        )
+        .duplicate_id_policy(args.duplicate_ids.into()))
+}
+
+fn query_relation(
+    mut ids: Vec<String>,
+    catalog: Option<String>,
+    with_catalogs: &[String],
+    relation_kind: RelationKind,
+    format: CliListingFormat,
+    options: &QueryOptions,
+    compact: bool,
+) -> Result<(), Error> {
+    let store = docata::open_catalog_store(&split_catalog(&mut ids, catalog))?;
+
+    let mut stdout = io::stdout().lock();
+    if !with_catalogs.is_empty() {
+        let others = with_catalogs
+            .iter()
+            .map(|catalog| docata::open_catalog_store(catalog))
+            .collect::<Result<Vec<_>, Error>>()?;
+        let catalogs = std::iter::once(&store)
+            .chain(&others)
+            .map(|store| &**store)
+            .collect::<Vec<_>>();
+        let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+        return docata::query_federated_relations(
+            &ids,
+            &catalogs,
+            relation_kind,
+            output_format(format, compact),
+            options,
+            &mut stdout,
+        );
+    }
+    if let [id] = ids.as_slice() {
+        return docata::query_catalog_relation_with_options(
+            id,
+            &*store,
+            relation_kind,
+            output_format(format, compact),
+            options,
+            &mut stdout,
+        );
+    }
+
+    let ids = ids.iter().map(String::as_str).collect::<Vec<_>>();
+    docata::query_catalog_relations(
+        &ids,
+        &*store,
+        relation_kind,
+        output_format(format, compact),
+        options,
+        &mut stdout,
+    )
+}
+
+/// Print the dependency tree of each id, one after another.
+fn deps_tree(
+    mut args: DepsArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let store = docata::open_catalog_store(&split_catalog(&mut args.ids, args.catalog))?;
+    let format = output_format(args.format.unwrap_or(CliListingFormat::Text), compact);
+    let options = QueryOptions::new().strict(args.strict).edge_kind(args.kind);
+
+    let mut stdout = io::stdout().lock();
+    for id in &args.ids {
+        docata::query_catalog_tree(
             id,
+            &*store,
+            RelationKind::Deps,
+            args.depth,
+            format,
+            &options,
+            &mut stdout,
+        )?;
+    }
+    Ok(())
+}
+
+/// The catalog of a `deps`/`refs` query: `--catalog`, else the second of
+/// exactly two positional arguments, removed from `ids`, when it names a
+/// catalog, as in the `<ID> <CATALOG>` form; the default catalog otherwise.
+fn split_catalog(
+    ids: &mut Vec<String>,
+    catalog: Option<String>,
+) -> String {
+    catalog
+        .or_else(|| match ids.as_slice() {
+            [_, last] if names_catalog(last) => ids.pop(),
+            _ => None,
+        })
+        .unwrap_or_else(|| "./docs/catalog.json".to_owned())
+}
+
+/// Whether positional argument `argument` names a catalog rather than an
+/// id: a remote location or an existing local file or directory.
+fn names_catalog(argument: &str) -> bool {
+    argument.contains("://") || Path::new(argument).exists()
+}
+
+fn common(
+    args: &CommonArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let store = docata::open_catalog_store(&args.catalog)?;
+    let ids = args.ids.iter().map(String::as_str).collect::<Vec<_>>();
+
+    let mut stdout = io::stdout().lock();
+    docata::find_common_dependencies(
+        &ids,
+        &*store,
+        &CommonOptions {
+            direct: args.direct,
+            min_shared: args.min_shared,
+        },
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn show(
+    args: &ShowArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::show_catalog_node(
+        &args.id,
+        &*docata::open_catalog_store(&args.catalog)?,
+        output_format(args.format, compact),
+        &mut stdout,
+    )
+}
+
+fn list(
+    args: ListArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::list_catalog_nodes(
+        &*docata::open_catalog_store(&args.catalog)?,
+        &ListOptions {
+            tags: args.tags,
+            modified_before: args.modified_before,
+            owner: args.owner,
+        },
+        output_format(args.format, compact),
+        &mut stdout,
+    )
+}
+
+fn endpoints(
+    args: ListArgs,
+    endpoint: GraphEndpoint,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::list_catalog_endpoints(
+        &*docata::open_catalog_store(&args.catalog)?,
+        endpoint,
+        &ListOptions {
+            tags: args.tags,
+            modified_before: args.modified_before,
+            owner: args.owner,
+        },
+        output_format(args.format, compact),
+        &mut stdout,
+    )
+}
+
+fn stats(
+    args: &StatsArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::catalog_stats(
+        &*docata::open_catalog_store(&args.catalog)?,
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn components(
+    args: &ComponentsArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::find_components(
+        &*docata::open_catalog_store(&args.catalog)?,
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn hotspots(
+    args: &HotspotsArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::rank_hotspots(
+        &*docata::open_catalog_store(&args.catalog)?,
+        &HotspotOptions {
+            rank_by: args.by.into(),
+            dependents: args.dependents,
+            betweenness: args.betweenness,
+            limit: args.limit,
+        },
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn critical(
+    args: &CriticalArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::find_critical_documents(
+        &*docata::open_catalog_store(&args.catalog)?,
+        &CriticalOptions {
+            root: args.root.clone(),
+            limit: args.limit,
+        },
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn simulate(
+    args: &SimulateArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::simulate_catalog_change(
+        &*docata::open_catalog_store(&args.catalog)?,
+        &(&args.change).into(),
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn diff(
+    args: &DiffArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::diff_catalogs(
+        &*docata::open_catalog_store(&args.old)?,
+        &*docata::open_catalog_store(&args.new)?,
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn merge_catalog(args: &MergeCatalogArgs) -> Result<(), Error> {
+    let format = args.path.as_deref().map_or(CatalogFormat::Json, |path| {
+        CatalogFormat::from_path(Path::new(path))
+    });
+    let ours = Path::new(&args.ours);
+    let merged = docata::merge_catalogs(
+        &std::fs::read(&args.base)?,
+        &std::fs::read(ours)?,
+        &std::fs::read(&args.theirs)?,
+        format,
+    )?;
+    ours.save(&merged.content)?;
+
+    if merged.conflicts.is_empty() {
+        return Ok(());
+    }
+    let mut stderr = io::stderr().lock();
+    for conflict in &merged.conflicts {
+        match conflict {
+            MergeConflict::Node { id } => {
+                writeln!(stderr, "conflict: node '{id}' changed on both sides")?;
+            },
+            MergeConflict::BuildOptions => {
+                writeln!(
+                    stderr,
+                    "conflict: the catalogs were built with different options"
+                )?;
+            },
+        }
+    }
+    writeln!(
+        stderr,
+        "hint: resolve the documents, then run `docata build` to regenerate the catalog"
+    )?;
+    Err(Error::MergeConflicts {
+        conflicts: merged.conflicts.len(),
+    })
+}
+
+fn fetch(args: &FetchArgs) -> Result<(), Error> {
+    let remote = docata::fetch_remote_catalog(
+        &args.url,
+        &RemoteFetchOptions {
+            cache_dir: PathBuf::from(&args.cache_dir),
+            offline: args.offline,
+            max_attempts: args.attempts,
+            ..RemoteFetchOptions::default()
+        },
+    )?;
+    if let RemoteCatalogSource::Fallback { reason } = &remote.source {
+        eprintln!("warning: using cached copy of '{}' ({reason})", remote.url);
+    }
+
+    println!("{}", remote.path.display());
+    Ok(())
+}
+
+fn path(
+    args: &PathArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::find_catalog_paths(
+        &args.from,
+        &args.to,
+        &*docata::open_catalog_store(&args.catalog)?,
+        args.all,
+        output_format(args.format, compact),
+        &mut stdout,
+    )
+}
+
+fn config(
+    command: &ConfigCommand,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    match command {
+        ConfigCommand::Check { dir, format } => {
+            docata::check_config(Path::new(dir), output_format(*format, compact), &mut stdout)?;
+        },
+        ConfigCommand::Print { dir, resolved } => {
+            docata::print_config(Path::new(dir), *resolved, &mut stdout)?;
+        },
+    }
+    Ok(())
+}
+
+fn changed(
+    args: &ChangedArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::list_changed(
+        &*docata::open_catalog_store(&args.catalog)?,
+        Path::new("."),
+        &args.since,
+        args.transitive,
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn hook(
+    command: &HookCommand,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    match command {
+        HookCommand::PreCommit {
+            dir,
             catalog,
             format,
-            strict,
         } => {
-            let mut stdout = io::stdout().lock();
-            docata::query_catalog_relation_with_options(
-                &id,
-                Path::new(&catalog),
-                RelationKind::Deps,
-                format.into(),
-                QueryOptions { strict },
+            let dir = Path::new(dir);
+            let rules = Config::nearest(dir)?
+                .map(|config| config.validation_rules())
+                .unwrap_or_default();
+            docata::check_staged(
+                dir,
+                &*docata::open_catalog_store(catalog)?,
+                &rules,
+                output_format(*format, compact),
                 &mut stdout,
-            )
+            )?;
+        },
+    }
+    Ok(())
+}
+
+fn cycles(
+    args: &CyclesArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::find_cycles(
+        Path::new(&args.dir),
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn freshness(
+    args: &FreshnessArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::report_freshness(
+        Path::new(&args.dir),
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn stale(
+    args: &StaleArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::report_stale(
+        Path::new(&args.dir),
+        &StaleOptions {
+            older_than_days: args.older_than,
+            statuses: args.statuses.clone(),
+            today: args.today.clone(),
         },
-        Commands::Refs {
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn blame(
+    args: &BlameArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::blame_document(
+        Path::new(&args.dir),
+        &args.id,
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn route(
+    args: &RouteArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    docata::route_findings(
+        Path::new(&args.dir),
+        &args.changed,
+        output_format(args.format, compact),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn review_queue(args: &ReviewQueueArgs) -> Result<(), Error> {
+    let options = ReviewQueueOptions {
+        today: args.today.clone(),
+        recent_days: args.recent_days,
+        limit: Some(args.limit),
+    };
+    let mut stdout = io::stdout().lock();
+    docata::build_review_queue(
+        Path::new(&args.dir),
+        &options,
+        args.format.into(),
+        &mut stdout,
+    )?;
+    Ok(())
+}
+
+fn export_findings(args: &ExportFindingsArgs) -> Result<(), Error> {
+    let options = FindingsExportOptions {
+        state_path: args.state.as_ref().map(PathBuf::from),
+        link_base: args.link_base.clone(),
+    };
+    let mut stdout = io::stdout().lock();
+    let issues = docata::export_findings(
+        Path::new(&args.dir),
+        &options,
+        args.format.into(),
+        &mut stdout,
+    )?;
+    eprintln!("exported {} issue(s)", issues.len());
+    Ok(())
+}
+
+fn export(args: &ExportArgs) -> Result<(), Error> {
+    let catalog = docata::open_catalog_store(&args.catalog)?;
+    let options = ExportOptions {
+        summarize: args.summarize.then_some(SummarizeOptions {
+            min_degree: args.min_degree,
+            max_edges: args.max_edges,
+        }),
+    };
+
+    if let Some(out) = &args.out {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+        docata::export_catalog_with_options(&*catalog, args.format.into(), &options, &mut file)?;
+        file.flush()?;
+        Ok(())
+    } else {
+        let mut stdout = io::stdout().lock();
+        docata::export_catalog_with_options(&*catalog, args.format.into(), &options, &mut stdout)
+    }
+}
+
+fn generate_index(args: &GenerateIndexArgs) -> Result<(), Error> {
+    let catalog = docata::open_catalog_store(&args.catalog)?;
+    match (args.format, &args.out) {
+        (CliIndexFormat::Hugo, out) => {
+            // clap requires --out for Hugo pages.
+            let dir = out.as_deref().unwrap_or(".");
+            for path in docata::generate_section_indexes(&*catalog, Path::new(dir))? {
+                eprintln!("wrote {}", path.display());
+            }
+        },
+        (CliIndexFormat::Docusaurus, Some(out)) => {
+            let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+            docata::generate_sidebar(&*catalog, Path::new(&args.docs_dir), &mut file)?;
+            file.flush()?;
+        },
+        (CliIndexFormat::Docusaurus, None) => {
+            docata::generate_sidebar(
+                &*catalog,
+                Path::new(&args.docs_dir),
+                &mut io::stdout().lock(),
+            )?;
+        },
+    }
+    Ok(())
+}
+
+fn subgraph(
+    args: &SubgraphArgs,
+    compact: bool,
+) -> Result<(), Error> {
+    let catalog = docata::open_catalog_store(&args.catalog)?;
+    // clap requires one of --focus and --domain.
+    let selection = match &args.focus {
+        Some(id) => SubgraphSelection::Focus {
+            id: id.clone(),
+            depth: args.depth,
+        },
+        None => SubgraphSelection::Domain(args.domain.clone().unwrap_or_default()),
+    };
+    let format = args.format.into();
+
+    if let Some(out) = &args.out {
+        let mut file = std::io::BufWriter::new(std::fs::File::create(out)?);
+        docata::extract_subgraph(&*catalog, &selection, format, compact, &mut file)?;
+        file.flush()?;
+        Ok(())
+    } else {
+        let mut stdout = io::stdout().lock();
+        docata::extract_subgraph(&*catalog, &selection, format, compact, &mut stdout)
+    }
+}
+
+fn new_document(args: NewArgs) -> Result<(), Error> {
+    let path = docata::create_document(
+        Path::new(&args.dir),
+        &NewDocument {
+            id: args.id,
+            node_type: args.node_type,
+            title: args.title,
+            status: args.status,
+            date: args.date,
+            path: args.path.map(PathBuf::from),
+        },
+    )?;
+    println!("{}", path.display());
+
+    Ok(())
+}
+
+fn edit_dependency(
+    args: &DepEditArgs,
+    change: DependencyChange,
+    locale: Locale,
+) -> Result<(), Error> {
+    let dir = Path::new(&args.dir);
+    let edit = docata::edit_dependency(dir, &args.id, &args.dep, change)?;
+    let verb = if edit.changed { "updated" } else { "unchanged" };
+    eprintln!("{verb} {}", edit.path.display());
+    if !args.check {
+        return Ok(());
+    }
+
+    let rules = Config::nearest(dir)?
+        .map(|config| config.validation_rules())
+        .unwrap_or_default();
+    let outcome = docata::validate_with_rules(dir, &rules)?;
+    docata::write_localized_validation_report(
+        &outcome.report,
+        OutputFormat::Text,
+        locale,
+        &mut io::stdout().lock(),
+    )?;
+    if !outcome.is_success() {
+        return Err(Error::CheckFailed {
+            findings: outcome.report.finding_count(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Subcommands whose positional arguments are document ids.
+const ID_COMMANDS: &str = "deps refs common show path add-dep rm-dep";
+
+fn completions(args: &CompletionsArgs) -> Result<(), Error> {
+    let mut stdout = io::stdout().lock();
+    clap_complete::generate(args.shell, &mut Cli::command(), "docata", &mut stdout);
+    // Wrap the generated completion so that, after an id subcommand, words
+    // not following an option complete to catalog ids.
+    match args.shell {
+        Shell::Bash => write!(
+            stdout,
+            r#"
+_docata_ids() {{
+    local cur=${{COMP_WORDS[COMP_CWORD]}} prev=${{COMP_WORDS[COMP_CWORD-1]}}
+    if [[ " {ID_COMMANDS} " == *" ${{COMP_WORDS[1]}} "* && $COMP_CWORD -gt 1 && $cur != -* && $prev != -* ]]; then
+        local IFS=$'\n'
+        COMPREPLY=($(docata __complete-ids "$cur" 2>/dev/null))
+        [[ ${{#COMPREPLY[@]}} -gt 0 ]] && return 0
+    fi
+    _docata "$@"
+}}
+complete -F _docata_ids -o bashdefault -o default docata
+"#
+        )?,
+        Shell::Zsh => write!(
+            stdout,
+            r#"
+_docata_ids() {{
+    if (( CURRENT > 2 )) && [[ " {ID_COMMANDS} " == *" $words[2] "* && $PREFIX != -* && $words[CURRENT-1] != -* ]]; then
+        local -a ids
+        ids=(${{(f)"$(docata __complete-ids "$PREFIX" 2>/dev/null)"}})
+        if (( $#ids )); then
+            compadd -a ids
+            return
+        fi
+    fi
+    _docata "$@"
+}}
+compdef _docata_ids docata
+"#
+        )?,
+        Shell::Fish => write!(
+            stdout,
+            "\ncomplete -c docata -n '__fish_seen_subcommand_from {ID_COMMANDS}' -f -a '(docata __complete-ids (commandline -ct) 2>/dev/null)'\n"
+        )?,
+        _ => {},
+    }
+    Ok(())
+}
+
+fn complete_ids(args: &CompleteIdsArgs) -> Result<(), Error> {
+    let store = docata::open_catalog_store(&args.catalog)?;
+    let mut stdout = io::stdout().lock();
+    for id in docata::complete_catalog_ids(&*store, &args.prefix)? {
+        writeln!(stdout, "{id}")?;
+    }
+    Ok(())
+}
+
+fn adopt(args: &AdoptArgs) -> Result<(), Error> {
+    let root = Path::new(&args.dir);
+    let resolutions_path = args.resolutions.as_deref().map(Path::new);
+    let mut resolutions = match resolutions_path {
+        Some(path) if path.exists() => AdoptionResolutions::load(path)?,
+        _ => AdoptionResolutions::default(),
+    };
+    let mut plan = docata::plan_adoption(root, &resolutions)?;
+    let mut stdout = io::stdout().lock();
+
+    if !plan.conflicts.is_empty() {
+        if args.yes {
+            let mut stderr = io::stderr().lock();
+            for conflict in &plan.conflicts {
+                describe_conflict(conflict, &mut stderr)?;
+            }
+            return Err(Error::UnresolvedConflicts {
+                conflicts: plan.conflicts.len(),
+            });
+        }
+
+        // Settle id collisions first so link candidates show the final ids.
+        while !plan.conflicts.is_empty() {
+            let collisions_only = plan
+                .conflicts
+                .iter()
+                .any(|conflict| matches!(conflict, AdoptionConflict::IdCollision { .. }));
+            for conflict in &plan.conflicts {
+                if collisions_only != matches!(conflict, AdoptionConflict::IdCollision { .. }) {
+                    continue;
+                }
+                if !resolve_conflict(conflict, &mut resolutions, &mut stdout)? {
+                    return Ok(());
+                }
+            }
+            plan = docata::plan_adoption(root, &resolutions)?;
+        }
+
+        if let Some(path) = resolutions_path {
+            resolutions.save(path)?;
+            writeln!(stdout, "recorded decisions in {}", path.display())?;
+        }
+    }
+
+    let mut apply_all = args.yes;
+    let mut applied = 0_usize;
+
+    if plan.proposals.is_empty() {
+        writeln!(stdout, "every document already has frontmatter")?;
+        return Ok(());
+    }
+
+    for proposal in &plan.proposals {
+        writeln!(stdout, "{}", proposal.path.display())?;
+        writeln!(stdout, "  id: {}", proposal.id)?;
+        if let Some(title) = &proposal.title {
+            writeln!(stdout, "  title: {title}")?;
+        }
+        if !proposal.deps.is_empty() {
+            writeln!(stdout, "  deps: {}", proposal.deps.join(", "))?;
+        }
+
+        if !apply_all {
+            let Some(answer) = prompt(&mut stdout, "apply? [y]es/[n]o/[a]ll/[q]uit: ")? else {
+                break;
+            };
+            match answer.as_str() {
+                "y" | "yes" => {},
+                "a" | "all" => apply_all = true,
+                "q" | "quit" => break,
+                _ => continue,
+            }
+        }
+
+        docata::apply_adoption(proposal)?;
+        applied += 1;
+    }
+
+    writeln!(
+        stdout,
+        "adopted {applied} of {} document(s)",
+        plan.proposals.len()
+    )?;
+    Ok(())
+}
+
+fn describe_conflict<W: Write>(
+    conflict: &AdoptionConflict,
+    out: &mut W,
+) -> io::Result<()> {
+    match conflict {
+        AdoptionConflict::IdCollision {
             id,
-            catalog,
-            format,
-            strict,
+            existing_path,
+            documents,
         } => {
-            let mut stdout = io::stdout().lock();
-            docata::query_catalog_relation_with_options(
-                &id,
-                Path::new(&catalog),
-                RelationKind::Refs,
-                format.into(),
-                QueryOptions { strict },
-                &mut stdout,
-            )
+            match existing_path {
+                Some(path) => writeln!(
+                    out,
+                    "id collision: `{id}` (already used by {})",
+                    path.display()
+                )?,
+                None => writeln!(out, "id collision: `{id}`")?,
+            }
+            for document in documents {
+                writeln!(out, "  {}", document.path.display())?;
+            }
         },
+        AdoptionConflict::AmbiguousLink {
+            path,
+            target,
+            candidates,
+            ..
+        } => {
+            writeln!(out, "ambiguous link `{target}` in {}", path.display())?;
+            for (index, candidate) in candidates.iter().enumerate() {
+                writeln!(
+                    out,
+                    "  {}) {} ({})",
+                    index + 1,
+                    candidate.id,
+                    candidate.path.display()
+                )?;
+            }
+        },
+    }
+
+    Ok(())
+}
+
+/// Ask for a decision on `conflict` and record it. Returns `false` when input
+/// ends before a decision is made.
+fn resolve_conflict<W: Write>(
+    conflict: &AdoptionConflict,
+    resolutions: &mut AdoptionResolutions,
+    out: &mut W,
+) -> io::Result<bool> {
+    describe_conflict(conflict, out)?;
+
+    match conflict {
+        AdoptionConflict::IdCollision { documents, .. } => {
+            for document in documents {
+                let question = format!("id for {} [{}]: ", document.key, document.suggested_id);
+                let Some(answer) = prompt(out, &question)? else {
+                    return Ok(false);
+                };
+                let id = if answer.is_empty() {
+                    document.suggested_id.clone()
+                } else {
+                    answer
+                };
+                resolutions.ids.insert(document.key.clone(), id);
+            }
+        },
+        AdoptionConflict::AmbiguousLink {
+            key,
+            target,
+            candidates,
+            ..
+        } => {
+            let question = format!(
+                "choose 1-{}, or leave empty to drop the link: ",
+                candidates.len()
+            );
+            let choice = loop {
+                let Some(answer) = prompt(out, &question)? else {
+                    return Ok(false);
+                };
+                if answer.is_empty() {
+                    break None;
+                }
+                if let Some(candidate) = answer
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| candidates.get(index.checked_sub(1)?))
+                {
+                    break Some(candidate.id.clone());
+                }
+            };
+            resolutions
+                .links
+                .entry(key.clone())
+                .or_default()
+                .insert(target.clone(), choice);
+        },
+    }
+
+    Ok(true)
+}
+
+/// Print `question` and read one trimmed line, or `None` at end of input.
+fn prompt<W: Write>(
+    out: &mut W,
+    question: &str,
+) -> io::Result<Option<String>> {
+    write!(out, "{question}")?;
+    out.flush()?;
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer)? == 0 {
+        return Ok(None);
     }
+    Ok(Some(answer.trim().to_owned()))
 }
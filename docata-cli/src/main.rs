@@ -2,7 +2,7 @@ mod app;
 
 fn main() {
     if let Err(err) = app::run() {
-        eprintln!("error: {err}");
+        eprintln!("{err}");
         std::process::exit(1);
     }
 }
@@ -0,0 +1,169 @@
+//! Runs of the `docata` binary against documents in a test workspace.
+
+use docata::testing::{TestWorkspace, write_markdown};
+use std::process::{Command, Output};
+
+/// Run `docata` with `args` in `workspace`.
+fn docata(
+    workspace: &TestWorkspace,
+    args: &[&str],
+) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_docata"))
+        .current_dir(workspace.path())
+        .args(args)
+        .output()
+        .expect("run docata")
+}
+
+fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "docata failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    String::from_utf8(output.stdout.clone()).expect("utf-8")
+}
+
+/// A workspace whose `docs` hold `web` -> `api` -> `db`, built into
+/// `docs/catalog.json`.
+fn built_workspace() -> TestWorkspace {
+    let workspace = TestWorkspace::new();
+    write_markdown(workspace.path(), "docs/web.md", "web", &["api"]);
+    write_markdown(workspace.path(), "docs/api.md", "api", &["db"]);
+    write_markdown(workspace.path(), "docs/db.md", "db", &[]);
+    stdout(&docata(&workspace, &["build", "docs"]));
+    workspace
+}
+
+#[test]
+fn relation_queries_take_the_catalog_by_option_or_after_a_single_id() {
+    let workspace = built_workspace();
+
+    let by_option = stdout(&docata(
+        &workspace,
+        &["deps", "web", "api", "--catalog", "docs/catalog.json"],
+    ));
+    assert!(by_option.contains("\"id\": \"api\""));
+    assert!(by_option.contains("\"id\": \"db\""));
+
+    let positional = stdout(&docata(&workspace, &["deps", "web", "docs/catalog.json"]));
+    assert!(positional.contains("\"id\": \"api\""));
+    assert!(!positional.contains("\"id\": \"db\""));
+
+    let three_ids = stdout(&docata(&workspace, &["refs", "db", "api", "web"]));
+    assert!(three_ids.contains("web"));
+
+    let two_ids = stdout(&docata(&workspace, &["deps", "web", "api"]));
+    assert!(two_ids.contains("\"id\": \"api\""));
+    assert!(two_ids.contains("\"id\": \"db\""));
+    assert!(two_ids.contains("\"groups\""));
+}
+
+#[test]
+fn every_existing_directory_given_to_build_is_a_root() {
+    let workspace = TestWorkspace::new();
+    write_markdown(workspace.path(), "guides/web.md", "web", &["api"]);
+    write_markdown(workspace.path(), "rfcs/api.md", "api", &[]);
+    std::fs::create_dir(workspace.path().join("docs")).expect("create docs directory");
+    let listing = |dir: &str| {
+        let mut names = std::fs::read_dir(workspace.path().join(dir))
+            .expect("read root")
+            .map(|entry| entry.expect("read entry").file_name())
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    };
+    let (guides, rfcs) = (listing("guides"), listing("rfcs"));
+
+    stdout(&docata(&workspace, &["build", "guides", "rfcs"]));
+    assert_eq!(listing("guides"), guides);
+    assert_eq!(listing("rfcs"), rfcs);
+    let deps = stdout(&docata(&workspace, &["deps", "web"]));
+    assert!(deps.contains("\"id\": \"api\""));
+    assert!(deps.contains("\"resolved\": true"));
+
+    let missing_root = docata(&workspace, &["build", "guides", "rfcs", "adr"]);
+    assert!(!missing_root.status.success());
+    assert!(!workspace.path().join("adr").exists());
+
+    stdout(&docata(
+        &workspace,
+        &["build", "guides", "rfcs", "--out", "catalog.json"],
+    ));
+    let deps = stdout(&docata(&workspace, &["deps", "web", "catalog.json"]));
+    assert!(deps.contains("\"resolved\": true"));
+}
+
+#[test]
+fn check_write_builds_missing_and_refreshes_stale_catalogs() {
+    let workspace = TestWorkspace::new();
+    write_markdown(workspace.path(), "docs/web.md", "web", &["api"]);
+    write_markdown(workspace.path(), "docs/api.md", "api", &[]);
+    let check_write = ["check", "docs", "--catalog", "catalog.json", "--write"];
+    let catalog = workspace.path().join("catalog.json");
+
+    let missing = docata(&workspace, &check_write);
+    stdout(&missing);
+    assert!(String::from_utf8_lossy(&missing.stderr).contains("updated catalog.json"));
+    stdout(&docata(
+        &workspace,
+        &["build", "docs", "--out", "built.json"],
+    ));
+    let built = std::fs::read(workspace.path().join("built.json")).expect("read built catalog");
+    assert_eq!(std::fs::read(&catalog).expect("read catalog"), built);
+
+    let up_to_date = docata(&workspace, &check_write);
+    stdout(&up_to_date);
+    assert!(!String::from_utf8_lossy(&up_to_date.stderr).contains("updated"));
+
+    write_markdown(workspace.path(), "docs/db.md", "db", &[]);
+    let stale = docata(&workspace, &check_write);
+    stdout(&stale);
+    assert!(String::from_utf8_lossy(&stale.stderr).contains("updated catalog.json"));
+    stdout(&docata(
+        &workspace,
+        &["check", "docs", "--catalog", "catalog.json"],
+    ));
+    let refs = stdout(&docata(&workspace, &["refs", "api", "catalog.json"]));
+    assert_eq!(refs.trim(), "web");
+}
+
+#[test]
+fn completes_ids_by_prefix_from_the_catalog() {
+    let workspace = built_workspace();
+    write_markdown(workspace.path(), "docs/app.md", "app", &[]);
+    stdout(&docata(&workspace, &["build", "docs"]));
+
+    let all = stdout(&docata(&workspace, &["__complete-ids"]));
+    assert_eq!(all.lines().collect::<Vec<_>>(), ["api", "app", "db", "web"]);
+    let prefixed = stdout(&docata(&workspace, &["__complete-ids", "ap"]));
+    assert_eq!(prefixed.lines().collect::<Vec<_>>(), ["api", "app"]);
+    assert!(stdout(&docata(&workspace, &["__complete-ids", "x"])).is_empty());
+
+    stdout(&docata(&workspace, &["build", "docs", "other.json"]));
+    let elsewhere = stdout(&docata(
+        &workspace,
+        &["__complete-ids", "w", "--catalog", "other.json"],
+    ));
+    assert_eq!(elsewhere, "web\n");
+
+    let missing = docata(
+        &workspace,
+        &["__complete-ids", "w", "--catalog", "missing.json"],
+    );
+    assert!(!missing.status.success());
+    assert!(missing.stdout.is_empty());
+}
+
+#[test]
+fn bash_and_zsh_completions_ask_the_catalog_for_ids() {
+    let workspace = TestWorkspace::new();
+    for shell in ["bash", "zsh"] {
+        let script = stdout(&docata(&workspace, &["completions", shell]));
+        assert!(
+            script.contains("docata __complete-ids"),
+            "{shell} script does not complete ids"
+        );
+        assert!(script.contains("deps refs common show"));
+    }
+}
@@ -0,0 +1,134 @@
+//! Scanning, building, validating, and querying generated corpora of 1k,
+//! 10k, and 100k documents. Corpora are written up front, so pick sizes
+//! with `DOCATA_BENCH_SIZES`, e.g.
+//! `DOCATA_BENCH_SIZES=1000,10000 cargo bench -p docata --features testing`.
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use docata::testing::{Corpus, CorpusOptions, TestWorkspace, generate_corpus};
+use docata::{
+    BuildOptions, OutputFormat, QueryOptions, RelationKind, build_catalog, build_catalog_into,
+    build_validation_report, query_catalog_relation_with_options, scan_documents,
+};
+use std::hint::black_box;
+use std::io;
+use std::path::PathBuf;
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+/// Corpus sizes to benchmark: `DOCATA_BENCH_SIZES`, or every one of
+/// [`SIZES`].
+fn sizes() -> Vec<usize> {
+    std::env::var("DOCATA_BENCH_SIZES").map_or_else(
+        |_| SIZES.to_vec(),
+        |sizes| {
+            sizes
+                .split(',')
+                .map(|size| {
+                    size.trim()
+                        .parse()
+                        .expect("DOCATA_BENCH_SIZES lists numbers")
+                })
+                .collect()
+        },
+    )
+}
+
+/// A corpus of `documents` documents, as generated and written to disk.
+struct Fixture {
+    corpus: Corpus,
+    workspace: TestWorkspace,
+}
+
+impl Fixture {
+    fn new(documents: usize) -> Self {
+        let corpus = generate_corpus(0, &CorpusOptions::realistic(documents));
+        let workspace = TestWorkspace::new();
+        corpus.write(workspace.path());
+        Self { corpus, workspace }
+    }
+
+    fn markdown(&self) -> Vec<(PathBuf, String)> {
+        self.corpus
+            .documents
+            .iter()
+            .map(|document| (document.path.clone(), document.to_markdown()))
+            .collect()
+    }
+}
+
+fn hot_paths(criterion: &mut Criterion) {
+    for documents in sizes() {
+        let fixture = Fixture::new(documents);
+        let markdown = fixture.markdown();
+        let markdown = markdown
+            .iter()
+            .map(|(path, content)| (path.clone(), content.as_str()))
+            .collect::<Vec<_>>();
+        let entries = scan_documents(&markdown).expect("scan corpus");
+        let elements = Throughput::Elements(documents as u64);
+
+        let mut group = criterion.benchmark_group("scan");
+        group.sample_size(10).throughput(elements.clone());
+        group.bench_function(BenchmarkId::from_parameter(documents), |bencher| {
+            bencher.iter(|| scan_documents(black_box(&markdown)).expect("scan corpus"));
+        });
+        group.finish();
+
+        let mut group = criterion.benchmark_group("build");
+        group.sample_size(10).throughput(elements.clone());
+        group.bench_function(BenchmarkId::from_parameter(documents), |bencher| {
+            bencher.iter(|| {
+                let mut catalog = Vec::new();
+                build_catalog(fixture.workspace.path(), &mut catalog).expect("build catalog");
+                catalog
+            });
+        });
+        group.finish();
+
+        let mut group = criterion.benchmark_group("validate");
+        group.sample_size(10).throughput(elements.clone());
+        group.bench_function(BenchmarkId::from_parameter(documents), |bencher| {
+            bencher.iter(|| build_validation_report(black_box(&entries)));
+        });
+        group.finish();
+
+        // Transitive deps of the last document reach deep into the corpus;
+        // the index only helps queries that reach a small part of it.
+        let catalog = fixture.workspace.path().join("catalog.json");
+        let last = format!("doc-{:04}", documents - 1);
+        for (name, write_index) in [("query", false), ("query_indexed", true)] {
+            build_catalog_into(
+                fixture.workspace.path(),
+                &catalog,
+                BuildOptions::new().write_index(write_index),
+            )
+            .expect("build catalog");
+            let mut group = criterion.benchmark_group(name);
+            group.sample_size(10);
+            for (relation_kind, id, transitive) in [
+                (RelationKind::Deps, last.as_str(), true),
+                (RelationKind::Refs, "doc-0000", false),
+            ] {
+                let options = QueryOptions::new().transitive(transitive);
+                let id_in_group = BenchmarkId::new(relation_kind.as_str(), documents);
+                group.bench_function(id_in_group, |bencher| {
+                    bencher.iter(|| {
+                        query_catalog_relation_with_options(
+                            id,
+                            &catalog,
+                            relation_kind,
+                            OutputFormat::Json,
+                            &options,
+                            &mut io::sink(),
+                        )
+                        .expect("query catalog");
+                    });
+                });
+            }
+            group.finish();
+        }
+    }
+}
+
+criterion_group!(benches, hot_paths);
+criterion_main!(benches);
@@ -0,0 +1,23 @@
+//! Write a synthetic documentation tree, as the benchmarks generate, for
+//! profiling the CLI on large corpora:
+//!
+//! ```sh
+//! cargo run -p docata --example generate_corpus --features testing -- 100000 /tmp/docs
+//! ```
+
+use docata::testing::{CorpusOptions, generate_corpus};
+use std::path::Path;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut args = std::env::args().skip(1);
+    let documents = args.next().and_then(|documents| documents.parse().ok());
+    let (Some(documents), Some(root)) = (documents, args.next()) else {
+        eprintln!("usage: generate_corpus <documents> <dir> [seed]");
+        return ExitCode::FAILURE;
+    };
+    let seed = args.next().and_then(|seed| seed.parse().ok()).unwrap_or(0);
+
+    generate_corpus(seed, &CorpusOptions::realistic(documents)).write(Path::new(&root));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,37 @@
+/*
+ * C ABI of the docata library, built with the `ffi` feature:
+ *
+ *     cargo rustc -p docata --release --features ffi --crate-type cdylib
+ *
+ * Every function returns a JSON string the caller frees with
+ * docata_string_free, or NULL with *error set to the error as JSON, freed
+ * the same way. error may be NULL when the message is not needed.
+ */
+#ifndef DOCATA_H
+#define DOCATA_H
+
+#include <stdbool.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Build the catalog of the documents under root into catalog, a path or
+ * store location, with the default options. Returns the build report. */
+char *docata_build_catalog(const char *root, const char *catalog, char **error);
+
+/* Query the dependencies of id in catalog, transitively when transitive is
+ * true. Returns the `query` command's JSON output. */
+char *docata_query_deps(const char *catalog, const char *id, bool transitive, char **error);
+
+/* Query the documents referring to id in catalog, like docata_query_deps. */
+char *docata_query_refs(const char *catalog, const char *id, bool transitive, char **error);
+
+/* Free a string returned by a docata_* function. NULL is ignored. */
+void docata_string_free(char *string);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* DOCATA_H */
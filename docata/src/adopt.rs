@@ -0,0 +1,508 @@
+use crate::json;
+use crate::links::{MarkdownLink, markdown_links, normalize};
+use crate::scan::{self, ScanError};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    io::BufReader,
+    path::{Component, Path, PathBuf},
+};
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// Frontmatter proposed for one Markdown file that has none yet.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AdoptionProposal {
+    /// File that would receive the frontmatter block.
+    pub path: PathBuf,
+    /// Proposed id, derived from the path relative to the scanned root.
+    pub id: String,
+    /// First `# heading` of the file, shown to help review the proposal.
+    pub title: Option<String>,
+    /// Ids of documents the file links to with relative Markdown links.
+    pub deps: Vec<String>,
+}
+
+/// Proposals for every Markdown file without frontmatter under a root.
+///
+/// `conflicts` lists decisions the planner could not make on its own. Until
+/// they are resolved, colliding documents carry suggested ids and ambiguous
+/// links are left out of `deps`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AdoptionPlan {
+    pub proposals: Vec<AdoptionProposal>,
+    pub conflicts: Vec<AdoptionConflict>,
+}
+
+/// A decision `adopt` needs before it can write reproducible frontmatter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AdoptionConflict {
+    /// Several documents, or a document and an existing id, map to the same
+    /// proposed id.
+    IdCollision {
+        id: String,
+        /// File that already owns `id` through its frontmatter, if any.
+        existing_path: Option<PathBuf>,
+        documents: Vec<CollidingDocument>,
+    },
+    /// A link target does not exist relative to the linking file, and its
+    /// file name matches more than one document.
+    AmbiguousLink {
+        path: PathBuf,
+        /// Root-relative `path`, used as the resolution key.
+        key: String,
+        target: String,
+        candidates: Vec<LinkCandidate>,
+    },
+}
+
+/// A document without frontmatter that takes part in an id collision.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CollidingDocument {
+    pub path: PathBuf,
+    /// Root-relative `path`, used as the resolution key.
+    pub key: String,
+    /// Unique id used when no resolution is recorded.
+    pub suggested_id: String,
+}
+
+/// A document an ambiguous link may point to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkCandidate {
+    pub id: String,
+    pub path: PathBuf,
+}
+
+/// Recorded answers to adoption conflicts, keyed by root-relative paths with
+/// `/` separators so the same file can be replayed on every machine.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdoptionResolutions {
+    /// Chosen id per document.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub ids: BTreeMap<String, String>,
+    /// Chosen dependency per document and link target; `null` drops the link.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub links: BTreeMap<String, BTreeMap<String, Option<String>>>,
+}
+
+impl AdoptionResolutions {
+    /// Read resolutions from a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AdoptError` when the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, AdoptError> {
+        let content = fs::read_to_string(path).map_err(|source| AdoptError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_str(&content).map_err(|source| AdoptError::ResolutionsJson {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Write resolutions to a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AdoptError` when serializing or writing fails.
+    pub fn save(
+        &self,
+        path: &Path,
+    ) -> Result<(), AdoptError> {
+        let content =
+            json::to_string(self, false).map_err(|source| AdoptError::ResolutionsJson {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        fs::write(path, content).map_err(|source| AdoptError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum AdoptError {
+    #[error("failed to read directory entries in '{root}': {source}")]
+    WalkDir {
+        root: PathBuf,
+        #[source]
+        source: walkdir::Error,
+    },
+    #[error("failed to read file '{path}': {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to write file '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("file '{path}' already starts with a frontmatter block")]
+    AlreadyAdopted { path: PathBuf },
+    #[error("failed to render frontmatter for '{path}': {source}")]
+    Render {
+        path: PathBuf,
+        #[source]
+        source: yaml_serde::Error,
+    },
+    #[error("failed to process resolutions file '{path}': {source}")]
+    ResolutionsJson {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error("resolved id '{id}' for '{key}' is already taken")]
+    ResolvedIdTaken { id: String, key: String },
+    #[error("{0}")]
+    Scan(#[from] ScanError),
+}
+
+#[derive(Serialize)]
+struct ProposedFrontmatter<'a> {
+    id: &'a str,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    deps: &'a [String],
+}
+
+struct Document {
+    path: PathBuf,
+    key: String,
+    content: String,
+}
+
+/// Scan `root` for Markdown files without frontmatter and propose ids and
+/// deps for them, applying recorded `resolutions` to conflicts.
+///
+/// Links to files that already have frontmatter resolve to their existing id.
+///
+/// # Errors
+///
+/// Returns `AdoptError` when walking the directory, reading files, or parsing
+/// existing frontmatter fails, or when a resolved id is already taken.
+pub fn plan(
+    root: &Path,
+    resolutions: &AdoptionResolutions,
+) -> Result<AdoptionPlan, AdoptError> {
+    let existing = scan::scan(root)?;
+    let existing_paths = existing
+        .iter()
+        .map(|entry| (entry.id.clone(), entry.path.clone()))
+        .collect::<HashMap<_, _>>();
+    let mut ids_by_path = existing
+        .into_iter()
+        .map(|entry| (normalize(&entry.path), entry.id))
+        .collect::<HashMap<_, _>>();
+
+    let mut documents = Vec::new();
+    for path in markdown_paths(root)? {
+        let content = fs::read_to_string(&path).map_err(|source| AdoptError::Read {
+            path: path.clone(),
+            source,
+        })?;
+        if has_frontmatter(&content) {
+            continue;
+        }
+
+        let key = resolution_key(root, &path);
+        documents.push(Document { path, key, content });
+    }
+
+    let mut conflicts = assign_ids(&documents, resolutions, &existing_paths, &mut ids_by_path)?;
+    let ids_by_file_name = index_file_names(&ids_by_path);
+
+    let mut proposals = Vec::with_capacity(documents.len());
+    for document in documents {
+        let id = ids_by_path[&normalize(&document.path)].clone();
+        let base = document.path.parent().unwrap_or(Path::new(""));
+        let recorded_links = resolutions.links.get(&document.key);
+        let mut deps = BTreeSet::new();
+
+        for target in markdown_links(&document.content)
+            .into_iter()
+            .filter(MarkdownLink::is_markdown)
+            .map(|link| link.target)
+        {
+            if let Some(dep) = ids_by_path.get(&normalize(&base.join(&target))) {
+                deps.insert(dep.clone());
+                continue;
+            }
+            if let Some(choice) = recorded_links.and_then(|links| links.get(&target)) {
+                deps.extend(choice.iter().cloned());
+                continue;
+            }
+
+            let file_name = Path::new(&target)
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            match ids_by_file_name.get(&file_name).map(Vec::as_slice) {
+                None | Some([]) => {},
+                Some([candidate]) => {
+                    deps.insert(candidate.id.clone());
+                },
+                Some(candidates) => {
+                    let conflict = AdoptionConflict::AmbiguousLink {
+                        path: document.path.clone(),
+                        key: document.key.clone(),
+                        target,
+                        candidates: candidates.to_vec(),
+                    };
+                    if !conflicts.contains(&conflict) {
+                        conflicts.push(conflict);
+                    }
+                },
+            }
+        }
+
+        deps.remove(&id);
+        let title = scan::read_first_heading(
+            &mut BufReader::new(document.content.as_bytes()),
+            &document.path,
+        )?;
+
+        proposals.push(AdoptionProposal {
+            path: document.path,
+            id,
+            title,
+            deps: deps.into_iter().collect(),
+        });
+    }
+
+    Ok(AdoptionPlan {
+        proposals,
+        conflicts,
+    })
+}
+
+/// Give every document an id, recording collisions that lack a resolution
+/// as conflicts with suggested ids.
+fn assign_ids(
+    documents: &[Document],
+    resolutions: &AdoptionResolutions,
+    existing_paths: &HashMap<String, PathBuf>,
+    ids_by_path: &mut HashMap<PathBuf, String>,
+) -> Result<Vec<AdoptionConflict>, AdoptError> {
+    let mut taken_ids = ids_by_path.values().cloned().collect::<BTreeSet<_>>();
+    let mut unresolved = BTreeMap::<String, Vec<&Document>>::new();
+    for document in documents {
+        if let Some(id) = resolutions.ids.get(&document.key) {
+            if !taken_ids.insert(id.clone()) {
+                return Err(AdoptError::ResolvedIdTaken {
+                    id: id.clone(),
+                    key: document.key.clone(),
+                });
+            }
+            ids_by_path.insert(normalize(&document.path), id.clone());
+        } else {
+            unresolved
+                .entry(propose_id(Path::new(&document.key)))
+                .or_default()
+                .push(document);
+        }
+    }
+
+    let mut conflicts = Vec::new();
+    for (id, group) in &unresolved {
+        if group.len() == 1 && !taken_ids.contains(id) {
+            taken_ids.insert(id.clone());
+            ids_by_path.insert(normalize(&group[0].path), id.clone());
+        }
+    }
+    for (id, group) in unresolved {
+        if ids_by_path.contains_key(&normalize(&group[0].path)) {
+            continue;
+        }
+
+        let mut colliding = Vec::with_capacity(group.len());
+        for document in group {
+            let suggested_id = unique_id(id.clone(), &taken_ids);
+            taken_ids.insert(suggested_id.clone());
+            ids_by_path.insert(normalize(&document.path), suggested_id.clone());
+            colliding.push(CollidingDocument {
+                path: document.path.clone(),
+                key: document.key.clone(),
+                suggested_id,
+            });
+        }
+        conflicts.push(AdoptionConflict::IdCollision {
+            existing_path: existing_paths.get(&id).cloned(),
+            id,
+            documents: colliding,
+        });
+    }
+
+    Ok(conflicts)
+}
+
+fn index_file_names(
+    ids_by_path: &HashMap<PathBuf, String>
+) -> BTreeMap<String, Vec<LinkCandidate>> {
+    let mut ids_by_file_name = BTreeMap::<String, Vec<LinkCandidate>>::new();
+    for (path, id) in ids_by_path {
+        if let Some(file_name) = path.file_name() {
+            ids_by_file_name
+                .entry(file_name.to_string_lossy().into_owned())
+                .or_default()
+                .push(LinkCandidate {
+                    id: id.clone(),
+                    path: path.clone(),
+                });
+        }
+    }
+    for candidates in ids_by_file_name.values_mut() {
+        candidates.sort_by(|left, right| left.path.cmp(&right.path));
+    }
+
+    ids_by_file_name
+}
+
+/// Prepend the proposed frontmatter block to the proposal's file.
+///
+/// # Errors
+///
+/// Returns `AdoptError` when the file cannot be read or written, or when it
+/// gained a frontmatter block since the plan was made.
+pub fn apply(proposal: &AdoptionProposal) -> Result<(), AdoptError> {
+    let content = fs::read_to_string(&proposal.path).map_err(|source| AdoptError::Read {
+        path: proposal.path.clone(),
+        source,
+    })?;
+    if has_frontmatter(&content) {
+        return Err(AdoptError::AlreadyAdopted {
+            path: proposal.path.clone(),
+        });
+    }
+
+    let frontmatter = yaml_serde::to_string(&ProposedFrontmatter {
+        id: &proposal.id,
+        deps: &proposal.deps,
+    })
+    .map_err(|source| AdoptError::Render {
+        path: proposal.path.clone(),
+        source,
+    })?;
+
+    fs::write(&proposal.path, format!("---\n{frontmatter}---\n{content}")).map_err(|source| {
+        AdoptError::Write {
+            path: proposal.path.clone(),
+            source,
+        }
+    })
+}
+
+fn markdown_paths(root: &Path) -> Result<Vec<PathBuf>, AdoptError> {
+    let mut paths = Vec::new();
+
+    for entry in WalkDir::new(root) {
+        let entry = entry.map_err(|source| AdoptError::WalkDir {
+            root: root.to_path_buf(),
+            source,
+        })?;
+        if entry.file_type().is_file() && entry.path().extension().is_some_and(|ext| ext == "md") {
+            paths.push(entry.into_path());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+fn resolution_key(
+    root: &Path,
+    path: &Path,
+) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn has_frontmatter(content: &str) -> bool {
+    content
+        .lines()
+        .next()
+        .is_some_and(|line| line.trim() == "---")
+}
+
+/// Derive an id from a relative path: `guides/Getting Started.md` becomes
+/// `guides-getting-started`, and `index.md`/`README.md` take their
+/// directory's name.
+fn propose_id(relative: &Path) -> String {
+    let mut parts = relative
+        .with_extension("")
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    if parts.len() > 1
+        && parts.last().is_some_and(|last| {
+            last.eq_ignore_ascii_case("index") || last.eq_ignore_ascii_case("readme")
+        })
+    {
+        parts.pop();
+    }
+
+    let mut id = String::new();
+    for character in parts.join("-").chars() {
+        if character.is_alphanumeric() {
+            id.extend(character.to_lowercase());
+        } else if !id.is_empty() && !id.ends_with('-') {
+            id.push('-');
+        }
+    }
+
+    let id = id.trim_end_matches('-');
+    if id.is_empty() {
+        "doc".to_owned()
+    } else {
+        id.to_owned()
+    }
+}
+
+fn unique_id(
+    id: String,
+    taken: &BTreeSet<String>,
+) -> String {
+    if !taken.contains(&id) {
+        return id;
+    }
+
+    let mut suffix = 2_usize;
+    loop {
+        let candidate = format!("{id}-{suffix}");
+        if !taken.contains(&candidate) {
+            return candidate;
+        }
+        suffix += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::propose_id;
+    use std::path::Path;
+
+    #[test]
+    fn proposes_ids_from_relative_paths() {
+        assert_eq!(
+            propose_id(Path::new("guides/Getting Started.md")),
+            "guides-getting-started"
+        );
+        assert_eq!(propose_id(Path::new("billing/README.md")), "billing");
+        assert_eq!(propose_id(Path::new("index.md")), "index");
+    }
+}
@@ -0,0 +1,319 @@
+use crate::{
+    format::CatalogFormat,
+    scan::ScanError,
+    source::{DocumentSource, FileSystemSource},
+    store::{CatalogLock, CatalogStore, LockPolicy, StoreError},
+};
+use std::{
+    future::Future,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
+};
+
+/// Persistence for a serialized catalog, for async code.
+///
+/// The async counterpart of [`CatalogStore`]. Local paths and `HttpStore`
+/// implement it, running their blocking I/O on tokio's blocking pool, so
+/// `Path::new("docs/catalog.json")` can be passed wherever a store is
+/// expected.
+pub trait AsyncCatalogStore: Sync {
+    /// Human-readable location, used in messages and errors.
+    fn location(&self) -> String;
+
+    /// Serialization format of the stored catalog.
+    fn format(&self) -> CatalogFormat;
+
+    /// Read the stored catalog bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError` when the catalog cannot be read.
+    fn load(&self) -> impl Future<Output = Result<Vec<u8>, StoreError>> + Send;
+
+    /// Replace the stored catalog with `content`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError` when the catalog cannot be written.
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> impl Future<Output = Result<(), StoreError>> + Send;
+
+    /// Take the exclusive write lock, like [`CatalogStore::lock`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError::Locked` when `policy` is [`LockPolicy::NoWait`]
+    /// and another writer holds the lock.
+    fn lock(
+        &self,
+        _policy: LockPolicy,
+    ) -> impl Future<Output = Result<Option<CatalogLock>, StoreError>> + Send {
+        async { Ok(None) }
+    }
+}
+
+impl AsyncCatalogStore for Path {
+    fn location(&self) -> String {
+        CatalogStore::location(self)
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogStore::format(self)
+    }
+
+    async fn load(&self) -> Result<Vec<u8>, StoreError> {
+        tokio::fs::read(self)
+            .await
+            .map_err(|source| StoreError::Read {
+                location: CatalogStore::location(self),
+                source,
+            })
+    }
+
+    async fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        let path = self.to_path_buf();
+        let content = content.to_vec();
+        unblock(move || CatalogStore::save(&path, &content)).await
+    }
+
+    async fn lock(
+        &self,
+        policy: LockPolicy,
+    ) -> Result<Option<CatalogLock>, StoreError> {
+        let path = self.to_path_buf();
+        unblock(move || CatalogStore::lock(&path, policy)).await
+    }
+}
+
+impl AsyncCatalogStore for PathBuf {
+    fn location(&self) -> String {
+        AsyncCatalogStore::location(self.as_path())
+    }
+
+    fn format(&self) -> CatalogFormat {
+        AsyncCatalogStore::format(self.as_path())
+    }
+
+    async fn load(&self) -> Result<Vec<u8>, StoreError> {
+        AsyncCatalogStore::load(self.as_path()).await
+    }
+
+    async fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        AsyncCatalogStore::save(self.as_path(), content).await
+    }
+
+    async fn lock(
+        &self,
+        policy: LockPolicy,
+    ) -> Result<Option<CatalogLock>, StoreError> {
+        AsyncCatalogStore::lock(self.as_path(), policy).await
+    }
+}
+
+#[cfg(feature = "http")]
+impl AsyncCatalogStore for crate::HttpStore {
+    fn location(&self) -> String {
+        CatalogStore::location(self)
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogStore::format(self)
+    }
+
+    async fn load(&self) -> Result<Vec<u8>, StoreError> {
+        let store = self.clone();
+        unblock(move || CatalogStore::load(&store)).await
+    }
+
+    async fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        let store = self.clone();
+        let content = content.to_vec();
+        unblock(move || CatalogStore::save(&store, &content)).await
+    }
+}
+
+/// Where scanning reads Markdown documents from, for async code.
+///
+/// The async counterpart of [`DocumentSource`], for documents behind an
+/// async client, such as a CMS API. [`crate::build_catalog_from_async_source`]
+/// reads every document before parsing them together.
+pub trait AsyncDocumentSource: Sync {
+    /// Human-readable location, used in messages and errors.
+    fn location(&self) -> String;
+
+    /// Paths of the Markdown documents, recorded as the catalog's node paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` when the documents cannot be listed.
+    fn documents(&self) -> impl Future<Output = io::Result<Vec<PathBuf>>> + Send;
+
+    /// Read the document at `path`, one of
+    /// [`AsyncDocumentSource::documents`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` when the document cannot be read.
+    fn read(
+        &self,
+        path: &Path,
+    ) -> impl Future<Output = io::Result<Vec<u8>>> + Send;
+
+    /// Directory the documents live under, like [`DocumentSource::root`].
+    fn root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+impl AsyncDocumentSource for FileSystemSource {
+    fn location(&self) -> String {
+        DocumentSource::location(self)
+    }
+
+    async fn documents(&self) -> io::Result<Vec<PathBuf>> {
+        let source = self.clone();
+        unblock(move || DocumentSource::documents(&source)).await
+    }
+
+    async fn read(
+        &self,
+        path: &Path,
+    ) -> io::Result<Vec<u8>> {
+        tokio::fs::read(path).await
+    }
+
+    fn root(&self) -> Option<&Path> {
+        DocumentSource::root(self)
+    }
+}
+
+/// The documents of an [`AsyncDocumentSource`], read up front so the
+/// synchronous scan can parse them on the blocking pool. A document that
+/// failed to read fails when opened, as it would have from the source.
+pub(crate) struct Fetched {
+    location: String,
+    root: Option<PathBuf>,
+    documents: Vec<(PathBuf, io::Result<Vec<u8>>)>,
+}
+
+impl Fetched {
+    /// Read every document of `source`, one after another.
+    pub(crate) async fn read<A: AsyncDocumentSource + ?Sized>(
+        source: &A
+    ) -> Result<Self, ScanError> {
+        let paths = source
+            .documents()
+            .await
+            .map_err(|error| ScanError::ListDocuments {
+                location: source.location(),
+                source: error,
+            })?;
+        let mut documents = Vec::with_capacity(paths.len());
+        for path in paths {
+            let content = source.read(&path).await;
+            documents.push((path, content));
+        }
+
+        Ok(Self {
+            location: source.location(),
+            root: source.root().map(Path::to_path_buf),
+            documents,
+        })
+    }
+}
+
+impl DocumentSource for Fetched {
+    fn location(&self) -> String {
+        self.location.clone()
+    }
+
+    fn documents(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self
+            .documents
+            .iter()
+            .map(|(path, _)| path.clone())
+            .collect())
+    }
+
+    fn open(
+        &self,
+        path: &Path,
+    ) -> io::Result<Box<dyn BufRead + '_>> {
+        match self.documents.iter().find(|(document, _)| document == path) {
+            Some((_, Ok(content))) => Ok(Box::new(content.as_slice())),
+            Some((_, Err(error))) => Err(io::Error::new(error.kind(), error.to_string())),
+            None => Err(io::Error::from(io::ErrorKind::NotFound)),
+        }
+    }
+
+    fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+}
+
+/// Run blocking `work` on tokio's blocking pool, resuming its panic if it
+/// panics.
+pub(crate) async fn unblock<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    match tokio::task::spawn_blocking(work).await {
+        Ok(value) => value,
+        Err(error) => std::panic::resume_unwind(error.into_panic()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::{TestWorkspace, write_markdown};
+    use crate::{
+        BuildOptions, Catalog, FileSystemSource, OutputFormat, QueryOptions, RelationKind,
+        build_catalog_from_async_source, query_catalog_relation_async,
+    };
+
+    #[test]
+    fn builds_loads_and_queries_catalogs_asynchronously() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "api.md", "api", &["db"]);
+        write_markdown(workspace.path(), "db.md", "db", &["cache"]);
+        write_markdown(workspace.path(), "cache.md", "cache", &[]);
+        let store = workspace.path().join("catalog.json");
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("build runtime");
+
+        runtime.block_on(async {
+            build_catalog_from_async_source(
+                &FileSystemSource::new(workspace.path()),
+                &store,
+                BuildOptions::default(),
+            )
+            .await
+            .expect("build catalog");
+
+            let catalog = Catalog::load_async(&store).await.expect("load catalog");
+            assert_eq!(catalog.nodes().count(), 3);
+
+            let mut out = Vec::new();
+            query_catalog_relation_async(
+                "api",
+                &store,
+                RelationKind::Deps,
+                OutputFormat::Text,
+                &QueryOptions::new().transitive(true),
+                &mut out,
+            )
+            .await
+            .expect("query catalog");
+            assert_eq!(String::from_utf8(out).expect("utf-8"), "cache\ndb\n");
+        });
+    }
+}
@@ -0,0 +1,226 @@
+use crate::catalog::{Catalog, Node};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use thiserror::Error;
+
+/// Node types exported as Backstage `Resource` entities; every other
+/// document becomes a `Component`.
+const RESOURCE_TYPES: [&str; 5] = ["resource", "database", "queue", "bucket", "storage"];
+/// Longest entity name Backstage accepts.
+const MAX_NAME_LENGTH: usize = 63;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Entity<'a> {
+    api_version: &'static str,
+    kind: &'static str,
+    metadata: Metadata<'a>,
+    spec: Spec<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Metadata<'a> {
+    name: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<&'a str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<String>,
+    annotations: BTreeMap<&'static str, &'a str>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Spec<'a> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    lifecycle: &'a str,
+    owner: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    depends_on: Vec<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum BackstagePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+}
+
+/// Write a catalog as Backstage `catalog-info.yaml` entities, one YAML
+/// document per node.
+///
+/// Nodes become `Component` entities, or `Resource` entities when their
+/// `type` names a resource such as `database`, with their `deps` edges as
+/// `dependsOn` relations. Edges to ids missing from the catalog are left out.
+///
+/// # Errors
+///
+/// Returns `BackstagePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    catalog: &Catalog,
+    out: &mut W,
+) -> Result<(), BackstagePresentationError> {
+    let names = catalog
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), (entity_kind(node), entity_name(&node.id))))
+        .collect::<HashMap<_, _>>();
+    let mut depends_on = HashMap::<&str, Vec<String>>::new();
+    for edge in catalog.dependency_edges() {
+        if let Some((kind, name)) = names.get(edge.to.as_str()) {
+            depends_on
+                .entry(edge.from.as_str())
+                .or_default()
+                .push(format!("{}:{name}", kind.to_ascii_lowercase()));
+        }
+    }
+
+    for node in &catalog.nodes {
+        let kind = entity_kind(node);
+        let name = &names[node.id.as_str()].1;
+        let mut annotations = BTreeMap::from([("docata/id", node.id.as_str())]);
+        annotations.insert("docata/path", node.path.as_str());
+        if let Some(domain) = &node.domain {
+            annotations.insert("docata/domain", domain);
+        }
+        let mut depends_on = depends_on.remove(node.id.as_str()).unwrap_or_default();
+        depends_on.sort();
+        depends_on.dedup();
+
+        let entity = Entity {
+            api_version: "backstage.io/v1alpha1",
+            kind,
+            metadata: Metadata {
+                name,
+                title: node.title.as_deref(),
+                tags: node.tags.iter().map(|tag| entity_tag(tag)).collect(),
+                annotations,
+            },
+            spec: Spec {
+                kind: node.kind.as_deref().unwrap_or("documentation"),
+                lifecycle: node.status.as_deref().unwrap_or("unknown"),
+                owner: node
+                    .owners
+                    .first()
+                    .map_or_else(|| "unknown".to_owned(), |owner| owner_ref(owner)),
+                depends_on,
+            },
+        };
+        writeln!(out, "---")?;
+        yaml_serde::to_writer(&mut *out, &entity)?;
+    }
+
+    Ok(())
+}
+
+fn entity_kind(node: &Node) -> &'static str {
+    if node
+        .kind
+        .as_deref()
+        .is_some_and(|kind| RESOURCE_TYPES.contains(&kind))
+    {
+        "Resource"
+    } else {
+        "Component"
+    }
+}
+
+/// `id` as a Backstage entity name: letters, digits, `-`, `_`, and `.`,
+/// starting and ending with a letter or digit.
+fn entity_name(id: &str) -> String {
+    let name = id
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() || matches!(char, '-' | '_' | '.') {
+                char
+            } else {
+                '-'
+            }
+        })
+        .take(MAX_NAME_LENGTH)
+        .collect::<String>();
+    name.trim_matches(|char: char| !char.is_ascii_alphanumeric())
+        .to_owned()
+}
+
+/// `tag` as a Backstage tag: lowercase letters, digits, `-`, `+`, and `#`.
+fn entity_tag(tag: &str) -> String {
+    tag.chars()
+        .map(|char| match char.to_ascii_lowercase() {
+            char @ ('a'..='z' | '0'..='9' | '-' | '+' | '#') => char,
+            _ => '-',
+        })
+        .collect()
+}
+
+/// A CODEOWNERS owner as a Backstage owner reference: `@org/team` becomes
+/// `group:team` and `@user` becomes `user:user`.
+fn owner_ref(owner: &str) -> String {
+    match owner.strip_prefix('@') {
+        Some(owner) => match owner.split_once('/') {
+            Some((_, team)) => format!("group:{team}"),
+            None => format!("user:{owner}"),
+        },
+        None => owner.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn node(
+        id: &str,
+        kind: Option<&str>,
+    ) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: kind.map(str::to_owned),
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn maps_nodes_to_entities_with_depends_on() {
+        let mut api = node("billing/api", Some("service"));
+        api.status = Some("production".to_owned());
+        api.owners = vec!["@org/billing".to_owned()];
+        let catalog = Catalog {
+            nodes: vec![api, node("db", Some("database"))],
+            edges: ["db", "missing"]
+                .into_iter()
+                .map(|to| Edge {
+                    from: "billing/api".to_owned(),
+                    to: to.to_owned(),
+                    kind: None,
+                    declared_at: None,
+                })
+                .collect(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let mut output = Vec::new();
+        write(&catalog, &mut output).expect("write backstage entities");
+        let yaml = String::from_utf8(output).expect("valid utf-8");
+
+        assert_eq!(yaml.matches("---\n").count(), 2);
+        assert!(yaml.contains("kind: Component\nmetadata:\n  name: billing-api\n"));
+        assert!(yaml.contains("  owner: group:billing\n  dependsOn:\n  - resource:db\n"));
+        assert!(yaml.contains("kind: Resource\nmetadata:\n  name: db\n"));
+        assert!(!yaml.contains("missing\n"));
+    }
+}
@@ -0,0 +1,259 @@
+use crate::json;
+use crate::scan::ScanDiagnostic;
+use crate::validate::{
+    BrokenLink, DependencyCycle, DomainViolation, DuplicateId, LapsedReview, MissingFields,
+    OrphanNode, UnknownStatus, UnresolvedDependency, ValidationReport,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Findings recorded as known, so only new findings fail a check.
+///
+/// Findings are keyed by rule and the ids or paths involved, not by line
+/// numbers, so unrelated edits do not resurface them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    #[serde(default)]
+    findings: BTreeSet<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum BaselineError {
+    #[error("failed to read baseline '{path}': {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to write baseline '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid baseline '{path}': {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+impl Baseline {
+    /// Record every finding of `report`.
+    #[must_use]
+    pub fn from_report(report: &ValidationReport) -> Self {
+        Self {
+            findings: finding_keys(report).into_iter().collect(),
+        }
+    }
+
+    /// Load a baseline file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BaselineError` when the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        let content = fs::read_to_string(path).map_err(|source| BaselineError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        serde_json::from_str(&content).map_err(|source| BaselineError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Write the baseline as pretty JSON, one finding per line.
+    ///
+    /// # Errors
+    ///
+    /// Returns `BaselineError` when the file cannot be written.
+    pub fn save(
+        &self,
+        path: &Path,
+    ) -> Result<(), BaselineError> {
+        let content = json::to_string(self, false).map_err(|source| BaselineError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        fs::write(path, content).map_err(|source| BaselineError::Write {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Number of recorded findings.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.findings.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.findings.is_empty()
+    }
+
+    /// Remove recorded findings from `report`, counting them in
+    /// [`ValidationReport::suppressed_count`].
+    pub fn suppress(
+        &self,
+        report: &mut ValidationReport,
+    ) {
+        let before = report.finding_count();
+        let known = |key: String| self.findings.contains(&key);
+
+        report
+            .duplicate_ids
+            .retain(|finding| !known(duplicate_id_key(finding)));
+        report
+            .unresolved_dependencies
+            .retain(|finding| !known(unresolved_dependency_key(finding)));
+        report
+            .dependency_cycles
+            .retain(|finding| !known(dependency_cycle_key(finding)));
+        report
+            .orphan_nodes
+            .retain(|finding| !known(orphan_node_key(finding)));
+        report
+            .domain_violations
+            .retain(|finding| !known(domain_violation_key(finding)));
+        report
+            .broken_links
+            .retain(|finding| !known(broken_link_key(finding)));
+        report
+            .missing_fields
+            .retain(|finding| !known(missing_fields_key(finding)));
+        report
+            .unknown_statuses
+            .retain(|finding| !known(unknown_status_key(finding)));
+        report
+            .lapsed_reviews
+            .retain(|finding| !known(lapsed_review_key(finding)));
+        report
+            .invalid_documents
+            .retain(|finding| !known(invalid_document_key(finding)));
+
+        report.suppressed_count += before - report.finding_count();
+    }
+}
+
+fn finding_keys(report: &ValidationReport) -> Vec<String> {
+    let mut keys = Vec::with_capacity(report.finding_count());
+    keys.extend(report.duplicate_ids.iter().map(duplicate_id_key));
+    keys.extend(
+        report
+            .unresolved_dependencies
+            .iter()
+            .map(unresolved_dependency_key),
+    );
+    keys.extend(report.dependency_cycles.iter().map(dependency_cycle_key));
+    keys.extend(report.orphan_nodes.iter().map(orphan_node_key));
+    keys.extend(report.domain_violations.iter().map(domain_violation_key));
+    keys.extend(report.broken_links.iter().map(broken_link_key));
+    keys.extend(report.missing_fields.iter().map(missing_fields_key));
+    keys.extend(report.unknown_statuses.iter().map(unknown_status_key));
+    keys.extend(report.lapsed_reviews.iter().map(lapsed_review_key));
+    keys.extend(report.invalid_documents.iter().map(invalid_document_key));
+    keys
+}
+
+fn duplicate_id_key(finding: &DuplicateId) -> String {
+    format!("duplicate-id:{}", finding.id)
+}
+
+fn unresolved_dependency_key(finding: &UnresolvedDependency) -> String {
+    format!(
+        "unresolved-dependency:{}->{}",
+        finding.from_id, finding.to_id
+    )
+}
+
+fn dependency_cycle_key(finding: &DependencyCycle) -> String {
+    format!("dependency-cycle:{}", finding.ids.join(","))
+}
+
+fn orphan_node_key(finding: &OrphanNode) -> String {
+    format!("orphan-node:{}", finding.id)
+}
+
+fn domain_violation_key(finding: &DomainViolation) -> String {
+    format!(
+        "forbidden-domain-dependency:{}->{}",
+        finding.from_id, finding.to_id
+    )
+}
+
+fn broken_link_key(finding: &BrokenLink) -> String {
+    format!(
+        "broken-link:{}:{}",
+        finding.path.trim_start_matches("./"),
+        finding.target
+    )
+}
+
+fn missing_fields_key(finding: &MissingFields) -> String {
+    format!(
+        "missing-required-field:{}:{}",
+        finding.id,
+        finding.fields.join(",")
+    )
+}
+
+fn unknown_status_key(finding: &UnknownStatus) -> String {
+    format!("unknown-status:{}:{}", finding.id, finding.status)
+}
+
+fn lapsed_review_key(finding: &LapsedReview) -> String {
+    format!("lapsed-review:{}:{}", finding.id, finding.last_reviewed)
+}
+
+fn invalid_document_key(finding: &ScanDiagnostic) -> String {
+    format!(
+        "invalid-document:{}",
+        finding.path.to_string_lossy().trim_start_matches("./")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Baseline;
+    use crate::validate::{OrphanNode, UnresolvedDependency, ValidationReport};
+
+    fn unresolved(to_id: &str) -> UnresolvedDependency {
+        UnresolvedDependency {
+            from_id: "guide".to_owned(),
+            to_id: to_id.to_owned(),
+            path: "docs/guide.md".to_owned(),
+        }
+    }
+
+    #[test]
+    fn suppresses_only_recorded_findings() {
+        let recorded = ValidationReport {
+            unresolved_dependencies: vec![unresolved("legacy")],
+            orphan_nodes: vec![OrphanNode {
+                id: "old".to_owned(),
+                path: "docs/old.md".to_owned(),
+            }],
+            ..ValidationReport::default()
+        };
+        let baseline = Baseline::from_report(&recorded);
+        assert_eq!(baseline.len(), 2);
+
+        let mut report = ValidationReport {
+            unresolved_dependencies: vec![unresolved("legacy"), unresolved("new")],
+            ..ValidationReport::default()
+        };
+        baseline.suppress(&mut report);
+
+        assert_eq!(report.unresolved_dependencies.len(), 1);
+        assert_eq!(report.unresolved_dependencies[0].to_id, "new");
+        assert_eq!(report.suppressed_count, 1);
+    }
+}
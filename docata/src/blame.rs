@@ -0,0 +1,112 @@
+use crate::{
+    error::Error,
+    scan::{ScanOptions, scan_with_options},
+};
+use std::path::{Path, PathBuf};
+
+/// Who answers for a document: its metadata, owners, git history, and the
+/// documents depending on it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct BlameReport {
+    pub id: String,
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub node_type: Option<String>,
+    pub status: Option<String>,
+    /// The frontmatter `owner`.
+    pub owner: Option<String>,
+    /// Owners the repository's CODEOWNERS file assigns the document to.
+    pub code_owners: Vec<String>,
+    /// Date (`YYYY-MM-DD`) the document was last reviewed.
+    pub last_reviewed: Option<String>,
+    /// Date (`YYYY-MM-DD`) of the latest commit touching the document;
+    /// `None` outside a git work tree.
+    pub last_modified: Option<String>,
+    /// Authors with the most commits touching the document, most first.
+    pub authors: Vec<String>,
+    /// Ids of documents whose `deps` list the document, sorted.
+    pub refs: Vec<String>,
+}
+
+/// Gather the accountability view of document `id` under `root`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when no document has the id, or `Error`
+/// when scanning documents fails.
+pub fn run(
+    root: &Path,
+    id: &str,
+) -> Result<BlameReport, Error> {
+    let entries = scan_with_options(
+        root,
+        ScanOptions {
+            git_metadata: true,
+            code_owners: true,
+            ..ScanOptions::default()
+        },
+    )?
+    .entries;
+    let Some(entry) = entries.iter().find(|entry| entry.id == id) else {
+        return Err(Error::NodeNotFound { id: id.to_owned() });
+    };
+
+    let mut refs = entries
+        .iter()
+        .filter(|other| other.deps.iter().any(|dep| dep == id))
+        .map(|other| other.id.clone())
+        .collect::<Vec<_>>();
+    refs.sort();
+    refs.dedup();
+
+    let history = entry.history.as_ref();
+    Ok(BlameReport {
+        id: entry.id.clone(),
+        path: entry.path.clone(),
+        title: entry.title.clone(),
+        node_type: entry.node_type.clone(),
+        status: entry.status.clone(),
+        owner: entry.owner.clone(),
+        code_owners: entry.code_owners.clone(),
+        last_reviewed: entry.last_reviewed.clone(),
+        last_modified: history.map(|history| history.last_modified.clone()),
+        authors: history
+            .map(|history| history.authors.clone())
+            .unwrap_or_default(),
+        refs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use crate::error::Error;
+    use crate::testing::{TestWorkspace, write_markdown};
+    use std::fs;
+
+    #[test]
+    fn gathers_owners_and_inbound_refs() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path();
+        fs::write(
+            docs.join("db.md"),
+            "---\nid: db\nstatus: published\nowner: data-team\nlast_reviewed: 2026-01-05\n---\n",
+        )
+        .expect("write db");
+        fs::write(docs.join("CODEOWNERS"), "*.md @org/docs\ndb.md @org/data\n")
+            .expect("write CODEOWNERS");
+        write_markdown(docs, "web.md", "web", &["db"]);
+        write_markdown(docs, "api.md", "api", &["db", "web"]);
+
+        let report = run(docs, "db").expect("blame db");
+
+        assert_eq!(report.owner.as_deref(), Some("data-team"));
+        assert_eq!(report.code_owners, ["@org/data"]);
+        assert_eq!(report.last_reviewed.as_deref(), Some("2026-01-05"));
+        assert_eq!(report.refs, ["api", "web"]);
+        assert!(matches!(
+            run(docs, "missing"),
+            Err(Error::NodeNotFound { .. })
+        ));
+    }
+}
@@ -0,0 +1,119 @@
+use crate::blame::BlameReport;
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct BlameReportJson<'a> {
+    command: &'static str,
+    id: &'a str,
+    path: String,
+    title: Option<&'a str>,
+    #[serde(rename = "type")]
+    node_type: Option<&'a str>,
+    status: Option<&'a str>,
+    owner: Option<&'a str>,
+    code_owners: &'a [String],
+    last_reviewed: Option<&'a str>,
+    last_modified: Option<&'a str>,
+    authors: &'a [String],
+    refs: &'a [String],
+}
+
+impl<'a> From<&'a BlameReport> for BlameReportJson<'a> {
+    fn from(report: &'a BlameReport) -> Self {
+        Self {
+            command: "blame",
+            id: &report.id,
+            path: report.path.to_string_lossy().to_string(),
+            title: report.title.as_deref(),
+            node_type: report.node_type.as_deref(),
+            status: report.status.as_deref(),
+            owner: report.owner.as_deref(),
+            code_owners: &report.code_owners,
+            last_reviewed: report.last_reviewed.as_deref(),
+            last_modified: report.last_modified.as_deref(),
+            authors: &report.authors,
+            refs: &report.refs,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BlamePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a document's accountability view according to the selected output
+/// format.
+///
+/// # Errors
+///
+/// Returns `BlamePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &BlameReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), BlamePresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &BlameReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &BlameReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(BlamePresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_text<W: Write>(
+    report: &BlameReport,
+    out: &mut W,
+) -> Result<(), BlamePresentationError> {
+    writeln!(out, "id: {}", report.id)?;
+    writeln!(out, "path: {}", report.path.display())?;
+
+    let fields = [
+        ("title", report.title.as_deref()),
+        ("type", report.node_type.as_deref()),
+        ("status", report.status.as_deref()),
+        ("owner", report.owner.as_deref()),
+        ("last_reviewed", report.last_reviewed.as_deref()),
+        ("last_modified", report.last_modified.as_deref()),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            writeln!(out, "{name}: {value}")?;
+        }
+    }
+    let lists = [
+        ("code owners", &report.code_owners),
+        ("authors", &report.authors),
+    ];
+    for (name, values) in lists {
+        if !values.is_empty() {
+            writeln!(out, "{name}: {}", values.join(", "))?;
+        }
+    }
+
+    writeln!(out, "refs: {}", report.refs.len())?;
+    for id in &report.refs {
+        writeln!(out, "  - {id}")?;
+    }
+
+    Ok(())
+}
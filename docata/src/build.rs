@@ -1,20 +1,569 @@
-use crate::{BuildOptions, catalog::Catalog, catalog_presentation, error::Error, scan::scan};
-use std::io::Write;
+use crate::{
+    BuildOptions,
+    catalog::{Catalog, CatalogWarning},
+    catalog_presentation::{self, CatalogPresentationError},
+    config::{CONFIG_FILE_NAME, Config, RelationDefinition, find_config_file},
+    error::Error,
+    format::{CatalogFormat, Compression},
+    hash,
+    progress::{NoProgress, ProgressSink},
+    scan::{Entry, ScanDiagnostic, SkippedFile, scan_source, scan_with_progress},
+    source::DocumentSource,
+};
+use serde::{Deserialize, Serialize, de::IgnoredAny};
+use std::collections::BTreeMap;
+use std::io::{self, Write};
 use std::path::Path;
 
+/// Frontmatter field producing `deps` edges, which every catalog has.
+pub(crate) const DEPS_EDGE_SOURCE: &str = "deps";
+
+/// Summary of a catalog build.
+#[derive(Debug, Default)]
+pub struct BuildReport {
+    pub warnings: Vec<CatalogWarning>,
+    /// Only populated when [`BuildOptions::report_skipped`] is enabled.
+    pub skipped: Vec<SkippedFile>,
+    /// Files left out because they failed to scan. Only populated when
+    /// [`BuildOptions::lenient`] is enabled.
+    pub diagnostics: Vec<ScanDiagnostic>,
+}
+
+/// Options recorded in a catalog's `build_options` block, so `check` can
+/// report an option mismatch instead of a byte diff.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RecordedOptions {
+    pub with_node_metadata: bool,
+    pub duplicate_ids: String,
+    pub edge_sources: Vec<String>,
+    /// Hash of the nearest `docata.toml` at or above the docs root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_hash: Option<String>,
+    /// Whether edges record where their `deps` entry is declared.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub with_provenance: bool,
+    /// Whether nodes record a hash of their document's body.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub with_content_hash: bool,
+    /// Whether nodes record their document's git history.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub with_git_metadata: bool,
+    /// Whether nodes record their owners from CODEOWNERS.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub with_code_owners: bool,
+}
+
+impl RecordedOptions {
+    #[must_use]
+    pub fn new(
+        root: &Path,
+        options: BuildOptions,
+    ) -> Self {
+        Self::for_root(Some(root), options)
+    }
+
+    /// Options for documents under `root`, or with no `docata.toml` when
+    /// they come from outside the filesystem.
+    fn for_root(
+        root: Option<&Path>,
+        options: BuildOptions,
+    ) -> Self {
+        Self {
+            with_node_metadata: options.include_node_metadata,
+            duplicate_ids: options.duplicate_id_policy.as_str().to_owned(),
+            edge_sources: edge_sources(&root.map(relations).unwrap_or_default()),
+            config_hash: root.and_then(config_hash),
+            with_provenance: options.include_provenance,
+            with_content_hash: options.include_content_hash,
+            with_git_metadata: options.include_git_metadata,
+            with_code_owners: options.include_code_owners,
+        }
+    }
+
+    /// Read the block recorded in serialized catalog `content`, if any.
+    #[must_use]
+    pub fn read(
+        content: &[u8],
+        format: CatalogFormat,
+    ) -> Option<Self> {
+        #[derive(Deserialize)]
+        struct Header {
+            build_options: Option<RecordedOptions>,
+        }
+
+        let header: Header = catalog_presentation::decode(content, format).ok()?;
+        header.build_options
+    }
+
+    /// Describe how `requested` differs from the options recorded in `self`.
+    #[must_use]
+    pub fn mismatches(
+        &self,
+        requested: &Self,
+    ) -> Vec<String> {
+        let mut mismatches = Vec::new();
+
+        if self.with_node_metadata != requested.with_node_metadata {
+            mismatches.push(if self.with_node_metadata {
+                "catalog was built with --with-node-metadata, check invoked without".to_owned()
+            } else {
+                "catalog was built without --with-node-metadata, check invoked with it".to_owned()
+            });
+        }
+        for (flag, built, checked) in [
+            (
+                "--with-provenance",
+                self.with_provenance,
+                requested.with_provenance,
+            ),
+            (
+                "--with-content-hash",
+                self.with_content_hash,
+                requested.with_content_hash,
+            ),
+            (
+                "--with-git-metadata",
+                self.with_git_metadata,
+                requested.with_git_metadata,
+            ),
+            (
+                "--with-code-owners",
+                self.with_code_owners,
+                requested.with_code_owners,
+            ),
+        ] {
+            match (built, checked) {
+                (true, false) => {
+                    mismatches.push(format!(
+                        "catalog was built with {flag}, check invoked without"
+                    ));
+                },
+                (false, true) => mismatches.push(format!(
+                    "catalog was built without {flag}, check invoked with it"
+                )),
+                _ => {},
+            }
+        }
+        if self.duplicate_ids != requested.duplicate_ids {
+            mismatches.push(format!(
+                "catalog was built with --duplicate-ids {}, check invoked with --duplicate-ids {}",
+                self.duplicate_ids, requested.duplicate_ids
+            ));
+        }
+        if self.edge_sources != requested.edge_sources {
+            mismatches.push(format!(
+                "catalog was built from edge sources [{}], check uses [{}]",
+                self.edge_sources.join(", "),
+                requested.edge_sources.join(", ")
+            ));
+        }
+        match (&self.config_hash, &requested.config_hash) {
+            (Some(recorded), Some(current)) if recorded != current => {
+                mismatches.push(format!(
+                    "{CONFIG_FILE_NAME} changed since the catalog was built"
+                ));
+            },
+            (Some(_), None) => {
+                mismatches.push(format!(
+                    "catalog was built with a {CONFIG_FILE_NAME}, none was found for check"
+                ));
+            },
+            (None, Some(_)) => {
+                mismatches.push(format!(
+                    "catalog was built without a {CONFIG_FILE_NAME}, check found one"
+                ));
+            },
+            _ => {},
+        }
+
+        mismatches
+    }
+}
+
+/// Detect whether serialized catalog `content` was built with node metadata.
+///
+/// The recorded `build_options` block wins; older catalogs without one are
+/// judged by the shape of their first node, since metadata-ful nodes always
+/// carry a `title` key. Empty or unreadable catalogs count as without.
+#[must_use]
+pub fn detect_node_metadata(
+    content: &[u8],
+    format: CatalogFormat,
+) -> bool {
+    // Only the keys of each node matter.
+    #[allow(clippy::zero_sized_map_values)]
+    #[derive(Deserialize)]
+    struct Shape {
+        #[serde(default)]
+        nodes: Vec<BTreeMap<String, IgnoredAny>>,
+    }
+
+    if let Some(recorded) = RecordedOptions::read(content, format) {
+        return recorded.with_node_metadata;
+    }
+
+    catalog_presentation::decode::<Shape>(content, format)
+        .ok()
+        .and_then(|shape| shape.nodes.into_iter().next())
+        .is_some_and(|node| node.contains_key("title"))
+}
+
+/// Detect whether serialized catalog `content`, compressed or not, was
+/// written as single-line JSON. YAML and `MessagePack` catalogs are never
+/// compact.
+#[must_use]
+pub fn detect_compact(
+    content: &[u8],
+    format: CatalogFormat,
+) -> bool {
+    let Ok(content) = catalog_presentation::decompress(content) else {
+        return false;
+    };
+    format == CatalogFormat::Json
+        && !content.is_empty()
+        && !content.trim_ascii_end().contains(&b'\n')
+}
+
 /// Build catalog from documents under `root` and write it to `out`.
 ///
 /// # Errors
 ///
-/// Returns `Error` when scanning fails or JSON serialization fails.
+/// Returns `Error` when scanning fails, duplicate ids are rejected, or
+/// serialization fails.
 pub fn run<W: Write>(
     root: &Path,
     out: &mut W,
     options: BuildOptions,
-) -> Result<(), Error> {
-    let entries = scan(root)?;
-    let catalog = Catalog::from_entries(&entries);
+) -> Result<BuildReport, Error> {
+    run_roots(&[root], out, options)
+}
+
+/// Build one catalog from the documents under every root in `roots` and
+/// write it to `out`. Ids must be unique across roots, as within one.
+///
+/// Relation kinds and the recorded config hash come from the `docata.toml`
+/// nearest the first root.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails, duplicate ids are rejected, or
+/// serialization fails.
+pub fn run_roots<W: Write>(
+    roots: &[&Path],
+    out: &mut W,
+    options: BuildOptions,
+) -> Result<BuildReport, Error> {
+    run_roots_with_progress(roots, out, options, &NoProgress)
+}
+
+/// Build like [`run_roots`], reporting scan progress to `progress`.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails, duplicate ids are rejected, or
+/// serialization fails.
+pub fn run_roots_with_progress<W: Write>(
+    roots: &[&Path],
+    out: &mut W,
+    options: BuildOptions,
+    progress: &dyn ProgressSink,
+) -> Result<BuildReport, Error> {
+    let built = build_roots(roots, options, progress)?;
+    built.write(out)?;
+    Ok(built.report)
+}
+
+/// A catalog built from scanned documents, not yet written.
+pub(crate) struct Built {
+    catalog: Catalog,
+    recorded: RecordedOptions,
+    options: BuildOptions,
+    pub(crate) report: BuildReport,
+}
+
+impl Built {
+    /// Write the catalog to `out` in the format, layout, and compression
+    /// its options ask for.
+    pub(crate) fn write<W: Write + ?Sized>(
+        &self,
+        out: &mut W,
+    ) -> Result<(), CatalogPresentationError> {
+        self.write_compressed(out, self.options.compression)
+    }
+
+    /// Whether serialized catalog `content` holds what [`Built::write`]
+    /// writes, once decompressed. The catalog is compared as it is
+    /// serialized, without rendering it into memory.
+    pub(crate) fn matches(
+        &self,
+        content: &[u8],
+    ) -> Result<bool, CatalogPresentationError> {
+        let Ok(content) = catalog_presentation::decompress(content) else {
+            return Ok(false);
+        };
+        let mut expected = Expected {
+            rest: &content,
+            same: true,
+        };
+        self.write_compressed(&mut expected, Compression::None)?;
+        Ok(expected.same && expected.rest.is_empty())
+    }
+
+    fn write_compressed<W: Write + ?Sized>(
+        &self,
+        mut out: &mut W,
+        compression: Compression,
+    ) -> Result<(), CatalogPresentationError> {
+        catalog_presentation::write_catalog(
+            &self.catalog,
+            &self.recorded,
+            &mut out,
+            self.options.include_node_metadata,
+            self.options.catalog_format,
+            self.options.compact,
+            compression,
+        )
+    }
+}
+
+/// A writer checking that what is written continues `rest`.
+struct Expected<'a> {
+    rest: &'a [u8],
+    same: bool,
+}
+
+impl Write for Expected<'_> {
+    fn write(
+        &mut self,
+        buf: &[u8],
+    ) -> io::Result<usize> {
+        if self.same {
+            match self.rest.strip_prefix(buf) {
+                Some(rest) => self.rest = rest,
+                None => self.same = false,
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Scan every root in `roots` and build one catalog from their documents.
+///
+/// # Errors
+///
+/// Returns `Error` when `roots` is empty, scanning fails, or duplicate ids
+/// are rejected.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "build", skip_all, fields(roots = roots.len()), err)
+)]
+pub(crate) fn build_roots(
+    roots: &[&Path],
+    options: BuildOptions,
+    progress: &dyn ProgressSink,
+) -> Result<Built, Error> {
+    let Some(&first) = roots.first() else {
+        return Err(Error::NoRoots);
+    };
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    let mut diagnostics = Vec::new();
+    for root in roots {
+        let outcome = scan_with_progress(root, options.scan_options(), progress)?;
+        entries.extend(outcome.entries);
+        skipped.extend(outcome.skipped);
+        diagnostics.extend(outcome.diagnostics);
+    }
+    build_entries(
+        &entries,
+        Some(first),
+        options,
+        BuildReport {
+            warnings: Vec::new(),
+            skipped,
+            diagnostics,
+        },
+    )
+}
+
+/// Scan the documents of `source` and build a catalog from them.
+///
+/// Relation kinds and the recorded config hash come from the `docata.toml`
+/// nearest the source's root; a source outside the filesystem has none.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails or duplicate ids are rejected.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "build", skip_all, fields(source = %source.location()), err)
+)]
+pub(crate) fn build_source(
+    source: &dyn DocumentSource,
+    options: BuildOptions,
+) -> Result<Built, Error> {
+    let outcome = scan_source(source, options.scan_options())?;
+    build_entries(
+        &outcome.entries,
+        source.root(),
+        options,
+        BuildReport {
+            warnings: Vec::new(),
+            skipped: outcome.skipped,
+            diagnostics: outcome.diagnostics,
+        },
+    )
+}
+
+/// Build the catalog for already-scanned `entries` of `root`, exactly as
+/// `run` would write it.
+///
+/// # Errors
+///
+/// Returns `Error` when duplicate ids are rejected.
+pub(crate) fn from_entries(
+    root: &Path,
+    entries: &[Entry],
+    options: BuildOptions,
+) -> Result<Built, Error> {
+    build_entries(entries, Some(root), options, BuildReport::default())
+}
+
+/// Build the catalog for `entries` of `root`, adding its warnings to
+/// `report`.
+fn build_entries(
+    entries: &[Entry],
+    root: Option<&Path>,
+    options: BuildOptions,
+    mut report: BuildReport,
+) -> Result<Built, Error> {
+    let (mut catalog, warnings) = Catalog::from_entries_with_relations(
+        entries,
+        options.duplicate_id_policy,
+        &root.map(relations).unwrap_or_default(),
+    )?;
+    catalog.record_external(&root.map(external_ids).unwrap_or_default());
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        nodes = catalog.nodes.len(),
+        edges = catalog.edges.len(),
+        warnings = warnings.len(),
+        "built catalog"
+    );
+
+    report.warnings = warnings;
+    Ok(Built {
+        catalog,
+        recorded: RecordedOptions::for_root(root, options),
+        options,
+        report,
+    })
+}
+
+/// Render the catalog for already-scanned `entries` of `root` into memory,
+/// exactly as `run` would write it.
+///
+/// # Errors
+///
+/// Returns `Error` when duplicate ids are rejected or serialization fails.
+pub fn render(
+    root: &Path,
+    entries: &[Entry],
+    options: BuildOptions,
+) -> Result<Vec<u8>, Error> {
+    let mut rendered = Vec::new();
+    from_entries(root, entries, options)?.write(&mut rendered)?;
+    Ok(rendered)
+}
+
+/// Relation kinds declared by the nearest `docata.toml`. An unreadable config
+/// declares none; `check` reports it through the config hash instead.
+fn relations(root: &Path) -> BTreeMap<String, RelationDefinition> {
+    Config::nearest(root)
+        .ok()
+        .flatten()
+        .map(|config| config.relations)
+        .unwrap_or_default()
+}
+
+/// Known external ids listed under `[validation]` in the nearest
+/// `docata.toml`; none when the config is missing or unreadable.
+pub(crate) fn external_ids(root: &Path) -> Vec<String> {
+    Config::nearest(root)
+        .ok()
+        .flatten()
+        .map(|config| config.validation.external_ids)
+        .unwrap_or_default()
+}
+
+/// Frontmatter fields producing edges: `deps`, then the field of each
+/// relation kind as `<kind>:<field>`.
+fn edge_sources(relations: &BTreeMap<String, RelationDefinition>) -> Vec<String> {
+    std::iter::once(DEPS_EDGE_SOURCE.to_owned())
+        .chain(
+            relations
+                .iter()
+                .map(|(kind, relation)| format!("{kind}:{}", relation.field(kind))),
+        )
+        .collect()
+}
+
+fn config_hash(root: &Path) -> Option<String> {
+    let content = std::fs::read(find_config_file(root)?).ok()?;
+
+    Some(format!("fnv1a64:{:016x}", hash::fnv1a(&content)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RecordedOptions, detect_node_metadata};
+    use crate::format::CatalogFormat;
+
+    fn recorded() -> RecordedOptions {
+        RecordedOptions {
+            with_node_metadata: true,
+            duplicate_ids: "error".to_owned(),
+            edge_sources: vec!["deps".to_owned()],
+            config_hash: None,
+            with_provenance: false,
+            with_content_hash: false,
+            with_git_metadata: false,
+            with_code_owners: false,
+        }
+    }
+
+    #[test]
+    fn explains_option_mismatches() {
+        let requested = RecordedOptions {
+            with_node_metadata: false,
+            ..recorded()
+        };
+
+        assert!(recorded().mismatches(&recorded()).is_empty());
+        assert_eq!(
+            recorded().mismatches(&requested),
+            vec!["catalog was built with --with-node-metadata, check invoked without".to_owned()]
+        );
+    }
+
+    #[test]
+    fn detects_node_metadata_from_catalog_shape() {
+        let detect = |content: &str| detect_node_metadata(content.as_bytes(), CatalogFormat::Json);
 
-    catalog_presentation::write_catalog(&catalog, out, options.include_node_metadata)?;
-    Ok(())
+        assert!(detect(
+            r#"{"nodes": [{"id": "a", "path": "a.md", "title": null, "tags": []}]}"#
+        ));
+        assert!(!detect(
+            r#"{"nodes": [{"id": "a", "path": "a.md"}], "edges": []}"#
+        ));
+        assert!(!detect(
+            r#"{"build_options": {"with_node_metadata": false, "duplicate_ids": "error", "edge_sources": ["deps"]}, "nodes": [{"id": "a", "path": "a.md", "title": null}]}"#
+        ));
+        assert!(!detect("not a catalog"));
+    }
 }
@@ -0,0 +1,91 @@
+use crate::build::BuildReport;
+use crate::format::OutputFormat;
+use crate::json;
+use crate::validation_presentation::{ScanDiagnosticJson, SkippedFileJson};
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct BuildReportJson<'a> {
+    command: &'static str,
+    warnings: Vec<String>,
+    skipped: Vec<SkippedFileJson>,
+    diagnostics: Vec<ScanDiagnosticJson<'a>>,
+}
+
+impl<'a> From<&'a BuildReport> for BuildReportJson<'a> {
+    fn from(report: &'a BuildReport) -> Self {
+        Self {
+            command: "build",
+            warnings: report.warnings.iter().map(ToString::to_string).collect(),
+            skipped: report.skipped.iter().map(Into::into).collect(),
+            diagnostics: report.diagnostics.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BuildPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a build report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `BuildPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &BuildReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), BuildPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(BuildPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    report: &BuildReport,
+    out: &mut W,
+) -> Result<(), BuildPresentationError> {
+    yaml_serde::to_writer(out, &BuildReportJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &BuildReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), BuildPresentationError> {
+    json::write(out, &BuildReportJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &BuildReport,
+    out: &mut W,
+) -> Result<(), BuildPresentationError> {
+    for warning in &report.warnings {
+        writeln!(out, "warning: {warning}")?;
+    }
+    for skipped in &report.skipped {
+        writeln!(out, "warning: {skipped}")?;
+    }
+    for diagnostic in &report.diagnostics {
+        writeln!(out, "warning: {diagnostic}")?;
+    }
+    Ok(())
+}
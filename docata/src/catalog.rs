@@ -1,17 +1,44 @@
+use crate::catalog_presentation;
+use crate::config::{RelationDefinition, RelationDirection};
+use crate::error::Error;
+use crate::format::CatalogFormat;
 use crate::scan::Entry;
-use serde::Deserialize;
+use crate::store::CatalogStore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::fmt::{self, Display, Formatter};
 use std::path::{Component, Path};
+use std::str::FromStr;
+use thiserror::Error;
 
+/// A catalog's documents and the edges between them.
+///
+/// Load a built catalog with [`Catalog::load`] and query it directly or
+/// through [`crate::Graph`].
 #[derive(Debug, Deserialize)]
+#[non_exhaustive]
 pub struct Catalog {
     pub nodes: Vec<Node>,
     pub edges: Vec<Edge>,
+    /// Relation kinds besides `deps` the catalog was built with.
+    #[serde(default)]
+    pub relations: BTreeMap<String, RelationDefinition>,
+    /// Ids outside the catalog that documents depend on and that
+    /// `docata.toml` lists as known `external_ids`, sorted.
+    #[serde(default)]
+    pub external: Vec<String>,
 }
 
-#[derive(Debug, Deserialize)]
+/// A document of the catalog.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[non_exhaustive]
 pub struct Node {
     pub id: String,
     pub path: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
     #[serde(default, rename = "type")]
     pub kind: Option<String>,
     #[serde(default)]
@@ -20,55 +47,544 @@ pub struct Node {
     pub status: Option<String>,
     #[serde(default)]
     pub source_of_truth: Option<String>,
+    /// `sha256:<hex>` of the document's body; recorded in catalogs built
+    /// with content hashes.
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// Date (`YYYY-MM-DD`) of the latest commit touching the document;
+    /// recorded in catalogs built with git metadata.
+    #[serde(default)]
+    pub last_modified: Option<String>,
+    /// Authors with the most commits touching the document, most first.
+    #[serde(default)]
+    pub authors: Vec<String>,
+    /// Users and teams the repository's CODEOWNERS file assigns the
+    /// document to; recorded in catalogs built with code owners.
+    #[serde(default)]
+    pub owners: Vec<String>,
+}
+
+impl Node {
+    /// Return whether the node carries every tag in `tags`.
+    #[must_use]
+    pub fn has_tags(
+        &self,
+        tags: &[String],
+    ) -> bool {
+        tags.iter().all(|tag| self.tags.contains(tag))
+    }
+
+    /// Return whether the node matches `filters`: filters on the same field
+    /// match any of their values, filters on different fields must all match.
+    #[must_use]
+    pub fn matches_filters(
+        &self,
+        filters: &[MetadataFilter],
+    ) -> bool {
+        filters.iter().all(|filter| {
+            filters
+                .iter()
+                .filter(|other| other.field == filter.field)
+                .any(|other| other.matches(self))
+        })
+    }
+}
+
+/// Node metadata field a [`MetadataFilter`] compares.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetadataField {
+    Type,
+    Domain,
+    Status,
+    SourceOfTruth,
+    /// Matches when the node carries the tag.
+    Tag,
+}
+
+impl MetadataField {
+    /// Key used in `field=value` filter expressions.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            MetadataField::Type => "type",
+            MetadataField::Domain => "domain",
+            MetadataField::Status => "status",
+            MetadataField::SourceOfTruth => "source_of_truth",
+            MetadataField::Tag => "tag",
+        }
+    }
+}
+
+/// A `field=value` condition on node metadata, such as `status=published`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MetadataFilter {
+    pub field: MetadataField,
+    pub value: String,
+}
+
+impl MetadataFilter {
+    /// Return whether `node` has `value` in the filtered field.
+    #[must_use]
+    pub fn matches(
+        &self,
+        node: &Node,
+    ) -> bool {
+        let value = match self.field {
+            MetadataField::Type => &node.kind,
+            MetadataField::Domain => &node.domain,
+            MetadataField::Status => &node.status,
+            MetadataField::SourceOfTruth => &node.source_of_truth,
+            MetadataField::Tag => return node.tags.contains(&self.value),
+        };
+        value.as_ref() == Some(&self.value)
+    }
 }
 
+#[derive(Debug, Error)]
+pub enum MetadataFilterError {
+    #[error("filter '{0}' is not of the form field=value")]
+    MissingValue(String),
+    #[error("unknown filter field '{0}' (expected type, domain, status, source_of_truth, or tag)")]
+    UnknownField(String),
+}
+
+impl FromStr for MetadataFilter {
+    type Err = MetadataFilterError;
+
+    fn from_str(filter: &str) -> Result<Self, Self::Err> {
+        let Some((field, value)) = filter.split_once('=') else {
+            return Err(MetadataFilterError::MissingValue(filter.to_owned()));
+        };
+        let field = match field.trim() {
+            "type" => MetadataField::Type,
+            "domain" => MetadataField::Domain,
+            "status" => MetadataField::Status,
+            "source_of_truth" => MetadataField::SourceOfTruth,
+            "tag" => MetadataField::Tag,
+            field => return Err(MetadataFilterError::UnknownField(field.to_owned())),
+        };
+
+        Ok(Self {
+            field,
+            value: value.trim().to_owned(),
+        })
+    }
+}
+
+/// A relation from one document to an id, which need not be a node.
 #[derive(Clone, Debug, Deserialize, Eq, Ord, PartialEq, PartialOrd)]
+#[non_exhaustive]
 pub struct Edge {
     pub from: String,
     pub to: String,
+    /// Relation kind of the edge; `None` for `deps` edges.
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Where the `deps` entry creating the edge is declared; recorded in
+    /// catalogs built with provenance.
+    #[serde(default)]
+    pub declared_at: Option<SourceLocation>,
+}
+
+/// A line of a document.
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, Ord, PartialEq, PartialOrd)]
+pub struct SourceLocation {
+    pub path: String,
+    /// 1-based line number.
+    pub line: usize,
+}
+
+/// How catalog construction resolves entries that share the same id.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum DuplicateIdPolicy {
+    /// Fail catalog construction.
+    #[default]
+    Error,
+    /// Keep the entry with the smallest path and drop the others.
+    KeepFirst,
+    /// Keep the entry with the smallest path and rename the others to
+    /// `<id>~<n>`.
+    Suffix,
+}
+
+impl DuplicateIdPolicy {
+    /// Name used by the `--duplicate-ids` flag.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            DuplicateIdPolicy::Error => "error",
+            DuplicateIdPolicy::KeepFirst => "keep-first",
+            DuplicateIdPolicy::Suffix => "suffix",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum CatalogWarning {
+    DuplicateIdDropped {
+        id: String,
+        kept_path: String,
+        dropped_path: String,
+    },
+    DuplicateIdRenamed {
+        id: String,
+        renamed_id: String,
+        path: String,
+    },
+}
+
+impl Display for CatalogWarning {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        match self {
+            CatalogWarning::DuplicateIdDropped {
+                id,
+                kept_path,
+                dropped_path,
+            } => write!(
+                f,
+                "duplicate id `{id}`: kept {kept_path}, dropped {dropped_path}"
+            ),
+            CatalogWarning::DuplicateIdRenamed {
+                id,
+                renamed_id,
+                path,
+            } => write!(f, "duplicate id `{id}`: renamed {path} to `{renamed_id}`"),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CatalogError {
+    #[error("duplicate id '{id}' appears in: {}", paths.join(", "))]
+    DuplicateId { id: String, paths: Vec<String> },
 }
 
 impl Catalog {
+    /// Read the catalog saved in `store`, in the store's format.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error` when the store cannot be read or the catalog does not
+    /// parse.
+    pub fn load<S: CatalogStore + ?Sized>(store: &S) -> Result<Self, Error> {
+        Self::from_slice(&store.load()?, store.format())
+    }
+
+    /// Parse a catalog serialized in `format`, e.g. one a browser fetched.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error` when the catalog does not parse.
+    pub fn from_slice(
+        content: &[u8],
+        format: CatalogFormat,
+    ) -> Result<Self, Error> {
+        let catalog = catalog_presentation::read_catalog(&mut &*content, format)?;
+
+        Ok(catalog)
+    }
+
+    /// Read the catalog saved in `store` like [`Catalog::load`], parsing it on
+    /// tokio's blocking pool.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error` when the store cannot be read or the catalog does not
+    /// parse.
+    #[cfg(feature = "tokio")]
+    pub async fn load_async<S: crate::AsyncCatalogStore + ?Sized>(
+        store: &S
+    ) -> Result<Self, Error> {
+        let content = store.load().await?;
+        let format = store.format();
+        let catalog = crate::async_api::unblock(move || {
+            catalog_presentation::read_catalog(&mut content.as_slice(), format)
+        })
+        .await?;
+
+        Ok(catalog)
+    }
+
+    /// The node with id `id`, searched for among the nodes sorted by id.
     #[must_use]
-    pub fn from_entries(entries: &[Entry]) -> Self {
-        let mut nodes = entries
+    pub fn node(
+        &self,
+        id: &str,
+    ) -> Option<&Node> {
+        self.nodes
+            .binary_search_by(|node| node.id.as_str().cmp(id))
+            .ok()
+            .map(|index| &self.nodes[index])
+    }
+
+    /// The catalog's nodes, sorted by id.
+    pub fn nodes(&self) -> impl Iterator<Item = &Node> {
+        self.nodes.iter()
+    }
+
+    /// The catalog's edges of every relation kind, sorted.
+    pub fn edges(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter()
+    }
+
+    /// Build a catalog from scanned entries, resolving duplicate ids with
+    /// `policy`.
+    ///
+    /// Entries sharing an id are ordered by normalized path, so the kept entry
+    /// does not depend on scan order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::DuplicateId` when `policy` is
+    /// `DuplicateIdPolicy::Error` and several entries share an id.
+    pub fn from_entries(
+        entries: &[Entry],
+        policy: DuplicateIdPolicy,
+    ) -> Result<(Self, Vec<CatalogWarning>), CatalogError> {
+        Self::from_entries_with_relations(entries, policy, &BTreeMap::new())
+    }
+
+    /// Build a catalog like [`Catalog::from_entries`], adding an edge of each
+    /// kind in `relations` for every id its frontmatter field lists.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::DuplicateId` when `policy` is
+    /// `DuplicateIdPolicy::Error` and several entries share an id.
+    pub fn from_entries_with_relations(
+        entries: &[Entry],
+        policy: DuplicateIdPolicy,
+        relations: &BTreeMap<String, RelationDefinition>,
+    ) -> Result<(Self, Vec<CatalogWarning>), CatalogError> {
+        let mut ordered_entries = entries
+            .iter()
+            .map(|entry| (entry, normalize_path_string(&entry.path)))
+            .collect::<Vec<_>>();
+        ordered_entries.sort_by(|(left, left_path), (right, right_path)| {
+            left.id.cmp(&right.id).then(left_path.cmp(right_path))
+        });
+
+        let mut used_ids = entries
             .iter()
-            .map(|entry| Node {
-                id: entry.id.clone(),
-                path: normalize_path_string(&entry.path),
-                kind: entry.node_type.clone(),
-                domain: entry.domain.clone(),
-                status: entry.status.clone(),
-                source_of_truth: entry.source_of_truth.clone(),
-            })
+            .map(|entry| entry.id.clone())
+            .collect::<BTreeSet<_>>();
+        let mut warnings = Vec::new();
+        let mut accepted: Vec<(&Entry, String, String)> = Vec::with_capacity(entries.len());
+        let mut kept: Option<(&str, String)> = None;
+
+        for (entry, path) in ordered_entries {
+            let kept_path = match &kept {
+                Some((kept_id, kept_path)) if *kept_id == entry.id => kept_path.clone(),
+                _ => {
+                    kept = Some((entry.id.as_str(), path.clone()));
+                    accepted.push((entry, entry.id.clone(), path));
+                    continue;
+                },
+            };
+
+            match policy {
+                DuplicateIdPolicy::Error => {
+                    return Err(CatalogError::DuplicateId {
+                        id: entry.id.clone(),
+                        paths: ordered_paths_for(entries, &entry.id),
+                    });
+                },
+                DuplicateIdPolicy::KeepFirst => {
+                    warnings.push(CatalogWarning::DuplicateIdDropped {
+                        id: entry.id.clone(),
+                        kept_path,
+                        dropped_path: path,
+                    });
+                },
+                DuplicateIdPolicy::Suffix => {
+                    let mut suffix = 2_usize;
+                    let renamed_id = loop {
+                        let candidate = format!("{}~{suffix}", entry.id);
+                        if !used_ids.contains(&candidate) {
+                            break candidate;
+                        }
+                        suffix += 1;
+                    };
+                    used_ids.insert(renamed_id.clone());
+                    warnings.push(CatalogWarning::DuplicateIdRenamed {
+                        id: entry.id.clone(),
+                        renamed_id: renamed_id.clone(),
+                        path: path.clone(),
+                    });
+                    accepted.push((entry, renamed_id, path));
+                },
+            }
+        }
+
+        let mut nodes = accepted
+            .iter()
+            .map(|(entry, id, path)| node_from_entry(entry, id, path))
             .collect::<Vec<_>>();
         nodes.sort_by(|left, right| {
             left.id
                 .cmp(&right.id)
                 .then(left.path.cmp(&right.path))
+                .then(left.title.cmp(&right.title))
+                .then(left.tags.cmp(&right.tags))
                 .then(left.kind.cmp(&right.kind))
                 .then(left.domain.cmp(&right.domain))
                 .then(left.status.cmp(&right.status))
                 .then(left.source_of_truth.cmp(&right.source_of_truth))
         });
 
-        let mut edges = Vec::new();
-        for entry in entries {
-            for dep in &entry.deps {
+        let edges = build_edges(&accepted, relations);
+
+        Ok((
+            Catalog {
+                nodes,
+                edges,
+                relations: relations.clone(),
+                external: Vec::new(),
+            },
+            warnings,
+        ))
+    }
+
+    /// Record the targets of edges that name one of `external_ids` instead of
+    /// a node as the catalog's external nodes.
+    pub(crate) fn record_external(
+        &mut self,
+        external_ids: &[String],
+    ) {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<HashSet<_>>();
+        let mut external = self
+            .edges
+            .iter()
+            .map(|edge| &edge.to)
+            .filter(|to| !nodes.contains(to.as_str()) && external_ids.contains(to))
+            .cloned()
+            .collect::<Vec<_>>();
+        external.sort();
+        external.dedup();
+        self.external = external;
+    }
+
+    /// Edges produced by `deps`, which every dependency analysis walks.
+    pub fn dependency_edges(&self) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(|edge| edge.kind.is_none())
+    }
+
+    /// Name shown for relation kind `kind`: its configured display name, or
+    /// the kind itself.
+    #[must_use]
+    pub fn relation_display<'a>(
+        &'a self,
+        kind: Option<&'a str>,
+    ) -> &'a str {
+        let Some(kind) = kind else {
+            return "deps";
+        };
+        self.relations
+            .get(kind)
+            .and_then(|relation| relation.display.as_deref())
+            .unwrap_or(kind)
+    }
+}
+
+/// The node for `entry`, accepted under `id` at `path`.
+fn node_from_entry(
+    entry: &Entry,
+    id: &str,
+    path: &str,
+) -> Node {
+    Node {
+        id: id.to_owned(),
+        path: path.to_owned(),
+        title: entry.title.clone(),
+        tags: normalize_tags(&entry.tags),
+        kind: entry.node_type.clone(),
+        domain: entry.domain.clone(),
+        status: entry.status.clone(),
+        source_of_truth: entry.source_of_truth.clone(),
+        content_hash: entry.content_hash.clone(),
+        last_modified: entry
+            .history
+            .as_ref()
+            .map(|history| history.last_modified.clone()),
+        authors: entry
+            .history
+            .as_ref()
+            .map(|history| history.authors.clone())
+            .unwrap_or_default(),
+        owners: entry.code_owners.clone(),
+    }
+}
+
+/// Edges of `deps` and of every kind in `relations`, sorted and deduplicated.
+fn build_edges(
+    accepted: &[(&Entry, String, String)],
+    relations: &BTreeMap<String, RelationDefinition>,
+) -> Vec<Edge> {
+    let mut edges = Vec::new();
+    for (entry, id, path) in accepted {
+        for (index, dep) in entry.deps.iter().enumerate() {
+            edges.push(Edge {
+                from: id.clone(),
+                to: dep.clone(),
+                kind: None,
+                declared_at: entry.dep_lines.get(index).map(|&line| SourceLocation {
+                    path: path.clone(),
+                    line,
+                }),
+            });
+        }
+        for (kind, relation) in relations {
+            let targets = entry.values.get(relation.field(kind)).into_iter().flatten();
+            for target in targets {
+                let (from, to) = match relation.direction {
+                    RelationDirection::Outgoing => (id.clone(), target.clone()),
+                    RelationDirection::Incoming => (target.clone(), id.clone()),
+                };
                 edges.push(Edge {
-                    from: entry.id.clone(),
-                    to: dep.clone(),
+                    from,
+                    to,
+                    kind: Some(kind.clone()),
+                    declared_at: None,
                 });
             }
         }
-        edges.sort();
-        edges.dedup();
-
-        Catalog { nodes, edges }
     }
+    // A dependency listed twice keeps its first declaration.
+    edges.sort();
+    edges.dedup_by(|next, kept| {
+        next.from == kept.from && next.to == kept.to && next.kind == kept.kind
+    });
+    edges
+}
+
+fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let mut tags = tags.to_vec();
+    tags.sort();
+    tags.dedup();
+    tags
 }
 
-fn normalize_path_string(path: &Path) -> String {
+fn ordered_paths_for(
+    entries: &[Entry],
+    id: &str,
+) -> Vec<String> {
+    let mut paths = entries
+        .iter()
+        .filter(|entry| entry.id == id)
+        .map(|entry| normalize_path_string(&entry.path))
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+pub(crate) fn normalize_path_string(path: &Path) -> String {
     let mut prefix = None::<String>;
     let mut has_root = false;
     let mut parts: Vec<String> = Vec::new();
@@ -121,8 +637,16 @@ fn normalize_path_string(path: &Path) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{Catalog, Edge};
+    use super::{
+        Catalog, CatalogError, CatalogWarning, DuplicateIdPolicy, Edge, MetadataFilter,
+        MetadataFilterError,
+    };
+    use crate::config::{RelationDefinition, RelationDirection};
+    use crate::format::CatalogFormat;
     use crate::scan::Entry;
+    use crate::testing::{TestWorkspace, write_markdown};
+    use crate::{BuildOptions, Graph, RelationKind, build_catalog_into};
+    use std::collections::BTreeMap;
     use std::path::PathBuf;
 
     fn entry(
@@ -133,12 +657,72 @@ mod tests {
         Entry {
             id: id.to_owned(),
             deps: deps.iter().map(ToString::to_string).collect(),
+            dep_lines: Vec::new(),
             path: PathBuf::from(path),
+            title: Some(format!("{id} title")),
+            tags: vec!["ops".to_owned(), "api".to_owned(), "ops".to_owned()],
             node_type: Some("note".to_owned()),
             domain: Some("engineering".to_owned()),
             status: Some("published".to_owned()),
             source_of_truth: Some("docs".to_owned()),
+            last_reviewed: None,
+            owner: None,
+            fields: Vec::new(),
+            values: BTreeMap::new(),
+            content_hash: None,
+            history: None,
+            code_owners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn loads_a_built_catalog_for_queries() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "web.md", "web", &["api"]);
+        write_markdown(workspace.path(), "api.md", "api", &["db"]);
+        write_markdown(workspace.path(), "db.md", "db", &[]);
+        let path = workspace.path().join("catalog.json");
+        build_catalog_into(workspace.path(), path.as_path(), BuildOptions::default())
+            .expect("build catalog");
+
+        let catalog = Catalog::load(path.as_path()).expect("load catalog");
+        assert_eq!(catalog.nodes().count(), 3);
+        assert_eq!(catalog.edges().count(), 2);
+        assert!(
+            catalog
+                .node("api")
+                .is_some_and(|node| node.path.ends_with("api.md"))
+        );
+        assert!(catalog.node("missing").is_none());
+        for id in ["api", "db", "web"] {
+            assert_eq!(catalog.node(id).map(|node| node.id.as_str()), Some(id));
+        }
+        for id in ["", "ap", "apis", "zzz"] {
+            assert!(catalog.node(id).is_none(), "{id}");
         }
+
+        let graph = Graph::from_catalog(&catalog);
+        assert_eq!(graph.deps("web"), ["api"]);
+        assert_eq!(graph.transitive("web", RelationKind::Deps), ["api", "db"]);
+        assert_eq!(graph.transitive("db", RelationKind::Refs), ["api", "web"]);
+    }
+
+    #[test]
+    fn parses_a_catalog_from_bytes() {
+        let json =
+            br#"{"nodes": [{"id": "web", "path": "web.md"}, {"id": "api", "path": "api.md"}],
+            "edges": [{"from": "web", "to": "api"}]}"#;
+        let catalog = Catalog::from_slice(json, CatalogFormat::Json).expect("parse json");
+        assert_eq!(catalog.nodes().count(), 2);
+        assert_eq!(Graph::from_catalog(&catalog).deps("web"), ["api"]);
+
+        let yaml = b"nodes:\n  - id: web\n    path: web.md\nedges: []\n";
+        let catalog = Catalog::from_slice(yaml, CatalogFormat::Yaml).expect("parse yaml");
+        assert!(catalog.node("web").is_some());
+
+        assert!(Catalog::from_slice(b"{\"nodes\": [", CatalogFormat::Json).is_err());
+        assert!(Catalog::from_slice(json, CatalogFormat::Yaml).is_ok());
+        assert!(Catalog::from_slice(b"nodes: 3\n", CatalogFormat::Yaml).is_err());
     }
 
     #[test]
@@ -148,8 +732,10 @@ mod tests {
             entry("alpha", &["zeta"], "docs/alpha.md"),
         ];
 
-        let catalog = Catalog::from_entries(&entries);
+        let (catalog, warnings) =
+            Catalog::from_entries(&entries, DuplicateIdPolicy::Error).expect("build catalog");
 
+        assert!(warnings.is_empty());
         assert_eq!(catalog.nodes.len(), 2);
         assert_eq!(catalog.nodes[0].id, "alpha");
         assert_eq!(catalog.nodes[0].path, "docs/alpha.md");
@@ -162,10 +748,14 @@ mod tests {
                 Edge {
                     from: "alpha".to_owned(),
                     to: "zeta".to_owned(),
+                    kind: None,
+                    declared_at: None,
                 },
                 Edge {
                     from: "zeta".to_owned(),
                     to: "alpha".to_owned(),
+                    kind: None,
+                    declared_at: None,
                 },
             ]
         );
@@ -175,10 +765,132 @@ mod tests {
     fn includes_node_metadata_fields() {
         let entries = vec![entry("alpha", &[], "docs/alpha.md")];
 
-        let catalog = Catalog::from_entries(&entries);
+        let (catalog, _) =
+            Catalog::from_entries(&entries, DuplicateIdPolicy::Error).expect("build catalog");
+        assert_eq!(catalog.nodes[0].title.as_deref(), Some("alpha title"));
+        assert_eq!(
+            catalog.nodes[0].tags,
+            vec!["api".to_owned(), "ops".to_owned()]
+        );
         assert_eq!(catalog.nodes[0].kind.as_deref(), Some("note"));
         assert_eq!(catalog.nodes[0].domain.as_deref(), Some("engineering"));
         assert_eq!(catalog.nodes[0].status.as_deref(), Some("published"));
         assert_eq!(catalog.nodes[0].source_of_truth.as_deref(), Some("docs"));
     }
+
+    #[test]
+    fn resolves_duplicate_ids_by_policy() {
+        let entries = vec![
+            entry("alpha", &["zeta"], "docs/b/alpha.md"),
+            entry("alpha", &["beta"], "docs/a/alpha.md"),
+            entry("beta", &[], "docs/beta.md"),
+            entry("zeta", &[], "docs/zeta.md"),
+        ];
+
+        let error = Catalog::from_entries(&entries, DuplicateIdPolicy::Error)
+            .expect_err("duplicate ids must fail");
+        assert!(matches!(
+            error,
+            CatalogError::DuplicateId { id, paths }
+                if id == "alpha" && paths == ["docs/a/alpha.md", "docs/b/alpha.md"]
+        ));
+
+        let (catalog, warnings) = Catalog::from_entries(&entries, DuplicateIdPolicy::KeepFirst)
+            .expect("keep-first must succeed");
+        assert_eq!(catalog.nodes.len(), 3);
+        assert_eq!(catalog.nodes[0].path, "docs/a/alpha.md");
+        assert!(!catalog.edges.iter().any(|edge| edge.to == "zeta"));
+        assert!(matches!(
+            warnings.as_slice(),
+            [CatalogWarning::DuplicateIdDropped { dropped_path, .. }]
+                if dropped_path == "docs/b/alpha.md"
+        ));
+
+        let (catalog, warnings) = Catalog::from_entries(&entries, DuplicateIdPolicy::Suffix)
+            .expect("suffix must succeed");
+        assert_eq!(catalog.nodes.len(), 4);
+        assert_eq!(catalog.nodes[1].id, "alpha~2");
+        assert_eq!(catalog.nodes[1].path, "docs/b/alpha.md");
+        assert!(catalog.edges.contains(&Edge {
+            from: "alpha~2".to_owned(),
+            to: "zeta".to_owned(),
+            kind: None,
+            declared_at: None,
+        }));
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn adds_edges_for_relation_kinds() {
+        let mut old = entry("old", &[], "docs/old.md");
+        let mut new = entry("new", &["old"], "docs/new.md");
+        old.values
+            .insert("implements".to_owned(), vec!["spec".to_owned()]);
+        new.values
+            .insert("supersedes".to_owned(), vec!["old".to_owned()]);
+        let relations = BTreeMap::from([
+            ("implements".to_owned(), RelationDefinition::default()),
+            (
+                "superseded_by".to_owned(),
+                RelationDefinition {
+                    field: Some("supersedes".to_owned()),
+                    display: Some("Superseded by".to_owned()),
+                    direction: RelationDirection::Incoming,
+                },
+            ),
+        ]);
+
+        let (catalog, _) =
+            Catalog::from_entries_with_relations(&[old, new], DuplicateIdPolicy::Error, &relations)
+                .expect("build catalog");
+
+        let edge = |from: &str, to: &str, kind: Option<&str>| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: kind.map(str::to_owned),
+            declared_at: None,
+        };
+        assert_eq!(
+            catalog.edges,
+            vec![
+                edge("new", "old", None),
+                edge("old", "new", Some("superseded_by")),
+                edge("old", "spec", Some("implements")),
+            ]
+        );
+        assert_eq!(catalog.dependency_edges().count(), 1);
+        assert_eq!(
+            catalog.relation_display(Some("superseded_by")),
+            "Superseded by"
+        );
+        assert_eq!(catalog.relation_display(Some("implements")), "implements");
+    }
+
+    #[test]
+    fn filters_nodes_by_metadata() {
+        let (catalog, _) = Catalog::from_entries(
+            &[entry("alpha", &[], "docs/alpha.md")],
+            DuplicateIdPolicy::Error,
+        )
+        .expect("build catalog");
+        let node = &catalog.nodes[0];
+        let filters = |expressions: &[&str]| {
+            expressions
+                .iter()
+                .map(|expression| expression.parse::<MetadataFilter>().expect("parse filter"))
+                .collect::<Vec<_>>()
+        };
+
+        assert!(node.matches_filters(&filters(&["status=published", "tag=api"])));
+        assert!(node.matches_filters(&filters(&["status=draft", "status=published"])));
+        assert!(!node.matches_filters(&filters(&["status=published", "domain=billing"])));
+        assert!(matches!(
+            "owner=ops".parse::<MetadataFilter>(),
+            Err(MetadataFilterError::UnknownField(field)) if field == "owner"
+        ));
+        assert!(matches!(
+            "status".parse::<MetadataFilter>(),
+            Err(MetadataFilterError::MissingValue(_))
+        ));
+    }
 }
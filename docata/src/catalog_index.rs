@@ -0,0 +1,702 @@
+use crate::{
+    RelationKind, build,
+    catalog::{Catalog, Edge, Node},
+    config::RelationDefinition,
+    format::{CatalogFormat, Compression},
+    store::{CatalogStore, StoreError, with_suffix},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use std::collections::{BTreeMap, BTreeSet, btree_map};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::num::TryFromIntError;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use thiserror::Error;
+
+/// Start of every index; an index of another layout version is ignored like
+/// a stale one.
+const MAGIC: &[u8; 8] = b"DOCATAIX";
+const VERSION: u32 = 3;
+
+/// Bytes taken by the header, an id entry, and an edge entry.
+const HEADER_LEN: u64 = 48;
+const ID_LEN: u64 = 40;
+const EDGE_LEN: u64 = 24;
+
+/// Query index kept beside a local JSON catalog as `<catalog>.idx`, read by
+/// seeking to the entries a query needs rather than loaded whole.
+///
+/// The file holds, little-endian and in order:
+///
+/// - a header: [`MAGIC`], [`VERSION`], the id and edge counts, the length
+///   of the metadata, and the size and modification time the catalog had
+///   when indexed, which tell whether the index still matches it;
+/// - one entry per id, sorted by id: where its name lies in the string
+///   table, where its node lies in the catalog (empty for ids only edges
+///   mention), and which postings list its outgoing and incoming edges;
+/// - one entry per edge, in catalog order: the ids it joins, its relation
+///   kind, and where it lies in the catalog;
+/// - the postings, edge numbers grouped by id: outgoing, then incoming;
+/// - the catalog's relation kinds and external ids, as JSON;
+/// - the string table of ids.
+///
+/// A query binary-searches the entries for the ids queried, follows their
+/// postings to the ids they reach, and reads only those nodes and edges
+/// from the catalog.
+pub(crate) struct CatalogIndex {
+    file: Reader,
+    catalog: PathBuf,
+    id_count: u32,
+    edge_count: u32,
+    metadata: Metadata,
+    strings_at: u64,
+}
+
+/// Catalog-wide parts of the index, small enough to read whole.
+#[derive(Default, Deserialize, Serialize)]
+struct Metadata {
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    relations: BTreeMap<String, RelationDefinition>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    external: Vec<String>,
+    /// Relation kinds of the catalog's edges, numbered from 1 in edge
+    /// entries; 0 stands for `deps`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    kinds: Vec<String>,
+    /// Whether the catalog was built with node metadata, so its nodes
+    /// carry tags, titles, and metadata fields.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    annotated: bool,
+}
+
+/// Byte offset and length of a node, an edge, or a name.
+type Span = (u64, u32);
+
+/// First posting and number of postings.
+type Postings = (u32, u32);
+
+#[derive(Clone, Copy)]
+struct IdEntry {
+    name: Span,
+    /// Empty for ids only edges mention.
+    node: Span,
+    deps: Postings,
+    refs: Postings,
+}
+
+#[derive(Clone, Copy)]
+struct EdgeEntry {
+    from: u32,
+    to: u32,
+    kind: u32,
+    span: Span,
+}
+
+/// Size and modification time of a catalog file, recorded in its index.
+#[derive(Debug, Eq, PartialEq)]
+struct Stamp {
+    len: u64,
+    secs: u64,
+    nanos: u32,
+}
+
+/// The parts of a JSON catalog the index records, borrowed from it.
+#[derive(Deserialize)]
+struct Layout<'a> {
+    #[serde(borrow)]
+    nodes: Vec<&'a RawValue>,
+    #[serde(borrow)]
+    edges: Vec<&'a RawValue>,
+    #[serde(default)]
+    relations: BTreeMap<String, RelationDefinition>,
+    #[serde(default)]
+    external: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct NodeId {
+    id: String,
+}
+
+#[derive(Debug, Error)]
+pub enum IndexError {
+    #[error("catalog '{location}' cannot keep a query index; only local files can")]
+    Unsupported { location: String },
+    #[error("catalog '{location}' cannot be indexed; only uncompressed JSON catalogs can")]
+    NotJson { location: String },
+    #[error("catalog is too large to index")]
+    TooLarge(#[from] TryFromIntError),
+    #[error("failed to read catalog '{location}' through its query index: {source}")]
+    Read {
+        location: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Store(#[from] StoreError),
+}
+
+impl CatalogIndex {
+    /// The query index kept beside `store`'s catalog, or `None` when there
+    /// is none, it is unreadable, or the catalog changed since it was
+    /// indexed.
+    pub(crate) fn open<S: CatalogStore + ?Sized>(store: &S) -> Option<Self> {
+        let catalog = store.local_path()?;
+        let mut file = Reader::new(File::open(index_path(catalog)).ok()?);
+        let header = file.read_at(0, HEADER_LEN).ok()?;
+        let mut fields = Fields(&header);
+        if fields.bytes() != *MAGIC || fields.u32() != VERSION {
+            return None;
+        }
+        let id_count = fields.u32();
+        let edge_count = fields.u32();
+        let metadata_len = fields.u32();
+        let stamp = Stamp {
+            len: fields.u64(),
+            secs: fields.u64(),
+            nanos: fields.u32(),
+        };
+        if Stamp::of(catalog).ok()? != stamp {
+            return None;
+        }
+
+        let mut index = Self {
+            file,
+            catalog: catalog.to_path_buf(),
+            id_count,
+            edge_count,
+            metadata: Metadata::default(),
+            strings_at: 0,
+        };
+        let metadata_at = index.postings_at() + 8 * u64::from(edge_count);
+        let metadata = index.file.read_at(metadata_at, metadata_len.into()).ok()?;
+        index.metadata = serde_json::from_slice(&metadata).ok()?;
+        index.strings_at = metadata_at + u64::from(metadata_len);
+        Some(index)
+    }
+
+    /// Whether the indexed catalog was built with node metadata.
+    pub(crate) fn node_metadata(&self) -> bool {
+        self.metadata.annotated
+    }
+
+    /// The part of the catalog answering a `relation_kind` query of
+    /// `query_ids` along `edge_kind` edges: the queried nodes, the ids they
+    /// reach (transitively when `transitive`), and the edges followed.
+    /// `None` when a query id is not a node, since suggesting ids takes the
+    /// whole catalog.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IndexError::Read` when the index or the catalog cannot be
+    /// read at the recorded offsets.
+    pub(crate) fn reach(
+        &mut self,
+        query_ids: &[&str],
+        relation_kind: RelationKind,
+        edge_kind: Option<&str>,
+        transitive: bool,
+    ) -> Result<Option<Catalog>, IndexError> {
+        self.read_reach(query_ids, relation_kind, edge_kind, transitive)
+            .map_err(|source| IndexError::Read {
+                location: self.catalog.display().to_string(),
+                source,
+            })
+    }
+
+    fn read_reach(
+        &mut self,
+        query_ids: &[&str],
+        relation_kind: RelationKind,
+        edge_kind: Option<&str>,
+        transitive: bool,
+    ) -> io::Result<Option<Catalog>> {
+        let kind = match edge_kind {
+            None => Some(0),
+            Some(edge_kind) => self
+                .metadata
+                .kinds
+                .iter()
+                .zip(1..)
+                .find_map(|(kind, number)| (kind == edge_kind).then_some(number)),
+        };
+
+        let mut reached = BTreeMap::new();
+        for id in query_ids {
+            match self.find(id)? {
+                Some((number, entry)) if entry.node.1 > 0 => {
+                    reached.insert(number, entry);
+                },
+                _ => return Ok(None),
+            }
+        }
+
+        let mut followed = BTreeMap::new();
+        let mut pending = reached.keys().copied().collect::<Vec<_>>();
+        while let Some(number) = pending.pop() {
+            let entry = reached[&number];
+            let postings = match relation_kind {
+                RelationKind::Deps => entry.deps,
+                RelationKind::Refs => entry.refs,
+            };
+            for position in self.postings(postings)? {
+                let edge = self.edge(position)?;
+                if Some(edge.kind) != kind {
+                    continue;
+                }
+                followed.insert(position, edge);
+                let related = match relation_kind {
+                    RelationKind::Deps => edge.to,
+                    RelationKind::Refs => edge.from,
+                };
+                if let btree_map::Entry::Vacant(vacant) = reached.entry(related) {
+                    vacant.insert(self.id(related)?);
+                    if transitive {
+                        pending.push(related);
+                    }
+                }
+            }
+        }
+
+        // Both follow catalog order, so reading them moves forward through
+        // the catalog.
+        let mut catalog = Reader::new(File::open(&self.catalog)?);
+        let nodes = reached
+            .values()
+            .filter(|entry| entry.node.1 > 0)
+            .map(|entry| catalog.read_json::<Node>(entry.node))
+            .collect::<io::Result<_>>()?;
+        let edges = followed
+            .values()
+            .map(|edge| catalog.read_json::<Edge>(edge.span))
+            .collect::<io::Result<_>>()?;
+
+        Ok(Some(Catalog {
+            nodes,
+            edges,
+            relations: self.metadata.relations.clone(),
+            external: self.metadata.external.clone(),
+        }))
+    }
+
+    /// Number and entry of `id`, by binary search over the sorted entries.
+    fn find(
+        &mut self,
+        id: &str,
+    ) -> io::Result<Option<(u32, IdEntry)>> {
+        let (mut low, mut high) = (0, self.id_count);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let entry = self.id(middle)?;
+            let name = self
+                .file
+                .read_at(self.strings_at + entry.name.0, entry.name.1.into())?;
+            match name.as_slice().cmp(id.as_bytes()) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Ok(Some((middle, entry))),
+            }
+        }
+        Ok(None)
+    }
+
+    fn id(
+        &mut self,
+        number: u32,
+    ) -> io::Result<IdEntry> {
+        let entry = self
+            .file
+            .read_at(HEADER_LEN + u64::from(number) * ID_LEN, ID_LEN)?;
+        let mut fields = Fields(&entry);
+        let name_at = fields.u64();
+        let node_at = fields.u64();
+        Ok(IdEntry {
+            name: (name_at, fields.u32()),
+            node: (node_at, fields.u32()),
+            deps: (fields.u32(), fields.u32()),
+            refs: (fields.u32(), fields.u32()),
+        })
+    }
+
+    fn edge(
+        &mut self,
+        number: u32,
+    ) -> io::Result<EdgeEntry> {
+        let at = self.edges_at() + u64::from(number) * EDGE_LEN;
+        let entry = self.file.read_at(at, EDGE_LEN)?;
+        let mut fields = Fields(&entry);
+        let from = fields.u32();
+        let to = fields.u32();
+        let kind = fields.u32();
+        let len = fields.u32();
+        Ok(EdgeEntry {
+            from,
+            to,
+            kind,
+            span: (fields.u64(), len),
+        })
+    }
+
+    fn postings(
+        &mut self,
+        (first, count): Postings,
+    ) -> io::Result<Vec<u32>> {
+        let at = self.postings_at() + 4 * u64::from(first);
+        let postings = self.file.read_at(at, 4 * u64::from(count))?;
+        Ok(postings
+            .chunks_exact(4)
+            .map(|posting| Fields(posting).u32())
+            .collect())
+    }
+
+    fn edges_at(&self) -> u64 {
+        HEADER_LEN + u64::from(self.id_count) * ID_LEN
+    }
+
+    fn postings_at(&self) -> u64 {
+        self.edges_at() + u64::from(self.edge_count) * EDGE_LEN
+    }
+}
+
+impl Stamp {
+    fn of(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let modified = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(Self {
+            len: metadata.len(),
+            secs: modified.as_secs(),
+            nanos: modified.subsec_nanos(),
+        })
+    }
+}
+
+/// Little-endian fields read in order from an index entry.
+struct Fields<'a>(&'a [u8]);
+
+impl Fields<'_> {
+    fn bytes<const N: usize>(&mut self) -> [u8; N] {
+        let (field, rest) = self
+            .0
+            .split_first_chunk::<N>()
+            .expect("entry holds every field");
+        self.0 = rest;
+        *field
+    }
+
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.bytes())
+    }
+
+    fn u64(&mut self) -> u64 {
+        u64::from_le_bytes(self.bytes())
+    }
+}
+
+/// A file read at given offsets through a buffer, so nearby reads cost no
+/// system call.
+struct Reader {
+    file: BufReader<File>,
+    position: u64,
+}
+
+impl Reader {
+    fn new(file: File) -> Self {
+        Self {
+            file: BufReader::new(file),
+            position: 0,
+        }
+    }
+
+    fn read_at(
+        &mut self,
+        at: u64,
+        len: u64,
+    ) -> io::Result<Vec<u8>> {
+        let offset = i128::from(at) - i128::from(self.position);
+        self.file
+            .seek_relative(i64::try_from(offset).map_err(io::Error::other)?)?;
+        let mut bytes = vec![0; usize::try_from(len).map_err(io::Error::other)?];
+        self.file.read_exact(&mut bytes)?;
+        self.position = at + len;
+        Ok(bytes)
+    }
+
+    fn read_json<T: for<'de> Deserialize<'de>>(
+        &mut self,
+        (at, len): Span,
+    ) -> io::Result<T> {
+        Ok(serde_json::from_slice(&self.read_at(at, len.into())?)?)
+    }
+}
+
+/// The local file holding `store`'s catalog, when the catalog can keep a
+/// query index.
+///
+/// # Errors
+///
+/// Returns `IndexError` when the store is not a local file or its catalog
+/// is not uncompressed JSON.
+pub(crate) fn indexable<S: CatalogStore + ?Sized>(
+    store: &S,
+    format: CatalogFormat,
+) -> Result<&Path, IndexError> {
+    let location = store.location();
+    if format != CatalogFormat::Json || store.compression() != Compression::None {
+        return Err(IndexError::NotJson { location });
+    }
+    store
+        .local_path()
+        .ok_or(IndexError::Unsupported { location })
+}
+
+/// Index the catalog just saved to `catalog` into `<catalog>.idx`.
+///
+/// # Errors
+///
+/// Returns `IndexError` when the catalog cannot be read or is not a JSON
+/// catalog, or the index cannot be written.
+pub(crate) fn write(catalog: &Path) -> Result<(), IndexError> {
+    let read_error = |source| IndexError::Read {
+        location: catalog.display().to_string(),
+        source,
+    };
+    let stamp = Stamp::of(catalog).map_err(read_error)?;
+    let content = fs::read(catalog).map_err(read_error)?;
+    let index = encode(&content, &stamp)?;
+    CatalogStore::save(index_path(catalog).as_path(), &index)?;
+    Ok(())
+}
+
+fn encode(
+    content: &[u8],
+    stamp: &Stamp,
+) -> Result<Vec<u8>, IndexError> {
+    #[derive(Default)]
+    struct Id {
+        node: Span,
+        deps: Vec<u32>,
+        refs: Vec<u32>,
+    }
+
+    let layout: Layout<'_> = serde_json::from_slice(content)?;
+    let span = |raw: &RawValue| -> Result<Span, IndexError> {
+        let start = raw.get().as_ptr().addr() - content.as_ptr().addr();
+        Ok((u64::try_from(start)?, u32::try_from(raw.get().len())?))
+    };
+
+    let mut ids = BTreeMap::<String, Id>::new();
+    for raw in &layout.nodes {
+        let NodeId { id } = serde_json::from_str(raw.get())?;
+        ids.entry(id).or_default().node = span(raw)?;
+    }
+    let mut edges = Vec::with_capacity(layout.edges.len());
+    for (position, raw) in (0..).zip(&layout.edges) {
+        let Edge { from, to, kind, .. } = serde_json::from_str(raw.get())?;
+        ids.entry(from.clone()).or_default().deps.push(position);
+        ids.entry(to.clone()).or_default().refs.push(position);
+        edges.push((from, to, kind, span(raw)?));
+    }
+    let kinds = edges
+        .iter()
+        .filter_map(|(_, _, kind, _)| kind.clone())
+        .collect::<BTreeSet<_>>();
+    let numbers = ids
+        .keys()
+        .map(String::as_str)
+        .zip(0..)
+        .collect::<BTreeMap<_, u32>>();
+    let metadata = serde_json::to_vec(&Metadata {
+        relations: layout.relations,
+        external: layout.external,
+        kinds: kinds.iter().cloned().collect(),
+        annotated: build::detect_node_metadata(content, CatalogFormat::Json),
+    })?;
+
+    let mut index = Vec::new();
+    index.extend_from_slice(MAGIC);
+    for field in [
+        VERSION,
+        u32::try_from(ids.len())?,
+        u32::try_from(edges.len())?,
+        u32::try_from(metadata.len())?,
+    ] {
+        index.extend_from_slice(&field.to_le_bytes());
+    }
+    index.extend_from_slice(&stamp.len.to_le_bytes());
+    index.extend_from_slice(&stamp.secs.to_le_bytes());
+    index.extend_from_slice(&stamp.nanos.to_le_bytes());
+    index.extend_from_slice(&0_u32.to_le_bytes());
+
+    let mut strings = Vec::new();
+    let (mut deps, mut refs) = (Vec::new(), Vec::new());
+    for (name, id) in &ids {
+        index.extend_from_slice(&u64::try_from(strings.len())?.to_le_bytes());
+        index.extend_from_slice(&id.node.0.to_le_bytes());
+        strings.extend_from_slice(name.as_bytes());
+        for field in [
+            u32::try_from(name.len())?,
+            id.node.1,
+            u32::try_from(deps.len())?,
+            u32::try_from(id.deps.len())?,
+            u32::try_from(edges.len() + refs.len())?,
+            u32::try_from(id.refs.len())?,
+        ] {
+            index.extend_from_slice(&field.to_le_bytes());
+        }
+        deps.extend_from_slice(&id.deps);
+        refs.extend_from_slice(&id.refs);
+    }
+    for (from, to, kind, (at, len)) in &edges {
+        let kind = kind.as_ref().map_or(Some(0), |kind| {
+            kinds
+                .iter()
+                .zip(1..)
+                .find_map(|(known, number)| (known == kind).then_some(number))
+        });
+        for field in [
+            numbers[from.as_str()],
+            numbers[to.as_str()],
+            kind.unwrap_or_default(),
+            *len,
+        ] {
+            index.extend_from_slice(&field.to_le_bytes());
+        }
+        index.extend_from_slice(&at.to_le_bytes());
+    }
+    for posting in deps.iter().chain(&refs) {
+        index.extend_from_slice(&posting.to_le_bytes());
+    }
+    index.extend_from_slice(&metadata);
+    index.extend_from_slice(&strings);
+
+    Ok(index)
+}
+
+fn index_path(catalog: &Path) -> PathBuf {
+    with_suffix(catalog, ".idx")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CatalogIndex;
+    use crate::testing::{TestWorkspace, write_markdown};
+    use crate::{
+        BuildOptions, OutputFormat, QueryOptions, RelationKind, build_catalog_into,
+        query_catalog_relation_with_options, query_catalog_relations,
+    };
+    use std::fs;
+
+    #[test]
+    fn answers_queries_from_the_index_like_the_whole_catalog() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "web.md", "web", &["api"]);
+        write_markdown(workspace.path(), "api.md", "api", &["db", "queue"]);
+        write_markdown(workspace.path(), "db.md", "db", &["disk"]);
+        write_markdown(workspace.path(), "disk.md", "disk", &[]);
+        let store = workspace.path().join("catalog.json");
+        build_catalog_into(
+            workspace.path(),
+            &store,
+            BuildOptions::new().write_index(true),
+        )
+        .expect("build catalog");
+        let index_path = workspace.path().join("catalog.json.idx");
+
+        let mut index = CatalogIndex::open(&store).expect("index up to date");
+        let reached = index
+            .reach(&["db"], RelationKind::Refs, None, false)
+            .expect("read reached documents")
+            .expect("db is a node");
+        let ids = reached
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["api", "db"]);
+        assert_eq!(reached.edges.len(), 1);
+        assert!(
+            index
+                .reach(&["queue"], RelationKind::Refs, None, false)
+                .expect("read index")
+                .is_none()
+        );
+
+        let query = |ids: &[&str], relation_kind, transitive| {
+            let mut out = Vec::new();
+            let options = QueryOptions::new()
+                .transitive(transitive)
+                .include_node_metadata(true);
+            match ids {
+                [id] => query_catalog_relation_with_options(
+                    id,
+                    &store,
+                    relation_kind,
+                    OutputFormat::Json,
+                    &options,
+                    &mut out,
+                ),
+                ids => query_catalog_relations(
+                    ids,
+                    &store,
+                    relation_kind,
+                    OutputFormat::Json,
+                    &options,
+                    &mut out,
+                ),
+            }
+            .expect("query catalog");
+            String::from_utf8(out).expect("utf-8")
+        };
+        let queries = [
+            (&["api"][..], RelationKind::Deps, false),
+            (&["web"], RelationKind::Deps, true),
+            (&["disk"], RelationKind::Refs, true),
+            (&["db", "web"], RelationKind::Deps, false),
+            (&["missing"], RelationKind::Refs, false),
+        ];
+        let indexed =
+            queries.map(|(ids, relation_kind, transitive)| query(ids, relation_kind, transitive));
+
+        fs::remove_file(&index_path).expect("remove index");
+        let whole =
+            queries.map(|(ids, relation_kind, transitive)| query(ids, relation_kind, transitive));
+        assert_eq!(indexed, whole);
+    }
+
+    #[test]
+    fn ignores_an_index_once_the_catalog_changes() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "api.md", "api", &["db"]);
+        write_markdown(workspace.path(), "db.md", "db", &[]);
+        let store = workspace.path().join("catalog.json");
+        build_catalog_into(
+            workspace.path(),
+            &store,
+            BuildOptions::new().write_index(true),
+        )
+        .expect("build catalog");
+        assert!(CatalogIndex::open(&store).is_some());
+
+        write_markdown(workspace.path(), "cache.md", "cache", &[]);
+        build_catalog_into(workspace.path(), &store, BuildOptions::new()).expect("rebuild catalog");
+        assert!(CatalogIndex::open(&store).is_none());
+
+        build_catalog_into(
+            workspace.path(),
+            &store,
+            BuildOptions::new().write_index(true),
+        )
+        .expect("rebuild catalog");
+        let mut content = fs::read(&store).expect("read catalog");
+        content.push(b'\n');
+        fs::write(&store, &content).expect("edit catalog");
+        assert!(CatalogIndex::open(&store).is_none());
+    }
+}
@@ -1,23 +1,49 @@
-use crate::catalog::Catalog;
-use serde::Serialize;
-use std::io::{Read, Write};
+use crate::build::RecordedOptions;
+use crate::catalog::{Catalog, Edge, Node, SourceLocation};
+use crate::config::RelationDefinition;
+use crate::format::{CatalogFormat, Compression};
+use crate::json;
+use serde::de::DeserializeOwned;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::io::{self, Read, Write};
 use thiserror::Error;
 
 #[derive(Debug, Serialize)]
 struct CatalogNodeBasic<'a> {
     id: &'a str,
     path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_hash: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    authors: &'a [String],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    owners: &'a [String],
 }
 
 #[derive(Debug, Serialize)]
 struct CatalogNodeWithMetadata<'a> {
     id: &'a str,
     path: &'a str,
+    title: Option<&'a str>,
+    tags: &'a [String],
     #[serde(rename = "type")]
     kind: Option<&'a str>,
     domain: Option<&'a str>,
     status: Option<&'a str>,
     source_of_truth: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content_hash: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    authors: &'a [String],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    owners: &'a [String],
 }
 
 #[derive(Debug, Serialize)]
@@ -31,51 +57,193 @@ enum CatalogNode<'a> {
 struct CatalogEdge<'a> {
     from: &'a str,
     to: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    declared_at: Option<&'a SourceLocation>,
 }
 
-#[derive(Debug, Serialize)]
+/// A catalog as written, in sorted key order. Nodes and edges are viewed one
+/// at a time as they are serialized, so writing never holds a second copy
+/// of them.
+#[derive(Debug)]
 struct CatalogView<'a> {
-    nodes: Vec<CatalogNode<'a>>,
-    edges: Vec<CatalogEdge<'a>>,
+    build_options: &'a RecordedOptions,
+    relations: &'a BTreeMap<String, RelationDefinition>,
+    nodes: NodesView<'a>,
+    edges: EdgesView<'a>,
+    external: &'a [String],
+}
+
+/// A top-level field of a [`CatalogView`].
+#[derive(Debug)]
+enum CatalogField<'v, 'a> {
+    BuildOptions(&'a RecordedOptions),
+    Edges(&'v EdgesView<'a>),
+    External(&'a [String]),
+    Nodes(&'v NodesView<'a>),
+    Relations(&'a BTreeMap<String, RelationDefinition>),
 }
 
 impl<'a> CatalogView<'a> {
     fn from_catalog(
         catalog: &'a Catalog,
+        build_options: &'a RecordedOptions,
         include_node_metadata: bool,
     ) -> Self {
-        let nodes = catalog
-            .nodes
-            .iter()
-            .map(|node| {
-                if include_node_metadata {
-                    CatalogNode::WithMetadata(CatalogNodeWithMetadata {
-                        id: node.id.as_str(),
-                        path: node.path.as_str(),
-                        kind: node.kind.as_deref(),
-                        domain: node.domain.as_deref(),
-                        status: node.status.as_deref(),
-                        source_of_truth: node.source_of_truth.as_deref(),
-                    })
-                } else {
-                    CatalogNode::Basic(CatalogNodeBasic {
-                        id: node.id.as_str(),
-                        path: node.path.as_str(),
-                    })
-                }
-            })
-            .collect();
+        Self {
+            build_options,
+            relations: &catalog.relations,
+            nodes: NodesView {
+                nodes: &catalog.nodes,
+                build_options,
+                include_node_metadata,
+            },
+            edges: EdgesView {
+                edges: &catalog.edges,
+                build_options,
+            },
+            external: &catalog.external,
+        }
+    }
 
-        let edges = catalog
-            .edges
-            .iter()
-            .map(|edge| CatalogEdge {
-                from: edge.from.as_str(),
-                to: edge.to.as_str(),
-            })
-            .collect();
+    /// The fields written, by name in sorted key order; empty relations and
+    /// external ids are left out. Every format writes the fields listed here.
+    fn fields(&self) -> impl Iterator<Item = (&'static str, CatalogField<'_, 'a>)> {
+        [
+            Some((
+                "build_options",
+                CatalogField::BuildOptions(self.build_options),
+            )),
+            Some(("edges", CatalogField::Edges(&self.edges))),
+            (!self.external.is_empty())
+                .then_some(("external", CatalogField::External(self.external))),
+            Some(("nodes", CatalogField::Nodes(&self.nodes))),
+            (!self.relations.is_empty())
+                .then_some(("relations", CatalogField::Relations(self.relations))),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl Serialize for CatalogView<'_> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let fields = self.fields().collect::<Vec<_>>();
+        let mut map = serializer.serialize_map(Some(fields.len()))?;
+        for (name, field) in &fields {
+            map.serialize_entry(name, field)?;
+        }
+        map.end()
+    }
+}
+
+impl Serialize for CatalogField<'_, '_> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::BuildOptions(build_options) => build_options.serialize(serializer),
+            Self::Edges(edges) => edges.serialize(serializer),
+            Self::External(external) => external.serialize(serializer),
+            Self::Nodes(nodes) => nodes.serialize(serializer),
+            Self::Relations(relations) => relations.serialize(serializer),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct NodesView<'a> {
+    nodes: &'a [Node],
+    build_options: &'a RecordedOptions,
+    include_node_metadata: bool,
+}
 
-        Self { nodes, edges }
+impl<'a> NodesView<'a> {
+    fn iter(&self) -> impl ExactSizeIterator<Item = CatalogNode<'a>> + use<'a> {
+        let build_options = self.build_options;
+        let include_node_metadata = self.include_node_metadata;
+        self.nodes.iter().map(move |node| {
+            let content_hash = node
+                .content_hash
+                .as_deref()
+                .filter(|_| build_options.with_content_hash);
+            let (last_modified, authors) = if build_options.with_git_metadata {
+                (node.last_modified.as_deref(), node.authors.as_slice())
+            } else {
+                (None, &[][..])
+            };
+            let owners = if build_options.with_code_owners {
+                node.owners.as_slice()
+            } else {
+                &[]
+            };
+            if include_node_metadata {
+                CatalogNode::WithMetadata(CatalogNodeWithMetadata {
+                    id: node.id.as_str(),
+                    path: node.path.as_str(),
+                    title: node.title.as_deref(),
+                    tags: &node.tags,
+                    kind: node.kind.as_deref(),
+                    domain: node.domain.as_deref(),
+                    status: node.status.as_deref(),
+                    source_of_truth: node.source_of_truth.as_deref(),
+                    content_hash,
+                    last_modified,
+                    authors,
+                    owners,
+                })
+            } else {
+                CatalogNode::Basic(CatalogNodeBasic {
+                    id: node.id.as_str(),
+                    path: node.path.as_str(),
+                    content_hash,
+                    last_modified,
+                    authors,
+                    owners,
+                })
+            }
+        })
+    }
+}
+
+impl Serialize for NodesView<'_> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+#[derive(Debug)]
+struct EdgesView<'a> {
+    edges: &'a [Edge],
+    build_options: &'a RecordedOptions,
+}
+
+impl<'a> EdgesView<'a> {
+    fn iter(&self) -> impl ExactSizeIterator<Item = CatalogEdge<'a>> + use<'a> {
+        let with_provenance = self.build_options.with_provenance;
+        self.edges.iter().map(move |edge| CatalogEdge {
+            from: edge.from.as_str(),
+            to: edge.to.as_str(),
+            kind: edge.kind.as_deref(),
+            declared_at: edge.declared_at.as_ref().filter(|_| with_provenance),
+        })
+    }
+}
+
+impl Serialize for EdgesView<'_> {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
     }
 }
 
@@ -83,53 +251,375 @@ impl<'a> CatalogView<'a> {
 pub enum CatalogPresentationError {
     #[error("json encoding error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[cfg(feature = "msgpack")]
+    #[error("msgpack decoding error: {0}")]
+    MessagePackDecode(#[from] rmp_serde::decode::Error),
+    #[cfg(feature = "msgpack")]
+    #[error("msgpack encoding error: {0}")]
+    MessagePackEncode(#[from] rmp_serde::encode::Error),
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("{format} catalogs need docata built with the `{feature}` feature")]
+    FeatureDisabled {
+        format: &'static str,
+        feature: &'static str,
+    },
 }
 
-/// Read catalog JSON from the provided reader.
+/// Read a catalog from the provided reader, in the format its content
+/// starts with (see [`CatalogFormat::sniff`]) or else in `format`. Gzip and
+/// Zstandard content is decompressed first.
 ///
 /// # Errors
 ///
-/// Returns `CatalogPresentationError` when deserialization fails.
-pub fn read_catalog<R: Read>(input: &mut R) -> Result<Catalog, CatalogPresentationError> {
-    let catalog = serde_json::from_reader(input)?;
-    Ok(catalog)
+/// Returns `CatalogPresentationError` when reading or deserialization fails.
+pub fn read_catalog<R: Read>(
+    input: &mut R,
+    format: CatalogFormat,
+) -> Result<Catalog, CatalogPresentationError> {
+    let mut content = Vec::new();
+    input.read_to_end(&mut content)?;
+    decode(&content, format)
+}
+
+/// Deserialize catalog `content`, or a part of it, in the format its content
+/// starts with or else in `format`, decompressing it first if needed.
+pub(crate) fn decode<T: DeserializeOwned>(
+    content: &[u8],
+    format: CatalogFormat,
+) -> Result<T, CatalogPresentationError> {
+    let content = &*decompress(content)?;
+    match CatalogFormat::sniff(content).unwrap_or(format) {
+        CatalogFormat::Json => Ok(serde_json::from_slice(content)?),
+        CatalogFormat::Yaml => Ok(yaml_serde::from_slice(content)?),
+        #[cfg(feature = "msgpack")]
+        CatalogFormat::MessagePack => Ok(rmp_serde::from_slice(content)?),
+        #[cfg(not(feature = "msgpack"))]
+        CatalogFormat::MessagePack => Err(disabled("msgpack", "msgpack")),
+    }
+}
+
+/// `content` decompressed when it starts with gzip or Zstandard magic bytes,
+/// as is otherwise.
+pub(crate) fn decompress(content: &[u8]) -> Result<Cow<'_, [u8]>, CatalogPresentationError> {
+    match Compression::sniff(content) {
+        None => Ok(Cow::Borrowed(content)),
+        #[cfg(feature = "gzip")]
+        Some(Compression::Gzip) => {
+            let mut decompressed = Vec::new();
+            flate2::read::GzDecoder::new(content).read_to_end(&mut decompressed)?;
+            Ok(Cow::Owned(decompressed))
+        },
+        #[cfg(feature = "zstd")]
+        Some(Compression::Zstd) => Ok(Cow::Owned(zstd::decode_all(content)?)),
+        #[allow(unreachable_patterns)]
+        Some(compression) => Err(compression_disabled(compression)),
+    }
+}
+
+/// Whether serialized catalogs `left` and `right` hold the same bytes once
+/// decompressed, so a catalog compressed by another tool or at another
+/// level still counts as up to date.
+pub(crate) fn same_content(
+    left: &[u8],
+    right: &[u8],
+) -> bool {
+    match (decompress(left), decompress(right)) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+fn disabled(
+    format: &'static str,
+    feature: &'static str,
+) -> CatalogPresentationError {
+    CatalogPresentationError::FeatureDisabled { format, feature }
 }
 
-/// Write catalog JSON to the provided writer.
+/// The error for `compression` when docata was built without its feature.
+fn compression_disabled(compression: Compression) -> CatalogPresentationError {
+    match compression {
+        Compression::Gzip => disabled("gzip", "gzip"),
+        Compression::Zstd | Compression::None => disabled("zstd", "zstd"),
+    }
+}
+
+/// Write a catalog in `format` to the provided writer, preceded by the
+/// `build_options` it was generated with, and compressed with
+/// `compression`.
 ///
 /// # Errors
 ///
 /// Returns `CatalogPresentationError` when serialization or output fails.
 pub fn write_catalog<W: Write>(
     catalog: &Catalog,
+    build_options: &RecordedOptions,
     out: &mut W,
     include_node_metadata: bool,
+    format: CatalogFormat,
+    compact: bool,
+    compression: Compression,
 ) -> Result<(), CatalogPresentationError> {
-    let view = CatalogView::from_catalog(catalog, include_node_metadata);
+    let view = CatalogView::from_catalog(catalog, build_options, include_node_metadata);
+    match compression {
+        Compression::None => encode(&view, out, format, compact),
+        #[cfg(feature = "gzip")]
+        Compression::Gzip => {
+            let mut gzip = flate2::write::GzEncoder::new(out, flate2::Compression::default());
+            encode(&view, &mut gzip, format, compact)?;
+            gzip.finish()?;
+            Ok(())
+        },
+        #[cfg(feature = "zstd")]
+        Compression::Zstd => {
+            let mut zstd = zstd::stream::Encoder::new(out, 0)?;
+            encode(&view, &mut zstd, format, compact)?;
+            zstd.finish()?;
+            Ok(())
+        },
+        #[allow(unreachable_patterns)]
+        compression => Err(compression_disabled(compression)),
+    }
+}
 
-    serde_json::to_writer_pretty(out, &view)?;
+fn encode<W: Write>(
+    view: &CatalogView<'_>,
+    out: &mut W,
+    format: CatalogFormat,
+    compact: bool,
+) -> Result<(), CatalogPresentationError> {
+    match format {
+        CatalogFormat::Json => write_json(view, out, compact)?,
+        CatalogFormat::Yaml => yaml_serde::to_writer(out, view)?,
+        #[cfg(feature = "msgpack")]
+        CatalogFormat::MessagePack => rmp_serde::encode::write_named(out, view)?,
+        #[cfg(not(feature = "msgpack"))]
+        CatalogFormat::MessagePack => return Err(disabled("msgpack", "msgpack")),
+    }
     Ok(())
 }
 
+/// Write `view` as JSON in the shared style (see [`json`]) one node and
+/// edge at a time, so large catalogs are never held whole as a
+/// `serde_json::Value`. Fields come from [`CatalogView::fields`], in the
+/// sorted order the shared style writes keys in.
+fn write_json<W: Write>(
+    view: &CatalogView<'_>,
+    out: &mut W,
+    compact: bool,
+) -> io::Result<()> {
+    let (open, separator, close) = if compact {
+        ("{", ",", "}\n")
+    } else {
+        ("{\n  ", ",\n  ", "\n}\n")
+    };
+    let field = |out: &mut W, name: &str| {
+        if compact {
+            write!(out, "\"{name}\":")
+        } else {
+            write!(out, "\"{name}\": ")
+        }
+    };
+
+    out.write_all(open.as_bytes())?;
+    for (index, (name, value)) in view.fields().enumerate() {
+        if index > 0 {
+            out.write_all(separator.as_bytes())?;
+        }
+        field(out, name)?;
+        match value {
+            CatalogField::Edges(edges) => write_json_seq(out, edges.iter(), compact)?,
+            CatalogField::Nodes(nodes) => write_json_seq(out, nodes.iter(), compact)?,
+            value => write_json_value(out, &value, compact, 1)?,
+        }
+    }
+    out.write_all(close.as_bytes())
+}
+
+/// Write the array of `items`, a top-level field, one item at a time.
+fn write_json_seq<W: Write, T: Serialize>(
+    out: &mut W,
+    items: impl ExactSizeIterator<Item = T>,
+    compact: bool,
+) -> io::Result<()> {
+    if items.len() == 0 {
+        return out.write_all(b"[]");
+    }
+    let (open, separator, close) = if compact {
+        ("[", ",", "]")
+    } else {
+        ("[\n    ", ",\n    ", "\n  ]")
+    };
+
+    out.write_all(open.as_bytes())?;
+    for (index, item) in items.enumerate() {
+        if index > 0 {
+            out.write_all(separator.as_bytes())?;
+        }
+        write_json_value(out, &item, compact, 2)?;
+    }
+    out.write_all(close.as_bytes())
+}
+
+/// Write `value` in the shared style, indented to start `depth` levels
+/// deep. Serialized strings escape newlines, so every newline is a line
+/// break to indent.
+fn write_json_value<W: Write, T: Serialize + ?Sized>(
+    out: &mut W,
+    value: &T,
+    compact: bool,
+    depth: usize,
+) -> io::Result<()> {
+    let content = json::to_string(value, compact)?;
+    let content = content.trim_end_matches('\n');
+    if compact {
+        return out.write_all(content.as_bytes());
+    }
+    let indent = format!("\n{}", "  ".repeat(depth));
+    out.write_all(content.replace('\n', &indent).as_bytes())
+}
+
+/// A JSON catalog split into files: a manifest with the build options,
+/// relations, and external ids, one file per node, and an edges index.
+pub(crate) struct CatalogShards {
+    pub(crate) manifest: String,
+    /// Node files by id.
+    pub(crate) nodes: Vec<(String, String)>,
+    pub(crate) edges: String,
+}
+
+#[derive(Debug, Serialize)]
+struct ManifestView<'a> {
+    build_options: &'a RecordedOptions,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    relations: &'a BTreeMap<String, RelationDefinition>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    external: &'a [String],
+}
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    build_options: RecordedOptions,
+    #[serde(default)]
+    relations: BTreeMap<String, RelationDefinition>,
+    #[serde(default)]
+    external: Vec<String>,
+}
+
+/// Split serialized catalog `content` into shards written in the shared
+/// JSON style.
+pub(crate) fn split(content: &[u8]) -> Result<CatalogShards, CatalogPresentationError> {
+    let catalog: Catalog = decode(content, CatalogFormat::Json)?;
+    let build_options = RecordedOptions::read(content, CatalogFormat::Json).unwrap_or_else(|| {
+        crate::subgraph::unrecorded_options(crate::build::detect_node_metadata(
+            content,
+            CatalogFormat::Json,
+        ))
+    });
+    let view =
+        CatalogView::from_catalog(&catalog, &build_options, build_options.with_node_metadata);
+
+    Ok(CatalogShards {
+        manifest: json::to_string(
+            &ManifestView {
+                build_options: view.build_options,
+                relations: view.relations,
+                external: view.external,
+            },
+            false,
+        )?,
+        nodes: catalog
+            .nodes
+            .iter()
+            .zip(view.nodes.iter())
+            .map(|(node, shard)| Ok((node.id.clone(), json::to_string(&shard, false)?)))
+            .collect::<Result<_, serde_json::Error>>()?,
+        edges: json::to_string(&view.edges, false)?,
+    })
+}
+
+/// Join shards read back from files into the indented JSON catalog
+/// [`split`] was given, nodes sorted by id.
+pub(crate) fn join(
+    manifest: &[u8],
+    nodes: &[Vec<u8>],
+    edges: &[u8],
+) -> Result<Vec<u8>, CatalogPresentationError> {
+    let manifest: Manifest = serde_json::from_slice(manifest)?;
+    let mut nodes = nodes
+        .iter()
+        .map(|node| serde_json::from_slice::<Node>(node))
+        .collect::<Result<Vec<_>, _>>()?;
+    nodes.sort_by(|left, right| left.id.cmp(&right.id));
+    let catalog = Catalog {
+        nodes,
+        edges: serde_json::from_slice::<Vec<Edge>>(edges)?,
+        relations: manifest.relations,
+        external: manifest.external,
+    };
+
+    let mut content = Vec::new();
+    write_catalog(
+        &catalog,
+        &manifest.build_options,
+        &mut content,
+        manifest.build_options.with_node_metadata,
+        CatalogFormat::Json,
+        false,
+        Compression::None,
+    )?;
+    Ok(content)
+}
+
 #[cfg(test)]
 mod tests {
-    use super::write_catalog;
-    use crate::catalog::{Catalog, Edge, Node};
+    use super::{CatalogView, read_catalog, write_catalog};
+    use crate::build::RecordedOptions;
+    use crate::catalog::{Catalog, Edge, Node, SourceLocation};
+    use crate::config::{RelationDefinition, RelationDirection};
+    use crate::format::{CatalogFormat, Compression};
+    use std::collections::BTreeMap;
 
     fn catalog_fixture() -> Catalog {
         Catalog {
             nodes: vec![Node {
                 id: "foo".to_owned(),
                 path: "docs/foo.md".to_owned(),
+                title: Some("Foo".to_owned()),
+                tags: vec!["payments".to_owned()],
                 kind: Some("spec".to_owned()),
                 domain: Some("billing".to_owned()),
                 status: Some("draft".to_owned()),
                 source_of_truth: Some("handbook".to_owned()),
+                content_hash: None,
+                last_modified: None,
+                authors: Vec::new(),
+                owners: Vec::new(),
             }],
             edges: vec![Edge {
                 from: "foo".to_owned(),
                 to: "bar".to_owned(),
+                kind: None,
+                declared_at: None,
             }],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    fn options_fixture(with_node_metadata: bool) -> RecordedOptions {
+        RecordedOptions {
+            with_node_metadata,
+            duplicate_ids: "error".to_owned(),
+            edge_sources: vec!["deps".to_owned()],
+            config_hash: None,
+            with_provenance: false,
+            with_content_hash: false,
+            with_git_metadata: false,
+            with_code_owners: false,
         }
     }
 
@@ -137,11 +627,22 @@ mod tests {
     fn writes_basic_node_without_metadata_fields() {
         let catalog = catalog_fixture();
         let mut output = Vec::new();
-        write_catalog(&catalog, &mut output, false).expect("write catalog");
+        write_catalog(
+            &catalog,
+            &options_fixture(false),
+            &mut output,
+            false,
+            CatalogFormat::Json,
+            false,
+            Compression::None,
+        )
+        .expect("write catalog");
 
         let json = String::from_utf8(output).expect("valid utf-8");
         assert!(json.contains("\"id\": \"foo\""));
         assert!(json.contains("\"path\": \"docs/foo.md\""));
+        assert!(!json.contains("\"title\""));
+        assert!(!json.contains("\"tags\""));
         assert!(!json.contains("\"type\""));
         assert!(!json.contains("\"domain\""));
         assert!(!json.contains("\"status\""));
@@ -152,12 +653,159 @@ mod tests {
     fn writes_node_with_metadata_fields_when_enabled() {
         let catalog = catalog_fixture();
         let mut output = Vec::new();
-        write_catalog(&catalog, &mut output, true).expect("write catalog");
+        write_catalog(
+            &catalog,
+            &options_fixture(true),
+            &mut output,
+            true,
+            CatalogFormat::Json,
+            false,
+            Compression::None,
+        )
+        .expect("write catalog");
 
         let json = String::from_utf8(output).expect("valid utf-8");
+        assert!(json.contains("\"title\": \"Foo\""));
+        assert!(json.contains("\"payments\""));
         assert!(json.contains("\"type\": \"spec\""));
         assert!(json.contains("\"domain\": \"billing\""));
         assert!(json.contains("\"status\": \"draft\""));
         assert!(json.contains("\"source_of_truth\": \"handbook\""));
     }
+
+    #[test]
+    fn round_trips_yaml_catalog() {
+        let catalog = catalog_fixture();
+        let mut output = Vec::new();
+        write_catalog(
+            &catalog,
+            &options_fixture(true),
+            &mut output,
+            true,
+            CatalogFormat::Yaml,
+            false,
+            Compression::None,
+        )
+        .expect("write catalog");
+
+        let yaml = String::from_utf8(output).expect("valid utf-8");
+        assert!(yaml.contains("- id: foo"));
+
+        assert!(yaml.starts_with("build_options:\n  with_node_metadata: true\n"));
+
+        let read = read_catalog(&mut yaml.as_bytes(), CatalogFormat::Yaml).expect("read catalog");
+        assert_eq!(read.nodes[0].id, "foo");
+        assert_eq!(read.nodes[0].domain.as_deref(), Some("billing"));
+        assert_eq!(read.edges, catalog.edges);
+    }
+
+    #[test]
+    fn streams_json_identical_to_the_shared_style() {
+        let mut catalog = catalog_fixture();
+        catalog.nodes.push(Node {
+            id: "bar".to_owned(),
+            tags: Vec::new(),
+            authors: vec!["Ada".to_owned()],
+            ..catalog.nodes[0].clone()
+        });
+        catalog.edges.push(Edge {
+            from: "bar".to_owned(),
+            to: "foo".to_owned(),
+            kind: Some("supersedes".to_owned()),
+            declared_at: Some(SourceLocation {
+                path: "docs/bar.md".to_owned(),
+                line: 3,
+            }),
+        });
+        catalog.relations.insert(
+            "supersedes".to_owned(),
+            RelationDefinition {
+                field: None,
+                display: Some("Supersedes".to_owned()),
+                direction: RelationDirection::Incoming,
+            },
+        );
+        catalog.external.push("vendor".to_owned());
+        let build_options = RecordedOptions {
+            with_provenance: true,
+            with_git_metadata: true,
+            ..options_fixture(true)
+        };
+        let empty = Catalog {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        for catalog in [&catalog, &empty] {
+            for include_node_metadata in [false, true] {
+                for compact in [false, true] {
+                    let mut output = Vec::new();
+                    write_catalog(
+                        catalog,
+                        &build_options,
+                        &mut output,
+                        include_node_metadata,
+                        CatalogFormat::Json,
+                        compact,
+                        Compression::None,
+                    )
+                    .expect("write catalog");
+
+                    let view =
+                        CatalogView::from_catalog(catalog, &build_options, include_node_metadata);
+                    let expected = crate::json::to_string(&view, compact).expect("serialize view");
+                    assert_eq!(String::from_utf8(output).expect("valid utf-8"), expected);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn detects_msgpack_catalog_on_read() {
+        let catalog = catalog_fixture();
+        let mut output = Vec::new();
+        write_catalog(
+            &catalog,
+            &options_fixture(true),
+            &mut output,
+            true,
+            CatalogFormat::MessagePack,
+            false,
+            Compression::None,
+        )
+        .expect("write catalog");
+
+        let read = read_catalog(&mut output.as_slice(), CatalogFormat::Json).expect("read catalog");
+        assert_eq!(read.nodes[0].title.as_deref(), Some("Foo"));
+        assert_eq!(read.edges, catalog.edges);
+        assert!(crate::build::detect_node_metadata(
+            &output,
+            CatalogFormat::Json
+        ));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn round_trips_gzip_catalog() {
+        let catalog = catalog_fixture();
+        let mut output = Vec::new();
+        write_catalog(
+            &catalog,
+            &options_fixture(false),
+            &mut output,
+            false,
+            CatalogFormat::Json,
+            true,
+            Compression::Gzip,
+        )
+        .expect("write catalog");
+
+        assert_eq!(Compression::sniff(&output), Some(Compression::Gzip));
+        let read = read_catalog(&mut output.as_slice(), CatalogFormat::Json).expect("read catalog");
+        assert_eq!(read.edges, catalog.edges);
+        assert!(crate::build::detect_compact(&output, CatalogFormat::Json));
+    }
 }
@@ -0,0 +1,211 @@
+use crate::{
+    catalog::{Catalog, Node, normalize_path_string},
+    error::Error,
+    git,
+    graph::Graph,
+};
+use std::collections::{BTreeSet, VecDeque};
+use std::path::Path;
+
+/// A catalog document listed by [`ChangedReport`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangedDocument {
+    pub id: String,
+    pub path: String,
+    pub title: Option<String>,
+}
+
+impl From<&Node> for ChangedDocument {
+    fn from(node: &Node) -> Self {
+        Self {
+            id: node.id.clone(),
+            path: node.path.clone(),
+            title: node.title.clone(),
+        }
+    }
+}
+
+/// Catalog documents whose files changed since a git ref.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ChangedReport {
+    /// The git ref compared with the work tree.
+    pub since: String,
+    /// Documents whose files changed, sorted by id.
+    pub changed: Vec<ChangedDocument>,
+    /// Whether [`ChangedReport::referrers`] was collected.
+    pub transitive: bool,
+    /// Documents depending directly or transitively on a changed document
+    /// without changing themselves, sorted by id.
+    pub referrers: Vec<ChangedDocument>,
+}
+
+/// List the documents of `catalog` whose files differ between git ref
+/// `since` and the work tree, and with `transitive` the documents depending
+/// on them.
+///
+/// Node paths are read relative to `base`, the directory `docata build` ran
+/// in.
+///
+/// # Errors
+///
+/// Returns `Error::Git` when `base` is not inside a git work tree or `since`
+/// names no commit.
+pub fn run(
+    catalog: &Catalog,
+    base: &Path,
+    since: &str,
+    transitive: bool,
+) -> Result<ChangedReport, Error> {
+    let changed_paths = git::changed_files(base, since)
+        .map_err(|message| Error::Git { message })?
+        .into_iter()
+        .map(|path| normalize_path_string(&base.join(path)))
+        .collect::<BTreeSet<_>>();
+
+    let mut changed = catalog
+        .nodes
+        .iter()
+        .filter(|node| changed_paths.contains(&normalize_path_string(&base.join(&node.path))))
+        .collect::<Vec<_>>();
+    changed.sort_by(|left, right| left.id.cmp(&right.id));
+
+    let referrers = if transitive {
+        let ids = changed
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<BTreeSet<_>>();
+        let referrer_ids = referrers_of(&Graph::from_catalog(catalog), &ids);
+        let mut referrers = catalog
+            .nodes
+            .iter()
+            .filter(|node| referrer_ids.contains(&node.id))
+            .map(ChangedDocument::from)
+            .collect::<Vec<_>>();
+        referrers.sort_by(|left, right| left.id.cmp(&right.id));
+        referrers
+    } else {
+        Vec::new()
+    };
+
+    Ok(ChangedReport {
+        since: since.to_owned(),
+        changed: changed.into_iter().map(ChangedDocument::from).collect(),
+        transitive,
+        referrers,
+    })
+}
+
+/// Ids depending directly or transitively on any of `ids`, excluding `ids`.
+fn referrers_of(
+    graph: &Graph,
+    ids: &BTreeSet<&str>,
+) -> BTreeSet<String> {
+    let mut reached = BTreeSet::new();
+    let mut queue = ids.iter().copied().collect::<VecDeque<_>>();
+    while let Some(id) = queue.pop_front() {
+        for referrer in graph.refs(id) {
+            if !ids.contains(referrer) && reached.insert(referrer) {
+                queue.push_back(referrer);
+            }
+        }
+    }
+    reached.into_iter().map(str::to_owned).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChangedDocument, run};
+    use crate::catalog::{Catalog, Edge, Node};
+    use crate::error::Error;
+    use crate::testing::TestWorkspace;
+    use std::collections::BTreeMap;
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(
+        dir: &Path,
+        args: &[&str],
+    ) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=docata",
+                "-c",
+                "user.email=docata@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    fn node(id: &str) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("./docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    fn edge(
+        from: &str,
+        to: &str,
+    ) -> Edge {
+        Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        }
+    }
+
+    #[test]
+    fn lists_changed_documents_and_their_referrers() {
+        let workspace = TestWorkspace::new();
+        let base = workspace.path();
+        fs::create_dir(base.join("docs")).expect("create docs");
+        for id in ["api", "db", "web", "ops"] {
+            fs::write(base.join(format!("docs/{id}.md")), format!("# {id}\n")).expect("write doc");
+        }
+        git(base, &["init", "--quiet"]);
+        git(base, &["add", "."]);
+        git(base, &["commit", "--quiet", "-m", "docs"]);
+        fs::write(base.join("docs/db.md"), "# db v2\n").expect("change db");
+        let catalog = Catalog {
+            nodes: ["api", "db", "web", "ops"].map(node).into(),
+            edges: vec![edge("api", "db"), edge("web", "api")],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let report = run(&catalog, base, "HEAD", false).expect("list changed");
+        let ids = |documents: &[ChangedDocument]| {
+            documents
+                .iter()
+                .map(|document| document.id.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(ids(&report.changed), ["db"]);
+        assert!(report.referrers.is_empty());
+
+        let report = run(&catalog, base, "HEAD", true).expect("list changed");
+        assert_eq!(ids(&report.referrers), ["api", "web"]);
+
+        assert!(matches!(
+            run(&catalog, base, "no-such-ref", false),
+            Err(Error::Git { .. })
+        ));
+    }
+}
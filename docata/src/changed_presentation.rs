@@ -0,0 +1,92 @@
+use crate::changed::{ChangedDocument, ChangedReport};
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct ChangedDocumentJson<'a> {
+    id: &'a str,
+    path: &'a str,
+    title: Option<&'a str>,
+}
+
+impl<'a> From<&'a ChangedDocument> for ChangedDocumentJson<'a> {
+    fn from(document: &'a ChangedDocument) -> Self {
+        Self {
+            id: &document.id,
+            path: &document.path,
+            title: document.title.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ChangedReportJson<'a> {
+    command: &'static str,
+    since: &'a str,
+    count: usize,
+    changed: Vec<ChangedDocumentJson<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    referrers: Option<Vec<ChangedDocumentJson<'a>>>,
+}
+
+impl<'a> From<&'a ChangedReport> for ChangedReportJson<'a> {
+    fn from(report: &'a ChangedReport) -> Self {
+        Self {
+            command: "changed",
+            since: &report.since,
+            count: report.changed.len(),
+            changed: report.changed.iter().map(Into::into).collect(),
+            referrers: report
+                .transitive
+                .then(|| report.referrers.iter().map(Into::into).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ChangedPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write the documents changed since a git ref according to the selected
+/// output format. Text output lists one id per line, changed documents
+/// first, so it can feed other commands.
+///
+/// # Errors
+///
+/// Returns `ChangedPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &ChangedReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ChangedPresentationError> {
+    match format {
+        OutputFormat::Text => {
+            for document in report.changed.iter().chain(&report.referrers) {
+                writeln!(out, "{}", document.id)?;
+            }
+            Ok(())
+        },
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &ChangedReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &ChangedReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(ChangedPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
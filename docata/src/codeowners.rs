@@ -0,0 +1,185 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Where GitHub looks for a CODEOWNERS file, relative to the repository
+/// root, in order.
+const LOCATIONS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// One `pattern owner...` line of a CODEOWNERS file.
+#[derive(Debug)]
+struct Rule {
+    segments: Vec<String>,
+    /// A trailing `/` limits the pattern to directories and what they hold.
+    directory: bool,
+    owners: Vec<String>,
+}
+
+/// The rules of a repository's CODEOWNERS file.
+#[derive(Debug)]
+pub(crate) struct CodeOwners {
+    /// Directory the patterns are relative to.
+    base: PathBuf,
+    rules: Vec<Rule>,
+}
+
+impl CodeOwners {
+    /// Find the CODEOWNERS file of the repository holding `root`, looking in
+    /// `.github/`, the top level, and `docs/` of each directory from `root`
+    /// up.
+    pub(crate) fn find(root: &Path) -> Option<Self> {
+        let root = root.canonicalize().ok()?;
+        root.ancestors().find_map(|base| {
+            LOCATIONS.iter().find_map(|location| {
+                let content = fs::read_to_string(base.join(location)).ok()?;
+                Some(Self::parse(base, &content))
+            })
+        })
+    }
+
+    /// Parse CODEOWNERS `content` with patterns relative to `base`.
+    pub(crate) fn parse(
+        base: &Path,
+        content: &str,
+    ) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next()?;
+                let owners = fields
+                    .take_while(|field| !field.starts_with('#'))
+                    .map(str::to_owned)
+                    .collect();
+                Some(Rule::new(pattern, owners))
+            })
+            .collect();
+
+        Self {
+            base: base.to_path_buf(),
+            rules,
+        }
+    }
+
+    /// Owners of the file at `path`, from the last rule matching it; none
+    /// when no rule matches or `path` lies outside the repository.
+    pub(crate) fn owners(
+        &self,
+        path: &Path,
+    ) -> Vec<String> {
+        let Some(relative) = path
+            .canonicalize()
+            .ok()
+            .and_then(|path| path.strip_prefix(&self.base).ok().map(Path::to_path_buf))
+        else {
+            return Vec::new();
+        };
+        let names = relative
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| match_segments(&rule.segments, &names, rule.directory))
+            .map(|rule| rule.owners.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Rule {
+    /// A pattern with a leading or inner `/` is anchored at the base; any
+    /// other pattern matches at every depth.
+    fn new(
+        pattern: &str,
+        owners: Vec<String>,
+    ) -> Self {
+        let directory = pattern.ends_with('/');
+        let pattern = pattern.trim_end_matches('/');
+        let anchored = pattern.contains('/');
+        let mut segments = pattern
+            .trim_start_matches('/')
+            .split('/')
+            .map(str::to_owned)
+            .collect::<Vec<_>>();
+        if !anchored {
+            segments.insert(0, "**".to_owned());
+        }
+
+        Self {
+            segments,
+            directory,
+            owners,
+        }
+    }
+}
+
+/// Whether `pattern` matches a leading part of `path`: the file itself or,
+/// unless `directory`-only patterns match it exactly, a directory holding it.
+fn match_segments(
+    pattern: &[String],
+    path: &[String],
+    directory: bool,
+) -> bool {
+    match pattern.split_first() {
+        None => !path.is_empty() || !directory,
+        Some((segment, rest)) if segment == "**" => {
+            (0..=path.len()).any(|skip| match_segments(rest, &path[skip..], directory))
+        },
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path)| {
+            match_name(segment.as_bytes(), name.as_bytes()) && match_segments(rest, path, directory)
+        }),
+    }
+}
+
+/// Match one path segment against a pattern with `*` and `?` wildcards.
+fn match_name(
+    pattern: &[u8],
+    name: &[u8],
+) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => (0..=name.len()).any(|skip| match_name(rest, &name[skip..])),
+        Some((b'?', rest)) => name
+            .split_first()
+            .is_some_and(|(_, name)| match_name(rest, name)),
+        Some((byte, rest)) => name
+            .split_first()
+            .is_some_and(|(first, name)| first == byte && match_name(rest, name)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CodeOwners;
+    use crate::testing::TestWorkspace;
+    use std::fs;
+
+    #[test]
+    fn resolves_owners_from_the_last_matching_rule() {
+        let workspace = TestWorkspace::new();
+        let base = workspace.path();
+        for file in ["docs/api.md", "docs/billing/invoice.md", "docs/notes.txt"] {
+            let path = base.join(file);
+            fs::create_dir_all(path.parent().expect("parent")).expect("create directory");
+            fs::write(path, "").expect("write file");
+        }
+        let owners = CodeOwners::parse(
+            &base.canonicalize().expect("canonical base"),
+            "# Docs\n*.md @org/docs\n/docs/billing/ @org/billing @ann # billing\n*.txt\n",
+        );
+        let owners_of = |file: &str| owners.owners(&base.join(file));
+
+        assert_eq!(owners_of("docs/api.md"), ["@org/docs"]);
+        assert_eq!(
+            owners_of("docs/billing/invoice.md"),
+            ["@org/billing", "@ann"]
+        );
+        assert!(owners_of("docs/notes.txt").is_empty());
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{error::Error, graph::Graph};
+use std::collections::BTreeMap;
+
+/// How `docata common` compares the dependencies of several ids.
+#[derive(Clone, Debug, Default)]
+pub struct CommonOptions {
+    /// Compare direct dependencies only instead of transitive ones.
+    pub direct: bool,
+    /// Keep dependencies shared by at least this many query ids; every
+    /// query id when unset.
+    pub min_shared: Option<usize>,
+}
+
+/// A dependency reached from several query ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SharedDependency {
+    pub id: String,
+    /// Whether `id` is a catalog node.
+    pub resolved: bool,
+    /// Query ids depending on `id`, in query order.
+    pub shared_by: Vec<String>,
+}
+
+/// Dependencies several documents have in common.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommonDependencies {
+    /// Distinct query ids, in query order.
+    pub query_ids: Vec<String>,
+    pub transitive: bool,
+    pub min_shared: usize,
+    /// Most widely shared first, then by id.
+    pub items: Vec<SharedDependency>,
+}
+
+/// Find the dependencies shared by the documents `query_ids`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when a query id is not a catalog node.
+pub fn build(
+    query_ids: &[&str],
+    graph: &Graph,
+    options: &CommonOptions,
+) -> Result<CommonDependencies, Error> {
+    let mut distinct = Vec::<String>::with_capacity(query_ids.len());
+    for &query_id in query_ids {
+        if !graph.contains(query_id) {
+            return Err(Error::NodeNotFound {
+                id: query_id.to_owned(),
+            });
+        }
+        if !distinct.iter().any(|id| id == query_id) {
+            distinct.push(query_id.to_owned());
+        }
+    }
+
+    let resolve = if options.direct {
+        Graph::deps
+    } else {
+        Graph::transitive_deps
+    };
+    let mut shared = BTreeMap::<String, Vec<String>>::new();
+    for query_id in &distinct {
+        let mut deps = resolve(graph, query_id);
+        deps.sort_unstable();
+        deps.dedup();
+        for dep in deps {
+            shared
+                .entry(dep.to_owned())
+                .or_default()
+                .push(query_id.clone());
+        }
+    }
+
+    let min_shared = options.min_shared.unwrap_or(distinct.len());
+    let mut items = shared
+        .into_iter()
+        .filter(|(_, shared_by)| shared_by.len() >= min_shared)
+        .map(|(id, shared_by)| SharedDependency {
+            resolved: graph.contains(&id),
+            id,
+            shared_by,
+        })
+        .collect::<Vec<_>>();
+    items.sort_by(|left, right| {
+        right
+            .shared_by
+            .len()
+            .cmp(&left.shared_by.len())
+            .then_with(|| left.id.cmp(&right.id))
+    });
+
+    Ok(CommonDependencies {
+        query_ids: distinct,
+        transitive: !options.direct,
+        min_shared,
+        items,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommonOptions, build};
+    use crate::catalog::{Catalog, Edge, Node};
+    use crate::graph::Graph;
+    use std::collections::BTreeMap;
+
+    fn graph() -> Graph {
+        let node = |id: &str| Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        };
+        let edge = |from: &str, to: &str| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        };
+        Graph::from_catalog(&Catalog {
+            nodes: [
+                "billing", "glossary", "invoices", "platform", "refunds", "style",
+            ]
+            .into_iter()
+            .map(node)
+            .collect(),
+            edges: vec![
+                edge("invoices", "billing"),
+                edge("refunds", "billing"),
+                edge("refunds", "style"),
+                edge("billing", "platform"),
+                edge("platform", "glossary"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        })
+    }
+
+    fn ids(
+        query_ids: &[&str],
+        options: &CommonOptions,
+    ) -> Vec<(String, usize)> {
+        build(query_ids, &graph(), options)
+            .expect("common dependencies")
+            .items
+            .into_iter()
+            .map(|item| (item.id, item.shared_by.len()))
+            .collect()
+    }
+
+    #[test]
+    fn intersects_transitive_dependencies() {
+        let shared = |id: &str| (id.to_owned(), 2);
+
+        assert_eq!(
+            ids(&["invoices", "refunds"], &CommonOptions::default()),
+            [shared("billing"), shared("glossary"), shared("platform")]
+        );
+        assert_eq!(
+            ids(
+                &["invoices", "refunds"],
+                &CommonOptions {
+                    direct: true,
+                    ..CommonOptions::default()
+                }
+            ),
+            [shared("billing")]
+        );
+    }
+
+    #[test]
+    fn ranks_partially_shared_dependencies() {
+        let options = CommonOptions {
+            direct: true,
+            min_shared: Some(1),
+        };
+
+        assert_eq!(
+            ids(&["invoices", "refunds", "invoices"], &options),
+            [("billing".to_owned(), 2), ("style".to_owned(), 1)]
+        );
+        assert!(
+            build(&["invoices", "nope"], &graph(), &options).is_err(),
+            "unknown ids are rejected"
+        );
+    }
+}
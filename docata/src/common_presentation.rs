@@ -0,0 +1,105 @@
+use crate::common::CommonDependencies;
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct CommonDependenciesJson<'a> {
+    command: &'static str,
+    query_ids: &'a [String],
+    transitive: bool,
+    min_shared: usize,
+    count: usize,
+    items: Vec<SharedDependencyJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct SharedDependencyJson<'a> {
+    id: &'a str,
+    resolved: bool,
+    shared_by: &'a [String],
+}
+
+impl<'a> From<&'a CommonDependencies> for CommonDependenciesJson<'a> {
+    fn from(common: &'a CommonDependencies) -> Self {
+        Self {
+            command: "common",
+            query_ids: &common.query_ids,
+            transitive: common.transitive,
+            min_shared: common.min_shared,
+            count: common.items.len(),
+            items: common
+                .items
+                .iter()
+                .map(|item| SharedDependencyJson {
+                    id: &item.id,
+                    resolved: item.resolved,
+                    shared_by: &item.shared_by,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CommonPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write common dependencies according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `CommonPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    common: &CommonDependencies,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), CommonPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(common, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &CommonDependenciesJson::from(common), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &CommonDependenciesJson::from(common))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(CommonPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+/// One id per line; ids not shared by every query id name the ones sharing
+/// them.
+fn write_text<W: Write>(
+    common: &CommonDependencies,
+    out: &mut W,
+) -> Result<(), CommonPresentationError> {
+    for item in &common.items {
+        if item.shared_by.len() == common.query_ids.len() {
+            writeln!(out, "{}", item.id)?;
+        } else {
+            writeln!(
+                out,
+                "{} ({}/{}: {})",
+                item.id,
+                item.shared_by.len(),
+                common.query_ids.len(),
+                item.shared_by.join(", ")
+            )?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,160 @@
+use crate::{catalog::Catalog, validate::strongly_connected_components};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Strongly and weakly connected components of a catalog's dependency graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ComponentReport {
+    pub node_count: usize,
+    /// Groups of documents all depending on each other, with more than one
+    /// document, largest first.
+    pub strongly_connected: Vec<Vec<String>>,
+    /// Groups of documents linked by dependencies in either direction,
+    /// largest first. Documents without any resolved edge form their own
+    /// group.
+    pub weakly_connected: Vec<Vec<String>>,
+}
+
+/// Find the connected components among the catalog nodes of `catalog`.
+/// Dependencies missing from the catalog are ignored.
+#[must_use]
+pub fn build(catalog: &Catalog) -> ComponentReport {
+    let mut adjacency = catalog
+        .nodes
+        .iter()
+        .map(|node| (node.id.clone(), BTreeSet::new()))
+        .collect::<BTreeMap<_, _>>();
+    for edge in catalog.dependency_edges() {
+        if adjacency.contains_key(&edge.to)
+            && let Some(deps) = adjacency.get_mut(&edge.from)
+        {
+            deps.insert(edge.to.clone());
+        }
+    }
+
+    let mut strongly_connected = strongly_connected_components(&adjacency)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .collect::<Vec<_>>();
+    sort_components(&mut strongly_connected);
+
+    let mut weakly_connected = weakly_connected_components(&adjacency);
+    sort_components(&mut weakly_connected);
+
+    ComponentReport {
+        node_count: adjacency.len(),
+        strongly_connected,
+        weakly_connected,
+    }
+}
+
+/// Union-find over the edges with path halving; every member list comes out
+/// sorted because ids are visited in order.
+fn weakly_connected_components(adjacency: &BTreeMap<String, BTreeSet<String>>) -> Vec<Vec<String>> {
+    let index_of = adjacency
+        .keys()
+        .enumerate()
+        .map(|(index, id)| (id.as_str(), index))
+        .collect::<BTreeMap<_, _>>();
+    let mut parent = (0..adjacency.len()).collect::<Vec<_>>();
+
+    for (from, deps) in adjacency {
+        for to in deps {
+            let from = find(&mut parent, index_of[from.as_str()]);
+            let to = find(&mut parent, index_of[to.as_str()]);
+            parent[from.max(to)] = from.min(to);
+        }
+    }
+
+    let mut components = BTreeMap::<usize, Vec<String>>::new();
+    for (index, id) in adjacency.keys().enumerate() {
+        let root = find(&mut parent, index);
+        components.entry(root).or_default().push(id.clone());
+    }
+    components.into_values().collect()
+}
+
+fn find(
+    parent: &mut [usize],
+    mut index: usize,
+) -> usize {
+    while parent[index] != index {
+        parent[index] = parent[parent[index]];
+        index = parent[index];
+    }
+    index
+}
+
+/// Largest first, ties broken by the smallest id.
+fn sort_components(components: &mut [Vec<String>]) {
+    components.sort_by(|left, right| right.len().cmp(&left.len()).then_with(|| left.cmp(right)));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build;
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn catalog(
+        ids: &[&str],
+        edges: &[(&str, &str)],
+    ) -> Catalog {
+        Catalog {
+            nodes: ids
+                .iter()
+                .map(|id| Node {
+                    id: (*id).to_owned(),
+                    path: format!("docs/{id}.md"),
+                    title: None,
+                    tags: Vec::new(),
+                    kind: None,
+                    domain: None,
+                    status: None,
+                    source_of_truth: None,
+                    content_hash: None,
+                    last_modified: None,
+                    authors: Vec::new(),
+                    owners: Vec::new(),
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: (*from).to_owned(),
+                    to: (*to).to_owned(),
+                    kind: None,
+                    declared_at: None,
+                })
+                .collect(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn groups_tangles_and_islands() {
+        let catalog = catalog(
+            &["api", "db", "guide", "island", "ops", "runbook"],
+            &[
+                ("api", "db"),
+                ("db", "api"),
+                ("guide", "api"),
+                ("ops", "runbook"),
+                ("runbook", "missing"),
+            ],
+        );
+
+        let report = build(&catalog);
+
+        assert_eq!(report.node_count, 6);
+        assert_eq!(report.strongly_connected, vec![vec!["api", "db"]]);
+        assert_eq!(
+            report.weakly_connected,
+            vec![
+                vec!["api", "db", "guide"],
+                vec!["ops", "runbook"],
+                vec!["island"],
+            ]
+        );
+    }
+}
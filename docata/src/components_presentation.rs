@@ -0,0 +1,97 @@
+use crate::components::ComponentReport;
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct ComponentReportJson<'a> {
+    command: &'static str,
+    nodes: usize,
+    strongly_connected: Vec<ComponentJson<'a>>,
+    weakly_connected: Vec<ComponentJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComponentJson<'a> {
+    size: usize,
+    ids: &'a [String],
+}
+
+impl<'a> From<&'a ComponentReport> for ComponentReportJson<'a> {
+    fn from(report: &'a ComponentReport) -> Self {
+        let components = |components: &'a [Vec<String>]| {
+            components
+                .iter()
+                .map(|ids| ComponentJson {
+                    size: ids.len(),
+                    ids,
+                })
+                .collect()
+        };
+
+        Self {
+            command: "components",
+            nodes: report.node_count,
+            strongly_connected: components(&report.strongly_connected),
+            weakly_connected: components(&report.weakly_connected),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ComponentsPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write connected components according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `ComponentsPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &ComponentReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ComponentsPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &ComponentReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &ComponentReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(ComponentsPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_text<W: Write>(
+    report: &ComponentReport,
+    out: &mut W,
+) -> Result<(), ComponentsPresentationError> {
+    writeln!(out, "nodes: {}", report.node_count)?;
+    let sections = [
+        ("strongly connected", &report.strongly_connected),
+        ("weakly connected", &report.weakly_connected),
+    ];
+    for (label, components) in sections {
+        writeln!(out, "{label} components: {}", components.len())?;
+        for ids in components {
+            writeln!(out, "  {}: {}", ids.len(), ids.join(", "))?;
+        }
+    }
+    Ok(())
+}
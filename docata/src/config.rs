@@ -0,0 +1,574 @@
+use crate::scan::Entry;
+use crate::validate::ValidationRules;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+use walkdir::WalkDir;
+
+/// File name marking a docata root.
+pub const CONFIG_FILE_NAME: &str = "docata.toml";
+
+const DEFAULT_DOCS_DIR: &str = "docs";
+
+/// Contents of a `docata.toml` file.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub build: BuildConfig,
+    pub validation: ValidationRules,
+    /// `[types.<type>]` tables keyed by document `type`.
+    pub types: BTreeMap<String, TypeContract>,
+    /// `[[overrides]]` tables, applied in order.
+    pub overrides: Vec<RuleOverride>,
+    /// `[relations.<kind>]` tables declaring relation kinds besides `deps`.
+    pub relations: BTreeMap<String, RelationDefinition>,
+    /// `[namespaces]` table: id prefixes derived from directories.
+    pub namespaces: NamespaceConfig,
+}
+
+/// `[namespaces]` table: prefixes added to the ids of documents by the
+/// directory they sit in below the documents directory, so documents of
+/// different directories may share an `id` in frontmatter.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct NamespaceConfig {
+    /// Prefix the ids of documents under a directory with the directory name
+    /// and `/`, e.g. `adr/0001` for `adr/0001.md` with `id: "0001"`.
+    pub directories: bool,
+    /// Prefixes by directory, relative to the documents directory, taking
+    /// precedence over `directories`; the longest matching directory wins and
+    /// `""` leaves a directory unprefixed.
+    pub prefixes: BTreeMap<String, String>,
+}
+
+impl NamespaceConfig {
+    /// The prefix for a document at `relative`, a path below the documents
+    /// directory, if any.
+    #[must_use]
+    pub fn prefix(
+        &self,
+        relative: &Path,
+    ) -> Option<String> {
+        let directory = relative.parent()?;
+        let configured = self
+            .prefixes
+            .iter()
+            .filter(|(prefix_directory, _)| directory.starts_with(prefix_directory))
+            .max_by_key(|(prefix_directory, _)| Path::new(prefix_directory).components().count());
+        let prefix = match configured {
+            Some((_, prefix)) => prefix.clone(),
+            None if self.directories => {
+                let first = directory.components().next()?;
+                format!("{}/", first.as_os_str().to_string_lossy())
+            },
+            None => return None,
+        };
+        (!prefix.is_empty()).then_some(prefix)
+    }
+}
+
+/// `[build]` table: where documents and the committed catalog live, relative
+/// to the directory containing `docata.toml`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct BuildConfig {
+    pub docs: Option<PathBuf>,
+    pub catalog: Option<PathBuf>,
+    /// Whether the committed catalog includes node metadata; detected from
+    /// the catalog when unset.
+    pub with_node_metadata: Option<bool>,
+}
+
+impl BuildConfig {
+    /// Documents directory, defaulting to `docs`.
+    #[must_use]
+    pub fn docs_dir(&self) -> &Path {
+        self.docs
+            .as_deref()
+            .unwrap_or_else(|| Path::new(DEFAULT_DOCS_DIR))
+    }
+}
+
+/// `[types.<type>]` table: how documents of one `type` are written and what
+/// their frontmatter must contain.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TypeContract {
+    /// Frontmatter fields every document of this type must set, e.g.
+    /// `["service", "oncall"]` for runbooks.
+    pub required: Vec<String>,
+    /// Template for new documents of this type, relative to the directory
+    /// containing `docata.toml`.
+    pub template: Option<PathBuf>,
+}
+
+/// `[relations.<kind>]` table: a frontmatter field whose ids produce catalog
+/// edges of one kind.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RelationDefinition {
+    /// Frontmatter field listing the related ids, defaulting to the kind name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    /// Name shown in exports instead of the kind name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display: Option<String>,
+    pub direction: RelationDirection,
+}
+
+impl RelationDefinition {
+    /// Frontmatter field read for the relation kind `kind`.
+    #[must_use]
+    pub fn field<'a>(
+        &'a self,
+        kind: &'a str,
+    ) -> &'a str {
+        self.field.as_deref().unwrap_or(kind)
+    }
+}
+
+/// Which way the edges of a relation kind point.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RelationDirection {
+    /// From the document declaring the field to each listed id, like `deps`.
+    #[default]
+    Outgoing,
+    /// From each listed id to the document declaring the field, e.g. a
+    /// `supersedes` field stored on the newer document.
+    Incoming,
+}
+
+/// `[[overrides]]` table: rules for the documents of one `domain` and/or
+/// under one `path`, merged over the global `[validation]` and `[types]`
+/// rules. Unset fields keep the global value.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RuleOverride {
+    /// Match documents with this `domain`.
+    pub domain: Option<String>,
+    /// Match documents under this directory, relative to the documents
+    /// directory.
+    pub path: Option<PathBuf>,
+    pub orphans: Option<bool>,
+    pub orphan_root_types: Option<Vec<String>>,
+    pub broken_links: Option<bool>,
+    pub statuses: Option<Vec<String>>,
+    /// Contracts replacing the global ones for the same `type`.
+    pub types: BTreeMap<String, TypeContract>,
+}
+
+impl RuleOverride {
+    /// Whether the override applies to `entry`, scanned from `root`. An
+    /// override with neither `domain` nor `path` applies to every document.
+    pub(crate) fn matches(
+        &self,
+        root: &Path,
+        entry: &Entry,
+    ) -> bool {
+        let domain_matches = self
+            .domain
+            .as_ref()
+            .is_none_or(|domain| entry.domain.as_ref() == Some(domain));
+        let path_matches = self.path.as_ref().is_none_or(|prefix| {
+            entry
+                .path
+                .strip_prefix(root)
+                .unwrap_or(&entry.path)
+                .starts_with(prefix)
+        });
+        domain_matches && path_matches
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("failed to read config '{path}': {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to parse config '{path}': {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("no {CONFIG_FILE_NAME} found at or above '{root}'")]
+    NotFound { root: PathBuf },
+    #[error("failed to encode config: {0}")]
+    Encode(#[from] toml::ser::Error),
+    #[error("failed to search for config files in '{root}': {source}")]
+    Discover {
+        root: PathBuf,
+        #[source]
+        source: walkdir::Error,
+    },
+}
+
+impl Config {
+    /// Load a config file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` when the file cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        toml::from_str(&contents).map_err(|source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Load a config file like [`Config::load`], also reporting keys no
+    /// table of `docata.toml` defines. Unknown keys are otherwise ignored, so
+    /// a misspelled key silently keeps the default.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` when the file cannot be read or parsed.
+    pub fn load_checked(path: &Path) -> Result<(Self, Vec<ConfigWarning>), ConfigError> {
+        let contents = std::fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_path_buf(),
+            source,
+        })?;
+        let parse_error = |source| ConfigError::Parse {
+            path: path.to_path_buf(),
+            source,
+        };
+
+        let config = toml::from_str(&contents).map_err(parse_error)?;
+        let document = toml::de::DeTable::parse(&contents).map_err(parse_error)?;
+        let mut warnings = Vec::new();
+        unknown_keys(
+            &toml::de::DeValue::Table(document.into_inner()),
+            &CONFIG_SCHEMA,
+            "",
+            &contents,
+            &mut warnings,
+        );
+        warnings.sort_by_key(|warning| (warning.line, warning.column));
+
+        Ok((config, warnings))
+    }
+
+    /// This config with defaults filled in, as `docata` applies it.
+    #[must_use]
+    pub fn resolved(&self) -> Self {
+        let mut config = self.clone();
+        config.build.docs = Some(self.build.docs_dir().to_path_buf());
+        for (kind, relation) in &mut config.relations {
+            relation.field = Some(relation.field(kind).to_owned());
+        }
+        config
+    }
+
+    /// Encode this config as `docata.toml` contents.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::Encode` when the config cannot be encoded.
+    pub fn to_toml(&self) -> Result<String, ConfigError> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// `[validation]` rules with the required fields of every `[types]`
+    /// table and the `[[overrides]]` tables applied.
+    #[must_use]
+    pub fn validation_rules(&self) -> ValidationRules {
+        ValidationRules {
+            types: self.types.clone(),
+            overrides: self.overrides.clone(),
+            ..self.validation.clone()
+        }
+    }
+
+    /// Load the nearest `docata.toml` at or above `root`, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError` when the file found cannot be read or parsed.
+    pub fn nearest(root: &Path) -> Result<Option<Self>, ConfigError> {
+        find_config_file(root)
+            .map(|path| Self::load(&path))
+            .transpose()
+    }
+}
+
+/// A key in `docata.toml` that no table defines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigWarning {
+    /// Dotted path of the key, e.g. `build.dcos` or `overrides[0].domian`.
+    pub key: String,
+    /// 1-based line of the key.
+    pub line: usize,
+    /// 1-based column of the key.
+    pub column: usize,
+}
+
+/// Result of checking one `docata.toml`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigReport {
+    pub path: PathBuf,
+    pub warnings: Vec<ConfigWarning>,
+}
+
+/// Keys each table of `docata.toml` defines, mirroring the config structs.
+enum Schema {
+    Table(&'static [(&'static str, Schema)]),
+    /// A table with arbitrary keys, e.g. `[types]` keyed by document type.
+    Map(&'static Schema),
+    Array(&'static Schema),
+    Value,
+}
+
+const TYPE_CONTRACT_SCHEMA: Schema =
+    Schema::Table(&[("required", Schema::Value), ("template", Schema::Value)]);
+
+const CONFIG_SCHEMA: Schema = Schema::Table(&[
+    (
+        "build",
+        Schema::Table(&[
+            ("docs", Schema::Value),
+            ("catalog", Schema::Value),
+            ("with_node_metadata", Schema::Value),
+        ]),
+    ),
+    (
+        "validation",
+        Schema::Table(&[
+            ("orphans", Schema::Value),
+            ("orphan_root_types", Schema::Value),
+            ("allowed_domain_deps", Schema::Map(&Schema::Value)),
+            ("broken_links", Schema::Value),
+            ("report_skipped", Schema::Value),
+            ("lenient", Schema::Value),
+            ("statuses", Schema::Value),
+            ("external_ids", Schema::Value),
+            ("removed_ids", Schema::Value),
+            ("stale_after_days", Schema::Value),
+            ("stale_statuses", Schema::Value),
+        ]),
+    ),
+    ("types", Schema::Map(&TYPE_CONTRACT_SCHEMA)),
+    (
+        "overrides",
+        Schema::Array(&Schema::Table(&[
+            ("domain", Schema::Value),
+            ("path", Schema::Value),
+            ("orphans", Schema::Value),
+            ("orphan_root_types", Schema::Value),
+            ("broken_links", Schema::Value),
+            ("statuses", Schema::Value),
+            ("types", Schema::Map(&TYPE_CONTRACT_SCHEMA)),
+        ])),
+    ),
+    (
+        "relations",
+        Schema::Map(&Schema::Table(&[
+            ("field", Schema::Value),
+            ("display", Schema::Value),
+            ("direction", Schema::Value),
+        ])),
+    ),
+    (
+        "namespaces",
+        Schema::Table(&[
+            ("directories", Schema::Value),
+            ("prefixes", Schema::Map(&Schema::Value)),
+        ]),
+    ),
+]);
+
+/// Collect the keys under `value` that `schema` does not define. Values of
+/// the wrong type are left to deserialization to report.
+fn unknown_keys(
+    value: &toml::de::DeValue<'_>,
+    schema: &Schema,
+    prefix: &str,
+    contents: &str,
+    warnings: &mut Vec<ConfigWarning>,
+) {
+    let key_path = |key: &str| {
+        if prefix.is_empty() {
+            key.to_owned()
+        } else {
+            format!("{prefix}.{key}")
+        }
+    };
+
+    match (schema, value) {
+        (Schema::Table(fields), toml::de::DeValue::Table(table)) => {
+            for (key, value) in table {
+                let path = key_path(key.get_ref());
+                if let Some((_, schema)) = fields.iter().find(|(name, _)| name == key.get_ref()) {
+                    unknown_keys(value.get_ref(), schema, &path, contents, warnings);
+                } else {
+                    let (line, column) = position(contents, key.span().start);
+                    warnings.push(ConfigWarning {
+                        key: path,
+                        line,
+                        column,
+                    });
+                }
+            }
+        },
+        (Schema::Map(schema), toml::de::DeValue::Table(table)) => {
+            for (key, value) in table {
+                let path = key_path(key.get_ref());
+                unknown_keys(value.get_ref(), schema, &path, contents, warnings);
+            }
+        },
+        (Schema::Array(schema), toml::de::DeValue::Array(items)) => {
+            for (index, item) in items.iter().enumerate() {
+                let path = format!("{prefix}[{index}]");
+                unknown_keys(item.get_ref(), schema, &path, contents, warnings);
+            }
+        },
+        _ => {},
+    }
+}
+
+/// 1-based line and column of byte `offset` in `contents`.
+fn position(
+    contents: &str,
+    offset: usize,
+) -> (usize, usize) {
+    let before = &contents[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+/// Path of the nearest `docata.toml` at or above `root`.
+#[must_use]
+pub fn find_config_file(root: &Path) -> Option<PathBuf> {
+    let root = root.canonicalize().ok()?;
+    root.ancestors()
+        .map(|directory| directory.join(CONFIG_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+/// Find directories under `root` containing a `docata.toml`, sorted by path.
+///
+/// Hidden directories and `target`/`node_modules` are not searched.
+///
+/// # Errors
+///
+/// Returns `ConfigError::Discover` when walking the directory fails.
+pub fn discover_roots(root: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let mut roots = Vec::new();
+
+    let walker = WalkDir::new(root).into_iter().filter_entry(|entry| {
+        entry.depth() == 0
+            || !entry.file_type().is_dir()
+            || !entry.file_name().to_str().is_some_and(|name| {
+                name.starts_with('.') || name == "target" || name == "node_modules"
+            })
+    });
+
+    for entry in walker {
+        let entry = entry.map_err(|source| ConfigError::Discover {
+            root: root.to_path_buf(),
+            source,
+        })?;
+
+        if entry.file_type().is_file()
+            && entry.file_name() == CONFIG_FILE_NAME
+            && let Some(parent) = entry.path().parent()
+        {
+            roots.push(parent.to_path_buf());
+        }
+    }
+
+    roots.sort();
+    Ok(roots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Config, ConfigWarning};
+    use crate::testing::TestWorkspace;
+    use std::path::Path;
+
+    #[test]
+    fn parses_build_table_with_defaults() {
+        let config: Config =
+            toml::from_str("[build]\ncatalog = \"docs/catalog.json\"\nwith_node_metadata = true\n")
+                .expect("parse config");
+
+        assert_eq!(config.build.docs_dir(), Path::new("docs"));
+        assert_eq!(
+            config.build.catalog.as_deref(),
+            Some(Path::new("docs/catalog.json"))
+        );
+        assert_eq!(config.build.with_node_metadata, Some(true));
+
+        let empty: Config = toml::from_str("").expect("parse empty config");
+        assert!(empty.build.catalog.is_none());
+        assert!(empty.build.with_node_metadata.is_none());
+    }
+
+    #[test]
+    fn applies_type_contracts_to_validation_rules() {
+        let config: Config = toml::from_str(
+            "[validation]\norphans = true\n\n[types.runbook]\nrequired = [\"service\", \"oncall\"]\ntemplate = \"templates/runbook.md\"\n",
+        )
+        .expect("parse config");
+
+        let rules = config.validation_rules();
+        assert!(rules.orphans);
+        assert_eq!(rules.types["runbook"].required, ["service", "oncall"]);
+        assert_eq!(
+            config.types["runbook"].template.as_deref(),
+            Some(Path::new("templates/runbook.md"))
+        );
+    }
+
+    #[test]
+    fn reports_unknown_keys_with_locations() {
+        let workspace = TestWorkspace::new();
+        let path = workspace.path().join("docata.toml");
+        std::fs::write(
+            &path,
+            "[build]\ndcos = \"pages\"\n\n[validation]\norphans = true\n\n[validation.allowed_domain_deps]\nfrontend = [\"api\"]\n\n[types.runbook]\nrequired = [\"service\"]\ntemplat = \"t.md\"\n\n[[overrides]]\ndomian = \"billing\"\n\n[relations.supersedes]\ndirection = \"incoming\"\n",
+        )
+        .expect("write config");
+
+        let (config, warnings) = Config::load_checked(&path).expect("load config");
+
+        assert!(config.validation.orphans);
+        assert_eq!(
+            warnings,
+            vec![
+                ConfigWarning {
+                    key: "build.dcos".to_owned(),
+                    line: 2,
+                    column: 1,
+                },
+                ConfigWarning {
+                    key: "types.runbook.templat".to_owned(),
+                    line: 12,
+                    column: 1,
+                },
+                ConfigWarning {
+                    key: "overrides[0].domian".to_owned(),
+                    line: 15,
+                    column: 1,
+                },
+            ]
+        );
+
+        let resolved = config.resolved().to_toml().expect("encode config");
+        assert!(resolved.contains("docs = \"docs\""), "{resolved}");
+        assert!(resolved.contains("field = \"supersedes\""), "{resolved}");
+    }
+}
@@ -0,0 +1,99 @@
+use crate::config::ConfigReport;
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct ConfigReportJson<'a> {
+    command: &'static str,
+    path: String,
+    warnings: Vec<ConfigWarningJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ConfigWarningJson<'a> {
+    key: &'a str,
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+impl<'a> From<&'a ConfigReport> for ConfigReportJson<'a> {
+    fn from(report: &'a ConfigReport) -> Self {
+        Self {
+            command: "config check",
+            path: report.path.display().to_string(),
+            warnings: report
+                .warnings
+                .iter()
+                .map(|warning| ConfigWarningJson {
+                    key: &warning.key,
+                    line: warning.line,
+                    column: warning.column,
+                    message: format!("unknown key '{}'", warning.key),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ConfigPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a config check report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `ConfigPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &ConfigReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ConfigPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &ConfigReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &ConfigReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(ConfigPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+/// One `path:line:column: warning: ...` line per warning, like a compiler.
+fn write_text<W: Write>(
+    report: &ConfigReport,
+    out: &mut W,
+) -> Result<(), ConfigPresentationError> {
+    let path = report.path.display();
+    for warning in &report.warnings {
+        writeln!(
+            out,
+            "{path}:{}:{}: warning: unknown key '{}'",
+            warning.line, warning.column, warning.key
+        )?;
+    }
+    if report.warnings.is_empty() {
+        writeln!(out, "{path}: ok")?;
+    } else {
+        writeln!(out, "{path}: {} warning(s)", report.warnings.len())?;
+    }
+    Ok(())
+}
@@ -0,0 +1,575 @@
+use crate::catalog::Catalog;
+use std::collections::{BTreeMap, HashMap};
+
+/// A document whose removal cuts other documents off.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CriticalNode {
+    pub id: String,
+    pub path: String,
+    /// Documents cut off from the rest of the graph when this one is removed.
+    pub disconnected: usize,
+}
+
+/// A dependency edge whose removal splits its connected component.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bridge {
+    pub from: String,
+    pub to: String,
+    /// Documents on the smaller side of the split.
+    pub disconnected: usize,
+}
+
+/// Single points of failure of a catalog's dependency graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CriticalReport {
+    /// Articulation points of the graph with edge directions ignored, most
+    /// disconnecting first.
+    pub articulation_points: Vec<CriticalNode>,
+    /// Bridges of the graph with edge directions ignored, most disconnecting
+    /// first.
+    pub bridges: Vec<Bridge>,
+    /// Documents dominating others from [`CriticalOptions::root`], most
+    /// dominating first; empty without a root.
+    pub dominators: Vec<CriticalNode>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CriticalOptions {
+    /// Also rank documents by how many documents reachable from this id are
+    /// only reachable through them.
+    pub root: Option<String>,
+    /// Keep at most this many entries in each list.
+    pub limit: Option<usize>,
+}
+
+/// Dominator tree of the documents reachable from `root` along dependency
+/// edges: a document dominates another when every dependency path from the
+/// root to the other passes through it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DominatorTree {
+    pub root: String,
+    /// Immediate dominator of every reachable document except the root.
+    pub immediate_dominators: BTreeMap<String, String>,
+}
+
+impl DominatorTree {
+    /// Number of documents each document strictly dominates; documents
+    /// dominating nothing are omitted.
+    #[must_use]
+    pub fn dominated_counts(&self) -> BTreeMap<String, usize> {
+        let mut counts = BTreeMap::<String, usize>::new();
+        for id in self.immediate_dominators.keys() {
+            let mut current = id;
+            while let Some(dominator) = self.immediate_dominators.get(current) {
+                *counts.entry(dominator.clone()).or_default() += 1;
+                current = dominator;
+            }
+        }
+        counts
+    }
+}
+
+/// Compute the dominator tree of `catalog` rooted at `root`, or `None` when
+/// `root` is not a catalog node.
+///
+/// Uses the iterative algorithm of Cooper, Harvey, and Kennedy over the
+/// reverse postorder of a depth-first search from the root.
+#[must_use]
+pub fn dominator_tree(
+    catalog: &Catalog,
+    root: &str,
+) -> Option<DominatorTree> {
+    let graph = IndexedGraph::new(catalog);
+    let root_index = *graph.index.get(root)?;
+
+    // Reverse postorder of the documents reachable from the root.
+    let mut postorder = Vec::new();
+    let mut visited = vec![false; graph.ids.len()];
+    let mut stack = vec![(root_index, 0_usize)];
+    visited[root_index] = true;
+    while let Some((node, next)) = stack.pop() {
+        if let Some(&dep) = graph.forward[node].get(next) {
+            stack.push((node, next + 1));
+            if !visited[dep] {
+                visited[dep] = true;
+                stack.push((dep, 0));
+            }
+        } else {
+            postorder.push(node);
+        }
+    }
+    let order = postorder.iter().rev().copied().collect::<Vec<_>>();
+    let mut rank = vec![usize::MAX; graph.ids.len()];
+    for (position, &node) in order.iter().enumerate() {
+        rank[node] = position;
+    }
+
+    let mut predecessors = vec![Vec::new(); graph.ids.len()];
+    for &node in &order {
+        for &dep in &graph.forward[node] {
+            predecessors[dep].push(node);
+        }
+    }
+
+    // Immediate dominators by reverse postorder rank.
+    let mut idom = vec![None; order.len()];
+    idom[0] = Some(0);
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for position in 1..order.len() {
+            let mut candidate = None;
+            for &predecessor in &predecessors[order[position]] {
+                let predecessor = rank[predecessor];
+                if idom[predecessor].is_none() {
+                    continue;
+                }
+                candidate = Some(match candidate {
+                    None => predecessor,
+                    Some(current) => intersect(&idom, current, predecessor),
+                });
+            }
+            if candidate.is_some() && idom[position] != candidate {
+                idom[position] = candidate;
+                changed = true;
+            }
+        }
+    }
+
+    let immediate_dominators = (1..order.len())
+        .filter_map(|position| {
+            let dominator = idom[position]?;
+            Some((
+                graph.ids[order[position]].to_owned(),
+                graph.ids[order[dominator]].to_owned(),
+            ))
+        })
+        .collect();
+
+    Some(DominatorTree {
+        root: root.to_owned(),
+        immediate_dominators,
+    })
+}
+
+/// Walk both ranks up the dominator tree until they meet.
+fn intersect(
+    idom: &[Option<usize>],
+    mut left: usize,
+    mut right: usize,
+) -> usize {
+    while left != right {
+        while left > right {
+            left = idom[left].unwrap_or(0);
+        }
+        while right > left {
+            right = idom[right].unwrap_or(0);
+        }
+    }
+    left
+}
+
+/// Find articulation points and bridges of `catalog`, and dominators from
+/// `options.root` when set.
+#[must_use]
+pub fn build(
+    catalog: &Catalog,
+    options: &CriticalOptions,
+) -> CriticalReport {
+    let graph = IndexedGraph::new(catalog);
+    let paths = catalog
+        .nodes
+        .iter()
+        .map(|node| (node.id.as_str(), node.path.as_str()))
+        .collect::<HashMap<_, _>>();
+    let critical_node = |id: &str, disconnected: usize| CriticalNode {
+        id: id.to_owned(),
+        path: paths.get(id).copied().unwrap_or_default().to_owned(),
+        disconnected,
+    };
+
+    let cuts = graph.cuts();
+    let mut report = CriticalReport {
+        articulation_points: cuts
+            .articulation_points
+            .into_iter()
+            .map(|(node, disconnected)| critical_node(graph.ids[node], disconnected))
+            .collect(),
+        bridges: cuts
+            .bridges
+            .into_iter()
+            .map(|(edge, disconnected)| {
+                let (from, to) = graph.edges[edge];
+                Bridge {
+                    from: graph.ids[from].to_owned(),
+                    to: graph.ids[to].to_owned(),
+                    disconnected,
+                }
+            })
+            .collect(),
+        dominators: options
+            .root
+            .as_deref()
+            .and_then(|root| dominator_tree(catalog, root))
+            .map(|tree| {
+                tree.dominated_counts()
+                    .into_iter()
+                    .filter(|(id, _)| *id != tree.root)
+                    .map(|(id, dominated)| critical_node(&id, dominated))
+                    .collect()
+            })
+            .unwrap_or_default(),
+    };
+
+    let by_impact = |left: &CriticalNode, right: &CriticalNode| {
+        right
+            .disconnected
+            .cmp(&left.disconnected)
+            .then_with(|| left.id.cmp(&right.id))
+    };
+    report.articulation_points.sort_by(by_impact);
+    report.dominators.sort_by(by_impact);
+    report.bridges.sort_by(|left, right| {
+        right
+            .disconnected
+            .cmp(&left.disconnected)
+            .then_with(|| left.from.cmp(&right.from))
+            .then_with(|| left.to.cmp(&right.to))
+    });
+
+    if let Some(limit) = options.limit {
+        report.articulation_points.truncate(limit);
+        report.bridges.truncate(limit);
+        report.dominators.truncate(limit);
+    }
+
+    report
+}
+
+/// Resolved, non-looping dependency edges over node indices.
+struct IndexedGraph<'a> {
+    ids: Vec<&'a str>,
+    index: HashMap<&'a str, usize>,
+    edges: Vec<(usize, usize)>,
+    forward: Vec<Vec<usize>>,
+}
+
+/// Articulation points and bridges with the number of documents each cuts off.
+struct Cuts {
+    articulation_points: Vec<(usize, usize)>,
+    bridges: Vec<(usize, usize)>,
+}
+
+impl<'a> IndexedGraph<'a> {
+    fn new(catalog: &'a Catalog) -> Self {
+        let mut ids = catalog
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        let index = ids
+            .iter()
+            .enumerate()
+            .map(|(position, id)| (*id, position))
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = catalog
+            .dependency_edges()
+            .filter_map(|edge| {
+                Some((
+                    *index.get(edge.from.as_str())?,
+                    *index.get(edge.to.as_str())?,
+                ))
+            })
+            .filter(|(from, to)| from != to)
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut forward = vec![Vec::new(); ids.len()];
+        for &(from, to) in &edges {
+            forward[from].push(to);
+        }
+
+        Self {
+            ids,
+            index,
+            edges,
+            forward,
+        }
+    }
+
+    /// Tarjan's low-link search over the undirected graph. Opposite edges
+    /// between two documents count as parallel links, so neither is a bridge.
+    ///
+    /// Depth-first search keeps its own stack of nodes being visited instead
+    /// of recursing, so dependency chains of any length fit in the thread's
+    /// stack.
+    fn cuts(&self) -> Cuts {
+        let count = self.ids.len();
+        let mut adjacency = vec![Vec::new(); count];
+        for (edge, &(from, to)) in self.edges.iter().enumerate() {
+            adjacency[from].push((to, edge));
+            adjacency[to].push((from, edge));
+        }
+        let mut state = CutState {
+            adjacency,
+            timer: 0,
+            discovered: vec![None; count],
+            low: vec![0; count],
+            size: vec![0; count],
+            separated: vec![Vec::new(); count],
+            tree_edges: Vec::new(),
+        };
+        let mut cuts = Cuts {
+            articulation_points: Vec::new(),
+            bridges: Vec::new(),
+        };
+
+        for root in 0..count {
+            if state.discovered[root].is_some() {
+                continue;
+            }
+            let first = state.timer;
+            visit(root, &mut state);
+            let component_size = state.size[root];
+
+            for node in 0..count {
+                if state.discovered[node].is_none_or(|discovered| discovered < first) {
+                    continue;
+                }
+                let mut pieces = std::mem::take(&mut state.separated[node]);
+                if node != root {
+                    let rest = component_size - 1 - pieces.iter().sum::<usize>();
+                    if rest > 0 {
+                        pieces.push(rest);
+                    }
+                }
+                if pieces.len() >= 2 {
+                    let largest = pieces.iter().copied().max().unwrap_or(0);
+                    let disconnected = pieces.iter().sum::<usize>() - largest;
+                    cuts.articulation_points.push((node, disconnected));
+                }
+            }
+
+            for (edge, child) in state.tree_edges.drain(..) {
+                let parent = self.edges[edge].0 + self.edges[edge].1 - child;
+                if state.discovered[parent].is_some_and(|discovered| state.low[child] > discovered)
+                {
+                    let below = state.size[child];
+                    cuts.bridges.push((edge, below.min(component_size - below)));
+                }
+            }
+        }
+
+        cuts
+    }
+}
+
+/// Depth-first search state of [`IndexedGraph::cuts`].
+struct CutState {
+    adjacency: Vec<Vec<(usize, usize)>>,
+    timer: usize,
+    discovered: Vec<Option<usize>>,
+    low: Vec<usize>,
+    size: Vec<usize>,
+    /// Sizes of the child subtrees each node separates from its parent.
+    separated: Vec<Vec<usize>>,
+    tree_edges: Vec<(usize, usize)>,
+}
+
+impl CutState {
+    fn enter(
+        &mut self,
+        node: usize,
+    ) {
+        self.discovered[node] = Some(self.timer);
+        self.low[node] = self.timer;
+        self.timer += 1;
+        self.size[node] = 1;
+    }
+
+    /// Fold the finished subtree of `child`, reached over `edge`,
+    /// into `node`.
+    fn leave(
+        &mut self,
+        node: usize,
+        child: usize,
+        edge: usize,
+    ) {
+        self.low[node] = self.low[node].min(self.low[child]);
+        self.size[node] += self.size[child];
+        self.tree_edges.push((edge, child));
+        if self.discovered[node].is_some_and(|own| self.low[child] >= own) {
+            self.separated[node].push(self.size[child]);
+        }
+    }
+}
+
+/// Visit the nodes reachable from `root` not visited yet.
+fn visit(
+    root: usize,
+    state: &mut CutState,
+) {
+    // The nodes being visited, innermost last, each with the edge it
+    // was reached over and the position of its next neighbor.
+    let mut visiting = vec![(root, None, 0)];
+    state.enter(root);
+    while let Some(&mut (node, parent_edge, ref mut position)) = visiting.last_mut() {
+        let Some(&(neighbor, edge)) = state.adjacency[node].get(*position) else {
+            visiting.pop();
+            if let (Some(&(parent, _, _)), Some(edge)) = (visiting.last(), parent_edge) {
+                state.leave(parent, node, edge);
+            }
+            continue;
+        };
+        *position += 1;
+        if Some(edge) == parent_edge {
+            continue;
+        }
+        if let Some(discovered) = state.discovered[neighbor] {
+            state.low[node] = state.low[node].min(discovered);
+            continue;
+        }
+        state.enter(neighbor);
+        visiting.push((neighbor, Some(edge), 0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CriticalOptions, build, dominator_tree};
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn catalog(edges: &[(&str, &str)]) -> Catalog {
+        let mut ids = edges
+            .iter()
+            .flat_map(|(from, to)| [*from, *to])
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        Catalog {
+            nodes: ids
+                .into_iter()
+                .map(|id| Node {
+                    id: id.to_owned(),
+                    path: format!("docs/{id}.md"),
+                    title: None,
+                    tags: Vec::new(),
+                    kind: None,
+                    domain: None,
+                    status: None,
+                    source_of_truth: None,
+                    content_hash: None,
+                    last_modified: None,
+                    authors: Vec::new(),
+                    owners: Vec::new(),
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: (*from).to_owned(),
+                    to: (*to).to_owned(),
+                    kind: None,
+                    declared_at: None,
+                })
+                .collect(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn finds_articulation_points_and_bridges() {
+        // a, b, c form a cycle; c hangs d, which hangs e and f.
+        let catalog = catalog(&[
+            ("a", "b"),
+            ("b", "c"),
+            ("c", "a"),
+            ("c", "d"),
+            ("d", "e"),
+            ("d", "f"),
+            ("f", "d"),
+        ]);
+
+        let report = build(&catalog, &CriticalOptions::default());
+
+        let points = report
+            .articulation_points
+            .iter()
+            .map(|node| (node.id.as_str(), node.disconnected))
+            .collect::<Vec<_>>();
+        assert_eq!(points, vec![("c", 2), ("d", 2)]);
+
+        let bridges = report
+            .bridges
+            .iter()
+            .map(|bridge| {
+                (
+                    bridge.from.as_str(),
+                    bridge.to.as_str(),
+                    bridge.disconnected,
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(bridges, vec![("c", "d", 3), ("d", "e", 1)]);
+        assert!(report.dominators.is_empty());
+    }
+
+    #[test]
+    fn finds_cuts_in_chains_deeper_than_the_stack() {
+        const DEPTH: usize = 100_000;
+        let ids = (0..=DEPTH)
+            .map(|node| format!("doc-{node:06}"))
+            .collect::<Vec<_>>();
+        let edges = ids
+            .windows(2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect::<Vec<_>>();
+
+        let report = build(&catalog(&edges), &CriticalOptions::default());
+
+        assert_eq!(report.articulation_points.len(), DEPTH - 1);
+        assert_eq!(report.articulation_points[0].disconnected, DEPTH / 2);
+        assert_eq!(report.bridges.len(), DEPTH);
+        assert_eq!(report.bridges[0].disconnected, DEPTH / 2);
+    }
+
+    #[test]
+    fn computes_dominators_from_a_root() {
+        let catalog = catalog(&[
+            ("index", "api"),
+            ("index", "guide"),
+            ("api", "auth"),
+            ("guide", "auth"),
+            ("auth", "tokens"),
+            ("tokens", "keys"),
+        ]);
+
+        let tree = dominator_tree(&catalog, "index").expect("root is a node");
+        assert_eq!(
+            tree.immediate_dominators.get("auth").map(String::as_str),
+            Some("index")
+        );
+        assert_eq!(
+            tree.immediate_dominators.get("keys").map(String::as_str),
+            Some("tokens")
+        );
+        assert!(dominator_tree(&catalog, "missing").is_none());
+
+        let report = build(
+            &catalog,
+            &CriticalOptions {
+                root: Some("index".to_owned()),
+                limit: Some(1),
+            },
+        );
+        assert_eq!(report.dominators.len(), 1);
+        assert_eq!(report.dominators[0].id, "auth");
+        assert_eq!(report.dominators[0].disconnected, 2);
+    }
+}
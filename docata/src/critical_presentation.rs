@@ -0,0 +1,161 @@
+use crate::critical::{Bridge, CriticalNode, CriticalReport};
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct CriticalNodeJson<'a> {
+    id: &'a str,
+    path: &'a str,
+    disconnected: usize,
+}
+
+impl<'a> From<&'a CriticalNode> for CriticalNodeJson<'a> {
+    fn from(node: &'a CriticalNode) -> Self {
+        Self {
+            id: &node.id,
+            path: &node.path,
+            disconnected: node.disconnected,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BridgeJson<'a> {
+    from: &'a str,
+    to: &'a str,
+    disconnected: usize,
+}
+
+impl<'a> From<&'a Bridge> for BridgeJson<'a> {
+    fn from(bridge: &'a Bridge) -> Self {
+        Self {
+            from: &bridge.from,
+            to: &bridge.to,
+            disconnected: bridge.disconnected,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CriticalReportJson<'a> {
+    command: &'static str,
+    articulation_points: Vec<CriticalNodeJson<'a>>,
+    bridges: Vec<BridgeJson<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    root: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dominators: Option<Vec<CriticalNodeJson<'a>>>,
+}
+
+impl<'a> CriticalReportJson<'a> {
+    fn new(
+        report: &'a CriticalReport,
+        root: Option<&'a str>,
+    ) -> Self {
+        Self {
+            command: "critical",
+            articulation_points: report.articulation_points.iter().map(Into::into).collect(),
+            bridges: report.bridges.iter().map(Into::into).collect(),
+            root,
+            dominators: root.map(|_| report.dominators.iter().map(Into::into).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CriticalPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write single points of failure according to the selected output format.
+///
+/// `root` is the id dominators were computed from, if any.
+///
+/// # Errors
+///
+/// Returns `CriticalPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &CriticalReport,
+    root: Option<&str>,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), CriticalPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, root, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, root, format, out),
+        OutputFormat::Yaml => write_yaml(report, root, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(CriticalPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    report: &CriticalReport,
+    root: Option<&str>,
+    out: &mut W,
+) -> Result<(), CriticalPresentationError> {
+    yaml_serde::to_writer(out, &CriticalReportJson::new(report, root))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &CriticalReport,
+    root: Option<&str>,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), CriticalPresentationError> {
+    json::write(out, &CriticalReportJson::new(report, root), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &CriticalReport,
+    root: Option<&str>,
+    out: &mut W,
+) -> Result<(), CriticalPresentationError> {
+    writeln!(
+        out,
+        "articulation points: {}",
+        report.articulation_points.len()
+    )?;
+    for node in &report.articulation_points {
+        writeln!(
+            out,
+            "  - {} ({}): disconnects {}",
+            node.id, node.path, node.disconnected
+        )?;
+    }
+
+    writeln!(out, "bridges: {}", report.bridges.len())?;
+    for bridge in &report.bridges {
+        writeln!(
+            out,
+            "  - {} -> {}: disconnects {}",
+            bridge.from, bridge.to, bridge.disconnected
+        )?;
+    }
+
+    if let Some(root) = root {
+        writeln!(out, "dominators from {root}: {}", report.dominators.len())?;
+        for node in &report.dominators {
+            writeln!(
+                out,
+                "  - {} ({}): dominates {}",
+                node.id, node.path, node.disconnected
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+use crate::{
+    error::Error,
+    scan::{Entry, scan},
+    validate::build_validation_report,
+};
+use std::{collections::BTreeSet, path::Path};
+
+/// A `deps` edge inside a dependency cycle.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct CycleEdge {
+    pub from: String,
+    pub to: String,
+}
+
+/// Documents depending on each other in a cycle, with the edges closing it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Sorted ids of the documents in the cycle.
+    pub ids: Vec<String>,
+    /// Edges between documents of the cycle, sorted.
+    pub edges: Vec<CycleEdge>,
+}
+
+/// Every dependency cycle among the documents under a root.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CycleReport {
+    pub cycles: Vec<Cycle>,
+}
+
+/// Find the dependency cycles among documents under `root`.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents fails.
+pub fn run(root: &Path) -> Result<CycleReport, Error> {
+    let entries = scan(root)?;
+
+    Ok(report(&entries))
+}
+
+pub(crate) fn report(entries: &[Entry]) -> CycleReport {
+    let cycles = build_validation_report(entries)
+        .dependency_cycles
+        .into_iter()
+        .map(|cycle| {
+            let edges = entries
+                .iter()
+                .filter(|entry| cycle.ids.contains(&entry.id))
+                .flat_map(|entry| {
+                    entry
+                        .deps
+                        .iter()
+                        .filter(|dep| cycle.ids.contains(dep))
+                        .map(|dep| CycleEdge {
+                            from: entry.id.clone(),
+                            to: dep.clone(),
+                        })
+                })
+                .collect::<BTreeSet<_>>();
+
+            Cycle {
+                ids: cycle.ids,
+                edges: edges.into_iter().collect(),
+            }
+        })
+        .collect();
+
+    CycleReport { cycles }
+}
@@ -0,0 +1,159 @@
+use crate::cycles::{Cycle, CycleEdge, CycleReport};
+use crate::dot::quote;
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct CycleReportJson<'a> {
+    command: &'static str,
+    count: usize,
+    cycles: Vec<CycleJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct CycleJson<'a> {
+    ids: &'a [String],
+    edges: Vec<CycleEdgeJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct CycleEdgeJson<'a> {
+    from: &'a str,
+    to: &'a str,
+}
+
+impl<'a> From<&'a CycleReport> for CycleReportJson<'a> {
+    fn from(report: &'a CycleReport) -> Self {
+        Self {
+            command: "cycles",
+            count: report.cycles.len(),
+            cycles: report.cycles.iter().map(CycleJson::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a Cycle> for CycleJson<'a> {
+    fn from(cycle: &'a Cycle) -> Self {
+        Self {
+            ids: &cycle.ids,
+            edges: cycle
+                .edges
+                .iter()
+                .map(|edge| CycleEdgeJson {
+                    from: &edge.from,
+                    to: &edge.to,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CyclesPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write dependency cycles according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `CyclesPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &CycleReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), CyclesPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &CycleReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &CycleReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Dot => write_dot(report, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Junit => {
+            Err(CyclesPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_text<W: Write>(
+    report: &CycleReport,
+    out: &mut W,
+) -> Result<(), CyclesPresentationError> {
+    if report.cycles.is_empty() {
+        writeln!(out, "no dependency cycles")?;
+        return Ok(());
+    }
+
+    for (index, cycle) in report.cycles.iter().enumerate() {
+        writeln!(out, "cycle {}: {}", index + 1, cycle.ids.join(", "))?;
+        for CycleEdge { from, to } in &cycle.edges {
+            writeln!(out, "  {from} -> {to}")?;
+        }
+    }
+    Ok(())
+}
+
+/// One cluster per cycle, holding its documents and the edges closing it.
+fn write_dot<W: Write>(
+    report: &CycleReport,
+    out: &mut W,
+) -> Result<(), CyclesPresentationError> {
+    writeln!(out, "digraph cycles {{")?;
+    for (index, cycle) in report.cycles.iter().enumerate() {
+        writeln!(out, "  subgraph cluster_{index} {{")?;
+        writeln!(out, "    label=\"cycle {}\";", index + 1)?;
+        for id in &cycle.ids {
+            writeln!(out, "    {};", quote(id))?;
+        }
+        for CycleEdge { from, to } in &cycle.edges {
+            writeln!(out, "    {} -> {};", quote(from), quote(to))?;
+        }
+        writeln!(out, "  }}")?;
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::cycles::{Cycle, CycleEdge, CycleReport};
+    use crate::format::OutputFormat;
+
+    #[test]
+    fn writes_cycles_as_dot_clusters() {
+        let edge = |from: &str, to: &str| CycleEdge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+        };
+        let report = CycleReport {
+            cycles: vec![Cycle {
+                ids: vec!["api".to_owned(), "say \"hi\"".to_owned()],
+                edges: vec![edge("api", "say \"hi\""), edge("say \"hi\"", "api")],
+            }],
+        };
+
+        let mut output = Vec::new();
+        write(&report, OutputFormat::Dot, &mut output).expect("write dot");
+
+        assert_eq!(
+            String::from_utf8(output).expect("valid utf-8"),
+            "digraph cycles {\n  subgraph cluster_0 {\n    label=\"cycle 1\";\n    \"api\";\n    \"say \\\"hi\\\"\";\n    \"api\" -> \"say \\\"hi\\\"\";\n    \"say \\\"hi\\\"\" -> \"api\";\n  }\n}\n"
+        );
+    }
+}
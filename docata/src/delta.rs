@@ -0,0 +1,259 @@
+use crate::catalog::{Catalog, Node};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A node present on only one side of a comparison.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeltaNode {
+    pub id: String,
+    pub path: String,
+}
+
+/// An edge present on only one side of a comparison.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DeltaEdge {
+    pub from: String,
+    pub to: String,
+    /// Relation kind of the edge; `None` for `deps` edges.
+    pub kind: Option<String>,
+}
+
+/// One node field that differs between the two sides; `None` means the field
+/// is unset on that side.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    /// Catalog field name, e.g. `title` or `type`.
+    pub field: &'static str,
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// A node present on both sides with at least one changed field.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NodeChange {
+    pub id: String,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two catalogs, the shared shape of the comparisons
+/// reported by `diff` and by `check --catalog`.
+///
+/// Nodes are matched by id; all lists are sorted by id, or by `from` then
+/// `to` for edges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CatalogDelta {
+    pub added_nodes: Vec<DeltaNode>,
+    pub removed_nodes: Vec<DeltaNode>,
+    pub modified_nodes: Vec<NodeChange>,
+    pub added_edges: Vec<DeltaEdge>,
+    pub removed_edges: Vec<DeltaEdge>,
+}
+
+impl CatalogDelta {
+    /// Compare `old` with `new`.
+    #[must_use]
+    pub fn between(
+        old: &Catalog,
+        new: &Catalog,
+    ) -> Self {
+        let old_nodes = nodes_by_id(old);
+        let new_nodes = nodes_by_id(new);
+
+        let mut delta = Self::default();
+        for (id, node) in &new_nodes {
+            match old_nodes.get(id) {
+                None => delta.added_nodes.push(delta_node(node)),
+                Some(old_node) => {
+                    let changes = field_changes(old_node, node);
+                    if !changes.is_empty() {
+                        delta.modified_nodes.push(NodeChange {
+                            id: (*id).to_owned(),
+                            changes,
+                        });
+                    }
+                },
+            }
+        }
+        delta.removed_nodes = old_nodes
+            .iter()
+            .filter(|(id, _)| !new_nodes.contains_key(*id))
+            .map(|(_, node)| delta_node(node))
+            .collect();
+
+        let old_edges = edge_set(old);
+        let new_edges = edge_set(new);
+        delta.added_edges = new_edges.difference(&old_edges).cloned().collect();
+        delta.removed_edges = old_edges.difference(&new_edges).cloned().collect();
+
+        delta
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.change_count() == 0
+    }
+
+    /// Number of added, removed, and modified nodes and edges.
+    #[must_use]
+    pub fn change_count(&self) -> usize {
+        self.added_nodes.len()
+            + self.removed_nodes.len()
+            + self.modified_nodes.len()
+            + self.added_edges.len()
+            + self.removed_edges.len()
+    }
+}
+
+/// Index nodes by id; the first node wins if a catalog repeats an id.
+fn nodes_by_id(catalog: &Catalog) -> BTreeMap<&str, &Node> {
+    let mut nodes = BTreeMap::new();
+    for node in &catalog.nodes {
+        nodes.entry(node.id.as_str()).or_insert(node);
+    }
+    nodes
+}
+
+fn edge_set(catalog: &Catalog) -> BTreeSet<DeltaEdge> {
+    catalog
+        .edges
+        .iter()
+        .map(|edge| DeltaEdge {
+            from: edge.from.clone(),
+            to: edge.to.clone(),
+            kind: edge.kind.clone(),
+        })
+        .collect()
+}
+
+fn delta_node(node: &Node) -> DeltaNode {
+    DeltaNode {
+        id: node.id.clone(),
+        path: node.path.clone(),
+    }
+}
+
+fn field_changes(
+    old: &Node,
+    new: &Node,
+) -> Vec<FieldChange> {
+    let tags = |node: &Node| (!node.tags.is_empty()).then(|| node.tags.join(", "));
+    let authors = |node: &Node| (!node.authors.is_empty()).then(|| node.authors.join(", "));
+    let owners = |node: &Node| (!node.owners.is_empty()).then(|| node.owners.join(", "));
+    let fields = [
+        ("path", Some(old.path.clone()), Some(new.path.clone())),
+        ("title", old.title.clone(), new.title.clone()),
+        ("tags", tags(old), tags(new)),
+        ("type", old.kind.clone(), new.kind.clone()),
+        ("domain", old.domain.clone(), new.domain.clone()),
+        ("status", old.status.clone(), new.status.clone()),
+        (
+            "source_of_truth",
+            old.source_of_truth.clone(),
+            new.source_of_truth.clone(),
+        ),
+        (
+            "content_hash",
+            old.content_hash.clone(),
+            new.content_hash.clone(),
+        ),
+        (
+            "last_modified",
+            old.last_modified.clone(),
+            new.last_modified.clone(),
+        ),
+        ("authors", authors(old), authors(new)),
+        ("owners", owners(old), owners(new)),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange { field, old, new })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CatalogDelta, DeltaEdge, FieldChange};
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn node(
+        id: &str,
+        title: Option<&str>,
+    ) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: title.map(str::to_owned),
+            tags: Vec::new(),
+            kind: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    fn edge(
+        from: &str,
+        to: &str,
+    ) -> Edge {
+        Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        }
+    }
+
+    #[test]
+    fn reports_node_edge_and_field_changes() {
+        let old = Catalog {
+            nodes: vec![
+                node("api", Some("API")),
+                node("guide", None),
+                node("old", None),
+            ],
+            edges: vec![edge("guide", "api"), edge("guide", "old")],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+        let new = Catalog {
+            nodes: vec![
+                node("api", Some("Public API")),
+                node("guide", None),
+                node("new", None),
+            ],
+            edges: vec![edge("guide", "api"), edge("guide", "new")],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let delta = CatalogDelta::between(&old, &new);
+
+        assert_eq!(delta.change_count(), 5);
+        assert_eq!(delta.added_nodes[0].id, "new");
+        assert_eq!(delta.removed_nodes[0].id, "old");
+        assert_eq!(delta.modified_nodes[0].id, "api");
+        assert_eq!(
+            delta.modified_nodes[0].changes,
+            vec![FieldChange {
+                field: "title",
+                old: Some("API".to_owned()),
+                new: Some("Public API".to_owned()),
+            }]
+        );
+        assert_eq!(
+            delta.added_edges,
+            vec![DeltaEdge {
+                from: "guide".to_owned(),
+                to: "new".to_owned(),
+                kind: None,
+            }]
+        );
+        assert!(CatalogDelta::between(&new, &new).is_empty());
+    }
+}
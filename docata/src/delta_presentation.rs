@@ -0,0 +1,228 @@
+use crate::delta::{CatalogDelta, DeltaEdge, DeltaNode, FieldChange, NodeChange};
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct DeltaNodeJson<'a> {
+    id: &'a str,
+    path: &'a str,
+}
+
+impl<'a> From<&'a DeltaNode> for DeltaNodeJson<'a> {
+    fn from(node: &'a DeltaNode) -> Self {
+        Self {
+            id: &node.id,
+            path: &node.path,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DeltaEdgeJson<'a> {
+    from: &'a str,
+    to: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'a str>,
+}
+
+impl<'a> From<&'a DeltaEdge> for DeltaEdgeJson<'a> {
+    fn from(edge: &'a DeltaEdge) -> Self {
+        Self {
+            from: &edge.from,
+            to: &edge.to,
+            kind: edge.kind.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct FieldChangeJson<'a> {
+    field: &'static str,
+    old: Option<&'a str>,
+    new: Option<&'a str>,
+}
+
+impl<'a> From<&'a FieldChange> for FieldChangeJson<'a> {
+    fn from(change: &'a FieldChange) -> Self {
+        Self {
+            field: change.field,
+            old: change.old.as_deref(),
+            new: change.new.as_deref(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct NodeChangeJson<'a> {
+    id: &'a str,
+    changes: Vec<FieldChangeJson<'a>>,
+}
+
+impl<'a> From<&'a NodeChange> for NodeChangeJson<'a> {
+    fn from(node: &'a NodeChange) -> Self {
+        Self {
+            id: &node.id,
+            changes: node.changes.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DeltaSummaryJson {
+    added_nodes: usize,
+    removed_nodes: usize,
+    modified_nodes: usize,
+    added_edges: usize,
+    removed_edges: usize,
+}
+
+/// Changes of a catalog delta, shared by every report shape that embeds one.
+#[derive(Debug, Serialize)]
+pub(crate) struct DeltaChangesJson<'a> {
+    summary: DeltaSummaryJson,
+    added_nodes: Vec<DeltaNodeJson<'a>>,
+    removed_nodes: Vec<DeltaNodeJson<'a>>,
+    modified_nodes: Vec<NodeChangeJson<'a>>,
+    added_edges: Vec<DeltaEdgeJson<'a>>,
+    removed_edges: Vec<DeltaEdgeJson<'a>>,
+}
+
+impl<'a> From<&'a CatalogDelta> for DeltaChangesJson<'a> {
+    fn from(delta: &'a CatalogDelta) -> Self {
+        Self {
+            summary: DeltaSummaryJson {
+                added_nodes: delta.added_nodes.len(),
+                removed_nodes: delta.removed_nodes.len(),
+                modified_nodes: delta.modified_nodes.len(),
+                added_edges: delta.added_edges.len(),
+                removed_edges: delta.removed_edges.len(),
+            },
+            added_nodes: delta.added_nodes.iter().map(Into::into).collect(),
+            removed_nodes: delta.removed_nodes.iter().map(Into::into).collect(),
+            modified_nodes: delta.modified_nodes.iter().map(Into::into).collect(),
+            added_edges: delta.added_edges.iter().map(Into::into).collect(),
+            removed_edges: delta.removed_edges.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct CatalogDeltaJson<'a> {
+    command: &'a str,
+    #[serde(flatten)]
+    changes: DeltaChangesJson<'a>,
+}
+
+impl<'a> CatalogDeltaJson<'a> {
+    fn new(
+        delta: &'a CatalogDelta,
+        command: &'a str,
+    ) -> Self {
+        Self {
+            command,
+            changes: delta.into(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum DeltaPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a catalog delta according to the selected output format.
+///
+/// `command` names the comparison in JSON and YAML output, e.g. `diff`, so
+/// every comparison shares one shape.
+///
+/// # Errors
+///
+/// Returns `DeltaPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    delta: &CatalogDelta,
+    command: &str,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), DeltaPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(delta, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &CatalogDeltaJson::new(delta, command), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &CatalogDeltaJson::new(delta, command))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(DeltaPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+pub(crate) fn write_text<W: Write>(
+    delta: &CatalogDelta,
+    out: &mut W,
+) -> Result<(), DeltaPresentationError> {
+    if delta.is_empty() {
+        writeln!(out, "no changes")?;
+        return Ok(());
+    }
+
+    for node in &delta.added_nodes {
+        writeln!(out, "+ node {} ({})", node.id, node.path)?;
+    }
+    for node in &delta.removed_nodes {
+        writeln!(out, "- node {} ({})", node.id, node.path)?;
+    }
+    for node in &delta.modified_nodes {
+        writeln!(out, "~ node {}", node.id)?;
+        for change in &node.changes {
+            writeln!(
+                out,
+                "    {}: {} -> {}",
+                change.field,
+                field_value(change.old.as_deref()),
+                field_value(change.new.as_deref())
+            )?;
+        }
+    }
+    for edge in &delta.added_edges {
+        writeln!(out, "+ edge {}", edge_text(edge))?;
+    }
+    for edge in &delta.removed_edges {
+        writeln!(out, "- edge {}", edge_text(edge))?;
+    }
+
+    writeln!(
+        out,
+        "{} node(s) added, {} removed, {} modified; {} edge(s) added, {} removed",
+        delta.added_nodes.len(),
+        delta.removed_nodes.len(),
+        delta.modified_nodes.len(),
+        delta.added_edges.len(),
+        delta.removed_edges.len()
+    )?;
+    Ok(())
+}
+
+fn field_value(value: Option<&str>) -> String {
+    value.map_or_else(|| "(unset)".to_owned(), |value| format!("{value:?}"))
+}
+
+fn edge_text(edge: &DeltaEdge) -> String {
+    match &edge.kind {
+        Some(kind) => format!("{} -> {} ({kind})", edge.from, edge.to),
+        None => format!("{} -> {}", edge.from, edge.to),
+    }
+}
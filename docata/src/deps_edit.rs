@@ -0,0 +1,523 @@
+use crate::scan::{ScanError, ScanOptions, scan_with_options};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Whether a dependency is added to or removed from a document's `deps`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DependencyChange {
+    Add,
+    Remove,
+}
+
+/// Outcome of editing a document's `deps`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyEdit {
+    /// File of the edited document.
+    pub path: PathBuf,
+    /// Whether the file changed; adding a listed dependency or removing an
+    /// unlisted one leaves it as is.
+    pub changed: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum DepsEditError {
+    #[error("no document with id '{id}' under '{root}'")]
+    DocumentNotFound { id: String, root: PathBuf },
+    #[error("'{path}' does not start with a frontmatter block")]
+    NoFrontmatter { path: PathBuf },
+    #[error(
+        "'{path}':{line}: cannot edit this form of `deps`; use a `[a, b]` list or one `- id` per line"
+    )]
+    UnsupportedDeps { path: PathBuf, line: usize },
+    #[error("failed to read file '{path}': {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to write file '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{0}")]
+    Scan(#[from] ScanError),
+}
+
+/// Why `check --fix` dropped a dependency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FixReason {
+    /// The id is listed earlier in the same `deps`.
+    Duplicate,
+    /// The document depends on itself.
+    SelfDependency,
+    /// The id is in `[validation] removed_ids`.
+    RemovedId,
+}
+
+impl FixReason {
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Duplicate => "duplicate",
+            Self::SelfDependency => "self-dependency",
+            Self::RemovedId => "removed id",
+        }
+    }
+}
+
+/// A dependency `check --fix` dropped from a document.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DependencyFix {
+    pub id: String,
+    pub path: PathBuf,
+    pub dep: String,
+    pub reason: FixReason,
+}
+
+/// Add `dep` to, or remove it from, the `deps` of the document `id` under
+/// `root`, rewriting only the lines of the `deps` key.
+///
+/// # Errors
+///
+/// Returns `DepsEditError` when no document has the id, its frontmatter or
+/// `deps` cannot be edited, or reading or writing the file fails.
+pub fn edit(
+    root: &Path,
+    id: &str,
+    dep: &str,
+    change: DependencyChange,
+) -> Result<DependencyEdit, DepsEditError> {
+    let path = scan(root)?
+        .into_iter()
+        .filter(|entry| entry.id == id)
+        .map(|entry| entry.path)
+        .min()
+        .ok_or_else(|| DepsEditError::DocumentNotFound {
+            id: id.to_owned(),
+            root: root.to_path_buf(),
+        })?;
+
+    let content = read(&path)?;
+    let edited = match change {
+        DependencyChange::Add => add(&content, dep),
+        DependencyChange::Remove => retain(&content, |item| item != dep),
+    }
+    .map_err(|error| error.at(&path))?;
+    let changed = edited != content;
+    if changed {
+        write(&path, &edited)?;
+    }
+
+    Ok(DependencyEdit { path, changed })
+}
+
+/// Drop duplicate `deps` entries, self-dependencies, and deps on
+/// `removed_ids` from the documents under `root`, and return what was
+/// dropped.
+///
+/// Every file is edited in memory before any is written, so a `deps` list
+/// that cannot be edited leaves all files untouched.
+///
+/// # Errors
+///
+/// Returns `DepsEditError` when scanning fails, the `deps` of a document to
+/// fix cannot be edited, or reading or writing a file fails.
+pub fn fix(
+    root: &Path,
+    removed_ids: &[String],
+) -> Result<Vec<DependencyFix>, DepsEditError> {
+    let reason = |id: &str, dep: &str, seen: &mut HashSet<String>| {
+        if dep == id {
+            Some(FixReason::SelfDependency)
+        } else if removed_ids.iter().any(|removed| removed == dep) {
+            Some(FixReason::RemovedId)
+        } else if !seen.insert(dep.to_owned()) {
+            Some(FixReason::Duplicate)
+        } else {
+            None
+        }
+    };
+
+    let mut fixes = Vec::new();
+    let mut edited = Vec::new();
+    for entry in scan(root)? {
+        let mut seen = HashSet::new();
+        if !entry
+            .deps
+            .iter()
+            .any(|dep| reason(&entry.id, dep, &mut seen).is_some())
+        {
+            continue;
+        }
+
+        let content = read(&entry.path)?;
+        let mut seen = HashSet::new();
+        let rewritten = retain(&content, |dep| {
+            let Some(reason) = reason(&entry.id, dep, &mut seen) else {
+                return true;
+            };
+            fixes.push(DependencyFix {
+                id: entry.id.clone(),
+                path: entry.path.clone(),
+                dep: dep.to_owned(),
+                reason,
+            });
+            false
+        })
+        .map_err(|error| error.at(&entry.path))?;
+        edited.push((entry.path, rewritten));
+    }
+    for (path, content) in edited {
+        write(&path, &content)?;
+    }
+
+    Ok(fixes)
+}
+
+fn scan(root: &Path) -> Result<Vec<crate::scan::Entry>, DepsEditError> {
+    let scanned = scan_with_options(
+        root,
+        ScanOptions {
+            report_skipped: true,
+            lenient: true,
+            content_hash: false,
+            git_metadata: false,
+            code_owners: false,
+        },
+    )?;
+    Ok(scanned.entries)
+}
+
+fn read(path: &Path) -> Result<String, DepsEditError> {
+    fs::read_to_string(path).map_err(|source| DepsEditError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn write(
+    path: &Path,
+    content: &str,
+) -> Result<(), DepsEditError> {
+    fs::write(path, content).map_err(|source| DepsEditError::Write {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum ContentError {
+    NoFrontmatter,
+    /// 1-based line of a `deps` value other than a one-line flow list or a
+    /// block list.
+    Unsupported {
+        line: usize,
+    },
+}
+
+impl ContentError {
+    fn at(
+        self,
+        path: &Path,
+    ) -> DepsEditError {
+        let path = path.to_path_buf();
+        match self {
+            Self::NoFrontmatter => DepsEditError::NoFrontmatter { path },
+            Self::Unsupported { line } => DepsEditError::UnsupportedDeps { path, line },
+        }
+    }
+}
+
+/// The lines of a document, split around its frontmatter `deps`.
+struct Frontmatter<'a> {
+    lines: Vec<&'a str>,
+    /// Index of the closing `---` line.
+    end: usize,
+    newline: &'static str,
+    deps: Option<Deps<'a>>,
+}
+
+enum Deps<'a> {
+    /// `deps: [a, b]` on line `line`.
+    Flow { line: usize, items: Vec<&'a str> },
+    /// `deps:` on line `key`, followed by the `- id` lines `items`.
+    Block { key: usize, items: Vec<usize> },
+}
+
+fn parse(content: &str) -> Result<Frontmatter<'_>, ContentError> {
+    let lines = content.split_inclusive('\n').collect::<Vec<_>>();
+    if lines.first().is_none_or(|line| line.trim_end() != "---") {
+        return Err(ContentError::NoFrontmatter);
+    }
+    let end = lines
+        .iter()
+        .skip(1)
+        .position(|line| line.trim_end() == "---")
+        .map(|index| index + 1)
+        .ok_or(ContentError::NoFrontmatter)?;
+    let newline = if lines[0].ends_with("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+
+    let deps = (1..end)
+        .find(|&index| lines[index].starts_with("deps:"))
+        .map(|key| {
+            let value = lines[key]["deps:".len()..].trim();
+            if value.starts_with('[') {
+                let inner = value
+                    .strip_prefix('[')
+                    .and_then(|value| value.strip_suffix(']'))
+                    .ok_or(ContentError::Unsupported { line: key + 1 })?;
+                let items = inner
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .collect();
+                return Ok(Deps::Flow { line: key, items });
+            }
+            if !value.is_empty() && !value.starts_with('#') {
+                return Err(ContentError::Unsupported { line: key + 1 });
+            }
+            let items = (key + 1..end)
+                .take_while(|&index| lines[index].trim_start().starts_with("- "))
+                .collect();
+            Ok(Deps::Block { key, items })
+        })
+        .transpose()?;
+
+    Ok(Frontmatter {
+        lines,
+        end,
+        newline,
+        deps,
+    })
+}
+
+/// `content` with `dep` appended to its frontmatter `deps`, unless listed.
+fn add(
+    content: &str,
+    dep: &str,
+) -> Result<String, ContentError> {
+    let Frontmatter {
+        mut lines,
+        end,
+        newline,
+        deps,
+    } = parse(content)?;
+    let quoted = quote(dep);
+    let added;
+    match deps {
+        None => {
+            added = format!("deps:{newline}  - {quoted}{newline}");
+            lines.insert(end, &added);
+        },
+        Some(Deps::Flow { line, mut items }) => {
+            if items.iter().any(|item| unquote(item) == dep) {
+                return Ok(content.to_owned());
+            }
+            items.push(&quoted);
+            added = format!("deps: [{}]{newline}", items.join(", "));
+            lines[line] = &added;
+        },
+        Some(Deps::Block { key, items }) => {
+            if items
+                .iter()
+                .any(|&index| unquote(item_value(lines[index])) == dep)
+            {
+                return Ok(content.to_owned());
+            }
+            let indent = items.first().map_or("  ", |&index| {
+                let line = lines[index];
+                &line[..line.len() - line.trim_start().len()]
+            });
+            added = format!("{indent}- {quoted}{newline}");
+            lines.insert(key + 1 + items.len(), &added);
+        },
+    }
+    Ok(lines.concat())
+}
+
+/// `content` keeping only the frontmatter `deps` entries `keep` accepts,
+/// called once per entry in order.
+fn retain(
+    content: &str,
+    mut keep: impl FnMut(&str) -> bool,
+) -> Result<String, ContentError> {
+    let Frontmatter {
+        mut lines,
+        newline,
+        deps,
+        ..
+    } = parse(content)?;
+    let replaced;
+    match deps {
+        None => return Ok(content.to_owned()),
+        Some(Deps::Flow { line, mut items }) => {
+            let count = items.len();
+            items.retain(|item| keep(unquote(item)));
+            if items.len() == count {
+                return Ok(content.to_owned());
+            }
+            replaced = format!("deps: [{}]{newline}", items.join(", "));
+            lines[line] = &replaced;
+        },
+        Some(Deps::Block { items, .. }) => {
+            let dropped = items
+                .into_iter()
+                .filter(|&index| !keep(unquote(item_value(lines[index]))))
+                .collect::<Vec<_>>();
+            for index in dropped.into_iter().rev() {
+                lines.remove(index);
+            }
+        },
+    }
+    Ok(lines.concat())
+}
+
+/// The value of a `- value` block list line, without a trailing comment.
+fn item_value(line: &str) -> &str {
+    let value = line.trim_start().trim_start_matches("- ");
+    value
+        .split_once(" #")
+        .map_or(value, |(value, _)| value)
+        .trim()
+}
+
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .or_else(|| {
+            value
+                .strip_prefix('\'')
+                .and_then(|value| value.strip_suffix('\''))
+        })
+        .unwrap_or(value)
+}
+
+/// `id` as a YAML scalar: plain when it only uses characters that cannot
+/// change its meaning, double-quoted otherwise.
+fn quote(id: &str) -> String {
+    let plain = id.chars().next().is_some_and(char::is_alphanumeric)
+        && id
+            .chars()
+            .all(|character| character.is_alphanumeric() || "-_./".contains(character));
+    if plain {
+        id.to_owned()
+    } else {
+        format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ContentError, DependencyChange, DependencyFix, DepsEditError, FixReason, add, edit, fix,
+        retain,
+    };
+    use crate::testing::TestWorkspace;
+    use std::fs;
+
+    #[test]
+    fn edits_block_and_flow_lists_in_place() {
+        let block = "---\nid: api\ndeps:\n    - db # primary\n    - cache\ntags: [x]\n---\nBody\n";
+        assert_eq!(
+            add(block, "auth"),
+            Ok("---\nid: api\ndeps:\n    - db # primary\n    - cache\n    - auth\ntags: [x]\n---\nBody\n".to_owned())
+        );
+        assert_eq!(
+            retain(block, |dep| dep != "db"),
+            Ok("---\nid: api\ndeps:\n    - cache\ntags: [x]\n---\nBody\n".to_owned())
+        );
+
+        let flow = "---\nid: api\ndeps: [db, \"cache\"]\n---\n";
+        assert_eq!(
+            add(flow, "service:auth"),
+            Ok("---\nid: api\ndeps: [db, \"cache\", \"service:auth\"]\n---\n".to_owned())
+        );
+        assert_eq!(
+            retain(flow, |dep| dep != "cache"),
+            Ok("---\nid: api\ndeps: [db]\n---\n".to_owned())
+        );
+        assert_eq!(add(flow, "db"), Ok(flow.to_owned()));
+    }
+
+    #[test]
+    fn edits_the_document_with_the_id() {
+        let workspace = TestWorkspace::new();
+        let path = workspace.path().join("guides/api.md");
+        fs::create_dir_all(workspace.path().join("guides")).expect("create guides directory");
+        fs::write(&path, "---\nid: api\ntitle: API\n---\n# API\n").expect("write document");
+
+        let added = edit(workspace.path(), "api", "db", DependencyChange::Add).expect("add dep");
+        let again = edit(workspace.path(), "api", "db", DependencyChange::Add).expect("add dep");
+        let missing = edit(workspace.path(), "web", "db", DependencyChange::Add);
+
+        assert!(added.changed);
+        assert_eq!(added.path, path);
+        assert!(!again.changed);
+        assert_eq!(
+            fs::read_to_string(&path).expect("read document"),
+            "---\nid: api\ntitle: API\ndeps:\n  - db\n---\n# API\n"
+        );
+        assert!(matches!(
+            missing,
+            Err(DepsEditError::DocumentNotFound { id, .. }) if id == "web"
+        ));
+    }
+
+    #[test]
+    fn adds_a_deps_key_when_missing() {
+        assert_eq!(
+            add("---\nid: api\n---\n", "db"),
+            Ok("---\nid: api\ndeps:\n  - db\n---\n".to_owned())
+        );
+        assert_eq!(
+            add("---\nid: api\ndeps: [a,\n  b]\n---\n", "db"),
+            Err(ContentError::Unsupported { line: 3 })
+        );
+    }
+
+    #[test]
+    fn drops_duplicate_self_and_removed_deps() {
+        let workspace = TestWorkspace::new();
+        let api = workspace.path().join("api.md");
+        fs::write(
+            &api,
+            "---\nid: api\ndeps:\n  - db\n  - api\n  - legacy\n  - db\n---\n",
+        )
+        .expect("write api");
+        fs::write(
+            workspace.path().join("db.md"),
+            "---\nid: db\ndeps: [cache]\n---\n",
+        )
+        .expect("write db");
+
+        let fixes = fix(workspace.path(), &["legacy".to_owned()]).expect("fix deps");
+
+        let dropped = |dep: &str, reason| DependencyFix {
+            id: "api".to_owned(),
+            path: api.clone(),
+            dep: dep.to_owned(),
+            reason,
+        };
+        assert_eq!(
+            fixes,
+            [
+                dropped("api", FixReason::SelfDependency),
+                dropped("legacy", FixReason::RemovedId),
+                dropped("db", FixReason::Duplicate),
+            ]
+        );
+        assert_eq!(
+            fs::read_to_string(&api).expect("read api"),
+            "---\nid: api\ndeps:\n  - db\n---\n"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use crate::catalog_presentation;
+use crate::format::{CatalogFormat, Compression};
+use crate::store::{CatalogLock, CatalogStore, LockPolicy, StoreError};
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+const MANIFEST: &str = "manifest.json";
+const EDGES: &str = "edges.json";
+const NODES: &str = "nodes";
+
+/// A JSON catalog sharded into a directory: `manifest.json` with the build
+/// options, relations, and external ids, `nodes/<id>.json` per node, and
+/// `edges.json` indexing every edge.
+///
+/// Adding or changing a document only touches its own node file and the
+/// lines of its edges, so teams updating the catalog on separate branches
+/// rarely conflict. Loading joins the shards back into the catalog a
+/// single-file build writes.
+#[derive(Clone, Debug)]
+pub struct DirectoryStore {
+    root: PathBuf,
+}
+
+impl DirectoryStore {
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Whether `path` is a directory holding a sharded catalog.
+    #[must_use]
+    pub fn holds_catalog(path: &Path) -> bool {
+        path.join(MANIFEST).is_file()
+    }
+
+    fn read(path: &Path) -> Result<Vec<u8>, StoreError> {
+        fs::read(path).map_err(|source| StoreError::Read {
+            location: path.to_string_lossy().into_owned(),
+            source,
+        })
+    }
+
+    /// Write `content` to `path` unless it already holds it, so unchanged
+    /// shards keep their modification time.
+    fn write(
+        path: &Path,
+        content: &str,
+    ) -> Result<(), StoreError> {
+        if fs::read(path).is_ok_and(|current| current == content.as_bytes()) {
+            return Ok(());
+        }
+        path.save(content.as_bytes())
+    }
+
+    fn write_error(
+        &self,
+        source: io::Error,
+    ) -> StoreError {
+        StoreError::Write {
+            location: self.location(),
+            source,
+        }
+    }
+}
+
+impl CatalogStore for DirectoryStore {
+    fn location(&self) -> String {
+        self.root.location()
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogFormat::Json
+    }
+
+    fn compression(&self) -> Compression {
+        Compression::None
+    }
+
+    fn load(&self) -> Result<Vec<u8>, StoreError> {
+        let manifest = Self::read(&self.root.join(MANIFEST)).map_err(|error| match error {
+            StoreError::Read { source, .. } if source.kind() == io::ErrorKind::NotFound => {
+                StoreError::NotFound {
+                    location: self.location(),
+                }
+            },
+            error => error,
+        })?;
+        let edges = Self::read(&self.root.join(EDGES))?;
+        let directory = self.root.join(NODES);
+        let mut nodes = Vec::new();
+        for entry in fs::read_dir(&directory).map_err(|source| StoreError::Read {
+            location: directory.to_string_lossy().into_owned(),
+            source,
+        })? {
+            let path = entry
+                .map_err(|source| StoreError::Read {
+                    location: directory.to_string_lossy().into_owned(),
+                    source,
+                })?
+                .path();
+            if path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+            {
+                nodes.push(Self::read(&path)?);
+            }
+        }
+
+        catalog_presentation::join(&manifest, &nodes, &edges).map_err(|error| StoreError::Read {
+            location: self.location(),
+            source: io::Error::new(io::ErrorKind::InvalidData, error),
+        })
+    }
+
+    /// Write changed shards and remove the files of nodes no longer in the
+    /// catalog. Each file is replaced atomically, but a reader can see a mix
+    /// of old and new shards; writers serialize through [`Self::lock`].
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        let shards = catalog_presentation::split(content)
+            .map_err(|error| self.write_error(io::Error::new(io::ErrorKind::InvalidData, error)))?;
+        let directory = self.root.join(NODES);
+        fs::create_dir_all(&directory).map_err(|source| self.write_error(source))?;
+
+        let mut names = HashSet::new();
+        for (id, node) in &shards.nodes {
+            let name = file_name(id);
+            Self::write(&directory.join(&name), node)?;
+            names.insert(name);
+        }
+        for entry in fs::read_dir(&directory).map_err(|source| self.write_error(source))? {
+            let path = entry.map_err(|source| self.write_error(source))?.path();
+            let stale = path
+                .extension()
+                .is_some_and(|extension| extension == "json")
+                && path
+                    .file_name()
+                    .is_some_and(|name| !names.contains(&*name.to_string_lossy()));
+            if stale {
+                fs::remove_file(&path).map_err(|source| self.write_error(source))?;
+            }
+        }
+        Self::write(&self.root.join(EDGES), &shards.edges)?;
+        Self::write(&self.root.join(MANIFEST), &shards.manifest)
+    }
+
+    /// Lock `<directory>.lock`, next to the directory, like a catalog file.
+    fn lock(
+        &self,
+        policy: LockPolicy,
+    ) -> Result<Option<CatalogLock>, StoreError> {
+        self.root.lock(policy)
+    }
+}
+
+/// The node file for `id`: characters outside `[A-Za-z0-9._-]` are
+/// percent-encoded, so namespaced ids like `team/api` stay one flat file.
+fn file_name(id: &str) -> String {
+    let mut name = String::with_capacity(id.len() + 5);
+    for byte in id.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'.' | b'_' | b'-') {
+            name.push(char::from(byte));
+        } else {
+            let _ = write!(name, "%{byte:02X}");
+        }
+    }
+    name.push_str(".json");
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DirectoryStore, file_name};
+    use crate::BuildOptions;
+    use crate::store::CatalogStore;
+    use crate::testing::{TestWorkspace, write_markdown};
+    use std::fs;
+
+    #[test]
+    fn shards_catalogs_and_reads_them_back() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "api.md", "api", &["db"]);
+        write_markdown(&docs, "db.md", "db", &[]);
+        write_markdown(&docs, "team.md", "team/web", &["api"]);
+        let store = DirectoryStore::new(workspace.path().join("catalog"));
+
+        crate::build_merged_catalog_into(&[&docs], &store, BuildOptions::default())
+            .expect("build catalog");
+        let nodes = workspace.path().join("catalog/nodes");
+        assert!(nodes.join("team%2Fweb.json").is_file());
+        let mut single = Vec::new();
+        crate::build::run_roots(&[&docs], &mut single, BuildOptions::default())
+            .expect("build single-file catalog");
+        assert_eq!(store.load().expect("load catalog"), single);
+        crate::check_catalog(&docs, &store, BuildOptions::default()).expect("catalog up to date");
+
+        fs::remove_file(docs.join("team.md")).expect("remove document");
+        crate::build_merged_catalog_into(&[&docs], &store, BuildOptions::default())
+            .expect("rebuild catalog");
+        assert!(!nodes.join("team%2Fweb.json").exists());
+        assert_eq!(file_name("a b"), "a%20b.json");
+    }
+}
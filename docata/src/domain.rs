@@ -1,7 +1,12 @@
-use crate::{catalog::Catalog, graph::Graph};
-use std::collections::HashMap;
+use crate::{
+    QueryOptions,
+    catalog::{Catalog, Node, SourceLocation},
+    graph::Graph,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::Infallible;
 
-pub type RelationResolver = fn(&Graph, &str) -> Vec<String>;
+pub type RelationResolver = for<'a> fn(&'a Graph, &str) -> Vec<&'a str>;
 
 #[derive(Clone, Copy, Debug)]
 pub enum RelationKind {
@@ -25,75 +30,450 @@ impl RelationKind {
             RelationKind::Refs => Graph::refs,
         }
     }
+
+    /// Like `resolver`, following relations transitively.
+    #[must_use]
+    pub const fn transitive_resolver(self) -> RelationResolver {
+        match self {
+            RelationKind::Deps => Graph::transitive_deps,
+            RelationKind::Refs => Graph::transitive_refs,
+        }
+    }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct RelationItem {
     pub id: String,
     pub path: Option<String>,
+    pub title: Option<String>,
     pub resolved: bool,
+    /// Set for resolved items when [`QueryOptions::include_node_metadata`]
+    /// is enabled.
+    pub metadata: Option<RelationItemMetadata>,
+    /// Location of the catalog holding the item; set for resolved items of
+    /// federated queries.
+    pub catalog: Option<String>,
+    /// Whether the unresolved item is one of the catalog's known external
+    /// ids rather than a missing document.
+    pub external: bool,
+    /// Where the edge to the item is declared; set for direct relations when
+    /// [`QueryOptions::include_provenance`] is enabled and the catalog
+    /// records it.
+    pub declared_at: Option<SourceLocation>,
+}
+
+/// Node metadata embedded in a relation item.
+#[derive(Clone, Debug)]
+pub struct RelationItemMetadata {
+    pub tags: Vec<String>,
+    pub kind: Option<String>,
+    pub domain: Option<String>,
+    pub status: Option<String>,
+    pub source_of_truth: Option<String>,
+}
+
+impl From<&Node> for RelationItemMetadata {
+    fn from(node: &Node) -> Self {
+        Self {
+            tags: node.tags.clone(),
+            kind: node.kind.clone(),
+            domain: node.domain.clone(),
+            status: node.status.clone(),
+            source_of_truth: node.source_of_truth.clone(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct RelationMeta {
     pub missing_nodes: Vec<String>,
+    /// Catalog ids close to an unresolved query id, closest first.
+    pub suggestions: Vec<String>,
 }
 
 #[derive(Debug)]
 pub struct RelationResponse {
     pub command: RelationKind,
     pub query_id: String,
+    /// Whether `query_id` is a catalog node.
+    pub query_resolved: bool,
     pub count: usize,
     pub items: Vec<RelationItem>,
     pub meta: RelationMeta,
 }
 
+/// How the results of several query ids are merged.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RelationCombine {
+    /// Items related to any query id.
+    #[default]
+    Union,
+    /// Items related to every query id.
+    Intersection,
+}
+
+impl RelationCombine {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            RelationCombine::Union => "union",
+            RelationCombine::Intersection => "intersection",
+        }
+    }
+}
+
+/// Relations of several query ids: the merged items plus one response per
+/// query id.
+#[derive(Debug)]
+pub struct RelationSetResponse {
+    pub command: RelationKind,
+    pub combine: RelationCombine,
+    /// Merged items, deduplicated and sorted by id.
+    pub items: Vec<RelationItem>,
+    /// One response per distinct query id, in query order.
+    pub groups: Vec<RelationResponse>,
+}
+
+#[derive(Debug)]
+pub struct NodeDetail {
+    pub id: String,
+    pub path: String,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub kind: Option<String>,
+    pub domain: Option<String>,
+    pub status: Option<String>,
+    pub source_of_truth: Option<String>,
+    pub last_modified: Option<String>,
+    pub authors: Vec<String>,
+    pub owners: Vec<String>,
+    pub deps: Vec<RelationItem>,
+    pub refs: Vec<RelationItem>,
+}
+
+#[derive(Debug)]
+pub struct PathResponse {
+    pub from: String,
+    pub to: String,
+    pub paths: Vec<Vec<String>>,
+}
+
+#[derive(Debug)]
+pub struct ListItem {
+    pub id: String,
+    pub path: String,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
+    pub last_modified: Option<String>,
+    pub owners: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct ListResponse {
+    /// Command that produced the listing: `list`, `roots`, or `leaves`.
+    pub command: &'static str,
+    pub count: usize,
+    pub items: Vec<ListItem>,
+}
+
+/// Everything about a relation query besides its items, known once every
+/// item has been produced.
+#[derive(Debug)]
+pub struct RelationSummary {
+    pub command: RelationKind,
+    pub query_id: String,
+    /// Whether `query_id` is a catalog node.
+    pub query_resolved: bool,
+    pub count: usize,
+    pub meta: RelationMeta,
+}
+
 /// Build relation output from an already-created catalog.
+///
+/// When `options.tags` or `options.filters` are not empty, only items whose
+/// node carries every tag and matches the filters are kept; unresolved items
+/// never match.
 #[must_use]
 pub fn build_relation(
     query_id: &str,
     catalog: &Catalog,
     graph: &Graph,
     relation_kind: RelationKind,
+    options: &QueryOptions,
 ) -> RelationResponse {
-    let mut ids = (relation_kind.resolver())(graph, query_id);
+    let mut items = Vec::new();
+    let Ok(summary) = visit_relation(query_id, catalog, graph, relation_kind, options, |item| {
+        items.push(item);
+        Ok::<_, Infallible>(())
+    });
 
-    ids.sort();
-    ids.dedup();
+    RelationResponse {
+        command: summary.command,
+        query_id: summary.query_id,
+        query_resolved: summary.query_resolved,
+        count: summary.count,
+        items,
+        meta: summary.meta,
+    }
+}
 
-    let node_paths = catalog
-        .nodes
-        .iter()
-        .map(|node| (node.id.as_str(), node.path.as_str()))
-        .collect::<HashMap<_, _>>();
+/// Produce the items `build_relation` would collect one at a time, in id
+/// order, handing each to `visit` instead of keeping it.
+///
+/// # Errors
+///
+/// Returns the first error `visit` returns.
+pub fn visit_relation<E>(
+    query_id: &str,
+    catalog: &Catalog,
+    graph: &Graph,
+    relation_kind: RelationKind,
+    options: &QueryOptions,
+    mut visit: impl FnMut(RelationItem) -> Result<(), E>,
+) -> Result<RelationSummary, E> {
+    let QueryOptions { tags, filters, .. } = options;
+    let declarations = if options.include_provenance && !options.transitive {
+        declarations(
+            catalog,
+            query_id,
+            relation_kind,
+            options.edge_kind.as_deref(),
+        )
+    } else {
+        HashMap::new()
+    };
 
     let mut missing_nodes = Vec::new();
-    let mut items = Vec::with_capacity(ids.len());
+    let mut count = 0;
 
-    for id in ids {
-        if let Some(path) = node_paths.get(id.as_str()) {
-            items.push(RelationItem {
-                id,
-                path: Some((*path).to_owned()),
+    for (id, position) in graph.related(query_id, relation_kind, options.transitive) {
+        // The graph may come from another catalog than `catalog`.
+        let node = position
+            .and_then(|position| catalog.nodes.get(position))
+            .filter(|node| node.id == id);
+        if (!tags.is_empty() || !filters.is_empty())
+            && !node.is_some_and(|node| node.has_tags(tags) && node.matches_filters(filters))
+        {
+            continue;
+        }
+
+        count += 1;
+        if let Some(node) = node {
+            visit(RelationItem {
+                path: Some(node.path.clone()),
+                title: node.title.clone(),
                 resolved: true,
-            });
+                metadata: options
+                    .include_node_metadata
+                    .then(|| RelationItemMetadata::from(node)),
+                catalog: None,
+                external: false,
+                declared_at: declarations.get(id).copied().cloned(),
+                id: id.to_owned(),
+            })?;
         } else {
-            missing_nodes.push(id.clone());
-            items.push(RelationItem {
-                id,
+            let external = catalog.external.iter().any(|external| external == id);
+            if !external {
+                missing_nodes.push(id.to_owned());
+            }
+            visit(RelationItem {
                 path: None,
+                title: None,
                 resolved: false,
-            });
+                metadata: None,
+                catalog: None,
+                external,
+                declared_at: declarations.get(id).copied().cloned(),
+                id: id.to_owned(),
+            })?;
         }
     }
 
-    missing_nodes.sort();
+    let query_resolved = graph.contains(query_id);
+    let suggestions = if query_resolved {
+        Vec::new()
+    } else {
+        suggest_ids(query_id, catalog)
+    };
 
-    RelationResponse {
+    Ok(RelationSummary {
         command: relation_kind,
         query_id: query_id.to_owned(),
+        query_resolved,
+        count,
+        meta: RelationMeta {
+            missing_nodes,
+            suggestions,
+        },
+    })
+}
+
+/// Declarations of the edges of kind `edge_kind` linking `query_id` to its
+/// direct relations, by related id.
+fn declarations<'a>(
+    catalog: &'a Catalog,
+    query_id: &str,
+    relation_kind: RelationKind,
+    edge_kind: Option<&str>,
+) -> HashMap<&'a str, &'a SourceLocation> {
+    catalog
+        .edges
+        .iter()
+        .filter(|edge| edge.kind.as_deref() == edge_kind)
+        .filter_map(|edge| {
+            let related = match relation_kind {
+                RelationKind::Deps if edge.from == query_id => &edge.to,
+                RelationKind::Refs if edge.to == query_id => &edge.from,
+                _ => return None,
+            };
+            Some((related.as_str(), edge.declared_at.as_ref()?))
+        })
+        .collect()
+}
+
+/// Build relation output for several query ids and merge the per-id results
+/// with `options.combine`. Repeated query ids are queried once.
+#[must_use]
+pub fn build_relation_set(
+    query_ids: &[&str],
+    catalog: &Catalog,
+    graph: &Graph,
+    relation_kind: RelationKind,
+    options: &QueryOptions,
+) -> RelationSetResponse {
+    let combine = options.combine;
+    let mut distinct = Vec::with_capacity(query_ids.len());
+    for &query_id in query_ids {
+        if !distinct.contains(&query_id) {
+            distinct.push(query_id);
+        }
+    }
+    let groups = distinct
+        .into_iter()
+        .map(|query_id| build_relation(query_id, catalog, graph, relation_kind, options))
+        .collect::<Vec<_>>();
+
+    let mut merged = BTreeMap::<&str, (usize, &RelationItem)>::new();
+    for item in groups.iter().flat_map(|group| &group.items) {
+        merged.entry(&item.id).or_insert((0, item)).0 += 1;
+    }
+    let items = merged
+        .into_values()
+        .filter(|(hits, _)| match combine {
+            RelationCombine::Union => true,
+            RelationCombine::Intersection => *hits == groups.len(),
+        })
+        .map(|(_, item)| item.clone())
+        .collect();
+
+    RelationSetResponse {
+        command: relation_kind,
+        combine,
+        items,
+        groups,
+    }
+}
+
+const MAX_SUGGESTIONS: usize = 5;
+
+/// Suggest catalog ids within a small edit distance of `query_id`.
+fn suggest_ids(
+    query_id: &str,
+    catalog: &Catalog,
+) -> Vec<String> {
+    let max_distance = query_id.chars().count() / 3 + 1;
+
+    let mut candidates = catalog
+        .nodes
+        .iter()
+        .map(|node| (edit_distance(query_id, &node.id), node.id.as_str()))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect::<Vec<_>>();
+    candidates.sort_unstable();
+    candidates.dedup();
+
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, id)| id.to_owned())
+        .collect()
+}
+
+fn edit_distance(
+    left: &str,
+    right: &str,
+) -> usize {
+    let right_chars = right.chars().collect::<Vec<_>>();
+    let mut previous = (0..=right_chars.len()).collect::<Vec<_>>();
+    let mut current = vec![0; right_chars.len() + 1];
+
+    for (left_index, left_char) in left.chars().enumerate() {
+        current[0] = left_index + 1;
+        for (right_index, right_char) in right_chars.iter().enumerate() {
+            let substitution = previous[right_index] + usize::from(left_char != *right_char);
+            current[right_index + 1] = substitution
+                .min(previous[right_index + 1] + 1)
+                .min(current[right_index] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[right_chars.len()]
+}
+
+/// Build the node listing for catalog nodes accepted by `keep`.
+pub(crate) fn build_filtered_list(
+    command: &'static str,
+    catalog: &Catalog,
+    keep: impl Fn(&Node) -> bool,
+) -> ListResponse {
+    let items = catalog
+        .nodes
+        .iter()
+        .filter(|node| keep(node))
+        .map(|node| ListItem {
+            id: node.id.clone(),
+            path: node.path.clone(),
+            title: node.title.clone(),
+            tags: node.tags.clone(),
+            last_modified: node.last_modified.clone(),
+            owners: node.owners.clone(),
+        })
+        .collect::<Vec<_>>();
+
+    ListResponse {
+        command,
         count: items.len(),
         items,
-        meta: RelationMeta { missing_nodes },
     }
 }
+
+/// Build a single node's metadata with its direct deps and refs, or `None`
+/// when `id` is not a catalog node.
+#[must_use]
+pub fn build_node_detail(
+    id: &str,
+    catalog: &Catalog,
+    graph: &Graph,
+) -> Option<NodeDetail> {
+    let node = catalog.nodes.iter().find(|node| node.id == id)?;
+    let options = QueryOptions::default();
+    let deps = build_relation(id, catalog, graph, RelationKind::Deps, &options);
+    let refs = build_relation(id, catalog, graph, RelationKind::Refs, &options);
+
+    Some(NodeDetail {
+        id: node.id.clone(),
+        path: node.path.clone(),
+        title: node.title.clone(),
+        tags: node.tags.clone(),
+        kind: node.kind.clone(),
+        domain: node.domain.clone(),
+        status: node.status.clone(),
+        source_of_truth: node.source_of_truth.clone(),
+        last_modified: node.last_modified.clone(),
+        authors: node.authors.clone(),
+        owners: node.owners.clone(),
+        deps: deps.items,
+        refs: refs.items,
+    })
+}
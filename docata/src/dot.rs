@@ -0,0 +1,4 @@
+/// Quote text as a DOT double-quoted string.
+pub fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
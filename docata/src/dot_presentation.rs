@@ -0,0 +1,121 @@
+use crate::catalog::{Catalog, Node};
+use crate::dot::quote;
+use std::collections::BTreeSet;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum DotPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Write a catalog as a Graphviz `digraph`.
+///
+/// Nodes are labeled with their title when the catalog has one. Edge targets
+/// missing from the catalog are drawn dashed, and edges of relation kinds
+/// besides `deps` are labeled with the kind's display name.
+///
+/// # Errors
+///
+/// Returns `DotPresentationError` if writing fails.
+pub fn write<W: Write>(
+    catalog: &Catalog,
+    out: &mut W,
+) -> Result<(), DotPresentationError> {
+    writeln!(out, "digraph docata {{")?;
+    for node in &catalog.nodes {
+        write_node(out, node)?;
+    }
+
+    let known_ids = catalog
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    let unresolved_ids = catalog
+        .edges
+        .iter()
+        .map(|edge| edge.to.as_str())
+        .filter(|id| !known_ids.contains(id))
+        .collect::<BTreeSet<_>>();
+    for id in unresolved_ids {
+        writeln!(out, "  {} [style=dashed];", quote(id))?;
+    }
+
+    for edge in &catalog.edges {
+        let (from, to) = (quote(&edge.from), quote(&edge.to));
+        match edge.kind.as_deref() {
+            Some(kind) => writeln!(
+                out,
+                "  {from} -> {to} [label={}];",
+                quote(catalog.relation_display(Some(kind)))
+            )?,
+            None => writeln!(out, "  {from} -> {to};")?,
+        }
+    }
+    writeln!(out, "}}")?;
+    Ok(())
+}
+
+fn write_node<W: Write>(
+    out: &mut W,
+    node: &Node,
+) -> Result<(), DotPresentationError> {
+    match node.title.as_deref() {
+        Some(title) => writeln!(out, "  {} [label={}];", quote(&node.id), quote(title))?,
+        None => writeln!(out, "  {};", quote(&node.id))?,
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn writes_titles_unresolved_targets_and_kinds() {
+        let catalog = Catalog {
+            nodes: vec![Node {
+                id: "api".to_owned(),
+                path: "docs/api.md".to_owned(),
+                title: Some("API \"v2\"".to_owned()),
+                tags: Vec::new(),
+                kind: None,
+                domain: None,
+                status: None,
+                source_of_truth: None,
+                content_hash: None,
+                last_modified: None,
+                authors: Vec::new(),
+                owners: Vec::new(),
+            }],
+            edges: vec![
+                Edge {
+                    from: "api".to_owned(),
+                    to: "db".to_owned(),
+                    kind: None,
+                    declared_at: None,
+                },
+                Edge {
+                    from: "api".to_owned(),
+                    to: "spec".to_owned(),
+                    kind: Some("implements".to_owned()),
+                    declared_at: None,
+                },
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let mut output = Vec::new();
+        write(&catalog, &mut output).expect("write dot");
+
+        assert_eq!(
+            String::from_utf8(output).expect("valid utf-8"),
+            "digraph docata {\n  \"api\" [label=\"API \\\"v2\\\"\"];\n  \"db\" [style=dashed];\n  \"spec\" [style=dashed];\n  \"api\" -> \"db\";\n  \"api\" -> \"spec\" [label=\"implements\"];\n}\n"
+        );
+    }
+}
@@ -1,19 +1,361 @@
+use crate::catalog::CatalogError;
+use crate::scan::ScanError;
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
+    #[error("adopt error: {0}")]
+    Adopt(#[from] crate::adopt::AdoptError),
+    #[error("backstage presentation error: {0}")]
+    BackstagePresentation(#[from] crate::backstage_presentation::BackstagePresentationError),
+    #[error("baseline error: {0}")]
+    Baseline(#[from] crate::baseline::BaselineError),
+    #[error("blame presentation error: {0}")]
+    BlamePresentation(#[from] crate::blame_presentation::BlamePresentationError),
+    #[error("build presentation error: {0}")]
+    BuildPresentation(#[from] crate::build_presentation::BuildPresentationError),
     #[error("scan error: {0}")]
     Scan(#[from] crate::scan::ScanError),
+    #[error("changed presentation error: {0}")]
+    ChangedPresentation(#[from] crate::changed_presentation::ChangedPresentationError),
+    #[error("common presentation error: {0}")]
+    CommonPresentation(#[from] crate::common_presentation::CommonPresentationError),
+    #[error("components presentation error: {0}")]
+    ComponentsPresentation(#[from] crate::components_presentation::ComponentsPresentationError),
+    #[error("config error: {0}")]
+    Config(#[from] crate::config::ConfigError),
+    #[error("catalog error: {0}")]
+    Catalog(#[from] crate::catalog::CatalogError),
+    #[error("catalog index error: {0}")]
+    CatalogIndex(#[from] crate::catalog_index::IndexError),
     #[error("catalog presentation error: {0}")]
     CatalogPresentation(#[from] crate::catalog_presentation::CatalogPresentationError),
+    #[error("config presentation error: {0}")]
+    ConfigPresentation(#[from] crate::config_presentation::ConfigPresentationError),
+    #[error("critical presentation error: {0}")]
+    CriticalPresentation(#[from] crate::critical_presentation::CriticalPresentationError),
+    #[error("cycles presentation error: {0}")]
+    CyclesPresentation(#[from] crate::cycles_presentation::CyclesPresentationError),
+    #[error("delta presentation error: {0}")]
+    DeltaPresentation(#[from] crate::delta_presentation::DeltaPresentationError),
+    #[error("deps edit error: {0}")]
+    DepsEdit(#[from] crate::deps_edit::DepsEditError),
+    #[error("dot presentation error: {0}")]
+    DotPresentation(#[from] crate::dot_presentation::DotPresentationError),
+    #[error("freshness presentation error: {0}")]
+    FreshnessPresentation(#[from] crate::freshness_presentation::FreshnessPresentationError),
+    #[error("graphml presentation error: {0}")]
+    GraphMlPresentation(#[from] crate::graphml_presentation::GraphMlPresentationError),
+    #[error("hook presentation error: {0}")]
+    HookPresentation(#[from] crate::hook_presentation::HookPresentationError),
+    #[error("hotspots presentation error: {0}")]
+    HotspotsPresentation(#[from] crate::hotspots_presentation::HotspotsPresentationError),
+    #[error("html presentation error: {0}")]
+    HtmlPresentation(#[from] crate::html_presentation::HtmlPresentationError),
+    #[error("issue export error: {0}")]
+    IssueExport(#[from] crate::issue_export::IssueExportError),
+    #[error("issue presentation error: {0}")]
+    IssuePresentation(#[from] crate::issue_presentation::IssuePresentationError),
+    #[error("list presentation error: {0}")]
+    ListPresentation(#[from] crate::list_presentation::ListPresentationError),
+    #[error("navigation presentation error: {0}")]
+    NavigationPresentation(#[from] crate::navigation_presentation::NavigationPresentationError),
+    #[error("path presentation error: {0}")]
+    PathPresentation(#[from] crate::shortest_path_presentation::PathPresentationError),
     #[error("relation presentation error: {0}")]
     RelationPresentation(#[from] crate::relation_presentation::RelationPresentationError),
+    #[error("recursive check presentation error: {0}")]
+    RecursiveCheckPresentation(
+        #[from] crate::recursive_check_presentation::RecursiveCheckPresentationError,
+    ),
+    #[cfg(feature = "http")]
+    #[error("remote catalog error: {0}")]
+    Remote(#[from] crate::remote::RemoteError),
+    #[error("review queue presentation error: {0}")]
+    ReviewQueuePresentation(#[from] crate::review_queue_presentation::ReviewQueuePresentationError),
+    #[error("route presentation error: {0}")]
+    RoutePresentation(#[from] crate::route_presentation::RoutePresentationError),
+    #[error("new document error: {0}")]
+    Scaffold(#[from] crate::scaffold::ScaffoldError),
+    #[error("show presentation error: {0}")]
+    ShowPresentation(#[from] crate::show_presentation::ShowPresentationError),
+    #[error("simulate presentation error: {0}")]
+    SimulatePresentation(#[from] crate::simulate_presentation::SimulatePresentationError),
+    #[error("catalog store error: {0}")]
+    Store(#[from] crate::store::StoreError),
+    #[error("stale presentation error: {0}")]
+    StalePresentation(#[from] crate::stale_presentation::StalePresentationError),
+    #[error("stats presentation error: {0}")]
+    StatsPresentation(#[from] crate::stats_presentation::StatsPresentationError),
+    #[error("tree presentation error: {0}")]
+    TreePresentation(#[from] crate::tree_presentation::TreePresentationError),
+    #[error("validation presentation error: {0}")]
+    ValidationPresentation(#[from] crate::validation_presentation::ValidationPresentationError),
     #[error("{0}")]
     Validation(#[from] crate::validate::ValidationError),
+    #[error("adoption has {conflicts} unresolved conflict(s)")]
+    UnresolvedConflicts { conflicts: usize },
+    #[error("check failed with {findings} finding(s)")]
+    CheckFailed { findings: usize },
+    #[error(
+        "catalog merge has {conflicts} conflict(s); rebuild the catalog from the merged documents"
+    )]
+    MergeConflicts { conflicts: usize },
     #[error("query id '{query_id}' was not found in catalog (strict mode)")]
     QueryIdNotFound { query_id: String },
-    #[error("catalog check failed: regenerated output differs from '{catalog_path}'")]
-    CatalogDiff { catalog_path: String },
+    #[error("node '{id}' was not found in catalog")]
+    NodeNotFound { id: String },
+    #[error("relation kind '{kind}' is not declared in catalog")]
+    UnknownRelationKind { kind: String },
+    #[error(
+        "catalog '{catalog_path}' was built without node metadata, which tag and metadata filters match; rebuild it with --with-node-metadata"
+    )]
+    NodeMetadataMissing { catalog_path: String },
+    #[error(
+        "catalog '{catalog_path}' was built without {option}, which the filter matches against; rebuild it with {option}"
+    )]
+    BuildOptionMissing {
+        catalog_path: String,
+        option: String,
+    },
+    #[error("no document roots given")]
+    NoRoots,
+    #[error("'{}' is not inside a git work tree", path.display())]
+    NotInGitRepository { path: std::path::PathBuf },
+    #[error("git failed: {message}")]
+    Git { message: String },
+    #[error(
+        "catalog check failed: '{catalog_path}' was built with different options: {}",
+        mismatches.join("; ")
+    )]
+    CatalogOptionsMismatch {
+        catalog_path: String,
+        mismatches: Vec<String>,
+    },
+    #[error(
+        "catalog check failed: regenerated output differs from '{catalog_path}'{}",
+        diff_summary(delta.as_deref())
+    )]
+    CatalogDiff {
+        catalog_path: String,
+        /// Out-of-date nodes and edges; `None` when the stored catalog could
+        /// not be parsed.
+        delta: Option<Box<crate::delta::CatalogDelta>>,
+    },
+}
+
+fn diff_summary(delta: Option<&crate::delta::CatalogDelta>) -> String {
+    match delta {
+        None => " (unreadable catalog)".to_owned(),
+        Some(delta) if delta.is_empty() => " (layout only, no node or edge changes)".to_owned(),
+        Some(delta) => format!(" ({} node or edge change(s))", delta.change_count()),
+    }
+}
+
+impl Error {
+    /// Stable code of the failure, e.g. `E001` for a duplicate id, for tools
+    /// that branch on failures without parsing messages. Codes are never
+    /// reused; presentation errors share `E022`.
+    #[must_use]
+    pub fn code(&self) -> &'static str {
+        self.classify().0
+    }
+
+    /// Stable `snake_case` name of the failure, e.g. `duplicate_id`, matching
+    /// [`Error::code`].
+    #[must_use]
+    pub fn kind(&self) -> &'static str {
+        self.classify().1
+    }
+
+    fn classify(&self) -> (&'static str, &'static str) {
+        match self {
+            Self::Catalog(CatalogError::DuplicateId { .. }) => ("E001", "duplicate_id"),
+            Self::Validation(_) => ("E002", "validation_failed"),
+            Self::CheckFailed { .. } => ("E003", "check_failed"),
+            Self::CatalogDiff { .. } => ("E004", "catalog_out_of_date"),
+            Self::CatalogOptionsMismatch { .. } => ("E005", "catalog_options_mismatch"),
+            Self::NodeNotFound { .. } => ("E006", "node_not_found"),
+            Self::QueryIdNotFound { .. } => ("E007", "query_id_not_found"),
+            Self::UnknownRelationKind { .. } => ("E008", "unknown_relation_kind"),
+            Self::UnresolvedConflicts { .. } => ("E009", "unresolved_conflicts"),
+            Self::NoRoots => ("E010", "no_roots"),
+            Self::Scan(
+                ScanError::ParseYaml { .. }
+                | ScanError::FrontmatterTooLarge { .. }
+                | ScanError::MissingId { .. },
+            ) => ("E011", "invalid_frontmatter"),
+            Self::Scan(_) => ("E012", "scan_failed"),
+            Self::Config(_) => ("E013", "config"),
+            Self::Store(_) => ("E014", "catalog_store"),
+            #[cfg(feature = "http")]
+            Self::Remote(_) => ("E015", "remote_catalog"),
+            Self::Baseline(_) => ("E016", "baseline"),
+            Self::Adopt(_) => ("E017", "adopt"),
+            Self::Scaffold(_) => ("E018", "new_document"),
+            Self::DepsEdit(_) => ("E019", "deps_edit"),
+            Self::IssueExport(_) => ("E020", "issue_export"),
+            Self::Io(_) => ("E021", "io"),
+            Self::MergeConflicts { .. } => ("E023", "merge_conflicts"),
+            Self::NotInGitRepository { .. } => ("E024", "not_in_git_repository"),
+            Self::Git { .. } => ("E025", "git"),
+            Self::CatalogIndex(_) => ("E026", "catalog_index"),
+            Self::NodeMetadataMissing { .. } => ("E027", "node_metadata_missing"),
+            Self::BuildOptionMissing { .. } => ("E028", "build_option_missing"),
+            Self::BackstagePresentation(_)
+            | Self::BlamePresentation(_)
+            | Self::BuildPresentation(_)
+            | Self::ChangedPresentation(_)
+            | Self::CommonPresentation(_)
+            | Self::ComponentsPresentation(_)
+            | Self::CatalogPresentation(_)
+            | Self::ConfigPresentation(_)
+            | Self::CriticalPresentation(_)
+            | Self::CyclesPresentation(_)
+            | Self::DeltaPresentation(_)
+            | Self::DotPresentation(_)
+            | Self::FreshnessPresentation(_)
+            | Self::GraphMlPresentation(_)
+            | Self::HookPresentation(_)
+            | Self::HotspotsPresentation(_)
+            | Self::HtmlPresentation(_)
+            | Self::IssuePresentation(_)
+            | Self::ListPresentation(_)
+            | Self::NavigationPresentation(_)
+            | Self::PathPresentation(_)
+            | Self::RelationPresentation(_)
+            | Self::RecursiveCheckPresentation(_)
+            | Self::ReviewQueuePresentation(_)
+            | Self::RoutePresentation(_)
+            | Self::ShowPresentation(_)
+            | Self::SimulatePresentation(_)
+            | Self::StalePresentation(_)
+            | Self::StatsPresentation(_)
+            | Self::TreePresentation(_)
+            | Self::ValidationPresentation(_) => ("E022", "output"),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorJson<'a> {
+    code: &'static str,
+    kind: &'static str,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paths: Option<&'a [String]>,
+    /// Findings of a failed check or conflicts of an adoption.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    count: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    catalog_path: Option<&'a str>,
+}
+
+/// `code`, `kind`, the English `message`, and, where the failure has them,
+/// the `id`, `paths`, `count`, and `catalog_path` involved.
+impl Serialize for Error {
+    fn serialize<S: Serializer>(
+        &self,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut json = ErrorJson {
+            code: self.code(),
+            kind: self.kind(),
+            message: self.to_string(),
+            id: None,
+            paths: None,
+            count: None,
+            catalog_path: None,
+        };
+        match self {
+            Self::Catalog(CatalogError::DuplicateId { id, paths }) => {
+                json.id = Some(id);
+                json.paths = Some(paths);
+            },
+            Self::NodeNotFound { id } | Self::QueryIdNotFound { query_id: id } => {
+                json.id = Some(id);
+            },
+            Self::UnknownRelationKind { kind } => json.id = Some(kind),
+            Self::CheckFailed { findings: count }
+            | Self::UnresolvedConflicts { conflicts: count }
+            | Self::MergeConflicts { conflicts: count } => json.count = Some(*count),
+            Self::CatalogDiff { catalog_path, .. }
+            | Self::CatalogOptionsMismatch { catalog_path, .. }
+            | Self::NodeMetadataMissing { catalog_path }
+            | Self::BuildOptionMissing { catalog_path, .. } => {
+                json.catalog_path = Some(catalog_path);
+            },
+            _ => {},
+        }
+        json.serialize(serializer)
+    }
+}
+
+/// Render `error` as one line of JSON (see [`Error::code`]), for
+/// `--error-format json`.
+#[must_use]
+pub fn error_json(error: &Error) -> String {
+    serde_json::to_string(error).unwrap_or_else(|_| {
+        format!(
+            "{{\"code\":\"{}\",\"kind\":\"{}\"}}",
+            error.code(),
+            error.kind()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, error_json};
+    use crate::catalog::CatalogError;
+    use crate::delta::{CatalogDelta, DeltaEdge, DeltaNode};
+
+    #[test]
+    fn renders_code_and_details_as_json() {
+        let error = Error::from(CatalogError::DuplicateId {
+            id: "api".to_owned(),
+            paths: vec!["a.md".to_owned(), "b.md".to_owned()],
+        });
+
+        assert_eq!(
+            error_json(&error),
+            r#"{"code":"E001","kind":"duplicate_id","message":"catalog error: duplicate id 'api' appears in: a.md, b.md","id":"api","paths":["a.md","b.md"]}"#
+        );
+        assert_eq!(Error::CheckFailed { findings: 2 }.code(), "E003");
+    }
+
+    #[test]
+    fn summarizes_the_catalog_delta_in_the_diff_message() {
+        let diff = |delta: Option<CatalogDelta>| {
+            Error::CatalogDiff {
+                catalog_path: "catalog.json".to_owned(),
+                delta: delta.map(Box::new),
+            }
+            .to_string()
+        };
+        let stale = CatalogDelta {
+            added_nodes: vec![DeltaNode {
+                id: "api".to_owned(),
+                path: "docs/api.md".to_owned(),
+            }],
+            removed_edges: vec![DeltaEdge {
+                from: "web".to_owned(),
+                to: "db".to_owned(),
+                kind: None,
+            }],
+            ..CatalogDelta::default()
+        };
+
+        assert!(diff(Some(stale)).ends_with("'catalog.json' (2 node or edge change(s))"));
+        assert!(
+            diff(Some(CatalogDelta::default()))
+                .ends_with("'catalog.json' (layout only, no node or edge changes)")
+        );
+        assert!(diff(None).ends_with("'catalog.json' (unreadable catalog)"));
+    }
 }
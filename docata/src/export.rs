@@ -0,0 +1,209 @@
+use crate::{
+    catalog::{Catalog, Edge, Node},
+    error::Error,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+
+/// Graph file formats for exporting a catalog to other tools.
+#[derive(Clone, Copy, Debug)]
+pub enum ExportFormat {
+    /// `GraphML`, readable by yEd and Gephi.
+    GraphMl,
+    /// Graphviz DOT.
+    Dot,
+    /// Backstage `catalog-info.yaml` entities.
+    Backstage,
+    /// A self-contained HTML page with an interactive graph viewer.
+    Html,
+}
+
+/// Run export command and write the catalog graph to the provided writer.
+///
+/// # Errors
+///
+/// Returns `Error` when writing fails.
+pub fn run<W: Write>(
+    catalog: &Catalog,
+    format: ExportFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    match format {
+        ExportFormat::GraphMl => crate::graphml_presentation::write(catalog, out)?,
+        ExportFormat::Dot => crate::dot_presentation::write(catalog, out)?,
+        ExportFormat::Backstage => crate::backstage_presentation::write(catalog, out)?,
+        ExportFormat::Html => crate::html_presentation::write(catalog, out)?,
+    }
+
+    Ok(())
+}
+
+/// How [`summarize`] shrinks a catalog graph before export.
+#[derive(Clone, Copy, Debug)]
+pub struct SummarizeOptions {
+    /// Documents with fewer edges than this are collapsed into one group
+    /// node per domain.
+    pub min_degree: usize,
+    /// Keep at most this many edges, preferring those standing for the most
+    /// original edges.
+    pub max_edges: Option<usize>,
+}
+
+impl Default for SummarizeOptions {
+    fn default() -> Self {
+        Self {
+            min_degree: 2,
+            max_edges: None,
+        }
+    }
+}
+
+/// Collapse low-degree documents of `catalog` into `group:<domain>` nodes
+/// and merge the edges between them, so very large graphs stay viewable.
+///
+/// Documents without a domain are grouped under `group:(none)`. Edges inside
+/// a group are dropped.
+#[must_use]
+pub fn summarize(
+    catalog: Catalog,
+    options: SummarizeOptions,
+) -> Catalog {
+    let mut degrees = HashMap::<&str, usize>::new();
+    for edge in &catalog.edges {
+        *degrees.entry(&edge.from).or_default() += 1;
+        *degrees.entry(&edge.to).or_default() += 1;
+    }
+
+    let mut groups = BTreeMap::<String, (Option<String>, usize)>::new();
+    let mut representative = HashMap::<String, String>::new();
+    let mut nodes = Vec::new();
+    for node in catalog.nodes {
+        if degrees.get(node.id.as_str()).copied().unwrap_or_default() >= options.min_degree {
+            nodes.push(node);
+            continue;
+        }
+        let group_id = format!("group:{}", node.domain.as_deref().unwrap_or("(none)"));
+        groups.entry(group_id.clone()).or_insert((node.domain, 0)).1 += 1;
+        representative.insert(node.id, group_id);
+    }
+    nodes.extend(groups.into_iter().map(|(id, (domain, members))| Node {
+        id,
+        path: String::new(),
+        title: Some(format!("{members} documents")),
+        tags: Vec::new(),
+        kind: Some("group".to_owned()),
+        domain,
+        status: None,
+        source_of_truth: None,
+        content_hash: None,
+        last_modified: None,
+        authors: Vec::new(),
+        owners: Vec::new(),
+    }));
+
+    let mut weights = BTreeMap::<Edge, usize>::new();
+    for edge in catalog.edges {
+        let from = representative.get(&edge.from).cloned().unwrap_or(edge.from);
+        let to = representative.get(&edge.to).cloned().unwrap_or(edge.to);
+        if from != to {
+            let kind = edge.kind;
+            let edge = Edge {
+                from,
+                to,
+                kind,
+                declared_at: None,
+            };
+            *weights.entry(edge).or_default() += 1;
+        }
+    }
+    let mut edges = weights.into_iter().collect::<Vec<_>>();
+    if let Some(max_edges) = options.max_edges {
+        edges.sort_by(|left, right| right.1.cmp(&left.1).then_with(|| left.0.cmp(&right.0)));
+        edges.truncate(max_edges);
+    }
+    let mut edges = edges.into_iter().map(|(edge, _)| edge).collect::<Vec<_>>();
+    edges.sort();
+
+    Catalog {
+        nodes,
+        edges,
+        relations: catalog.relations,
+        external: catalog.external,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SummarizeOptions, summarize};
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn node(
+        id: &str,
+        domain: Option<&str>,
+    ) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: None,
+            domain: domain.map(ToOwned::to_owned),
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    fn edge(
+        from: &str,
+        to: &str,
+    ) -> Edge {
+        Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        }
+    }
+
+    #[test]
+    fn collapses_low_degree_nodes_per_domain() {
+        let catalog = Catalog {
+            nodes: vec![
+                node("hub", None),
+                node("invoice", Some("billing")),
+                node("refund", Some("billing")),
+                node("login", Some("auth")),
+            ],
+            edges: vec![
+                edge("invoice", "hub"),
+                edge("refund", "hub"),
+                edge("login", "hub"),
+                edge("refund", "invoice"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let summary = summarize(
+            catalog,
+            SummarizeOptions {
+                min_degree: 3,
+                max_edges: Some(1),
+            },
+        );
+
+        let ids = summary
+            .nodes
+            .iter()
+            .map(|node| node.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["hub", "group:auth", "group:billing"]);
+        assert_eq!(summary.nodes[2].title.as_deref(), Some("2 documents"));
+        assert_eq!(summary.edges, vec![edge("group:billing", "hub")]);
+    }
+}
@@ -0,0 +1,162 @@
+use crate::{
+    catalog::{Catalog, CatalogError},
+    domain::RelationItem,
+    error::Error,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// Several catalogs merged into one graph, e.g. one per repository of a
+/// polyrepo organization, remembering which catalog holds each node.
+pub(crate) struct Federation {
+    pub(crate) catalog: Catalog,
+    /// Location of the catalog holding each node, by id.
+    sources: HashMap<String, String>,
+}
+
+impl Federation {
+    /// Merge `catalogs`, given as `(location, catalog)` pairs. Edges of one
+    /// catalog resolve against nodes of any other; relation kinds declared by
+    /// several catalogs keep the first definition.
+    ///
+    /// # Errors
+    ///
+    /// Returns `CatalogError::DuplicateId` when several catalogs hold the
+    /// same id.
+    pub(crate) fn merge(catalogs: Vec<(String, Catalog)>) -> Result<Self, Error> {
+        let mut merged = Catalog {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+        let mut sources = HashMap::new();
+        let mut paths = HashMap::<String, String>::new();
+        for (location, catalog) in catalogs {
+            for node in catalog.nodes {
+                let path = format!("{location}: {}", node.path);
+                if let Some(first) = paths.get(&node.id) {
+                    return Err(CatalogError::DuplicateId {
+                        id: node.id,
+                        paths: vec![first.clone(), path],
+                    }
+                    .into());
+                }
+                paths.insert(node.id.clone(), path);
+                sources.insert(node.id.clone(), location.clone());
+                merged.nodes.push(node);
+            }
+            merged.edges.extend(catalog.edges);
+            merged.external.extend(catalog.external);
+            for (kind, definition) in catalog.relations {
+                merged.relations.entry(kind).or_insert(definition);
+            }
+        }
+        merged.nodes.sort_by(|left, right| left.id.cmp(&right.id));
+        merged.external.retain(|id| !sources.contains_key(id));
+        merged.external.sort();
+        merged.external.dedup();
+
+        Ok(Self {
+            catalog: merged,
+            sources,
+        })
+    }
+
+    /// Mark a resolved `item` with the location of the catalog holding it.
+    pub(crate) fn annotate(
+        &self,
+        item: &mut RelationItem,
+    ) {
+        item.catalog = self.sources.get(&item.id).cloned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Federation;
+    use crate::catalog::{Catalog, CatalogError, Edge, Node};
+    use crate::domain::build_relation;
+    use crate::error::Error;
+    use crate::graph::Graph;
+    use crate::relation::RelationKind;
+    use std::collections::BTreeMap;
+
+    fn catalog(
+        ids: &[&str],
+        edges: &[(&str, &str)],
+    ) -> Catalog {
+        Catalog {
+            nodes: ids
+                .iter()
+                .map(|id| Node {
+                    id: (*id).to_owned(),
+                    path: format!("docs/{id}.md"),
+                    title: None,
+                    tags: Vec::new(),
+                    kind: None,
+                    domain: None,
+                    status: None,
+                    source_of_truth: None,
+                    content_hash: None,
+                    last_modified: None,
+                    authors: Vec::new(),
+                    owners: Vec::new(),
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: (*from).to_owned(),
+                    to: (*to).to_owned(),
+                    kind: None,
+                    declared_at: None,
+                })
+                .collect(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn resolves_edges_across_catalogs() {
+        let federation = Federation::merge(vec![
+            (
+                "web/catalog.json".to_owned(),
+                catalog(&["checkout"], &[("checkout", "invoice")]),
+            ),
+            (
+                "billing/catalog.json".to_owned(),
+                catalog(&["invoice"], &[]),
+            ),
+        ])
+        .expect("merge catalogs");
+        let graph = Graph::from_catalog(&federation.catalog);
+
+        let mut response = build_relation(
+            "checkout",
+            &federation.catalog,
+            &graph,
+            RelationKind::Deps,
+            &crate::QueryOptions::default(),
+        );
+        let item = &mut response.items[0];
+        federation.annotate(item);
+
+        assert!(item.resolved);
+        assert_eq!(item.catalog.as_deref(), Some("billing/catalog.json"));
+    }
+
+    #[test]
+    fn rejects_ids_held_by_several_catalogs() {
+        let result = Federation::merge(vec![
+            ("web/catalog.json".to_owned(), catalog(&["api"], &[])),
+            ("billing/catalog.json".to_owned(), catalog(&["api"], &[])),
+        ]);
+
+        assert!(matches!(
+            result,
+            Err(Error::Catalog(CatalogError::DuplicateId { paths, .. }))
+                if paths == ["web/catalog.json: docs/api.md", "billing/catalog.json: docs/api.md"]
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+//! C ABI for embedding docata in non-Rust build systems.
+//!
+//! Every function returns a JSON string the caller frees with
+//! [`docata_string_free`], or null with `*error` set to the error as JSON
+//! (see [`crate::error_json`]), which is freed the same way. `error` may be
+//! null when the caller does not need the message.
+#![allow(unsafe_code)]
+
+use crate::{
+    BuildOptions, Error, OutputFormat, QueryOptions, RelationKind, build_catalog_into, error_json,
+    open_catalog_store, query_catalog_relation_with_options, write_build_report,
+};
+use std::{
+    ffi::{CStr, CString, c_char},
+    io,
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+    ptr,
+};
+
+/// Build the catalog of the documents under `root` into `catalog`, a path or
+/// store location, with the default options. Returns the build report.
+///
+/// # Safety
+///
+/// `root` and `catalog` must be null or point to NUL-terminated strings, and
+/// `error` must be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn docata_build_catalog(
+    root: *const c_char,
+    catalog: *const c_char,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: the caller upholds the contract documented above.
+    unsafe {
+        call(error, || {
+            let root = argument(root, "root")?;
+            let store = open_catalog_store(argument(catalog, "catalog")?)?;
+            let report = build_catalog_into(Path::new(root), &*store, BuildOptions::default())?;
+            let mut out = Vec::new();
+            write_build_report(&report, OutputFormat::Json, &mut out)?;
+            Ok(out)
+        })
+    }
+}
+
+/// Query the dependencies of `id` in `catalog`, transitively when
+/// `transitive` is true. Returns the `query` command's JSON output.
+///
+/// # Safety
+///
+/// As for [`docata_build_catalog`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn docata_query_deps(
+    catalog: *const c_char,
+    id: *const c_char,
+    transitive: bool,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: the caller upholds the contract documented above.
+    unsafe { query(catalog, id, RelationKind::Deps, transitive, error) }
+}
+
+/// Query the documents referring to `id` in `catalog`, like
+/// [`docata_query_deps`].
+///
+/// # Safety
+///
+/// As for [`docata_build_catalog`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn docata_query_refs(
+    catalog: *const c_char,
+    id: *const c_char,
+    transitive: bool,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: the caller upholds the contract documented above.
+    unsafe { query(catalog, id, RelationKind::Refs, transitive, error) }
+}
+
+/// Free a string returned by a `docata_*` function. Null is ignored.
+///
+/// # Safety
+///
+/// `string` must be null or a string returned by this library, not freed
+/// before.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn docata_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the string came from `CString::into_raw` in `into_c_string`.
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+unsafe fn query(
+    catalog: *const c_char,
+    id: *const c_char,
+    relation_kind: RelationKind,
+    transitive: bool,
+    error: *mut *mut c_char,
+) -> *mut c_char {
+    // SAFETY: forwarded from the public functions' contract.
+    unsafe {
+        call(error, || {
+            let store = open_catalog_store(argument(catalog, "catalog")?)?;
+            let mut out = Vec::new();
+            query_catalog_relation_with_options(
+                argument(id, "id")?,
+                &*store,
+                relation_kind,
+                OutputFormat::Json,
+                &QueryOptions::new().transitive(transitive),
+                &mut out,
+            )?;
+            Ok(out)
+        })
+    }
+}
+
+/// Run `body`, returning its output as a C string, or null with its error,
+/// or a panic, stored in `error`. Unwinding into C would abort the caller.
+unsafe fn call(
+    error: *mut *mut c_char,
+    body: impl FnOnce() -> Result<Vec<u8>, Error>,
+) -> *mut c_char {
+    let result = panic::catch_unwind(AssertUnwindSafe(body))
+        .unwrap_or_else(|_| Err(Error::Io(io::Error::other("docata panicked"))));
+    match result {
+        Ok(out) => into_c_string(out),
+        Err(failure) => {
+            if !error.is_null() {
+                // SAFETY: the caller passes an `error` valid for writes.
+                unsafe { *error = into_c_string(error_json(&failure).into_bytes()) };
+            }
+            ptr::null_mut()
+        },
+    }
+}
+
+/// The UTF-8 string at `pointer`, the argument called `name`.
+unsafe fn argument<'a>(
+    pointer: *const c_char,
+    name: &str,
+) -> Result<&'a str, Error> {
+    let invalid = |reason: &str| {
+        Error::Io(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{name} {reason}"),
+        ))
+    };
+    if pointer.is_null() {
+        return Err(invalid("is null"));
+    }
+    // SAFETY: the caller passes a NUL-terminated string.
+    unsafe { CStr::from_ptr(pointer) }
+        .to_str()
+        .map_err(|_| invalid("is not UTF-8"))
+}
+
+/// `bytes` as a C string owned by the caller. Output never holds NUL, since
+/// JSON escapes it; a stray one truncates the string.
+fn into_c_string(mut bytes: Vec<u8>) -> *mut c_char {
+    if let Some(nul) = bytes.iter().position(|&byte| byte == 0) {
+        bytes.truncate(nul);
+    }
+    CString::new(bytes).unwrap_or_default().into_raw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{docata_build_catalog, docata_query_deps, docata_query_refs, docata_string_free};
+    use crate::testing::{TestWorkspace, write_markdown};
+    use std::ffi::{CStr, CString, c_char};
+    use std::ptr;
+
+    /// Take ownership of a string returned through the C ABI.
+    fn take(string: *mut c_char) -> String {
+        assert!(!string.is_null());
+        // SAFETY: `string` was returned by a `docata_*` function.
+        let owned = unsafe { CStr::from_ptr(string) }
+            .to_string_lossy()
+            .into_owned();
+        // SAFETY: as above, and freed only here.
+        unsafe { docata_string_free(string) };
+        owned
+    }
+
+    #[test]
+    fn builds_and_queries_catalogs_through_the_c_abi() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "api.md", "api", &["db"]);
+        write_markdown(workspace.path(), "db.md", "db", &[]);
+        let root = CString::new(workspace.path().to_string_lossy().as_bytes()).expect("root");
+        let catalog = CString::new(
+            workspace
+                .path()
+                .join("catalog.json")
+                .to_string_lossy()
+                .as_bytes(),
+        )
+        .expect("catalog");
+        let mut error = ptr::null_mut();
+
+        // SAFETY: every pointer is a valid C string or a writable slot.
+        unsafe {
+            let report = take(docata_build_catalog(
+                root.as_ptr(),
+                catalog.as_ptr(),
+                &raw mut error,
+            ));
+            assert!(report.starts_with('{'));
+
+            let api = CString::new("api").expect("id");
+            let deps = take(docata_query_deps(
+                catalog.as_ptr(),
+                api.as_ptr(),
+                false,
+                &raw mut error,
+            ));
+            let deps: serde_json::Value = serde_json::from_str(&deps).expect("parse deps");
+            assert_eq!(deps["items"][0]["id"], "db");
+
+            let db = CString::new("db").expect("id");
+            let refs = take(docata_query_refs(
+                catalog.as_ptr(),
+                db.as_ptr(),
+                true,
+                ptr::null_mut(),
+            ));
+            assert!(refs.contains("\"api\""));
+
+            assert!(docata_query_deps(ptr::null(), api.as_ptr(), false, &raw mut error).is_null());
+            let error: serde_json::Value = serde_json::from_str(&take(error)).expect("parse error");
+            assert_eq!(error["kind"], "io");
+        }
+    }
+}
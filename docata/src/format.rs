@@ -1,5 +1,126 @@
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
 #[derive(Clone, Copy, Debug)]
 pub enum OutputFormat {
     Text,
     Json,
+    /// JSON on a single line.
+    CompactJson,
+    Yaml,
+    Sarif,
+    /// One compact JSON object per line, for streaming into line-based tools.
+    Ndjson,
+    /// Graphviz DOT, for commands whose output is a graph.
+    Dot,
+    /// `JUnit` XML, for CI test report views.
+    Junit,
+}
+
+impl OutputFormat {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::CompactJson => "compact-json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Sarif => "sarif",
+            OutputFormat::Ndjson => "ndjson",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Junit => "junit",
+        }
+    }
+
+    /// The single-line variant of this format: `CompactJson` for `Json`, the
+    /// format itself otherwise.
+    #[must_use]
+    pub const fn compact(self) -> Self {
+        match self {
+            OutputFormat::Json => OutputFormat::CompactJson,
+            format => format,
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Serialization format of a catalog file.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CatalogFormat {
+    #[default]
+    Json,
+    Yaml,
+    /// Binary `MessagePack`, much faster to load than JSON for large
+    /// catalogs. Needs the `msgpack` feature.
+    MessagePack,
+}
+
+impl CatalogFormat {
+    /// Detect the catalog format from a file extension, defaulting to JSON.
+    /// A compression extension is skipped, so `catalog.yaml.gz` is YAML.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        let path = match Compression::from_path(path) {
+            Compression::None => path,
+            _ => path.file_stem().map_or(path, Path::new),
+        };
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("yaml" | "yml") => CatalogFormat::Yaml,
+            Some("msgpack" | "mpk") => CatalogFormat::MessagePack,
+            _ => CatalogFormat::Json,
+        }
+    }
+
+    /// The format of serialized catalog `content` when its first byte tells:
+    /// a `MessagePack` catalog starts with a map marker, which is never the
+    /// first byte of UTF-8 JSON or YAML text.
+    #[must_use]
+    pub fn sniff(content: &[u8]) -> Option<Self> {
+        matches!(content.first(), Some(0x80..=0x8f | 0xde | 0xdf))
+            .then_some(CatalogFormat::MessagePack)
+    }
+}
+
+/// Compression of a catalog file, applied over its [`CatalogFormat`].
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Compression {
+    #[default]
+    None,
+    /// Gzip, e.g. `catalog.json.gz`. Needs the `gzip` feature.
+    Gzip,
+    /// Zstandard, e.g. `catalog.json.zst`. Needs the `zstd` feature.
+    Zstd,
+}
+
+impl Compression {
+    /// Detect the compression from a file extension: `.gz` or `.zst`.
+    #[must_use]
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// The compression of `content` when it starts with the gzip or
+    /// Zstandard magic bytes.
+    #[must_use]
+    pub fn sniff(content: &[u8]) -> Option<Self> {
+        if content.starts_with(&[0x1f, 0x8b]) {
+            Some(Compression::Gzip)
+        } else if content.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
 }
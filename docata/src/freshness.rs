@@ -0,0 +1,140 @@
+use crate::{
+    error::Error,
+    git::{file_modified_date, is_iso_date, last_commit_dates},
+    scan::{Entry, scan},
+};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+/// A dependency modified after the depending document was last reviewed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ChangedDependency {
+    pub id: String,
+    pub path: PathBuf,
+    /// Date (`YYYY-MM-DD`) the dependency was last modified.
+    pub modified: String,
+}
+
+/// A reviewed document with at least one dependency that changed since.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StaleDocument {
+    pub id: String,
+    pub path: PathBuf,
+    pub last_reviewed: String,
+    pub changed_deps: Vec<ChangedDependency>,
+}
+
+/// A document whose `last_reviewed` value is not a `YYYY-MM-DD` date.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvalidReviewDate {
+    pub id: String,
+    pub path: PathBuf,
+    pub value: String,
+}
+
+/// Documents whose upstream moved since their last review.
+///
+/// Modification dates come from the latest git commit touching each file,
+/// falling back to the file's modification time outside git.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FreshnessReport {
+    pub stale: Vec<StaleDocument>,
+    /// Ids of documents without a `last_reviewed` date.
+    pub unreviewed: Vec<String>,
+    pub invalid_review_dates: Vec<InvalidReviewDate>,
+}
+
+impl FreshnessReport {
+    #[must_use]
+    pub fn is_fresh(&self) -> bool {
+        self.stale.is_empty() && self.invalid_review_dates.is_empty()
+    }
+}
+
+/// Compare each document's `last_reviewed` date with the modification dates
+/// of its dependencies under `root`.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents fails.
+pub fn run(root: &Path) -> Result<FreshnessReport, Error> {
+    let entries = scan(root)?;
+    Ok(report(root, &entries))
+}
+
+/// Build a freshness report for `entries` scanned from `root`.
+#[must_use]
+pub fn report(
+    root: &Path,
+    entries: &[Entry],
+) -> FreshnessReport {
+    let mut entries = entries.iter().collect::<Vec<_>>();
+    entries.sort_by(|left, right| left.id.cmp(&right.id).then(left.path.cmp(&right.path)));
+
+    let modified_dates = modified_dates(root, entries.iter().copied());
+
+    let mut report = FreshnessReport::default();
+    for entry in entries {
+        let Some(last_reviewed) = &entry.last_reviewed else {
+            report.unreviewed.push(entry.id.clone());
+            continue;
+        };
+        if !is_iso_date(last_reviewed) {
+            report.invalid_review_dates.push(InvalidReviewDate {
+                id: entry.id.clone(),
+                path: entry.path.clone(),
+                value: last_reviewed.clone(),
+            });
+            continue;
+        }
+
+        let mut changed_deps = entry
+            .deps
+            .iter()
+            .filter_map(|dep| {
+                let (path, modified) = modified_dates.get(dep.as_str())?;
+                (modified.as_str() > last_reviewed.as_str()).then(|| ChangedDependency {
+                    id: dep.clone(),
+                    path: path.to_path_buf(),
+                    modified: modified.clone(),
+                })
+            })
+            .collect::<Vec<_>>();
+        if changed_deps.is_empty() {
+            continue;
+        }
+
+        changed_deps.sort_by(|left, right| left.id.cmp(&right.id));
+        changed_deps.dedup_by(|left, right| left.id == right.id);
+        report.stale.push(StaleDocument {
+            id: entry.id.clone(),
+            path: entry.path.clone(),
+            last_reviewed: last_reviewed.clone(),
+            changed_deps,
+        });
+    }
+
+    report
+}
+
+/// Map each document id to its path and last modification date
+/// (`YYYY-MM-DD`); later entries win for duplicate ids.
+pub(crate) fn modified_dates<'a>(
+    root: &Path,
+    entries: impl IntoIterator<Item = &'a Entry>,
+) -> HashMap<&'a str, (&'a Path, String)> {
+    let commit_dates = last_commit_dates(root);
+    entries
+        .into_iter()
+        .filter_map(|entry| {
+            let relative = entry.path.strip_prefix(root).unwrap_or(&entry.path);
+            let date = commit_dates
+                .get(relative)
+                .cloned()
+                .or_else(|| file_modified_date(&entry.path))?;
+            Some((entry.id.as_str(), (entry.path.as_path(), date)))
+        })
+        .collect()
+}
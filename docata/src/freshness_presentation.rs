@@ -0,0 +1,194 @@
+use crate::format::OutputFormat;
+use crate::freshness::{ChangedDependency, FreshnessReport, InvalidReviewDate, StaleDocument};
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct FreshnessReportJson<'a> {
+    command: &'static str,
+    status: &'static str,
+    summary: FreshnessSummaryJson,
+    stale: Vec<StaleDocumentJson<'a>>,
+    unreviewed: &'a [String],
+    invalid_review_dates: Vec<InvalidReviewDateJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct FreshnessSummaryJson {
+    stale: usize,
+    unreviewed: usize,
+    invalid_review_dates: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct StaleDocumentJson<'a> {
+    id: &'a str,
+    path: String,
+    last_reviewed: &'a str,
+    changed_deps: Vec<ChangedDependencyJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ChangedDependencyJson<'a> {
+    id: &'a str,
+    path: String,
+    modified: &'a str,
+}
+
+#[derive(Debug, Serialize)]
+struct InvalidReviewDateJson<'a> {
+    id: &'a str,
+    path: String,
+    value: &'a str,
+}
+
+impl<'a> From<&'a FreshnessReport> for FreshnessReportJson<'a> {
+    fn from(report: &'a FreshnessReport) -> Self {
+        Self {
+            command: "freshness",
+            status: if report.is_fresh() { "fresh" } else { "stale" },
+            summary: FreshnessSummaryJson {
+                stale: report.stale.len(),
+                unreviewed: report.unreviewed.len(),
+                invalid_review_dates: report.invalid_review_dates.len(),
+            },
+            stale: report.stale.iter().map(StaleDocumentJson::from).collect(),
+            unreviewed: &report.unreviewed,
+            invalid_review_dates: report
+                .invalid_review_dates
+                .iter()
+                .map(InvalidReviewDateJson::from)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a StaleDocument> for StaleDocumentJson<'a> {
+    fn from(document: &'a StaleDocument) -> Self {
+        Self {
+            id: &document.id,
+            path: document.path.to_string_lossy().to_string(),
+            last_reviewed: &document.last_reviewed,
+            changed_deps: document
+                .changed_deps
+                .iter()
+                .map(ChangedDependencyJson::from)
+                .collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a ChangedDependency> for ChangedDependencyJson<'a> {
+    fn from(dep: &'a ChangedDependency) -> Self {
+        Self {
+            id: &dep.id,
+            path: dep.path.to_string_lossy().to_string(),
+            modified: &dep.modified,
+        }
+    }
+}
+
+impl<'a> From<&'a InvalidReviewDate> for InvalidReviewDateJson<'a> {
+    fn from(invalid: &'a InvalidReviewDate) -> Self {
+        Self {
+            id: &invalid.id,
+            path: invalid.path.to_string_lossy().to_string(),
+            value: &invalid.value,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum FreshnessPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a freshness report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `FreshnessPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &FreshnessReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), FreshnessPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(FreshnessPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    report: &FreshnessReport,
+    out: &mut W,
+) -> Result<(), FreshnessPresentationError> {
+    yaml_serde::to_writer(out, &FreshnessReportJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &FreshnessReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), FreshnessPresentationError> {
+    json::write(out, &FreshnessReportJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &FreshnessReport,
+    out: &mut W,
+) -> Result<(), FreshnessPresentationError> {
+    for document in &report.stale {
+        writeln!(
+            out,
+            "{} ({}) reviewed {}",
+            document.id,
+            document.path.display(),
+            document.last_reviewed
+        )?;
+        for dep in &document.changed_deps {
+            writeln!(
+                out,
+                "  - {} modified {} ({})",
+                dep.id,
+                dep.modified,
+                dep.path.display()
+            )?;
+        }
+    }
+
+    for invalid in &report.invalid_review_dates {
+        writeln!(
+            out,
+            "invalid last_reviewed `{}` in {} ({})",
+            invalid.value,
+            invalid.id,
+            invalid.path.display()
+        )?;
+    }
+
+    writeln!(
+        out,
+        "{} stale, {} unreviewed, {} invalid review date(s)",
+        report.stale.len(),
+        report.unreviewed.len(),
+        report.invalid_review_dates.len()
+    )?;
+
+    Ok(())
+}
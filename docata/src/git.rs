@@ -0,0 +1,357 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::Command,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Return the committer date (`YYYY-MM-DD`) of the latest commit touching each
+/// file under `root`, keyed by path relative to `root`.
+///
+/// Returns an empty map when `root` is not inside a git work tree or `git` is
+/// not installed, so callers can fall back to [`file_modified_date`].
+#[must_use]
+pub fn last_commit_dates(root: &Path) -> HashMap<PathBuf, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotepath=off",
+            "log",
+            "--format=%x00%cs",
+            "--name-only",
+            "--relative",
+            "--",
+            ".",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+    if !output.status.success() {
+        return HashMap::new();
+    }
+
+    let mut dates = HashMap::new();
+    let mut current_date = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(date) = line.strip_prefix('\0') {
+            current_date = Some(date.to_owned());
+        } else if !line.is_empty()
+            && let Some(date) = &current_date
+        {
+            // `git log` lists newest commits first, so keep the first date.
+            dates
+                .entry(PathBuf::from(line))
+                .or_insert_with(|| date.clone());
+        }
+    }
+
+    dates
+}
+
+/// Authors [`file_histories`] keeps per file.
+const TOP_AUTHORS: usize = 3;
+
+/// A file's git history, summarized.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FileHistory {
+    /// Committer date (`YYYY-MM-DD`) of the latest commit touching the file.
+    pub last_modified: String,
+    /// Authors with the most commits touching the file, most first; ties
+    /// are sorted by name.
+    pub authors: Vec<String>,
+}
+
+/// Return the [`FileHistory`] of each file under `root`, keyed by path
+/// relative to `root`; empty outside a git work tree or without `git`.
+#[must_use]
+pub fn file_histories(root: &Path) -> HashMap<PathBuf, FileHistory> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotepath=off",
+            "log",
+            "--format=%x00%cs%x00%aN",
+            "--name-only",
+            "--relative",
+            "--",
+            ".",
+        ])
+        .output();
+    let Ok(output) = output else {
+        return HashMap::new();
+    };
+    if !output.status.success() {
+        return HashMap::new();
+    }
+
+    let mut commits = HashMap::<PathBuf, (String, HashMap<String, usize>)>::new();
+    let mut current = None;
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if let Some(header) = line.strip_prefix('\0') {
+            current = header.split_once('\0');
+        } else if !line.is_empty()
+            && let Some((date, author)) = current
+        {
+            // `git log` lists newest commits first, so keep the first date.
+            let (_, authors) = commits
+                .entry(PathBuf::from(line))
+                .or_insert_with(|| (date.to_owned(), HashMap::new()));
+            *authors.entry(author.to_owned()).or_default() += 1;
+        }
+    }
+
+    commits
+        .into_iter()
+        .map(|(path, (last_modified, authors))| {
+            let mut authors = authors.into_iter().collect::<Vec<_>>();
+            authors.sort_by(|left, right| right.1.cmp(&left.1).then(left.0.cmp(&right.0)));
+            let authors = authors
+                .into_iter()
+                .take(TOP_AUTHORS)
+                .map(|(author, _)| author)
+                .collect();
+            (
+                path,
+                FileHistory {
+                    last_modified,
+                    authors,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Return the files under `dir` that differ between commit `since` and the
+/// work tree, relative to `dir` and sorted; a rename counts as a change of
+/// both paths.
+///
+/// # Errors
+///
+/// Returns git's message when `git` cannot run, `dir` is not inside a git
+/// work tree, or `since` names no commit.
+pub(crate) fn changed_files(
+    dir: &Path,
+    since: &str,
+) -> Result<Vec<PathBuf>, String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "-c",
+            "core.quotepath=off",
+            "diff",
+            "--name-only",
+            "--no-renames",
+            "--relative",
+            "-z",
+            "--end-of-options",
+            since,
+            "--",
+        ])
+        .output()
+        .map_err(|error| format!("failed to run git: {error}"))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_owned());
+    }
+
+    let mut paths = String::from_utf8_lossy(&output.stdout)
+        .split('\0')
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Markdown files under `root` with changes staged for the next commit.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct StagedFiles {
+    /// Added or modified files, relative to `root`, sorted.
+    pub changed: Vec<PathBuf>,
+    /// Deleted files, relative to `root`, sorted. A staged rename counts as
+    /// the deletion of the old path and the addition of the new one.
+    pub deleted: Vec<PathBuf>,
+}
+
+/// Return the Markdown files under `root` staged in the index, or `None`
+/// when `root` is not inside a git work tree or `git` is not installed.
+pub(crate) fn staged_markdown_files(root: &Path) -> Option<StagedFiles> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args([
+            "-c",
+            "core.quotepath=off",
+            "diff",
+            "--cached",
+            "--name-status",
+            "--no-renames",
+            "--relative",
+            "-z",
+            "--",
+            "*.md",
+        ])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    let mut staged = StagedFiles::default();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut fields = stdout.split('\0');
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        let path = PathBuf::from(path);
+        if status == "D" {
+            staged.deleted.push(path);
+        } else {
+            staged.changed.push(path);
+        }
+    }
+    staged.changed.sort();
+    staged.deleted.sort();
+
+    Some(staged)
+}
+
+/// Return whether `path` has changes, or is untracked, in the work tree but
+/// not staged in the index; `false` outside a git work tree or without `git`.
+pub(crate) fn has_unstaged_changes(path: &Path) -> bool {
+    let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+        return false;
+    };
+    let dir = if dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        dir
+    };
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["status", "--porcelain", "--"])
+        .arg(name)
+        .output();
+    let Ok(output) = output else {
+        return false;
+    };
+
+    // The second column of each entry is its work tree status.
+    output.status.success()
+        && String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .any(|line| line.as_bytes().get(1).is_some_and(|&status| status != b' '))
+}
+
+/// Return the file's modification date (`YYYY-MM-DD`, UTC), or `None` when it
+/// cannot be read.
+#[must_use]
+pub fn file_modified_date(path: &Path) -> Option<String> {
+    let modified = path.metadata().ok()?.modified().ok()?;
+    let seconds = modified.duration_since(UNIX_EPOCH).ok()?.as_secs();
+    Some(format_date(seconds / 86_400))
+}
+
+/// Format days since the Unix epoch as a proleptic Gregorian `YYYY-MM-DD`.
+pub(crate) fn format_date(days: u64) -> String {
+    // Howard Hinnant's `civil_from_days`, restricted to dates after 1970.
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Return today's date (`YYYY-MM-DD`, UTC).
+pub(crate) fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs());
+    format_date(seconds / 86_400)
+}
+
+/// Parse a `YYYY-MM-DD` date into days since the Unix epoch, the inverse of
+/// [`format_date`]. Returns `None` for malformed or pre-1970 dates.
+pub(crate) fn parse_date(value: &str) -> Option<u64> {
+    if !is_iso_date(value) {
+        return None;
+    }
+    let year = value[0..4].parse::<u64>().ok()?;
+    let month = value[5..7].parse::<u64>().ok()?;
+    let day = value[8..10].parse::<u64>().ok()?;
+
+    // Howard Hinnant's `days_from_civil`.
+    let year = if month <= 2 {
+        year.checked_sub(1)?
+    } else {
+        year
+    };
+    let era = year / 400;
+    let year_of_era = year % 400;
+    let month_index = if month > 2 { month - 3 } else { month + 9 };
+    let day_of_year = (153 * month_index + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    (era * 146_097 + day_of_era).checked_sub(719_468)
+}
+
+/// Return whether `value` looks like a `YYYY-MM-DD` date.
+#[must_use]
+pub fn is_iso_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    bytes.len() == 10
+        && bytes[4] == b'-'
+        && bytes[7] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(index, byte)| index == 4 || index == 7 || byte.is_ascii_digit())
+        && matches!(
+            &value[5..7],
+            "01" | "02" | "03" | "04" | "05" | "06" | "07" | "08" | "09" | "10" | "11" | "12"
+        )
+        && matches!(value[8..10].parse::<u8>(), Ok(1..=31))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_date, is_iso_date, parse_date};
+
+    #[test]
+    fn formats_days_since_epoch() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(11_016), "2000-02-29");
+        assert_eq!(format_date(19_723), "2024-01-01");
+    }
+
+    #[test]
+    fn parses_dates_back_to_days_since_epoch() {
+        for days in [0, 11_016, 19_723] {
+            assert_eq!(parse_date(&format_date(days)), Some(days));
+        }
+        assert_eq!(parse_date("1969-12-31"), None);
+        assert_eq!(parse_date("yesterday"), None);
+    }
+
+    #[test]
+    fn recognizes_iso_dates() {
+        assert!(is_iso_date("2024-05-31"));
+        assert!(!is_iso_date("2024-5-31"));
+        assert!(!is_iso_date("2024-13-01"));
+        assert!(!is_iso_date("yesterday"));
+    }
+}
@@ -1,44 +1,589 @@
-use crate::catalog::Catalog;
-use std::collections::HashMap;
+use crate::{catalog::Catalog, domain::RelationKind, reachability::Reachability};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, OnceLock};
 
+/// Adjacency of a [`Catalog`]'s edges of one relation kind, for answering
+/// dependency queries without scanning the edge list.
+///
+/// Each id is stored once and edges refer to it by index, so large catalogs
+/// do not hold a copy of an id per edge; queries return ids borrowed from the
+/// graph. Only the `deps` direction is built up front: the `refs` direction
+/// is built by the first query needing it. Transitive queries walk the graph
+/// unless [`Graph::with_reachability_index`] asks for a closure shared by
+/// every query.
 pub struct Graph {
-    forward: HashMap<String, Vec<String>>,
-    reverse: HashMap<String, Vec<String>>,
+    /// Every id: the catalog's nodes first, then ids only edges mention.
+    ids: Vec<Arc<str>>,
+    index: HashMap<Arc<str>, usize>,
+    /// Number of ids that are catalog nodes, at the start of `ids`.
+    node_count: usize,
+    /// Position in the catalog's nodes of each node id, by index.
+    positions: Vec<usize>,
+    /// Indices each id depends on directly, in catalog edge order.
+    forward: Vec<Vec<usize>>,
+    /// Indices depending on each id directly, in `ids` order. Built by the
+    /// first `refs` query.
+    reverse: OnceLock<Vec<Vec<usize>>>,
+    /// Set by [`Graph::with_reachability_index`]; built by the first
+    /// transitive query and shared by the rest.
+    reachability: Option<OnceLock<Reachability>>,
 }
 
 impl Graph {
+    /// Graph of the catalog's `deps` edges.
+    #[must_use]
     pub fn from_catalog(catalog: &Catalog) -> Self {
-        let mut forward = HashMap::new();
-        let mut reverse = HashMap::new();
+        Self::from_catalog_kind(catalog, None)
+    }
 
-        for edge in &catalog.edges {
-            forward
-                .entry(edge.from.clone())
-                .or_insert_with(Vec::new)
-                .push(edge.to.clone());
+    /// Graph of the catalog's edges of relation kind `kind`, or of its `deps`
+    /// edges when `kind` is `None`.
+    #[must_use]
+    pub fn from_catalog_kind(
+        catalog: &Catalog,
+        kind: Option<&str>,
+    ) -> Self {
+        let mut graph = Self {
+            ids: Vec::with_capacity(catalog.nodes.len()),
+            index: HashMap::with_capacity(catalog.nodes.len()),
+            node_count: 0,
+            positions: Vec::with_capacity(catalog.nodes.len()),
+            forward: Vec::with_capacity(catalog.nodes.len()),
+            reverse: OnceLock::new(),
+            reachability: None,
+        };
+        for (position, node) in catalog.nodes.iter().enumerate() {
+            let index = graph.intern(&node.id);
+            if index == graph.positions.len() {
+                graph.positions.push(position);
+            } else {
+                graph.positions[index] = position;
+            }
+        }
+        graph.node_count = graph.ids.len();
 
-            reverse
-                .entry(edge.to.clone())
-                .or_insert_with(Vec::new)
-                .push(edge.from.clone());
+        for edge in catalog
+            .edges
+            .iter()
+            .filter(|edge| edge.kind.as_deref() == kind)
+        {
+            let from = graph.intern(&edge.from);
+            let to = graph.intern(&edge.to);
+            graph.forward[from].push(to);
+        }
+
+        graph
+    }
+
+    /// Answer transitive queries from a closure of the graph, per strongly
+    /// connected component, instead of walking the graph for each one.
+    ///
+    /// Building it takes memory quadratic in the number of components, so it
+    /// only pays off when most ids are queried, as for ranking hotspots.
+    #[must_use]
+    pub fn with_reachability_index(mut self) -> Self {
+        self.reachability = Some(OnceLock::new());
+        self
+    }
+
+    /// Index of `id`, added as a vertex without edges when new.
+    fn intern(
+        &mut self,
+        id: &str,
+    ) -> usize {
+        if let Some(&index) = self.index.get(id) {
+            return index;
         }
+        let id = Arc::<str>::from(id);
+        let index = self.ids.len();
+        self.ids.push(Arc::clone(&id));
+        self.index.insert(id, index);
+        self.forward.push(Vec::new());
+        index
+    }
 
-        Self { forward, reverse }
+    /// Return whether `id` is a catalog node, regardless of its edges.
+    #[must_use]
+    pub fn contains(
+        &self,
+        id: &str,
+    ) -> bool {
+        self.index
+            .get(id)
+            .is_some_and(|&index| index < self.node_count)
     }
 
+    /// Ids `id` depends on directly, in catalog edge order.
     #[must_use]
     pub fn deps(
         &self,
         id: &str,
-    ) -> Vec<String> {
-        self.forward.get(id).cloned().unwrap_or_default()
+    ) -> Vec<&str> {
+        self.adjacent(&self.forward, id)
     }
 
+    /// Ids depending on `id` directly: catalog nodes in catalog order, then
+    /// ids only edges mention.
     #[must_use]
     pub fn refs(
         &self,
         id: &str,
-    ) -> Vec<String> {
-        self.reverse.get(id).cloned().unwrap_or_default()
+    ) -> Vec<&str> {
+        self.adjacent(self.reverse(), id)
+    }
+
+    fn reverse(&self) -> &[Vec<usize>] {
+        self.reverse.get_or_init(|| {
+            let mut reverse = vec![Vec::new(); self.forward.len()];
+            for (from, targets) in self.forward.iter().enumerate() {
+                for &to in targets {
+                    reverse[to].push(from);
+                }
+            }
+            reverse
+        })
+    }
+
+    fn adjacent(
+        &self,
+        adjacency: &[Vec<usize>],
+        id: &str,
+    ) -> Vec<&str> {
+        self.index.get(id).map_or_else(Vec::new, |&index| {
+            adjacency[index]
+                .iter()
+                .map(|&adjacent| &*self.ids[adjacent])
+                .collect()
+        })
+    }
+
+    /// Ids related to `id` by `relation`, directly or with `transitive` also
+    /// indirectly, in id order without duplicates, each with the position
+    /// of its node in the catalog the graph was built from, if it is one.
+    pub(crate) fn related(
+        &self,
+        id: &str,
+        relation: RelationKind,
+        transitive: bool,
+    ) -> impl Iterator<Item = (&str, Option<usize>)> {
+        let mut related = match self.index.get(id) {
+            None => Vec::new(),
+            Some(&index) if transitive => self.reached(index, relation),
+            Some(&index) => match relation {
+                RelationKind::Deps => self.forward[index].clone(),
+                RelationKind::Refs => self.reverse()[index].clone(),
+            },
+        };
+        related.sort_unstable_by(|left, right| self.ids[*left].cmp(&self.ids[*right]));
+        related.dedup();
+        related
+            .into_iter()
+            .map(|index| (&*self.ids[index], self.positions.get(index).copied()))
+    }
+
+    /// Ids `id` depends on directly or transitively, sorted.
+    #[must_use]
+    pub fn transitive_deps(
+        &self,
+        id: &str,
+    ) -> Vec<&str> {
+        self.transitive(id, RelationKind::Deps)
+    }
+
+    /// Ids depending on `id` directly or transitively, sorted.
+    #[must_use]
+    pub fn transitive_refs(
+        &self,
+        id: &str,
+    ) -> Vec<&str> {
+        self.transitive(id, RelationKind::Refs)
+    }
+
+    /// Ids reached from `id` directly or transitively by following
+    /// `relation`, sorted.
+    #[must_use]
+    pub fn transitive(
+        &self,
+        id: &str,
+        relation: RelationKind,
+    ) -> Vec<&str> {
+        self.related(id, relation, true)
+            .map(|(related, _)| related)
+            .collect()
+    }
+
+    /// Indices reached from `index` by following `relation`, in no
+    /// particular order.
+    fn reached(
+        &self,
+        index: usize,
+        relation: RelationKind,
+    ) -> Vec<usize> {
+        if let Some(reachability) = &self.reachability {
+            let reachability = reachability.get_or_init(|| Reachability::new(&self.forward));
+            match relation {
+                RelationKind::Deps => reachability.dependencies(index).collect(),
+                RelationKind::Refs => reachability.dependents(index).collect(),
+            }
+        } else {
+            match relation {
+                RelationKind::Deps => walk(&self.forward, index),
+                RelationKind::Refs => walk(self.reverse(), index),
+            }
+        }
+    }
+
+    /// Return the shortest dependency paths from `from` to `to`, each listing
+    /// the ids along the way including both ends.
+    ///
+    /// With `all` unset only the lexicographically smallest shortest path is
+    /// returned. The result is empty when `to` is not reachable.
+    #[must_use]
+    pub fn shortest_paths(
+        &self,
+        from: &str,
+        to: &str,
+        all: bool,
+    ) -> Vec<Vec<String>> {
+        if from == to {
+            return vec![vec![from.to_owned()]];
+        }
+        let (Some(&from), Some(&to)) = (self.index.get(from), self.index.get(to)) else {
+            return Vec::new();
+        };
+
+        // Breadth-first search recording every predecessor on a shortest path.
+        let mut distance = HashMap::from([(from, 0_usize)]);
+        let mut predecessors = HashMap::<usize, Vec<usize>>::new();
+        let mut queue = VecDeque::from([from]);
+        while let Some(current) = queue.pop_front() {
+            let next_distance = distance[&current] + 1;
+            if distance
+                .get(&to)
+                .is_some_and(|found| *found < next_distance)
+            {
+                break;
+            }
+
+            for &next in &self.forward[current] {
+                match distance.get(&next) {
+                    None => {
+                        distance.insert(next, next_distance);
+                        predecessors.insert(next, vec![current]);
+                        queue.push_back(next);
+                    },
+                    Some(known) if *known == next_distance => {
+                        predecessors.entry(next).or_default().push(current);
+                    },
+                    Some(_) => {},
+                }
+            }
+        }
+
+        if !distance.contains_key(&to) {
+            return Vec::new();
+        }
+
+        let mut paths = Vec::new();
+        let mut stack = vec![vec![to]];
+        while let Some(partial) = stack.pop() {
+            let head = partial[partial.len() - 1];
+            if head == from {
+                paths.push(
+                    partial
+                        .iter()
+                        .rev()
+                        .map(|&index| self.ids[index].to_string())
+                        .collect(),
+                );
+                continue;
+            }
+            for &previous in predecessors.get(&head).into_iter().flatten() {
+                let mut extended = partial.clone();
+                extended.push(previous);
+                stack.push(extended);
+            }
+        }
+
+        paths.sort();
+        if !all {
+            paths.truncate(1);
+        }
+        paths
+    }
+}
+
+/// Vertices reached from `start` along `adjacency`, in no particular order;
+/// `start` itself is left out even when it is part of a cycle.
+fn walk(
+    adjacency: &[Vec<usize>],
+    start: usize,
+) -> Vec<usize> {
+    let mut seen = vec![false; adjacency.len()];
+    seen[start] = true;
+    let mut reached = Vec::new();
+    let mut pending = vec![start];
+    while let Some(vertex) = pending.pop() {
+        for &next in &adjacency[vertex] {
+            if !seen[next] {
+                seen[next] = true;
+                reached.push(next);
+                pending.push(next);
+            }
+        }
+    }
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Graph;
+    use crate::catalog::{Catalog, Edge, Node};
+    use crate::domain::RelationKind;
+    use std::collections::{BTreeMap, BTreeSet, HashMap};
+    use std::sync::Arc;
+
+    fn node(id: &str) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn isolated_nodes_are_vertices() {
+        let catalog = Catalog {
+            nodes: vec![node("alpha"), node("isolated")],
+            edges: vec![Edge {
+                from: "alpha".to_owned(),
+                to: "missing".to_owned(),
+                kind: None,
+                declared_at: None,
+            }],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let graph = Graph::from_catalog(&catalog);
+
+        assert!(graph.contains("alpha"));
+        assert!(graph.contains("isolated"));
+        assert!(!graph.contains("missing"));
+        assert!(graph.deps("isolated").is_empty());
+        assert_eq!(graph.refs("missing"), ["alpha"]);
+    }
+
+    #[test]
+    fn resolves_related_ids_to_catalog_positions_in_id_order() {
+        let edge = |from: &str, to: &str| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        };
+        let catalog = Catalog {
+            nodes: vec![node("web"), node("db"), node("api")],
+            edges: vec![
+                edge("web", "db"),
+                edge("web", "api"),
+                edge("web", "db"),
+                edge("api", "cache"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let graph = Graph::from_catalog(&catalog);
+
+        assert_eq!(
+            graph
+                .related("web", RelationKind::Deps, false)
+                .collect::<Vec<_>>(),
+            [("api", Some(2)), ("db", Some(1))]
+        );
+        assert_eq!(
+            graph
+                .related("web", RelationKind::Deps, true)
+                .collect::<Vec<_>>(),
+            [("api", Some(2)), ("cache", None), ("db", Some(1))]
+        );
+        assert_eq!(
+            graph
+                .related("cache", RelationKind::Refs, true)
+                .collect::<Vec<_>>(),
+            [("api", Some(2)), ("web", Some(0))]
+        );
+        assert_eq!(
+            graph.related("unknown", RelationKind::Refs, false).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn builds_the_refs_direction_on_first_use() {
+        let edge = |from: &str, to: &str| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        };
+        let catalog = Catalog {
+            nodes: vec![node("web"), node("api"), node("db")],
+            edges: vec![edge("web", "db"), edge("api", "db"), edge("web", "api")],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let graph = Graph::from_catalog(&catalog);
+        assert_eq!(graph.deps("web"), ["db", "api"]);
+        assert_eq!(graph.transitive_deps("web"), ["api", "db"]);
+        assert!(graph.reverse.get().is_none());
+
+        assert_eq!(graph.refs("db"), ["web", "api"]);
+        assert_eq!(graph.refs("api"), ["web"]);
+        assert!(graph.reverse.get().is_some());
+    }
+
+    #[test]
+    fn answers_queries_from_interned_ids_as_before_interning() {
+        let edge = |from: &str, to: &str, kind: Option<&str>| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: kind.map(str::to_owned),
+            declared_at: None,
+        };
+        let catalog = Catalog {
+            nodes: vec![
+                node("web"),
+                node("api"),
+                node("db"),
+                node("api"),
+                node("lone"),
+            ],
+            edges: vec![
+                edge("web", "api", None),
+                edge("api", "db", None),
+                edge("db", "api", None),
+                edge("web", "db", None),
+                edge("web", "api", None),
+                edge("db", "cache", None),
+                edge("ghost", "web", None),
+                edge("lone", "web", Some("implements")),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        // The adjacency the graph kept before interning: one `String` per id
+        // per edge end, keyed by id.
+        let mut forward = HashMap::<String, Vec<String>>::new();
+        let mut reverse = HashMap::<String, Vec<String>>::new();
+        for edge in catalog.edges.iter().filter(|edge| edge.kind.is_none()) {
+            forward
+                .entry(edge.from.clone())
+                .or_default()
+                .push(edge.to.clone());
+            reverse
+                .entry(edge.to.clone())
+                .or_default()
+                .push(edge.from.clone());
+        }
+        let transitive = |adjacency: &HashMap<String, Vec<String>>, id: &str| {
+            let mut reached = BTreeSet::new();
+            let mut pending = vec![id.to_owned()];
+            while let Some(current) = pending.pop() {
+                for next in adjacency.get(&current).into_iter().flatten() {
+                    if next != id && reached.insert(next.clone()) {
+                        pending.push(next.clone());
+                    }
+                }
+            }
+            reached.into_iter().collect::<Vec<_>>()
+        };
+
+        let graph = Graph::from_catalog(&catalog);
+        let distinct = ["web", "api", "db", "lone", "cache", "ghost"];
+        assert_eq!(graph.ids.len(), distinct.len());
+        for id in distinct {
+            let direct = forward.get(id).cloned().unwrap_or_default();
+            assert_eq!(graph.deps(id), direct, "deps of {id}");
+            let mut refs = graph.refs(id);
+            refs.sort_unstable();
+            let mut expected = reverse.get(id).cloned().unwrap_or_default();
+            expected.sort_unstable();
+            assert_eq!(refs, expected, "refs of {id}");
+            assert_eq!(
+                graph.transitive_deps(id),
+                transitive(&forward, id),
+                "transitive deps of {id}"
+            );
+            assert_eq!(
+                graph.transitive_refs(id),
+                transitive(&reverse, id),
+                "transitive refs of {id}"
+            );
+            assert_eq!(
+                graph.contains(id),
+                catalog.nodes.iter().any(|node| node.id == id)
+            );
+
+            // Every answer borrows the one interned copy of an id, shared
+            // only with the index, rather than a copy per edge.
+            let interned = &graph.ids[graph.index[id]];
+            assert_eq!(Arc::strong_count(interned), 2, "copies of {id}");
+            for answer in [
+                graph.deps("web"),
+                graph.refs("db"),
+                graph.transitive_refs(id),
+            ] {
+                for found in answer.into_iter().filter(|found| *found == id) {
+                    assert!(found.as_ptr() == interned.as_ptr(), "{id} not interned");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn finds_all_shortest_paths() {
+        let edge = |from: &str, to: &str| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        };
+        let catalog = Catalog {
+            nodes: vec![node("a"), node("b"), node("c"), node("d")],
+            edges: vec![
+                edge("a", "b"),
+                edge("a", "c"),
+                edge("b", "d"),
+                edge("c", "d"),
+                edge("a", "d-long"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let graph = Graph::from_catalog(&catalog);
+
+        assert_eq!(
+            graph.shortest_paths("a", "d", true),
+            vec![
+                vec!["a".to_owned(), "b".to_owned(), "d".to_owned()],
+                vec!["a".to_owned(), "c".to_owned(), "d".to_owned()],
+            ]
+        );
+        assert_eq!(graph.shortest_paths("a", "d", false).len(), 1);
+        assert!(graph.shortest_paths("d", "a", true).is_empty());
     }
 }
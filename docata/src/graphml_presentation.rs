@@ -0,0 +1,181 @@
+use crate::catalog::{Catalog, Node};
+use crate::xml::escape;
+use std::collections::BTreeSet;
+use std::io::Write;
+use thiserror::Error;
+
+const NODE_KEYS: [(&str, &str); 9] = [
+    ("label", "string"),
+    ("path", "string"),
+    ("title", "string"),
+    ("type", "string"),
+    ("domain", "string"),
+    ("status", "string"),
+    ("source_of_truth", "string"),
+    ("tags", "string"),
+    ("resolved", "boolean"),
+];
+
+#[derive(Debug, Error)]
+pub enum GraphMlPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Write a catalog as a directed `GraphML` graph.
+///
+/// Edge targets missing from the catalog are written as nodes with
+/// `resolved` set to `false`, so every edge endpoint exists.
+///
+/// # Errors
+///
+/// Returns `GraphMlPresentationError` if writing fails.
+pub fn write<W: Write>(
+    catalog: &Catalog,
+    out: &mut W,
+) -> Result<(), GraphMlPresentationError> {
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns" xmlns:xsi="http://www.w3.org/2001/XMLSchema-instance" xsi:schemaLocation="http://graphml.graphdrawing.org/xmlns http://graphml.graphdrawing.org/xmlns/1.0/graphml.xsd">"#
+    )?;
+    for (name, kind) in NODE_KEYS {
+        writeln!(
+            out,
+            r#"  <key id="{name}" for="node" attr.name="{name}" attr.type="{kind}"/>"#
+        )?;
+    }
+    // Edges of other relation kinds override these `deps` defaults.
+    for (id, name) in [("kind", "kind"), ("edge_label", "label")] {
+        writeln!(
+            out,
+            r#"  <key id="{id}" for="edge" attr.name="{name}" attr.type="string"><default>deps</default></key>"#
+        )?;
+    }
+    writeln!(out, r#"  <graph id="docata" edgedefault="directed">"#)?;
+
+    let known_ids = catalog
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    for node in &catalog.nodes {
+        write_node(out, node)?;
+    }
+
+    let unresolved_ids = catalog
+        .edges
+        .iter()
+        .map(|edge| edge.to.as_str())
+        .filter(|id| !known_ids.contains(id))
+        .collect::<BTreeSet<_>>();
+    for id in unresolved_ids {
+        let id = escape(id);
+        writeln!(out, r#"    <node id="{id}">"#)?;
+        writeln!(out, r#"      <data key="label">{id}</data>"#)?;
+        writeln!(out, r#"      <data key="resolved">false</data>"#)?;
+        writeln!(out, "    </node>")?;
+    }
+
+    for (index, edge) in catalog.edges.iter().enumerate() {
+        let source = escape(&edge.from);
+        let target = escape(&edge.to);
+        let Some(kind) = edge.kind.as_deref() else {
+            writeln!(
+                out,
+                r#"    <edge id="e{index}" source="{source}" target="{target}"/>"#
+            )?;
+            continue;
+        };
+        writeln!(
+            out,
+            r#"    <edge id="e{index}" source="{source}" target="{target}">"#
+        )?;
+        writeln!(out, r#"      <data key="kind">{}</data>"#, escape(kind))?;
+        writeln!(
+            out,
+            r#"      <data key="edge_label">{}</data>"#,
+            escape(catalog.relation_display(Some(kind)))
+        )?;
+        writeln!(out, "    </edge>")?;
+    }
+
+    writeln!(out, "  </graph>")?;
+    writeln!(out, "</graphml>")?;
+    Ok(())
+}
+
+fn write_node<W: Write>(
+    out: &mut W,
+    node: &Node,
+) -> Result<(), GraphMlPresentationError> {
+    let label = node.title.as_deref().unwrap_or(&node.id);
+    let tags = (!node.tags.is_empty()).then(|| node.tags.join(","));
+    let fields = [
+        ("label", Some(label)),
+        ("path", Some(node.path.as_str())),
+        ("title", node.title.as_deref()),
+        ("type", node.kind.as_deref()),
+        ("domain", node.domain.as_deref()),
+        ("status", node.status.as_deref()),
+        ("source_of_truth", node.source_of_truth.as_deref()),
+        ("tags", tags.as_deref()),
+        ("resolved", Some("true")),
+    ];
+
+    writeln!(out, r#"    <node id="{}">"#, escape(&node.id))?;
+    for (key, value) in fields {
+        if let Some(value) = value {
+            writeln!(out, r#"      <data key="{key}">{}</data>"#, escape(value))?;
+        }
+    }
+    writeln!(out, "    </node>")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn writes_nodes_with_metadata_and_placeholder_targets() {
+        let catalog = Catalog {
+            nodes: vec![Node {
+                id: "foo".to_owned(),
+                path: "docs/foo.md".to_owned(),
+                title: Some("Foo & Bar".to_owned()),
+                tags: vec!["api".to_owned()],
+                kind: Some("spec".to_owned()),
+                domain: None,
+                status: None,
+                source_of_truth: None,
+                content_hash: None,
+                last_modified: None,
+                authors: Vec::new(),
+                owners: Vec::new(),
+            }],
+            edges: vec![Edge {
+                from: "foo".to_owned(),
+                to: "missing".to_owned(),
+                kind: None,
+                declared_at: None,
+            }],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let mut output = Vec::new();
+        write(&catalog, &mut output).expect("write graphml");
+        let xml = String::from_utf8(output).expect("valid utf-8");
+
+        assert!(xml.contains(r#"<graph id="docata" edgedefault="directed">"#));
+        assert!(xml.contains(r#"<data key="label">Foo &amp; Bar</data>"#));
+        assert!(xml.contains(r#"<data key="type">spec</data>"#));
+        assert!(!xml.contains(r#"<data key="domain">"#));
+        assert!(xml.contains(r#"<node id="missing">"#));
+        assert!(xml.contains(r#"<edge id="e0" source="foo" target="missing"/>"#));
+    }
+}
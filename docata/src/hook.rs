@@ -0,0 +1,311 @@
+use crate::{
+    BuildOptions, CatalogStore, Compression, DuplicateIdPolicy, build,
+    catalog::{Catalog, normalize_path_string},
+    catalog_presentation,
+    delta::CatalogDelta,
+    error::Error,
+    git,
+    progress::NoProgress,
+    scan,
+    simulate::entries_from_catalog,
+    validate::{ValidationReport, ValidationRules, build_validation_report_for},
+};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// Outcome of checking the documents staged for a commit.
+#[derive(Clone, Debug, Default)]
+pub struct PreCommitReport {
+    /// Staged Markdown files, relative to the documents directory, sorted.
+    pub staged: Vec<PathBuf>,
+    /// Markdown files whose deletion is staged, likewise.
+    pub deleted: Vec<PathBuf>,
+    /// Findings about the staged documents, and about documents depending on
+    /// ids the staged changes remove.
+    pub findings: ValidationReport,
+    /// Location of the catalog checked.
+    pub catalog: String,
+    /// How the catalog's nodes and edges for the staged documents differ
+    /// from what building would write; empty when the catalog is up to date.
+    pub catalog_delta: CatalogDelta,
+    /// Whether the catalog has work tree changes that are not staged, so the
+    /// commit would not include them.
+    pub catalog_unstaged: bool,
+}
+
+impl PreCommitReport {
+    /// Number of problems that fail the hook.
+    #[must_use]
+    pub fn failure_count(&self) -> usize {
+        self.findings.finding_count()
+            + usize::from(!self.catalog_delta.is_empty())
+            + usize::from(self.catalog_unstaged)
+    }
+
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failure_count() == 0
+    }
+}
+
+/// Check the Markdown files staged under `root` against `catalog` without
+/// scanning the rest of the tree.
+///
+/// Staged documents are validated with `rules` against the catalog's graph,
+/// standing in for the documents not staged; documents depending on an id
+/// the staged changes remove are read again and validated too. The
+/// catalog's nodes and edges for the staged documents are then compared with
+/// what building them would write. Staged files are read from the work
+/// tree.
+///
+/// # Errors
+///
+/// Returns `Error::NotInGitRepository` when `root` is not inside a git work
+/// tree, or `Error` when the catalog cannot be read or a staged document
+/// fails to scan.
+pub fn run<S: CatalogStore + ?Sized>(
+    root: &Path,
+    catalog: &S,
+    rules: &ValidationRules,
+) -> Result<PreCommitReport, Error> {
+    let staged = git::staged_markdown_files(root).ok_or_else(|| Error::NotInGitRepository {
+        path: root.to_path_buf(),
+    })?;
+    let content = catalog.load()?;
+    let stored = catalog_presentation::read_catalog(&mut content.as_slice(), catalog.format())?;
+
+    let changed_paths = staged
+        .changed
+        .iter()
+        .chain(&staged.deleted)
+        .map(|path| normalize_path_string(&root.join(path)))
+        .collect::<BTreeSet<_>>();
+    let replaced_ids = stored
+        .nodes
+        .iter()
+        .filter(|node| changed_paths.contains(&node.path))
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+
+    let options = BuildOptions {
+        report_skipped: rules.report_skipped,
+        lenient: rules.lenient,
+        ..recorded_options(&content, catalog)
+    };
+    let mut outcome = scan::scan_paths(
+        root,
+        staged.changed.iter().map(|path| root.join(path)).collect(),
+        options.scan_options(),
+    )?;
+    let staged_count = outcome.entries.len();
+    let staged_ids = outcome
+        .entries
+        .iter()
+        .map(|entry| entry.id.as_str())
+        .collect::<BTreeSet<_>>();
+
+    // Documents left depending on a removed id are read again so they are
+    // validated from their frontmatter, not their catalog node.
+    let dependents = dependents_of(&stored, &replaced_ids, &staged_ids, &changed_paths);
+    let rescanned = scan::scan_paths(root, dependents, options.scan_options())?;
+    outcome.entries.extend(rescanned.entries);
+    outcome.diagnostics.extend(rescanned.diagnostics);
+
+    let catalog_delta = staged_delta(
+        root,
+        &stored,
+        &changed_paths,
+        &outcome.entries[..staged_count],
+        options,
+    )?;
+
+    let subject_paths = outcome
+        .entries
+        .iter()
+        .map(|entry| normalize_path_string(&entry.path))
+        .collect::<BTreeSet<_>>();
+    let mut entries = entries_from_catalog(&stored);
+    entries.retain(|entry| {
+        let path = normalize_path_string(&entry.path);
+        !changed_paths.contains(&path) && !subject_paths.contains(&path)
+    });
+    entries.extend(outcome.entries);
+    let mut findings = build_validation_report_for(
+        root,
+        &entries,
+        rules,
+        |entry| subject_paths.contains(&normalize_path_string(&entry.path)),
+        &NoProgress,
+    );
+    findings.skipped = outcome.skipped;
+    findings.invalid_documents = outcome.diagnostics;
+
+    let location = catalog.location();
+    let catalog_path = Path::new(&location);
+    Ok(PreCommitReport {
+        staged: staged.changed,
+        deleted: staged.deleted,
+        findings,
+        catalog_unstaged: catalog_path.exists() && git::has_unstaged_changes(catalog_path),
+        catalog: location,
+        catalog_delta,
+    })
+}
+
+/// The options the catalog in `content` was built with, adjusted to render
+/// a few nodes for comparison: git history is left out, since the commit
+/// being made changes it.
+fn recorded_options<S: CatalogStore + ?Sized>(
+    content: &[u8],
+    catalog: &S,
+) -> BuildOptions {
+    let recorded = build::RecordedOptions::read(content, catalog.format());
+    let recorded_flag =
+        |flag: fn(&build::RecordedOptions) -> bool| recorded.as_ref().is_some_and(flag);
+    BuildOptions {
+        include_node_metadata: build::detect_node_metadata(content, catalog.format()),
+        duplicate_id_policy: DuplicateIdPolicy::KeepFirst,
+        catalog_format: catalog.format(),
+        compression: Compression::None,
+        include_provenance: recorded_flag(|recorded| recorded.with_provenance),
+        include_content_hash: recorded_flag(|recorded| recorded.with_content_hash),
+        include_code_owners: recorded_flag(|recorded| recorded.with_code_owners),
+        ..BuildOptions::default()
+    }
+}
+
+/// Paths of the catalog documents outside `changed_paths` that depend on an
+/// id in `removed_ids` no staged document keeps.
+fn dependents_of(
+    catalog: &Catalog,
+    removed_ids: &BTreeSet<&str>,
+    staged_ids: &BTreeSet<&str>,
+    changed_paths: &BTreeSet<String>,
+) -> Vec<PathBuf> {
+    let removed = removed_ids
+        .difference(staged_ids)
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let dependent_ids = catalog
+        .dependency_edges()
+        .filter(|edge| removed.contains(edge.to.as_str()))
+        .map(|edge| edge.from.as_str())
+        .collect::<BTreeSet<_>>();
+
+    catalog
+        .nodes
+        .iter()
+        .filter(|node| dependent_ids.contains(node.id.as_str()))
+        .filter(|node| !changed_paths.contains(&node.path))
+        .map(|node| PathBuf::from(&node.path))
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+/// Compare the nodes and edges `catalog` holds for the documents at
+/// `changed_paths` with those building `staged` would write.
+fn staged_delta(
+    root: &Path,
+    catalog: &Catalog,
+    changed_paths: &BTreeSet<String>,
+    staged: &[scan::Entry],
+    options: BuildOptions,
+) -> Result<CatalogDelta, Error> {
+    let rendered = build::render(root, staged, options)?;
+    let rendered =
+        catalog_presentation::read_catalog(&mut rendered.as_slice(), options.catalog_format)?;
+
+    let nodes = catalog
+        .nodes
+        .iter()
+        .filter(|node| changed_paths.contains(&node.path))
+        .map(|node| {
+            let mut node = node.clone();
+            node.last_modified = None;
+            node.authors.clear();
+            node
+        })
+        .collect::<Vec<_>>();
+    let ids = nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    let edges = catalog
+        .edges
+        .iter()
+        .filter(|edge| ids.contains(edge.from.as_str()))
+        .cloned()
+        .collect();
+    let current = Catalog {
+        nodes,
+        edges,
+        relations: catalog.relations.clone(),
+        external: Vec::new(),
+    };
+
+    Ok(CatalogDelta::between(&current, &rendered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::run;
+    use crate::testing::{TestWorkspace, write_markdown};
+    use crate::{BuildOptions, ValidationRules, build_catalog_into, open_catalog_store};
+    use std::path::Path;
+    use std::process::Command;
+
+    fn git(
+        dir: &Path,
+        args: &[&str],
+    ) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args([
+                "-c",
+                "user.name=docata",
+                "-c",
+                "user.email=docata@example.com",
+            ])
+            .args(args)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {args:?} failed");
+    }
+
+    #[test]
+    fn checks_staged_documents_against_the_catalog() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "api.md", "api", &["db"]);
+        write_markdown(&docs, "db.md", "db", &[]);
+        let store = open_catalog_store(&docs.join("catalog.json").to_string_lossy())
+            .expect("open catalog store");
+        build_catalog_into(&docs, &*store, BuildOptions::default()).expect("build catalog");
+        git(workspace.path(), &["init", "--quiet"]);
+        git(workspace.path(), &["add", "."]);
+        git(workspace.path(), &["commit", "--quiet", "-m", "docs"]);
+
+        let report = run(&docs, &*store, &ValidationRules::default()).expect("run hook");
+        assert!(report.is_success());
+        assert!(report.staged.is_empty());
+
+        write_markdown(&docs, "web.md", "web", &["api", "cache"]);
+        git(workspace.path(), &["add", "docs/web.md"]);
+        git(workspace.path(), &["rm", "--quiet", "docs/db.md"]);
+
+        let report = run(&docs, &*store, &ValidationRules::default()).expect("run hook");
+        assert_eq!(report.staged, [Path::new("web.md")]);
+        assert_eq!(report.deleted, [Path::new("db.md")]);
+        let unresolved = report
+            .findings
+            .unresolved_dependencies
+            .iter()
+            .map(|unresolved| (unresolved.from_id.as_str(), unresolved.to_id.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(unresolved, [("api", "db"), ("web", "cache")]);
+        assert_eq!(report.catalog_delta.added_nodes[0].id, "web");
+        assert_eq!(report.catalog_delta.removed_nodes[0].id, "db");
+        assert!(!report.catalog_unstaged);
+    }
+}
@@ -0,0 +1,138 @@
+use crate::delta_presentation::{self, DeltaChangesJson, DeltaPresentationError};
+use crate::format::OutputFormat;
+use crate::hook::PreCommitReport;
+use crate::json;
+use crate::validation_presentation::ValidationFindingsJson;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct HookCatalogJson<'a> {
+    location: &'a str,
+    status: &'static str,
+    unstaged: bool,
+    #[serde(flatten)]
+    changes: DeltaChangesJson<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct PreCommitReportJson<'a> {
+    command: &'static str,
+    status: &'static str,
+    staged: Vec<String>,
+    deleted: Vec<String>,
+    catalog: HookCatalogJson<'a>,
+    #[serde(flatten)]
+    findings: ValidationFindingsJson<'a>,
+}
+
+impl<'a> From<&'a PreCommitReport> for PreCommitReportJson<'a> {
+    fn from(report: &'a PreCommitReport) -> Self {
+        let paths = |paths: &[std::path::PathBuf]| {
+            paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect()
+        };
+        Self {
+            command: "hook pre-commit",
+            status: status_label(report.is_success()),
+            staged: paths(&report.staged),
+            deleted: paths(&report.deleted),
+            catalog: HookCatalogJson {
+                location: &report.catalog,
+                status: if report.catalog_delta.is_empty() {
+                    "up_to_date"
+                } else {
+                    "outdated"
+                },
+                unstaged: report.catalog_unstaged,
+                changes: (&report.catalog_delta).into(),
+            },
+            findings: (&report.findings).into(),
+        }
+    }
+}
+
+const fn status_label(success: bool) -> &'static str {
+    if success { "passed" } else { "failed" }
+}
+
+#[derive(Debug, Error)]
+pub enum HookPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error(transparent)]
+    Delta(#[from] DeltaPresentationError),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a pre-commit hook report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `HookPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &PreCommitReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), HookPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &PreCommitReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &PreCommitReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(HookPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_text<W: Write>(
+    report: &PreCommitReport,
+    out: &mut W,
+) -> Result<(), HookPresentationError> {
+    writeln!(
+        out,
+        "staged: {} document(s), {} deletion(s)",
+        report.staged.len(),
+        report.deleted.len()
+    )?;
+
+    if report.findings.is_empty() {
+        writeln!(out, "validation passed")?;
+    } else {
+        write!(out, "{}", report.findings)?;
+    }
+
+    if report.catalog_delta.is_empty() {
+        writeln!(out, "catalog {} is up to date", report.catalog)?;
+    } else {
+        writeln!(
+            out,
+            "catalog {} is out of date; rebuild it and stage it:",
+            report.catalog
+        )?;
+        delta_presentation::write_text(&report.catalog_delta, out)?;
+    }
+    if report.catalog_unstaged {
+        writeln!(
+            out,
+            "catalog {} has changes that are not staged",
+            report.catalog
+        )?;
+    }
+
+    Ok(())
+}
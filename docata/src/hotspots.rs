@@ -0,0 +1,314 @@
+use crate::{catalog::Catalog, graph::Graph};
+use std::collections::{HashMap, VecDeque};
+
+/// What `docata hotspots` ranks documents by.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HotspotMetric {
+    /// Documents depending on it directly.
+    #[default]
+    InDegree,
+    /// Documents it depends on directly.
+    OutDegree,
+    /// Documents depending on it directly or transitively: how far a change
+    /// ripples.
+    Dependents,
+    /// Shortest dependency paths between other documents passing through it.
+    Betweenness,
+}
+
+impl HotspotMetric {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            HotspotMetric::InDegree => "in_degree",
+            HotspotMetric::OutDegree => "out_degree",
+            HotspotMetric::Dependents => "dependents",
+            HotspotMetric::Betweenness => "betweenness",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HotspotOptions {
+    pub rank_by: HotspotMetric,
+    /// Count transitive dependents, which costs one walk per document.
+    /// Always counted when ranking by them.
+    pub dependents: bool,
+    /// Compute betweenness centrality, which costs one breadth-first search
+    /// per document. Always computed when ranking by it.
+    pub betweenness: bool,
+    /// Keep at most this many documents.
+    pub limit: Option<usize>,
+}
+
+impl HotspotOptions {
+    pub(crate) fn counts_dependents(&self) -> bool {
+        self.dependents || self.rank_by == HotspotMetric::Dependents
+    }
+}
+
+/// Centrality figures of one document. Only `deps` edges between catalog
+/// documents count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hotspot {
+    pub id: String,
+    pub path: String,
+    pub in_degree: usize,
+    pub out_degree: usize,
+    pub dependents: Option<usize>,
+    pub betweenness: Option<f64>,
+}
+
+/// Documents ranked by a centrality metric, highest first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HotspotReport {
+    pub rank_by: HotspotMetric,
+    pub hotspots: Vec<Hotspot>,
+}
+
+/// Rank the documents of `catalog` by `options.rank_by`; ties are broken by
+/// dependents when counted, then id.
+#[must_use]
+pub fn build(
+    catalog: &Catalog,
+    graph: &Graph,
+    options: &HotspotOptions,
+) -> HotspotReport {
+    let index_of = catalog
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| (node.id.as_str(), index))
+        .collect::<HashMap<_, _>>();
+    let mut successors = vec![Vec::new(); catalog.nodes.len()];
+    for edge in catalog.dependency_edges() {
+        if let (Some(&from), Some(&to)) = (
+            index_of.get(edge.from.as_str()),
+            index_of.get(edge.to.as_str()),
+        ) {
+            successors[from].push(to);
+        }
+    }
+    let mut in_degrees = vec![0; catalog.nodes.len()];
+    for targets in &mut successors {
+        targets.sort_unstable();
+        targets.dedup();
+        for &to in targets.iter() {
+            in_degrees[to] += 1;
+        }
+    }
+
+    let betweenness = (options.betweenness || options.rank_by == HotspotMetric::Betweenness)
+        .then(|| betweenness(&successors));
+
+    let mut hotspots = catalog
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(index, node)| Hotspot {
+            id: node.id.clone(),
+            path: node.path.clone(),
+            in_degree: in_degrees[index],
+            out_degree: successors[index].len(),
+            dependents: options
+                .counts_dependents()
+                .then(|| graph.transitive_refs(&node.id).len()),
+            betweenness: betweenness.as_ref().map(|scores| scores[index]),
+        })
+        .collect::<Vec<_>>();
+
+    hotspots.sort_by(|left, right| {
+        let primary = match options.rank_by {
+            HotspotMetric::InDegree => right.in_degree.cmp(&left.in_degree),
+            HotspotMetric::OutDegree => right.out_degree.cmp(&left.out_degree),
+            HotspotMetric::Dependents => std::cmp::Ordering::Equal,
+            HotspotMetric::Betweenness => right
+                .betweenness
+                .unwrap_or_default()
+                .total_cmp(&left.betweenness.unwrap_or_default()),
+        };
+        primary
+            .then_with(|| right.dependents.cmp(&left.dependents))
+            .then_with(|| left.id.cmp(&right.id))
+    });
+    if let Some(limit) = options.limit {
+        hotspots.truncate(limit);
+    }
+
+    HotspotReport {
+        rank_by: options.rank_by,
+        hotspots,
+    }
+}
+
+/// Brandes' algorithm for unweighted directed graphs: for every document,
+/// the fraction of shortest paths between each other pair passing through
+/// it, summed.
+#[allow(clippy::cast_precision_loss)]
+fn betweenness(successors: &[Vec<usize>]) -> Vec<f64> {
+    let count = successors.len();
+    let mut scores = vec![0.0; count];
+
+    for source in 0..count {
+        let mut order = Vec::with_capacity(count);
+        let mut predecessors = vec![Vec::new(); count];
+        let mut paths = vec![0.0_f64; count];
+        let mut distance = vec![usize::MAX; count];
+        paths[source] = 1.0;
+        distance[source] = 0;
+
+        let mut queue = VecDeque::from([source]);
+        while let Some(current) = queue.pop_front() {
+            order.push(current);
+            for &next in &successors[current] {
+                if distance[next] == usize::MAX {
+                    distance[next] = distance[current] + 1;
+                    queue.push_back(next);
+                }
+                if distance[next] == distance[current] + 1 {
+                    paths[next] += paths[current];
+                    predecessors[next].push(current);
+                }
+            }
+        }
+
+        let mut dependency = vec![0.0_f64; count];
+        while let Some(current) = order.pop() {
+            for &previous in &predecessors[current] {
+                dependency[previous] +=
+                    paths[previous] / paths[current] * (1.0 + dependency[current]);
+            }
+            if current != source {
+                scores[current] += dependency[current];
+            }
+        }
+    }
+
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HotspotMetric, HotspotOptions, build};
+    use crate::catalog::{Catalog, Edge, Node};
+    use crate::graph::Graph;
+    use std::collections::BTreeMap;
+
+    fn catalog(edges: &[(&str, &str)]) -> Catalog {
+        let mut ids = edges
+            .iter()
+            .flat_map(|(from, to)| [*from, *to])
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        ids.dedup();
+        Catalog {
+            nodes: ids
+                .into_iter()
+                .map(|id| Node {
+                    id: id.to_owned(),
+                    path: format!("docs/{id}.md"),
+                    title: None,
+                    tags: Vec::new(),
+                    kind: None,
+                    domain: None,
+                    status: None,
+                    source_of_truth: None,
+                    content_hash: None,
+                    last_modified: None,
+                    authors: Vec::new(),
+                    owners: Vec::new(),
+                })
+                .collect(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: (*from).to_owned(),
+                    to: (*to).to_owned(),
+                    kind: None,
+                    declared_at: None,
+                })
+                .collect(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn ranks_by_dependents_and_betweenness() {
+        // a and b reach d only through c.
+        let catalog = catalog(&[("a", "c"), ("b", "c"), ("c", "d")]);
+        let graph = Graph::from_catalog(&catalog);
+
+        let report = build(
+            &catalog,
+            &graph,
+            &HotspotOptions {
+                rank_by: HotspotMetric::Dependents,
+                betweenness: true,
+                ..HotspotOptions::default()
+            },
+        );
+        let ranked = report
+            .hotspots
+            .iter()
+            .map(|hotspot| (hotspot.id.as_str(), hotspot.dependents, hotspot.in_degree))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            [
+                ("d", Some(3), 1),
+                ("c", Some(2), 2),
+                ("a", Some(0), 0),
+                ("b", Some(0), 0)
+            ]
+        );
+        assert_eq!(report.hotspots[1].betweenness, Some(2.0));
+
+        let report = build(
+            &catalog,
+            &graph,
+            &HotspotOptions {
+                rank_by: HotspotMetric::Betweenness,
+                limit: Some(1),
+                ..HotspotOptions::default()
+            },
+        );
+        assert_eq!(report.hotspots.len(), 1);
+        assert_eq!(report.hotspots[0].id, "c");
+        assert_eq!(report.hotspots[0].dependents, None);
+    }
+
+    #[test]
+    fn ranks_by_in_degree_without_counting_dependents_by_default() {
+        let catalog = catalog(&[("a", "c"), ("b", "c"), ("c", "d")]);
+        let graph = Graph::from_catalog(&catalog);
+
+        let report = build(&catalog, &graph, &HotspotOptions::default());
+        assert_eq!(report.rank_by, HotspotMetric::InDegree);
+        let ranked = report
+            .hotspots
+            .iter()
+            .map(|hotspot| (hotspot.id.as_str(), hotspot.in_degree, hotspot.dependents))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ranked,
+            [
+                ("c", 2, None),
+                ("d", 1, None),
+                ("a", 0, None),
+                ("b", 0, None)
+            ]
+        );
+
+        let report = build(
+            &catalog,
+            &graph,
+            &HotspotOptions {
+                dependents: true,
+                ..HotspotOptions::default()
+            },
+        );
+        assert_eq!(report.hotspots[0].id, "c");
+        assert_eq!(report.hotspots[1].dependents, Some(3));
+    }
+}
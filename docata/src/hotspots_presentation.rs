@@ -0,0 +1,150 @@
+use crate::format::OutputFormat;
+use crate::hotspots::{Hotspot, HotspotReport};
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct HotspotReportJson<'a> {
+    command: &'static str,
+    rank_by: &'static str,
+    count: usize,
+    items: Vec<HotspotJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct HotspotJson<'a> {
+    rank: usize,
+    id: &'a str,
+    path: &'a str,
+    in_degree: usize,
+    out_degree: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dependents: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    betweenness: Option<f64>,
+}
+
+impl<'a> From<&'a HotspotReport> for HotspotReportJson<'a> {
+    fn from(report: &'a HotspotReport) -> Self {
+        Self {
+            command: "hotspots",
+            rank_by: report.rank_by.as_str(),
+            count: report.hotspots.len(),
+            items: report
+                .hotspots
+                .iter()
+                .enumerate()
+                .map(|(index, hotspot)| HotspotJson {
+                    rank: index + 1,
+                    id: &hotspot.id,
+                    path: &hotspot.path,
+                    in_degree: hotspot.in_degree,
+                    out_degree: hotspot.out_degree,
+                    dependents: hotspot.dependents,
+                    betweenness: hotspot.betweenness.map(round),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Round to two decimals so output stays stable across platforms.
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[derive(Debug, Error)]
+pub enum HotspotsPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a hotspot ranking according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `HotspotsPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &HotspotReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), HotspotsPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &HotspotReportJson::from(report), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &HotspotReportJson::from(report))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(HotspotsPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+/// A table with one row per document, ids left-aligned and figures
+/// right-aligned.
+fn write_text<W: Write>(
+    report: &HotspotReport,
+    out: &mut W,
+) -> Result<(), HotspotsPresentationError> {
+    let width = report
+        .hotspots
+        .iter()
+        .map(|hotspot| hotspot.id.chars().count())
+        .chain(std::iter::once("id".len()))
+        .max()
+        .unwrap_or_default();
+    let with_dependents = report
+        .hotspots
+        .iter()
+        .any(|hotspot| hotspot.dependents.is_some());
+    let with_betweenness = report
+        .hotspots
+        .iter()
+        .any(|hotspot| hotspot.betweenness.is_some());
+
+    write!(out, "{:<width$}  {:>4}  {:>4}", "id", "in", "out")?;
+    if with_dependents {
+        write!(out, "  {:>10}", "dependents")?;
+    }
+    if with_betweenness {
+        write!(out, "  {:>11}", "betweenness")?;
+    }
+    writeln!(out)?;
+    for hotspot in &report.hotspots {
+        write_row(out, hotspot, width)?;
+    }
+    Ok(())
+}
+
+fn write_row<W: Write>(
+    out: &mut W,
+    hotspot: &Hotspot,
+    width: usize,
+) -> Result<(), HotspotsPresentationError> {
+    write!(
+        out,
+        "{:<width$}  {:>4}  {:>4}",
+        hotspot.id, hotspot.in_degree, hotspot.out_degree
+    )?;
+    if let Some(dependents) = hotspot.dependents {
+        write!(out, "  {dependents:>10}")?;
+    }
+    if let Some(betweenness) = hotspot.betweenness {
+        write!(out, "  {betweenness:>11.2}")?;
+    }
+    writeln!(out)?;
+    Ok(())
+}
@@ -0,0 +1,159 @@
+use crate::catalog::Catalog;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::io::Write;
+use thiserror::Error;
+
+/// Page with the viewer's styles and script; `{{catalog}}` stands for the
+/// embedded graph.
+const TEMPLATE: &str = include_str!("viewer.html");
+
+#[derive(Debug, Serialize)]
+struct GraphJson<'a> {
+    nodes: Vec<NodeJson<'a>>,
+    edges: Vec<EdgeJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct NodeJson<'a> {
+    id: &'a str,
+    path: Option<&'a str>,
+    title: Option<&'a str>,
+    #[serde(rename = "type")]
+    kind: Option<&'a str>,
+    domain: Option<&'a str>,
+    status: Option<&'a str>,
+    source_of_truth: Option<&'a str>,
+    tags: &'a [String],
+    owners: &'a [String],
+    resolved: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct EdgeJson<'a> {
+    from: &'a str,
+    to: &'a str,
+    kind: Option<&'a str>,
+}
+
+#[derive(Debug, Error)]
+pub enum HtmlPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Write a catalog as a self-contained HTML page: an interactive
+/// force-directed graph with node search and metadata tooltips, needing no
+/// network access.
+///
+/// Edge targets missing from the catalog are drawn as hollow nodes.
+///
+/// # Errors
+///
+/// Returns `HtmlPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    catalog: &Catalog,
+    out: &mut W,
+) -> Result<(), HtmlPresentationError> {
+    let known_ids = catalog
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    let unresolved = catalog
+        .edges
+        .iter()
+        .map(|edge| edge.to.as_str())
+        .filter(|id| !known_ids.contains(id))
+        .collect::<BTreeSet<_>>();
+
+    let nodes = catalog
+        .nodes
+        .iter()
+        .map(|node| NodeJson {
+            id: &node.id,
+            path: Some(&node.path),
+            title: node.title.as_deref(),
+            kind: node.kind.as_deref(),
+            domain: node.domain.as_deref(),
+            status: node.status.as_deref(),
+            source_of_truth: node.source_of_truth.as_deref(),
+            tags: &node.tags,
+            owners: &node.owners,
+            resolved: true,
+        })
+        .chain(unresolved.into_iter().map(|id| NodeJson {
+            id,
+            path: None,
+            title: None,
+            kind: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            tags: &[],
+            owners: &[],
+            resolved: false,
+        }))
+        .collect();
+    let edges = catalog
+        .edges
+        .iter()
+        .map(|edge| EdgeJson {
+            from: &edge.from,
+            to: &edge.to,
+            kind: edge.kind.as_deref(),
+        })
+        .collect();
+
+    // Escaping `<` keeps document text from closing the script element.
+    let graph = serde_json::to_string(&GraphJson { nodes, edges })?.replace('<', "\\u003c");
+    out.write_all(TEMPLATE.replace("{{catalog}}", &graph).as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn embeds_the_graph_in_a_self_contained_page() {
+        let catalog = Catalog {
+            nodes: vec![Node {
+                id: "foo".to_owned(),
+                path: "docs/foo.md".to_owned(),
+                title: Some("</script><b>".to_owned()),
+                tags: Vec::new(),
+                kind: None,
+                domain: None,
+                status: None,
+                source_of_truth: None,
+                content_hash: None,
+                last_modified: None,
+                authors: Vec::new(),
+                owners: Vec::new(),
+            }],
+            edges: vec![Edge {
+                from: "foo".to_owned(),
+                to: "missing".to_owned(),
+                kind: None,
+                declared_at: None,
+            }],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let mut output = Vec::new();
+        write(&catalog, &mut output).expect("write html");
+        let html = String::from_utf8(output).expect("valid utf-8");
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(!html.contains("{{catalog}}"));
+        assert!(!html.contains("</script><b>"));
+        assert!(html.contains(r#""id":"missing","path":null"#));
+        assert!(!html.contains("src=\"http"));
+    }
+}
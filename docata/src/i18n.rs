@@ -0,0 +1,242 @@
+use crate::{error::Error, scan::SkipReason, validate::BrokenLinkReason};
+use std::env;
+
+/// Language of validation reports and CLI error messages.
+///
+/// English is always available; other languages need the `i18n` feature.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    #[cfg(feature = "i18n")]
+    Ja,
+}
+
+/// Fixed labels of a validation report.
+pub(crate) struct Labels {
+    pub validation_passed: &'static str,
+    pub validation_failed: &'static str,
+    pub invalid_documents: &'static str,
+    pub duplicate_ids: &'static str,
+    pub appears_in: &'static str,
+    pub unresolved_dependencies: &'static str,
+    pub from: &'static str,
+    pub dependency_cycles: &'static str,
+    pub orphan_nodes: &'static str,
+    pub forbidden_domain_dependencies: &'static str,
+    pub broken_links: &'static str,
+    pub missing_fields: &'static str,
+    pub unknown_statuses: &'static str,
+    pub lapsed_reviews: &'static str,
+    pub allowed: &'static str,
+    pub warning: &'static str,
+    pub error: &'static str,
+}
+
+const EN: Labels = Labels {
+    validation_passed: "validation passed",
+    validation_failed: "validation failed:",
+    invalid_documents: "invalid documents",
+    duplicate_ids: "duplicate ids",
+    appears_in: "appears in",
+    unresolved_dependencies: "unresolved dependencies",
+    from: "from",
+    dependency_cycles: "dependency cycles",
+    orphan_nodes: "orphan nodes",
+    forbidden_domain_dependencies: "forbidden domain dependencies",
+    broken_links: "broken links",
+    missing_fields: "missing required fields",
+    unknown_statuses: "unknown statuses",
+    lapsed_reviews: "lapsed reviews",
+    allowed: "allowed",
+    warning: "warning",
+    error: "error",
+};
+
+#[cfg(feature = "i18n")]
+const JA: Labels = Labels {
+    validation_passed: "検証に成功しました",
+    validation_failed: "検証に失敗しました:",
+    invalid_documents: "読み込めない文書",
+    duplicate_ids: "重複した ID",
+    appears_in: "使用箇所",
+    unresolved_dependencies: "解決できない依存関係",
+    from: "参照元",
+    dependency_cycles: "依存関係の循環",
+    orphan_nodes: "どこからも参照されない文書",
+    forbidden_domain_dependencies: "許可されていないドメイン間の依存",
+    broken_links: "リンク切れ",
+    missing_fields: "必須フィールドの不足",
+    unknown_statuses: "未定義のステータス",
+    lapsed_reviews: "レビュー期限切れ",
+    allowed: "使用可能な値",
+    warning: "警告",
+    error: "エラー",
+};
+
+impl Locale {
+    /// Parse a language tag or POSIX locale name such as `ja`, `ja-JP`, or
+    /// `ja_JP.UTF-8`. Returns `None` for languages this build cannot show.
+    #[must_use]
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag
+            .split(['-', '_', '.', '@'])
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+        match language.as_str() {
+            "en" | "c" | "posix" => Some(Locale::En),
+            #[cfg(feature = "i18n")]
+            "ja" => Some(Locale::Ja),
+            _ => None,
+        }
+    }
+
+    /// Pick the locale from `LC_ALL`, `LC_MESSAGES`, then `LANG`, falling
+    /// back to English when none is set to a supported language.
+    #[must_use]
+    pub fn from_env() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .into_iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .and_then(|value| Self::from_tag(&value))
+            .unwrap_or_default()
+    }
+
+    pub(crate) const fn labels(self) -> &'static Labels {
+        match self {
+            Locale::En => &EN,
+            #[cfg(feature = "i18n")]
+            Locale::Ja => &JA,
+        }
+    }
+
+    pub(crate) fn suppressed(
+        self,
+        count: usize,
+    ) -> String {
+        match self {
+            Locale::En => format!("({count} known finding(s) suppressed by baseline)"),
+            #[cfg(feature = "i18n")]
+            Locale::Ja => format!("(ベースラインにより既知の指摘 {count} 件を抑制しました)"),
+        }
+    }
+
+    pub(crate) fn days_ago(
+        self,
+        days: u64,
+    ) -> String {
+        match self {
+            Locale::En => format!("{days} days ago"),
+            #[cfg(feature = "i18n")]
+            Locale::Ja => format!("{days} 日前"),
+        }
+    }
+
+    pub(crate) fn broken_link(
+        self,
+        target: &str,
+        reason: BrokenLinkReason,
+    ) -> String {
+        match (self, reason) {
+            (Locale::En, BrokenLinkReason::Missing) => format!("`{target}` does not exist"),
+            (Locale::En, BrokenLinkReason::NotInCatalog) => {
+                format!("`{target}` is not a catalog document")
+            },
+            #[cfg(feature = "i18n")]
+            (Locale::Ja, BrokenLinkReason::Missing) => format!("`{target}` は存在しません"),
+            #[cfg(feature = "i18n")]
+            (Locale::Ja, BrokenLinkReason::NotInCatalog) => {
+                format!("`{target}` はカタログの文書ではありません")
+            },
+        }
+    }
+
+    pub(crate) fn skipped(
+        self,
+        path: &str,
+        reason: SkipReason,
+    ) -> String {
+        match (self, reason) {
+            (Locale::En, reason) => format!("skipped {path}: {reason}"),
+            #[cfg(feature = "i18n")]
+            (Locale::Ja, SkipReason::NoFrontmatter) => {
+                format!("{path} をスキップしました: frontmatter がありません")
+            },
+            #[cfg(feature = "i18n")]
+            (Locale::Ja, SkipReason::MissingId) => {
+                format!("{path} をスキップしました: frontmatter に `id` がありません")
+            },
+        }
+    }
+}
+
+/// Render `error` for the CLI, prefixed with the localized `error:` label.
+///
+/// Errors without a translation keep their English message.
+#[must_use]
+pub fn error_message(
+    error: &Error,
+    locale: Locale,
+) -> String {
+    let message = match locale {
+        Locale::En => None,
+        #[cfg(feature = "i18n")]
+        Locale::Ja => ja_error_message(error),
+    }
+    .unwrap_or_else(|| error.to_string());
+
+    format!("{}: {message}", locale.labels().error)
+}
+
+#[cfg(feature = "i18n")]
+fn ja_error_message(error: &Error) -> Option<String> {
+    Some(match error {
+        Error::Validation(_) => "検証に失敗しました".to_owned(),
+        Error::CheckFailed { findings } => format!("検査で {findings} 件の指摘が見つかりました"),
+        Error::NodeNotFound { id } => format!("文書 '{id}' はカタログにありません"),
+        Error::QueryIdNotFound { query_id } => {
+            format!("ID '{query_id}' はカタログにありません (strict モード)")
+        },
+        Error::UnresolvedConflicts { conflicts } => {
+            format!("取り込みに未解決の衝突が {conflicts} 件あります")
+        },
+        Error::MergeConflicts { conflicts } => format!(
+            "カタログのマージに {conflicts} 件の衝突があります。マージした文書からカタログを再生成してください"
+        ),
+        Error::CatalogDiff { catalog_path, .. } => {
+            format!("カタログの検査に失敗しました: 再生成した内容が '{catalog_path}' と異なります")
+        },
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Locale, error_message};
+    use crate::error::Error;
+
+    #[test]
+    fn parses_language_tags_and_locale_names() {
+        assert_eq!(Locale::from_tag("en_US.UTF-8"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("C"), Some(Locale::En));
+        assert_eq!(Locale::from_tag("xx"), None);
+        #[cfg(feature = "i18n")]
+        assert_eq!(Locale::from_tag("ja-JP"), Some(Locale::Ja));
+    }
+
+    #[test]
+    fn prefixes_error_messages() {
+        let error = Error::CheckFailed { findings: 2 };
+        assert_eq!(
+            error_message(&error, Locale::En),
+            "error: check failed with 2 finding(s)"
+        );
+        #[cfg(feature = "i18n")]
+        assert_eq!(
+            error_message(&error, Locale::Ja),
+            "エラー: 検査で 2 件の指摘が見つかりました"
+        );
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    error::Error,
+    json,
+    route::{RouteFinding, RouteFindingKind, collect_findings},
+    scan::scan,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+/// Issue tracker payload formats for exported findings.
+#[derive(Clone, Copy, Debug)]
+pub enum IssueFormat {
+    /// JSON array of GitHub "create an issue" request bodies.
+    GithubIssues,
+    /// CSV for the Jira issue importer.
+    JiraCsv,
+}
+
+/// Options for exporting findings as issues.
+#[derive(Clone, Debug, Default)]
+pub struct FindingsExportOptions {
+    /// JSON file remembering findings exported by earlier runs. Findings
+    /// recorded there are skipped; findings that disappeared are forgotten.
+    pub state_path: Option<PathBuf>,
+    /// Prefix for file links in issue bodies, e.g. a repository blob URL.
+    pub link_base: Option<String>,
+}
+
+/// A ready-to-file issue for one finding.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Issue {
+    /// Stable fingerprint used to deduplicate across runs.
+    pub key: String,
+    pub title: String,
+    pub body: String,
+    pub labels: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ExportState {
+    #[serde(default)]
+    exported: BTreeSet<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum IssueExportError {
+    #[error("failed to read state file '{path}': {source}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to write state file '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("invalid state file '{path}': {source}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+}
+
+/// Build issues for current validation and freshness findings under `root`,
+/// skipping findings already recorded in the state file and updating it.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents fails or the state file cannot be
+/// read or written.
+pub fn run(
+    root: &Path,
+    options: &FindingsExportOptions,
+) -> Result<Vec<Issue>, Error> {
+    let entries = scan(root)?;
+    let metadata_by_path = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.path.to_string_lossy().to_string(),
+                (entry.owner.as_deref(), entry.domain.as_deref()),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+
+    let mut issues = collect_findings(root, &entries, &[])
+        .iter()
+        .map(|finding| {
+            let (owner, domain) = metadata_by_path
+                .get(&finding.path)
+                .copied()
+                .unwrap_or_default();
+            build_issue(finding, owner, domain, options.link_base.as_deref())
+        })
+        .collect::<Vec<_>>();
+    issues.sort_by(|left, right| left.key.cmp(&right.key));
+    issues.dedup_by(|left, right| left.key == right.key);
+
+    let Some(state_path) = &options.state_path else {
+        return Ok(issues);
+    };
+
+    let previous = load_state(state_path)?;
+    let current = ExportState {
+        exported: issues.iter().map(|issue| issue.key.clone()).collect(),
+    };
+    issues.retain(|issue| !previous.exported.contains(&issue.key));
+    save_state(state_path, &current)?;
+
+    Ok(issues)
+}
+
+fn build_issue(
+    finding: &RouteFinding,
+    owner: Option<&str>,
+    domain: Option<&str>,
+    link_base: Option<&str>,
+) -> Issue {
+    let kind = finding.kind.as_str();
+    let path = finding.path.trim_start_matches("./");
+    let key = match finding.kind {
+        // Stale findings name modification dates; keep one issue per document.
+        RouteFindingKind::Stale => format!("{kind}:{path}"),
+        _ => format!("{kind}:{path}:{}", finding.message),
+    };
+
+    let link = format!("{}{path}", link_base.unwrap_or_default());
+    let mut lines = vec![
+        format!("docata reported `{kind}` for `{}`.", finding.id),
+        String::new(),
+        finding.message.clone(),
+        String::new(),
+        format!("File: [{path}]({link})"),
+    ];
+    lines.extend(owner.map(|owner| format!("Owner: {owner}")));
+    lines.extend(domain.map(|domain| format!("Domain: {domain}")));
+    lines.push(String::new());
+    lines.push(format!("<!-- docata-finding: {key} -->"));
+    let body = lines.join("\n") + "\n";
+
+    let mut labels = vec!["docata".to_owned(), kind.to_owned()];
+    labels.extend(domain.map(|domain| format!("domain:{domain}")));
+    labels.extend(owner.map(|owner| format!("owner:{owner}")));
+
+    Issue {
+        title: format!("[docata] {kind} in `{}`: {}", finding.id, finding.message),
+        key,
+        body,
+        labels,
+    }
+}
+
+fn load_state(path: &Path) -> Result<ExportState, IssueExportError> {
+    if !path.exists() {
+        return Ok(ExportState::default());
+    }
+
+    let content = fs::read_to_string(path).map_err(|source| IssueExportError::Read {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    serde_json::from_str(&content).map_err(|source| IssueExportError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+fn save_state(
+    path: &Path,
+    state: &ExportState,
+) -> Result<(), IssueExportError> {
+    let content = json::to_string(state, false).map_err(|source| IssueExportError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    fs::write(path, content).map_err(|source| IssueExportError::Write {
+        path: path.to_path_buf(),
+        source,
+    })
+}
@@ -0,0 +1,119 @@
+use crate::format::OutputFormat;
+use crate::issue_export::{Issue, IssueFormat};
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct GithubIssueJson<'a> {
+    title: &'a str,
+    body: &'a str,
+    labels: &'a [String],
+}
+
+#[derive(Debug, Error)]
+pub enum IssuePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Write issues as payloads for the selected issue tracker.
+///
+/// # Errors
+///
+/// Returns `IssuePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    issues: &[Issue],
+    format: IssueFormat,
+    out: &mut W,
+) -> Result<(), IssuePresentationError> {
+    match format {
+        IssueFormat::GithubIssues => write_github_issues(issues, out),
+        IssueFormat::JiraCsv => write_jira_csv(issues, out),
+    }
+}
+
+fn write_github_issues<W: Write>(
+    issues: &[Issue],
+    out: &mut W,
+) -> Result<(), IssuePresentationError> {
+    let payloads = issues
+        .iter()
+        .map(|issue| GithubIssueJson {
+            title: &issue.title,
+            body: &issue.body,
+            labels: &issue.labels,
+        })
+        .collect::<Vec<_>>();
+    json::write(out, &payloads, OutputFormat::Json)?;
+    Ok(())
+}
+
+/// Jira reads repeated `Labels` columns as multiple labels, and labels may
+/// not contain spaces.
+fn write_jira_csv<W: Write>(
+    issues: &[Issue],
+    out: &mut W,
+) -> Result<(), IssuePresentationError> {
+    let label_columns = issues
+        .iter()
+        .map(|issue| issue.labels.len())
+        .max()
+        .unwrap_or(1);
+
+    let mut header = vec!["Summary", "Description"];
+    header.extend(std::iter::repeat_n("Labels", label_columns));
+    writeln!(out, "{}", header.join(","))?;
+
+    for issue in issues {
+        let mut row = vec![csv_field(&issue.title), csv_field(&issue.body)];
+        for index in 0..label_columns {
+            let label = issue
+                .labels
+                .get(index)
+                .map(|label| label.replace(char::is_whitespace, "-"))
+                .unwrap_or_default();
+            row.push(csv_field(&label));
+        }
+        writeln!(out, "{}", row.join(","))?;
+    }
+
+    Ok(())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::issue_export::{Issue, IssueFormat};
+
+    #[test]
+    fn writes_jira_csv_with_quoted_fields_and_label_columns() {
+        let issues = vec![Issue {
+            key: "stale:docs/guide.md".to_owned(),
+            title: "[docata] stale in `guide`".to_owned(),
+            body: "line one, \"quoted\"\nline two".to_owned(),
+            labels: vec!["docata".to_owned(), "owner:team docs".to_owned()],
+        }];
+
+        let mut output = Vec::new();
+        write(&issues, IssueFormat::JiraCsv, &mut output).expect("write csv");
+        let csv = String::from_utf8(output).expect("valid utf-8");
+
+        assert_eq!(
+            csv,
+            "Summary,Description,Labels,Labels\n\
+             [docata] stale in `guide`,\"line one, \"\"quoted\"\"\nline two\",docata,owner:team-docs\n"
+        );
+    }
+}
@@ -0,0 +1,76 @@
+//! The JSON style shared by every writer: object keys in sorted order,
+//! two-space indent (or a single line when compact), and a trailing newline,
+//! so committed artifacts only change where their content does.
+
+use crate::format::OutputFormat;
+use serde::Serialize;
+use std::io::Write;
+
+/// Serialize `value` in the shared style.
+///
+/// Going through `serde_json::Value` sorts object keys regardless of the
+/// field order of the serialized structs.
+pub(crate) fn to_string<T: Serialize + ?Sized>(
+    value: &T,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    let value = serde_json::to_value(value)?;
+    let mut content = if compact {
+        serde_json::to_string(&value)?
+    } else {
+        serde_json::to_string_pretty(&value)?
+    };
+    content.push('\n');
+    Ok(content)
+}
+
+/// Write `value` in the shared style, on a single line for
+/// [`OutputFormat::CompactJson`] and [`OutputFormat::Ndjson`] and indented
+/// otherwise.
+pub(crate) fn write<W: Write + ?Sized, T: Serialize + ?Sized>(
+    out: &mut W,
+    value: &T,
+    format: OutputFormat,
+) -> Result<(), serde_json::Error> {
+    let compact = matches!(format, OutputFormat::CompactJson | OutputFormat::Ndjson);
+    out.write_all(to_string(value, compact)?.as_bytes())
+        .map_err(serde_json::Error::io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_string;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct Unordered {
+        zeta: u8,
+        alpha: Nested,
+    }
+
+    #[derive(Serialize)]
+    struct Nested {
+        beta: bool,
+        able: bool,
+    }
+
+    #[test]
+    fn sorts_keys_and_ends_with_newline() {
+        let value = Unordered {
+            zeta: 1,
+            alpha: Nested {
+                beta: true,
+                able: false,
+            },
+        };
+
+        assert_eq!(
+            to_string(&value, false).expect("serialize"),
+            "{\n  \"alpha\": {\n    \"able\": false,\n    \"beta\": true\n  },\n  \"zeta\": 1\n}\n"
+        );
+        assert_eq!(
+            to_string(&value, true).expect("serialize"),
+            "{\"alpha\":{\"able\":false,\"beta\":true},\"zeta\":1}\n"
+        );
+    }
+}
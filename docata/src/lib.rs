@@ -1,276 +1,4116 @@
+mod adopt;
+#[cfg(feature = "tokio")]
+mod async_api;
+mod backstage_presentation;
+mod baseline;
+mod blame;
+mod blame_presentation;
 mod build;
+mod build_presentation;
 mod catalog;
+mod catalog_index;
 mod catalog_presentation;
+mod changed;
+mod changed_presentation;
+mod codeowners;
+mod common;
+mod common_presentation;
+mod components;
+mod components_presentation;
+mod config;
+mod config_presentation;
+mod critical;
+mod critical_presentation;
+mod cycles;
+mod cycles_presentation;
+mod delta;
+mod delta_presentation;
+mod deps_edit;
+mod directory_store;
 mod domain;
+mod dot;
+mod dot_presentation;
 mod error;
+mod export;
+mod federation;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 mod format;
+mod freshness;
+mod freshness_presentation;
+mod git;
 mod graph;
+mod graphml_presentation;
+mod hash;
+mod hook;
+mod hook_presentation;
+mod hotspots;
+mod hotspots_presentation;
+mod html_presentation;
+mod i18n;
+mod issue_export;
+mod issue_presentation;
+mod json;
+mod links;
+mod list;
+mod list_presentation;
+mod merge;
+mod namespace;
+mod navigation;
+mod navigation_presentation;
+mod progress;
+mod reachability;
+mod recursive_check;
+mod recursive_check_presentation;
 mod relation;
 mod relation_presentation;
+#[cfg(feature = "http")]
+mod remote;
+mod review_queue;
+mod review_queue_presentation;
+mod route;
+mod route_presentation;
+#[cfg(feature = "s3")]
+mod s3_store;
+mod scaffold;
 mod scan;
+mod shortest_path;
+mod shortest_path_presentation;
+mod show;
+mod show_presentation;
+mod simulate;
+mod simulate_presentation;
+mod source;
+#[cfg(feature = "sqlite")]
+mod sqlite_store;
+mod stale;
+mod stale_presentation;
+mod stats;
+mod stats_presentation;
+mod store;
+mod subgraph;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+mod tree;
+mod tree_presentation;
 mod validate;
+mod validation_presentation;
+mod xml;
 
-pub use error::Error;
-pub use format::OutputFormat;
-pub use relation::RelationKind;
-use std::io::Write;
-use std::path::Path;
+pub use adopt::{
+    AdoptionConflict, AdoptionPlan, AdoptionProposal, AdoptionResolutions, CollidingDocument,
+    LinkCandidate,
+};
+#[cfg(feature = "tokio")]
+pub use async_api::{AsyncCatalogStore, AsyncDocumentSource};
+pub use baseline::{Baseline, BaselineError};
+pub use blame::BlameReport;
+pub use build::BuildReport;
+pub use catalog::{
+    Catalog, CatalogError, CatalogWarning, DuplicateIdPolicy, Edge, MetadataField, MetadataFilter,
+    MetadataFilterError, Node, SourceLocation,
+};
+pub use changed::{ChangedDocument, ChangedReport};
+pub use common::{CommonDependencies, CommonOptions, SharedDependency};
+pub use components::ComponentReport;
+pub use config::{
+    BuildConfig, CONFIG_FILE_NAME, Config, ConfigError, ConfigReport, ConfigWarning,
+    NamespaceConfig, RelationDefinition, RelationDirection, RuleOverride, TypeContract,
+    find_config_file,
+};
+pub use critical::{Bridge, CriticalNode, CriticalOptions, CriticalReport, DominatorTree};
+pub use cycles::{Cycle, CycleEdge, CycleReport};
+pub use delta::{CatalogDelta, DeltaEdge, DeltaNode, FieldChange, NodeChange};
+pub use deps_edit::{DependencyChange, DependencyEdit, DependencyFix, DepsEditError, FixReason};
+pub use directory_store::DirectoryStore;
+pub use error::{Error, error_json};
+pub use export::{ExportFormat, SummarizeOptions};
+pub use format::{CatalogFormat, Compression, OutputFormat};
+pub use freshness::{ChangedDependency, FreshnessReport, InvalidReviewDate, StaleDocument};
+pub use graph::Graph;
+pub use hook::PreCommitReport;
+pub use hotspots::{Hotspot, HotspotMetric, HotspotOptions, HotspotReport};
+pub use i18n::{Locale, error_message};
+pub use issue_export::{FindingsExportOptions, Issue, IssueFormat};
+pub use list::GraphEndpoint;
+pub use merge::{CatalogMerge, MergeConflict};
+pub use navigation::{NavDocument, NavSection};
+pub use progress::{NoProgress, ProgressEvent, ProgressSink, ValidationPhase};
+pub use recursive_check::{CatalogStatus, RecursiveCheckReport, RootCheck};
+pub use relation::{RelationCombine, RelationKind};
+#[cfg(feature = "http")]
+pub use remote::{RemoteCatalog, RemoteCatalogSource, RemoteFetchOptions};
+pub use review_queue::{ReviewItem, ReviewQueue, ReviewQueueFormat, ReviewQueueOptions};
+pub use route::{OwnerRoute, RouteFinding, RouteFindingKind, RoutingReport};
+#[cfg(feature = "s3")]
+pub use s3_store::S3Store;
+pub use scaffold::{NewDocument, ScaffoldError};
+pub use scan::{
+    Entry, ScanDiagnostic, ScanError, ScanOptions, SkipReason, SkippedFile, scan_documents,
+};
+pub use simulate::{SimulatedChange, SimulationReport};
+#[cfg(feature = "http-source")]
+pub use source::HttpSource;
+#[cfg(feature = "memory-source")]
+pub use source::MemorySource;
+pub use source::{DocumentSource, FileSystemSource};
+#[cfg(feature = "sqlite")]
+pub use sqlite_store::SqliteStore;
+pub use stale::{AgedDocument, DateSource, StaleOptions, StaleReport};
+pub use stats::CatalogStats;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+#[cfg(feature = "http")]
+pub use store::HttpStore;
+pub use store::{
+    CatalogLock, CatalogStore, HTTP_CACHE_DIR_ENV, HTTP_TOKEN_ENV, LockPolicy, StoreError,
+};
+pub use subgraph::{SubgraphFormat, SubgraphSelection};
+pub use tree::{DependencyTree, TreeMarker, TreeNode};
+pub use validate::{
+    BrokenLink, BrokenLinkReason, DependencyCycle, DomainViolation, DuplicateId, LapsedReview,
+    MissingFields, OrphanNode, UnknownStatus, UnresolvedDependency, ValidationOutcome,
+    ValidationReport, ValidationRules, build_validation_report, build_validation_report_with_rules,
+};
 
+/// How a catalog is built.
+///
+/// Start from [`BuildOptions::new`] and chain a setter per option, e.g.
+/// `BuildOptions::new().include_node_metadata(true)`, so options added later
+/// do not break existing callers.
 #[derive(Clone, Copy, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+#[non_exhaustive]
 pub struct BuildOptions {
     pub include_node_metadata: bool,
+    pub duplicate_id_policy: DuplicateIdPolicy,
+    pub catalog_format: CatalogFormat,
+    /// What [`build_catalog_into`] does when another writer holds the
+    /// catalog's lock.
+    pub lock_policy: LockPolicy,
+    /// Collect Markdown files without frontmatter or without an `id` into
+    /// [`BuildReport::skipped`] instead of dropping or rejecting them.
+    pub report_skipped: bool,
+    /// Leave out files that fail to scan, listing them in
+    /// [`BuildReport::diagnostics`], instead of failing the build.
+    pub lenient: bool,
+    /// Write a JSON catalog on a single line instead of indented.
+    pub compact: bool,
+    /// Compress the catalog; pass `store.compression()` to follow the
+    /// store's location.
+    pub compression: Compression,
+    /// Record on each `deps` edge the file and line declaring it.
+    pub include_provenance: bool,
+    /// Record a SHA-256 of each document's body on its node.
+    pub include_content_hash: bool,
+    /// Record each document's last commit date and top authors from git on
+    /// its node.
+    pub include_git_metadata: bool,
+    /// Record on each node the owners the repository's CODEOWNERS file
+    /// assigns its document to.
+    pub include_code_owners: bool,
+    /// Write a query index next to a local, uncompressed JSON catalog, as
+    /// `<catalog>.idx`, so `deps` and `refs` queries read only the documents
+    /// they reach. Only builds asking for it write the index; once the
+    /// catalog is written otherwise, queries ignore the stale index.
+    pub write_index: bool,
 }
 
-#[derive(Clone, Copy, Debug, Default)]
+impl BuildOptions {
+    /// The defaults: a JSON catalog without node metadata, failing on
+    /// duplicate ids.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[must_use]
+    pub const fn include_node_metadata(
+        mut self,
+        include_node_metadata: bool,
+    ) -> Self {
+        self.include_node_metadata = include_node_metadata;
+        self
+    }
+
+    #[must_use]
+    pub const fn duplicate_id_policy(
+        mut self,
+        duplicate_id_policy: DuplicateIdPolicy,
+    ) -> Self {
+        self.duplicate_id_policy = duplicate_id_policy;
+        self
+    }
+
+    #[must_use]
+    pub const fn catalog_format(
+        mut self,
+        catalog_format: CatalogFormat,
+    ) -> Self {
+        self.catalog_format = catalog_format;
+        self
+    }
+
+    #[must_use]
+    pub const fn lock_policy(
+        mut self,
+        lock_policy: LockPolicy,
+    ) -> Self {
+        self.lock_policy = lock_policy;
+        self
+    }
+
+    #[must_use]
+    pub const fn report_skipped(
+        mut self,
+        report_skipped: bool,
+    ) -> Self {
+        self.report_skipped = report_skipped;
+        self
+    }
+
+    #[must_use]
+    pub const fn lenient(
+        mut self,
+        lenient: bool,
+    ) -> Self {
+        self.lenient = lenient;
+        self
+    }
+
+    #[must_use]
+    pub const fn compact(
+        mut self,
+        compact: bool,
+    ) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    #[must_use]
+    pub const fn compression(
+        mut self,
+        compression: Compression,
+    ) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    #[must_use]
+    pub const fn include_provenance(
+        mut self,
+        include_provenance: bool,
+    ) -> Self {
+        self.include_provenance = include_provenance;
+        self
+    }
+
+    #[must_use]
+    pub const fn include_content_hash(
+        mut self,
+        include_content_hash: bool,
+    ) -> Self {
+        self.include_content_hash = include_content_hash;
+        self
+    }
+
+    #[must_use]
+    pub const fn include_git_metadata(
+        mut self,
+        include_git_metadata: bool,
+    ) -> Self {
+        self.include_git_metadata = include_git_metadata;
+        self
+    }
+
+    #[must_use]
+    pub const fn include_code_owners(
+        mut self,
+        include_code_owners: bool,
+    ) -> Self {
+        self.include_code_owners = include_code_owners;
+        self
+    }
+
+    #[must_use]
+    pub const fn write_index(
+        mut self,
+        write_index: bool,
+    ) -> Self {
+        self.write_index = write_index;
+        self
+    }
+
+    pub(crate) const fn scan_options(self) -> ScanOptions {
+        ScanOptions {
+            report_skipped: self.report_skipped,
+            lenient: self.lenient,
+            content_hash: self.include_content_hash,
+            git_metadata: self.include_git_metadata,
+            code_owners: self.include_code_owners,
+        }
+    }
+}
+
+/// How relation queries are answered.
+///
+/// Start from [`QueryOptions::new`] and chain a setter per option, like
+/// [`BuildOptions`].
+#[derive(Clone, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+#[non_exhaustive]
 pub struct QueryOptions {
     pub strict: bool,
+    /// Keep only related documents carrying every tag. Needs a catalog
+    /// built with [`BuildOptions::include_node_metadata`].
+    pub tags: Vec<String>,
+    /// Keep only related documents whose metadata matches the filters.
+    /// Needs a catalog built with [`BuildOptions::include_node_metadata`].
+    pub filters: Vec<MetadataFilter>,
+    /// How results are merged when several ids are queried at once.
+    pub combine: RelationCombine,
+    /// Embed each related document's tags, type, domain, status, and source
+    /// of truth in JSON and YAML output, like
+    /// [`BuildOptions::include_node_metadata`] does for catalogs.
+    pub include_node_metadata: bool,
+    /// Relation kind to follow instead of `deps`, as declared under
+    /// `[relations]` in `docata.toml`.
+    pub edge_kind: Option<String>,
+    /// Write the items of a single-id query as they are resolved, as JSON
+    /// Lines followed by a `summary` line for JSON and NDJSON output, instead
+    /// of building the whole response first.
+    pub stream: bool,
+    /// Follow relations transitively: every document reachable from the
+    /// query id rather than its direct neighbours, found by walking the
+    /// graph from each id queried.
+    pub transitive: bool,
+    /// Name the file and line declaring each direct relation, for catalogs
+    /// built with [`BuildOptions::include_provenance`].
+    pub include_provenance: bool,
+}
+
+impl QueryOptions {
+    /// The defaults: direct `deps` relations, unfiltered.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether tag or metadata filters need the catalog's node metadata.
+    fn filters_node_metadata(&self) -> bool {
+        !self.tags.is_empty() || !self.filters.is_empty()
+    }
+
+    #[must_use]
+    pub fn strict(
+        mut self,
+        strict: bool,
+    ) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    #[must_use]
+    pub fn tags(
+        mut self,
+        tags: Vec<String>,
+    ) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    #[must_use]
+    pub fn filters(
+        mut self,
+        filters: Vec<MetadataFilter>,
+    ) -> Self {
+        self.filters = filters;
+        self
+    }
+
+    #[must_use]
+    pub fn combine(
+        mut self,
+        combine: RelationCombine,
+    ) -> Self {
+        self.combine = combine;
+        self
+    }
+
+    #[must_use]
+    pub fn include_node_metadata(
+        mut self,
+        include_node_metadata: bool,
+    ) -> Self {
+        self.include_node_metadata = include_node_metadata;
+        self
+    }
+
+    #[must_use]
+    pub fn edge_kind(
+        mut self,
+        edge_kind: Option<String>,
+    ) -> Self {
+        self.edge_kind = edge_kind;
+        self
+    }
+
+    #[must_use]
+    pub fn stream(
+        mut self,
+        stream: bool,
+    ) -> Self {
+        self.stream = stream;
+        self
+    }
+
+    #[must_use]
+    pub fn transitive(
+        mut self,
+        transitive: bool,
+    ) -> Self {
+        self.transitive = transitive;
+        self
+    }
+
+    #[must_use]
+    pub fn include_provenance(
+        mut self,
+        include_provenance: bool,
+    ) -> Self {
+        self.include_provenance = include_provenance;
+        self
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ExportOptions {
+    /// Collapse low-degree documents into per-domain group nodes and cap the
+    /// edge count before exporting.
+    pub summarize: Option<SummarizeOptions>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ListOptions {
+    /// Keep only documents carrying every tag. Needs a catalog built with
+    /// [`BuildOptions::include_node_metadata`].
+    pub tags: Vec<String>,
+    /// Keep only documents whose last commit is before this date
+    /// (`YYYY-MM-DD`). Needs a catalog built with
+    /// [`BuildOptions::include_git_metadata`]; documents without a recorded
+    /// date are left out.
+    pub modified_before: Option<String>,
+    /// Keep only documents CODEOWNERS assigns to this user or team. Needs a
+    /// catalog built with [`BuildOptions::include_code_owners`].
+    pub owner: Option<String>,
+}
+
+impl ListOptions {
+    pub(crate) fn matches(
+        &self,
+        node: &catalog::Node,
+    ) -> bool {
+        node.has_tags(&self.tags)
+            && self.modified_before.as_ref().is_none_or(|before| {
+                node.last_modified
+                    .as_ref()
+                    .is_some_and(|modified| modified < before)
+            })
+            && self
+                .owner
+                .as_ref()
+                .is_none_or(|owner| node.owners.contains(owner))
+    }
 }
 
 /// Build catalog from documents under `root` and write it to `out`.
 ///
 /// # Errors
 ///
-/// Returns `Error` when scanning fails or serialization fails.
+/// Returns `Error` when scanning fails, duplicate ids are rejected, or
+/// serialization fails.
 pub fn build_catalog<W: Write>(
     root: &Path,
     out: &mut W,
-) -> Result<(), Error> {
+) -> Result<BuildReport, Error> {
     build_catalog_with_options(root, out, BuildOptions::default())
 }
 
 /// Build catalog from documents under `root` with options and write it to `out`.
 ///
+/// Warnings about duplicate ids resolved by `options.duplicate_id_policy` are
+/// returned in the `BuildReport`.
+///
 /// # Errors
 ///
-/// Returns `Error` when scanning fails or serialization fails.
+/// Returns `Error` when scanning fails, duplicate ids are rejected, or
+/// serialization fails.
 pub fn build_catalog_with_options<W: Write>(
     root: &Path,
     out: &mut W,
     options: BuildOptions,
-) -> Result<(), Error> {
+) -> Result<BuildReport, Error> {
     build::run(root, out, options)
 }
 
+/// Build catalog from documents under `root` and save it to `store`.
+///
+/// The catalog is rendered in `options.catalog_format`; pass
+/// `store.format()` to follow the store's location. Concurrent builds into
+/// the same store are serialized by its write lock, following
+/// `options.lock_policy`.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails, duplicate ids are rejected,
+/// serialization fails, the lock is held elsewhere under
+/// [`LockPolicy::NoWait`], or the store rejects the write.
+pub fn build_catalog_into<S: CatalogStore + ?Sized>(
+    root: &Path,
+    store: &S,
+    options: BuildOptions,
+) -> Result<BuildReport, Error> {
+    build_merged_catalog_into(&[root], store, options)
+}
+
+/// Build one catalog from the documents under several `roots`, e.g. `docs/`,
+/// `rfcs/`, and `adr/`, and save it to `store` like [`build_catalog_into`].
+///
+/// Ids must be unique across all roots; duplicates are handled by
+/// `options.duplicate_id_policy`. Relation kinds and the recorded config
+/// hash come from the `docata.toml` nearest the first root.
+///
+/// # Errors
+///
+/// Returns `Error::NoRoots` when `roots` is empty, or `Error` when scanning
+/// fails, duplicate ids are rejected, serialization fails, the lock is held
+/// elsewhere under [`LockPolicy::NoWait`], or the store rejects the write.
+pub fn build_merged_catalog_into<S: CatalogStore + ?Sized>(
+    roots: &[&Path],
+    store: &S,
+    options: BuildOptions,
+) -> Result<BuildReport, Error> {
+    build_merged_catalog_into_with_progress(roots, store, options, &NoProgress)
+}
+
+/// Build like [`build_merged_catalog_into`], reporting the files discovered,
+/// parsed, and skipped to `progress`.
+///
+/// # Errors
+///
+/// Returns `Error` like [`build_merged_catalog_into`].
+pub fn build_merged_catalog_into_with_progress<S: CatalogStore + ?Sized>(
+    roots: &[&Path],
+    store: &S,
+    options: BuildOptions,
+    progress: &dyn ProgressSink,
+) -> Result<BuildReport, Error> {
+    let _lock = store.lock(options.lock_policy)?;
+    let built = build::build_roots(roots, options, progress)?;
+    save_catalog(store, &built, options)?;
+
+    Ok(built.report)
+}
+
+/// Build catalog from the documents of `source`, e.g. a CMS export held in
+/// memory, and save it to `store` like [`build_catalog_into`].
+///
+/// Namespaces, relation kinds, git history, and CODEOWNERS come from the
+/// source's root on disk; a source without one builds without them.
+///
+/// # Errors
+///
+/// Returns `Error` when listing or scanning the documents fails, duplicate
+/// ids are rejected, serialization fails, the lock is held elsewhere under
+/// [`LockPolicy::NoWait`], or the store rejects the write.
+pub fn build_catalog_from_source<S: CatalogStore + ?Sized>(
+    source: &dyn DocumentSource,
+    store: &S,
+    options: BuildOptions,
+) -> Result<BuildReport, Error> {
+    let _lock = store.lock(options.lock_policy)?;
+    let built = build::build_source(source, options)?;
+    save_catalog(store, &built, options)?;
+
+    Ok(built.report)
+}
+
+/// Stream `built` into `store`, and index it when `options.write_index`
+/// asks to. Saving without it leaves an index kept beside the catalog
+/// stale, so queries ignore it.
+fn save_catalog<S: CatalogStore + ?Sized>(
+    store: &S,
+    built: &build::Built,
+    options: BuildOptions,
+) -> Result<(), Error> {
+    let indexed = options
+        .write_index
+        .then(|| catalog_index::indexable(store, options.catalog_format))
+        .transpose()?;
+    // The store only sees I/O errors; serialization errors are kept aside
+    // and reported as themselves.
+    let mut encoding = Ok(());
+    let saved = store.save_with(&mut |out| match built.write(out) {
+        Err(catalog_presentation::CatalogPresentationError::Io(error)) => Err(error),
+        Err(error) => {
+            encoding = Err(error);
+            Err(io::Error::other("catalog serialization failed"))
+        },
+        Ok(()) => Ok(()),
+    });
+    encoding?;
+    saved?;
+    if let Some(catalog) = indexed {
+        catalog_index::write(catalog)?;
+    }
+
+    Ok(())
+}
+
+/// Build a catalog from the documents of an async `source` and save it to an
+/// async `store`, like [`build_catalog_from_source`].
+///
+/// Every document is read before any is parsed; parsing and serializing run
+/// on tokio's blocking pool. The store's lock is held only while saving.
+///
+/// # Errors
+///
+/// Returns `Error` when listing or scanning the documents fails,
+/// serialization fails, the lock is held elsewhere under
+/// [`LockPolicy::NoWait`], or the store rejects the write.
+#[cfg(feature = "tokio")]
+pub async fn build_catalog_from_async_source<A, S>(
+    source: &A,
+    store: &S,
+    options: BuildOptions,
+) -> Result<BuildReport, Error>
+where
+    A: AsyncDocumentSource + ?Sized,
+    S: AsyncCatalogStore + ?Sized,
+{
+    let documents = async_api::Fetched::read(source).await?;
+    let (report, content) = async_api::unblock(move || {
+        let built = build::build_source(&documents, options)?;
+        let mut content = Vec::new();
+        built.write(&mut content)?;
+        Ok::<_, Error>((built.report, content))
+    })
+    .await?;
+    let _lock = store.lock(options.lock_policy).await?;
+    store.save(&content).await?;
+
+    Ok(report)
+}
+
+/// Write a build report's warnings, skipped files, and files that failed to
+/// scan to `out` in `format`.
+///
+/// # Errors
+///
+/// Returns `Error` when the format is not supported for build reports or
+/// writing fails.
+pub fn write_build_report<W: Write>(
+    report: &BuildReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    build_presentation::write(report, format, out)?;
+    Ok(())
+}
+
+/// Open the catalog store for `location`: a local path, an `http(s)://` URL,
+/// or, with the matching features, `sqlite://<database>#<name>` and
+/// `s3://<bucket>/<key>`.
+///
+/// # Errors
+///
+/// Returns `Error` when the location is malformed or needs a disabled
+/// feature.
+pub fn open_catalog_store(location: &str) -> Result<Box<dyn CatalogStore>, Error> {
+    Ok(store::open(location)?)
+}
+
 /// Check document graph structure under `root`.
 ///
 /// # Errors
 ///
 /// Returns `Error` when scanning fails or validation checks fail.
 pub fn check_catalog_structure(root: &Path) -> Result<(), Error> {
-    let _entries = scan_and_validate(root)?;
+    let _entries = scan_and_validate(root, ScanOptions::default())?;
     Ok(())
 }
 
-/// Check catalog consistency by validating docs and ensuring regenerated output
-/// matches `catalog_path`.
+/// Validate documents under `root` and return the full report.
+///
+/// Findings do not produce an error; inspect `ValidationOutcome::report` or
+/// `ValidationOutcome::is_success` instead.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails.
+pub fn validate(root: &Path) -> Result<ValidationOutcome, Error> {
+    validate_with_rules(root, &ValidationRules::default())
+}
+
+/// Validate documents under `root` with opt-in `rules` enabled and return the
+/// full report.
 ///
 /// # Errors
 ///
-/// Returns `Error` when scanning fails, validation checks fail, or catalog
-/// differs from regenerated output.
-pub fn check_catalog(
+/// Returns `Error` when scanning fails.
+pub fn validate_with_rules(
     root: &Path,
-    catalog_path: &Path,
-    options: BuildOptions,
-) -> Result<(), Error> {
-    let entries = scan_and_validate(root)?;
-    let catalog = catalog::Catalog::from_entries(&entries);
+    rules: &ValidationRules,
+) -> Result<ValidationOutcome, Error> {
+    validate_with_progress(root, rules, &NoProgress)
+}
 
-    let mut regenerated = Vec::new();
-    catalog_presentation::write_catalog(&catalog, &mut regenerated, options.include_node_metadata)?;
-    let current = std::fs::read(catalog_path)?;
+/// Validate like [`validate_with_rules`], reporting the files discovered,
+/// parsed, and skipped and each validation phase to `progress`.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails.
+pub fn validate_with_progress(
+    root: &Path,
+    rules: &ValidationRules,
+    progress: &dyn ProgressSink,
+) -> Result<ValidationOutcome, Error> {
+    let options = BuildOptions::new()
+        .report_skipped(rules.report_skipped)
+        .lenient(rules.lenient);
+    Ok(ScannedDocuments::scan(root, options, progress)?.validate(rules, progress))
+}
 
-    if current != regenerated {
-        return Err(Error::CatalogDiff {
-            catalog_path: catalog_path.to_string_lossy().to_string(),
-        });
+/// Documents under a root scanned once as a build with given options would
+/// scan them, to validate and then compare or update the catalog, as
+/// `check --catalog` does, without scanning twice.
+#[derive(Debug)]
+pub struct ScannedDocuments {
+    root: PathBuf,
+    options: BuildOptions,
+    outcome: scan::ScanOutcome,
+}
+
+impl ScannedDocuments {
+    /// Scan the documents under `root` as building with `options` would,
+    /// reporting the files discovered and parsed to `progress`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error` when scanning fails.
+    pub fn scan(
+        root: &Path,
+        options: BuildOptions,
+        progress: &dyn ProgressSink,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            root: root.to_path_buf(),
+            options,
+            outcome: scan::scan_with_progress(root, options.scan_options(), progress)?,
+        })
     }
 
-    Ok(())
+    /// Validate the documents with opt-in `rules` enabled, reporting each
+    /// validation phase to `progress`, like [`validate_with_progress`].
+    #[must_use]
+    pub fn validate(
+        &self,
+        rules: &ValidationRules,
+        progress: &dyn ProgressSink,
+    ) -> ValidationOutcome {
+        let entries = &self.outcome.entries;
+        let mut report =
+            validate::build_validation_report_for(&self.root, entries, rules, |_| true, progress);
+        report.skipped.clone_from(&self.outcome.skipped);
+        report
+            .invalid_documents
+            .clone_from(&self.outcome.diagnostics);
+        ValidationOutcome {
+            report,
+            document_count: entries.len(),
+        }
+    }
 }
 
-fn scan_and_validate(root: &Path) -> Result<Vec<scan::Entry>, Error> {
-    let entries = scan::scan(root)?;
-    validate::validate_entries(&entries)?;
-    Ok(entries)
+/// Write a validation report to `out` in `format`.
+///
+/// # Errors
+///
+/// Returns `Error` when the format is not supported for reports or writing
+/// fails.
+pub fn write_validation_report<W: Write>(
+    report: &ValidationReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    write_localized_validation_report(report, format, Locale::En, out)
 }
 
-fn load_index(catalog_path: &Path) -> Result<(catalog::Catalog, graph::Graph), Error> {
-    let mut file = std::fs::File::open(catalog_path)?;
-    let catalog = catalog_presentation::read_catalog(&mut file)?;
-    let graph = graph::Graph::from_catalog(&catalog);
-
-    Ok((catalog, graph))
+/// Write a validation report to `out` in `format`, with text output in
+/// `locale`.
+///
+/// # Errors
+///
+/// Returns `Error` when the format is not supported for reports or writing
+/// fails.
+pub fn write_localized_validation_report<W: Write>(
+    report: &ValidationReport,
+    format: OutputFormat,
+    locale: Locale,
+    out: &mut W,
+) -> Result<(), Error> {
+    validation_presentation::write(report, format, locale, out)?;
+    Ok(())
 }
 
-/// Query catalog relations and write output to `out`.
+/// Check document graph structure under `root` and write the validation
+/// report to `out`.
+///
+/// The report is written for passing and failing checks alike.
 ///
 /// # Errors
 ///
-/// Returns `Error` when reading catalog files or writing output fails.
-pub fn query_catalog_relation<W: Write>(
-    query_id: &str,
-    catalog_path: &Path,
-    relation_kind: RelationKind,
+/// Returns `Error::CheckFailed` after writing the report when validation
+/// finds problems, or `Error` when scanning or writing output fails.
+pub fn check_catalog_structure_with_format<W: Write>(
+    root: &Path,
     format: OutputFormat,
     out: &mut W,
 ) -> Result<(), Error> {
-    query_catalog_relation_with_options(
-        query_id,
-        catalog_path,
-        relation_kind,
-        format,
-        QueryOptions::default(),
-        out,
-    )
+    check_catalog_structure_with_rules(root, &ValidationRules::default(), format, out)
 }
 
-/// Query catalog relations and write output to `out` with options.
+/// Check document graph structure under `root` with opt-in `rules` enabled
+/// and write the validation report to `out`.
 ///
 /// # Errors
 ///
-/// Returns `Error` when reading catalog files or writing output fails.
-pub fn query_catalog_relation_with_options<W: Write>(
-    query_id: &str,
-    catalog_path: &Path,
-    relation_kind: RelationKind,
+/// Returns `Error::CheckFailed` after writing the report when validation
+/// finds problems, or `Error` when scanning or writing output fails.
+pub fn check_catalog_structure_with_rules<W: Write>(
+    root: &Path,
+    rules: &ValidationRules,
     format: OutputFormat,
-    options: QueryOptions,
     out: &mut W,
 ) -> Result<(), Error> {
-    let (catalog, graph) = load_index(catalog_path)?;
-    relation::run(
-        query_id,
-        &catalog,
-        &graph,
-        relation_kind,
-        options.strict,
-        format,
-        out,
-    )
-}
+    let outcome = validate_with_rules(root, rules)?;
 
-#[cfg(test)]
-mod tests {
-    use super::{
-        BuildOptions, Error, OutputFormat, QueryOptions, RelationKind, build_catalog,
-        check_catalog, query_catalog_relation_with_options,
-    };
-    use std::fs;
-    use std::path::{Path, PathBuf};
-    use std::time::{SystemTime, UNIX_EPOCH};
-
-    struct TestWorkspace {
-        root: PathBuf,
-    }
-
-    impl TestWorkspace {
-        fn new() -> Self {
-            let mut root = std::env::temp_dir();
-            let timestamp = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .expect("system time is after epoch")
-                .as_nanos();
-            root.push(format!("docata-tests-{timestamp}"));
-            fs::create_dir_all(&root).expect("create workspace");
-            Self { root }
-        }
+    write_validation_report(&outcome.report, format, out)?;
 
-        fn path(&self) -> &Path {
-            &self.root
-        }
+    if outcome.is_success() {
+        Ok(())
+    } else {
+        Err(Error::CheckFailed {
+            findings: outcome.report.finding_count(),
+        })
     }
+}
 
-    impl Drop for TestWorkspace {
-        fn drop(&mut self) {
-            let _result = fs::remove_dir_all(&self.root);
-        }
+/// Discover every `docata.toml` root under `search_root`, check each root in
+/// parallel, and write the combined report with one section per root to
+/// `out`.
+///
+/// Each root validates the documents under its `[build] docs` directory and,
+/// when `[build] catalog` is set, compares the committed catalog with a
+/// regenerated one.
+///
+/// # Errors
+///
+/// Returns `Error::CheckFailed` after writing the report when any root fails,
+/// or `Error` when searching for roots or writing output fails.
+pub fn check_recursive<W: Write>(
+    search_root: &Path,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<RecursiveCheckReport, Error> {
+    let report = recursive_check::run(search_root)?;
+
+    recursive_check_presentation::write(&report, format, out)?;
+
+    if report.is_success() {
+        Ok(report)
+    } else {
+        Err(Error::CheckFailed {
+            findings: report.failure_count(),
+        })
     }
+}
 
-    fn write_markdown(
-        root: &Path,
-        relative_path: &str,
-        id: &str,
-        deps: &[&str],
-    ) {
-        let path = root.join(relative_path);
-        if let Some(parent) = path.parent() {
-            fs::create_dir_all(parent).expect("create parent directory");
-        }
+/// Check the nearest `docata.toml` at or above `root` and write any unknown
+/// keys to `out`.
+///
+/// Unknown keys are warnings; a config that fails to parse is an error whose
+/// message points at the offending line and column.
+///
+/// # Errors
+///
+/// Returns `Error` when no config is found, it cannot be read or parsed, or
+/// writing output fails.
+pub fn check_config<W: Write>(
+    root: &Path,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<ConfigReport, Error> {
+    let path = nearest_config_file(root)?;
+    let (_, warnings) = Config::load_checked(&path)?;
+    let report = ConfigReport { path, warnings };
 
-        let mut contents = String::new();
-        contents.push_str("---\n");
-        contents.push_str("id: ");
-        contents.push_str(id);
-        contents.push('\n');
-        if !deps.is_empty() {
-            contents.push_str("deps:\n");
-            for dep in deps {
-                contents.push_str("  - ");
-                contents.push_str(dep);
-                contents.push('\n');
-            }
-        }
-        contents.push_str("---\n");
+    config_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Write the nearest `docata.toml` at or above `root` to `out`, preceded by a
+/// comment naming the file. With `resolved`, write the configuration
+/// `docata` applies instead, with defaults filled in.
+///
+/// # Errors
+///
+/// Returns `Error` when no config is found, it cannot be read or parsed, or
+/// writing output fails.
+pub fn print_config<W: Write>(
+    root: &Path,
+    resolved: bool,
+    out: &mut W,
+) -> Result<(), Error> {
+    let path = nearest_config_file(root)?;
+    let config = Config::load(&path)?;
 
-        fs::write(path, contents).expect("write markdown");
+    writeln!(out, "# {}", path.display())?;
+    if resolved {
+        out.write_all(config.resolved().to_toml()?.as_bytes())?;
+    } else {
+        out.write_all(&std::fs::read(&path)?)?;
     }
+    Ok(())
+}
 
-    #[test]
-    fn strict_query_fails_for_unknown_id() {
-        let workspace = TestWorkspace::new();
-        let docs = workspace.path().join("docs");
-        fs::create_dir_all(&docs).expect("create docs directory");
-        write_markdown(&docs, "foo.md", "foo", &[]);
+fn nearest_config_file(root: &Path) -> Result<PathBuf, ConfigError> {
+    find_config_file(root).ok_or_else(|| ConfigError::NotFound {
+        root: root.to_path_buf(),
+    })
+}
 
-        let catalog_path = workspace.path().join("catalog.json");
-        let mut catalog_output = Vec::new();
-        build_catalog(&docs, &mut catalog_output).expect("build catalog");
-        fs::write(&catalog_path, catalog_output).expect("write catalog");
+/// Find the dependency cycles among documents under `root` and write them to
+/// `out`.
+///
+/// Unlike [`check_catalog_structure`], finding cycles is not an error.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents or writing output fails.
+pub fn find_cycles<W: Write>(
+    root: &Path,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<CycleReport, Error> {
+    let report = cycles::run(root)?;
 
-        let mut output = Vec::new();
-        let strict_result = query_catalog_relation_with_options(
+    cycles_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Report documents under `root` whose dependencies were modified after the
+/// document's `last_reviewed` date, and write the report to `out`.
+///
+/// Modification dates come from the latest git commit touching each file,
+/// falling back to the file's modification time outside git.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents or writing output fails.
+pub fn report_freshness<W: Write>(
+    root: &Path,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<FreshnessReport, Error> {
+    let report = freshness::run(root)?;
+
+    freshness_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Report documents under `root` whose `last_reviewed` date, or last
+/// modification when never reviewed, is older than `options` allows, and
+/// write the report to `out`, oldest first.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents or writing output fails.
+pub fn report_stale<W: Write>(
+    root: &Path,
+    options: &StaleOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<StaleReport, Error> {
+    let report = stale::run(root, options)?;
+
+    stale_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Gather who answers for document `id` under `root`: its metadata,
+/// frontmatter and CODEOWNERS owners, git history, and the documents
+/// depending on it, and write the view to `out`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when no document has the id, or `Error`
+/// when scanning documents or writing output fails.
+pub fn blame_document<W: Write>(
+    root: &Path,
+    id: &str,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<BlameReport, Error> {
+    let report = blame::run(root, id)?;
+
+    blame_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// List the documents in `catalog` whose files differ between git ref `since`
+/// and the work tree, with `transitive` also the documents depending on them,
+/// and write the listing to `out`.
+///
+/// Node paths are read relative to `base`, the directory `docata build` ran
+/// in.
+///
+/// # Errors
+///
+/// Returns `Error::Git` when `base` is not inside a git work tree or `since`
+/// names no commit, or `Error` when reading the catalog or writing output
+/// fails.
+pub fn list_changed<S: CatalogStore + ?Sized, W: Write>(
+    catalog: &S,
+    base: &Path,
+    since: &str,
+    transitive: bool,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<ChangedReport, Error> {
+    let report = changed::run(&load_catalog(catalog)?, base, since, transitive)?;
+
+    changed_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Check the Markdown files staged under `root` as a pre-commit hook and
+/// write the report to `out`.
+///
+/// Only the staged documents, and documents depending on ids they remove,
+/// are read; `catalog` stands in for the rest of the tree. The hook fails
+/// when those documents have findings under `rules`, when the catalog's
+/// nodes and edges for the staged documents are out of date, or when the
+/// catalog has changes that are not staged.
+///
+/// # Errors
+///
+/// Returns `Error::CheckFailed` after writing the report when the hook
+/// fails, `Error::NotInGitRepository` when `root` is not inside a git work
+/// tree, or `Error` when reading the catalog, scanning, or writing output
+/// fails.
+pub fn check_staged<S: CatalogStore + ?Sized, W: Write>(
+    root: &Path,
+    catalog: &S,
+    rules: &ValidationRules,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<PreCommitReport, Error> {
+    let report = hook::run(root, catalog, rules)?;
+
+    hook_presentation::write(&report, format, out)?;
+
+    if report.is_success() {
+        Ok(report)
+    } else {
+        Err(Error::CheckFailed {
+            findings: report.failure_count(),
+        })
+    }
+}
+
+/// Score documents under `root` by how urgently they need review, and write
+/// the queue to `out`, highest score first.
+///
+/// Scores combine dependencies changed since the last review, recent
+/// upstream changes, review age, status, and fan-in.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents or writing output fails.
+pub fn build_review_queue<W: Write>(
+    root: &Path,
+    options: &ReviewQueueOptions,
+    format: ReviewQueueFormat,
+    out: &mut W,
+) -> Result<ReviewQueue, Error> {
+    let queue = review_queue::run(root, options)?;
+
+    review_queue_presentation::write(&queue, format, out)?;
+
+    Ok(queue)
+}
+
+/// Group current findings under `root` by the `owner` of the affected
+/// document, and write the routing report to `out`.
+///
+/// Findings cover validation rules, documents whose dependencies changed
+/// after their last review, and documents that depend on any id in
+/// `changed`, directly or transitively.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents or writing output fails.
+pub fn route_findings<W: Write>(
+    root: &Path,
+    changed: &[String],
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<RoutingReport, Error> {
+    let report = route::run(root, changed)?;
+
+    route_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Turn current validation and freshness findings under `root` into issue
+/// tracker payloads and write them to `out`, returning the issues written.
+///
+/// With [`FindingsExportOptions::state_path`] set, findings exported by an
+/// earlier run are skipped and the state file is updated.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents, reading or writing the state
+/// file, or writing output fails.
+pub fn export_findings<W: Write>(
+    root: &Path,
+    options: &FindingsExportOptions,
+    format: IssueFormat,
+    out: &mut W,
+) -> Result<Vec<Issue>, Error> {
+    let issues = issue_export::run(root, options)?;
+
+    issue_presentation::write(&issues, format, out)?;
+
+    Ok(issues)
+}
+
+/// Check catalog consistency by validating docs and ensuring regenerated output
+/// matches the catalog in `catalog`.
+///
+/// The catalog format is the store's format, e.g. the file extension for
+/// local paths. A single-line JSON catalog is regenerated compact. When the
+/// catalog is out of date, the returned `Error::CatalogDiff` carries the
+/// nodes and edges that changed.
+///
+/// # Errors
+///
+/// Returns `Error::CatalogOptionsMismatch` when the catalog records different
+/// build options than `options`, or `Error` when scanning fails, validation
+/// checks fail, or catalog differs from regenerated output.
+pub fn check_catalog<S: CatalogStore + ?Sized>(
+    root: &Path,
+    catalog: &S,
+    options: BuildOptions,
+) -> Result<(), Error> {
+    check_scanned_catalog(
+        &ScannedDocuments::scan(root, options, &NoProgress)?,
+        catalog,
+    )
+}
+
+/// Check the catalog in `catalog` like [`check_catalog`], regenerating it
+/// from `documents` with the options they were scanned with.
+///
+/// # Errors
+///
+/// Returns `Error::CatalogOptionsMismatch` when the catalog records different
+/// build options than `documents`, or `Error` when validation checks fail or
+/// catalog differs from regenerated output.
+pub fn check_scanned_catalog<S: CatalogStore + ?Sized>(
+    documents: &ScannedDocuments,
+    catalog: &S,
+) -> Result<(), Error> {
+    let current = catalog.load()?;
+    let (regenerated, format) = regenerate_catalog(documents, catalog, Some(&current))?;
+    if !regenerated.matches(&current)? {
+        let mut rendered = Vec::new();
+        regenerated.write(&mut rendered)?;
+        let regenerated = rendered;
+        let read =
+            |content: &[u8]| catalog_presentation::read_catalog(&mut &content[..], format).ok();
+        let delta = read(&current)
+            .zip(read(&regenerated))
+            .map(|(current, regenerated)| Box::new(CatalogDelta::between(&current, &regenerated)));
+        return Err(Error::CatalogDiff {
+            catalog_path: catalog.location(),
+            delta,
+        });
+    }
+
+    Ok(())
+}
+
+/// Like [`check_catalog`], but when the catalog in `catalog` is out of date,
+/// save the regenerated catalog in its place instead of failing. A catalog
+/// not stored yet is out of date, and is built with `options`. Returns
+/// whether the catalog was written.
+///
+/// The write holds the store's lock, following `options.lock_policy`.
+///
+/// # Errors
+///
+/// Returns `Error::CatalogOptionsMismatch` when the catalog records different
+/// build options than `options`, or `Error` when scanning fails, validation
+/// checks fail, the lock is held elsewhere under [`LockPolicy::NoWait`], or
+/// the store rejects the write.
+pub fn update_catalog<S: CatalogStore + ?Sized>(
+    root: &Path,
+    catalog: &S,
+    options: BuildOptions,
+) -> Result<bool, Error> {
+    update_scanned_catalog(
+        &ScannedDocuments::scan(root, options, &NoProgress)?,
+        catalog,
+    )
+}
+
+/// Update the catalog in `catalog` like [`update_catalog`], regenerating it
+/// from `documents` with the options they were scanned with.
+///
+/// # Errors
+///
+/// Returns `Error::CatalogOptionsMismatch` when the catalog records different
+/// build options than `documents`, or `Error` when validation checks fail,
+/// the lock is held elsewhere under [`LockPolicy::NoWait`], or the store
+/// rejects the write.
+pub fn update_scanned_catalog<S: CatalogStore + ?Sized>(
+    documents: &ScannedDocuments,
+    catalog: &S,
+) -> Result<bool, Error> {
+    let _lock = catalog.lock(documents.options.lock_policy)?;
+    let current = match catalog.load() {
+        Ok(current) => Some(current),
+        Err(StoreError::NotFound { .. }) => None,
+        Err(error) => return Err(error.into()),
+    };
+    let (regenerated, format) = regenerate_catalog(documents, catalog, current.as_deref())?;
+    if let Some(current) = &current
+        && regenerated.matches(current)?
+    {
+        return Ok(false);
+    }
+    save_catalog(
+        catalog,
+        &regenerated,
+        documents.options.catalog_format(format),
+    )?;
+
+    Ok(true)
+}
+
+/// The catalog regenerated from `documents` in place of `current`, the
+/// stored catalog bytes, and its format. Regeneration follows the store's
+/// format and the layout of `current`; without it, the options `documents`
+/// were scanned with and the store's location decide.
+fn regenerate_catalog<S: CatalogStore + ?Sized>(
+    documents: &ScannedDocuments,
+    catalog: &S,
+    current: Option<&[u8]>,
+) -> Result<(build::Built, CatalogFormat), Error> {
+    let (root, options) = (&*documents.root, documents.options);
+    let recorded =
+        current.and_then(|current| build::RecordedOptions::read(current, catalog.format()));
+    let options = BuildOptions {
+        catalog_format: catalog.format(),
+        compact: current.map_or(options.compact, |current| {
+            build::detect_compact(current, catalog.format())
+        }),
+        compression: current
+            .and_then(Compression::sniff)
+            .unwrap_or_else(|| catalog.compression()),
+        ..options
+    };
+    let entries = &documents.outcome.entries;
+    validate_entries(root, entries)?;
+
+    if let Some(recorded) = recorded {
+        let mismatches = recorded.mismatches(&build::RecordedOptions::new(root, options));
+        if !mismatches.is_empty() {
+            return Err(Error::CatalogOptionsMismatch {
+                catalog_path: catalog.location(),
+                mismatches,
+            });
+        }
+    }
+
+    let regenerated = build::from_entries(root, entries, options)?;
+
+    Ok((regenerated, options.catalog_format))
+}
+
+/// Write `delta`, e.g. from [`Error::CatalogDiff`], to `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when writing output fails.
+pub fn write_catalog_delta<W: Write>(
+    delta: &CatalogDelta,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    delta_presentation::write(delta, "check", format, out)?;
+
+    Ok(())
+}
+
+/// Describe how the catalog in `catalog` differs from the catalog `options`
+/// would regenerate from `root`, and write the delta to `out`.
+///
+/// Use after [`check_catalog`] fails with [`Error::CatalogDiff`] to show
+/// which nodes and edges are out of date.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning, rendering, reading the catalog, or writing
+/// output fails.
+pub fn explain_catalog_diff<S: CatalogStore + ?Sized, W: Write>(
+    root: &Path,
+    catalog: &S,
+    options: BuildOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<CatalogDelta, Error> {
+    let options = BuildOptions {
+        catalog_format: catalog.format(),
+        ..options
+    };
+    let entries = scan_and_validate(root, options.scan_options())?;
+    let regenerated = build::render(root, &entries, options)?;
+    let regenerated =
+        catalog_presentation::read_catalog(&mut regenerated.as_slice(), options.catalog_format)?;
+    let delta = CatalogDelta::between(&load_catalog(catalog)?, &regenerated);
+
+    delta_presentation::write(&delta, "check", format, out)?;
+
+    Ok(delta)
+}
+
+/// Three-way merge serialized catalogs `ours` and `theirs`, both changed
+/// from `base`, as a git merge driver does. The merged catalog keeps the
+/// serialization of `ours`; conflicting nodes keep its version and are
+/// listed in [`CatalogMerge::conflicts`].
+///
+/// # Errors
+///
+/// Returns `Error` when a catalog cannot be read or the merged catalog
+/// cannot be written.
+pub fn merge_catalogs(
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    format: CatalogFormat,
+) -> Result<CatalogMerge, Error> {
+    Ok(merge::merge(base, ours, theirs, format)?)
+}
+
+/// Compare the catalog in `old` with the catalog in `new` and write the
+/// delta to `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when either catalog cannot be read or writing output
+/// fails.
+pub fn diff_catalogs<O: CatalogStore + ?Sized, N: CatalogStore + ?Sized, W: Write>(
+    old: &O,
+    new: &N,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<CatalogDelta, Error> {
+    let delta = CatalogDelta::between(&load_catalog(old)?, &load_catalog(new)?);
+
+    delta_presentation::write(&delta, "diff", format, out)?;
+
+    Ok(delta)
+}
+
+/// Detect whether the catalog in `catalog` was built with node metadata,
+/// from its recorded build options or, for older catalogs, its node shape.
+///
+/// The result fits [`BuildOptions::include_node_metadata`]; to regenerate a
+/// committed catalog the way it was built, pass [`detect_build_options`] to
+/// [`check_catalog`] instead, which detects the other recorded options too.
+///
+/// # Errors
+///
+/// Returns `Error` when the catalog cannot be read.
+pub fn detect_node_metadata<S: CatalogStore + ?Sized>(catalog: &S) -> Result<bool, Error> {
+    Ok(build::detect_node_metadata(
+        &catalog.load()?,
+        catalog.format(),
+    ))
+}
+
+/// Detect which node and edge fields the catalog in `catalog` was built
+/// with: node metadata as [`detect_node_metadata`] does, and provenance,
+/// content hashes, git metadata, and code owners from its recorded build
+/// options. Other options are left at their defaults.
+///
+/// Pass the result to [`check_catalog`] so a committed catalog is
+/// regenerated the way it was built; [`check_catalog`] itself reports a
+/// catalog built with other options than it is given.
+///
+/// # Errors
+///
+/// Returns `Error` when the catalog cannot be read.
+pub fn detect_build_options<S: CatalogStore + ?Sized>(catalog: &S) -> Result<BuildOptions, Error> {
+    let content = catalog.load()?;
+    let recorded = build::RecordedOptions::read(&content, catalog.format());
+    let recorded_flag =
+        |flag: fn(&build::RecordedOptions) -> bool| recorded.as_ref().is_some_and(flag);
+    Ok(BuildOptions::new()
+        .include_node_metadata(build::detect_node_metadata(&content, catalog.format()))
+        .include_provenance(recorded_flag(|recorded| recorded.with_provenance))
+        .include_content_hash(recorded_flag(|recorded| recorded.with_content_hash))
+        .include_git_metadata(recorded_flag(|recorded| recorded.with_git_metadata))
+        .include_code_owners(recorded_flag(|recorded| recorded.with_code_owners)))
+}
+
+fn scan_and_validate(
+    root: &Path,
+    options: ScanOptions,
+) -> Result<Vec<scan::Entry>, Error> {
+    let entries = scan::scan_with_options(root, options)?.entries;
+    validate_entries(root, &entries)?;
+    Ok(entries)
+}
+
+/// Fail on the problems that stop a build of `entries` under `root`.
+fn validate_entries(
+    root: &Path,
+    entries: &[scan::Entry],
+) -> Result<(), Error> {
+    let rules = ValidationRules {
+        external_ids: build::external_ids(root),
+        ..ValidationRules::default()
+    };
+    validate::validate_entries(entries, &rules)?;
+    Ok(())
+}
+
+fn load_catalog<S: CatalogStore + ?Sized>(catalog: &S) -> Result<catalog::Catalog, Error> {
+    load_filtered_catalog(catalog, false)
+}
+
+/// Load `catalog`; when `filtered` by tags or metadata, fail unless it was
+/// built with the node metadata they match.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(catalog = %catalog.location()), err)
+)]
+fn load_filtered_catalog<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    filtered: bool,
+) -> Result<catalog::Catalog, Error> {
+    let content = catalog.load()?;
+    if filtered {
+        require_node_metadata(catalog, &content)?;
+    }
+    decode_catalog(catalog, &content)
+}
+
+/// Load `catalog` for a listing with `options`, failing unless it was built
+/// with the node fields their filters match.
+fn load_listed_catalog<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    options: &ListOptions,
+) -> Result<catalog::Catalog, Error> {
+    let content = catalog.load()?;
+    if !options.tags.is_empty() {
+        require_node_metadata(catalog, &content)?;
+    }
+    let recorded = build::RecordedOptions::read(&content, catalog.format());
+    let built_with =
+        |flag: fn(&build::RecordedOptions) -> bool| recorded.as_ref().is_some_and(flag);
+    for (filtered, built, option) in [
+        (
+            options.modified_before.is_some(),
+            built_with(|recorded| recorded.with_git_metadata),
+            "--with-git-metadata",
+        ),
+        (
+            options.owner.is_some(),
+            built_with(|recorded| recorded.with_code_owners),
+            "--with-code-owners",
+        ),
+    ] {
+        if filtered && !built {
+            return Err(Error::BuildOptionMissing {
+                catalog_path: catalog.location(),
+                option: option.to_owned(),
+            });
+        }
+    }
+    decode_catalog(catalog, &content)
+}
+
+/// Parse serialized catalog `content` read from `catalog`.
+fn decode_catalog<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    content: &[u8],
+) -> Result<catalog::Catalog, Error> {
+    let catalog = catalog::Catalog::from_slice(content, catalog.format())?;
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        nodes = catalog.nodes.len(),
+        edges = catalog.edges.len(),
+        "loaded catalog"
+    );
+
+    Ok(catalog)
+}
+
+fn load_index<S: CatalogStore + ?Sized>(
+    catalog: &S
+) -> Result<(catalog::Catalog, graph::Graph), Error> {
+    let catalog = load_catalog(catalog)?;
+    let graph = graph::Graph::from_catalog(&catalog);
+
+    Ok((catalog, graph))
+}
+
+/// Fail with `Error::NodeMetadataMissing` unless serialized catalog
+/// `content` of `catalog` was built with node metadata.
+fn require_node_metadata<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    content: &[u8],
+) -> Result<(), Error> {
+    if build::detect_node_metadata(content, catalog.format()) {
+        Ok(())
+    } else {
+        Err(Error::NodeMetadataMissing {
+            catalog_path: catalog.location(),
+        })
+    }
+}
+
+/// Like `load_index`, with the graph over edges of relation kind `kind`.
+fn load_relation_index<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    kind: Option<&str>,
+) -> Result<(catalog::Catalog, graph::Graph), Error> {
+    let catalog = load_catalog(catalog)?;
+    let graph = relation_graph(&catalog, kind)?;
+
+    Ok((catalog, graph))
+}
+
+/// The catalog and graph answering a relation query of `query_ids`: only the
+/// part of the catalog the query reaches when an up-to-date query index is
+/// kept beside it, the whole catalog otherwise.
+fn load_relation_reach<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    query_ids: &[&str],
+    relation_kind: RelationKind,
+    options: &QueryOptions,
+) -> Result<(catalog::Catalog, graph::Graph), Error> {
+    let filtered = options.filters_node_metadata();
+    let reached = match catalog_index::CatalogIndex::open(catalog) {
+        Some(index) if filtered && !index.node_metadata() => {
+            return Err(Error::NodeMetadataMissing {
+                catalog_path: catalog.location(),
+            });
+        },
+        Some(mut index) => index.reach(
+            query_ids,
+            relation_kind,
+            options.edge_kind.as_deref(),
+            options.transitive,
+        )?,
+        None => None,
+    };
+    let catalog = if let Some(reached) = reached {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            nodes = reached.nodes.len(),
+            "read catalog through its index"
+        );
+        reached
+    } else {
+        load_filtered_catalog(catalog, filtered)?
+    };
+    let graph = relation_graph(&catalog, options.edge_kind.as_deref())?;
+
+    Ok((catalog, graph))
+}
+
+/// The graph of `kind` edges, or of `deps` edges when `kind` is `None`.
+fn relation_graph(
+    catalog: &catalog::Catalog,
+    kind: Option<&str>,
+) -> Result<graph::Graph, Error> {
+    if let Some(kind) = kind
+        && !catalog.relations.contains_key(kind)
+    {
+        return Err(Error::UnknownRelationKind {
+            kind: kind.to_owned(),
+        });
+    }
+
+    Ok(graph::Graph::from_catalog_kind(catalog, kind))
+}
+
+/// Query catalog relations and write output to `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn query_catalog_relation<W: Write, S: CatalogStore + ?Sized>(
+    query_id: &str,
+    catalog: &S,
+    relation_kind: RelationKind,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    query_catalog_relation_with_options(
+        query_id,
+        catalog,
+        relation_kind,
+        format,
+        &QueryOptions::default(),
+        out,
+    )
+}
+
+/// Query catalog relations and write output to `out` with options.
+///
+/// # Errors
+///
+/// Returns `Error::UnknownRelationKind` when `options.edge_kind` names a kind
+/// the catalog was not built with, `Error::NodeMetadataMissing` when tag or
+/// metadata filters are set for a catalog built without node metadata, or
+/// `Error` when reading catalog files or writing output fails.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "query",
+        skip_all,
+        fields(id = query_id, relation = relation_kind.as_str()),
+        err
+    )
+)]
+pub fn query_catalog_relation_with_options<W: Write, S: CatalogStore + ?Sized>(
+    query_id: &str,
+    catalog: &S,
+    relation_kind: RelationKind,
+    format: OutputFormat,
+    options: &QueryOptions,
+    out: &mut W,
+) -> Result<(), Error> {
+    let (catalog, graph) = load_relation_reach(catalog, &[query_id], relation_kind, options)?;
+    relation::run(
+        query_id,
+        &catalog,
+        &graph,
+        relation_kind,
+        options,
+        format,
+        out,
+    )
+}
+
+/// Query catalog relations like [`query_catalog_relation_with_options`],
+/// loading the catalog from an async `catalog` store and writing output to
+/// an async `out`. The query runs on tokio's blocking pool.
+///
+/// # Errors
+///
+/// Returns `Error::UnknownRelationKind` when `options.edge_kind` names a kind
+/// the catalog was not built with, or `Error` when reading the catalog or
+/// writing output fails.
+#[cfg(feature = "tokio")]
+pub async fn query_catalog_relation_async<W, S>(
+    query_id: &str,
+    catalog: &S,
+    relation_kind: RelationKind,
+    format: OutputFormat,
+    options: &QueryOptions,
+    out: &mut W,
+) -> Result<(), Error>
+where
+    W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    S: AsyncCatalogStore + ?Sized,
+{
+    use tokio::io::AsyncWriteExt;
+
+    let content = catalog.load().await?;
+    let (location, catalog_format) = (catalog.location(), catalog.format());
+    let query_id = query_id.to_owned();
+    let options = options.clone();
+    let output = async_api::unblock(move || {
+        if options.filters_node_metadata() && !build::detect_node_metadata(&content, catalog_format)
+        {
+            return Err(Error::NodeMetadataMissing {
+                catalog_path: location,
+            });
+        }
+        let catalog = catalog::Catalog::from_slice(&content, catalog_format)?;
+        let graph = relation_graph(&catalog, options.edge_kind.as_deref())?;
+        let mut output = Vec::new();
+        relation::run(
+            &query_id,
+            &catalog,
+            &graph,
+            relation_kind,
+            &options,
+            format,
+            &mut output,
+        )?;
+        Ok::<_, Error>(output)
+    })
+    .await?;
+    out.write_all(&output).await?;
+
+    Ok(())
+}
+
+/// Query catalog relations of several ids at once and write the merged,
+/// deduplicated result set to `out`, with the results of each id grouped
+/// separately in JSON and YAML output.
+///
+/// # Errors
+///
+/// Returns `Error::QueryIdNotFound` for an unknown id in strict mode,
+/// `Error::NodeMetadataMissing` when tag or metadata filters are set for a
+/// catalog built without node metadata, or `Error` when reading catalog
+/// files or writing output fails.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "query",
+        skip_all,
+        fields(ids = ?query_ids, relation = relation_kind.as_str()),
+        err
+    )
+)]
+pub fn query_catalog_relations<W: Write, S: CatalogStore + ?Sized>(
+    query_ids: &[&str],
+    catalog: &S,
+    relation_kind: RelationKind,
+    format: OutputFormat,
+    options: &QueryOptions,
+    out: &mut W,
+) -> Result<(), Error> {
+    let (catalog, graph) = load_relation_reach(catalog, query_ids, relation_kind, options)?;
+    relation::run_set(
+        query_ids,
+        &catalog,
+        &graph,
+        relation_kind,
+        options,
+        format,
+        out,
+    )
+}
+
+/// Query relations across several catalogs, e.g. one per repository, as a
+/// single graph and write the result to `out`. Edges resolve across
+/// catalogs, and each resolved item names the catalog holding it.
+///
+/// One query id is answered like [`query_catalog_relation_with_options`],
+/// several like [`query_catalog_relations`].
+///
+/// # Errors
+///
+/// Returns `Error::Catalog` when several catalogs hold the same id,
+/// `Error::QueryIdNotFound` for an unknown id in strict mode,
+/// `Error::UnknownRelationKind` for an `options.edge_kind` no catalog
+/// declares, or `Error` when reading catalogs or writing output fails.
+pub fn query_federated_relations<W: Write>(
+    query_ids: &[&str],
+    catalogs: &[&dyn CatalogStore],
+    relation_kind: RelationKind,
+    format: OutputFormat,
+    options: &QueryOptions,
+    out: &mut W,
+) -> Result<(), Error> {
+    let catalogs = catalogs
+        .iter()
+        .map(|catalog| {
+            Ok((
+                catalog.location(),
+                load_filtered_catalog(*catalog, options.filters_node_metadata())?,
+            ))
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let federation = federation::Federation::merge(catalogs)?;
+    let graph = relation_graph(&federation.catalog, options.edge_kind.as_deref())?;
+
+    relation::run_federated(
+        query_ids,
+        &federation,
+        &graph,
+        relation_kind,
+        options,
+        format,
+        out,
+    )
+}
+
+/// Write the transitive relations of `query_id` as a tree to `out`, each id
+/// expanded once and at most `max_depth` levels deep.
+///
+/// `options.strict` and `options.edge_kind` apply as for
+/// [`query_catalog_relation_with_options`]; tag and metadata filters do not.
+///
+/// # Errors
+///
+/// Returns `Error::QueryIdNotFound` for an unknown id in strict mode,
+/// `Error::UnknownRelationKind` for an undeclared `options.edge_kind`, or
+/// `Error` when reading catalog files or writing output fails.
+pub fn query_catalog_tree<W: Write, S: CatalogStore + ?Sized>(
+    query_id: &str,
+    catalog: &S,
+    relation_kind: RelationKind,
+    max_depth: Option<usize>,
+    format: OutputFormat,
+    options: &QueryOptions,
+    out: &mut W,
+) -> Result<(), Error> {
+    let (_, graph) = load_relation_index(catalog, options.edge_kind.as_deref())?;
+    tree::run(
+        query_id,
+        &graph,
+        relation_kind,
+        max_depth,
+        options.strict,
+        format,
+        out,
+    )
+}
+
+/// Show a single node's metadata with its direct deps and refs, and write
+/// output to `out`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when `id` is not in the catalog, or `Error`
+/// when reading catalog files or writing output fails.
+pub fn show_catalog_node<W: Write, S: CatalogStore + ?Sized>(
+    id: &str,
+    catalog: &S,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let (catalog, graph) = load_index(catalog)?;
+    show::run(id, &catalog, &graph, format, out)
+}
+
+/// Find the shortest dependency path from `from` to `to`, or every shortest
+/// path when `all` is set, and write output to `out`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when either id is unknown, or `Error` when
+/// reading catalog files or writing output fails.
+pub fn find_catalog_paths<W: Write, S: CatalogStore + ?Sized>(
+    from: &str,
+    to: &str,
+    catalog: &S,
+    all: bool,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let (_, graph) = load_index(catalog)?;
+    shortest_path::run(from, to, &graph, all, format, out)
+}
+
+/// Propose frontmatter for Markdown files under `root` that have none.
+///
+/// Ids are derived from file paths and deps from relative Markdown links.
+/// Id collisions and ambiguous links are settled by `resolutions`; the rest
+/// are reported in [`AdoptionPlan::conflicts`]. Nothing is written until each
+/// proposal is passed to [`apply_adoption`].
+///
+/// # Errors
+///
+/// Returns `Error` when scanning the tree fails or a resolved id is already
+/// taken.
+pub fn plan_adoption(
+    root: &Path,
+    resolutions: &AdoptionResolutions,
+) -> Result<AdoptionPlan, Error> {
+    Ok(adopt::plan(root, resolutions)?)
+}
+
+/// Create a Markdown file for `document` under `root` from the template of
+/// its type, with its frontmatter filled in, and return the file's path.
+///
+/// # Errors
+///
+/// Returns `Error` when a document under `root` already uses the id, the
+/// file exists, or reading the config or template or writing the file fails.
+pub fn create_document(
+    root: &Path,
+    document: &NewDocument,
+) -> Result<PathBuf, Error> {
+    Ok(scaffold::create(root, document)?)
+}
+
+/// Create a stub document with `status: stub` in `dir`, relative to `root`,
+/// for every unresolved dependency of the documents under `root`, and
+/// return the paths created.
+///
+/// # Errors
+///
+/// Returns `Error` when a missing id cannot name a file, a stub's file
+/// exists, or scanning or writing fails.
+pub fn create_stubs(
+    root: &Path,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    Ok(scaffold::create_stubs(root, dir)?)
+}
+
+/// Add `dep` to, or remove it from, the `deps` of the document `id` under
+/// `root`. Only the lines of the `deps` key change; the rest of the file is
+/// kept byte for byte.
+///
+/// # Errors
+///
+/// Returns `Error` when no document has the id, its `deps` is not a list
+/// docata can edit in place, or reading or writing the file fails.
+pub fn edit_dependency(
+    root: &Path,
+    id: &str,
+    dep: &str,
+    change: DependencyChange,
+) -> Result<DependencyEdit, Error> {
+    Ok(deps_edit::edit(root, id, dep, change)?)
+}
+
+/// Drop duplicate `deps` entries, self-dependencies, and deps on
+/// `removed_ids` from the documents under `root`, editing only their `deps`
+/// lines, and return what was dropped.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning fails, the `deps` of a document to fix is
+/// not a list docata can edit in place, or reading or writing a file fails.
+pub fn fix_dependencies(
+    root: &Path,
+    removed_ids: &[String],
+) -> Result<Vec<DependencyFix>, Error> {
+    Ok(deps_edit::fix(root, removed_ids)?)
+}
+
+/// Prepend the proposed frontmatter block to the proposal's file.
+///
+/// # Errors
+///
+/// Returns `Error` when the file cannot be rewritten or already has
+/// frontmatter.
+pub fn apply_adoption(proposal: &AdoptionProposal) -> Result<(), Error> {
+    Ok(adopt::apply(proposal)?)
+}
+
+/// Summarize the catalog in `catalog` and write the statistics to
+/// `out`.
+///
+/// Counts by type, domain, and status are only available for catalogs built
+/// with node metadata.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn catalog_stats<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<CatalogStats, Error> {
+    let catalog = load_catalog(catalog)?;
+    let stats = stats::build(&catalog);
+
+    stats_presentation::write(&stats, format, out)?;
+
+    Ok(stats)
+}
+
+/// Find the dependencies shared by the documents `query_ids` in `catalog`
+/// and write them to `out`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when a query id is not a catalog node, or
+/// `Error` when reading catalog files or writing output fails.
+pub fn find_common_dependencies<W: Write, S: CatalogStore + ?Sized>(
+    query_ids: &[&str],
+    catalog: &S,
+    options: &CommonOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<CommonDependencies, Error> {
+    let (_, graph) = load_index(catalog)?;
+    let common = common::build(query_ids, &graph, options)?;
+
+    common_presentation::write(&common, format, out)?;
+
+    Ok(common)
+}
+
+/// Rank the documents of `catalog` by centrality and write the ranking to
+/// `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn rank_hotspots<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    options: &HotspotOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<HotspotReport, Error> {
+    let (catalog, mut graph) = load_index(catalog)?;
+    if options.counts_dependents() {
+        graph = graph.with_reachability_index();
+    }
+    let report = hotspots::build(&catalog, &graph, options);
+
+    hotspots_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Find the strongly and weakly connected components of the dependency
+/// graph in `catalog` and write them to `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn find_components<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<ComponentReport, Error> {
+    let catalog = load_catalog(catalog)?;
+    let report = components::build(&catalog);
+
+    components_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Apply `change` to the catalog in `catalog` in memory and write the
+/// documents it affects and the validation findings that would result to
+/// `out`. No file is modified.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when a changed id is not a catalog node, or
+/// `Error` when reading catalog files or writing output fails.
+pub fn simulate_catalog_change<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    change: &SimulatedChange,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<SimulationReport, Error> {
+    let catalog = load_catalog(catalog)?;
+    let report = simulate::run(&catalog, change)?;
+
+    simulate_presentation::write(&report, format, out)?;
+
+    Ok(report)
+}
+
+/// Find single points of failure in the catalog in `catalog` and write them
+/// to `out`: documents and dependency edges whose removal disconnects parts
+/// of the graph, and with `options.root`, documents every path from the root
+/// to others passes through.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when `options.root` is not a catalog node,
+/// or `Error` when reading catalog files or writing output fails.
+pub fn find_critical_documents<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    options: &CriticalOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<CriticalReport, Error> {
+    let catalog = load_catalog(catalog)?;
+    if let Some(root) = &options.root
+        && !catalog.nodes.iter().any(|node| node.id == *root)
+    {
+        return Err(Error::NodeNotFound { id: root.clone() });
+    }
+
+    let report = critical::build(&catalog, options);
+    critical_presentation::write(&report, options.root.as_deref(), format, out)?;
+
+    Ok(report)
+}
+
+/// Compute the dominator tree of the catalog in `catalog` from `root`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when `root` is not a catalog node, or
+/// `Error` when reading the catalog fails.
+pub fn catalog_dominator_tree<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    root: &str,
+) -> Result<DominatorTree, Error> {
+    let catalog = load_catalog(catalog)?;
+    critical::dominator_tree(&catalog, root).ok_or_else(|| Error::NodeNotFound {
+        id: root.to_owned(),
+    })
+}
+
+/// Fetch the catalog at `url` into `options.cache_dir`, reusing the cached
+/// copy when the server reports it unchanged or cannot be reached.
+///
+/// # Errors
+///
+/// Returns `Error` when the server rejects the request, or when the catalog
+/// is unavailable and was never cached.
+#[cfg(feature = "http")]
+pub fn fetch_remote_catalog(
+    url: &str,
+    options: &RemoteFetchOptions,
+) -> Result<RemoteCatalog, Error> {
+    Ok(remote::fetch(url, options)?)
+}
+
+/// Export the catalog graph in `format` and write it to `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn export_catalog<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    format: ExportFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    export_catalog_with_options(catalog, format, &ExportOptions::default(), out)
+}
+
+/// Export the catalog graph in `format` with options and write it to `out`.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn export_catalog_with_options<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    format: ExportFormat,
+    options: &ExportOptions,
+    out: &mut W,
+) -> Result<(), Error> {
+    let mut catalog = load_catalog(catalog)?;
+    if let Some(summarize) = options.summarize {
+        catalog = export::summarize(catalog, summarize);
+    }
+    export::run(&catalog, format, out)
+}
+
+/// Write a Docusaurus `sidebars.json` grouping the documents of `catalog`
+/// by domain, then by type, to `out`, with doc ids relative to `docs_dir`.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing output fails.
+pub fn generate_sidebar<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    docs_dir: &Path,
+    out: &mut W,
+) -> Result<NavSection, Error> {
+    let navigation = navigation::build(&load_catalog(catalog)?, docs_dir);
+    navigation_presentation::write_sidebar(&navigation, out)?;
+    Ok(navigation)
+}
+
+/// Write a Hugo `_index.md` into `dir`, the documents directory, and into a
+/// subdirectory for each domain and each type within it, listing the
+/// documents of `catalog`, and return the paths written.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files or writing a page fails.
+pub fn generate_section_indexes<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, Error> {
+    let navigation = navigation::build(&load_catalog(catalog)?, dir);
+    Ok(navigation_presentation::write_section_indexes(
+        &navigation,
+        dir,
+    )?)
+}
+
+/// Extract the part of the catalog in `catalog` chosen by `selection` and
+/// write it to `out` in `format`.
+///
+/// Catalog output keeps the `build_options` block and node metadata of the
+/// source catalog, so the result can be queried like any other catalog.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when the focused id is not a catalog node,
+/// or `Error` when reading catalog files or writing output fails.
+pub fn extract_subgraph<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    selection: &SubgraphSelection,
+    format: SubgraphFormat,
+    compact: bool,
+    out: &mut W,
+) -> Result<(), Error> {
+    let content = catalog.load()?;
+    let catalog_format = catalog.format();
+    let full = catalog_presentation::read_catalog(&mut content.as_slice(), catalog_format)?;
+    let subgraph = subgraph::extract(full, selection)?;
+
+    match format {
+        SubgraphFormat::Catalog(format) => {
+            let include_node_metadata = build::detect_node_metadata(&content, catalog_format);
+            let recorded = build::RecordedOptions::read(&content, catalog_format)
+                .unwrap_or_else(|| subgraph::unrecorded_options(include_node_metadata));
+            catalog_presentation::write_catalog(
+                &subgraph,
+                &recorded,
+                out,
+                include_node_metadata,
+                format,
+                compact,
+                Compression::None,
+            )?;
+        },
+        SubgraphFormat::Export(format) => export::run(&subgraph, format, out)?,
+    }
+
+    Ok(())
+}
+
+/// List catalog nodes matching `options` and write output to `out`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeMetadataMissing` when `options.tags` is set for a
+/// catalog built without node metadata, `Error::BuildOptionMissing` when
+/// `options.modified_before` or `options.owner` is set for a catalog built
+/// without git metadata or code owners, or `Error` when reading catalog
+/// files or writing output fails.
+pub fn list_catalog_nodes<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    options: &ListOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let catalog = load_listed_catalog(catalog, options)?;
+    list::run(&catalog, options, format, out)
+}
+
+/// List catalog nodes at `endpoint` of the dependency graph matching
+/// `options` and write output to `out`.
+///
+/// # Errors
+///
+/// Returns `Error::NodeMetadataMissing` when `options.tags` is set for a
+/// catalog built without node metadata, `Error::BuildOptionMissing` when
+/// `options.modified_before` or `options.owner` is set for a catalog built
+/// without git metadata or code owners, or `Error` when reading catalog
+/// files or writing output fails.
+pub fn list_catalog_endpoints<W: Write, S: CatalogStore + ?Sized>(
+    catalog: &S,
+    endpoint: GraphEndpoint,
+    options: &ListOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let catalog = load_listed_catalog(catalog, options)?;
+    list::run_endpoints(&catalog, endpoint, options, format, out)
+}
+
+/// Ids of the catalog nodes starting with `prefix`, sorted, for shell
+/// completion.
+///
+/// # Errors
+///
+/// Returns `Error` when reading catalog files fails.
+pub fn complete_catalog_ids<S: CatalogStore + ?Sized>(
+    catalog: &S,
+    prefix: &str,
+) -> Result<Vec<String>, Error> {
+    let catalog = load_catalog(catalog)?;
+    let mut ids = catalog
+        .nodes
+        .into_iter()
+        .map(|node| node.id)
+        .filter(|id| id.starts_with(prefix))
+        .collect::<Vec<_>>();
+    ids.sort();
+    Ok(ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AdoptionConflict, AdoptionResolutions, BrokenLinkReason, BuildOptions, Error,
+        FindingsExportOptions, GraphEndpoint, IssueFormat, ListOptions, OutputFormat,
+        ProgressEvent, QueryOptions, RelationCombine, RelationKind, ReviewQueueFormat,
+        ReviewQueueOptions, ScannedDocuments, SkipReason, StoreError, ValidationRules,
+        apply_adoption, build_catalog, build_catalog_into, build_catalog_with_options,
+        build_merged_catalog_into, build_review_queue, catalog_stats, check_catalog,
+        check_recursive, check_scanned_catalog, complete_catalog_ids, detect_build_options,
+        explain_catalog_diff, export_findings, find_catalog_paths, list_catalog_endpoints,
+        list_catalog_nodes, plan_adoption, query_catalog_relation_with_options,
+        query_catalog_relations, report_freshness, route_findings, show_catalog_node,
+        update_catalog, update_scanned_catalog, validate, validate_with_rules, write_build_report,
+        write_catalog_delta,
+    };
+    use crate::scan::ScanError;
+    use crate::testing::{TestWorkspace, write_markdown};
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[test]
+    fn strict_query_fails_for_unknown_id() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
+
+        let mut output = Vec::new();
+        let strict_result = query_catalog_relation_with_options(
+            "missing",
+            &catalog_path,
+            RelationKind::Deps,
+            OutputFormat::Json,
+            &QueryOptions {
+                strict: true,
+                ..QueryOptions::default()
+            },
+            &mut output,
+        );
+        assert!(matches!(
+            strict_result,
+            Err(Error::QueryIdNotFound { query_id }) if query_id == "missing"
+        ));
+
+        let non_strict_result = query_catalog_relation_with_options(
             "missing",
             &catalog_path,
             RelationKind::Deps,
             OutputFormat::Json,
-            QueryOptions { strict: true },
+            &QueryOptions::default(),
+            &mut output,
+        );
+        assert!(non_strict_result.is_ok());
+    }
+
+    #[test]
+    fn non_strict_query_reports_unresolved_id_with_suggestions() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "billing.md", "billing", &[]);
+        write_markdown(&docs, "isolated.md", "isolated", &[]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
+
+        let query = |query_id: &str| {
+            let mut output = Vec::new();
+            query_catalog_relation_with_options(
+                query_id,
+                &catalog_path,
+                RelationKind::Deps,
+                OutputFormat::Json,
+                &QueryOptions::default(),
+                &mut output,
+            )
+            .expect("query deps");
+            String::from_utf8(output).expect("valid utf-8")
+        };
+
+        let isolated = query("isolated");
+        assert!(isolated.contains("\"query_resolved\": true"));
+        assert!(isolated.contains("\"suggestions\": []"));
+
+        let typo = query("biling");
+        assert!(typo.contains("\"query_resolved\": false"));
+        assert!(typo.contains("\"suggestions\": [\n      \"billing\"\n    ]"));
+    }
+
+    #[test]
+    fn merges_roots_and_rejects_ids_shared_across_them() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        let adr = workspace.path().join("adr");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::create_dir_all(&adr).expect("create adr directory");
+        write_markdown(&docs, "api.md", "api", &["adr-1"]);
+        write_markdown(&adr, "adr-1.md", "adr-1", &[]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        build_merged_catalog_into(&[&docs, &adr], &catalog_path, BuildOptions::default())
+            .expect("build merged catalog");
+        let catalog = fs::read_to_string(&catalog_path).expect("read catalog");
+        assert!(catalog.contains("\"id\": \"adr-1\""));
+        assert!(catalog.contains("\"from\": \"api\",\n      \"to\": \"adr-1\""));
+
+        write_markdown(&adr, "api.md", "api", &[]);
+        let result =
+            build_merged_catalog_into(&[&docs, &adr], &catalog_path, BuildOptions::default());
+        assert!(matches!(
+            result,
+            Err(Error::Catalog(
+                crate::catalog::CatalogError::DuplicateId { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn check_catalog_requires_no_regeneration_diff() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, &catalog_output).expect("write catalog");
+
+        check_catalog(&docs, &catalog_path, BuildOptions::default())
+            .expect("check should pass for up-to-date catalog");
+
+        fs::write(&catalog_path, "{}").expect("break catalog content");
+        let result = check_catalog(&docs, &catalog_path, BuildOptions::default());
+        assert!(matches!(
+            result,
+            Err(Error::CatalogDiff { delta: None, .. })
+        ));
+
+        let stale = String::from_utf8(catalog_output)
+            .expect("valid utf-8")
+            .replace("\"id\": \"foo\"", "\"id\": \"gone\"");
+        fs::write(&catalog_path, stale).expect("write stale catalog");
+        let Err(Error::CatalogDiff {
+            delta: Some(delta), ..
+        }) = check_catalog(&docs, &catalog_path, BuildOptions::default())
+        else {
+            panic!("stale catalog should fail with a delta");
+        };
+        assert_eq!(delta.added_nodes[0].id, "foo");
+        assert_eq!(delta.removed_nodes[0].id, "gone");
+
+        assert!(
+            update_catalog(&docs, &catalog_path, BuildOptions::default()).expect("update catalog")
+        );
+        assert!(
+            !update_catalog(&docs, &catalog_path, BuildOptions::default()).expect("update catalog")
+        );
+        check_catalog(&docs, &catalog_path, BuildOptions::default())
+            .expect("check should pass for updated catalog");
+    }
+
+    #[test]
+    fn update_builds_a_missing_catalog() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+        let catalog_path = workspace.path().join("catalog.json");
+
+        assert!(matches!(
+            check_catalog(&docs, &catalog_path, BuildOptions::default()),
+            Err(Error::Store(StoreError::NotFound { .. }))
+        ));
+        assert!(
+            update_catalog(&docs, &catalog_path, BuildOptions::default()).expect("update catalog")
+        );
+
+        let mut built = Vec::new();
+        build_catalog(&docs, &mut built).expect("build catalog");
+        assert_eq!(fs::read(&catalog_path).expect("read catalog"), built);
+        assert!(
+            !update_catalog(&docs, &catalog_path, BuildOptions::default()).expect("update catalog")
+        );
+    }
+
+    #[test]
+    fn completes_catalog_ids_by_prefix() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "web.md", "web", &["api"]);
+        write_markdown(&docs, "app.md", "app", &[]);
+        write_markdown(&docs, "api.md", "api", &[]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+
+        let complete = |prefix| complete_catalog_ids(&catalog_path, prefix).expect("complete ids");
+        assert_eq!(complete(""), ["api", "app", "web"]);
+        assert_eq!(complete("ap"), ["api", "app"]);
+        assert_eq!(complete("app"), ["app"]);
+        assert!(complete("API").is_empty());
+
+        let missing = complete_catalog_ids(&workspace.path().join("missing.json"), "");
+        assert!(matches!(
+            missing,
+            Err(Error::Store(StoreError::NotFound { .. }))
+        ));
+    }
+
+    #[test]
+    fn update_leaves_the_catalog_alone_when_it_cannot_regenerate_it() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+        let built = fs::read(&catalog_path).expect("read catalog");
+
+        write_markdown(&docs, "baz.md", "baz", &[]);
+        let mismatch = update_catalog(
+            &docs,
+            &catalog_path,
+            BuildOptions::new().include_provenance(true),
+        );
+        assert!(matches!(
+            mismatch,
+            Err(Error::CatalogOptionsMismatch { .. })
+        ));
+        assert_eq!(fs::read(&catalog_path).expect("read catalog"), built);
+
+        write_markdown(&docs, "bar.md", "bar", &["missing"]);
+        assert!(update_catalog(&docs, &catalog_path, BuildOptions::default()).is_err());
+        assert_eq!(fs::read(&catalog_path).expect("read catalog"), built);
+
+        let unbuilt = workspace.path().join("unbuilt.json");
+        assert!(update_catalog(&docs, &unbuilt, BuildOptions::default()).is_err());
+        assert!(!unbuilt.exists());
+    }
+
+    #[test]
+    fn scanned_documents_validate_and_check_the_catalog_from_one_scan() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::new()).expect("build catalog");
+
+        let discovered = AtomicUsize::new(0);
+        let progress = |event: &ProgressEvent<'_>| {
+            if matches!(event, ProgressEvent::FilesDiscovered { .. }) {
+                discovered.fetch_add(1, Ordering::Relaxed);
+            }
+        };
+        let documents =
+            ScannedDocuments::scan(&docs, BuildOptions::new(), &progress).expect("scan documents");
+        // Changes after the scan go unseen: nothing reads the documents again.
+        fs::remove_file(docs.join("bar.md")).expect("remove document");
+
+        let outcome = documents.validate(&ValidationRules::default(), &progress);
+        assert!(outcome.is_success());
+        assert_eq!(outcome.document_count, 2);
+        check_scanned_catalog(&documents, &catalog_path).expect("check catalog");
+        assert!(!update_scanned_catalog(&documents, &catalog_path).expect("update catalog"));
+        assert_eq!(discovered.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn compact_catalogs_and_output_are_single_line() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog_with_options(
+            &docs,
+            &mut catalog_output,
+            BuildOptions {
+                compact: true,
+                ..BuildOptions::default()
+            },
+        )
+        .expect("build compact catalog");
+        assert!(!catalog_output.trim_ascii_end().contains(&b'\n'));
+        assert!(catalog_output.ends_with(b"\n"));
+        fs::write(&catalog_path, &catalog_output).expect("write catalog");
+
+        check_catalog(&docs, &catalog_path, BuildOptions::default())
+            .expect("check should regenerate a compact catalog compactly");
+
+        let mut output = Vec::new();
+        list_catalog_nodes(
+            &catalog_path,
+            &ListOptions::default(),
+            OutputFormat::Json.compact(),
+            &mut output,
+        )
+        .expect("list nodes as compact json");
+        let output = String::from_utf8(output).expect("valid utf-8");
+        assert_eq!(output.lines().count(), 1);
+        assert!(output.ends_with('\n'));
+    }
+
+    #[test]
+    fn explain_catalog_diff_lists_out_of_date_nodes_and_edges() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, &catalog_output).expect("write catalog");
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+
+        let mut output = Vec::new();
+        let delta = explain_catalog_diff(
+            &docs,
+            &catalog_path,
+            BuildOptions::default(),
+            OutputFormat::Text,
+            &mut output,
+        )
+        .expect("explain diff");
+        assert_eq!(delta.added_nodes.len(), 1);
+        assert_eq!(delta.added_edges.len(), 1);
+
+        let text = String::from_utf8(output).expect("valid utf-8");
+        assert!(text.contains("+ edge bar -> foo\n"));
+    }
+
+    #[test]
+    fn check_catalog_returns_the_delta_of_a_stale_catalog() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "web.md", "web", &["api"]);
+        write_markdown(&docs, "api.md", "api", &[]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+
+        write_markdown(&docs, "web.md", "web", &["db"]);
+        write_markdown(&docs, "db.md", "db", &[]);
+        let Err(Error::CatalogDiff {
+            delta: Some(delta), ..
+        }) = check_catalog(&docs, &catalog_path, BuildOptions::default())
+        else {
+            panic!("stale catalog should fail with a delta");
+        };
+        assert_eq!(delta.change_count(), 3);
+        assert_eq!(delta.added_nodes[0].id, "db");
+        assert_eq!(
+            (
+                delta.added_edges[0].to.as_str(),
+                delta.removed_edges[0].to.as_str()
+            ),
+            ("db", "api")
+        );
+
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+        let mut relaid = fs::read(&catalog_path).expect("read catalog");
+        relaid.push(b'\n');
+        fs::write(&catalog_path, relaid).expect("write catalog");
+        let Err(Error::CatalogDiff {
+            delta: Some(delta), ..
+        }) = check_catalog(&docs, &catalog_path, BuildOptions::default())
+        else {
+            panic!("relaid catalog should fail with a delta");
+        };
+        assert!(delta.is_empty());
+    }
+
+    #[test]
+    fn writes_the_delta_of_a_failed_check() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "web.md", "web", &["api"]);
+        write_markdown(&docs, "api.md", "api", &[]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+        check_catalog(&docs, &catalog_path, BuildOptions::default()).expect("fresh catalog");
+
+        fs::remove_file(docs.join("api.md")).expect("remove api");
+        write_markdown(&docs, "web.md", "web", &[]);
+        let Err(Error::CatalogDiff {
+            delta: Some(delta), ..
+        }) = check_catalog(&docs, &catalog_path, BuildOptions::default())
+        else {
+            panic!("stale catalog should fail with a delta");
+        };
+
+        let mut text = Vec::new();
+        write_catalog_delta(&delta, OutputFormat::Text, &mut text).expect("write text");
+        let text = String::from_utf8(text).expect("valid utf-8");
+        assert!(text.starts_with("- node api ("), "{text}");
+        assert!(text.contains("\n- edge web -> api\n"), "{text}");
+        assert!(text.ends_with("0 edge(s) added, 1 removed\n"), "{text}");
+        let mut json = Vec::new();
+        write_catalog_delta(&delta, OutputFormat::Json, &mut json).expect("write json");
+        let json: serde_json::Value = serde_json::from_slice(&json).expect("parse json");
+        assert_eq!(json["command"], "check");
+        assert_eq!(json["removed_nodes"][0]["id"], "api");
+        assert!(write_catalog_delta(&delta, OutputFormat::Sarif, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    fn check_catalog_reports_build_option_mismatch() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let metadata = BuildOptions {
+            include_node_metadata: true,
+            ..BuildOptions::default()
+        };
+        let mut catalog_output = Vec::new();
+        build_catalog_with_options(&docs, &mut catalog_output, metadata).expect("build catalog");
+        fs::write(&catalog_path, &catalog_output).expect("write catalog");
+
+        check_catalog(&docs, &catalog_path, metadata).expect("options match");
+        let Err(Error::CatalogOptionsMismatch { mismatches, .. }) =
+            check_catalog(&docs, &catalog_path, BuildOptions::default())
+        else {
+            panic!("expected option mismatch");
+        };
+        assert_eq!(
+            mismatches,
+            vec!["catalog was built with --with-node-metadata, check invoked without".to_owned()]
+        );
+    }
+
+    #[test]
+    fn check_catalog_compares_recorded_provenance_with_the_requested_options() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+        let catalog_path = workspace.path().join("catalog.json");
+        let provenance = BuildOptions::new().include_provenance(true);
+        build_catalog_into(&docs, &catalog_path, provenance).expect("build catalog");
+
+        check_catalog(&docs, &catalog_path, provenance).expect("options match");
+        assert!(
+            detect_build_options(&catalog_path)
+                .expect("detect options")
+                .include_provenance
+        );
+        let mismatches = |options| match check_catalog(&docs, &catalog_path, options) {
+            Err(Error::CatalogOptionsMismatch { mismatches, .. }) => mismatches,
+            other => panic!("expected option mismatch, got {other:?}"),
+        };
+        assert_eq!(
+            mismatches(BuildOptions::new()),
+            ["catalog was built with --with-provenance, check invoked without"]
+        );
+        let before = fs::read(&catalog_path).expect("read catalog");
+        assert!(matches!(
+            update_catalog(&docs, &catalog_path, BuildOptions::new()),
+            Err(Error::CatalogOptionsMismatch { .. })
+        ));
+        assert_eq!(fs::read(&catalog_path).expect("read catalog"), before);
+
+        build_catalog_into(&docs, &catalog_path, BuildOptions::new()).expect("build catalog");
+        assert_eq!(
+            mismatches(provenance),
+            ["catalog was built without --with-provenance, check invoked with it"]
+        );
+    }
+
+    #[test]
+    fn streams_relation_items_before_summary() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &[]);
+        write_markdown(&docs, "hub.md", "hub", &["foo", "bar", "missing"]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, &catalog_output).expect("write catalog");
+
+        let mut output = Vec::new();
+        query_catalog_relation_with_options(
+            "hub",
+            &catalog_path,
+            RelationKind::Deps,
+            OutputFormat::Json,
+            &QueryOptions {
+                stream: true,
+                ..QueryOptions::default()
+            },
+            &mut output,
+        )
+        .expect("stream deps");
+        let lines = String::from_utf8(output)
+            .expect("valid utf-8")
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("parse line"))
+            .collect::<Vec<_>>();
+
+        let ids = lines[..3]
+            .iter()
+            .map(|item| item["id"].as_str().unwrap_or_default())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["bar", "foo", "missing"]);
+        let summary = &lines[3]["summary"];
+        assert_eq!(summary["count"], 3);
+        assert_eq!(summary["query_resolved"], true);
+        assert_eq!(summary["meta"]["missing_nodes"][0], "missing");
+    }
+
+    #[test]
+    fn relation_items_embed_node_metadata_only_on_request() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            docs.join("api.md"),
+            "---\nid: api\ntags: [billing]\ntype: spec\ndomain: payments\nstatus: draft\n---\n",
+        )
+        .expect("write markdown");
+        write_markdown(&docs, "web.md", "web", &["api"]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(
+            &docs,
+            &catalog_path,
+            BuildOptions::new().include_node_metadata(true),
+        )
+        .expect("build catalog");
+        let item = |options: QueryOptions| {
+            let mut output = Vec::new();
+            query_catalog_relation_with_options(
+                "web",
+                &catalog_path,
+                RelationKind::Deps,
+                OutputFormat::Json,
+                &options,
+                &mut output,
+            )
+            .expect("query deps");
+            let json: serde_json::Value = serde_json::from_slice(&output).expect("parse json");
+            json["items"][0].clone()
+        };
+
+        let plain = item(QueryOptions::new());
+        assert_eq!(plain["id"], "api");
+        for field in ["tags", "type", "domain", "status", "source_of_truth"] {
+            assert!(plain.get(field).is_none(), "{field} embedded by default");
+        }
+
+        let embedded = item(QueryOptions::new().include_node_metadata(true));
+        assert_eq!(embedded["tags"], serde_json::Value::from(["billing"]));
+        assert_eq!(embedded["type"], "spec");
+        assert_eq!(embedded["domain"], "payments");
+        assert_eq!(embedded["status"], "draft");
+        assert!(embedded["source_of_truth"].is_null());
+    }
+
+    #[test]
+    fn embedded_node_metadata_skips_missing_documents_and_text_output() {
+        let workspace = TestWorkspace::new();
+        let catalog_path = workspace.path().join("catalog.json");
+        fs::write(
+            &catalog_path,
+            r#"{"nodes": [
+                {"id": "api", "path": "api.md", "tags": ["billing"], "type": "spec"},
+                {"id": "db", "path": "db.md", "tags": [], "type": "store"},
+                {"id": "web", "path": "web.md", "tags": []}
+            ], "edges": [
+                {"from": "web", "to": "api"},
+                {"from": "web", "to": "missing"},
+                {"from": "api", "to": "db"}
+            ]}"#,
+        )
+        .expect("write catalog");
+        let options = QueryOptions::new().include_node_metadata(true);
+        let query = |query_ids: &[&str], format| {
+            let mut output = Vec::new();
+            query_catalog_relations(
+                query_ids,
+                &catalog_path,
+                RelationKind::Deps,
+                format,
+                &options,
+                &mut output,
+            )
+            .map(|()| String::from_utf8(output).expect("valid utf-8"))
+        };
+
+        let json = query(&["web"], OutputFormat::Json).expect("query deps");
+        let json: serde_json::Value = serde_json::from_str(&json).expect("parse json");
+        assert_eq!(json["items"][0]["type"], "spec");
+        assert_eq!(json["items"][1]["id"], "missing");
+        assert_eq!(json["items"][1]["resolved"], false);
+        assert!(json["items"][1].get("type").is_none());
+
+        let json = query(&["web", "api"], OutputFormat::Json).expect("query deps of two ids");
+        let json: serde_json::Value = serde_json::from_str(&json).expect("parse json");
+        let db = json["items"]
+            .as_array()
+            .expect("items")
+            .iter()
+            .find(|item| item["id"] == "db")
+            .expect("db item");
+        assert_eq!(db["type"], "store");
+
+        let text = query(&["web"], OutputFormat::Text).expect("query deps as text");
+        assert!(!text.contains("spec"), "{text}");
+
+        let strict = QueryOptions::new().include_node_metadata(true).strict(true);
+        let unknown = query_catalog_relation_with_options(
+            "nope",
+            &catalog_path,
+            RelationKind::Deps,
+            OutputFormat::Json,
+            &strict,
+            &mut Vec::new(),
+        );
+        assert!(matches!(unknown, Err(Error::QueryIdNotFound { .. })));
+    }
+
+    #[test]
+    fn ndjson_writes_one_object_per_line() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "web.md", "web", &["api", "db"]);
+        write_markdown(&docs, "api.md", "api", &["db"]);
+        write_markdown(&docs, "db.md", "db", &[]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+        let ids = |output: Vec<u8>| {
+            String::from_utf8(output)
+                .expect("valid utf-8")
+                .lines()
+                .map(|line| {
+                    let item: serde_json::Value = serde_json::from_str(line).expect("parse line");
+                    assert!(item.is_object(), "{line} is not an object");
+                    item["id"].as_str().expect("id").to_owned()
+                })
+                .collect::<Vec<_>>()
+        };
+        let query = |id: &str, relation_kind: RelationKind| {
+            let mut output = Vec::new();
+            query_catalog_relation_with_options(
+                id,
+                &catalog_path,
+                relation_kind,
+                OutputFormat::Ndjson,
+                &QueryOptions::new(),
+                &mut output,
+            )
+            .expect("query catalog");
+            output
+        };
+        let list = |tags: Vec<String>| {
+            let mut output = Vec::new();
+            list_catalog_nodes(
+                &catalog_path,
+                &ListOptions {
+                    tags,
+                    ..ListOptions::default()
+                },
+                OutputFormat::Ndjson,
+                &mut output,
+            )
+            .expect("list nodes");
+            output
+        };
+
+        assert_eq!(ids(query("web", RelationKind::Deps)), ["api", "db"]);
+        assert_eq!(ids(query("db", RelationKind::Refs)), ["api", "web"]);
+        assert_eq!(ids(list(Vec::new())), ["api", "db", "web"]);
+
+        assert!(query("db", RelationKind::Deps).is_empty());
+        assert!(query("web", RelationKind::Refs).is_empty());
+        build_catalog_into(
+            &docs,
+            &catalog_path,
+            BuildOptions::new().include_node_metadata(true),
+        )
+        .expect("build catalog");
+        assert!(list(vec!["untagged".to_owned()]).is_empty());
+    }
+
+    #[test]
+    fn ndjson_merges_several_ids_and_is_refused_by_reports() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "web.md", "web", &["api", "db"]);
+        write_markdown(&docs, "cli.md", "cli", &["api"]);
+        write_markdown(&docs, "api.md", "api", &[]);
+        write_markdown(&docs, "db.md", "db", &[]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::default()).expect("build catalog");
+        let lines = |output: Vec<u8>| {
+            String::from_utf8(output)
+                .expect("valid utf-8")
+                .lines()
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).expect("parse line"))
+                .collect::<Vec<_>>()
+        };
+
+        let mut output = Vec::new();
+        query_catalog_relations(
+            &["web", "cli"],
+            &catalog_path,
+            RelationKind::Deps,
+            OutputFormat::Ndjson,
+            &QueryOptions::new().combine(RelationCombine::Intersection),
+            &mut output,
+        )
+        .expect("query deps of two ids");
+        let items = lines(output);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0]["id"], "api");
+        assert!(items[0].get("groups").is_none());
+
+        let mut output = Vec::new();
+        list_catalog_endpoints(
+            &catalog_path,
+            GraphEndpoint::Roots,
+            &ListOptions::default(),
+            OutputFormat::Ndjson,
+            &mut output,
+        )
+        .expect("list roots");
+        let roots = lines(output)
+            .iter()
+            .map(|item| item["id"].as_str().expect("id").to_owned())
+            .collect::<Vec<_>>();
+        assert_eq!(roots, ["cli", "web"]);
+
+        let mut output = Vec::new();
+        let Err(error) = catalog_stats(&catalog_path, OutputFormat::Ndjson, &mut output) else {
+            panic!("stats should refuse ndjson");
+        };
+        assert!(error.to_string().contains("ndjson"), "{error}");
+        assert!(output.is_empty());
+        let shown = show_catalog_node("web", &catalog_path, OutputFormat::Ndjson, &mut Vec::new());
+        assert!(shown.is_err());
+    }
+
+    #[test]
+    fn tag_filters_fail_on_catalogs_without_node_metadata() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(docs.join("foo.md"), "---\nid: foo\ntags: [api]\n---\n").expect("write markdown");
+        write_markdown(&docs, "baz.md", "baz", &["foo"]);
+        let catalog_path = workspace.path().join("catalog.json");
+        let tagged = QueryOptions::new().tags(vec!["api".to_owned()]);
+        let query = |options: &QueryOptions| {
+            query_catalog_relation_with_options(
+                "baz",
+                &catalog_path,
+                RelationKind::Deps,
+                OutputFormat::Text,
+                options,
+                &mut Vec::new(),
+            )
+        };
+
+        for write_index in [false, true] {
+            build_catalog_into(
+                &docs,
+                &catalog_path,
+                BuildOptions::new().write_index(write_index),
+            )
+            .expect("build catalog");
+            let Err(error) = query(&tagged) else {
+                panic!("tag filter should need node metadata");
+            };
+            assert_eq!(error.code(), "E027");
+            query(&QueryOptions::new()).expect("query deps unfiltered");
+        }
+        let listed = list_catalog_nodes(
+            &catalog_path,
+            &ListOptions {
+                tags: vec!["api".to_owned()],
+                ..ListOptions::default()
+            },
+            OutputFormat::Text,
+            &mut Vec::new(),
+        );
+        assert!(matches!(listed, Err(Error::NodeMetadataMissing { .. })));
+
+        for write_index in [false, true] {
+            build_catalog_into(
+                &docs,
+                &catalog_path,
+                BuildOptions::new()
+                    .include_node_metadata(true)
+                    .write_index(write_index),
+            )
+            .expect("build catalog");
+            query(&tagged).expect("query deps by tag");
+        }
+    }
+
+    #[test]
+    fn filters_list_and_relations_by_tags() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            docs.join("foo.md"),
+            "---\nid: foo\ntags: [billing, api]\n---\n",
+        )
+        .expect("write markdown");
+        fs::write(docs.join("bar.md"), "---\nid: bar\ntags: [billing]\n---\n")
+            .expect("write markdown");
+        write_markdown(&docs, "baz.md", "baz", &["foo", "bar"]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog_with_options(
+            &docs,
+            &mut catalog_output,
+            BuildOptions {
+                include_node_metadata: true,
+                ..BuildOptions::default()
+            },
+        )
+        .expect("build catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
+
+        let mut output = Vec::new();
+        list_catalog_nodes(
+            &catalog_path,
+            &ListOptions {
+                tags: vec!["billing".to_owned()],
+                ..ListOptions::default()
+            },
+            OutputFormat::Text,
+            &mut output,
+        )
+        .expect("list nodes");
+        assert_eq!(
+            String::from_utf8(output).expect("valid utf-8"),
+            "bar\nfoo\n"
+        );
+
+        let mut output = Vec::new();
+        list_catalog_nodes(
+            &catalog_path,
+            &ListOptions {
+                tags: vec!["api".to_owned()],
+                ..ListOptions::default()
+            },
+            OutputFormat::Ndjson,
+            &mut output,
+        )
+        .expect("list nodes as ndjson");
+        let output = String::from_utf8(output).expect("valid utf-8");
+        let lines = output.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 1);
+        let item: serde_json::Value = serde_json::from_str(lines[0]).expect("parse line");
+        assert_eq!(item["id"], "foo");
+
+        let mut output = Vec::new();
+        query_catalog_relation_with_options(
+            "baz",
+            &catalog_path,
+            RelationKind::Deps,
+            OutputFormat::Text,
+            &QueryOptions {
+                tags: vec!["api".to_owned()],
+                ..QueryOptions::default()
+            },
+            &mut output,
+        )
+        .expect("query deps");
+        assert_eq!(String::from_utf8(output).expect("valid utf-8"), "foo\n");
+
+        let mut output = Vec::new();
+        query_catalog_relation_with_options(
+            "baz",
+            &catalog_path,
+            RelationKind::Deps,
+            OutputFormat::Json,
+            &QueryOptions {
+                include_node_metadata: true,
+                ..QueryOptions::default()
+            },
             &mut output,
+        )
+        .expect("query deps with metadata");
+        let json: serde_json::Value = serde_json::from_slice(&output).expect("parse json");
+        assert_eq!(json["items"][1]["id"], "foo");
+        assert_eq!(
+            json["items"][1]["tags"],
+            serde_json::Value::from(["api", "billing"])
         );
-        assert!(matches!(
-            strict_result,
-            Err(Error::QueryIdNotFound { query_id }) if query_id == "missing"
-        ));
+        assert!(json["items"][1]["status"].is_null());
+    }
 
-        let non_strict_result = query_catalog_relation_with_options(
-            "missing",
+    #[test]
+    fn lists_documents_last_modified_before_a_date() {
+        let workspace = TestWorkspace::new();
+        let catalog_path = workspace.path().join("catalog.json");
+        fs::write(
             &catalog_path,
-            RelationKind::Deps,
+            r#"{"build_options": {"with_node_metadata": false, "duplicate_ids": "error", "edge_sources": ["deps"], "with_git_metadata": true},
+            "nodes": [
+                {"id": "api", "path": "api.md", "last_modified": "2024-03-01", "authors": ["Ann"]},
+                {"id": "db", "path": "db.md", "last_modified": "2025-11-20"},
+                {"id": "web", "path": "web.md"}
+            ], "edges": []}"#,
+        )
+        .expect("write catalog");
+
+        let mut output = Vec::new();
+        list_catalog_nodes(
+            &catalog_path,
+            &ListOptions {
+                modified_before: Some("2025-01-01".to_owned()),
+                ..ListOptions::default()
+            },
             OutputFormat::Json,
-            QueryOptions { strict: false },
             &mut output,
+        )
+        .expect("list nodes");
+
+        let json: serde_json::Value = serde_json::from_slice(&output).expect("parse json");
+        assert_eq!(json["count"], 1);
+        assert_eq!(json["items"][0]["id"], "api");
+        assert_eq!(json["items"][0]["last_modified"], "2024-03-01");
+    }
+
+    #[test]
+    fn fails_to_list_by_fields_the_catalog_was_built_without() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "api.md", "api", &[]);
+        let catalog_path = workspace.path().join("catalog.json");
+        build_catalog_into(&docs, &catalog_path, BuildOptions::new()).expect("build catalog");
+
+        for (options, option) in [
+            (
+                ListOptions {
+                    modified_before: Some("2025-01-01".to_owned()),
+                    ..ListOptions::default()
+                },
+                "--with-git-metadata",
+            ),
+            (
+                ListOptions {
+                    owner: Some("@org/billing".to_owned()),
+                    ..ListOptions::default()
+                },
+                "--with-code-owners",
+            ),
+        ] {
+            let listed =
+                list_catalog_nodes(&catalog_path, &options, OutputFormat::Text, &mut Vec::new());
+            let Err(error) = listed else {
+                panic!("{option} filter should need the option");
+            };
+            assert_eq!(error.code(), "E028");
+            assert!(error.to_string().contains(option), "{error}");
+            let listed = list_catalog_endpoints(
+                &catalog_path,
+                GraphEndpoint::Roots,
+                &options,
+                OutputFormat::Text,
+                &mut Vec::new(),
+            );
+            assert!(matches!(listed, Err(Error::BuildOptionMissing { .. })));
+        }
+        list_catalog_nodes(
+            &catalog_path,
+            &ListOptions::default(),
+            OutputFormat::Text,
+            &mut Vec::new(),
+        )
+        .expect("list unfiltered");
+    }
+
+    #[test]
+    fn queries_several_ids_with_union_or_intersection() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "a.md", "a", &["shared", "only-a"]);
+        write_markdown(&docs, "b.md", "b", &["shared", "only-b"]);
+        write_markdown(&docs, "shared.md", "shared", &[]);
+        write_markdown(&docs, "only-a.md", "only-a", &[]);
+        write_markdown(&docs, "only-b.md", "only-b", &[]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
+
+        let query = |combine: RelationCombine, format: OutputFormat| {
+            let mut output = Vec::new();
+            query_catalog_relations(
+                &["a", "b", "a"],
+                &catalog_path,
+                RelationKind::Deps,
+                format,
+                &QueryOptions {
+                    combine,
+                    ..QueryOptions::default()
+                },
+                &mut output,
+            )
+            .expect("query deps");
+            String::from_utf8(output).expect("valid utf-8")
+        };
+
+        assert_eq!(
+            query(RelationCombine::Union, OutputFormat::Text),
+            "only-a\nonly-b\nshared\n"
         );
-        assert!(non_strict_result.is_ok());
+        assert_eq!(
+            query(RelationCombine::Intersection, OutputFormat::Text),
+            "shared\n"
+        );
+
+        let json = query(RelationCombine::Intersection, OutputFormat::Json);
+        assert!(json.contains("\"combine\": \"intersection\""));
+        assert!(json.contains("\"query_ids\": [\n    \"a\",\n    \"b\"\n  ]"));
+        assert_eq!(json.matches("\"query_id\":").count(), 2);
     }
 
     #[test]
-    fn check_catalog_requires_no_regeneration_diff() {
+    fn show_reports_node_with_deps_and_refs() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &[]);
+        write_markdown(&docs, "bar.md", "bar", &["foo", "missing"]);
+        write_markdown(&docs, "baz.md", "baz", &["bar"]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
+
+        let mut output = Vec::new();
+        show_catalog_node("bar", &catalog_path, OutputFormat::Text, &mut output)
+            .expect("show node");
+        let text = String::from_utf8(output).expect("valid utf-8");
+        assert!(text.starts_with("id: bar\n"));
+        assert!(text.contains("deps: 2\n"));
+        assert!(text.contains("  - missing (unresolved)\n"));
+        assert!(text.contains("refs: 1\n"));
+
+        let result = show_catalog_node(
+            "missing",
+            &catalog_path,
+            OutputFormat::Json,
+            &mut Vec::new(),
+        );
+        assert!(matches!(result, Err(Error::NodeNotFound { id }) if id == "missing"));
+    }
+
+    #[test]
+    fn validate_returns_report_without_error() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "foo.md", "foo", &["missing"]);
+        write_markdown(&docs, "bar.md", "bar", &["foo"]);
+
+        let outcome = validate(&docs).expect("validate docs");
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.document_count, 2);
+        assert_eq!(outcome.report.unresolved_dependencies.len(), 1);
+        assert_eq!(outcome.report.unresolved_dependencies[0].to_id, "missing");
+    }
+
+    #[test]
+    fn check_recursive_reports_each_root() {
+        let workspace = TestWorkspace::new();
+        let good = workspace.path().join("good");
+        let bad = workspace.path().join("nested/bad");
+        write_markdown(&good.join("docs"), "foo.md", "foo", &[]);
+        write_markdown(&bad.join("pages"), "bar.md", "bar", &["missing"]);
+        fs::write(
+            good.join("docata.toml"),
+            "[build]\ncatalog = \"docs/catalog.json\"\n",
+        )
+        .expect("write config");
+        fs::write(bad.join("docata.toml"), "[build]\ndocs = \"pages\"\n").expect("write config");
+
+        let mut catalog_output = Vec::new();
+        build_catalog(&good.join("docs"), &mut catalog_output).expect("build catalog");
+        let catalog = String::from_utf8(catalog_output).expect("valid utf-8");
+        let relative_catalog = catalog.replace(&format!("{}/", good.display()), "");
+        fs::write(good.join("docs/catalog.json"), relative_catalog).expect("write catalog");
+
+        let mut output = Vec::new();
+        let result = check_recursive(workspace.path(), OutputFormat::Text, &mut output);
+        assert!(matches!(result, Err(Error::CheckFailed { findings: 1 })));
+
+        let text = String::from_utf8(output).expect("valid utf-8");
+        assert!(text.contains("catalog.json: up_to_date"));
+        assert!(text.contains("`bar` -> `missing`"));
+        assert!(text.ends_with("checked 2 roots: 1 passed, 1 failed\n"));
+    }
+
+    #[test]
+    fn adoption_writes_frontmatter_with_link_deps() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(docs.join("guides")).expect("create docs directory");
+        write_markdown(&docs, "core.md", "core", &[]);
+        fs::write(
+            docs.join("guides/setup.md"),
+            "# Setup\nRead [core](../core.md) first.\n",
+        )
+        .expect("write setup");
+
+        let plan = plan_adoption(&docs, &AdoptionResolutions::default()).expect("plan adoption");
+        assert!(plan.conflicts.is_empty());
+        assert_eq!(plan.proposals.len(), 1);
+        let proposal = &plan.proposals[0];
+        assert_eq!(proposal.id, "guides-setup");
+        assert_eq!(proposal.title.as_deref(), Some("Setup"));
+        assert_eq!(proposal.deps, vec!["core".to_owned()]);
+
+        apply_adoption(proposal).expect("apply adoption");
+        assert!(
+            plan_adoption(&docs, &AdoptionResolutions::default())
+                .expect("plan again")
+                .proposals
+                .is_empty()
+        );
+        assert!(validate(&docs).expect("validate docs").is_success());
+        assert!(apply_adoption(proposal).is_err());
+    }
+
+    #[test]
+    fn adoption_reports_conflicts_until_resolved() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(docs.join("a")).expect("create docs directory");
+        fs::create_dir_all(docs.join("b")).expect("create docs directory");
+        write_markdown(&docs, "a-setup.md", "a-setup", &[]);
+        fs::write(docs.join("a/setup.md"), "# A\n").expect("write a/setup");
+        fs::write(docs.join("b/setup.md"), "# B\n").expect("write b/setup");
+        fs::write(docs.join("intro.md"), "See [setup](setup.md).\n").expect("write intro");
+
+        let plan = plan_adoption(&docs, &AdoptionResolutions::default()).expect("plan adoption");
+        assert_eq!(plan.conflicts.len(), 2);
+        assert!(plan.conflicts.iter().any(|conflict| matches!(
+            conflict,
+            AdoptionConflict::IdCollision { id, existing_path: Some(_), documents }
+                if id == "a-setup" && documents.len() == 1
+        )));
+        assert!(plan.conflicts.iter().any(|conflict| matches!(
+            conflict,
+            AdoptionConflict::AmbiguousLink { target, candidates, .. }
+                if target == "setup.md" && candidates.len() == 2
+        )));
+
+        let mut resolutions = AdoptionResolutions::default();
+        resolutions
+            .ids
+            .insert("a/setup.md".to_owned(), "alpha-setup".to_owned());
+        resolutions
+            .links
+            .entry("intro.md".to_owned())
+            .or_default()
+            .insert("setup.md".to_owned(), Some("b-setup".to_owned()));
+        let resolutions_path = workspace.path().join("resolutions.json");
+        resolutions
+            .save(&resolutions_path)
+            .expect("save resolutions");
+        let resolutions = AdoptionResolutions::load(&resolutions_path).expect("load resolutions");
+
+        let plan = plan_adoption(&docs, &resolutions).expect("plan with resolutions");
+        assert!(plan.conflicts.is_empty());
+        let intro = plan
+            .proposals
+            .iter()
+            .find(|proposal| proposal.id == "intro")
+            .expect("intro proposal");
+        assert_eq!(intro.deps, vec!["b-setup".to_owned()]);
+    }
+
+    #[test]
+    fn path_explains_transitive_dependency() {
         let workspace = TestWorkspace::new();
         let docs = workspace.path().join("docs");
         fs::create_dir_all(&docs).expect("create docs directory");
         write_markdown(&docs, "foo.md", "foo", &[]);
         write_markdown(&docs, "bar.md", "bar", &["foo"]);
+        write_markdown(&docs, "baz.md", "baz", &["bar"]);
 
         let catalog_path = workspace.path().join("catalog.json");
         let mut catalog_output = Vec::new();
         build_catalog(&docs, &mut catalog_output).expect("build catalog");
-        fs::write(&catalog_path, &catalog_output).expect("write catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
 
-        check_catalog(&docs, &catalog_path, BuildOptions::default())
-            .expect("check should pass for up-to-date catalog");
+        let mut output = Vec::new();
+        find_catalog_paths(
+            "baz",
+            "foo",
+            &catalog_path,
+            false,
+            OutputFormat::Text,
+            &mut output,
+        )
+        .expect("find path");
+        assert_eq!(
+            String::from_utf8(output).expect("valid utf-8"),
+            "baz -> bar -> foo\n"
+        );
 
-        fs::write(&catalog_path, "{}").expect("break catalog content");
-        let result = check_catalog(&docs, &catalog_path, BuildOptions::default());
-        assert!(matches!(result, Err(Error::CatalogDiff { .. })));
+        let mut output = Vec::new();
+        find_catalog_paths(
+            "foo",
+            "baz",
+            &catalog_path,
+            true,
+            OutputFormat::Json,
+            &mut output,
+        )
+        .expect("find reverse path");
+        let json: serde_json::Value = serde_json::from_slice(&output).expect("valid json");
+        assert_eq!(json["found"], false);
+        assert_eq!(json["count"], 0);
+
+        let result = find_catalog_paths(
+            "nope",
+            "foo",
+            &catalog_path,
+            false,
+            OutputFormat::Text,
+            &mut Vec::new(),
+        );
+        assert!(matches!(result, Err(Error::NodeNotFound { id }) if id == "nope"));
+    }
+
+    #[test]
+    fn freshness_flags_deps_changed_after_review() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "upstream.md", "upstream", &[]);
+        fs::write(
+            docs.join("reviewed.md"),
+            "---\nid: reviewed\ndeps:\n  - upstream\nlast_reviewed: 2000-01-01\n---\n",
+        )
+        .expect("write reviewed");
+        fs::write(
+            docs.join("recent.md"),
+            "---\nid: recent\ndeps:\n  - upstream\nlast_reviewed: 9999-12-31\n---\n",
+        )
+        .expect("write recent");
+
+        let mut output = Vec::new();
+        let report =
+            report_freshness(&docs, OutputFormat::Text, &mut output).expect("report freshness");
+        assert!(!report.is_fresh());
+        assert_eq!(report.stale.len(), 1);
+        assert_eq!(report.stale[0].id, "reviewed");
+        assert_eq!(report.stale[0].changed_deps[0].id, "upstream");
+        assert_eq!(report.unreviewed, vec!["upstream".to_owned()]);
+
+        let text = String::from_utf8(output).expect("valid utf-8");
+        assert!(text.starts_with("reviewed ("));
+        assert!(text.ends_with("1 stale, 1 unreviewed, 0 invalid review date(s)\n"));
+    }
+
+    #[test]
+    fn review_queue_ranks_documents_with_recent_upstream_changes_first() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "upstream.md", "upstream", &[]);
+        fs::write(
+            docs.join("reviewed.md"),
+            "---\nid: reviewed\ndeps:\n  - upstream\nlast_reviewed: 2000-01-01\n---\n",
+        )
+        .expect("write reviewed");
+        fs::write(
+            docs.join("retired.md"),
+            "---\nid: retired\nstatus: deprecated\ndeps:\n  - upstream\n---\n",
+        )
+        .expect("write retired");
+
+        let mut output = Vec::new();
+        let queue = build_review_queue(
+            &docs,
+            &ReviewQueueOptions::default(),
+            ReviewQueueFormat::Markdown,
+            &mut output,
+        )
+        .expect("build review queue");
+        let ids = queue
+            .items
+            .iter()
+            .map(|item| item.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(ids, vec!["reviewed", "upstream"]);
+        assert_eq!(queue.items[0].changed_deps, vec!["upstream".to_owned()]);
+        assert_eq!(queue.items[0].recent_changes, vec!["upstream".to_owned()]);
+        assert_eq!(queue.items[1].fan_in, 1);
+
+        let markdown = String::from_utf8(output).expect("valid utf-8");
+        assert!(markdown.contains("| [ ] | [reviewed]("));
+        assert!(markdown.contains("changed recently: upstream"));
+    }
+
+    #[test]
+    fn route_groups_findings_by_owner() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "api.md", "api", &[]);
+        fs::write(
+            docs.join("guide.md"),
+            "---\nid: guide\nowner: team-docs\ndeps:\n  - api\n  - missing\n---\n",
+        )
+        .expect("write guide");
+
+        let mut output = Vec::new();
+        let report = route_findings(&docs, &["api".to_owned()], OutputFormat::Json, &mut output)
+            .expect("route findings");
+        assert_eq!(report.routes.len(), 1);
+        assert_eq!(report.routes[0].owner.as_deref(), Some("team-docs"));
+        assert_eq!(report.finding_count(), 2);
+
+        let json: serde_json::Value = serde_json::from_slice(&output).expect("valid json");
+        assert_eq!(json["command"], "route");
+        assert_eq!(
+            json["routes"][0]["findings"][0]["kind"],
+            "unresolved_dependency"
+        );
+        assert_eq!(json["routes"][0]["findings"][1]["kind"], "impacted");
+    }
+
+    #[test]
+    fn export_findings_skips_issues_recorded_in_state() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            docs.join("guide.md"),
+            "---\nid: guide\nowner: team-docs\ndomain: billing\ndeps:\n  - missing\n---\n",
+        )
+        .expect("write guide");
+        let options = FindingsExportOptions {
+            state_path: Some(workspace.path().join("state.json")),
+            link_base: Some("https://example.com/blob/main/".to_owned()),
+        };
+
+        let mut output = Vec::new();
+        let issues = export_findings(&docs, &options, IssueFormat::GithubIssues, &mut output)
+            .expect("export findings");
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].labels.contains(&"owner:team-docs".to_owned()));
+        assert!(issues[0].labels.contains(&"domain:billing".to_owned()));
+        let json: serde_json::Value = serde_json::from_slice(&output).expect("valid json");
+        assert!(
+            json[0]["body"]
+                .as_str()
+                .is_some_and(|body| body.contains("(https://example.com/blob/main/"))
+        );
+
+        let issues = export_findings(&docs, &options, IssueFormat::JiraCsv, &mut Vec::new())
+            .expect("export findings again");
+        assert!(issues.is_empty());
+    }
+
+    #[test]
+    fn orphan_rule_is_opt_in_and_skips_root_types() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            docs.join("index.md"),
+            "---\nid: index\ntype: index\ndeps:\n  - guide\n---\n",
+        )
+        .expect("write index");
+        write_markdown(&docs, "guide.md", "guide", &[]);
+        write_markdown(&docs, "lost.md", "lost", &[]);
+
+        assert!(validate(&docs).expect("validate docs").is_success());
+
+        let rules = ValidationRules {
+            orphans: true,
+            orphan_root_types: vec!["index".to_owned()],
+            ..ValidationRules::default()
+        };
+        let outcome = validate_with_rules(&docs, &rules).expect("validate with rules");
+        let orphans = outcome
+            .report
+            .orphan_nodes
+            .iter()
+            .map(|orphan| orphan.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(orphans, vec!["lost"]);
+    }
+
+    #[test]
+    fn broken_link_rule_reports_missing_and_uncataloged_targets() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            docs.join("guide.md"),
+            "---\nid: guide\n---\n# Guide\n\nSee [api](api.md), [draft](draft.md),\n[gone](old/gone.md#intro) and ![chart](chart.png).\n",
+        )
+        .expect("write guide");
+        write_markdown(&docs, "api.md", "api", &[]);
+        fs::write(docs.join("draft.md"), "# Draft\n").expect("write draft");
+        fs::write(docs.join("chart.png"), "").expect("write chart");
+
+        assert!(validate(&docs).expect("validate docs").is_success());
+
+        let rules = ValidationRules {
+            broken_links: true,
+            ..ValidationRules::default()
+        };
+        let outcome = validate_with_rules(&docs, &rules).expect("validate with rules");
+        let broken = outcome
+            .report
+            .broken_links
+            .iter()
+            .map(|link| (link.line, link.target.as_str(), link.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            broken,
+            vec![
+                (6, "draft.md", BrokenLinkReason::NotInCatalog),
+                (7, "old/gone.md", BrokenLinkReason::Missing),
+            ]
+        );
+    }
+
+    #[test]
+    fn skipped_files_are_reported_when_requested() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "api.md", "api", &[]);
+        fs::write(docs.join("notes.md"), "# Notes\n").expect("write notes");
+        fs::write(docs.join("untitled.md"), "---\ntitle: Untitled\n---\n").expect("write untitled");
+
+        let result = build_catalog(&docs, &mut Vec::new());
+        assert!(matches!(
+            result,
+            Err(Error::Scan(ScanError::MissingId { path })) if path.ends_with("untitled.md")
+        ));
+
+        let options = BuildOptions {
+            report_skipped: true,
+            ..BuildOptions::default()
+        };
+        let report =
+            build_catalog_with_options(&docs, &mut Vec::new(), options).expect("build catalog");
+        let skipped = report
+            .skipped
+            .iter()
+            .map(|file| (file.path.file_name(), file.reason))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            skipped,
+            vec![
+                (Some("notes.md".as_ref()), SkipReason::NoFrontmatter),
+                (Some("untitled.md".as_ref()), SkipReason::MissingId),
+            ]
+        );
+
+        let mut output = Vec::new();
+        write_build_report(&report, OutputFormat::Json, &mut output).expect("write report");
+        let json = String::from_utf8(output).expect("valid utf-8");
+        assert!(json.contains("\"reason\": \"missing_id\""));
+
+        let rules = ValidationRules {
+            report_skipped: true,
+            ..ValidationRules::default()
+        };
+        let outcome = validate_with_rules(&docs, &rules).expect("validate with rules");
+        assert!(outcome.is_success());
+        assert_eq!(outcome.report.skipped.len(), 2);
+    }
+
+    #[test]
+    fn lenient_mode_collects_malformed_documents() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        write_markdown(&docs, "api.md", "api", &[]);
+        fs::write(docs.join("broken.md"), "---\nid: [unclosed\n---\n").expect("write broken");
+        fs::write(docs.join("worse.md"), "---\nid: worse\ndeps: {\n---\n").expect("write worse");
+
+        let result = build_catalog(&docs, &mut Vec::new());
+        assert!(matches!(
+            result,
+            Err(Error::Scan(ScanError::ParseYaml { .. }))
+        ));
+
+        let options = BuildOptions {
+            lenient: true,
+            ..BuildOptions::default()
+        };
+        let mut catalog = Vec::new();
+        let report = build_catalog_with_options(&docs, &mut catalog, options).expect("build");
+        assert_eq!(report.diagnostics.len(), 2);
+        assert!(report.diagnostics[0].path.ends_with("broken.md"));
+        let catalog = String::from_utf8(catalog).expect("valid utf-8");
+        assert!(catalog.contains("\"id\": \"api\""));
+
+        let rules = ValidationRules {
+            lenient: true,
+            ..ValidationRules::default()
+        };
+        let outcome = validate_with_rules(&docs, &rules).expect("validate with rules");
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.report.invalid_documents.len(), 2);
+        assert_eq!(outcome.document_count, 1);
+    }
+
+    #[test]
+    fn parse_errors_point_at_file_lines() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            docs.join("broken.md"),
+            "---\nid: broken\ntags: 5\ntitle: Broken\n---\n",
+        )
+        .expect("write broken");
+
+        let Err(Error::Scan(error)) = build_catalog(&docs, &mut Vec::new()) else {
+            panic!("expected a scan error");
+        };
+        assert_eq!(error.location(), Some((3, 7)));
+        assert!(error.to_string().contains("at line 3 column 7"));
+    }
+
+    #[test]
+    fn lists_roots_and_leaves() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        write_markdown(&docs, "api.md", "api", &["db"]);
+        write_markdown(&docs, "db.md", "db", &[]);
+        write_markdown(&docs, "guide.md", "guide", &["api", "missing"]);
+        write_markdown(&docs, "legacy.md", "legacy", &["missing"]);
+
+        let catalog_path = workspace.path().join("catalog.json");
+        let mut catalog_output = Vec::new();
+        build_catalog(&docs, &mut catalog_output).expect("build catalog");
+        fs::write(&catalog_path, catalog_output).expect("write catalog");
+
+        let list = |endpoint| {
+            let mut output = Vec::new();
+            list_catalog_endpoints(
+                &catalog_path,
+                endpoint,
+                &ListOptions::default(),
+                OutputFormat::Text,
+                &mut output,
+            )
+            .expect("list endpoints");
+            String::from_utf8(output).expect("valid utf-8")
+        };
+
+        assert_eq!(list(GraphEndpoint::Roots), "guide\nlegacy\n");
+        assert_eq!(list(GraphEndpoint::Leaves), "db\n");
+    }
+
+    /// Span names and fields recorded by a capturing subscriber.
+    #[cfg(feature = "tracing")]
+    mod spans {
+        use super::{
+            BuildOptions, OutputFormat, QueryOptions, RelationKind, build_catalog_into,
+            query_catalog_relation_with_options, query_catalog_relations,
+        };
+        use crate::testing::{TestWorkspace, write_markdown};
+        use std::collections::BTreeMap;
+        use std::fmt;
+        use std::sync::atomic::{AtomicU64, Ordering};
+        use std::sync::{Arc, Mutex};
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        type Span = (&'static str, BTreeMap<&'static str, String>);
+
+        #[derive(Clone, Default)]
+        struct Capture {
+            next_id: Arc<AtomicU64>,
+            spans: Arc<Mutex<Vec<Span>>>,
+        }
+
+        impl Capture {
+            /// Fields of every recorded span named `name`, in creation order.
+            fn named(
+                &self,
+                name: &str,
+            ) -> Vec<BTreeMap<&'static str, String>> {
+                self.spans
+                    .lock()
+                    .expect("lock spans")
+                    .iter()
+                    .filter(|(span, _)| *span == name)
+                    .map(|(_, fields)| fields.clone())
+                    .collect()
+            }
+        }
+
+        struct Fields<'f>(&'f mut BTreeMap<&'static str, String>);
+
+        impl Visit for Fields<'_> {
+            fn record_str(
+                &mut self,
+                field: &Field,
+                value: &str,
+            ) {
+                self.0.insert(field.name(), value.to_owned());
+            }
+
+            fn record_debug(
+                &mut self,
+                field: &Field,
+                value: &dyn fmt::Debug,
+            ) {
+                self.0.insert(field.name(), format!("{value:?}"));
+            }
+        }
+
+        impl Subscriber for Capture {
+            fn enabled(
+                &self,
+                _: &Metadata<'_>,
+            ) -> bool {
+                true
+            }
+
+            fn new_span(
+                &self,
+                span: &Attributes<'_>,
+            ) -> Id {
+                let mut fields = BTreeMap::new();
+                span.record(&mut Fields(&mut fields));
+                self.spans
+                    .lock()
+                    .expect("lock spans")
+                    .push((span.metadata().name(), fields));
+                Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+            }
+
+            fn record(
+                &self,
+                _: &Id,
+                _: &Record<'_>,
+            ) {
+            }
+
+            fn record_follows_from(
+                &self,
+                _: &Id,
+                _: &Id,
+            ) {
+            }
+
+            fn event(
+                &self,
+                _: &Event<'_>,
+            ) {
+            }
+
+            fn enter(
+                &self,
+                _: &Id,
+            ) {
+            }
+
+            fn exit(
+                &self,
+                _: &Id,
+            ) {
+            }
+        }
+
+        #[test]
+        fn build_and_query_record_named_spans_with_fields() {
+            let workspace = TestWorkspace::new();
+            let docs = workspace.path().join("docs");
+            write_markdown(&docs, "web.md", "web", &["api"]);
+            write_markdown(&docs, "api.md", "api", &[]);
+            let catalog_path = workspace.path().join("catalog.json");
+
+            let capture = Capture::default();
+            tracing::subscriber::with_default(capture.clone(), || {
+                build_catalog_into(&docs, &catalog_path, BuildOptions::new())
+                    .expect("build catalog");
+                query_catalog_relation_with_options(
+                    "web",
+                    &catalog_path,
+                    RelationKind::Deps,
+                    OutputFormat::Json,
+                    &QueryOptions::new(),
+                    &mut Vec::new(),
+                )
+                .expect("query deps");
+                query_catalog_relations(
+                    &["api", "web"],
+                    &catalog_path,
+                    RelationKind::Refs,
+                    OutputFormat::Json,
+                    &QueryOptions::new(),
+                    &mut Vec::new(),
+                )
+                .expect("query refs");
+            });
+
+            let builds = capture.named("build");
+            assert_eq!(builds.len(), 1);
+            assert_eq!(builds[0].get("roots").map(String::as_str), Some("1"));
+
+            let queries = capture.named("query");
+            assert_eq!(queries.len(), 2);
+            assert_eq!(queries[0].get("id").map(String::as_str), Some("web"));
+            assert_eq!(queries[0].get("relation").map(String::as_str), Some("deps"));
+            assert_eq!(
+                queries[1].get("ids").map(String::as_str),
+                Some(r#"["api", "web"]"#)
+            );
+            assert_eq!(queries[1].get("relation").map(String::as_str), Some("refs"));
+
+            let loads = capture.named("load_filtered_catalog");
+            assert_eq!(loads.len(), 2);
+            assert!(
+                loads.iter().all(
+                    |fields| fields.get("catalog") == Some(&catalog_path.display().to_string())
+                )
+            );
+        }
     }
 }
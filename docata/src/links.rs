@@ -0,0 +1,119 @@
+use std::path::{Component, Path, PathBuf};
+
+/// A relative link in a Markdown body.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct MarkdownLink {
+    /// Link destination without its `#fragment`.
+    pub target: String,
+    /// 1-based line number of the link.
+    pub line: usize,
+}
+
+impl MarkdownLink {
+    /// Whether the link points at another Markdown document.
+    pub fn is_markdown(&self) -> bool {
+        Path::new(&self.target)
+            .extension()
+            .is_some_and(|ext| ext == "md")
+    }
+}
+
+/// Collect relative link destinations from inline Markdown links and images,
+/// skipping fenced code blocks, URLs, absolute paths, and in-page anchors.
+pub(crate) fn markdown_links(content: &str) -> Vec<MarkdownLink> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+
+        let mut rest = line;
+        while let Some(start) = rest.find("](") {
+            rest = &rest[start + 2..];
+            let Some(end) = rest.find(')') else {
+                break;
+            };
+            let destination = rest[..end].trim();
+            rest = &rest[end + 1..];
+
+            let destination = destination
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .trim_start_matches('<')
+                .trim_end_matches('>');
+            let path = destination.split('#').next().unwrap_or_default();
+            if path.is_empty()
+                || path.contains("://")
+                || path.starts_with('/')
+                || path.starts_with("mailto:")
+            {
+                continue;
+            }
+
+            links.push(MarkdownLink {
+                target: path.to_owned(),
+                line: index + 1,
+            });
+        }
+    }
+
+    links
+}
+
+/// Resolve `.` and `..` components without touching the filesystem.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {},
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push(component);
+                }
+            },
+            _ => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MarkdownLink, markdown_links};
+
+    #[test]
+    fn collects_relative_links_with_line_numbers() {
+        let content = "\
+See [setup](./setup.md#install) and [api](../api/overview.md \"API\").
+[site](https://example.com/page.md) [anchor](#top) ![diagram](diagram.png)
+```
+[ignored](ignored.md)
+```
+";
+
+        let link = |target: &str, line: usize| MarkdownLink {
+            target: target.to_owned(),
+            line,
+        };
+        let links = markdown_links(content);
+        assert_eq!(
+            links,
+            vec![
+                link("./setup.md", 1),
+                link("../api/overview.md", 1),
+                link("diagram.png", 2),
+            ]
+        );
+        assert!(!links[2].is_markdown());
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{ListOptions, catalog::Catalog, error::Error, format::OutputFormat};
+use std::collections::HashSet;
+use std::io::Write;
+
+/// Where a document sits in the dependency graph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphEndpoint {
+    /// No document depends on it: an entry point of the graph.
+    Roots,
+    /// It depends on nothing: an exit point of the graph.
+    Leaves,
+}
+
+impl GraphEndpoint {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            GraphEndpoint::Roots => "roots",
+            GraphEndpoint::Leaves => "leaves",
+        }
+    }
+}
+
+/// Run list command and write formatted output to the provided writer.
+///
+/// # Errors
+///
+/// Returns `Error` when writing fails.
+pub fn run<W: Write>(
+    catalog: &Catalog,
+    options: &ListOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let response =
+        crate::domain::build_filtered_list("list", catalog, |node| options.matches(node));
+
+    crate::list_presentation::write(&response, format, out)?;
+
+    Ok(())
+}
+
+/// List the catalog nodes at `endpoint` of the `deps` graph and write
+/// formatted output to the provided writer. Dependencies missing from the
+/// catalog still count, so a document depending only on them is no leaf.
+///
+/// # Errors
+///
+/// Returns `Error` when writing fails.
+pub fn run_endpoints<W: Write>(
+    catalog: &Catalog,
+    endpoint: GraphEndpoint,
+    options: &ListOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let linked = catalog
+        .dependency_edges()
+        .map(|edge| match endpoint {
+            GraphEndpoint::Roots => edge.to.as_str(),
+            GraphEndpoint::Leaves => edge.from.as_str(),
+        })
+        .collect::<HashSet<_>>();
+    let response = crate::domain::build_filtered_list(endpoint.as_str(), catalog, |node| {
+        options.matches(node) && !linked.contains(node.id.as_str())
+    });
+
+    crate::list_presentation::write(&response, format, out)?;
+
+    Ok(())
+}
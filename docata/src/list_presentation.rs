@@ -0,0 +1,122 @@
+use crate::domain::{ListItem, ListResponse};
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct ListItemJson {
+    id: String,
+    path: String,
+    title: Option<String>,
+    tags: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    owners: Vec<String>,
+}
+
+impl From<&ListItem> for ListItemJson {
+    fn from(item: &ListItem) -> Self {
+        Self {
+            id: item.id.clone(),
+            path: item.path.clone(),
+            title: item.title.clone(),
+            tags: item.tags.clone(),
+            last_modified: item.last_modified.clone(),
+            owners: item.owners.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ListResponseJson {
+    command: String,
+    count: usize,
+    items: Vec<ListItemJson>,
+}
+
+impl From<&ListResponse> for ListResponseJson {
+    fn from(response: &ListResponse) -> Self {
+        Self {
+            command: response.command.to_owned(),
+            count: response.count,
+            items: response.items.iter().map(ListItemJson::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ListPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a node listing according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `ListPresentationError` if JSON serialization or writing fails.
+pub fn write<W: Write>(
+    response: &ListResponse,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ListPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(response, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(response, format, out),
+        OutputFormat::Yaml => write_yaml(response, out),
+        OutputFormat::Ndjson => write_ndjson(response, out),
+        OutputFormat::Sarif | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(ListPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+/// Write one item per line.
+fn write_ndjson<W: Write>(
+    response: &ListResponse,
+    out: &mut W,
+) -> Result<(), ListPresentationError> {
+    for item in &response.items {
+        json::write(out, &ListItemJson::from(item), OutputFormat::Ndjson)?;
+    }
+    Ok(())
+}
+
+fn write_yaml<W: Write>(
+    response: &ListResponse,
+    out: &mut W,
+) -> Result<(), ListPresentationError> {
+    yaml_serde::to_writer(out, &ListResponseJson::from(response))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    response: &ListResponse,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ListPresentationError> {
+    let response_json = ListResponseJson::from(response);
+
+    json::write(out, &response_json, format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    response: &ListResponse,
+    out: &mut W,
+) -> Result<(), ListPresentationError> {
+    for item in &response.items {
+        writeln!(out, "{}", item.id)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use crate::build::{self, RecordedOptions};
+use crate::catalog::{Catalog, Node};
+use crate::catalog_presentation::{self, CatalogPresentationError};
+use crate::format::{CatalogFormat, Compression};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Result of a three-way catalog merge.
+#[derive(Debug)]
+pub struct CatalogMerge {
+    /// The merged catalog, serialized like `ours`. Conflicting nodes keep
+    /// the version of `ours`.
+    pub content: Vec<u8>,
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// A change the merge could not reconcile; regenerating the catalog from
+/// the merged documents settles it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MergeConflict {
+    /// Both sides changed the node differently, or one changed it while the
+    /// other removed it.
+    Node { id: String },
+    /// The sides record different `build_options`.
+    BuildOptions,
+}
+
+struct Side {
+    catalog: Catalog,
+    options: Option<RecordedOptions>,
+}
+
+impl Side {
+    /// Parse one version of the catalog; an empty file, as git passes for a
+    /// catalog added on both sides, is an empty catalog.
+    fn read(
+        content: &[u8],
+        format: CatalogFormat,
+    ) -> Result<Self, CatalogPresentationError> {
+        if content.trim_ascii().is_empty() {
+            return Ok(Self {
+                catalog: Catalog {
+                    nodes: Vec::new(),
+                    edges: Vec::new(),
+                    relations: BTreeMap::new(),
+                    external: Vec::new(),
+                },
+                options: None,
+            });
+        }
+        Ok(Self {
+            catalog: catalog_presentation::decode(content, format)?,
+            options: RecordedOptions::read(content, format),
+        })
+    }
+}
+
+/// Merge the catalogs `ours` and `theirs` changed from `base` node by node
+/// and edge by edge.
+///
+/// A node changed on one side only takes that change; edges and external
+/// ids are merged as sets, and edges leaving removed nodes are dropped.
+pub(crate) fn merge(
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+    format: CatalogFormat,
+) -> Result<CatalogMerge, CatalogPresentationError> {
+    let compact = build::detect_compact(ours, format);
+    let compression = Compression::sniff(ours).unwrap_or_default();
+    let base_side = Side::read(base, format)?;
+    let ours_side = Side::read(ours, format)?;
+    let theirs_side = Side::read(theirs, format)?;
+    let mut conflicts = Vec::new();
+
+    let options = match (ours_side.options, theirs_side.options) {
+        (Some(ours), Some(theirs)) => {
+            if ours != theirs {
+                conflicts.push(MergeConflict::BuildOptions);
+            }
+            ours
+        },
+        (Some(options), None) | (None, Some(options)) => options,
+        (None, None) => {
+            crate::subgraph::unrecorded_options(build::detect_node_metadata(ours, format))
+        },
+    };
+
+    let mut versions = BTreeMap::<&str, [Option<&Node>; 3]>::new();
+    for (side, catalog) in [&base_side.catalog, &ours_side.catalog, &theirs_side.catalog]
+        .into_iter()
+        .enumerate()
+    {
+        for node in &catalog.nodes {
+            versions.entry(&node.id).or_default()[side] = Some(node);
+        }
+    }
+    let mut nodes = Vec::new();
+    for (id, [base, ours, theirs]) in versions {
+        let node = three_way(base, ours, theirs).unwrap_or_else(|ours| {
+            conflicts.push(MergeConflict::Node { id: id.to_owned() });
+            ours
+        });
+        nodes.extend(node.cloned());
+    }
+
+    let ids = nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<BTreeSet<_>>();
+    let edges = merge_sets(
+        &base_side.catalog.edges,
+        &ours_side.catalog.edges,
+        &theirs_side.catalog.edges,
+    )
+    .into_iter()
+    .filter(|edge| ids.contains(edge.from.as_str()))
+    .cloned()
+    .collect();
+    let external = merge_sets(
+        &base_side.catalog.external,
+        &ours_side.catalog.external,
+        &theirs_side.catalog.external,
+    )
+    .into_iter()
+    .filter(|id| !ids.contains(id.as_str()))
+    .cloned()
+    .collect();
+    let mut relations = ours_side.catalog.relations;
+    for (kind, definition) in theirs_side.catalog.relations {
+        relations.entry(kind).or_insert(definition);
+    }
+
+    let merged = Catalog {
+        nodes,
+        edges,
+        relations,
+        external,
+    };
+    let mut content = Vec::new();
+    catalog_presentation::write_catalog(
+        &merged,
+        &options,
+        &mut content,
+        options.with_node_metadata,
+        format,
+        compact,
+        compression,
+    )?;
+
+    Ok(CatalogMerge { content, conflicts })
+}
+
+/// The side that changed from `base`, or `Err(ours)` when both changed it
+/// differently.
+fn three_way<T: PartialEq + Copy>(
+    base: Option<T>,
+    ours: Option<T>,
+    theirs: Option<T>,
+) -> Result<Option<T>, Option<T>> {
+    if ours == theirs || theirs == base {
+        Ok(ours)
+    } else if ours == base {
+        Ok(theirs)
+    } else {
+        Err(ours)
+    }
+}
+
+/// Items in both sides, or added by one side and not in `base`, sorted.
+fn merge_sets<'a, T: Ord>(
+    base: &'a [T],
+    ours: &'a [T],
+    theirs: &'a [T],
+) -> BTreeSet<&'a T> {
+    let base = base.iter().collect::<BTreeSet<_>>();
+    let theirs = theirs.iter().collect::<BTreeSet<_>>();
+    let mut merged = ours
+        .iter()
+        .filter(|item| theirs.contains(item) || !base.contains(item))
+        .collect::<BTreeSet<_>>();
+    merged.extend(theirs.into_iter().filter(|item| !base.contains(item)));
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MergeConflict, merge};
+    use crate::catalog_presentation::read_catalog;
+    use crate::format::CatalogFormat;
+
+    const BASE: &str = r#"{"nodes": [{"id": "api", "path": "api.md"}, {"id": "db", "path": "db.md"}],
+        "edges": [{"from": "api", "to": "db"}]}"#;
+
+    fn ids(content: &[u8]) -> (Vec<String>, Vec<(String, String)>) {
+        let catalog = read_catalog(&mut &content[..], CatalogFormat::Json).expect("read catalog");
+        (
+            catalog.nodes.into_iter().map(|node| node.id).collect(),
+            catalog
+                .edges
+                .into_iter()
+                .map(|edge| (edge.from, edge.to))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn merges_changes_from_both_sides() {
+        let ours = r#"{"nodes": [{"id": "api", "path": "api.md"}, {"id": "db", "path": "db.md"},
+            {"id": "web", "path": "web.md"}],
+            "edges": [{"from": "api", "to": "db"}, {"from": "web", "to": "api"}]}"#;
+        let theirs = r#"{"nodes": [{"id": "api", "path": "api.md"}, {"id": "db", "path": "data/db.md"}],
+            "edges": []}"#;
+
+        let merged = merge(
+            BASE.as_bytes(),
+            ours.as_bytes(),
+            theirs.as_bytes(),
+            CatalogFormat::Json,
+        )
+        .expect("merge catalogs");
+
+        assert!(merged.conflicts.is_empty());
+        assert_eq!(
+            ids(&merged.content),
+            (
+                vec!["api".to_owned(), "db".to_owned(), "web".to_owned()],
+                vec![("web".to_owned(), "api".to_owned())]
+            )
+        );
+        assert!(String::from_utf8_lossy(&merged.content).contains("data/db.md"));
+    }
+
+    #[test]
+    fn reports_nodes_changed_on_both_sides() {
+        let ours = BASE.replace("db.md", "ours/db.md");
+        let theirs = BASE.replace("db.md", "theirs/db.md");
+
+        let merged = merge(
+            BASE.as_bytes(),
+            ours.as_bytes(),
+            theirs.as_bytes(),
+            CatalogFormat::Json,
+        )
+        .expect("merge catalogs");
+
+        assert_eq!(
+            merged.conflicts,
+            [MergeConflict::Node {
+                id: "db".to_owned()
+            }]
+        );
+        assert!(String::from_utf8_lossy(&merged.content).contains("ours/db.md"));
+    }
+}
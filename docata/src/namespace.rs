@@ -0,0 +1,112 @@
+use crate::{
+    config::{Config, NamespaceConfig},
+    scan::Entry,
+};
+use std::collections::HashSet;
+use std::path::Path;
+
+/// Prefix the ids of `entries`, scanned from `root`, as the `[namespaces]`
+/// table of `config` asks. Ids already carrying their prefix are kept, and a
+/// dependency naming an id of the document's own namespace without the
+/// prefix is rewritten to the prefixed id.
+pub(crate) fn apply(
+    root: &Path,
+    config: &Config,
+    entries: &mut [Entry],
+) {
+    if config.namespaces == NamespaceConfig::default() {
+        return;
+    }
+
+    let prefixes = entries
+        .iter()
+        .map(|entry| {
+            config
+                .namespaces
+                .prefix(entry.path.strip_prefix(root).unwrap_or(&entry.path))
+        })
+        .collect::<Vec<_>>();
+    for (entry, prefix) in entries.iter_mut().zip(&prefixes) {
+        if let Some(prefix) = prefix
+            && !entry.id.starts_with(prefix.as_str())
+        {
+            entry.id = format!("{prefix}{}", entry.id);
+        }
+    }
+
+    let ids = entries
+        .iter()
+        .map(|entry| entry.id.clone())
+        .collect::<HashSet<_>>();
+    let fields = config
+        .relations
+        .iter()
+        .map(|(kind, relation)| relation.field(kind).to_owned())
+        .collect::<Vec<_>>();
+    for (entry, prefix) in entries.iter_mut().zip(&prefixes) {
+        let Some(prefix) = prefix else {
+            continue;
+        };
+        let resolve = |id: &mut String| {
+            let namespaced = format!("{prefix}{id}");
+            if !ids.contains(id.as_str()) && ids.contains(&namespaced) {
+                *id = namespaced;
+            }
+        };
+        for dep in &mut entry.deps {
+            resolve(dep);
+        }
+        for field in &fields {
+            for id in entry.values.get_mut(field).into_iter().flatten() {
+                resolve(id);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::apply;
+    use crate::config::Config;
+    use crate::scan::scan_documents;
+    use std::path::Path;
+
+    #[test]
+    fn prefixes_ids_by_directory_and_resolves_local_deps() {
+        let root = Path::new("docs");
+        let mut entries = scan_documents(&[
+            (
+                root.join("index.md"),
+                "---\nid: index\ndeps: [adr/0001, rfc:0001]\n---\n",
+            ),
+            (root.join("adr/0001.md"), "---\nid: \"0001\"\n---\n"),
+            (
+                root.join("adr/0002.md"),
+                "---\nid: adr/0002\ndeps: [\"0001\"]\n---\n",
+            ),
+            (root.join("rfcs/0001.md"), "---\nid: \"0001\"\n---\n"),
+        ])
+        .expect("scan docs");
+
+        let config: Config = toml::from_str(
+            "[namespaces]\ndirectories = true\n\n[namespaces.prefixes]\nrfcs = \"rfc:\"\n",
+        )
+        .expect("parse config");
+        apply(root, &config, &mut entries);
+        entries.sort_by(|left, right| left.id.cmp(&right.id));
+
+        let ids = entries
+            .iter()
+            .map(|entry| (entry.id.as_str(), entry.deps.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                ("adr/0001", vec![]),
+                ("adr/0002", vec!["adr/0001".to_owned()]),
+                ("index", vec!["adr/0001".to_owned(), "rfc:0001".to_owned()]),
+                ("rfc:0001", vec![]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,160 @@
+use crate::catalog::{Catalog, Node};
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+/// A navigation section: the documents of one domain, or of one type within
+/// a domain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NavSection {
+    /// Domain or type the section groups by; empty for the root section.
+    pub label: String,
+    /// Subsections, sorted by label.
+    pub sections: Vec<NavSection>,
+    /// Documents directly in the section, sorted by id.
+    pub documents: Vec<NavDocument>,
+}
+
+/// A document linked from a navigation section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NavDocument {
+    pub id: String,
+    /// Path relative to the documents directory, or as the catalog records
+    /// it when outside that directory.
+    pub path: String,
+    pub title: Option<String>,
+}
+
+/// Group the documents of `catalog` by domain, then by type. A document
+/// without a domain, or without a type, sits directly in the section above.
+///
+/// Document paths are made relative to `docs_dir`, since catalogs record
+/// them relative to where `docata build` ran.
+#[must_use]
+pub fn build(
+    catalog: &Catalog,
+    docs_dir: &Path,
+) -> NavSection {
+    let mut root = NavSection::default();
+    let mut nodes = catalog.nodes.iter().collect::<Vec<_>>();
+    nodes.sort_by(|left, right| left.id.cmp(&right.id));
+
+    let mut domains = BTreeMap::<&str, BTreeMap<&str, Vec<&Node>>>::new();
+    for node in nodes {
+        let Some(domain) = &node.domain else {
+            root.documents.push(NavDocument::new(node, docs_dir));
+            continue;
+        };
+        domains
+            .entry(domain)
+            .or_default()
+            .entry(node.kind.as_deref().unwrap_or_default())
+            .or_default()
+            .push(node);
+    }
+
+    for (domain, types) in domains {
+        let mut section = NavSection {
+            label: domain.to_owned(),
+            ..NavSection::default()
+        };
+        for (kind, nodes) in types {
+            let documents = nodes
+                .into_iter()
+                .map(|node| NavDocument::new(node, docs_dir));
+            if kind.is_empty() {
+                section.documents.extend(documents);
+            } else {
+                section.sections.push(NavSection {
+                    label: kind.to_owned(),
+                    sections: Vec::new(),
+                    documents: documents.collect(),
+                });
+            }
+        }
+        root.sections.push(section);
+    }
+
+    root
+}
+
+impl NavDocument {
+    fn new(
+        node: &Node,
+        docs_dir: &Path,
+    ) -> Self {
+        let without_cur_dir = |path: &Path| {
+            path.components()
+                .filter(|component| !matches!(component, Component::CurDir))
+                .collect::<PathBuf>()
+        };
+        let path = without_cur_dir(Path::new(&node.path))
+            .strip_prefix(without_cur_dir(docs_dir))
+            .map_or_else(
+                |_| node.path.clone(),
+                |relative| relative.to_string_lossy().replace('\\', "/"),
+            );
+        Self {
+            id: node.id.clone(),
+            path,
+            title: node.title.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build;
+    use crate::catalog::{Catalog, Node};
+    use std::collections::BTreeMap;
+
+    fn node(
+        id: &str,
+        domain: Option<&str>,
+        kind: Option<&str>,
+    ) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("./docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: kind.map(str::to_owned),
+            domain: domain.map(str::to_owned),
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn groups_documents_by_domain_then_type() {
+        let catalog = Catalog {
+            nodes: vec![
+                node("invoices", Some("billing"), Some("runbook")),
+                node("intro", None, Some("guide")),
+                node("billing", Some("billing"), None),
+                node("api", Some("billing"), Some("spec")),
+            ],
+            edges: Vec::new(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let root = build(&catalog, "docs".as_ref());
+
+        assert_eq!(root.documents[0].id, "intro");
+        assert_eq!(root.documents[0].path, "intro.md");
+        let billing = &root.sections[0];
+        assert_eq!(billing.label, "billing");
+        assert_eq!(billing.documents[0].id, "billing");
+        let labels = billing
+            .sections
+            .iter()
+            .map(|section| section.label.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(labels, ["runbook", "spec"]);
+        assert_eq!(billing.sections[1].documents[0].id, "api");
+    }
+}
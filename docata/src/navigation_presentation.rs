@@ -0,0 +1,219 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::navigation::{NavDocument, NavSection};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// File name of a Hugo section's list page.
+const SECTION_INDEX: &str = "_index.md";
+
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+enum SidebarItem {
+    Doc(String),
+    Category {
+        #[serde(rename = "type")]
+        kind: &'static str,
+        label: String,
+        items: Vec<SidebarItem>,
+    },
+}
+
+#[derive(Debug, Error)]
+pub enum NavigationPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("failed to write file '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+}
+
+/// Write `root` as a Docusaurus `sidebars.json` with a single `docs`
+/// sidebar: one category per section, then the section's documents.
+///
+/// # Errors
+///
+/// Returns `NavigationPresentationError` if serialization or writing fails.
+pub fn write_sidebar<W: Write>(
+    root: &NavSection,
+    out: &mut W,
+) -> Result<(), NavigationPresentationError> {
+    let sidebars = BTreeMap::from([("docs", sidebar_items(root))]);
+    json::write(out, &sidebars, OutputFormat::Json)?;
+    Ok(())
+}
+
+fn sidebar_items(section: &NavSection) -> Vec<SidebarItem> {
+    let categories = section
+        .sections
+        .iter()
+        .map(|section| SidebarItem::Category {
+            kind: "category",
+            label: section.label.clone(),
+            items: sidebar_items(section),
+        });
+    let documents = section
+        .documents
+        .iter()
+        .map(|document| SidebarItem::Doc(doc_id(document)));
+    categories.chain(documents).collect()
+}
+
+/// The Docusaurus id of `document`: its frontmatter `id`, prefixed with the
+/// directory holding it.
+fn doc_id(document: &NavDocument) -> String {
+    match document.path.rsplit_once('/') {
+        Some((dir, _)) => format!("{dir}/{}", document.id),
+        None => document.id.clone(),
+    }
+}
+
+/// Write a Hugo `_index.md` for `root` into `dir`, and one for each section
+/// into a subdirectory named after its label, and return the paths written.
+///
+/// Pages link documents and subsections with `ref` shortcodes relative to
+/// `dir`, so `dir` is expected to be the documents directory. They carry no
+/// frontmatter, so scanning the documents directory skips them.
+///
+/// # Errors
+///
+/// Returns `NavigationPresentationError::Write` when creating a directory or
+/// writing a file fails.
+pub fn write_section_indexes(
+    root: &NavSection,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, NavigationPresentationError> {
+    let mut written = Vec::new();
+    write_section_index(root, dir, "", &mut written)?;
+    Ok(written)
+}
+
+fn write_section_index(
+    section: &NavSection,
+    dir: &Path,
+    relative: &str,
+    written: &mut Vec<PathBuf>,
+) -> Result<(), NavigationPresentationError> {
+    let mut page = String::new();
+    if !section.label.is_empty() {
+        let _ = writeln!(page, "# {}\n", section.label);
+    }
+    let mut children = Vec::new();
+    for child in &section.sections {
+        let child_relative = format!("{relative}{}/", slug(&child.label));
+        let _ = writeln!(
+            page,
+            "- [{}]({{{{< ref \"{child_relative}{SECTION_INDEX}\" >}}}})",
+            child.label
+        );
+        children.push((child, child_relative));
+    }
+    for document in &section.documents {
+        let _ = writeln!(
+            page,
+            "- [{}]({{{{< ref \"{}\" >}}}})",
+            document.title.as_deref().unwrap_or(&document.id),
+            document.path
+        );
+    }
+
+    let path = dir.join(relative).join(SECTION_INDEX);
+    let write_error = |source| NavigationPresentationError::Write {
+        path: path.clone(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    fs::write(&path, page).map_err(write_error)?;
+    written.push(path);
+
+    for (child, child_relative) in children {
+        write_section_index(child, dir, &child_relative, written)?;
+    }
+    Ok(())
+}
+
+/// `label` as a directory name: lowercase letters and digits, other runs of
+/// characters turned into `-`.
+fn slug(label: &str) -> String {
+    let mut slug = String::new();
+    for char in label.chars() {
+        if char.is_alphanumeric() {
+            slug.extend(char.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{write_section_indexes, write_sidebar};
+    use crate::navigation::{NavDocument, NavSection};
+    use crate::testing::TestWorkspace;
+    use std::fs;
+
+    fn navigation() -> NavSection {
+        let document = |id: &str, path: &str| NavDocument {
+            id: id.to_owned(),
+            path: path.to_owned(),
+            title: None,
+        };
+        NavSection {
+            label: String::new(),
+            sections: vec![NavSection {
+                label: "Billing Ops".to_owned(),
+                sections: Vec::new(),
+                documents: vec![document("billing-api", "guides/api.md")],
+            }],
+            documents: vec![document("intro", "intro.md")],
+        }
+    }
+
+    #[test]
+    fn writes_a_docusaurus_sidebar() {
+        let mut output = Vec::new();
+        write_sidebar(&navigation(), &mut output).expect("write sidebar");
+        let sidebar: serde_json::Value = serde_json::from_slice(&output).expect("parse sidebar");
+
+        let expected: serde_json::Value = serde_json::from_str(
+            r#"{"docs": [
+                {"type": "category", "label": "Billing Ops", "items": ["guides/billing-api"]},
+                "intro"
+            ]}"#,
+        )
+        .expect("parse expected sidebar");
+        assert_eq!(sidebar, expected);
+    }
+
+    #[test]
+    fn writes_hugo_section_indexes() {
+        let workspace = TestWorkspace::new();
+
+        let written =
+            write_section_indexes(&navigation(), workspace.path()).expect("write indexes");
+
+        assert_eq!(written.len(), 2);
+        assert_eq!(
+            fs::read_to_string(workspace.path().join("_index.md")).expect("read root index"),
+            "- [Billing Ops]({{< ref \"billing-ops/_index.md\" >}})\n- [intro]({{< ref \"intro.md\" >}})\n"
+        );
+        assert_eq!(
+            fs::read_to_string(workspace.path().join("billing-ops/_index.md"))
+                .expect("read section index"),
+            "# Billing Ops\n\n- [billing-api]({{< ref \"guides/api.md\" >}})\n"
+        );
+    }
+}
@@ -0,0 +1,154 @@
+use crate::scan::SkipReason;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+/// A step of a build, scan, or validation, reported to a [`ProgressSink`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ProgressEvent<'a> {
+    /// The documents at `location` were listed; `count` of them will be read.
+    FilesDiscovered { location: &'a str, count: usize },
+    /// A document was read. Documents are read in parallel, so these arrive
+    /// from several threads in no particular order.
+    FileParsed { path: &'a Path },
+    /// A document produced no entry.
+    FileSkipped { path: &'a Path, reason: SkipReason },
+    /// A document failed to scan and was left out, in a lenient scan.
+    FileFailed { path: &'a Path },
+    /// Validation started looking for one kind of finding.
+    ValidationStarted { phase: ValidationPhase },
+}
+
+/// The kinds of findings validation looks for, in the order it does.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ValidationPhase {
+    DuplicateIds,
+    UnresolvedDependencies,
+    DependencyCycles,
+    DomainViolations,
+    LapsedReviews,
+    /// Orphans, broken links, missing fields, and unknown statuses, checked
+    /// per document with the rules that apply to it.
+    DocumentRules,
+}
+
+impl ValidationPhase {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::DuplicateIds => "duplicate ids",
+            Self::UnresolvedDependencies => "unresolved dependencies",
+            Self::DependencyCycles => "dependency cycles",
+            Self::DomainViolations => "domain violations",
+            Self::LapsedReviews => "lapsed reviews",
+            Self::DocumentRules => "document rules",
+        }
+    }
+}
+
+impl Display for ValidationPhase {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Receives [`ProgressEvent`]s, e.g. to drive a progress bar or write logs.
+///
+/// Closures taking `&ProgressEvent` implement the trait.
+pub trait ProgressSink: Sync {
+    fn event(
+        &self,
+        event: &ProgressEvent<'_>,
+    );
+}
+
+impl<F: Fn(&ProgressEvent<'_>) + Sync> ProgressSink for F {
+    fn event(
+        &self,
+        event: &ProgressEvent<'_>,
+    ) {
+        self(event);
+    }
+}
+
+/// A [`ProgressSink`] ignoring every event.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {
+    fn event(
+        &self,
+        _event: &ProgressEvent<'_>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ProgressEvent, ValidationPhase};
+    use crate::testing::{TestWorkspace, write_markdown};
+    use crate::{BuildOptions, ValidationRules, build_merged_catalog_into_with_progress};
+    use std::fs;
+    use std::sync::Mutex;
+
+    #[test]
+    fn reports_scan_and_validation_progress() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "api.md", "api", &["db"]);
+        write_markdown(workspace.path(), "db.md", "db", &[]);
+        fs::write(workspace.path().join("notes.md"), "# Notes\n").expect("write notes");
+        let events = Mutex::new(Vec::new());
+        let record = |event: &ProgressEvent<'_>| {
+            let event = match event {
+                ProgressEvent::FilesDiscovered { count, .. } => format!("discovered {count}"),
+                ProgressEvent::FileParsed { .. } => "parsed".to_owned(),
+                ProgressEvent::FileSkipped { path, .. } => {
+                    format!("skipped {}", path.file_name().expect("name").display())
+                },
+                ProgressEvent::FileFailed { .. } => "failed".to_owned(),
+                ProgressEvent::ValidationStarted { phase } => format!("validating {phase}"),
+            };
+            events.lock().expect("lock events").push(event);
+        };
+
+        build_merged_catalog_into_with_progress(
+            &[workspace.path()],
+            workspace.path().join("catalog.json").as_path(),
+            BuildOptions::default(),
+            &record,
+        )
+        .expect("build catalog");
+        let built = events
+            .lock()
+            .expect("lock events")
+            .drain(..)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            built,
+            [
+                "discovered 3",
+                "parsed",
+                "parsed",
+                "parsed",
+                "skipped notes.md"
+            ]
+        );
+
+        crate::validate_with_progress(workspace.path(), &ValidationRules::default(), &record)
+            .expect("validate");
+        let validated = events.into_inner().expect("events");
+        assert_eq!(validated.len(), 9);
+        assert_eq!(
+            validated[5],
+            format!("validating {}", ValidationPhase::DuplicateIds)
+        );
+        assert_eq!(
+            validated[8],
+            format!("validating {}", ValidationPhase::DocumentRules)
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use crate::validate::strongly_connected_components;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Transitive closure of a dependency graph, per strongly connected
+/// component, in both directions.
+///
+/// Building costs one pass over the condensed graph with a bitset per
+/// component; afterwards each query costs the size of its answer plus one
+/// scan of a bitset, however deep the graph is.
+pub(crate) struct Reachability {
+    /// Component of each vertex.
+    component_of: Vec<usize>,
+    /// Vertices of each component, in reverse topological order.
+    components: Vec<Vec<usize>>,
+    dependencies: Vec<BitSet>,
+    dependents: Vec<BitSet>,
+}
+
+impl Reachability {
+    /// Index the graph given by `forward`, which lists for each vertex the
+    /// vertices it depends on, all below `forward.len()`.
+    pub(crate) fn new(forward: &[Vec<usize>]) -> Self {
+        let adjacency = forward
+            .iter()
+            .enumerate()
+            .map(|(from, targets)| (from, targets.iter().copied().collect::<BTreeSet<_>>()))
+            .collect::<BTreeMap<_, _>>();
+
+        let components = strongly_connected_components(&adjacency);
+        let mut component_of = vec![0; forward.len()];
+        for (index, vertices) in components.iter().enumerate() {
+            for &vertex in vertices {
+                component_of[vertex] = index;
+            }
+        }
+
+        let mut successors = vec![BTreeSet::new(); components.len()];
+        let mut predecessors = vec![BTreeSet::new(); components.len()];
+        let mut cyclic = components
+            .iter()
+            .map(|ids| ids.len() > 1)
+            .collect::<Vec<_>>();
+        for (&from, targets) in &adjacency {
+            let from = component_of[from];
+            for &to in targets {
+                let to = component_of[to];
+                if from == to {
+                    cyclic[from] = true;
+                } else {
+                    successors[from].insert(to);
+                    predecessors[to].insert(from);
+                }
+            }
+        }
+
+        // Components come after every component they depend on, so walking
+        // forward closes dependencies and walking backward closes dependents.
+        let dependencies = close(&successors, &cyclic, 0..components.len());
+        let dependents = close(&predecessors, &cyclic, (0..components.len()).rev());
+
+        Self {
+            component_of,
+            components,
+            dependencies,
+            dependents,
+        }
+    }
+
+    /// Vertices `vertex` depends on directly or transitively, in no
+    /// particular order; `vertex` itself is left out even when it is part of
+    /// a cycle.
+    pub(crate) fn dependencies(
+        &self,
+        vertex: usize,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.collect(vertex, &self.dependencies)
+    }
+
+    /// Vertices depending on `vertex` directly or transitively, likewise.
+    pub(crate) fn dependents(
+        &self,
+        vertex: usize,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.collect(vertex, &self.dependents)
+    }
+
+    fn collect<'a>(
+        &'a self,
+        vertex: usize,
+        closure: &'a [BitSet],
+    ) -> impl Iterator<Item = usize> + 'a {
+        closure[self.component_of[vertex]]
+            .iter()
+            .flat_map(|reached| &self.components[reached])
+            .copied()
+            .filter(move |&reached| reached != vertex)
+    }
+}
+
+/// Close `edges` over components visited in `order`, where every component
+/// is visited after the components its edges point to. A cyclic component
+/// reaches itself.
+fn close(
+    edges: &[BTreeSet<usize>],
+    cyclic: &[bool],
+    order: impl Iterator<Item = usize>,
+) -> Vec<BitSet> {
+    let mut closure = vec![BitSet::new(edges.len()); edges.len()];
+    for component in order {
+        let mut reached = BitSet::new(edges.len());
+        if cyclic[component] {
+            reached.insert(component);
+        }
+        for &next in &edges[component] {
+            reached.insert(next);
+            reached.union_with(&closure[next]);
+        }
+        closure[component] = reached;
+    }
+    closure
+}
+
+#[derive(Clone)]
+struct BitSet(Vec<u64>);
+
+impl BitSet {
+    fn new(len: usize) -> Self {
+        Self(vec![0; len.div_ceil(64)])
+    }
+
+    fn insert(
+        &mut self,
+        index: usize,
+    ) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    fn union_with(
+        &mut self,
+        other: &Self,
+    ) {
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word |= other;
+        }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.0.iter().enumerate().flat_map(|(word_index, &word)| {
+            (0..64)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| word_index * 64 + bit)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::catalog::{Catalog, DuplicateIdPolicy};
+    use crate::graph::Graph;
+    use crate::testing::{CorpusOptions, TestWorkspace, generate_corpus};
+    use std::collections::{BTreeSet, HashMap};
+
+    fn traverse(
+        edges: &HashMap<String, Vec<String>>,
+        id: &str,
+    ) -> Vec<String> {
+        let mut reached = BTreeSet::new();
+        let mut stack = vec![id.to_owned()];
+        while let Some(current) = stack.pop() {
+            for next in edges.get(&current).into_iter().flatten() {
+                if reached.insert(next.clone()) {
+                    stack.push(next.clone());
+                }
+            }
+        }
+        reached.remove(id);
+        reached.into_iter().collect()
+    }
+
+    #[test]
+    fn matches_traversal_on_generated_corpora() {
+        for seed in 0..8 {
+            let options = CorpusOptions {
+                cycles: seed % 2 == 0,
+                unresolved_deps: 2,
+                ..CorpusOptions::default()
+            };
+            let workspace = TestWorkspace::new();
+            generate_corpus(seed, &options).write(workspace.path());
+            let entries = crate::scan::scan(workspace.path()).expect("scan corpus");
+            let (catalog, _) =
+                Catalog::from_entries(&entries, DuplicateIdPolicy::Error).expect("build catalog");
+            let walked = Graph::from_catalog(&catalog);
+            let indexed = Graph::from_catalog(&catalog).with_reachability_index();
+
+            let mut forward = HashMap::<String, Vec<String>>::new();
+            let mut reverse = HashMap::<String, Vec<String>>::new();
+            for edge in &catalog.edges {
+                forward
+                    .entry(edge.from.clone())
+                    .or_default()
+                    .push(edge.to.clone());
+                reverse
+                    .entry(edge.to.clone())
+                    .or_default()
+                    .push(edge.from.clone());
+            }
+
+            for node in &catalog.nodes {
+                for graph in [&walked, &indexed] {
+                    assert_eq!(
+                        graph.transitive_deps(&node.id),
+                        traverse(&forward, &node.id),
+                        "seed {seed}, {}",
+                        node.id
+                    );
+                    assert_eq!(
+                        graph.transitive_refs(&node.id),
+                        traverse(&reverse, &node.id),
+                        "seed {seed}, {}",
+                        node.id
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use crate::{
+    BuildOptions, CatalogFormat, Compression, build, catalog_presentation,
+    config::{CONFIG_FILE_NAME, Config, discover_roots},
+    error::Error,
+    scan::{Entry, ScanOptions, scan_with_options},
+    validate::{ValidationReport, build_validation_report_in},
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+
+/// State of a root's committed catalog after a recursive check.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CatalogStatus {
+    /// The root's config does not name a catalog.
+    NotConfigured,
+    /// Validation failed, so the catalog was not regenerated.
+    NotChecked,
+    UpToDate,
+    Outdated,
+    Missing,
+}
+
+impl CatalogStatus {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            CatalogStatus::NotConfigured => "not_configured",
+            CatalogStatus::NotChecked => "not_checked",
+            CatalogStatus::UpToDate => "up_to_date",
+            CatalogStatus::Outdated => "outdated",
+            CatalogStatus::Missing => "missing",
+        }
+    }
+
+    const fn is_failure(self) -> bool {
+        matches!(self, CatalogStatus::Outdated | CatalogStatus::Missing)
+    }
+}
+
+/// Check result for one `docata.toml` root.
+#[derive(Debug)]
+pub struct RootCheck {
+    pub root: PathBuf,
+    pub document_count: usize,
+    pub report: ValidationReport,
+    pub catalog_path: Option<PathBuf>,
+    pub catalog_status: CatalogStatus,
+    /// Set when the root could not be checked at all, e.g. a broken config.
+    pub error: Option<String>,
+}
+
+impl RootCheck {
+    fn failed(
+        root: &Path,
+        error: &Error,
+    ) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            document_count: 0,
+            report: ValidationReport::default(),
+            catalog_path: None,
+            catalog_status: CatalogStatus::NotChecked,
+            error: Some(error.to_string()),
+        }
+    }
+
+    /// Number of problems found in this root.
+    #[must_use]
+    pub fn failure_count(&self) -> usize {
+        self.report.finding_count()
+            + usize::from(self.catalog_status.is_failure())
+            + usize::from(self.error.is_some())
+    }
+
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.failure_count() == 0
+    }
+}
+
+/// Combined result of checking every root under a search directory.
+#[derive(Debug, Default)]
+pub struct RecursiveCheckReport {
+    pub roots: Vec<RootCheck>,
+}
+
+impl RecursiveCheckReport {
+    #[must_use]
+    pub fn failure_count(&self) -> usize {
+        self.roots.iter().map(RootCheck::failure_count).sum()
+    }
+
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.roots.iter().all(RootCheck::is_success)
+    }
+}
+
+/// Discover `docata.toml` roots under `search_root` and check them in
+/// parallel with the `parallel` feature.
+///
+/// # Errors
+///
+/// Returns `Error` when searching for config files fails. Failures inside a
+/// single root are recorded in its `RootCheck` instead.
+pub fn run(search_root: &Path) -> Result<RecursiveCheckReport, Error> {
+    let roots = discover_roots(search_root)?;
+
+    #[cfg(feature = "parallel")]
+    let pending = roots.par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let pending = roots.iter();
+
+    let roots = pending
+        .map(|root| check_root(root).unwrap_or_else(|error| RootCheck::failed(root, &error)))
+        .collect();
+
+    Ok(RecursiveCheckReport { roots })
+}
+
+fn check_root(root: &Path) -> Result<RootCheck, Error> {
+    let config = Config::load(&root.join(CONFIG_FILE_NAME))?;
+    let docs = root.join(config.build.docs_dir());
+
+    let catalog_path = config.build.catalog.as_ref().map(|path| root.join(path));
+    let current = match &catalog_path {
+        Some(path) => match std::fs::read(path) {
+            Ok(current) => Some(current),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error.into()),
+        },
+        None => None,
+    };
+    let recorded = current
+        .as_deref()
+        .zip(catalog_path.as_deref())
+        .and_then(|(current, path)| {
+            build::RecordedOptions::read(current, CatalogFormat::from_path(path))
+        });
+
+    // Catalog paths are recorded relative to the root, as `docata build` run
+    // from inside the root would write them.
+    let scan_options = ScanOptions {
+        content_hash: recorded
+            .as_ref()
+            .is_some_and(|recorded| recorded.with_content_hash),
+        git_metadata: recorded
+            .as_ref()
+            .is_some_and(|recorded| recorded.with_git_metadata),
+        code_owners: recorded
+            .as_ref()
+            .is_some_and(|recorded| recorded.with_code_owners),
+        ..ScanOptions::default()
+    };
+    let entries = scan_with_options(&docs, scan_options)?
+        .entries
+        .into_iter()
+        .map(|mut entry| {
+            if let Ok(relative) = entry.path.strip_prefix(root) {
+                entry.path = relative.to_path_buf();
+            }
+            entry
+        })
+        .collect::<Vec<_>>();
+
+    let report = build_validation_report_in(&docs, &entries, &config.validation_rules());
+    let catalog_status = match (&catalog_path, &current) {
+        (None, _) => CatalogStatus::NotConfigured,
+        (Some(_), _) if !report.is_empty() => CatalogStatus::NotChecked,
+        (Some(_), None) => CatalogStatus::Missing,
+        (Some(path), Some(current)) => compare_catalog(
+            &docs,
+            &entries,
+            path,
+            current,
+            recorded.as_ref(),
+            config.build.with_node_metadata,
+        )?,
+    };
+
+    Ok(RootCheck {
+        root: root.to_path_buf(),
+        document_count: entries.len(),
+        report,
+        catalog_path,
+        catalog_status,
+        error: None,
+    })
+}
+
+fn compare_catalog(
+    docs: &Path,
+    entries: &[Entry],
+    catalog_path: &Path,
+    current: &[u8],
+    recorded: Option<&build::RecordedOptions>,
+    include_node_metadata: Option<bool>,
+) -> Result<CatalogStatus, Error> {
+    let catalog_format = CatalogFormat::from_path(catalog_path);
+    let regenerated = build::render(
+        docs,
+        entries,
+        BuildOptions {
+            include_node_metadata: include_node_metadata
+                .unwrap_or_else(|| build::detect_node_metadata(current, catalog_format)),
+            catalog_format,
+            compact: build::detect_compact(current, catalog_format),
+            compression: Compression::sniff(current)
+                .unwrap_or_else(|| Compression::from_path(catalog_path)),
+            include_provenance: recorded.is_some_and(|recorded| recorded.with_provenance),
+            include_content_hash: recorded.is_some_and(|recorded| recorded.with_content_hash),
+            include_git_metadata: recorded.is_some_and(|recorded| recorded.with_git_metadata),
+            include_code_owners: recorded.is_some_and(|recorded| recorded.with_code_owners),
+            ..BuildOptions::default()
+        },
+    )?;
+
+    if catalog_presentation::same_content(current, &regenerated) {
+        Ok(CatalogStatus::UpToDate)
+    } else {
+        Ok(CatalogStatus::Outdated)
+    }
+}
@@ -0,0 +1,173 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::recursive_check::{RecursiveCheckReport, RootCheck};
+use crate::validation_presentation::ValidationFindingsJson;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct CatalogCheckJson {
+    path: Option<String>,
+    status: &'static str,
+}
+
+#[derive(Debug, Serialize)]
+struct RootCheckJson<'a> {
+    root: String,
+    status: &'static str,
+    document_count: usize,
+    error: Option<&'a str>,
+    catalog: CatalogCheckJson,
+    #[serde(flatten)]
+    findings: ValidationFindingsJson<'a>,
+}
+
+impl<'a> From<&'a RootCheck> for RootCheckJson<'a> {
+    fn from(root: &'a RootCheck) -> Self {
+        Self {
+            root: root.root.to_string_lossy().to_string(),
+            status: status_label(root.is_success()),
+            document_count: root.document_count,
+            error: root.error.as_deref(),
+            catalog: CatalogCheckJson {
+                path: root
+                    .catalog_path
+                    .as_ref()
+                    .map(|path| path.to_string_lossy().to_string()),
+                status: root.catalog_status.as_str(),
+            },
+            findings: (&root.report).into(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RecursiveSummaryJson {
+    roots: usize,
+    passed: usize,
+    failed: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct RecursiveCheckJson<'a> {
+    command: &'static str,
+    status: &'static str,
+    summary: RecursiveSummaryJson,
+    roots: Vec<RootCheckJson<'a>>,
+}
+
+impl<'a> From<&'a RecursiveCheckReport> for RecursiveCheckJson<'a> {
+    fn from(report: &'a RecursiveCheckReport) -> Self {
+        let passed = report.roots.iter().filter(|root| root.is_success()).count();
+
+        Self {
+            command: "check",
+            status: status_label(report.is_success()),
+            summary: RecursiveSummaryJson {
+                roots: report.roots.len(),
+                passed,
+                failed: report.roots.len() - passed,
+            },
+            roots: report.roots.iter().map(RootCheckJson::from).collect(),
+        }
+    }
+}
+
+const fn status_label(success: bool) -> &'static str {
+    if success { "passed" } else { "failed" }
+}
+
+#[derive(Debug, Error)]
+pub enum RecursiveCheckPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a recursive check report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `RecursiveCheckPresentationError` if serialization or writing
+/// fails.
+pub fn write<W: Write>(
+    report: &RecursiveCheckReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RecursiveCheckPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(RecursiveCheckPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    report: &RecursiveCheckReport,
+    out: &mut W,
+) -> Result<(), RecursiveCheckPresentationError> {
+    yaml_serde::to_writer(out, &RecursiveCheckJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &RecursiveCheckReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RecursiveCheckPresentationError> {
+    json::write(out, &RecursiveCheckJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &RecursiveCheckReport,
+    out: &mut W,
+) -> Result<(), RecursiveCheckPresentationError> {
+    for root in &report.roots {
+        writeln!(
+            out,
+            "== {} ({} documents)",
+            root.root.display(),
+            root.document_count
+        )?;
+
+        if let Some(error) = &root.error {
+            writeln!(out, "error: {error}")?;
+            continue;
+        }
+
+        if root.report.is_empty() {
+            writeln!(out, "validation passed")?;
+        } else {
+            write!(out, "{}", root.report)?;
+        }
+
+        if let Some(path) = &root.catalog_path {
+            writeln!(
+                out,
+                "catalog {}: {}",
+                path.display(),
+                root.catalog_status.as_str()
+            )?;
+        }
+    }
+
+    let passed = report.roots.iter().filter(|root| root.is_success()).count();
+    writeln!(
+        out,
+        "checked {} roots: {passed} passed, {} failed",
+        report.roots.len(),
+        report.roots.len() - passed
+    )?;
+
+    Ok(())
+}
@@ -1,10 +1,16 @@
-use crate::{catalog::Catalog, error::Error, format::OutputFormat, graph::Graph};
+use crate::{
+    QueryOptions, catalog::Catalog, error::Error, federation::Federation, format::OutputFormat,
+    graph::Graph,
+};
 use std::io::Write;
 
-pub use crate::domain::RelationKind;
+pub use crate::domain::{RelationCombine, RelationKind};
 
 /// Run relation command and write formatted output to the provided writer.
 ///
+/// With `options.stream`, each item is written as soon as it is resolved
+/// instead of after the whole response is built.
+///
 /// # Errors
 ///
 /// Returns `Error` when response construction or writing fails.
@@ -13,19 +19,130 @@ pub fn run<W: Write>(
     catalog: &Catalog,
     graph: &Graph,
     relation_kind: RelationKind,
-    strict: bool,
+    options: &QueryOptions,
     format: OutputFormat,
     out: &mut W,
 ) -> Result<(), Error> {
-    if strict && !catalog.nodes.iter().any(|node| node.id == query_id) {
+    if options.strict && !graph.contains(query_id) {
         return Err(Error::QueryIdNotFound {
             query_id: query_id.to_owned(),
         });
     }
 
-    let response = crate::domain::build_relation(query_id, catalog, graph, relation_kind);
+    if options.stream {
+        let summary = crate::domain::visit_relation(
+            query_id,
+            catalog,
+            graph,
+            relation_kind,
+            options,
+            |item| crate::relation_presentation::write_stream_item(&item, format, out),
+        )?;
+        crate::relation_presentation::write_stream_summary(&summary, format, out)?;
+        return Ok(());
+    }
+
+    let response = crate::domain::build_relation(query_id, catalog, graph, relation_kind, options);
 
     crate::relation_presentation::write(&response, format, out)?;
 
     Ok(())
 }
+
+/// Run relation command for several query ids, merging their results with
+/// `options.combine`, and write formatted output to the provided writer.
+///
+/// # Errors
+///
+/// Returns `Error::QueryIdNotFound` for the first unknown query id in strict
+/// mode, or `Error` when writing fails.
+pub fn run_set<W: Write>(
+    query_ids: &[&str],
+    catalog: &Catalog,
+    graph: &Graph,
+    relation_kind: RelationKind,
+    options: &QueryOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    if options.strict
+        && let Some(query_id) = query_ids.iter().find(|query_id| !graph.contains(query_id))
+    {
+        return Err(Error::QueryIdNotFound {
+            query_id: (*query_id).to_owned(),
+        });
+    }
+
+    let response =
+        crate::domain::build_relation_set(query_ids, catalog, graph, relation_kind, options);
+
+    crate::relation_presentation::write_set(&response, format, out)?;
+
+    Ok(())
+}
+
+/// Run relation command over federated catalogs, marking each resolved item
+/// with the catalog holding it. One query id is answered like [`run`],
+/// several like [`run_set`].
+///
+/// # Errors
+///
+/// Returns `Error::QueryIdNotFound` for the first unknown query id in strict
+/// mode, or `Error` when writing fails.
+pub(crate) fn run_federated<W: Write>(
+    query_ids: &[&str],
+    federation: &Federation,
+    graph: &Graph,
+    relation_kind: RelationKind,
+    options: &QueryOptions,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    if options.strict
+        && let Some(query_id) = query_ids.iter().find(|query_id| !graph.contains(query_id))
+    {
+        return Err(Error::QueryIdNotFound {
+            query_id: (*query_id).to_owned(),
+        });
+    }
+
+    let catalog = &federation.catalog;
+    if let [query_id] = query_ids {
+        if options.stream {
+            let summary = crate::domain::visit_relation(
+                query_id,
+                catalog,
+                graph,
+                relation_kind,
+                options,
+                |mut item| {
+                    federation.annotate(&mut item);
+                    crate::relation_presentation::write_stream_item(&item, format, out)
+                },
+            )?;
+            crate::relation_presentation::write_stream_summary(&summary, format, out)?;
+            return Ok(());
+        }
+
+        let mut response =
+            crate::domain::build_relation(query_id, catalog, graph, relation_kind, options);
+        for item in &mut response.items {
+            federation.annotate(item);
+        }
+        crate::relation_presentation::write(&response, format, out)?;
+        return Ok(());
+    }
+
+    let mut response =
+        crate::domain::build_relation_set(query_ids, catalog, graph, relation_kind, options);
+    let group_items = response
+        .groups
+        .iter_mut()
+        .flat_map(|group| group.items.iter_mut());
+    for item in response.items.iter_mut().chain(group_items) {
+        federation.annotate(item);
+    }
+    crate::relation_presentation::write_set(&response, format, out)?;
+
+    Ok(())
+}
@@ -1,14 +1,28 @@
-use crate::domain::{RelationItem, RelationMeta, RelationResponse};
+use crate::catalog::SourceLocation;
+use crate::domain::{
+    RelationItem, RelationItemMetadata, RelationMeta, RelationResponse, RelationSetResponse,
+    RelationSummary,
+};
 use crate::format::OutputFormat;
+use crate::json;
 use serde::Serialize;
 use std::io::Write;
 use thiserror::Error;
 
 #[derive(Debug, Serialize)]
-struct RelationItemJson {
+pub(crate) struct RelationItemJson {
     id: String,
     path: Option<String>,
+    title: Option<String>,
     resolved: bool,
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    metadata: Option<RelationItemMetadataJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    catalog: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    external: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    declared_at: Option<SourceLocation>,
 }
 
 impl From<&RelationItem> for RelationItemJson {
@@ -16,7 +30,34 @@ impl From<&RelationItem> for RelationItemJson {
         Self {
             id: item.id.clone(),
             path: item.path.clone(),
+            title: item.title.clone(),
             resolved: item.resolved,
+            metadata: item.metadata.as_ref().map(RelationItemMetadataJson::from),
+            catalog: item.catalog.clone(),
+            external: item.external,
+            declared_at: item.declared_at.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RelationItemMetadataJson {
+    tags: Vec<String>,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    domain: Option<String>,
+    status: Option<String>,
+    source_of_truth: Option<String>,
+}
+
+impl From<&RelationItemMetadata> for RelationItemMetadataJson {
+    fn from(metadata: &RelationItemMetadata) -> Self {
+        Self {
+            tags: metadata.tags.clone(),
+            kind: metadata.kind.clone(),
+            domain: metadata.domain.clone(),
+            status: metadata.status.clone(),
+            source_of_truth: metadata.source_of_truth.clone(),
         }
     }
 }
@@ -24,32 +65,34 @@ impl From<&RelationItem> for RelationItemJson {
 #[derive(Debug, Serialize)]
 struct RelationMetaJson {
     missing_nodes: Vec<String>,
+    suggestions: Vec<String>,
 }
 
 impl From<&RelationMeta> for RelationMetaJson {
     fn from(meta: &RelationMeta) -> Self {
         Self {
             missing_nodes: meta.missing_nodes.clone(),
+            suggestions: meta.suggestions.clone(),
         }
     }
 }
 
 #[derive(Debug, Serialize)]
-struct RelationResponseJson {
-    command: String,
+struct RelationGroupJson {
     query_id: String,
+    query_resolved: bool,
     count: usize,
     items: Vec<RelationItemJson>,
     meta: RelationMetaJson,
 }
 
-impl From<&RelationResponse> for RelationResponseJson {
+impl From<&RelationResponse> for RelationGroupJson {
     fn from(response: &RelationResponse) -> Self {
         let items = response.items.iter().map(RelationItemJson::from).collect();
 
         Self {
-            command: response.command.as_str().to_owned(),
             query_id: response.query_id.clone(),
+            query_resolved: response.query_resolved,
             count: response.count,
             items,
             meta: RelationMetaJson::from(&response.meta),
@@ -57,12 +100,92 @@ impl From<&RelationResponse> for RelationResponseJson {
     }
 }
 
+#[derive(Debug, Serialize)]
+struct RelationResponseJson {
+    command: String,
+    #[serde(flatten)]
+    group: RelationGroupJson,
+}
+
+impl From<&RelationResponse> for RelationResponseJson {
+    fn from(response: &RelationResponse) -> Self {
+        Self {
+            command: response.command.as_str().to_owned(),
+            group: RelationGroupJson::from(response),
+        }
+    }
+}
+
+/// Last line of a streamed relation query.
+#[derive(Debug, Serialize)]
+struct RelationSummaryJson {
+    summary: RelationSummaryFieldsJson,
+}
+
+#[derive(Debug, Serialize)]
+struct RelationSummaryFieldsJson {
+    command: String,
+    query_id: String,
+    query_resolved: bool,
+    count: usize,
+    meta: RelationMetaJson,
+}
+
+impl From<&RelationSummary> for RelationSummaryJson {
+    fn from(summary: &RelationSummary) -> Self {
+        Self {
+            summary: RelationSummaryFieldsJson {
+                command: summary.command.as_str().to_owned(),
+                query_id: summary.query_id.clone(),
+                query_resolved: summary.query_resolved,
+                count: summary.count,
+                meta: RelationMetaJson::from(&summary.meta),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RelationSetResponseJson {
+    command: String,
+    query_ids: Vec<String>,
+    combine: &'static str,
+    count: usize,
+    items: Vec<RelationItemJson>,
+    groups: Vec<RelationGroupJson>,
+}
+
+impl From<&RelationSetResponse> for RelationSetResponseJson {
+    fn from(response: &RelationSetResponse) -> Self {
+        Self {
+            command: response.command.as_str().to_owned(),
+            query_ids: response
+                .groups
+                .iter()
+                .map(|group| group.query_id.clone())
+                .collect(),
+            combine: response.combine.as_str(),
+            count: response.items.len(),
+            items: response.items.iter().map(RelationItemJson::from).collect(),
+            groups: response
+                .groups
+                .iter()
+                .map(RelationGroupJson::from)
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum RelationPresentationError {
     #[error("io error: {0}")]
     Io(#[from] std::io::Error),
     #[error("json encoding error: {0}")]
     Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
 }
 
 /// Write a relation response according to the selected output format.
@@ -77,10 +200,95 @@ pub fn write<W: Write>(
 ) -> Result<(), RelationPresentationError> {
     match format {
         OutputFormat::Text => write_text(response, out),
-        OutputFormat::Json => write_json(response, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(response, format, out),
+        OutputFormat::Yaml => write_yaml(response, out),
+        OutputFormat::Ndjson => write_ndjson(&response.items, out),
+        OutputFormat::Sarif | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(RelationPresentationError::UnsupportedFormat(format))
+        },
     }
 }
 
+/// Write one related item per line, without the response envelope.
+///
+/// # Errors
+///
+/// Returns `RelationPresentationError` if JSON serialization or writing fails.
+pub fn write_ndjson<W: Write>(
+    items: &[RelationItem],
+    out: &mut W,
+) -> Result<(), RelationPresentationError> {
+    for item in items {
+        json::write(out, &RelationItemJson::from(item), OutputFormat::Ndjson)?;
+    }
+    Ok(())
+}
+
+/// Write one item of a streamed relation query: its id for text output, or
+/// a JSON line for JSON and NDJSON output.
+///
+/// # Errors
+///
+/// Returns `RelationPresentationError` for formats that cannot be streamed,
+/// or if JSON serialization or writing fails.
+pub fn write_stream_item<W: Write>(
+    item: &RelationItem,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RelationPresentationError> {
+    match format {
+        OutputFormat::Text => writeln!(out, "{}", item.id)?,
+        OutputFormat::Json | OutputFormat::CompactJson | OutputFormat::Ndjson => {
+            json::write(out, &RelationItemJson::from(item), OutputFormat::Ndjson)?;
+        },
+        OutputFormat::Yaml | OutputFormat::Sarif | OutputFormat::Dot | OutputFormat::Junit => {
+            return Err(RelationPresentationError::UnsupportedFormat(format));
+        },
+    }
+    Ok(())
+}
+
+/// Finish a streamed relation query: JSON and NDJSON output end with a
+/// `summary` line carrying the count and meta; text output ends as is.
+///
+/// # Errors
+///
+/// Returns `RelationPresentationError` for formats that cannot be streamed,
+/// or if JSON serialization or writing fails.
+pub fn write_stream_summary<W: Write>(
+    summary: &RelationSummary,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RelationPresentationError> {
+    match format {
+        OutputFormat::Text => {},
+        OutputFormat::Json | OutputFormat::CompactJson | OutputFormat::Ndjson => {
+            json::write(
+                out,
+                &RelationSummaryJson::from(summary),
+                OutputFormat::Ndjson,
+            )?;
+        },
+        OutputFormat::Yaml | OutputFormat::Sarif | OutputFormat::Dot | OutputFormat::Junit => {
+            return Err(RelationPresentationError::UnsupportedFormat(format));
+        },
+    }
+    Ok(())
+}
+
+/// Write a relation response as YAML to the provided writer.
+///
+/// # Errors
+///
+/// Returns `RelationPresentationError` if YAML serialization fails.
+pub fn write_yaml<W: Write>(
+    response: &RelationResponse,
+    out: &mut W,
+) -> Result<(), RelationPresentationError> {
+    yaml_serde::to_writer(out, &RelationResponseJson::from(response))?;
+    Ok(())
+}
+
 /// Write a relation response as JSON to the provided writer.
 ///
 /// # Errors
@@ -88,12 +296,12 @@ pub fn write<W: Write>(
 /// Returns `RelationPresentationError` if JSON serialization fails.
 pub fn write_json<W: Write>(
     response: &RelationResponse,
+    format: OutputFormat,
     out: &mut W,
 ) -> Result<(), RelationPresentationError> {
     let response_json = RelationResponseJson::from(response);
 
-    serde_json::to_writer_pretty(&mut *out, &response_json)?;
-    writeln!(out)?;
+    json::write(out, &response_json, format)?;
     Ok(())
 }
 
@@ -107,7 +315,43 @@ pub fn write_text<W: Write>(
     out: &mut W,
 ) -> Result<(), RelationPresentationError> {
     for item in &response.items {
-        writeln!(out, "{}", item.id)?;
+        match &item.declared_at {
+            Some(SourceLocation { path, line }) => writeln!(out, "{} ({path}:{line})", item.id)?,
+            None => writeln!(out, "{}", item.id)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Write the merged relations of several query ids according to the selected
+/// output format. Text and NDJSON output list the merged items; JSON and YAML
+/// also carry the results of each query id.
+///
+/// # Errors
+///
+/// Returns `RelationPresentationError` if serialization or writing fails.
+pub fn write_set<W: Write>(
+    response: &RelationSetResponse,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RelationPresentationError> {
+    match format {
+        OutputFormat::Text => {
+            for item in &response.items {
+                writeln!(out, "{}", item.id)?;
+            }
+        },
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &RelationSetResponseJson::from(response), format)?;
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &RelationSetResponseJson::from(response))?;
+        },
+        OutputFormat::Ndjson => write_ndjson(&response.items, out)?,
+        OutputFormat::Sarif | OutputFormat::Dot | OutputFormat::Junit => {
+            return Err(RelationPresentationError::UnsupportedFormat(format));
+        },
     }
 
     Ok(())
@@ -0,0 +1,527 @@
+use crate::json;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+use thiserror::Error;
+use ureq::{Agent, http::Response};
+
+/// Longest wait between two attempts, including waits requested by the
+/// server through `Retry-After`.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// How remote catalogs are fetched and cached.
+#[derive(Clone, Debug)]
+pub struct RemoteFetchOptions {
+    /// Directory holding downloaded catalogs and their validators.
+    pub cache_dir: PathBuf,
+    /// Use the cached copy only, without touching the network.
+    pub offline: bool,
+    /// Attempts per fetch before falling back to the cached copy.
+    pub max_attempts: u32,
+    /// Wait before the first retry; doubled after each failed attempt.
+    pub initial_backoff: Duration,
+    /// Limit for a single request, including reading the body.
+    pub timeout: Duration,
+}
+
+impl Default for RemoteFetchOptions {
+    fn default() -> Self {
+        Self {
+            cache_dir: PathBuf::from(".docata/cache"),
+            offline: false,
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Where the local copy of a remote catalog came from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemoteCatalogSource {
+    /// The server sent a new version.
+    Downloaded,
+    /// The server confirmed the cached copy is current.
+    NotModified,
+    /// The cached copy was used without contacting the server.
+    Offline,
+    /// The server could not be reached; the cached copy may be out of date.
+    Fallback { reason: String },
+}
+
+/// A remote catalog available on disk.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RemoteCatalog {
+    pub url: String,
+    /// Cached copy, named after the URL and keeping its file extension so the
+    /// catalog format can be detected.
+    pub path: PathBuf,
+    pub source: RemoteCatalogSource,
+}
+
+#[derive(Debug, Error)]
+pub enum RemoteError {
+    #[error("failed to create cache directory '{path}': {source}")]
+    CreateDir {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to write cache file '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("invalid catalog url '{url}': {reason}")]
+    InvalidUrl { url: String, reason: String },
+    #[error("'{url}' is not cached; fetch it once without --offline")]
+    NotCached { url: String },
+    #[error("'{url}' responded with HTTP {status}")]
+    UnexpectedStatus { url: String, status: u16 },
+    #[error("'{url}' is unavailable after {attempts} attempt(s) and not cached: {reason}")]
+    Unavailable {
+        url: String,
+        attempts: u32,
+        reason: String,
+    },
+}
+
+/// Validators of the cached copy and of an interrupted download.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct CacheMeta {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) etag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) last_modified: Option<String>,
+    /// `ETag` of the response `.part` holds the beginning of, used to resume it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) partial_etag: Option<String>,
+}
+
+impl CacheMeta {
+    /// The validators stored at `path`, or none when it is missing or
+    /// unreadable.
+    pub(crate) fn read(path: &Path) -> Self {
+        fs::read_to_string(path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+}
+
+pub(crate) struct CachePaths {
+    pub(crate) catalog: PathBuf,
+    partial: PathBuf,
+    pub(crate) meta: PathBuf,
+}
+
+impl CachePaths {
+    pub(crate) fn new(
+        cache_dir: &Path,
+        url: &str,
+    ) -> Self {
+        let key = cache_key(url);
+        let extension = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| path.rsplit('/').next())
+            .and_then(|name| Path::new(name).extension())
+            .map_or_else(
+                || "json".to_owned(),
+                |ext| ext.to_string_lossy().into_owned(),
+            );
+
+        Self {
+            catalog: cache_dir.join(format!("{key}.{extension}")),
+            partial: cache_dir.join(format!("{key}.{extension}.part")),
+            meta: cache_dir.join(format!("{key}.meta.json")),
+        }
+    }
+}
+
+/// Outcome of one attempt that did not produce a catalog.
+enum AttemptError {
+    /// Worth another attempt, possibly after the server-requested delay.
+    Retry {
+        reason: String,
+        retry_after: Option<Duration>,
+    },
+    Fatal(RemoteError),
+}
+
+/// Fetch the catalog at `url` into the cache directory.
+///
+/// Requests are conditional on the cached copy's `ETag`/`Last-Modified`, and
+/// interrupted downloads resume with a range request. Transport errors, HTTP
+/// 429 and 5xx responses are retried with exponential backoff; when every
+/// attempt fails, the cached copy is returned as a fallback.
+///
+/// # Errors
+///
+/// Returns `RemoteError` when the server rejects the request, or when the
+/// catalog is unavailable and was never cached.
+pub fn fetch(
+    url: &str,
+    options: &RemoteFetchOptions,
+) -> Result<RemoteCatalog, RemoteError> {
+    let paths = CachePaths::new(&options.cache_dir, url);
+    let catalog = |source| RemoteCatalog {
+        url: url.to_owned(),
+        path: paths.catalog.clone(),
+        source,
+    };
+
+    if options.offline {
+        return if paths.catalog.is_file() {
+            Ok(catalog(RemoteCatalogSource::Offline))
+        } else {
+            Err(RemoteError::NotCached {
+                url: url.to_owned(),
+            })
+        };
+    }
+
+    fs::create_dir_all(&options.cache_dir).map_err(|source| RemoteError::CreateDir {
+        path: options.cache_dir.clone(),
+        source,
+    })?;
+    let mut meta = CacheMeta::read(&paths.meta);
+
+    let agent: Agent = Agent::config_builder()
+        .http_status_as_error(false)
+        .timeout_global(Some(options.timeout))
+        .build()
+        .into();
+
+    let attempts = options.max_attempts.max(1);
+    let mut backoff = options.initial_backoff;
+    let mut last_reason = String::new();
+    for attempt in 1..=attempts {
+        match attempt_fetch(&agent, url, &paths, &mut meta) {
+            Ok(source) => return Ok(catalog(source)),
+            Err(AttemptError::Fatal(error)) => return Err(error),
+            Err(AttemptError::Retry {
+                reason,
+                retry_after,
+            }) => {
+                last_reason = reason;
+                if attempt < attempts {
+                    thread::sleep(retry_after.unwrap_or(backoff).min(MAX_BACKOFF));
+                    backoff = backoff.saturating_mul(2);
+                }
+            },
+        }
+    }
+
+    if paths.catalog.is_file() {
+        Ok(catalog(RemoteCatalogSource::Fallback {
+            reason: last_reason,
+        }))
+    } else {
+        Err(RemoteError::Unavailable {
+            url: url.to_owned(),
+            attempts,
+            reason: last_reason,
+        })
+    }
+}
+
+fn attempt_fetch(
+    agent: &Agent,
+    url: &str,
+    paths: &CachePaths,
+    meta: &mut CacheMeta,
+) -> Result<RemoteCatalogSource, AttemptError> {
+    let mut request = agent.get(url);
+    if paths.catalog.is_file() {
+        if let Some(etag) = &meta.etag {
+            request = request.header("If-None-Match", etag);
+        }
+        if let Some(last_modified) = &meta.last_modified {
+            request = request.header("If-Modified-Since", last_modified);
+        }
+    }
+
+    let resume_from = fs::metadata(&paths.partial).map_or(0, |metadata| metadata.len());
+    let resumable = meta
+        .partial_etag
+        .as_ref()
+        .filter(|etag| resume_from > 0 && !etag.starts_with("W/"));
+    if let Some(etag) = resumable {
+        request = request
+            .header("Range", format!("bytes={resume_from}-"))
+            .header("If-Range", etag);
+    }
+
+    let response = request.call().map_err(|error| match error {
+        ureq::Error::BadUri(reason) => AttemptError::Fatal(RemoteError::InvalidUrl {
+            url: url.to_owned(),
+            reason,
+        }),
+        error => AttemptError::Retry {
+            reason: error.to_string(),
+            retry_after: None,
+        },
+    })?;
+
+    match response.status().as_u16() {
+        200 => download(response, paths, meta, false),
+        206 if content_range_start(&response) == Some(resume_from) => {
+            download(response, paths, meta, true)
+        },
+        206 | 416 => {
+            discard_partial(paths, meta);
+            Err(AttemptError::Retry {
+                reason: "server rejected the resumed range".to_owned(),
+                retry_after: None,
+            })
+        },
+        304 if paths.catalog.is_file() => Ok(RemoteCatalogSource::NotModified),
+        status @ (429 | 500..=599) => Err(AttemptError::Retry {
+            reason: format!("HTTP {status}"),
+            retry_after: header(&response, "retry-after")
+                .and_then(|value| value.trim().parse().ok())
+                .map(Duration::from_secs),
+        }),
+        status => Err(AttemptError::Fatal(RemoteError::UnexpectedStatus {
+            url: url.to_owned(),
+            status,
+        })),
+    }
+}
+
+/// Stream the response body into `.part`, then move it over the cached copy.
+fn download(
+    response: Response<ureq::Body>,
+    paths: &CachePaths,
+    meta: &mut CacheMeta,
+    append: bool,
+) -> Result<RemoteCatalogSource, AttemptError> {
+    let etag = header(&response, "etag");
+    let last_modified = header(&response, "last-modified");
+
+    meta.partial_etag.clone_from(&etag);
+    save_meta(paths, meta).map_err(AttemptError::Fatal)?;
+
+    let write_error = |source| {
+        AttemptError::Fatal(RemoteError::Write {
+            path: paths.partial.clone(),
+            source,
+        })
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(append)
+        .truncate(!append)
+        .open(&paths.partial)
+        .map_err(write_error)?;
+
+    let mut body = response.into_body().into_reader();
+    if let Err(error) = io::copy(&mut body, &mut file) {
+        // Keep what arrived so the next attempt can resume from it.
+        let _ = file.flush();
+        return Err(AttemptError::Retry {
+            reason: format!("download interrupted: {error}"),
+            retry_after: None,
+        });
+    }
+    file.sync_all().map_err(write_error)?;
+    drop(file);
+
+    fs::rename(&paths.partial, &paths.catalog).map_err(|source| {
+        AttemptError::Fatal(RemoteError::Write {
+            path: paths.catalog.clone(),
+            source,
+        })
+    })?;
+    *meta = CacheMeta {
+        etag,
+        last_modified,
+        partial_etag: None,
+    };
+    save_meta(paths, meta).map_err(AttemptError::Fatal)?;
+
+    Ok(RemoteCatalogSource::Downloaded)
+}
+
+fn discard_partial(
+    paths: &CachePaths,
+    meta: &mut CacheMeta,
+) {
+    let _ = fs::remove_file(&paths.partial);
+    meta.partial_etag = None;
+}
+
+fn save_meta(
+    paths: &CachePaths,
+    meta: &CacheMeta,
+) -> Result<(), RemoteError> {
+    let write_error = |source| RemoteError::Write {
+        path: paths.meta.clone(),
+        source,
+    };
+    let content = json::to_string(meta, false).map_err(|error| write_error(error.into()))?;
+    let temporary = paths.meta.with_extension("json.tmp");
+    fs::write(&temporary, content).map_err(write_error)?;
+    fs::rename(&temporary, &paths.meta).map_err(write_error)
+}
+
+fn header(
+    response: &Response<ureq::Body>,
+    name: &str,
+) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_owned)
+}
+
+/// Parse the first byte position of `Content-Range: bytes <start>-<end>/<len>`.
+fn content_range_start(response: &Response<ureq::Body>) -> Option<u64> {
+    header(response, "content-range")?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Derive a file name from `url`: a readable slug plus a hash of the full
+/// URL, so distinct URLs with the same slug do not share a cache entry.
+fn cache_key(url: &str) -> String {
+    let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let without_extension = without_scheme
+        .rsplit_once('.')
+        .filter(|(_, extension)| !extension.contains('/'))
+        .map_or(without_scheme, |(stem, _)| stem);
+
+    let mut slug = String::new();
+    for character in without_extension.chars() {
+        if character.is_ascii_alphanumeric() {
+            slug.push(character.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+
+    let hash = crate::hash::fnv1a(url.as_bytes());
+    format!("{slug}-{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RemoteCatalogSource, RemoteError, RemoteFetchOptions, cache_key, fetch};
+    use std::{
+        fs,
+        io::{BufRead, BufReader, Write},
+        net::TcpListener,
+        path::PathBuf,
+        thread,
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    fn temp_cache_dir(name: &str) -> PathBuf {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        std::env::temp_dir().join(format!("docata-remote-{name}-{nanos}"))
+    }
+
+    /// Serve one scripted response per connection and return the request
+    /// headers received for each.
+    fn serve(responses: Vec<&'static str>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        let url = format!(
+            "http://{}/team/catalog.json",
+            listener.local_addr().expect("local addr")
+        );
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().expect("accept connection");
+                let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+                let mut request = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read request");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    request.push_str(&line.to_ascii_lowercase());
+                }
+                requests.push(request);
+                stream
+                    .write_all(response.as_bytes())
+                    .expect("write response");
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn derives_distinct_readable_cache_keys() {
+        let key = cache_key("https://example.com/team/catalog.json");
+
+        assert!(key.starts_with("example-com-team-catalog-"));
+        assert_ne!(key, cache_key("https://example.com/team-catalog.json"));
+    }
+
+    #[test]
+    fn offline_requires_a_cached_copy() {
+        let options = RemoteFetchOptions {
+            cache_dir: temp_cache_dir("offline"),
+            offline: true,
+            ..RemoteFetchOptions::default()
+        };
+
+        let error = fetch("https://example.com/catalog.json", &options).expect_err("not cached");
+        assert!(matches!(error, RemoteError::NotCached { .. }));
+    }
+
+    #[test]
+    fn retries_revalidates_and_falls_back_to_cache() {
+        let (url, server) = serve(vec![
+            "HTTP/1.1 503 Service Unavailable\r\nRetry-After: 0\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 13\r\nConnection: close\r\n\r\n{\"nodes\": []}",
+            "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+        ]);
+        let options = RemoteFetchOptions {
+            cache_dir: temp_cache_dir("revalidate"),
+            max_attempts: 2,
+            initial_backoff: Duration::ZERO,
+            ..RemoteFetchOptions::default()
+        };
+
+        let first = fetch(&url, &options).expect("download after retry");
+        assert_eq!(first.source, RemoteCatalogSource::Downloaded);
+        assert_eq!(
+            fs::read_to_string(&first.path).expect("cached catalog"),
+            "{\"nodes\": []}"
+        );
+
+        let second = fetch(&url, &options).expect("revalidate");
+        assert_eq!(second.source, RemoteCatalogSource::NotModified);
+
+        let requests = server.join().expect("server thread");
+        assert!(requests[2].contains("if-none-match: \"v1\""));
+
+        let third = fetch(&url, &options).expect("fall back to cache");
+        assert!(matches!(third.source, RemoteCatalogSource::Fallback { .. }));
+
+        fs::remove_dir_all(&options.cache_dir).expect("remove cache dir");
+    }
+}
@@ -0,0 +1,308 @@
+use crate::{
+    error::Error,
+    freshness::modified_dates,
+    git::{parse_date, today},
+    scan::{Entry, scan},
+};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+};
+
+/// Points per dependency modified after the document's last review.
+const STALE_DEPENDENCY_WEIGHT: u32 = 3;
+/// Extra points per such dependency modified within the recent window.
+const RECENT_CHANGE_WEIGHT: u32 = 2;
+/// Points for a document without a valid `last_reviewed` date.
+const UNREVIEWED_WEIGHT: u32 = 4;
+/// Points for a document whose `status` is `draft`.
+const DRAFT_WEIGHT: u32 = 2;
+/// One point per this many days since the last review...
+const REVIEW_AGE_PERIOD_DAYS: u64 = 30;
+/// ...up to this many points.
+const MAX_REVIEW_AGE_POINTS: u32 = 6;
+/// Statuses of documents that are never queued for review.
+const RETIRED_STATUSES: [&str; 2] = ["archived", "deprecated"];
+
+/// Output formats for a review queue.
+#[derive(Clone, Copy, Debug)]
+pub enum ReviewQueueFormat {
+    /// A Markdown checklist table, ready to paste into an issue or chat.
+    Markdown,
+    Json,
+}
+
+/// Options for building a review queue.
+#[derive(Clone, Debug)]
+pub struct ReviewQueueOptions {
+    /// Date (`YYYY-MM-DD`) to score against; defaults to today (UTC).
+    pub today: Option<String>,
+    /// Upstream changes within this many days of `today` count as recent.
+    pub recent_days: u64,
+    /// Keep only the highest scoring documents.
+    pub limit: Option<usize>,
+}
+
+impl Default for ReviewQueueOptions {
+    fn default() -> Self {
+        Self {
+            today: None,
+            recent_days: 7,
+            limit: None,
+        }
+    }
+}
+
+/// A document queued for review with the signals behind its score.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ReviewItem {
+    pub id: String,
+    pub path: PathBuf,
+    pub owner: Option<String>,
+    pub status: Option<String>,
+    /// `None` when the document has no valid `last_reviewed` date.
+    pub last_reviewed: Option<String>,
+    /// Days between `last_reviewed` and the queue date.
+    pub review_age_days: Option<u64>,
+    /// Dependencies modified after the last review, sorted by id.
+    pub changed_deps: Vec<String>,
+    /// Subset of `changed_deps` modified within the recent window.
+    pub recent_changes: Vec<String>,
+    /// Number of documents depending directly on this one.
+    pub fan_in: usize,
+    pub score: u32,
+}
+
+/// Documents most needing review, highest score first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReviewQueue {
+    /// Date (`YYYY-MM-DD`) the queue was scored against.
+    pub today: String,
+    pub items: Vec<ReviewItem>,
+}
+
+/// Score documents under `root` by staleness, fan-in, recent upstream
+/// changes, and status.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents fails.
+pub fn run(
+    root: &Path,
+    options: &ReviewQueueOptions,
+) -> Result<ReviewQueue, Error> {
+    let entries = scan(root)?;
+    Ok(build(root, &entries, options))
+}
+
+/// Build a review queue for `entries` scanned from `root`.
+///
+/// A document needs review when a dependency changed since its last review,
+/// it was never reviewed, its review is old, or it is a draft. Fan-in then
+/// raises documents many others rely on; documents needing no review are
+/// left out regardless of fan-in. Archived and deprecated documents are
+/// neither queued nor counted as dependents.
+#[must_use]
+pub fn build(
+    root: &Path,
+    entries: &[Entry],
+    options: &ReviewQueueOptions,
+) -> ReviewQueue {
+    let today = options.today.clone().unwrap_or_else(today);
+    let today_days = parse_date(&today);
+    let modified = modified_dates(root, entries);
+
+    let active = entries
+        .iter()
+        .filter(|entry| {
+            !entry
+                .status
+                .as_deref()
+                .is_some_and(|status| RETIRED_STATUSES.contains(&status))
+        })
+        .collect::<Vec<_>>();
+
+    let mut fan_in = HashMap::<&str, BTreeSet<&str>>::new();
+    for entry in &active {
+        for dep in &entry.deps {
+            fan_in
+                .entry(dep.as_str())
+                .or_default()
+                .insert(entry.id.as_str());
+        }
+    }
+
+    let mut items = active
+        .iter()
+        .filter_map(|entry| review_item(entry, &modified, &fan_in, today_days, options))
+        .collect::<Vec<_>>();
+
+    items.sort_by(|left, right| {
+        right
+            .score
+            .cmp(&left.score)
+            .then_with(|| left.id.cmp(&right.id))
+            .then_with(|| left.path.cmp(&right.path))
+    });
+    if let Some(limit) = options.limit {
+        items.truncate(limit);
+    }
+
+    ReviewQueue { today, items }
+}
+
+/// Score one document, returning `None` when it needs no review.
+fn review_item(
+    entry: &Entry,
+    modified: &HashMap<&str, (&Path, String)>,
+    fan_in: &HashMap<&str, BTreeSet<&str>>,
+    today_days: Option<u64>,
+    options: &ReviewQueueOptions,
+) -> Option<ReviewItem> {
+    let last_reviewed = entry
+        .last_reviewed
+        .clone()
+        .filter(|date| parse_date(date).is_some());
+    let review_age_days = last_reviewed
+        .as_deref()
+        .and_then(parse_date)
+        .zip(today_days)
+        .map(|(reviewed, today)| today.saturating_sub(reviewed));
+
+    let mut changed_deps = entry
+        .deps
+        .iter()
+        .filter_map(|dep| {
+            let (_, date) = modified.get(dep.as_str())?;
+            let changed = last_reviewed
+                .as_deref()
+                .is_none_or(|reviewed| date.as_str() > reviewed);
+            changed.then(|| (dep.clone(), date.as_str()))
+        })
+        .collect::<Vec<_>>();
+    changed_deps.sort_unstable();
+    changed_deps.dedup_by(|left, right| left.0 == right.0);
+    let recent_changes = changed_deps
+        .iter()
+        .filter(|(_, date)| {
+            parse_date(date)
+                .zip(today_days)
+                .is_some_and(|(modified, today)| {
+                    today.saturating_sub(modified) <= options.recent_days
+                })
+        })
+        .map(|(id, _)| id.clone())
+        .collect::<Vec<_>>();
+    // Unreviewed documents score for never having been reviewed, not
+    // for every dependency they have.
+    let stale_count = if last_reviewed.is_some() {
+        changed_deps.len()
+    } else {
+        0
+    };
+
+    let mut score = weight(stale_count, STALE_DEPENDENCY_WEIGHT)
+        + weight(recent_changes.len(), RECENT_CHANGE_WEIGHT);
+    if last_reviewed.is_none() {
+        score += UNREVIEWED_WEIGHT;
+    }
+    if let Some(age) = review_age_days {
+        score += u32::try_from(age / REVIEW_AGE_PERIOD_DAYS)
+            .unwrap_or(u32::MAX)
+            .min(MAX_REVIEW_AGE_POINTS);
+    }
+    if entry.status.as_deref() == Some("draft") {
+        score += DRAFT_WEIGHT;
+    }
+    if score == 0 {
+        return None;
+    }
+
+    let fan_in = fan_in.get(entry.id.as_str()).map_or(0, BTreeSet::len);
+    score += weight(fan_in, 1);
+
+    Some(ReviewItem {
+        id: entry.id.clone(),
+        path: entry.path.clone(),
+        owner: entry.owner.clone(),
+        status: entry.status.clone(),
+        last_reviewed,
+        review_age_days,
+        changed_deps: changed_deps.into_iter().map(|(id, _)| id).collect(),
+        recent_changes,
+        fan_in,
+        score,
+    })
+}
+
+fn weight(
+    count: usize,
+    points: u32,
+) -> u32 {
+    u32::try_from(count)
+        .unwrap_or(u32::MAX)
+        .saturating_mul(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ReviewQueueOptions, build};
+    use crate::scan::Entry;
+    use std::collections::BTreeMap;
+    use std::path::{Path, PathBuf};
+
+    fn entry(
+        id: &str,
+        deps: &[&str],
+        last_reviewed: Option<&str>,
+        status: Option<&str>,
+    ) -> Entry {
+        Entry {
+            id: id.to_owned(),
+            deps: deps.iter().map(|dep| (*dep).to_owned()).collect(),
+            dep_lines: Vec::new(),
+            path: PathBuf::from(format!("missing/{id}.md")),
+            title: None,
+            tags: Vec::new(),
+            node_type: None,
+            domain: None,
+            status: status.map(str::to_owned),
+            source_of_truth: None,
+            last_reviewed: last_reviewed.map(str::to_owned),
+            owner: None,
+            fields: Vec::new(),
+            values: BTreeMap::new(),
+            content_hash: None,
+            history: None,
+            code_owners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn scores_review_age_status_and_fan_in() {
+        let entries = vec![
+            entry("api", &[], Some("2024-05-01"), None),
+            entry("guide", &["api"], Some("2024-01-01"), None),
+            entry("howto", &["api"], None, Some("draft")),
+            entry("legacy", &[], None, Some("deprecated")),
+            entry("fresh", &[], Some("2024-05-31"), None),
+        ];
+        let options = ReviewQueueOptions {
+            today: Some("2024-06-01".to_owned()),
+            ..ReviewQueueOptions::default()
+        };
+
+        let queue = build(Path::new("missing"), &entries, &options);
+
+        let scores = queue
+            .items
+            .iter()
+            .map(|item| (item.id.as_str(), item.score))
+            .collect::<Vec<_>>();
+        // howto: unreviewed (4) + draft (2); guide: 152 days old (5);
+        // api: 31 days old (1) + two dependents (2).
+        assert_eq!(scores, vec![("howto", 6), ("guide", 5), ("api", 3)]);
+        assert_eq!(queue.items[2].fan_in, 2);
+        assert_eq!(queue.items[1].review_age_days, Some(152));
+    }
+}
@@ -0,0 +1,147 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::review_queue::{ReviewItem, ReviewQueue, ReviewQueueFormat};
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct ReviewQueueJson<'a> {
+    command: &'static str,
+    today: &'a str,
+    documents: Vec<ReviewItemJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct ReviewItemJson<'a> {
+    id: &'a str,
+    path: String,
+    score: u32,
+    owner: Option<&'a str>,
+    status: Option<&'a str>,
+    last_reviewed: Option<&'a str>,
+    review_age_days: Option<u64>,
+    fan_in: usize,
+    changed_deps: &'a [String],
+    recent_changes: &'a [String],
+}
+
+impl<'a> From<&'a ReviewQueue> for ReviewQueueJson<'a> {
+    fn from(queue: &'a ReviewQueue) -> Self {
+        Self {
+            command: "review-queue",
+            today: &queue.today,
+            documents: queue.items.iter().map(ReviewItemJson::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a ReviewItem> for ReviewItemJson<'a> {
+    fn from(item: &'a ReviewItem) -> Self {
+        Self {
+            id: &item.id,
+            path: item.path.to_string_lossy().to_string(),
+            score: item.score,
+            owner: item.owner.as_deref(),
+            status: item.status.as_deref(),
+            last_reviewed: item.last_reviewed.as_deref(),
+            review_age_days: item.review_age_days,
+            fan_in: item.fan_in,
+            changed_deps: &item.changed_deps,
+            recent_changes: &item.recent_changes,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReviewQueuePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Write a review queue according to the selected format.
+///
+/// # Errors
+///
+/// Returns `ReviewQueuePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    queue: &ReviewQueue,
+    format: ReviewQueueFormat,
+    out: &mut W,
+) -> Result<(), ReviewQueuePresentationError> {
+    match format {
+        ReviewQueueFormat::Markdown => write_markdown(queue, out),
+        ReviewQueueFormat::Json => write_json(queue, out),
+    }
+}
+
+fn write_json<W: Write>(
+    queue: &ReviewQueue,
+    out: &mut W,
+) -> Result<(), ReviewQueuePresentationError> {
+    json::write(out, &ReviewQueueJson::from(queue), OutputFormat::Json)?;
+    Ok(())
+}
+
+fn write_markdown<W: Write>(
+    queue: &ReviewQueue,
+    out: &mut W,
+) -> Result<(), ReviewQueuePresentationError> {
+    writeln!(out, "# Review queue ({})", queue.today)?;
+    writeln!(out)?;
+    if queue.items.is_empty() {
+        writeln!(out, "Nothing needs review.")?;
+        return Ok(());
+    }
+
+    writeln!(out, "| | Document | Owner | Score | Why |")?;
+    writeln!(out, "|---|---|---|---:|---|")?;
+    for item in &queue.items {
+        writeln!(
+            out,
+            "| [ ] | [{}]({}) | {} | {} | {} |",
+            escape(&item.id),
+            item.path.to_string_lossy().replace(' ', "%20"),
+            item.owner.as_deref().map_or_else(String::new, escape),
+            item.score,
+            escape(&reasons(item).join("; ")),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn reasons(item: &ReviewItem) -> Vec<String> {
+    let mut reasons = Vec::new();
+    match (&item.last_reviewed, item.review_age_days) {
+        (None, _) => reasons.push("never reviewed".to_owned()),
+        (Some(date), Some(age)) => reasons.push(format!("reviewed {date} ({age} days ago)")),
+        (Some(date), None) => reasons.push(format!("reviewed {date}")),
+    }
+    if item.last_reviewed.is_some() && !item.changed_deps.is_empty() {
+        reasons.push(format!(
+            "changed since review: {}",
+            item.changed_deps.join(", ")
+        ));
+    }
+    if !item.recent_changes.is_empty() {
+        reasons.push(format!(
+            "changed recently: {}",
+            item.recent_changes.join(", ")
+        ));
+    }
+    if let Some(status) = &item.status {
+        reasons.push(format!("status {status}"));
+    }
+    if item.fan_in > 0 {
+        reasons.push(format!("{} dependent(s)", item.fan_in));
+    }
+    reasons
+}
+
+/// Keep table cells intact when values contain pipes.
+fn escape(value: &str) -> String {
+    value.replace('|', "\\|")
+}
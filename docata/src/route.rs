@@ -0,0 +1,282 @@
+use crate::{
+    error::Error,
+    freshness,
+    scan::{Entry, scan},
+    validate::build_validation_report,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    path::Path,
+};
+
+/// Kind of finding routed to a document owner.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RouteFindingKind {
+    DuplicateId,
+    UnresolvedDependency,
+    DependencyCycle,
+    /// A dependency changed after the document's `last_reviewed` date.
+    Stale,
+    /// The document transitively depends on a changed document.
+    Impacted,
+}
+
+impl RouteFindingKind {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            RouteFindingKind::DuplicateId => "duplicate_id",
+            RouteFindingKind::UnresolvedDependency => "unresolved_dependency",
+            RouteFindingKind::DependencyCycle => "dependency_cycle",
+            RouteFindingKind::Stale => "stale",
+            RouteFindingKind::Impacted => "impacted",
+        }
+    }
+}
+
+/// One finding attached to the document its owner should act on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RouteFinding {
+    pub kind: RouteFindingKind,
+    pub id: String,
+    pub path: String,
+    pub message: String,
+}
+
+/// Findings for documents sharing one owner; `owner` is `None` for documents
+/// without one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnerRoute {
+    pub owner: Option<String>,
+    pub findings: Vec<RouteFinding>,
+}
+
+/// Current findings grouped by the owner of the affected document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RoutingReport {
+    /// Owned routes sorted by owner, followed by the unowned route.
+    pub routes: Vec<OwnerRoute>,
+}
+
+impl RoutingReport {
+    #[must_use]
+    pub fn finding_count(&self) -> usize {
+        self.routes.iter().map(|route| route.findings.len()).sum()
+    }
+}
+
+/// Collect validation, freshness, and change-impact findings under `root` and
+/// group them by document owner.
+///
+/// `changed` lists ids of documents that changed; every document depending
+/// on one of them, directly or transitively, receives an impact finding.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents fails.
+pub fn run(
+    root: &Path,
+    changed: &[String],
+) -> Result<RoutingReport, Error> {
+    let entries = scan(root)?;
+    let owners_by_path = entries
+        .iter()
+        .map(|entry| {
+            (
+                entry.path.to_string_lossy().to_string(),
+                entry.owner.clone(),
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let findings = collect_findings(root, &entries, changed);
+
+    Ok(group_by_owner(findings, &owners_by_path))
+}
+
+/// Collect validation, freshness, and change-impact findings for `entries`
+/// scanned from `root`.
+pub(crate) fn collect_findings(
+    root: &Path,
+    entries: &[Entry],
+    changed: &[String],
+) -> Vec<RouteFinding> {
+    let mut findings = Vec::new();
+
+    let validation = build_validation_report(entries);
+    for duplicate in &validation.duplicate_ids {
+        for path in &duplicate.paths {
+            findings.push(RouteFinding {
+                kind: RouteFindingKind::DuplicateId,
+                id: duplicate.id.clone(),
+                path: path.clone(),
+                message: format!(
+                    "`{}` appears in {} documents",
+                    duplicate.id,
+                    duplicate.paths.len()
+                ),
+            });
+        }
+    }
+    for unresolved in &validation.unresolved_dependencies {
+        findings.push(RouteFinding {
+            kind: RouteFindingKind::UnresolvedDependency,
+            id: unresolved.from_id.clone(),
+            path: unresolved.path.clone(),
+            message: format!("`{}` -> `{}`", unresolved.from_id, unresolved.to_id),
+        });
+    }
+    for cycle in &validation.dependency_cycles {
+        let message = format!("dependency cycle: {}", cycle.ids.join(" -> "));
+        for id in &cycle.ids {
+            for entry in entries.iter().filter(|entry| entry.id == *id) {
+                findings.push(RouteFinding {
+                    kind: RouteFindingKind::DependencyCycle,
+                    id: id.clone(),
+                    path: entry.path.to_string_lossy().to_string(),
+                    message: message.clone(),
+                });
+            }
+        }
+    }
+
+    for stale in freshness::report(root, entries).stale {
+        let deps = stale
+            .changed_deps
+            .iter()
+            .map(|dep| format!("`{}` ({})", dep.id, dep.modified))
+            .collect::<Vec<_>>();
+        findings.push(RouteFinding {
+            kind: RouteFindingKind::Stale,
+            id: stale.id,
+            path: stale.path.to_string_lossy().to_string(),
+            message: format!(
+                "reviewed {}, since changed: {}",
+                stale.last_reviewed,
+                deps.join(", ")
+            ),
+        });
+    }
+
+    findings.extend(impact_findings(entries, changed));
+
+    findings
+}
+
+/// Walk reverse dependencies from each changed id and report every document
+/// reached, naming the changed id that reached it first.
+fn impact_findings(
+    entries: &[Entry],
+    changed: &[String],
+) -> Vec<RouteFinding> {
+    let mut dependents = HashMap::<&str, Vec<&Entry>>::new();
+    for entry in entries {
+        for dep in &entry.deps {
+            dependents.entry(dep.as_str()).or_default().push(entry);
+        }
+    }
+
+    let mut findings = Vec::new();
+    let mut reached = HashSet::new();
+    for changed_id in changed {
+        let mut queue = VecDeque::from([changed_id.as_str()]);
+        while let Some(id) = queue.pop_front() {
+            for entry in dependents.get(id).into_iter().flatten() {
+                if !reached.insert((entry.id.as_str(), entry.path.as_path())) {
+                    continue;
+                }
+                queue.push_back(&entry.id);
+                findings.push(RouteFinding {
+                    kind: RouteFindingKind::Impacted,
+                    id: entry.id.clone(),
+                    path: entry.path.to_string_lossy().to_string(),
+                    message: format!("depends on changed `{changed_id}`"),
+                });
+            }
+        }
+    }
+
+    findings
+}
+
+fn group_by_owner(
+    findings: Vec<RouteFinding>,
+    owners_by_path: &HashMap<String, Option<String>>,
+) -> RoutingReport {
+    let mut grouped = BTreeMap::<Option<String>, Vec<RouteFinding>>::new();
+    for finding in findings {
+        let owner = owners_by_path.get(&finding.path).cloned().flatten();
+        grouped.entry(owner).or_default().push(finding);
+    }
+
+    let mut routes = grouped
+        .into_iter()
+        .map(|(owner, mut findings)| {
+            findings.sort_by(|left, right| {
+                (left.kind, &left.id, &left.path).cmp(&(right.kind, &right.id, &right.path))
+            });
+            OwnerRoute { owner, findings }
+        })
+        .collect::<Vec<_>>();
+    // `None` sorts first in the map; list unowned findings last.
+    if routes.first().is_some_and(|route| route.owner.is_none()) {
+        routes.rotate_left(1);
+    }
+
+    RoutingReport { routes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RouteFindingKind, impact_findings};
+    use crate::scan::Entry;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn entry(
+        id: &str,
+        deps: &[&str],
+    ) -> Entry {
+        Entry {
+            id: id.to_owned(),
+            deps: deps.iter().map(ToString::to_string).collect(),
+            dep_lines: Vec::new(),
+            path: PathBuf::from(format!("docs/{id}.md")),
+            title: None,
+            tags: Vec::new(),
+            node_type: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            last_reviewed: None,
+            owner: None,
+            fields: Vec::new(),
+            values: BTreeMap::new(),
+            content_hash: None,
+            history: None,
+            code_owners: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn impact_reaches_transitive_dependents_once() {
+        let entries = vec![
+            entry("api", &[]),
+            entry("guide", &["api"]),
+            entry("tutorial", &["guide", "api"]),
+            entry("unrelated", &[]),
+        ];
+
+        let findings = impact_findings(&entries, &["api".to_owned()]);
+        let ids = findings
+            .iter()
+            .map(|finding| finding.id.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(ids, vec!["guide", "tutorial"]);
+        assert!(
+            findings
+                .iter()
+                .all(|finding| finding.kind == RouteFindingKind::Impacted)
+        );
+    }
+}
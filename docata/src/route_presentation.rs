@@ -0,0 +1,148 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::route::{OwnerRoute, RouteFinding, RoutingReport};
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct RoutingReportJson<'a> {
+    command: &'static str,
+    summary: RoutingSummaryJson,
+    routes: Vec<OwnerRouteJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RoutingSummaryJson {
+    owners: usize,
+    findings: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct OwnerRouteJson<'a> {
+    owner: Option<&'a str>,
+    count: usize,
+    findings: Vec<RouteFindingJson<'a>>,
+}
+
+#[derive(Debug, Serialize)]
+struct RouteFindingJson<'a> {
+    kind: &'static str,
+    id: &'a str,
+    path: &'a str,
+    message: &'a str,
+}
+
+impl<'a> From<&'a RoutingReport> for RoutingReportJson<'a> {
+    fn from(report: &'a RoutingReport) -> Self {
+        Self {
+            command: "route",
+            summary: RoutingSummaryJson {
+                owners: report.routes.len(),
+                findings: report.finding_count(),
+            },
+            routes: report.routes.iter().map(OwnerRouteJson::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a OwnerRoute> for OwnerRouteJson<'a> {
+    fn from(route: &'a OwnerRoute) -> Self {
+        Self {
+            owner: route.owner.as_deref(),
+            count: route.findings.len(),
+            findings: route.findings.iter().map(RouteFindingJson::from).collect(),
+        }
+    }
+}
+
+impl<'a> From<&'a RouteFinding> for RouteFindingJson<'a> {
+    fn from(finding: &'a RouteFinding) -> Self {
+        Self {
+            kind: finding.kind.as_str(),
+            id: &finding.id,
+            path: &finding.path,
+            message: &finding.message,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum RoutePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a routing report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `RoutePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &RoutingReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RoutePresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(RoutePresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    report: &RoutingReport,
+    out: &mut W,
+) -> Result<(), RoutePresentationError> {
+    yaml_serde::to_writer(out, &RoutingReportJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &RoutingReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), RoutePresentationError> {
+    json::write(out, &RoutingReportJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &RoutingReport,
+    out: &mut W,
+) -> Result<(), RoutePresentationError> {
+    if report.routes.is_empty() {
+        writeln!(out, "no findings to route")?;
+        return Ok(());
+    }
+
+    for route in &report.routes {
+        writeln!(
+            out,
+            "{} ({})",
+            route.owner.as_deref().unwrap_or("unowned"),
+            route.findings.len()
+        )?;
+        for finding in &route.findings {
+            writeln!(
+                out,
+                "  - [{}] {} ({}): {}",
+                finding.kind.as_str(),
+                finding.id,
+                finding.path,
+                finding.message
+            )?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,282 @@
+use crate::format::CatalogFormat;
+use crate::hash::hex;
+use crate::store::{CatalogStore, StoreError, url_path};
+use sha2::{Digest, Sha256};
+use std::{
+    io::Read,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use ureq::{
+    Agent, AsSendBody, Body,
+    http::{Request, Response},
+};
+
+const SERVICE: &str = "s3";
+const SIGNED_HEADERS: &str = "host;x-amz-content-sha256;x-amz-date";
+
+/// An object in S3-compatible storage, addressed path-style
+/// (`<endpoint>/<bucket>/<key>`) so self-hosted servers such as `MinIO` work
+/// with the same settings as AWS.
+#[derive(Clone, Debug)]
+pub struct S3Store {
+    endpoint: String,
+    region: String,
+    bucket: String,
+    key: String,
+    access_key_id: String,
+    secret_access_key: String,
+    session_token: Option<String>,
+    agent: Agent,
+}
+
+impl S3Store {
+    /// Read credentials and endpoint from the standard AWS variables:
+    /// `AWS_ACCESS_KEY_ID`, `AWS_SECRET_ACCESS_KEY`, optional
+    /// `AWS_SESSION_TOKEN`, `AWS_REGION` (default `us-east-1`), and
+    /// `AWS_ENDPOINT_URL` (default `https://s3.<region>.amazonaws.com`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError` when credentials are missing.
+    pub fn from_env(
+        bucket: &str,
+        key: &str,
+    ) -> Result<Self, StoreError> {
+        let var = |name: &str| std::env::var(name).ok().filter(|value| !value.is_empty());
+        let missing = |name: &str| StoreError::InvalidLocation {
+            location: format!("s3://{bucket}/{key}"),
+            reason: format!("{name} is not set"),
+        };
+
+        let region = var("AWS_REGION").unwrap_or_else(|| "us-east-1".to_owned());
+        Ok(Self {
+            endpoint: var("AWS_ENDPOINT_URL")
+                .unwrap_or_else(|| format!("https://s3.{region}.amazonaws.com")),
+            region,
+            bucket: bucket.to_owned(),
+            key: key.to_owned(),
+            access_key_id: var("AWS_ACCESS_KEY_ID").ok_or_else(|| missing("AWS_ACCESS_KEY_ID"))?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")
+                .ok_or_else(|| missing("AWS_SECRET_ACCESS_KEY"))?,
+            session_token: var("AWS_SESSION_TOKEN"),
+            agent: Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .into(),
+        })
+    }
+
+    fn canonical_uri(&self) -> String {
+        let mut uri = format!("/{}", uri_encode(&self.bucket));
+        for segment in self.key.split('/') {
+            uri.push('/');
+            uri.push_str(&uri_encode(segment));
+        }
+        uri
+    }
+
+    /// Compute the `Authorization` header for a request signed with AWS
+    /// Signature Version 4 at `timestamp` (`YYYYMMDDTHHMMSSZ`).
+    fn authorization(
+        &self,
+        method: &str,
+        host: &str,
+        payload_hash: &str,
+        timestamp: &str,
+    ) -> String {
+        let date = &timestamp[..8];
+        let scope = format!("{date}/{}/{SERVICE}/aws4_request", self.region);
+        let canonical_request = format!(
+            "{method}\n{}\n\nhost:{host}\nx-amz-content-sha256:{payload_hash}\nx-amz-date:{timestamp}\n\n{SIGNED_HEADERS}\n{payload_hash}",
+            self.canonical_uri()
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{timestamp}\n{scope}\n{}",
+            hex(&Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let mut key = hmac_sha256(
+            format!("AWS4{}", self.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        );
+        for part in [self.region.as_str(), SERVICE, "aws4_request"] {
+            key = hmac_sha256(&key, part.as_bytes());
+        }
+        let signature = hex(&hmac_sha256(&key, string_to_sign.as_bytes()));
+
+        format!(
+            "AWS4-HMAC-SHA256 Credential={}/{scope}, SignedHeaders={SIGNED_HEADERS}, Signature={signature}",
+            self.access_key_id
+        )
+    }
+
+    fn send(
+        &self,
+        method: &str,
+        body: Option<&[u8]>,
+    ) -> Result<Response<Body>, StoreError> {
+        let endpoint = self.endpoint.trim_end_matches('/');
+        let host = endpoint
+            .split_once("://")
+            .map_or(endpoint, |(_, rest)| rest)
+            .split('/')
+            .next()
+            .unwrap_or_default();
+        let payload_hash = hex(&Sha256::digest(body.unwrap_or_default()));
+        let timestamp = amz_timestamp(SystemTime::now());
+        let url = format!("{endpoint}{}", self.canonical_uri());
+
+        let request = Request::builder()
+            .method(method)
+            .uri(&url)
+            .header("x-amz-content-sha256", &payload_hash)
+            .header("x-amz-date", &timestamp)
+            .header(
+                "Authorization",
+                self.authorization(method, host, &payload_hash, &timestamp),
+            );
+        let request = match &self.session_token {
+            Some(token) => request.header("x-amz-security-token", token),
+            None => request,
+        };
+        let response = match body {
+            Some(body) => self.run(request.body(body)),
+            None => self.run(request.body(())),
+        }?;
+        match response.status().as_u16() {
+            200..=299 => Ok(response),
+            404 => Err(StoreError::NotFound {
+                location: self.location(),
+            }),
+            status => Err(StoreError::HttpStatus {
+                location: self.location(),
+                status,
+            }),
+        }
+    }
+
+    fn run(
+        &self,
+        request: Result<Request<impl AsSendBody>, ureq::http::Error>,
+    ) -> Result<Response<Body>, StoreError> {
+        let http_error = |reason: String| StoreError::Http {
+            location: self.location(),
+            reason,
+        };
+        let request = request.map_err(|error| http_error(error.to_string()))?;
+        self.agent
+            .run(request)
+            .map_err(|error| http_error(error.to_string()))
+    }
+}
+
+impl CatalogStore for S3Store {
+    fn location(&self) -> String {
+        format!("s3://{}/{}", self.bucket, self.key)
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogFormat::from_path(Path::new(url_path(&self.key)))
+    }
+
+    fn load(&self) -> Result<Vec<u8>, StoreError> {
+        let response = self.send("GET", None)?;
+        let mut content = Vec::new();
+        response
+            .into_body()
+            .into_reader()
+            .read_to_end(&mut content)
+            .map_err(|source| StoreError::Read {
+                location: self.location(),
+                source,
+            })?;
+        Ok(content)
+    }
+
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        self.send("PUT", Some(content))?;
+        Ok(())
+    }
+}
+
+fn hmac_sha256(
+    key: &[u8],
+    message: &[u8],
+) -> Vec<u8> {
+    const BLOCK_SIZE: usize = 64;
+
+    let mut block = [0_u8; BLOCK_SIZE];
+    if key.len() > BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|byte| byte ^ 0x36));
+    inner.update(message);
+    let mut outer = Sha256::new();
+    outer.update(block.map(|byte| byte ^ 0x5c));
+    outer.update(inner.finalize());
+    outer.finalize().to_vec()
+}
+
+/// Percent-encode everything except RFC 3986 unreserved characters.
+fn uri_encode(segment: &str) -> String {
+    use std::fmt::Write;
+
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+fn amz_timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+    let date = crate::git::format_date(seconds / 86_400).replace('-', "");
+    let time_of_day = seconds % 86_400;
+    format!(
+        "{date}T{:02}{:02}{:02}Z",
+        time_of_day / 3_600,
+        time_of_day % 3_600 / 60,
+        time_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{amz_timestamp, hex, hmac_sha256, uri_encode};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn computes_hmac_sha256() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn formats_signing_inputs() {
+        assert_eq!(
+            uri_encode("team catalog+v1.json"),
+            "team%20catalog%2Bv1.json"
+        );
+        assert_eq!(
+            amz_timestamp(UNIX_EPOCH + Duration::from_secs(1_369_353_600)),
+            "20130524T000000Z"
+        );
+    }
+}
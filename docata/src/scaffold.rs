@@ -0,0 +1,352 @@
+use crate::{
+    build::external_ids,
+    config::{Config, ConfigError, find_config_file},
+    git::today,
+    scan::{ScanError, ScanOptions, scan_with_options},
+    validate::find_unresolved_dependencies,
+};
+use std::collections::BTreeSet;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use thiserror::Error;
+use yaml_serde::{Mapping, Value};
+
+/// A document for [`create`] to scaffold.
+#[derive(Clone, Debug, Default)]
+pub struct NewDocument {
+    pub id: String,
+    /// Document `type`; its `[types.<type>]` table in `docata.toml` supplies
+    /// the template and the required fields.
+    pub node_type: Option<String>,
+    pub title: Option<String>,
+    /// Defaults to the first allowed `[validation] statuses` value, if any.
+    pub status: Option<String>,
+    /// `last_reviewed` date (`YYYY-MM-DD`); defaults to today (UTC).
+    pub date: Option<String>,
+    /// File to create, relative to the documents directory; defaults to
+    /// `<id>.md`.
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Debug, Error)]
+pub enum ScaffoldError {
+    #[error("id '{id}' is already used by '{path}'")]
+    IdTaken { id: String, path: PathBuf },
+    #[error("id '{id}' cannot name a file; pass a path for it")]
+    InvalidId { id: String },
+    #[error("file '{path}' already exists")]
+    FileExists { path: PathBuf },
+    #[error("failed to read template '{path}': {source}")]
+    ReadTemplate {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to parse frontmatter of template '{path}': {source}")]
+    ParseTemplate {
+        path: PathBuf,
+        #[source]
+        source: yaml_serde::Error,
+    },
+    #[error("failed to render frontmatter: {0}")]
+    Render(#[source] yaml_serde::Error),
+    #[error("failed to write file '{path}': {source}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("{0}")]
+    Config(#[from] ConfigError),
+    #[error("{0}")]
+    Scan(#[from] ScanError),
+}
+
+/// Create the Markdown file for `document` under `root` and return its path.
+///
+/// The frontmatter sets `id`, `title`, `type`, `status`, and
+/// `last_reviewed`, followed by the frontmatter of the type's template and
+/// an empty value for each of the type's required fields still unset. The
+/// template body follows, with `{{id}}`, `{{title}}`, `{{type}}`, and
+/// `{{date}}` filled in; without a template the body is a heading.
+///
+/// # Errors
+///
+/// Returns `ScaffoldError::IdTaken` when a document under `root` already
+/// uses the id, `ScaffoldError::FileExists` when the file exists, or
+/// `ScaffoldError` when reading the config or template or writing the file
+/// fails.
+pub fn create(
+    root: &Path,
+    document: &NewDocument,
+) -> Result<PathBuf, ScaffoldError> {
+    let relative = match &document.path {
+        Some(path) => path.clone(),
+        None if is_file_name(&document.id) => PathBuf::from(format!("{}.md", document.id)),
+        None => {
+            return Err(ScaffoldError::InvalidId {
+                id: document.id.clone(),
+            });
+        },
+    };
+
+    let scanned = scan_with_options(
+        root,
+        ScanOptions {
+            report_skipped: true,
+            lenient: true,
+            content_hash: false,
+            git_metadata: false,
+            code_owners: false,
+        },
+    )?;
+    if let Some(entry) = scanned.entries.iter().find(|entry| entry.id == document.id) {
+        return Err(ScaffoldError::IdTaken {
+            id: document.id.clone(),
+            path: entry.path.clone(),
+        });
+    }
+
+    let config_path = find_config_file(root);
+    let config = match &config_path {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    let date = document.date.clone().unwrap_or_else(today);
+    let title = document.title.as_deref().unwrap_or(&document.id);
+    let contract = document
+        .node_type
+        .as_ref()
+        .and_then(|node_type| config.types.get(node_type));
+
+    let mut frontmatter = Mapping::new();
+    let mut set = |key: &str, value: Option<&str>| {
+        if let Some(value) = value {
+            frontmatter.insert(key.into(), value.into());
+        }
+    };
+    set("id", Some(&document.id));
+    set("title", document.title.as_deref());
+    set("type", document.node_type.as_deref());
+    set(
+        "status",
+        document
+            .status
+            .as_deref()
+            .or(config.validation.statuses.first().map(String::as_str)),
+    );
+    set("last_reviewed", Some(&date));
+
+    let template = contract.and_then(|contract| contract.template.as_ref());
+    let body = match template {
+        Some(template) => {
+            let path = config_path
+                .as_deref()
+                .and_then(Path::parent)
+                .unwrap_or(root)
+                .join(template);
+            let text = fs::read_to_string(&path)
+                .map_err(|source| ScaffoldError::ReadTemplate {
+                    path: path.clone(),
+                    source,
+                })?
+                .replace("{{id}}", &document.id)
+                .replace("{{title}}", title)
+                .replace(
+                    "{{type}}",
+                    document.node_type.as_deref().unwrap_or_default(),
+                )
+                .replace("{{date}}", &date);
+            let (template_frontmatter, body) = split_frontmatter(&text);
+            if let Some(template_frontmatter) = template_frontmatter {
+                let fields: Mapping = yaml_serde::from_str(template_frontmatter)
+                    .map_err(|source| ScaffoldError::ParseTemplate { path, source })?;
+                for (key, value) in fields {
+                    frontmatter.entry(key).or_insert(value);
+                }
+            }
+            body.to_owned()
+        },
+        None => format!("# {title}\n"),
+    };
+    for field in contract.iter().flat_map(|contract| &contract.required) {
+        frontmatter
+            .entry(field.as_str().into())
+            .or_insert(Value::Null);
+    }
+
+    let frontmatter = yaml_serde::to_string(&frontmatter).map_err(ScaffoldError::Render)?;
+    let path = root.join(relative);
+    write_new(&path, &format!("---\n{frontmatter}---\n{body}"))?;
+
+    Ok(path)
+}
+
+/// Create a stub with `id` and `status: stub` in `dir`, relative to `root`,
+/// for every id that the `deps` of a document under `root` names but no
+/// document has, and return the paths created, sorted by id.
+///
+/// Ids in `[validation] external_ids` are not stubbed.
+///
+/// # Errors
+///
+/// Returns `ScaffoldError::InvalidId` when a missing id cannot name a file,
+/// before any stub is written, `ScaffoldError::FileExists` when a stub's file
+/// exists, or `ScaffoldError` when scanning or writing fails.
+pub fn create_stubs(
+    root: &Path,
+    dir: &Path,
+) -> Result<Vec<PathBuf>, ScaffoldError> {
+    let scanned = scan_with_options(
+        root,
+        ScanOptions {
+            report_skipped: true,
+            lenient: true,
+            content_hash: false,
+            git_metadata: false,
+            code_owners: false,
+        },
+    )?;
+    let missing = find_unresolved_dependencies(&scanned.entries, &external_ids(root))
+        .into_iter()
+        .map(|unresolved| unresolved.to_id)
+        .collect::<BTreeSet<_>>();
+    if let Some(id) = missing.iter().find(|id| !is_file_name(id)) {
+        return Err(ScaffoldError::InvalidId { id: id.clone() });
+    }
+
+    let mut created = Vec::new();
+    for id in missing {
+        let mut frontmatter = Mapping::new();
+        frontmatter.insert("id".into(), id.as_str().into());
+        frontmatter.insert("status".into(), "stub".into());
+        let frontmatter = yaml_serde::to_string(&frontmatter).map_err(ScaffoldError::Render)?;
+        let path = root.join(dir).join(format!("{id}.md"));
+        write_new(&path, &format!("---\n{frontmatter}---\n# {id}\n"))?;
+        created.push(path);
+    }
+    Ok(created)
+}
+
+/// Whether `id` can name a Markdown file below the documents directory.
+fn is_file_name(id: &str) -> bool {
+    !id.is_empty()
+        && Path::new(id)
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+/// The frontmatter block of `text`, if it starts with one, and the rest.
+fn split_frontmatter(text: &str) -> (Option<&str>, &str) {
+    let Some(rest) = text.strip_prefix("---\n") else {
+        return (None, text);
+    };
+    if let Some(body) = rest.strip_prefix("---\n") {
+        return (None, body);
+    }
+    match rest.find("\n---\n") {
+        Some(end) => (Some(&rest[..=end]), &rest[end + 5..]),
+        None => (None, text),
+    }
+}
+
+/// Write `content` to `path`, creating parent directories, unless the file
+/// already exists.
+fn write_new(
+    path: &Path,
+    content: &str,
+) -> Result<(), ScaffoldError> {
+    let write_error = |source| ScaffoldError::Write {
+        path: path.to_path_buf(),
+        source,
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(write_error)?;
+    }
+    let mut file = OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .map_err(|source| match source.kind() {
+            std::io::ErrorKind::AlreadyExists => ScaffoldError::FileExists {
+                path: path.to_path_buf(),
+            },
+            _ => write_error(source),
+        })?;
+    file.write_all(content.as_bytes()).map_err(write_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{NewDocument, ScaffoldError, create, create_stubs};
+    use crate::testing::{TestWorkspace, write_markdown};
+    use std::fs;
+
+    #[test]
+    fn fills_frontmatter_from_the_type_template() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path().join("docs");
+        fs::create_dir_all(&docs).expect("create docs directory");
+        fs::write(
+            workspace.path().join("docata.toml"),
+            "[validation]\nstatuses = [\"draft\", \"stable\"]\n\n[types.runbook]\nrequired = [\"service\", \"oncall\"]\ntemplate = \"runbook.md\"\n",
+        )
+        .expect("write config");
+        fs::write(
+            workspace.path().join("runbook.md"),
+            "---\nservice: billing\n---\n# {{title}}\n\nOwned since {{date}}.\n",
+        )
+        .expect("write template");
+
+        let path = create(
+            &docs,
+            &NewDocument {
+                id: "billing-oncall".to_owned(),
+                node_type: Some("runbook".to_owned()),
+                title: Some("Billing on-call".to_owned()),
+                date: Some("2026-01-02".to_owned()),
+                ..NewDocument::default()
+            },
+        )
+        .expect("create document");
+
+        assert_eq!(path, docs.join("billing-oncall.md"));
+        assert_eq!(
+            fs::read_to_string(path).expect("read document"),
+            "---\nid: billing-oncall\ntitle: Billing on-call\ntype: runbook\nstatus: draft\nlast_reviewed: 2026-01-02\nservice: billing\noncall: null\n---\n# Billing on-call\n\nOwned since 2026-01-02.\n"
+        );
+    }
+
+    #[test]
+    fn rejects_ids_already_in_use() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "guides/api.md", "api", &[]);
+
+        let result = create(
+            workspace.path(),
+            &NewDocument {
+                id: "api".to_owned(),
+                ..NewDocument::default()
+            },
+        );
+
+        assert!(matches!(result, Err(ScaffoldError::IdTaken { .. })));
+    }
+
+    #[test]
+    fn stubs_unresolved_dependencies() {
+        let workspace = TestWorkspace::new();
+        write_markdown(workspace.path(), "api.md", "api", &["db", "auth"]);
+        write_markdown(workspace.path(), "web.md", "web", &["api", "db"]);
+
+        let created = create_stubs(workspace.path(), "planned".as_ref()).expect("create stubs");
+
+        let planned = workspace.path().join("planned");
+        assert_eq!(created, [planned.join("auth.md"), planned.join("db.md")]);
+        assert_eq!(
+            fs::read_to_string(planned.join("db.md")).expect("read stub"),
+            "---\nid: db\nstatus: stub\n---\n# db\n"
+        );
+    }
+}
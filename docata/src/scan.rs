@@ -1,8 +1,17 @@
+use crate::{
+    codeowners::CodeOwners,
+    config::Config,
+    git, hash, namespace,
+    progress::{NoProgress, ProgressEvent, ProgressSink},
+    source::{DocumentSource, FileSystemSource},
+};
+#[cfg(feature = "parallel")]
 use rayon::prelude::*;
 use serde::Deserialize;
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+    io::{self, BufRead, Read},
     path::{Path, PathBuf},
 };
 use thiserror::Error;
@@ -12,11 +21,131 @@ use walkdir::WalkDir;
 pub struct Entry {
     pub id: String,
     pub deps: Vec<String>,
+    /// 1-based file line declaring each of `deps`, in the same order; empty
+    /// for entries not read from a file.
+    pub dep_lines: Vec<usize>,
     pub path: PathBuf,
+    pub title: Option<String>,
+    pub tags: Vec<String>,
     pub node_type: Option<String>,
     pub domain: Option<String>,
     pub status: Option<String>,
     pub source_of_truth: Option<String>,
+    /// Date (`YYYY-MM-DD`) the document was last reviewed.
+    pub last_reviewed: Option<String>,
+    /// Team or person responsible for the document.
+    pub owner: Option<String>,
+    /// Frontmatter keys set to a non-empty value, sorted.
+    pub fields: Vec<String>,
+    /// Frontmatter values that are a string or a list of strings, by key.
+    /// Custom relation kinds read their ids from here.
+    pub values: BTreeMap<String, Vec<String>>,
+    /// `sha256:<hex>` of the body after the frontmatter, when scanned with
+    /// [`ScanOptions::content_hash`].
+    pub content_hash: Option<String>,
+    /// The file's git history, when scanned with
+    /// [`ScanOptions::git_metadata`] inside a git work tree.
+    pub history: Option<git::FileHistory>,
+    /// Owners from the repository's CODEOWNERS file, when scanned with
+    /// [`ScanOptions::code_owners`].
+    pub code_owners: Vec<String>,
+}
+
+/// Why a Markdown file produced no catalog entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The file does not start with a `---` frontmatter block.
+    NoFrontmatter,
+    /// The frontmatter has no `id`.
+    MissingId,
+}
+
+impl SkipReason {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            SkipReason::NoFrontmatter => "no_frontmatter",
+            SkipReason::MissingId => "missing_id",
+        }
+    }
+}
+
+impl Display for SkipReason {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(match self {
+            SkipReason::NoFrontmatter => "no frontmatter",
+            SkipReason::MissingId => "frontmatter has no `id`",
+        })
+    }
+}
+
+/// A Markdown file left out of the catalog.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SkippedFile {
+    pub path: PathBuf,
+    pub reason: SkipReason,
+}
+
+impl Display for SkippedFile {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        write!(f, "skipped {}: {}", self.path.display(), self.reason)
+    }
+}
+
+/// A per-file scan error collected by a lenient scan.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ScanDiagnostic {
+    pub path: PathBuf,
+    pub message: String,
+    /// 1-based line in `path` where the error was found, when known.
+    pub line: Option<usize>,
+    /// 1-based column on `line`, when known.
+    pub column: Option<usize>,
+}
+
+impl Display for ScanDiagnostic {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// How [`scan_with_options`] treats files that produce no entry.
+#[derive(Clone, Copy, Debug, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ScanOptions {
+    /// Collect files without frontmatter or without an `id` into
+    /// [`ScanOutcome::skipped`] instead of dropping or rejecting them.
+    pub report_skipped: bool,
+    /// Collect per-file read and parse errors into
+    /// [`ScanOutcome::diagnostics`] instead of aborting the scan.
+    pub lenient: bool,
+    /// Read each document's body to the end to fill
+    /// [`Entry::content_hash`].
+    pub content_hash: bool,
+    /// Query git for each document's history to fill [`Entry::history`].
+    pub git_metadata: bool,
+    /// Resolve each document's path against the repository's CODEOWNERS
+    /// file to fill [`Entry::code_owners`].
+    pub code_owners: bool,
+}
+
+/// Entries scanned under a root, with the Markdown files that produced none.
+#[derive(Debug, Default)]
+pub struct ScanOutcome {
+    pub entries: Vec<Entry>,
+    /// Skipped files, sorted by path.
+    pub skipped: Vec<SkippedFile>,
+    /// Files that failed to scan, sorted by path.
+    pub diagnostics: Vec<ScanDiagnostic>,
 }
 
 #[derive(Debug, Error)]
@@ -27,6 +156,12 @@ pub enum ScanError {
         #[source]
         source: walkdir::Error,
     },
+    #[error("failed to list documents in '{location}': {source}")]
+    ListDocuments {
+        location: String,
+        #[source]
+        source: std::io::Error,
+    },
     #[error("failed to open file '{path}': {source}")]
     OpenFile {
         path: PathBuf,
@@ -47,16 +182,245 @@ pub enum ScanError {
     },
     #[error("frontmatter is too large in '{path}'")]
     FrontmatterTooLarge { path: PathBuf },
+    #[error("frontmatter in '{path}' has no `id`")]
+    MissingId { path: PathBuf },
+}
+
+impl ScanError {
+    /// 1-based line and column in the file where the error was found, for
+    /// frontmatter parse errors that carry a position.
+    #[must_use]
+    pub fn location(&self) -> Option<(usize, usize)> {
+        match self {
+            ScanError::ParseYaml { source, .. } => source
+                .location()
+                .map(|location| (location.line(), location.column())),
+            _ => None,
+        }
+    }
 }
 
 /// Scan markdown documents under `root` and extract frontmatter entries.
 ///
+/// Files without frontmatter are silently skipped.
+///
 /// # Errors
 ///
 /// Returns `ScanError` when walking the directory, opening files, reading
-/// lines, or parsing frontmatter fails.
+/// lines, or parsing frontmatter fails, or when frontmatter has no `id`.
 pub fn scan(root: &Path) -> Result<Vec<Entry>, ScanError> {
-    let paths: Vec<PathBuf> = WalkDir::new(root)
+    Ok(scan_with_options(root, ScanOptions::default())?.entries)
+}
+
+/// Scan like [`scan`], collecting skipped files or per-file errors as
+/// `options` asks instead of dropping or rejecting them.
+///
+/// # Errors
+///
+/// Returns `ScanError` when walking the directory fails, or, unless
+/// `options.lenient` is set, when any file fails to scan.
+pub fn scan_with_options(
+    root: &Path,
+    options: ScanOptions,
+) -> Result<ScanOutcome, ScanError> {
+    scan_with_progress(root, options, &NoProgress)
+}
+
+/// Scan like [`scan_with_options`], reporting each file to `progress`.
+///
+/// # Errors
+///
+/// Returns `ScanError` when walking the directory fails, or, unless
+/// `options.lenient` is set, when any file fails to scan.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(root = %root.display()), err)
+)]
+pub(crate) fn scan_with_progress(
+    root: &Path,
+    options: ScanOptions,
+    progress: &dyn ProgressSink,
+) -> Result<ScanOutcome, ScanError> {
+    let paths = markdown_files(root)?;
+    progress.event(&ProgressEvent::FilesDiscovered {
+        location: &root.display().to_string(),
+        count: paths.len(),
+    });
+    scan_documents_of(&FileSystemSource::new(root), paths, options, progress)
+}
+
+/// Scan like [`scan_with_options`], reading the documents of `source`.
+///
+/// Namespaces, git history, and CODEOWNERS apply only when the source has a
+/// [`DocumentSource::root`] on disk.
+///
+/// # Errors
+///
+/// Returns `ScanError` when listing the documents fails, or, unless
+/// `options.lenient` is set, when any document fails to scan.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(source = %source.location()), err)
+)]
+pub fn scan_source(
+    source: &dyn DocumentSource,
+    options: ScanOptions,
+) -> Result<ScanOutcome, ScanError> {
+    let paths = source
+        .documents()
+        .map_err(|error| ScanError::ListDocuments {
+            location: source.location(),
+            source: error,
+        })?;
+    scan_documents_of(source, paths, options, &NoProgress)
+}
+
+/// Scan like [`scan_with_options`], but only the Markdown files at `paths`,
+/// which lie under `root`.
+///
+/// # Errors
+///
+/// Returns `ScanError`, unless `options.lenient` is set, when any file fails
+/// to scan.
+pub(crate) fn scan_paths(
+    root: &Path,
+    paths: Vec<PathBuf>,
+    options: ScanOptions,
+) -> Result<ScanOutcome, ScanError> {
+    scan_documents_of(&FileSystemSource::new(root), paths, options, &NoProgress)
+}
+
+/// Scan in-memory `documents`, each a path and its content, without
+/// touching the filesystem. Entries keep the order of `documents`.
+///
+/// Files without frontmatter are silently skipped, as by [`scan`].
+///
+/// # Errors
+///
+/// Returns `ScanError` when frontmatter fails to parse or has no `id`.
+pub fn scan_documents(documents: &[(PathBuf, &str)]) -> Result<Vec<Entry>, ScanError> {
+    Ok(scan_source(&Documents(documents), ScanOptions::default())?.entries)
+}
+
+/// Documents passed to [`scan_documents`].
+struct Documents<'a>(&'a [(PathBuf, &'a str)]);
+
+impl DocumentSource for Documents<'_> {
+    fn location(&self) -> String {
+        "memory".to_owned()
+    }
+
+    fn documents(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.0.iter().map(|(path, _)| path.clone()).collect())
+    }
+
+    fn open(
+        &self,
+        path: &Path,
+    ) -> io::Result<Box<dyn BufRead + '_>> {
+        self.0
+            .iter()
+            .find(|(document, _)| document == path)
+            .map(|(_, content)| Box::new(content.as_bytes()) as Box<dyn BufRead>)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+/// Scan the documents of `source` at `paths`, reporting each to `progress`.
+fn scan_documents_of(
+    source: &dyn DocumentSource,
+    paths: Vec<PathBuf>,
+    options: ScanOptions,
+    progress: &dyn ProgressSink,
+) -> Result<ScanOutcome, ScanError> {
+    let mut outcome = ScanOutcome::default();
+    for (path, parsed) in parse_files(source, paths, options, progress) {
+        let parsed = match parsed {
+            Ok(Parsed::Skipped(SkipReason::MissingId)) if !options.report_skipped => {
+                Err(ScanError::MissingId { path: path.clone() })
+            },
+            parsed => parsed,
+        };
+        match parsed {
+            Ok(Parsed::Entry(entry)) => outcome.entries.push(*entry),
+            Ok(Parsed::Skipped(reason)) => {
+                progress.event(&ProgressEvent::FileSkipped {
+                    path: &path,
+                    reason,
+                });
+                if options.report_skipped {
+                    outcome.skipped.push(SkippedFile { path, reason });
+                }
+            },
+            Err(error) if options.lenient => {
+                #[cfg(feature = "tracing")]
+                tracing::warn!(path = %path.display(), %error, "document failed to scan");
+                progress.event(&ProgressEvent::FileFailed { path: &path });
+                let location = error.location();
+                outcome.diagnostics.push(ScanDiagnostic {
+                    path,
+                    message: error.to_string(),
+                    line: location.map(|(line, _)| line),
+                    column: location.map(|(_, column)| column),
+                });
+            },
+            Err(error) => return Err(error),
+        }
+    }
+    if let Some(root) = source.root() {
+        apply_root(root, &mut outcome.entries, options);
+    }
+    outcome
+        .skipped
+        .sort_by(|left, right| left.path.cmp(&right.path));
+    outcome
+        .diagnostics
+        .sort_by(|left, right| left.path.cmp(&right.path));
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        entries = outcome.entries.len(),
+        skipped = outcome.skipped.len(),
+        failed = outcome.diagnostics.len(),
+        "scanned documents"
+    );
+
+    Ok(outcome)
+}
+
+/// Fill in what the directory `root` holds about `entries`: namespaces from
+/// `docata.toml`, and as `options` asks, git history and CODEOWNERS.
+fn apply_root(
+    root: &Path,
+    entries: &mut [Entry],
+    options: ScanOptions,
+) {
+    if let Ok(Some(config)) = Config::nearest(root) {
+        namespace::apply(root, &config, entries);
+    }
+    if options.git_metadata {
+        let mut histories = git::file_histories(root);
+        for entry in entries.iter_mut() {
+            let relative = entry.path.strip_prefix(root).unwrap_or(&entry.path);
+            entry.history = histories.remove(relative);
+        }
+    }
+    if options.code_owners
+        && let Some(code_owners) = CodeOwners::find(root)
+    {
+        for entry in entries.iter_mut() {
+            entry.code_owners = code_owners.owners(&entry.path);
+        }
+    }
+}
+
+enum Parsed {
+    Entry(Box<Entry>),
+    Skipped(SkipReason),
+}
+
+/// Every Markdown file under `root`.
+pub(crate) fn markdown_files(root: &Path) -> Result<Vec<PathBuf>, ScanError> {
+    Ok(WalkDir::new(root)
         .into_iter()
         .map(|entry| {
             let entry = entry.map_err(|source| ScanError::WalkDir {
@@ -77,21 +441,40 @@ pub fn scan(root: &Path) -> Result<Vec<Entry>, ScanError> {
         .collect::<Result<Vec<_>, ScanError>>()?
         .into_iter()
         .flatten()
-        .collect();
+        .collect())
+}
 
-    let entries: Vec<Option<Entry>> = paths
-        .par_iter()
-        .map(|path| parse_frontmatter(path))
-        .collect::<Result<_, ScanError>>()?;
+/// Parse each document of `source` at `paths`.
+fn parse_files(
+    source: &dyn DocumentSource,
+    paths: Vec<PathBuf>,
+    options: ScanOptions,
+    progress: &dyn ProgressSink,
+) -> Vec<(PathBuf, Result<Parsed, ScanError>)> {
+    #[cfg(feature = "parallel")]
+    let paths = paths.into_par_iter();
+    #[cfg(not(feature = "parallel"))]
+    let paths = paths.into_iter();
 
-    Ok(entries.into_iter().flatten().collect())
+    paths
+        .map(|path| {
+            let parsed = parse_frontmatter(source, &path, options);
+            progress.event(&ProgressEvent::FileParsed { path: &path });
+            (path, parsed)
+        })
+        .collect()
 }
 
 #[derive(Deserialize)]
 struct Frontmatter {
-    id: String,
+    #[serde(default)]
+    id: Option<String>,
     #[serde(default)]
     deps: Vec<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
     #[serde(default, rename = "type")]
     node_type: Option<String>,
     #[serde(default)]
@@ -100,14 +483,21 @@ struct Frontmatter {
     status: Option<String>,
     #[serde(default)]
     source_of_truth: Option<String>,
+    #[serde(default)]
+    last_reviewed: Option<String>,
+    #[serde(default)]
+    owner: Option<String>,
 }
 
-fn parse_frontmatter(path: &Path) -> Result<Option<Entry>, ScanError> {
-    let file = File::open(path).map_err(|source| ScanError::OpenFile {
+fn parse_frontmatter(
+    source: &dyn DocumentSource,
+    path: &Path,
+    options: ScanOptions,
+) -> Result<Parsed, ScanError> {
+    let mut reader = source.open(path).map_err(|source| ScanError::OpenFile {
         path: path.to_path_buf(),
         source,
     })?;
-    let mut reader = BufReader::new(file);
 
     let mut first_line = String::new();
     reader
@@ -118,10 +508,12 @@ fn parse_frontmatter(path: &Path) -> Result<Option<Entry>, ScanError> {
         })?;
 
     if first_line.trim() != "---" {
-        return Ok(None);
+        return Ok(Parsed::Skipped(SkipReason::NoFrontmatter));
     }
 
+    // Stand in for the opening `---` line so parser positions are file lines.
     let mut yaml_buf = String::with_capacity(512);
+    yaml_buf.push('\n');
 
     loop {
         let mut line = String::new();
@@ -154,13 +546,267 @@ fn parse_frontmatter(path: &Path) -> Result<Option<Entry>, ScanError> {
             source,
         })?;
 
-    Ok(Some(Entry {
-        id: fm.id,
+    let Some(id) = fm.id else {
+        return Ok(Parsed::Skipped(SkipReason::MissingId));
+    };
+    let mapping: yaml_serde::Mapping = yaml_serde::from_str(&yaml_buf).unwrap_or_default();
+    let fields = frontmatter_fields(&mapping);
+    let values = frontmatter_values(&mapping);
+
+    let mut body = Vec::new();
+    if options.content_hash {
+        reader
+            .read_to_end(&mut body)
+            .map_err(|source| ScanError::ReadLine {
+                path: path.to_path_buf(),
+                source,
+            })?;
+    }
+    let title = match fm.title {
+        Some(title) => Some(title),
+        None if options.content_hash => read_first_heading(&mut body.as_slice(), path)?,
+        None => read_first_heading(&mut reader, path)?,
+    };
+
+    Ok(Parsed::Entry(Box::new(Entry {
+        id,
+        dep_lines: dependency_lines(&yaml_buf, &fm.deps),
         deps: fm.deps,
         path: path.to_path_buf(),
+        title,
+        tags: fm.tags,
         node_type: fm.node_type,
         domain: fm.domain,
         status: fm.status,
         source_of_truth: fm.source_of_truth,
-    }))
+        last_reviewed: fm.last_reviewed,
+        owner: fm.owner,
+        fields,
+        values,
+        content_hash: options
+            .content_hash
+            .then(|| format!("sha256:{}", hash::sha256(&body))),
+        history: None,
+        code_owners: Vec::new(),
+    })))
+}
+
+/// File line of each of `deps` in `frontmatter`, whose first line stands
+/// for the opening `---`: the line holding the id's first occurrence after
+/// the previous one within the `deps` value, or the line of the `deps` key.
+fn dependency_lines(
+    frontmatter: &str,
+    deps: &[String],
+) -> Vec<usize> {
+    let lines = frontmatter.lines().collect::<Vec<_>>();
+    let Some(key) = lines.iter().position(|line| line.starts_with("deps:")) else {
+        return Vec::new();
+    };
+    // The value ends at the next top-level key.
+    let end = lines
+        .iter()
+        .enumerate()
+        .skip(key + 1)
+        .find(|(_, line)| {
+            line.starts_with(|first: char| !first.is_whitespace() && first != '-' && first != '#')
+                && line.contains(':')
+        })
+        .map_or(lines.len(), |(index, _)| index);
+
+    let mut cursor = (key, "deps:".len());
+    deps.iter()
+        .map(|dep| {
+            let (mut line, mut column) = cursor;
+            while line < end {
+                if let Some(found) = lines[line]
+                    .get(column..)
+                    .and_then(|rest| rest.find(dep.as_str()))
+                {
+                    cursor = (line, column + found + dep.len());
+                    return line + 1;
+                }
+                line += 1;
+                column = 0;
+            }
+            key + 1
+        })
+        .collect()
+}
+
+/// Keys of the frontmatter mapping whose values are not null or empty.
+fn frontmatter_fields(mapping: &yaml_serde::Mapping) -> Vec<String> {
+    let mut fields = mapping
+        .iter()
+        .filter(|(_, value)| match value {
+            yaml_serde::Value::Null => false,
+            yaml_serde::Value::String(value) => !value.trim().is_empty(),
+            yaml_serde::Value::Sequence(values) => !values.is_empty(),
+            yaml_serde::Value::Mapping(values) => !values.is_empty(),
+            _ => true,
+        })
+        .filter_map(|(key, _)| key.as_str().map(str::to_owned))
+        .collect::<Vec<_>>();
+    fields.sort();
+    fields
+}
+
+/// String and string-list values of the frontmatter mapping, by key. Lists
+/// holding anything but strings are left out.
+fn frontmatter_values(mapping: &yaml_serde::Mapping) -> BTreeMap<String, Vec<String>> {
+    mapping
+        .iter()
+        .filter_map(|(key, value)| {
+            let values = match value {
+                yaml_serde::Value::String(value) => vec![value.clone()],
+                yaml_serde::Value::Sequence(values) => values
+                    .iter()
+                    .map(|value| value.as_str().map(str::to_owned))
+                    .collect::<Option<Vec<_>>>()?,
+                _ => return None,
+            };
+            Some((key.as_str()?.to_owned(), values))
+        })
+        .collect()
+}
+
+/// Read the body until the first level-one ATX heading (`# Title`), skipping
+/// fenced code blocks.
+pub(crate) fn read_first_heading<R: BufRead>(
+    reader: &mut R,
+    path: &Path,
+) -> Result<Option<String>, ScanError> {
+    let mut in_fence = false;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        let bytes = reader
+            .read_line(&mut line)
+            .map_err(|source| ScanError::ReadLine {
+                path: path.to_path_buf(),
+                source,
+            })?;
+        if bytes == 0 {
+            return Ok(None);
+        }
+
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+
+        if in_fence {
+            continue;
+        }
+
+        if let Some(heading) = trimmed.strip_prefix("# ") {
+            let heading = heading.trim().trim_end_matches('#').trim_end();
+            if !heading.is_empty() {
+                return Ok(Some(heading.to_owned()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ScanError, ScanOptions, dependency_lines, read_first_heading, scan_documents,
+        scan_with_options,
+    };
+    use crate::testing::TestWorkspace;
+    use std::fs;
+    use std::io::Cursor;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn reads_first_level_one_heading_outside_code_fences() {
+        let body = "intro\n```\n# not a title\n```\n## Section\n# Real Title #\n# Later\n";
+        let mut reader = Cursor::new(body);
+
+        let title = read_first_heading(&mut reader, Path::new("doc.md")).expect("read body");
+        assert_eq!(title.as_deref(), Some("Real Title"));
+    }
+
+    #[test]
+    fn locates_dependencies_in_flow_and_block_lists() {
+        let deps = |ids: &[&str]| ids.iter().map(|id| (*id).to_owned()).collect::<Vec<_>>();
+
+        let flow = "\nid: web\ndeps: [api-gateway, api]\ntitle: Web\n";
+        assert_eq!(
+            dependency_lines(flow, &deps(&["api-gateway", "api"])),
+            [3, 3]
+        );
+
+        let block = "\nid: web\ndeps:\n  - api\n  # auth comes later\n  - \"db\"\nowner: web\n";
+        assert_eq!(dependency_lines(block, &deps(&["api", "db"])), [4, 6]);
+    }
+
+    #[test]
+    fn scans_documents_held_in_memory() {
+        let entries = scan_documents(&[
+            (
+                PathBuf::from("web.md"),
+                "---\nid: web\ndeps: [api]\n---\n# Web\n",
+            ),
+            (PathBuf::from("notes.md"), "# Notes\n"),
+            (PathBuf::from("api.md"), "---\nid: api\n---\n"),
+        ])
+        .expect("scan documents");
+
+        let ids = entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry.id.as_str(),
+                    entry.title.as_deref(),
+                    entry.deps.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                ("web", Some("Web"), vec!["api".to_owned()]),
+                ("api", None, vec![]),
+            ]
+        );
+        assert_eq!(entries[0].dep_lines, [3]);
+
+        assert!(matches!(
+            scan_documents(&[(PathBuf::from("draft.md"), "---\ntitle: Draft\n---\n")]),
+            Err(ScanError::MissingId { .. })
+        ));
+    }
+
+    #[test]
+    fn hashes_the_body_after_the_frontmatter() {
+        let workspace = TestWorkspace::new();
+        fs::write(
+            workspace.path().join("api.md"),
+            "---\nid: api\n---\n# API\n",
+        )
+        .expect("write document");
+        let scan = |content_hash| {
+            scan_with_options(
+                workspace.path(),
+                ScanOptions {
+                    content_hash,
+                    ..ScanOptions::default()
+                },
+            )
+            .expect("scan documents")
+            .entries
+            .remove(0)
+        };
+
+        let entry = scan(true);
+        assert_eq!(entry.title.as_deref(), Some("API"));
+        assert_eq!(
+            entry.content_hash,
+            Some(format!("sha256:{}", crate::hash::sha256(b"# API\n")))
+        );
+        assert_eq!(scan(false).content_hash, None);
+    }
 }
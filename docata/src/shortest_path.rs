@@ -0,0 +1,39 @@
+use crate::{domain::PathResponse, error::Error, format::OutputFormat, graph::Graph};
+use std::io::Write;
+
+/// Run path command and write formatted output to the provided writer.
+///
+/// `to` may be an unresolved dependency, so a path can explain why a missing
+/// document is required.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when `from` is not a catalog node or `to` is
+/// neither a node nor a dependency, or `Error` when writing fails.
+pub fn run<W: Write>(
+    from: &str,
+    to: &str,
+    graph: &Graph,
+    all: bool,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    if !graph.contains(from) {
+        return Err(Error::NodeNotFound {
+            id: from.to_owned(),
+        });
+    }
+    if !graph.contains(to) && graph.refs(to).is_empty() {
+        return Err(Error::NodeNotFound { id: to.to_owned() });
+    }
+
+    let response = PathResponse {
+        from: from.to_owned(),
+        to: to.to_owned(),
+        paths: graph.shortest_paths(from, to, all),
+    };
+
+    crate::shortest_path_presentation::write(&response, format, out)?;
+
+    Ok(())
+}
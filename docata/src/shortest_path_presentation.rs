@@ -0,0 +1,96 @@
+use crate::domain::PathResponse;
+use crate::format::OutputFormat;
+use crate::json;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct PathResponseJson<'a> {
+    command: &'static str,
+    from: &'a str,
+    to: &'a str,
+    found: bool,
+    length: Option<usize>,
+    count: usize,
+    paths: &'a [Vec<String>],
+}
+
+impl<'a> From<&'a PathResponse> for PathResponseJson<'a> {
+    fn from(response: &'a PathResponse) -> Self {
+        Self {
+            command: "path",
+            from: &response.from,
+            to: &response.to,
+            found: !response.paths.is_empty(),
+            length: response.paths.first().map(|path| path.len() - 1),
+            count: response.paths.len(),
+            paths: &response.paths,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PathPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write shortest paths according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `PathPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    response: &PathResponse,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), PathPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(response, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(response, format, out),
+        OutputFormat::Yaml => write_yaml(response, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(PathPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    response: &PathResponse,
+    out: &mut W,
+) -> Result<(), PathPresentationError> {
+    yaml_serde::to_writer(out, &PathResponseJson::from(response))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    response: &PathResponse,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), PathPresentationError> {
+    json::write(out, &PathResponseJson::from(response), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    response: &PathResponse,
+    out: &mut W,
+) -> Result<(), PathPresentationError> {
+    if response.paths.is_empty() {
+        writeln!(out, "no path from {} to {}", response.from, response.to)?;
+        return Ok(());
+    }
+
+    for path in &response.paths {
+        writeln!(out, "{}", path.join(" -> "))?;
+    }
+
+    Ok(())
+}
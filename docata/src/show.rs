@@ -0,0 +1,23 @@
+use crate::{catalog::Catalog, error::Error, format::OutputFormat, graph::Graph};
+use std::io::Write;
+
+/// Run show command and write formatted output to the provided writer.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when `id` is not a catalog node, or `Error`
+/// when writing fails.
+pub fn run<W: Write>(
+    id: &str,
+    catalog: &Catalog,
+    graph: &Graph,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    let detail = crate::domain::build_node_detail(id, catalog, graph)
+        .ok_or_else(|| Error::NodeNotFound { id: id.to_owned() })?;
+
+    crate::show_presentation::write(&detail, format, out)?;
+
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use crate::domain::{NodeDetail, RelationItem};
+use crate::format::OutputFormat;
+use crate::json;
+use crate::relation_presentation::RelationItemJson;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct NodeDetailJson<'a> {
+    command: &'static str,
+    id: &'a str,
+    path: &'a str,
+    title: Option<&'a str>,
+    tags: &'a [String],
+    #[serde(rename = "type")]
+    kind: Option<&'a str>,
+    domain: Option<&'a str>,
+    status: Option<&'a str>,
+    source_of_truth: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_modified: Option<&'a str>,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    authors: &'a [String],
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    owners: &'a [String],
+    deps: Vec<RelationItemJson>,
+    refs: Vec<RelationItemJson>,
+}
+
+impl<'a> From<&'a NodeDetail> for NodeDetailJson<'a> {
+    fn from(detail: &'a NodeDetail) -> Self {
+        Self {
+            command: "show",
+            id: &detail.id,
+            path: &detail.path,
+            title: detail.title.as_deref(),
+            tags: &detail.tags,
+            kind: detail.kind.as_deref(),
+            domain: detail.domain.as_deref(),
+            status: detail.status.as_deref(),
+            source_of_truth: detail.source_of_truth.as_deref(),
+            last_modified: detail.last_modified.as_deref(),
+            authors: &detail.authors,
+            owners: &detail.owners,
+            deps: detail.deps.iter().map(RelationItemJson::from).collect(),
+            refs: detail.refs.iter().map(RelationItemJson::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ShowPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a node detail view according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `ShowPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    detail: &NodeDetail,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ShowPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(detail, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(detail, format, out),
+        OutputFormat::Yaml => write_yaml(detail, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(ShowPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    detail: &NodeDetail,
+    out: &mut W,
+) -> Result<(), ShowPresentationError> {
+    yaml_serde::to_writer(out, &NodeDetailJson::from(detail))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    detail: &NodeDetail,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ShowPresentationError> {
+    json::write(out, &NodeDetailJson::from(detail), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    detail: &NodeDetail,
+    out: &mut W,
+) -> Result<(), ShowPresentationError> {
+    writeln!(out, "id: {}", detail.id)?;
+    writeln!(out, "path: {}", detail.path)?;
+
+    let fields = [
+        ("title", detail.title.as_deref()),
+        ("type", detail.kind.as_deref()),
+        ("domain", detail.domain.as_deref()),
+        ("status", detail.status.as_deref()),
+        ("source_of_truth", detail.source_of_truth.as_deref()),
+        ("last_modified", detail.last_modified.as_deref()),
+    ];
+    for (name, value) in fields {
+        if let Some(value) = value {
+            writeln!(out, "{name}: {value}")?;
+        }
+    }
+    if !detail.tags.is_empty() {
+        writeln!(out, "tags: {}", detail.tags.join(", "))?;
+    }
+    if !detail.authors.is_empty() {
+        writeln!(out, "authors: {}", detail.authors.join(", "))?;
+    }
+    if !detail.owners.is_empty() {
+        writeln!(out, "owners: {}", detail.owners.join(", "))?;
+    }
+
+    write_text_items(out, "deps", &detail.deps)?;
+    write_text_items(out, "refs", &detail.refs)?;
+
+    Ok(())
+}
+
+fn write_text_items<W: Write>(
+    out: &mut W,
+    label: &str,
+    items: &[RelationItem],
+) -> Result<(), ShowPresentationError> {
+    writeln!(out, "{label}: {}", items.len())?;
+    for item in items {
+        match &item.path {
+            Some(path) => writeln!(out, "  - {} ({path})", item.id)?,
+            None => writeln!(out, "  - {} (unresolved)", item.id)?,
+        }
+    }
+
+    Ok(())
+}
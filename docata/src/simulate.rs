@@ -0,0 +1,261 @@
+use crate::{
+    catalog::Catalog,
+    error::Error,
+    scan::Entry,
+    validate::{ValidationReport, build_validation_report},
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    path::PathBuf,
+};
+
+/// A hypothetical catalog change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SimulatedChange {
+    /// Delete the document; dependencies on it become unresolved.
+    Remove { id: String },
+    /// Delete the document and point every dependency on it at `to`.
+    Retarget { id: String, to: String },
+}
+
+impl SimulatedChange {
+    /// Id of the document the change deletes.
+    #[must_use]
+    pub fn id(&self) -> &str {
+        match self {
+            SimulatedChange::Remove { id } | SimulatedChange::Retarget { id, .. } => id,
+        }
+    }
+}
+
+/// Outcome of applying a [`SimulatedChange`] to an in-memory catalog.
+#[derive(Clone, Debug)]
+pub struct SimulationReport {
+    pub change: SimulatedChange,
+    /// Documents whose own dependencies the change rewrites, sorted by id.
+    pub directly_affected: Vec<String>,
+    /// Documents depending on a directly affected document, directly or
+    /// transitively, sorted by id.
+    pub transitively_affected: Vec<String>,
+    /// Validation findings of the catalog after the change.
+    pub findings: ValidationReport,
+    /// Number of validation findings before the change.
+    pub baseline_finding_count: usize,
+}
+
+/// Apply `change` to `catalog` without touching any file and report the
+/// documents it affects and the validation findings that would result.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when the changed document or the retarget
+/// destination is not a catalog node.
+pub fn run(
+    catalog: &Catalog,
+    change: &SimulatedChange,
+) -> Result<SimulationReport, Error> {
+    let mut entries = entries_from_catalog(catalog);
+    let baseline_finding_count = build_validation_report(&entries).finding_count();
+
+    for id in [Some(change.id()), retarget_destination(change)]
+        .into_iter()
+        .flatten()
+    {
+        if !entries.iter().any(|entry| entry.id == id) {
+            return Err(Error::NodeNotFound { id: id.to_owned() });
+        }
+    }
+
+    let removed = change.id();
+    entries.retain(|entry| entry.id != removed);
+    let mut directly_affected = BTreeSet::new();
+    for entry in &mut entries {
+        if !entry.deps.iter().any(|dep| dep == removed) {
+            continue;
+        }
+        directly_affected.insert(entry.id.clone());
+        if let Some(to) = retarget_destination(change) {
+            for dep in &mut entry.deps {
+                if dep == removed {
+                    to.clone_into(dep);
+                }
+            }
+            entry.deps.sort();
+            entry.deps.dedup();
+        }
+    }
+
+    let transitively_affected = dependents_of(catalog, &directly_affected)
+        .into_iter()
+        .filter(|id| id != removed && !directly_affected.contains(id))
+        .collect();
+
+    Ok(SimulationReport {
+        change: change.clone(),
+        directly_affected: directly_affected.into_iter().collect(),
+        transitively_affected,
+        findings: build_validation_report(&entries),
+        baseline_finding_count,
+    })
+}
+
+fn retarget_destination(change: &SimulatedChange) -> Option<&str> {
+    match change {
+        SimulatedChange::Remove { .. } => None,
+        SimulatedChange::Retarget { to, .. } => Some(to),
+    }
+}
+
+/// Rebuild scanned entries from a catalog so the document validators apply.
+pub(crate) fn entries_from_catalog(catalog: &Catalog) -> Vec<Entry> {
+    let mut deps = BTreeMap::<&str, Vec<String>>::new();
+    for edge in catalog.dependency_edges() {
+        deps.entry(edge.from.as_str())
+            .or_default()
+            .push(edge.to.clone());
+    }
+
+    catalog
+        .nodes
+        .iter()
+        .map(|node| Entry {
+            id: node.id.clone(),
+            deps: deps.get(node.id.as_str()).cloned().unwrap_or_default(),
+            dep_lines: Vec::new(),
+            path: PathBuf::from(&node.path),
+            title: node.title.clone(),
+            tags: node.tags.clone(),
+            node_type: node.kind.clone(),
+            domain: node.domain.clone(),
+            status: node.status.clone(),
+            source_of_truth: node.source_of_truth.clone(),
+            last_reviewed: None,
+            owner: None,
+            fields: Vec::new(),
+            values: BTreeMap::new(),
+            content_hash: None,
+            history: None,
+            code_owners: Vec::new(),
+        })
+        .collect()
+}
+
+/// Every document reaching one of `ids` through dependency edges.
+fn dependents_of(
+    catalog: &Catalog,
+    ids: &BTreeSet<String>,
+) -> BTreeSet<String> {
+    let mut dependents = HashMap::<&str, Vec<&str>>::new();
+    for edge in catalog.dependency_edges() {
+        dependents
+            .entry(edge.to.as_str())
+            .or_default()
+            .push(edge.from.as_str());
+    }
+
+    let mut reached = BTreeSet::new();
+    let mut queue = ids.iter().map(String::as_str).collect::<VecDeque<_>>();
+    while let Some(id) = queue.pop_front() {
+        for dependent in dependents.get(id).into_iter().flatten() {
+            if reached.insert((*dependent).to_owned()) {
+                queue.push_back(dependent);
+            }
+        }
+    }
+
+    reached
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SimulatedChange, run};
+    use crate::catalog::{Catalog, Edge, Node};
+    use crate::error::Error;
+    use std::collections::BTreeMap;
+
+    fn catalog() -> Catalog {
+        let node = |id: &str| Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: None,
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        };
+        let edge = |from: &str, to: &str| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        };
+        Catalog {
+            nodes: ["api", "auth", "guide", "index", "tokens"]
+                .into_iter()
+                .map(node)
+                .collect(),
+            edges: vec![
+                edge("api", "auth"),
+                edge("auth", "tokens"),
+                edge("guide", "api"),
+                edge("index", "guide"),
+                edge("tokens", "guide"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn removing_a_document_leaves_unresolved_dependencies() {
+        let report = run(
+            &catalog(),
+            &SimulatedChange::Remove {
+                id: "auth".to_owned(),
+            },
+        )
+        .expect("simulate removal");
+
+        assert_eq!(report.baseline_finding_count, 1);
+        assert_eq!(report.directly_affected, vec!["api".to_owned()]);
+        assert_eq!(
+            report.transitively_affected,
+            vec!["guide".to_owned(), "index".to_owned(), "tokens".to_owned()]
+        );
+        assert_eq!(report.findings.unresolved_dependencies.len(), 1);
+        assert!(report.findings.dependency_cycles.is_empty());
+    }
+
+    #[test]
+    fn retargeting_rewrites_dependencies_and_checks_the_destination() {
+        let report = run(
+            &catalog(),
+            &SimulatedChange::Retarget {
+                id: "auth".to_owned(),
+                to: "index".to_owned(),
+            },
+        )
+        .expect("simulate retarget");
+
+        assert!(report.findings.unresolved_dependencies.is_empty());
+        assert_eq!(report.findings.dependency_cycles.len(), 1);
+        assert_eq!(
+            report.findings.dependency_cycles[0].ids,
+            vec!["api".to_owned(), "guide".to_owned(), "index".to_owned()]
+        );
+
+        let missing = run(
+            &catalog(),
+            &SimulatedChange::Retarget {
+                id: "auth".to_owned(),
+                to: "missing".to_owned(),
+            },
+        );
+        assert!(matches!(missing, Err(Error::NodeNotFound { id }) if id == "missing"));
+    }
+}
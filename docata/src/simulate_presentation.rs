@@ -0,0 +1,147 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::simulate::{SimulatedChange, SimulationReport};
+use crate::validation_presentation::ValidationFindingsJson;
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct SimulatedChangeJson<'a> {
+    action: &'static str,
+    id: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    to: Option<&'a str>,
+}
+
+impl<'a> From<&'a SimulatedChange> for SimulatedChangeJson<'a> {
+    fn from(change: &'a SimulatedChange) -> Self {
+        match change {
+            SimulatedChange::Remove { id } => Self {
+                action: "remove",
+                id,
+                to: None,
+            },
+            SimulatedChange::Retarget { id, to } => Self {
+                action: "retarget",
+                id,
+                to: Some(to),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SimulationReportJson<'a> {
+    command: &'static str,
+    change: SimulatedChangeJson<'a>,
+    status: &'static str,
+    directly_affected: &'a [String],
+    transitively_affected: &'a [String],
+    baseline_findings: usize,
+    #[serde(flatten)]
+    findings: ValidationFindingsJson<'a>,
+}
+
+impl<'a> From<&'a SimulationReport> for SimulationReportJson<'a> {
+    fn from(report: &'a SimulationReport) -> Self {
+        Self {
+            command: "simulate",
+            change: (&report.change).into(),
+            status: if report.findings.is_empty() {
+                "passed"
+            } else {
+                "failed"
+            },
+            directly_affected: &report.directly_affected,
+            transitively_affected: &report.transitively_affected,
+            baseline_findings: report.baseline_finding_count,
+            findings: (&report.findings).into(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum SimulatePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a simulation report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `SimulatePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &SimulationReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), SimulatePresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(SimulatePresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    report: &SimulationReport,
+    out: &mut W,
+) -> Result<(), SimulatePresentationError> {
+    yaml_serde::to_writer(out, &SimulationReportJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &SimulationReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), SimulatePresentationError> {
+    json::write(out, &SimulationReportJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &SimulationReport,
+    out: &mut W,
+) -> Result<(), SimulatePresentationError> {
+    match &report.change {
+        SimulatedChange::Remove { id } => writeln!(out, "simulated: remove {id}")?,
+        SimulatedChange::Retarget { id, to } => {
+            writeln!(out, "simulated: retarget {id} to {to}")?;
+        },
+    }
+
+    let groups = [
+        ("directly affected", &report.directly_affected),
+        ("transitively affected", &report.transitively_affected),
+    ];
+    for (label, ids) in groups {
+        writeln!(out, "{label}: {}", ids.len())?;
+        for id in ids {
+            writeln!(out, "  - {id}")?;
+        }
+    }
+
+    writeln!(
+        out,
+        "findings before the change: {}",
+        report.baseline_finding_count
+    )?;
+    if report.findings.is_empty() {
+        writeln!(out, "validation passed")?;
+    } else {
+        write!(out, "{}", report.findings)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,272 @@
+use crate::scan;
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::{Path, PathBuf},
+};
+
+/// Where scanning reads Markdown documents from.
+///
+/// The filesystem is the usual source; embedders can implement the trait to
+/// build catalogs from other inputs, such as a CMS export or a tarball, and
+/// pass it to [`crate::build_catalog_from_source`].
+pub trait DocumentSource: Sync {
+    /// Human-readable location, used in messages and errors.
+    fn location(&self) -> String;
+
+    /// Paths of the Markdown documents, recorded as the catalog's node paths.
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` when the documents cannot be listed.
+    fn documents(&self) -> io::Result<Vec<PathBuf>>;
+
+    /// Open the document at `path`, one of [`DocumentSource::documents`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `io::Error` when the document cannot be read.
+    fn open(
+        &self,
+        path: &Path,
+    ) -> io::Result<Box<dyn BufRead + '_>>;
+
+    /// Directory the documents live under, whose `docata.toml`, git history,
+    /// and CODEOWNERS apply to them; `None` for sources outside the
+    /// filesystem, which scan without them.
+    fn root(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// The Markdown files under a directory.
+#[derive(Clone, Debug)]
+pub struct FileSystemSource {
+    root: PathBuf,
+}
+
+impl FileSystemSource {
+    #[must_use]
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl DocumentSource for FileSystemSource {
+    fn location(&self) -> String {
+        self.root.display().to_string()
+    }
+
+    fn documents(&self) -> io::Result<Vec<PathBuf>> {
+        scan::markdown_files(&self.root).map_err(io::Error::other)
+    }
+
+    fn open(
+        &self,
+        path: &Path,
+    ) -> io::Result<Box<dyn BufRead + '_>> {
+        Ok(Box::new(BufReader::new(File::open(path)?)))
+    }
+
+    fn root(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+}
+
+/// Documents held in memory, keyed by path.
+#[cfg(feature = "memory-source")]
+#[derive(Clone, Debug, Default)]
+pub struct MemorySource {
+    documents: std::collections::BTreeMap<PathBuf, String>,
+}
+
+#[cfg(feature = "memory-source")]
+impl MemorySource {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the document at `path`, replacing any document already there.
+    #[must_use]
+    pub fn with_document(
+        mut self,
+        path: impl Into<PathBuf>,
+        content: impl Into<String>,
+    ) -> Self {
+        self.documents.insert(path.into(), content.into());
+        self
+    }
+}
+
+#[cfg(feature = "memory-source")]
+impl DocumentSource for MemorySource {
+    fn location(&self) -> String {
+        "memory".to_owned()
+    }
+
+    fn documents(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.documents.keys().cloned().collect())
+    }
+
+    fn open(
+        &self,
+        path: &Path,
+    ) -> io::Result<Box<dyn BufRead + '_>> {
+        self.documents
+            .get(path)
+            .map(|content| Box::new(content.as_bytes()) as Box<dyn BufRead>)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))
+    }
+}
+
+/// Documents read with `GET` below a base URL. HTTP has no directory
+/// listing, so the document paths are given up front.
+#[cfg(feature = "http-source")]
+#[derive(Clone, Debug)]
+pub struct HttpSource {
+    base_url: String,
+    paths: Vec<PathBuf>,
+    bearer_token: Option<String>,
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "http-source")]
+impl HttpSource {
+    /// Read each of `paths`, relative to `base_url`, from
+    /// `<base_url>/<path>`.
+    #[must_use]
+    pub fn new(
+        base_url: impl Into<String>,
+        paths: impl IntoIterator<Item = impl Into<PathBuf>>,
+    ) -> Self {
+        Self {
+            base_url: base_url.into(),
+            paths: paths.into_iter().map(Into::into).collect(),
+            bearer_token: None,
+            agent: ureq::Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .into(),
+        }
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request.
+    #[must_use]
+    pub fn with_bearer_token(
+        mut self,
+        token: impl Into<String>,
+    ) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    fn url(
+        &self,
+        path: &Path,
+    ) -> String {
+        let path = path
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        format!("{}/{path}", self.base_url.trim_end_matches('/'))
+    }
+}
+
+#[cfg(feature = "http-source")]
+impl DocumentSource for HttpSource {
+    fn location(&self) -> String {
+        self.base_url.clone()
+    }
+
+    fn documents(&self) -> io::Result<Vec<PathBuf>> {
+        Ok(self.paths.clone())
+    }
+
+    fn open(
+        &self,
+        path: &Path,
+    ) -> io::Result<Box<dyn BufRead + '_>> {
+        use std::io::Read;
+
+        let url = self.url(path);
+        let mut request = self.agent.get(&url);
+        if let Some(token) = &self.bearer_token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let response = request.call().map_err(io::Error::other)?;
+        match response.status().as_u16() {
+            200..=299 => {},
+            404 => return Err(io::Error::from(io::ErrorKind::NotFound)),
+            status => {
+                return Err(io::Error::other(format!(
+                    "'{url}' responded with HTTP {status}"
+                )));
+            },
+        }
+
+        let mut content = Vec::new();
+        response
+            .into_body()
+            .into_reader()
+            .read_to_end(&mut content)?;
+        Ok(Box::new(io::Cursor::new(content)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DocumentSource;
+    use crate::{BuildOptions, build};
+    use std::io::{self, BufRead};
+    use std::path::{Path, PathBuf};
+
+    /// Documents of a hypothetical CMS export.
+    struct Export(Vec<(&'static str, &'static str)>);
+
+    impl DocumentSource for Export {
+        fn location(&self) -> String {
+            "export".to_owned()
+        }
+
+        fn documents(&self) -> io::Result<Vec<PathBuf>> {
+            Ok(self.0.iter().map(|(path, _)| PathBuf::from(path)).collect())
+        }
+
+        fn open(
+            &self,
+            path: &Path,
+        ) -> io::Result<Box<dyn BufRead + '_>> {
+            let (_, content) = self
+                .0
+                .iter()
+                .find(|(document, _)| Path::new(document) == path)
+                .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+            Ok(Box::new(content.as_bytes()))
+        }
+    }
+
+    #[test]
+    fn builds_catalogs_from_documents_outside_the_filesystem() {
+        let export = Export(vec![
+            ("api.md", "---\nid: api\ndeps: [db]\n---\n# API\n"),
+            ("db.md", "---\nid: db\n---\n"),
+            ("notes.md", "no frontmatter\n"),
+        ]);
+
+        let mut out = Vec::new();
+        build::build_source(&export, BuildOptions::default())
+            .and_then(|built| Ok(built.write(&mut out)?))
+            .expect("build catalog");
+        let catalog: serde_json::Value = serde_json::from_slice(&out).expect("parse catalog");
+        let ids = catalog["nodes"]
+            .as_array()
+            .expect("nodes")
+            .iter()
+            .map(|node| node["id"].as_str().expect("id"))
+            .collect::<Vec<_>>();
+        assert_eq!(ids, ["api", "db"]);
+        assert_eq!(catalog["edges"][0]["to"], "db");
+    }
+}
@@ -0,0 +1,121 @@
+use crate::format::CatalogFormat;
+use crate::store::{CatalogStore, StoreError};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::path::{Path, PathBuf};
+
+/// Catalogs stored as rows of a `catalogs` table, so several repositories can
+/// publish into one shared database file.
+#[derive(Clone, Debug)]
+pub struct SqliteStore {
+    database: PathBuf,
+    name: String,
+}
+
+impl SqliteStore {
+    /// Store the catalog `name` in `database`; the format follows the name's
+    /// extension, as for files.
+    #[must_use]
+    pub fn new(
+        database: impl Into<PathBuf>,
+        name: impl Into<String>,
+    ) -> Self {
+        Self {
+            database: database.into(),
+            name: name.into(),
+        }
+    }
+
+    fn connect(&self) -> Result<Connection, StoreError> {
+        let connection = Connection::open(&self.database).map_err(|source| self.error(source))?;
+        connection
+            .execute(
+                "CREATE TABLE IF NOT EXISTS catalogs (
+                    name TEXT PRIMARY KEY,
+                    content BLOB NOT NULL,
+                    updated_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )",
+                [],
+            )
+            .map_err(|source| self.error(source))?;
+        Ok(connection)
+    }
+
+    fn error(
+        &self,
+        source: rusqlite::Error,
+    ) -> StoreError {
+        StoreError::Sqlite {
+            location: self.location(),
+            source,
+        }
+    }
+}
+
+impl CatalogStore for SqliteStore {
+    fn location(&self) -> String {
+        format!("sqlite://{}#{}", self.database.display(), self.name)
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogFormat::from_path(Path::new(&self.name))
+    }
+
+    fn load(&self) -> Result<Vec<u8>, StoreError> {
+        self.connect()?
+            .query_row(
+                "SELECT content FROM catalogs WHERE name = ?1",
+                params![self.name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|source| self.error(source))?
+            .ok_or_else(|| StoreError::NotFound {
+                location: self.location(),
+            })
+    }
+
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        self.connect()?
+            .execute(
+                "INSERT INTO catalogs (name, content) VALUES (?1, ?2)
+                 ON CONFLICT(name) DO UPDATE SET
+                    content = excluded.content,
+                    updated_at = CURRENT_TIMESTAMP",
+                params![self.name, content],
+            )
+            .map_err(|source| self.error(source))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SqliteStore;
+    use crate::store::{CatalogStore, StoreError};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn round_trips_catalogs_by_name() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("system time")
+            .as_nanos();
+        let database = std::env::temp_dir().join(format!("docata-store-{nanos}.sqlite"));
+        let store = SqliteStore::new(&database, "billing.json");
+
+        assert!(matches!(store.load(), Err(StoreError::NotFound { .. })));
+        store.save(b"{\"nodes\":[]}").expect("save catalog");
+        store
+            .save(b"{\"nodes\":[],\"edges\":[]}")
+            .expect("replace catalog");
+        assert_eq!(
+            store.load().expect("load catalog"),
+            b"{\"nodes\":[],\"edges\":[]}"
+        );
+
+        std::fs::remove_file(&database).expect("remove database");
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{
+    error::Error,
+    freshness::modified_dates,
+    git::{is_iso_date, parse_date, today},
+    scan::{Entry, scan},
+};
+use std::path::{Path, PathBuf};
+
+/// Where the date a document ages from comes from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateSource {
+    /// The document's `last_reviewed` frontmatter date.
+    LastReviewed,
+    /// The latest git commit touching the file, or its modification time
+    /// outside git, for documents never reviewed.
+    LastModified,
+}
+
+impl DateSource {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            DateSource::LastReviewed => "last_reviewed",
+            DateSource::LastModified => "last_modified",
+        }
+    }
+}
+
+/// A document not reviewed or modified for longer than the report allows.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgedDocument {
+    pub id: String,
+    pub path: PathBuf,
+    pub status: Option<String>,
+    /// Date (`YYYY-MM-DD`) the age counts from.
+    pub date: String,
+    pub source: DateSource,
+    /// Days between `date` and the report's `today`.
+    pub age_days: u64,
+}
+
+/// What [`run`] reports as stale.
+#[derive(Clone, Debug, Default)]
+pub struct StaleOptions {
+    /// Report documents whose date is more than this many days old.
+    pub older_than_days: u64,
+    /// Keep only documents with one of these statuses, e.g. `published`;
+    /// every document when empty.
+    pub statuses: Vec<String>,
+    /// Date (`YYYY-MM-DD`) to measure against; defaults to today (UTC).
+    pub today: Option<String>,
+}
+
+/// Documents past their review age, oldest first.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StaleReport {
+    pub today: String,
+    pub older_than_days: u64,
+    pub stale: Vec<AgedDocument>,
+    /// Ids of documents with neither a review nor a modification date.
+    pub undated: Vec<String>,
+}
+
+/// Report documents under `root` whose `last_reviewed` date, or last
+/// modification when they were never reviewed, is older than `options`
+/// allows.
+///
+/// # Errors
+///
+/// Returns `Error` when scanning documents fails.
+pub fn run(
+    root: &Path,
+    options: &StaleOptions,
+) -> Result<StaleReport, Error> {
+    let entries = scan(root)?;
+    Ok(report(root, &entries, options))
+}
+
+/// Build a staleness report for `entries` scanned from `root`.
+#[must_use]
+pub fn report(
+    root: &Path,
+    entries: &[Entry],
+    options: &StaleOptions,
+) -> StaleReport {
+    let today = options.today.clone().unwrap_or_else(today);
+    let today_days = parse_date(&today).unwrap_or_default();
+    let entries = entries
+        .iter()
+        .filter(|entry| {
+            options.statuses.is_empty()
+                || entry
+                    .status
+                    .as_ref()
+                    .is_some_and(|status| options.statuses.contains(status))
+        })
+        .collect::<Vec<_>>();
+    let modified = modified_dates(root, entries.iter().copied());
+
+    let mut report = StaleReport {
+        today,
+        older_than_days: options.older_than_days,
+        ..StaleReport::default()
+    };
+    for entry in entries {
+        let dated = match &entry.last_reviewed {
+            Some(reviewed) if is_iso_date(reviewed) => {
+                Some((reviewed.clone(), DateSource::LastReviewed))
+            },
+            _ => modified
+                .get(entry.id.as_str())
+                .map(|(_, date)| (date.clone(), DateSource::LastModified)),
+        };
+        let Some((date, source)) = dated else {
+            report.undated.push(entry.id.clone());
+            continue;
+        };
+        let age_days = parse_date(&date).map_or(0, |days| today_days.saturating_sub(days));
+        if age_days > options.older_than_days {
+            report.stale.push(AgedDocument {
+                id: entry.id.clone(),
+                path: entry.path.clone(),
+                status: entry.status.clone(),
+                date,
+                source,
+                age_days,
+            });
+        }
+    }
+    report.stale.sort_by(|left, right| {
+        right
+            .age_days
+            .cmp(&left.age_days)
+            .then(left.id.cmp(&right.id))
+    });
+    report.undated.sort();
+
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DateSource, StaleOptions, report};
+    use crate::testing::{TestWorkspace, write_markdown};
+    use std::fs;
+
+    #[test]
+    fn reports_documents_past_their_review_age() {
+        let workspace = TestWorkspace::new();
+        let docs = workspace.path();
+        fs::write(
+            docs.join("api.md"),
+            "---\nid: api\nstatus: published\nlast_reviewed: 2025-01-10\n---\n",
+        )
+        .expect("write api");
+        fs::write(
+            docs.join("db.md"),
+            "---\nid: db\nstatus: published\nlast_reviewed: 2025-09-01\n---\n",
+        )
+        .expect("write db");
+        fs::write(
+            docs.join("old.md"),
+            "---\nid: old\nstatus: draft\nlast_reviewed: 2020-01-01\n---\n",
+        )
+        .expect("write old");
+        write_markdown(docs, "web.md", "web", &[]);
+        let entries = crate::scan::scan(docs).expect("scan documents");
+
+        let stale = report(
+            docs,
+            &entries,
+            &StaleOptions {
+                older_than_days: 180,
+                statuses: vec!["published".to_owned()],
+                today: Some("2025-10-01".to_owned()),
+            },
+        );
+
+        assert_eq!(stale.stale.len(), 1);
+        assert_eq!(stale.stale[0].id, "api");
+        assert_eq!(stale.stale[0].source, DateSource::LastReviewed);
+        assert_eq!(stale.stale[0].age_days, 264);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::stale::{AgedDocument, StaleReport};
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct StaleReportJson<'a> {
+    command: &'static str,
+    today: &'a str,
+    older_than_days: u64,
+    count: usize,
+    stale: Vec<AgedDocumentJson<'a>>,
+    undated: &'a [String],
+}
+
+#[derive(Debug, Serialize)]
+struct AgedDocumentJson<'a> {
+    id: &'a str,
+    path: String,
+    status: Option<&'a str>,
+    date: &'a str,
+    source: &'static str,
+    age_days: u64,
+}
+
+impl<'a> From<&'a StaleReport> for StaleReportJson<'a> {
+    fn from(report: &'a StaleReport) -> Self {
+        Self {
+            command: "stale",
+            today: &report.today,
+            older_than_days: report.older_than_days,
+            count: report.stale.len(),
+            stale: report.stale.iter().map(AgedDocumentJson::from).collect(),
+            undated: &report.undated,
+        }
+    }
+}
+
+impl<'a> From<&'a AgedDocument> for AgedDocumentJson<'a> {
+    fn from(document: &'a AgedDocument) -> Self {
+        Self {
+            id: &document.id,
+            path: document.path.to_string_lossy().to_string(),
+            status: document.status.as_deref(),
+            date: &document.date,
+            source: document.source.as_str(),
+            age_days: document.age_days,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StalePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a staleness report according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `StalePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &StaleReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), StalePresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Ndjson => write_ndjson(report, out),
+        OutputFormat::Sarif | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(StalePresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+/// Write one stale document per line.
+fn write_ndjson<W: Write>(
+    report: &StaleReport,
+    out: &mut W,
+) -> Result<(), StalePresentationError> {
+    for document in &report.stale {
+        json::write(out, &AgedDocumentJson::from(document), OutputFormat::Ndjson)?;
+    }
+    Ok(())
+}
+
+fn write_yaml<W: Write>(
+    report: &StaleReport,
+    out: &mut W,
+) -> Result<(), StalePresentationError> {
+    yaml_serde::to_writer(out, &StaleReportJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &StaleReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), StalePresentationError> {
+    json::write(out, &StaleReportJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    report: &StaleReport,
+    out: &mut W,
+) -> Result<(), StalePresentationError> {
+    for document in &report.stale {
+        writeln!(
+            out,
+            "{} ({}) {} {}, {} days ago",
+            document.id,
+            document.path.display(),
+            document.source.as_str(),
+            document.date,
+            document.age_days
+        )?;
+    }
+
+    writeln!(
+        out,
+        "{} document(s) older than {} days as of {}",
+        report.stale.len(),
+        report.older_than_days,
+        report.today
+    )?;
+
+    Ok(())
+}
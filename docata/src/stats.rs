@@ -0,0 +1,223 @@
+use crate::{catalog::Catalog, validate::strongly_connected_components};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Summary figures for a catalog.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CatalogStats {
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Edges whose target is not a catalog node.
+    pub unresolved_edge_count: usize,
+    pub by_type: BTreeMap<String, usize>,
+    pub by_domain: BTreeMap<String, usize>,
+    pub by_status: BTreeMap<String, usize>,
+    /// Documents by the year of their last commit; empty unless the catalog
+    /// was built with git metadata.
+    pub by_last_modified_year: BTreeMap<String, usize>,
+    /// Documents by CODEOWNERS owner, counting a document once for each of
+    /// its owners; empty unless the catalog was built with code owners.
+    pub by_owner: BTreeMap<String, usize>,
+    /// Longest chain of resolved dependencies, in edges. Each cycle counts as
+    /// a single step.
+    pub max_depth: usize,
+    pub average_fan_out: f64,
+    pub average_fan_in: f64,
+    /// Nodes with neither dependencies nor references to them, unlike the
+    /// orphan rule of `check`, which flags nodes nothing depends on.
+    pub isolated_count: usize,
+    pub cycle_count: usize,
+}
+
+/// Compute summary figures for `catalog`.
+#[must_use]
+pub fn build(catalog: &Catalog) -> CatalogStats {
+    let mut adjacency = catalog
+        .nodes
+        .iter()
+        .map(|node| (node.id.clone(), BTreeSet::new()))
+        .collect::<BTreeMap<_, _>>();
+    let mut referenced = BTreeSet::new();
+    let mut unresolved_edge_count = 0;
+    for edge in catalog.dependency_edges() {
+        if adjacency.contains_key(&edge.to) {
+            referenced.insert(edge.to.as_str());
+            if let Some(deps) = adjacency.get_mut(&edge.from) {
+                deps.insert(edge.to.clone());
+            }
+        } else {
+            unresolved_edge_count += 1;
+        }
+    }
+
+    let mut stats = CatalogStats {
+        node_count: catalog.nodes.len(),
+        edge_count: catalog.dependency_edges().count(),
+        unresolved_edge_count,
+        ..CatalogStats::default()
+    };
+
+    for node in &catalog.nodes {
+        let fields = [
+            (&mut stats.by_type, &node.kind),
+            (&mut stats.by_domain, &node.domain),
+            (&mut stats.by_status, &node.status),
+        ];
+        for (counts, value) in fields {
+            if let Some(value) = value {
+                *counts.entry(value.clone()).or_default() += 1;
+            }
+        }
+        if let Some(year) = node.last_modified.as_ref().and_then(|date| date.get(..4)) {
+            *stats
+                .by_last_modified_year
+                .entry(year.to_owned())
+                .or_default() += 1;
+        }
+        for owner in &node.owners {
+            *stats.by_owner.entry(owner.clone()).or_default() += 1;
+        }
+    }
+
+    let has_deps = catalog
+        .dependency_edges()
+        .map(|edge| edge.from.as_str())
+        .collect::<BTreeSet<_>>();
+    stats.isolated_count = catalog
+        .nodes
+        .iter()
+        .filter(|node| {
+            !has_deps.contains(node.id.as_str()) && !referenced.contains(node.id.as_str())
+        })
+        .count();
+
+    stats.average_fan_out = ratio(stats.edge_count, stats.node_count);
+    stats.average_fan_in = ratio(stats.edge_count - unresolved_edge_count, stats.node_count);
+
+    let components = strongly_connected_components(&adjacency);
+    stats.cycle_count = components
+        .iter()
+        .filter(|component| {
+            component.len() > 1
+                || adjacency
+                    .get(&component[0])
+                    .is_some_and(|deps| deps.contains(&component[0]))
+        })
+        .count();
+    stats.max_depth = max_depth(&adjacency, &components);
+
+    stats
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn ratio(
+    numerator: usize,
+    denominator: usize,
+) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+/// Longest path over the condensation, relying on components arriving in
+/// reverse topological order.
+fn max_depth(
+    adjacency: &BTreeMap<String, BTreeSet<String>>,
+    components: &[Vec<String>],
+) -> usize {
+    let component_of = components
+        .iter()
+        .enumerate()
+        .flat_map(|(index, component)| component.iter().map(move |id| (id.as_str(), index)))
+        .collect::<HashMap<_, _>>();
+    let mut depths = vec![0_usize; components.len()];
+
+    for (index, component) in components.iter().enumerate() {
+        let depth = component
+            .iter()
+            .flat_map(|id| adjacency.get(id).into_iter().flatten())
+            .filter_map(|dep| component_of.get(dep.as_str()))
+            .filter(|dep_index| **dep_index != index)
+            .map(|dep_index| depths[*dep_index] + 1)
+            .max()
+            .unwrap_or(0);
+        depths[index] = depth;
+    }
+
+    depths.into_iter().max().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::build;
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn node(
+        id: &str,
+        kind: Option<&str>,
+    ) -> Node {
+        Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: kind.map(ToOwned::to_owned),
+            domain: None,
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        }
+    }
+
+    fn edge(
+        from: &str,
+        to: &str,
+    ) -> Edge {
+        Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        }
+    }
+
+    #[test]
+    fn summarizes_depth_cycles_and_isolated_documents() {
+        let catalog = Catalog {
+            nodes: vec![
+                node("a", Some("spec")),
+                node("b", Some("spec")),
+                node("c", Some("guide")),
+                node("d", None),
+                node("lonely", None),
+            ],
+            edges: vec![
+                edge("a", "b"),
+                edge("b", "c"),
+                edge("c", "d"),
+                edge("d", "c"),
+                edge("a", "missing"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+
+        let stats = build(&catalog);
+
+        assert_eq!(stats.node_count, 5);
+        assert_eq!(stats.edge_count, 5);
+        assert_eq!(stats.unresolved_edge_count, 1);
+        assert_eq!(stats.by_type.get("spec"), Some(&2));
+        assert_eq!(stats.max_depth, 2);
+        // Only `lonely`: nothing depends on `a`, but it has dependencies.
+        assert_eq!(stats.isolated_count, 1);
+        assert_eq!(stats.cycle_count, 1);
+        assert!((stats.average_fan_out - 1.0).abs() < f64::EPSILON);
+        assert!((stats.average_fan_in - 0.8).abs() < f64::EPSILON);
+    }
+}
@@ -0,0 +1,135 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::stats::CatalogStats;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct CatalogStatsJson<'a> {
+    command: &'static str,
+    nodes: usize,
+    edges: usize,
+    unresolved_edges: usize,
+    max_depth: usize,
+    average_fan_out: f64,
+    average_fan_in: f64,
+    isolated: usize,
+    cycles: usize,
+    by_type: &'a BTreeMap<String, usize>,
+    by_domain: &'a BTreeMap<String, usize>,
+    by_status: &'a BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    by_last_modified_year: &'a BTreeMap<String, usize>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    by_owner: &'a BTreeMap<String, usize>,
+}
+
+impl<'a> From<&'a CatalogStats> for CatalogStatsJson<'a> {
+    fn from(stats: &'a CatalogStats) -> Self {
+        Self {
+            command: "stats",
+            nodes: stats.node_count,
+            edges: stats.edge_count,
+            unresolved_edges: stats.unresolved_edge_count,
+            max_depth: stats.max_depth,
+            average_fan_out: round(stats.average_fan_out),
+            average_fan_in: round(stats.average_fan_in),
+            isolated: stats.isolated_count,
+            cycles: stats.cycle_count,
+            by_type: &stats.by_type,
+            by_domain: &stats.by_domain,
+            by_status: &stats.by_status,
+            by_last_modified_year: &stats.by_last_modified_year,
+            by_owner: &stats.by_owner,
+        }
+    }
+}
+
+/// Round to two decimals so output stays stable across platforms.
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[derive(Debug, Error)]
+pub enum StatsPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write catalog statistics according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `StatsPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    stats: &CatalogStats,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), StatsPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(stats, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(stats, format, out),
+        OutputFormat::Yaml => write_yaml(stats, out),
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(StatsPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_yaml<W: Write>(
+    stats: &CatalogStats,
+    out: &mut W,
+) -> Result<(), StatsPresentationError> {
+    yaml_serde::to_writer(out, &CatalogStatsJson::from(stats))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    stats: &CatalogStats,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), StatsPresentationError> {
+    json::write(out, &CatalogStatsJson::from(stats), format)?;
+    Ok(())
+}
+
+fn write_text<W: Write>(
+    stats: &CatalogStats,
+    out: &mut W,
+) -> Result<(), StatsPresentationError> {
+    writeln!(out, "nodes: {}", stats.node_count)?;
+    writeln!(out, "edges: {}", stats.edge_count)?;
+    writeln!(out, "unresolved edges: {}", stats.unresolved_edge_count)?;
+    writeln!(out, "max depth: {}", stats.max_depth)?;
+    writeln!(out, "average fan-out: {:.2}", stats.average_fan_out)?;
+    writeln!(out, "average fan-in: {:.2}", stats.average_fan_in)?;
+    writeln!(out, "isolated: {}", stats.isolated_count)?;
+    writeln!(out, "cycles: {}", stats.cycle_count)?;
+
+    let groups = [
+        ("type", &stats.by_type),
+        ("domain", &stats.by_domain),
+        ("status", &stats.by_status),
+        ("last modified year", &stats.by_last_modified_year),
+        ("owner", &stats.by_owner),
+    ];
+    for (label, counts) in groups {
+        if counts.is_empty() {
+            continue;
+        }
+        writeln!(out, "by {label}:")?;
+        for (value, count) in counts {
+            writeln!(out, "  {value}: {count}")?;
+        }
+    }
+
+    Ok(())
+}
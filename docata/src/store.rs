@@ -0,0 +1,809 @@
+use crate::directory_store::DirectoryStore;
+use crate::format::{CatalogFormat, Compression};
+#[cfg(feature = "http")]
+use crate::json;
+#[cfg(feature = "http")]
+use crate::remote::{CacheMeta, CachePaths};
+#[cfg(feature = "http")]
+use std::io::Read;
+use std::{
+    ffi::OsString,
+    fs::{self, File, OpenOptions, TryLockError},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+#[cfg(feature = "http")]
+use ureq::Agent;
+
+/// Environment variable holding a bearer token sent to HTTP catalog stores.
+pub const HTTP_TOKEN_ENV: &str = "DOCATA_HTTP_TOKEN";
+
+/// Environment variable naming the directory HTTP catalog stores cache
+/// downloads in, revalidated with their `ETag`.
+pub const HTTP_CACHE_DIR_ENV: &str = "DOCATA_HTTP_CACHE_DIR";
+
+/// Persistence for a serialized catalog.
+///
+/// Build writes through [`CatalogStore::save`]; check and query commands read
+/// through [`CatalogStore::load`]. Local paths implement the trait directly,
+/// so `Path::new("docs/catalog.json")` can be passed wherever a store is
+/// expected.
+pub trait CatalogStore {
+    /// Human-readable location, used in messages and errors.
+    fn location(&self) -> String;
+
+    /// Serialization format of the stored catalog.
+    fn format(&self) -> CatalogFormat;
+
+    /// Compression of the stored catalog, from the extension of its
+    /// location, e.g. `catalog.json.gz`.
+    fn compression(&self) -> Compression {
+        Compression::from_path(Path::new(&self.location()))
+    }
+
+    /// Read the stored catalog bytes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError::NotFound` when no catalog is stored yet, or
+    /// `StoreError` when the catalog cannot be read.
+    fn load(&self) -> Result<Vec<u8>, StoreError>;
+
+    /// Replace the stored catalog with `content`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError` when the catalog cannot be written.
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError>;
+
+    /// Replace the stored catalog with the bytes `write` writes, streamed
+    /// to storage where the backend can. By default they are collected and
+    /// passed to [`CatalogStore::save`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError` when `write` fails or the catalog cannot be
+    /// written.
+    fn save_with(
+        &self,
+        write: &mut dyn FnMut(&mut dyn Write) -> io::Result<()>,
+    ) -> Result<(), StoreError> {
+        let mut content = Vec::new();
+        write(&mut content).map_err(|source| StoreError::Write {
+            location: self.location(),
+            source,
+        })?;
+        self.save(&content)
+    }
+
+    /// Take the exclusive write lock, for backends whose writes can
+    /// interleave; `None` when the backend serializes writes itself.
+    ///
+    /// # Errors
+    ///
+    /// Returns `StoreError::Locked` when `policy` is [`LockPolicy::NoWait`]
+    /// and another writer holds the lock.
+    fn lock(
+        &self,
+        _policy: LockPolicy,
+    ) -> Result<Option<CatalogLock>, StoreError> {
+        Ok(None)
+    }
+
+    /// The local file holding the catalog, for backends storing it on disk;
+    /// a query index can be kept beside it (see
+    /// [`crate::BuildOptions::write_index`]).
+    fn local_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+/// What a writer does when another process holds the catalog's lock.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum LockPolicy {
+    /// Block until the lock is released.
+    #[default]
+    Wait,
+    /// Fail immediately with [`StoreError::Locked`].
+    NoWait,
+}
+
+/// Exclusive write lock on a catalog, released when dropped.
+#[derive(Debug)]
+pub struct CatalogLock {
+    _file: File,
+    path: PathBuf,
+}
+
+impl Drop for CatalogLock {
+    /// Remove the lock file while still holding it, so no `.lock` file is
+    /// left beside the catalog. A writer waiting on the removed file finds
+    /// it is no longer the one at the path and locks a new one.
+    fn drop(&mut self) {
+        if cfg!(unix) {
+            let _result = fs::remove_file(&self.path);
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum StoreError {
+    #[error("failed to read catalog '{location}': {source}")]
+    Read {
+        location: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("failed to write catalog '{location}': {source}")]
+    Write {
+        location: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("request for catalog '{location}' failed: {reason}")]
+    Http { location: String, reason: String },
+    #[error("catalog '{location}' responded with HTTP {status}")]
+    HttpStatus { location: String, status: u16 },
+    #[error("failed to lock catalog '{location}': {source}")]
+    Lock {
+        location: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("catalog '{location}' is locked by another writer")]
+    Locked { location: String },
+    #[error("catalog '{location}' was not found")]
+    NotFound { location: String },
+    #[error("catalog location '{location}' needs docata built with the `{feature}` feature")]
+    FeatureDisabled {
+        location: String,
+        feature: &'static str,
+    },
+    #[error("invalid catalog location '{location}': {reason}")]
+    InvalidLocation { location: String, reason: String },
+    #[cfg(feature = "sqlite")]
+    #[error("sqlite error for catalog '{location}': {source}")]
+    Sqlite {
+        location: String,
+        #[source]
+        source: rusqlite::Error,
+    },
+}
+
+impl CatalogStore for Path {
+    fn location(&self) -> String {
+        self.to_string_lossy().into_owned()
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogFormat::from_path(self)
+    }
+
+    fn load(&self) -> Result<Vec<u8>, StoreError> {
+        fs::read(self).map_err(|source| {
+            if source.kind() == io::ErrorKind::NotFound {
+                StoreError::NotFound {
+                    location: self.location(),
+                }
+            } else {
+                StoreError::Read {
+                    location: self.location(),
+                    source,
+                }
+            }
+        })
+    }
+
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        self.save_with(&mut |out| out.write_all(content))
+    }
+
+    /// Write to a temporary sibling and rename it over the catalog, so readers
+    /// never see a partially written file.
+    fn save_with(
+        &self,
+        write: &mut dyn FnMut(&mut dyn Write) -> io::Result<()>,
+    ) -> Result<(), StoreError> {
+        let temporary = with_suffix(self, &format!(".tmp-{}", std::process::id()));
+        File::create(&temporary)
+            .and_then(|file| {
+                let mut out = BufWriter::new(file);
+                write(&mut out)?;
+                out.flush()
+            })
+            .and_then(|()| fs::rename(&temporary, self))
+            .map_err(|source| {
+                let _ = fs::remove_file(&temporary);
+                StoreError::Write {
+                    location: self.location(),
+                    source,
+                }
+            })
+    }
+
+    /// Lock `<catalog>.lock`, an advisory lock shared by every docata writer
+    /// and removed again when released.
+    fn lock(
+        &self,
+        policy: LockPolicy,
+    ) -> Result<Option<CatalogLock>, StoreError> {
+        let lock_error = |source| StoreError::Lock {
+            location: self.location(),
+            source,
+        };
+        let path = with_suffix(self, ".lock");
+        loop {
+            let file = OpenOptions::new()
+                .create(true)
+                .truncate(false)
+                .write(true)
+                .open(&path)
+                .map_err(lock_error)?;
+
+            match policy {
+                LockPolicy::Wait => file.lock().map_err(lock_error)?,
+                LockPolicy::NoWait => match file.try_lock() {
+                    Ok(()) => {},
+                    Err(TryLockError::WouldBlock) => {
+                        return Err(StoreError::Locked {
+                            location: self.location(),
+                        });
+                    },
+                    Err(TryLockError::Error(source)) => return Err(lock_error(source)),
+                },
+            }
+
+            if is_current_lock(&file, &path).map_err(lock_error)? {
+                return Ok(Some(CatalogLock { _file: file, path }));
+            }
+        }
+    }
+
+    fn local_path(&self) -> Option<&Path> {
+        Some(self)
+    }
+}
+
+impl CatalogStore for PathBuf {
+    fn location(&self) -> String {
+        self.as_path().location()
+    }
+
+    fn format(&self) -> CatalogFormat {
+        self.as_path().format()
+    }
+
+    fn load(&self) -> Result<Vec<u8>, StoreError> {
+        self.as_path().load()
+    }
+
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        self.as_path().save(content)
+    }
+
+    fn save_with(
+        &self,
+        write: &mut dyn FnMut(&mut dyn Write) -> io::Result<()>,
+    ) -> Result<(), StoreError> {
+        self.as_path().save_with(write)
+    }
+
+    fn lock(
+        &self,
+        policy: LockPolicy,
+    ) -> Result<Option<CatalogLock>, StoreError> {
+        self.as_path().lock(policy)
+    }
+
+    fn local_path(&self) -> Option<&Path> {
+        Some(self)
+    }
+}
+
+/// `path` with `suffix` appended to its file name, e.g. `catalog.json.lock`.
+pub(crate) fn with_suffix(
+    path: &Path,
+    suffix: &str,
+) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+/// A catalog read with `GET` and written with `PUT` at an HTTP(S) URL.
+#[cfg(feature = "http")]
+#[derive(Clone, Debug)]
+pub struct HttpStore {
+    url: String,
+    bearer_token: Option<String>,
+    cache_dir: Option<PathBuf>,
+    agent: Agent,
+}
+
+#[cfg(feature = "http")]
+impl HttpStore {
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            bearer_token: None,
+            cache_dir: None,
+            agent: Agent::config_builder()
+                .http_status_as_error(false)
+                .build()
+                .into(),
+        }
+    }
+
+    /// Send `Authorization: Bearer <token>` with every request.
+    #[must_use]
+    pub fn with_bearer_token(
+        mut self,
+        token: impl Into<String>,
+    ) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Keep the last downloaded catalog in `dir` and send its `ETag` with
+    /// `If-None-Match`, so an unchanged catalog is not downloaded again.
+    ///
+    /// The cache shares its layout with `docata fetch`.
+    #[must_use]
+    pub fn with_cache_dir(
+        mut self,
+        dir: impl Into<PathBuf>,
+    ) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    fn check_status(
+        &self,
+        status: u16,
+    ) -> Result<(), StoreError> {
+        match status {
+            200..=299 => Ok(()),
+            404 => Err(StoreError::NotFound {
+                location: self.location(),
+            }),
+            status => Err(StoreError::HttpStatus {
+                location: self.location(),
+                status,
+            }),
+        }
+    }
+
+    fn http_error(
+        &self,
+        error: &ureq::Error,
+    ) -> StoreError {
+        StoreError::Http {
+            location: self.location(),
+            reason: error.to_string(),
+        }
+    }
+
+    /// Replace the cached copy with `content` and its `etag`.
+    fn write_cache(
+        &self,
+        paths: &CachePaths,
+        content: &[u8],
+        etag: Option<String>,
+    ) -> Result<(), StoreError> {
+        let write = |path: &Path, content: &[u8]| {
+            let temporary = with_suffix(path, ".tmp");
+            fs::write(&temporary, content)
+                .and_then(|()| fs::rename(&temporary, path))
+                .map_err(|source| StoreError::Write {
+                    location: path.display().to_string(),
+                    source,
+                })
+        };
+
+        if let Some(dir) = &self.cache_dir {
+            fs::create_dir_all(dir).map_err(|source| StoreError::Write {
+                location: dir.display().to_string(),
+                source,
+            })?;
+        }
+        write(&paths.catalog, content)?;
+        let meta = CacheMeta {
+            etag,
+            ..CacheMeta::default()
+        };
+        let meta = json::to_string(&meta, false).map_err(|error| StoreError::Write {
+            location: paths.meta.display().to_string(),
+            source: error.into(),
+        })?;
+        write(&paths.meta, meta.as_bytes())
+    }
+}
+
+#[cfg(feature = "http")]
+impl CatalogStore for HttpStore {
+    fn location(&self) -> String {
+        self.url.clone()
+    }
+
+    fn format(&self) -> CatalogFormat {
+        CatalogFormat::from_path(Path::new(url_path(&self.url)))
+    }
+
+    fn load(&self) -> Result<Vec<u8>, StoreError> {
+        let cache = self
+            .cache_dir
+            .as_deref()
+            .map(|dir| CachePaths::new(dir, &self.url));
+        let cached = cache.as_ref().and_then(|paths| {
+            let etag = CacheMeta::read(&paths.meta).etag?;
+            Some((etag, fs::read(&paths.catalog).ok()?))
+        });
+
+        let mut request = self.agent.get(&self.url);
+        if let Some(token) = &self.bearer_token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        if let Some((etag, _)) = &cached {
+            request = request.header("If-None-Match", etag);
+        }
+        let response = request.call().map_err(|error| self.http_error(&error))?;
+        if response.status().as_u16() == 304
+            && let Some((_, content)) = cached
+        {
+            return Ok(content);
+        }
+        self.check_status(response.status().as_u16())?;
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        let mut content = Vec::new();
+        response
+            .into_body()
+            .into_reader()
+            .read_to_end(&mut content)
+            .map_err(|source| StoreError::Read {
+                location: self.location(),
+                source,
+            })?;
+        if let Some(paths) = &cache {
+            self.write_cache(paths, &content, etag)?;
+        }
+        Ok(content)
+    }
+
+    fn save(
+        &self,
+        content: &[u8],
+    ) -> Result<(), StoreError> {
+        let content_type = match self.format() {
+            CatalogFormat::Json => "application/json",
+            CatalogFormat::Yaml => "application/yaml",
+            CatalogFormat::MessagePack => "application/msgpack",
+        };
+        let mut request = self
+            .agent
+            .put(&self.url)
+            .header("Content-Type", content_type);
+        if let Some(token) = &self.bearer_token {
+            request = request.header("Authorization", format!("Bearer {token}"));
+        }
+        let response = request
+            .send(content)
+            .map_err(|error| self.http_error(&error))?;
+        self.check_status(response.status().as_u16())
+    }
+}
+
+/// Open the store for a catalog `location`:
+///
+/// - `http://…` / `https://…`: `HttpStore`, authenticated with
+///   `DOCATA_HTTP_TOKEN` and cached in `DOCATA_HTTP_CACHE_DIR` when set
+///   (`http` feature)
+/// - `sqlite://<database>#<name>`: a row in a `SQLite` database (`sqlite` feature)
+/// - `s3://<bucket>/<key>`: an object in S3-compatible storage (`s3` feature)
+/// - an existing directory: a [`DirectoryStore`] of catalog shards
+/// - anything else: a local file path
+///
+/// # Errors
+///
+/// Returns `StoreError` when the location is malformed or needs a feature
+/// this build does not include.
+pub fn open(location: &str) -> Result<Box<dyn CatalogStore>, StoreError> {
+    if location.starts_with("http://") || location.starts_with("https://") {
+        return open_http(location);
+    }
+    if let Some(rest) = location.strip_prefix("sqlite://") {
+        return open_sqlite(location, rest);
+    }
+    if let Some(rest) = location.strip_prefix("s3://") {
+        return open_s3(location, rest);
+    }
+    if Path::new(location).is_dir() {
+        return Ok(Box::new(DirectoryStore::new(location)));
+    }
+
+    Ok(Box::new(PathBuf::from(location)))
+}
+
+#[cfg(feature = "http")]
+#[allow(clippy::unnecessary_wraps)]
+fn open_http(location: &str) -> Result<Box<dyn CatalogStore>, StoreError> {
+    let mut store = HttpStore::new(location);
+    if let Ok(token) = std::env::var(HTTP_TOKEN_ENV)
+        && !token.is_empty()
+    {
+        store = store.with_bearer_token(token);
+    }
+    if let Some(dir) = std::env::var_os(HTTP_CACHE_DIR_ENV)
+        && !dir.is_empty()
+    {
+        store = store.with_cache_dir(dir);
+    }
+    Ok(Box::new(store))
+}
+
+#[cfg(not(feature = "http"))]
+fn open_http(location: &str) -> Result<Box<dyn CatalogStore>, StoreError> {
+    Err(StoreError::FeatureDisabled {
+        location: location.to_owned(),
+        feature: "http",
+    })
+}
+
+#[cfg(feature = "sqlite")]
+fn open_sqlite(
+    location: &str,
+    rest: &str,
+) -> Result<Box<dyn CatalogStore>, StoreError> {
+    let (database, name) = rest.split_once('#').unwrap_or((rest, "catalog.json"));
+    if database.is_empty() || name.is_empty() {
+        return Err(StoreError::InvalidLocation {
+            location: location.to_owned(),
+            reason: "expected sqlite://<database>#<name>".to_owned(),
+        });
+    }
+    Ok(Box::new(crate::sqlite_store::SqliteStore::new(
+        database, name,
+    )))
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn open_sqlite(
+    location: &str,
+    _rest: &str,
+) -> Result<Box<dyn CatalogStore>, StoreError> {
+    Err(StoreError::FeatureDisabled {
+        location: location.to_owned(),
+        feature: "sqlite",
+    })
+}
+
+#[cfg(feature = "s3")]
+fn open_s3(
+    location: &str,
+    rest: &str,
+) -> Result<Box<dyn CatalogStore>, StoreError> {
+    let Some((bucket, key)) = rest
+        .split_once('/')
+        .filter(|(bucket, key)| !bucket.is_empty() && !key.is_empty())
+    else {
+        return Err(StoreError::InvalidLocation {
+            location: location.to_owned(),
+            reason: "expected s3://<bucket>/<key>".to_owned(),
+        });
+    };
+    Ok(Box::new(crate::s3_store::S3Store::from_env(bucket, key)?))
+}
+
+#[cfg(not(feature = "s3"))]
+fn open_s3(
+    location: &str,
+    _rest: &str,
+) -> Result<Box<dyn CatalogStore>, StoreError> {
+    Err(StoreError::FeatureDisabled {
+        location: location.to_owned(),
+        feature: "s3",
+    })
+}
+
+/// Whether the locked `file` is still the one at `path`, rather than one a
+/// previous holder removed on release while this writer waited on it.
+#[cfg(unix)]
+fn is_current_lock(
+    file: &File,
+    path: &Path,
+) -> io::Result<bool> {
+    use std::os::unix::fs::MetadataExt;
+
+    let locked = file.metadata()?;
+    match fs::metadata(path) {
+        Ok(current) => Ok((locked.dev(), locked.ino()) == (current.dev(), current.ino())),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(error) => Err(error),
+    }
+}
+
+/// Lock files are only removed on Unix, so the one locked is always current.
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)]
+fn is_current_lock(
+    _file: &File,
+    _path: &Path,
+) -> io::Result<bool> {
+    Ok(true)
+}
+
+/// Path component of a URL, without query string or fragment.
+#[cfg(feature = "http")]
+pub(crate) fn url_path(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CatalogStore, LockPolicy, StoreError, open};
+    use crate::format::CatalogFormat;
+    use crate::testing::TestWorkspace;
+    use std::io;
+    use std::path::Path;
+    use std::thread;
+    use std::time::Duration;
+
+    #[test]
+    fn opens_stores_by_location_scheme() {
+        let local = open("docs/catalog.yaml").expect("local store");
+        assert_eq!(local.location(), "docs/catalog.yaml");
+        assert_eq!(local.format(), CatalogFormat::Yaml);
+
+        #[cfg(feature = "http")]
+        {
+            let http = open("https://ci.example.com/catalog.json?ref=main").expect("http store");
+            assert_eq!(http.format(), CatalogFormat::Json);
+        }
+        #[cfg(not(feature = "http"))]
+        assert!(matches!(
+            open("https://ci.example.com/catalog.json"),
+            Err(StoreError::FeatureDisabled {
+                feature: "http",
+                ..
+            })
+        ));
+
+        #[cfg(not(feature = "s3"))]
+        assert!(open("s3://bucket/catalog.json").is_err());
+    }
+
+    #[test]
+    fn streamed_save_replaces_the_catalog_only_once_written() {
+        let workspace = TestWorkspace::new();
+        let catalog = workspace.path().join("catalog.json");
+        catalog.save(b"old").expect("save catalog");
+
+        let failed = catalog.save_with(&mut |out| {
+            out.write_all(b"partial")?;
+            Err(io::Error::other("serialization failed"))
+        });
+        assert!(matches!(failed, Err(StoreError::Write { .. })));
+        assert_eq!(catalog.load().expect("load catalog"), b"old");
+
+        catalog
+            .save_with(&mut |out| out.write_all(b"new"))
+            .expect("save catalog");
+        assert_eq!(catalog.load().expect("load catalog"), b"new");
+        assert_eq!(
+            std::fs::read_dir(workspace.path())
+                .expect("list workspace")
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn no_wait_fails_while_another_writer_holds_the_lock() {
+        let workspace = TestWorkspace::new();
+        let catalog = workspace.path().join("catalog.json");
+        let lock_file = workspace.path().join("catalog.json.lock");
+
+        let held = catalog.lock(LockPolicy::Wait).expect("take lock");
+        assert!(matches!(
+            catalog.lock(LockPolicy::NoWait),
+            Err(StoreError::Locked { .. })
+        ));
+        drop(held);
+        #[cfg(unix)]
+        assert!(!lock_file.exists());
+
+        let lock = catalog.lock(LockPolicy::NoWait).expect("lock released");
+        Path::new(&catalog).save(b"{}").expect("save catalog");
+        assert_eq!(std::fs::read(&catalog).expect("read catalog"), b"{}");
+        drop(lock);
+        #[cfg(unix)]
+        assert!(!lock_file.exists());
+    }
+
+    #[test]
+    fn waiting_writer_takes_the_lock_after_release() {
+        let workspace = TestWorkspace::new();
+        let catalog = workspace.path().join("catalog.json");
+
+        let held = catalog.lock(LockPolicy::Wait).expect("take lock");
+        let waiting = {
+            let catalog = catalog.clone();
+            thread::spawn(move || {
+                let lock = catalog.lock(LockPolicy::Wait).expect("take lock");
+                let taken = catalog.lock(LockPolicy::NoWait);
+                drop(lock);
+                taken
+            })
+        };
+        thread::sleep(Duration::from_millis(50));
+        drop(held);
+
+        assert!(matches!(
+            waiting.join().expect("waiting writer"),
+            Err(StoreError::Locked { .. })
+        ));
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn revalidates_cached_http_catalogs_with_their_etag() {
+        use super::HttpStore;
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind listener");
+        let url = format!(
+            "http://{}/catalog.json",
+            listener.local_addr().expect("local addr")
+        );
+        let server = thread::spawn(move || {
+            let responses = [
+                "HTTP/1.1 200 OK\r\nETag: \"v1\"\r\nContent-Length: 13\r\nConnection: close\r\n\r\n{\"nodes\": []}",
+                "HTTP/1.1 304 Not Modified\r\nConnection: close\r\n\r\n",
+            ];
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().expect("accept connection");
+                let mut reader = BufReader::new(stream.try_clone().expect("clone stream"));
+                let mut request = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read request");
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                    request.push_str(&line.to_ascii_lowercase());
+                }
+                requests.push(request);
+                stream
+                    .write_all(response.as_bytes())
+                    .expect("write response");
+            }
+            requests
+        });
+        let workspace = TestWorkspace::new();
+        let store = HttpStore::new(url).with_cache_dir(workspace.path());
+
+        assert_eq!(store.load().expect("download"), b"{\"nodes\": []}");
+        assert_eq!(store.load().expect("revalidate"), b"{\"nodes\": []}");
+
+        let requests = server.join().expect("server thread");
+        assert!(!requests[0].contains("if-none-match"));
+        assert!(requests[1].contains("if-none-match: \"v1\""));
+    }
+}
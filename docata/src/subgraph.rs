@@ -0,0 +1,197 @@
+use crate::{
+    build::RecordedOptions, catalog::Catalog, error::Error, export::ExportFormat,
+    format::CatalogFormat,
+};
+use std::collections::{HashMap, HashSet};
+
+/// Which documents a subgraph keeps.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubgraphSelection {
+    /// `id` and every document within `depth` edges of it, following edges
+    /// of any kind in either direction.
+    Focus { id: String, depth: usize },
+    /// Documents of one `domain`. Only catalogs built with node metadata
+    /// record domains.
+    Domain(String),
+}
+
+/// How an extracted subgraph is written.
+#[derive(Clone, Copy, Debug)]
+pub enum SubgraphFormat {
+    /// A catalog that `docata` commands can read again.
+    Catalog(CatalogFormat),
+    /// A graph file for other tools.
+    Export(ExportFormat),
+}
+
+/// Keep the nodes of `catalog` chosen by `selection` and the edges between
+/// them.
+///
+/// # Errors
+///
+/// Returns `Error::NodeNotFound` when the focused id is not a catalog node.
+pub fn extract(
+    catalog: Catalog,
+    selection: &SubgraphSelection,
+) -> Result<Catalog, Error> {
+    let selected = match selection {
+        SubgraphSelection::Focus { id, depth } => {
+            if !catalog.nodes.iter().any(|node| &node.id == id) {
+                return Err(Error::NodeNotFound { id: id.clone() });
+            }
+            neighborhood(&catalog, id, *depth)
+        },
+        SubgraphSelection::Domain(domain) => catalog
+            .nodes
+            .iter()
+            .filter(|node| node.domain.as_ref() == Some(domain))
+            .map(|node| node.id.clone())
+            .collect(),
+    };
+
+    let edges = catalog
+        .edges
+        .into_iter()
+        .filter(|edge| selected.contains(&edge.from) && selected.contains(&edge.to))
+        .collect::<Vec<_>>();
+    Ok(Catalog {
+        nodes: catalog
+            .nodes
+            .into_iter()
+            .filter(|node| selected.contains(&node.id))
+            .collect(),
+        external: catalog
+            .external
+            .into_iter()
+            .filter(|id| edges.iter().any(|edge| &edge.to == id))
+            .collect(),
+        edges,
+        relations: catalog.relations,
+    })
+}
+
+/// Catalog nodes within `depth` edges of `id`, breadth first.
+fn neighborhood(
+    catalog: &Catalog,
+    id: &str,
+    depth: usize,
+) -> HashSet<String> {
+    let nodes = catalog
+        .nodes
+        .iter()
+        .map(|node| node.id.as_str())
+        .collect::<HashSet<_>>();
+    let mut neighbors = HashMap::<&str, Vec<&str>>::new();
+    for edge in &catalog.edges {
+        if nodes.contains(edge.from.as_str()) && nodes.contains(edge.to.as_str()) {
+            neighbors.entry(&edge.from).or_default().push(&edge.to);
+            neighbors.entry(&edge.to).or_default().push(&edge.from);
+        }
+    }
+
+    let mut selected = HashSet::from([id.to_owned()]);
+    let mut frontier = vec![id];
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for current in frontier {
+            for &neighbor in neighbors.get(current).into_iter().flatten() {
+                if selected.insert(neighbor.to_owned()) {
+                    next.push(neighbor);
+                }
+            }
+        }
+        frontier = next;
+    }
+    selected
+}
+
+/// Options recorded for a subgraph of a catalog written before catalogs
+/// recorded their `build_options`: the defaults `docata build` used then.
+pub(crate) fn unrecorded_options(with_node_metadata: bool) -> RecordedOptions {
+    RecordedOptions {
+        with_node_metadata,
+        duplicate_ids: crate::catalog::DuplicateIdPolicy::Error.as_str().to_owned(),
+        edge_sources: vec![crate::build::DEPS_EDGE_SOURCE.to_owned()],
+        config_hash: None,
+        with_provenance: false,
+        with_content_hash: false,
+        with_git_metadata: false,
+        with_code_owners: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SubgraphSelection, extract};
+    use crate::catalog::{Catalog, Edge, Node};
+    use std::collections::BTreeMap;
+
+    fn catalog() -> Catalog {
+        let node = |id: &str, domain: &str| Node {
+            id: id.to_owned(),
+            path: format!("docs/{id}.md"),
+            title: None,
+            tags: Vec::new(),
+            kind: None,
+            domain: Some(domain.to_owned()),
+            status: None,
+            source_of_truth: None,
+            content_hash: None,
+            last_modified: None,
+            authors: Vec::new(),
+            owners: Vec::new(),
+        };
+        let edge = |from: &str, to: &str| Edge {
+            from: from.to_owned(),
+            to: to.to_owned(),
+            kind: None,
+            declared_at: None,
+        };
+        Catalog {
+            nodes: vec![
+                node("api", "platform"),
+                node("db", "platform"),
+                node("guide", "docs"),
+                node("invoice", "billing"),
+                node("refund", "billing"),
+            ],
+            edges: vec![
+                edge("api", "db"),
+                edge("guide", "api"),
+                edge("invoice", "api"),
+                edge("refund", "invoice"),
+                edge("refund", "missing"),
+            ],
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        }
+    }
+
+    fn ids(catalog: &Catalog) -> Vec<&str> {
+        catalog.nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn keeps_neighborhood_in_both_directions() {
+        let subgraph = extract(
+            catalog(),
+            &SubgraphSelection::Focus {
+                id: "invoice".to_owned(),
+                depth: 1,
+            },
+        )
+        .expect("extract subgraph");
+
+        assert_eq!(ids(&subgraph), ["api", "invoice", "refund"]);
+        assert_eq!(subgraph.edges.len(), 2);
+    }
+
+    #[test]
+    fn keeps_one_domain() {
+        let subgraph = extract(catalog(), &SubgraphSelection::Domain("billing".to_owned()))
+            .expect("extract subgraph");
+
+        assert_eq!(ids(&subgraph), ["invoice", "refund"]);
+        assert_eq!(subgraph.edges.len(), 1);
+    }
+}
@@ -0,0 +1,420 @@
+//! Fixtures for testing code built on docata: scratch workspaces and
+//! generated document corpora.
+//!
+//! Corpora are generated from a seed, so a property test can loop over seeds
+//! and report the failing one:
+//!
+//! ```
+//! use docata::testing::{CorpusOptions, TestWorkspace, generate_corpus};
+//!
+//! for seed in 0..8 {
+//!     let workspace = TestWorkspace::new();
+//!     let corpus = generate_corpus(seed, &CorpusOptions::default());
+//!     corpus.write(workspace.path());
+//!
+//!     let mut catalog = Vec::new();
+//!     docata::build_catalog(workspace.path(), &mut catalog).expect("build catalog");
+//! }
+//! ```
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A scratch directory under the system temp directory, removed on drop.
+pub struct TestWorkspace {
+    root: PathBuf,
+}
+
+impl TestWorkspace {
+    /// Create an empty workspace with a name unique to this process.
+    ///
+    /// # Panics
+    ///
+    /// Panics when the directory cannot be created.
+    #[must_use]
+    pub fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos())
+            .unwrap_or_default();
+        let root = std::env::temp_dir().join(format!(
+            "docata-tests-{}-{timestamp}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).expect("create workspace");
+        Self { root }
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl Default for TestWorkspace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for TestWorkspace {
+    fn drop(&mut self) {
+        let _result = fs::remove_dir_all(&self.root);
+    }
+}
+
+/// Write a Markdown file at `relative_path` under `root` whose frontmatter
+/// has `id` and, when not empty, `deps`.
+///
+/// # Panics
+///
+/// Panics when the file cannot be written.
+pub fn write_markdown(
+    root: &Path,
+    relative_path: &str,
+    id: &str,
+    deps: &[&str],
+) {
+    let document = CorpusDocument {
+        id: id.to_owned(),
+        path: PathBuf::from(relative_path),
+        deps: deps.iter().map(ToString::to_string).collect(),
+        ..CorpusDocument::default()
+    };
+    document.write(root);
+}
+
+/// Shape of a generated corpus.
+#[derive(Clone, Debug)]
+pub struct CorpusOptions {
+    pub documents: usize,
+    /// Upper bound of resolvable deps per document.
+    pub max_deps: usize,
+    /// Close one dependency cycle through the first and last documents.
+    pub cycles: bool,
+    /// Deps pointing at ids no document has, spread over random documents.
+    pub unresolved_deps: usize,
+    /// Values drawn for `domain`; left unset when empty.
+    pub domains: Vec<String>,
+    /// Values drawn for `status`; left unset when empty.
+    pub statuses: Vec<String>,
+    /// Pool each document draws up to two `tags` from.
+    pub tags: Vec<String>,
+}
+
+impl Default for CorpusOptions {
+    fn default() -> Self {
+        Self {
+            documents: 20,
+            max_deps: 3,
+            cycles: false,
+            unresolved_deps: 0,
+            domains: Vec::new(),
+            statuses: Vec::new(),
+            tags: Vec::new(),
+        }
+    }
+}
+
+impl CorpusOptions {
+    /// `documents` documents shaped like a large documentation tree, for
+    /// benchmarks and profiling: up to four deps each, a few domains,
+    /// statuses, and tags, and one missing dep per hundred documents.
+    #[must_use]
+    pub fn realistic(documents: usize) -> Self {
+        let values = |prefix: &str, count: usize| {
+            (0..count)
+                .map(|index| format!("{prefix}-{index}"))
+                .collect()
+        };
+        Self {
+            documents,
+            max_deps: 4,
+            cycles: false,
+            unresolved_deps: documents / 100,
+            domains: values("domain", 8),
+            statuses: ["draft", "accepted", "deprecated"]
+                .map(str::to_owned)
+                .to_vec(),
+            tags: values("tag", 16),
+        }
+    }
+}
+
+/// One generated document.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CorpusDocument {
+    pub id: String,
+    /// Path relative to the corpus root.
+    pub path: PathBuf,
+    pub deps: Vec<String>,
+    pub domain: Option<String>,
+    pub status: Option<String>,
+    pub tags: Vec<String>,
+}
+
+impl CorpusDocument {
+    /// The document as Markdown with YAML frontmatter.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut contents = format!("---\nid: {}\n", self.id);
+        if !self.deps.is_empty() {
+            contents.push_str("deps:\n");
+            for dep in &self.deps {
+                let _ = writeln!(contents, "  - {dep}");
+            }
+        }
+        if let Some(domain) = &self.domain {
+            let _ = writeln!(contents, "domain: {domain}");
+        }
+        if let Some(status) = &self.status {
+            let _ = writeln!(contents, "status: {status}");
+        }
+        if !self.tags.is_empty() {
+            let _ = writeln!(contents, "tags: [{}]", self.tags.join(", "));
+        }
+        contents.push_str("---\n");
+        contents
+    }
+
+    fn write(
+        &self,
+        root: &Path,
+    ) {
+        let path = root.join(&self.path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("create parent directory");
+        }
+        fs::write(path, self.to_markdown()).expect("write markdown");
+    }
+}
+
+/// A generated set of documents.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Corpus {
+    pub documents: Vec<CorpusDocument>,
+}
+
+impl Corpus {
+    /// Write every document under `root`.
+    ///
+    /// # Panics
+    ///
+    /// Panics when a file cannot be written.
+    pub fn write(
+        &self,
+        root: &Path,
+    ) {
+        for document in &self.documents {
+            document.write(root);
+        }
+    }
+
+    /// Deps that no document in the corpus defines, sorted.
+    #[must_use]
+    pub fn unresolved_deps(&self) -> Vec<&str> {
+        let mut unresolved = self
+            .documents
+            .iter()
+            .flat_map(|document| &document.deps)
+            .filter(|dep| !self.documents.iter().any(|document| document.id == **dep))
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        unresolved.sort_unstable();
+        unresolved.dedup();
+        unresolved
+    }
+}
+
+/// Generate a corpus shaped by `options`; the same seed always yields the
+/// same corpus.
+///
+/// Documents are spread over a few directories. Without `options.cycles`
+/// every document only depends on documents generated before it, so the
+/// dependency graph is acyclic.
+#[must_use]
+pub fn generate_corpus(
+    seed: u64,
+    options: &CorpusOptions,
+) -> Corpus {
+    let mut rng = SplitMix64(seed);
+    let mut documents = (0..options.documents)
+        .map(|index| {
+            let mut deps = (0..rng.below(options.max_deps.min(index) + 1))
+                .map(|_| format!("doc-{:04}", rng.below(index)))
+                .collect::<Vec<_>>();
+            deps.sort();
+            deps.dedup();
+            let mut tags = (0..rng.below(3))
+                .filter_map(|_| rng.pick(&options.tags).cloned())
+                .collect::<Vec<_>>();
+            tags.sort();
+            tags.dedup();
+
+            CorpusDocument {
+                id: format!("doc-{index:04}"),
+                path: PathBuf::from(format!("area-{}/doc-{index:04}.md", index % 4)),
+                deps,
+                domain: rng.pick(&options.domains).cloned(),
+                status: rng.pick(&options.statuses).cloned(),
+                tags,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    if options.cycles && documents.len() > 1 {
+        let last = documents.len() - 1;
+        let first_id = documents[0].id.clone();
+        let last_id = documents[last].id.clone();
+        documents[0].deps.push(last_id);
+        if !documents[last].deps.contains(&first_id) {
+            documents[last].deps.push(first_id);
+        }
+    }
+    if !documents.is_empty() {
+        for missing in 0..options.unresolved_deps {
+            let index = rng.below(documents.len());
+            documents[index].deps.push(format!("missing-{missing:04}"));
+        }
+    }
+
+    Corpus { documents }
+}
+
+/// Small deterministic generator; corpora need reproducibility, not quality.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut value = self.0;
+        value = (value ^ (value >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        value ^ (value >> 31)
+    }
+
+    /// A value in `0..bound`, or 0 when `bound` is 0.
+    fn below(
+        &mut self,
+        bound: usize,
+    ) -> usize {
+        if bound == 0 {
+            return 0;
+        }
+        usize::try_from(self.next() % bound as u64).unwrap_or_default()
+    }
+
+    fn pick<'a, T>(
+        &mut self,
+        values: &'a [T],
+    ) -> Option<&'a T> {
+        if values.is_empty() {
+            return None;
+        }
+        values.get(self.below(values.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CorpusOptions, TestWorkspace, generate_corpus};
+    use crate::validate::build_validation_report;
+
+    #[test]
+    fn generated_corpora_validate_as_shaped() {
+        for seed in 0..16 {
+            let options = CorpusOptions {
+                unresolved_deps: 2,
+                ..CorpusOptions::default()
+            };
+            let corpus = generate_corpus(seed, &options);
+            assert_eq!(corpus, generate_corpus(seed, &options));
+
+            let workspace = TestWorkspace::new();
+            corpus.write(workspace.path());
+            let entries = crate::scan::scan(workspace.path()).expect("scan corpus");
+            let report = build_validation_report(&entries);
+
+            assert_eq!(entries.len(), options.documents, "seed {seed}");
+            assert!(report.dependency_cycles.is_empty(), "seed {seed}");
+            let mut unresolved = report
+                .unresolved_dependencies
+                .iter()
+                .map(|unresolved| unresolved.to_id.as_str())
+                .collect::<Vec<_>>();
+            unresolved.sort_unstable();
+            unresolved.dedup();
+            assert_eq!(unresolved, corpus.unresolved_deps(), "seed {seed}");
+        }
+
+        let cyclic = generate_corpus(
+            0,
+            &CorpusOptions {
+                cycles: true,
+                ..CorpusOptions::default()
+            },
+        );
+        let workspace = TestWorkspace::new();
+        cyclic.write(workspace.path());
+        let entries = crate::scan::scan(workspace.path()).expect("scan corpus");
+        assert!(
+            !build_validation_report(&entries)
+                .dependency_cycles
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn realistic_corpora_scale_missing_deps_and_draw_metadata() {
+        let options = CorpusOptions::realistic(1_000);
+        let corpus = generate_corpus(7, &options);
+
+        assert_eq!(corpus.documents.len(), 1_000);
+        assert_eq!(corpus.unresolved_deps().len(), 10);
+        for document in &corpus.documents {
+            let resolved = document
+                .deps
+                .iter()
+                .filter(|dep| !dep.starts_with("missing-"))
+                .count();
+            assert!(resolved <= options.max_deps, "{}", document.id);
+            assert!(document.tags.len() <= 2, "{}", document.id);
+            assert!(
+                document
+                    .domain
+                    .as_ref()
+                    .is_some_and(|domain| options.domains.contains(domain))
+            );
+            assert!(
+                document
+                    .status
+                    .as_ref()
+                    .is_some_and(|status| options.statuses.contains(status))
+            );
+        }
+
+        let workspace = TestWorkspace::new();
+        corpus.write(workspace.path());
+        let entries = crate::scan::scan(workspace.path()).expect("scan corpus");
+        let report = build_validation_report(&entries);
+        assert_eq!(entries.len(), 1_000);
+        assert!(report.dependency_cycles.is_empty());
+
+        assert!(
+            generate_corpus(7, &CorpusOptions::realistic(99))
+                .unresolved_deps()
+                .is_empty()
+        );
+        assert!(
+            generate_corpus(7, &CorpusOptions::realistic(0))
+                .documents
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,286 @@
+use crate::{domain::RelationKind, error::Error, format::OutputFormat, graph::Graph};
+use std::{collections::HashSet, io::Write};
+
+/// Why a tree node is shown without its children.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeMarker {
+    /// The id is already on the path from the root.
+    Cycle,
+    /// The id was expanded earlier in the tree.
+    Repeated,
+}
+
+impl TreeMarker {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            TreeMarker::Cycle => "cycle",
+            TreeMarker::Repeated => "repeated",
+        }
+    }
+}
+
+/// One id in a dependency tree with the ids it relates to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TreeNode {
+    pub id: String,
+    /// Whether `id` is a catalog node.
+    pub resolved: bool,
+    pub marker: Option<TreeMarker>,
+    /// Related ids sorted by id; empty for marked nodes and at the depth
+    /// limit.
+    pub children: Vec<TreeNode>,
+}
+
+impl Drop for TreeNode {
+    /// Drop descendants one at a time rather than recursing per level, so
+    /// trees of long dependency chains fit in the thread's stack.
+    fn drop(&mut self) {
+        let mut pending = std::mem::take(&mut self.children);
+        while let Some(mut node) = pending.pop() {
+            pending.append(&mut node.children);
+        }
+    }
+}
+
+/// The transitive relations of one id, expanded like `cargo tree`: each id
+/// is expanded once, later occurrences and cycles are marked instead.
+#[derive(Clone, Debug)]
+pub struct DependencyTree {
+    pub command: RelationKind,
+    pub root: TreeNode,
+    pub max_depth: Option<usize>,
+}
+
+/// Build the tree of `relation_kind` relations below `root`, expanding at
+/// most `max_depth` levels.
+#[must_use]
+pub fn build(
+    root: &str,
+    graph: &Graph,
+    relation_kind: RelationKind,
+    max_depth: Option<usize>,
+) -> DependencyTree {
+    let mut walk = TreeWalk {
+        graph,
+        resolver: relation_kind.resolver(),
+        max_depth,
+        ancestors: HashSet::new(),
+        expanded: HashSet::new(),
+    };
+
+    DependencyTree {
+        command: relation_kind,
+        root: walk.expand(root.to_owned()),
+        max_depth,
+    }
+}
+
+struct TreeWalk<'a> {
+    graph: &'a Graph,
+    resolver: crate::domain::RelationResolver,
+    max_depth: Option<usize>,
+    /// Ids on the path from the root to the node being expanded.
+    ancestors: HashSet<String>,
+    expanded: HashSet<String>,
+}
+
+impl<'a> TreeWalk<'a> {
+    /// The tree below `root`.
+    ///
+    /// Keeps its own stack of nodes being expanded instead of recursing, so
+    /// dependency chains of any length fit in the thread's stack.
+    fn expand(
+        &mut self,
+        root: String,
+    ) -> TreeNode {
+        let (root, related) = self.open(root, 0);
+        let Some(related) = related else {
+            return root;
+        };
+        // The nodes being expanded, innermost last, each with the related
+        // ids it has yet to expand.
+        let mut expanding = vec![(root, related)];
+        while let Some((_, related)) = expanding.last_mut() {
+            if let Some(child) = related.next() {
+                let (child, related) = self.open(child.to_owned(), expanding.len());
+                match related {
+                    Some(related) => expanding.push((child, related)),
+                    None => {
+                        if let Some((parent, _)) = expanding.last_mut() {
+                            parent.children.push(child);
+                        }
+                    },
+                }
+                continue;
+            }
+
+            let Some((node, _)) = expanding.pop() else {
+                break;
+            };
+            self.ancestors.remove(&node.id);
+            match expanding.last_mut() {
+                Some((parent, _)) => parent.children.push(node),
+                None => return node,
+            }
+        }
+        unreachable!("the root is returned once expanded")
+    }
+
+    /// The node of `id` at `depth` without children, and the related ids to
+    /// expand below it unless it is marked or at the depth limit.
+    fn open(
+        &mut self,
+        id: String,
+        depth: usize,
+    ) -> (TreeNode, Option<std::vec::IntoIter<&'a str>>) {
+        let marker = if self.ancestors.contains(&id) {
+            Some(TreeMarker::Cycle)
+        } else if self.expanded.contains(&id) {
+            Some(TreeMarker::Repeated)
+        } else {
+            None
+        };
+        let node = TreeNode {
+            resolved: self.graph.contains(&id),
+            id,
+            marker,
+            children: Vec::new(),
+        };
+        if marker.is_some() || self.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            return (node, None);
+        }
+
+        let mut related = (self.resolver)(self.graph, &node.id);
+        related.sort_unstable();
+        related.dedup();
+
+        self.expanded.insert(node.id.clone());
+        self.ancestors.insert(node.id.clone());
+        (node, Some(related.into_iter()))
+    }
+}
+
+/// Build the relation tree of `query_id` and write it to the provided writer.
+///
+/// # Errors
+///
+/// Returns `Error::QueryIdNotFound` when `strict` is set and `query_id` is
+/// not a catalog node, or `Error` when writing fails.
+pub fn run<W: Write>(
+    query_id: &str,
+    graph: &Graph,
+    relation_kind: RelationKind,
+    max_depth: Option<usize>,
+    strict: bool,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), Error> {
+    if strict && !graph.contains(query_id) {
+        return Err(Error::QueryIdNotFound {
+            query_id: query_id.to_owned(),
+        });
+    }
+
+    let tree = build(query_id, graph, relation_kind, max_depth);
+
+    crate::tree_presentation::write(&tree, format, out)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TreeMarker, TreeNode, build};
+    use crate::catalog::{Catalog, Edge};
+    use crate::domain::RelationKind;
+    use crate::graph::Graph;
+    use std::collections::BTreeMap;
+
+    fn graph(edges: &[(&str, &str)]) -> Graph {
+        let catalog = Catalog {
+            nodes: Vec::new(),
+            edges: edges
+                .iter()
+                .map(|(from, to)| Edge {
+                    from: (*from).to_owned(),
+                    to: (*to).to_owned(),
+                    kind: None,
+                    declared_at: None,
+                })
+                .collect(),
+            relations: BTreeMap::new(),
+            external: Vec::new(),
+        };
+        Graph::from_catalog(&catalog)
+    }
+
+    fn summary(node: &TreeNode) -> String {
+        let marker = node
+            .marker
+            .map(|marker| format!(" ({})", marker.as_str()))
+            .unwrap_or_default();
+        let children = node.children.iter().map(summary).collect::<Vec<_>>();
+        if children.is_empty() {
+            format!("{}{marker}", node.id)
+        } else {
+            format!("{}[{}]", node.id, children.join(","))
+        }
+    }
+
+    #[test]
+    fn marks_repeated_ids_and_cycles() {
+        let graph = graph(&[
+            ("hub", "api"),
+            ("hub", "guide"),
+            ("guide", "api"),
+            ("api", "hub"),
+        ]);
+
+        let tree = build("hub", &graph, RelationKind::Deps, None);
+
+        assert_eq!(
+            summary(&tree.root),
+            "hub[api[hub (cycle)],guide[api (repeated)]]"
+        );
+        assert_eq!(
+            tree.root.children[0].children[0].marker,
+            Some(TreeMarker::Cycle)
+        );
+    }
+
+    #[test]
+    fn stops_at_depth_limit() {
+        let graph = graph(&[("a", "b"), ("b", "c"), ("c", "d")]);
+
+        let tree = build("a", &graph, RelationKind::Deps, Some(2));
+
+        assert_eq!(summary(&tree.root), "a[b[c]]");
+    }
+
+    #[test]
+    fn expands_chains_deeper_than_the_stack() {
+        const DEPTH: usize = 100_000;
+        let ids = (0..=DEPTH)
+            .map(|node| format!("doc-{node:06}"))
+            .collect::<Vec<_>>();
+        let mut edges = ids
+            .windows(2)
+            .map(|pair| (pair[0].as_str(), pair[1].as_str()))
+            .collect::<Vec<_>>();
+        edges.push((&ids[DEPTH], &ids[0]));
+        let graph = graph(&edges);
+
+        let tree = build(&ids[0], &graph, RelationKind::Deps, None);
+
+        let mut node = &tree.root;
+        let mut depth = 0;
+        while let [child] = node.children.as_slice() {
+            node = child;
+            depth += 1;
+        }
+        assert_eq!(depth, DEPTH + 1);
+        assert_eq!(node.id, ids[0]);
+        assert_eq!(node.marker, Some(TreeMarker::Cycle));
+    }
+}
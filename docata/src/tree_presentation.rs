@@ -0,0 +1,233 @@
+use crate::format::OutputFormat;
+use crate::json;
+use crate::tree::{DependencyTree, TreeMarker, TreeNode};
+use serde::Serialize;
+use std::io::Write;
+use thiserror::Error;
+
+#[derive(Debug, Serialize)]
+struct TreeNodeJson<'a> {
+    id: &'a str,
+    resolved: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    marker: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    children: Vec<TreeNodeJson<'a>>,
+}
+
+impl<'a> From<&'a TreeNode> for TreeNodeJson<'a> {
+    fn from(node: &'a TreeNode) -> Self {
+        Self {
+            id: &node.id,
+            resolved: node.resolved,
+            marker: node.marker.map(TreeMarker::as_str),
+            children: node.children.iter().map(TreeNodeJson::from).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DependencyTreeJson<'a> {
+    command: &'static str,
+    max_depth: Option<usize>,
+    tree: TreeNodeJson<'a>,
+}
+
+impl<'a> From<&'a DependencyTree> for DependencyTreeJson<'a> {
+    fn from(tree: &'a DependencyTree) -> Self {
+        Self {
+            command: tree.command.as_str(),
+            max_depth: tree.max_depth,
+            tree: TreeNodeJson::from(&tree.root),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum TreePresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a relation tree according to the selected output format.
+///
+/// # Errors
+///
+/// Returns `TreePresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    tree: &DependencyTree,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), TreePresentationError> {
+    match format {
+        OutputFormat::Text => write_text(tree, out),
+        OutputFormat::Json | OutputFormat::CompactJson => {
+            json::write(out, &DependencyTreeJson::from(tree), format)?;
+            Ok(())
+        },
+        OutputFormat::Yaml => {
+            yaml_serde::to_writer(out, &DependencyTreeJson::from(tree))?;
+            Ok(())
+        },
+        OutputFormat::Sarif | OutputFormat::Ndjson | OutputFormat::Dot | OutputFormat::Junit => {
+            Err(TreePresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+/// Draw the tree with box-drawing branches, one id per line.
+///
+/// Keeps its own stack of levels being drawn instead of recursing, so trees
+/// of any depth fit in the thread's stack.
+fn write_text<W: Write>(
+    tree: &DependencyTree,
+    out: &mut W,
+) -> Result<(), TreePresentationError> {
+    writeln!(out, "{}", label(&tree.root))?;
+
+    let mut prefix = String::new();
+    // The children each level has yet to draw, innermost last, with the
+    // length of `prefix` above the level.
+    let mut levels = vec![(tree.root.children.iter(), 0)];
+    while let Some((children, above)) = levels.last_mut() {
+        let Some(child) = children.next() else {
+            prefix.truncate(*above);
+            levels.pop();
+            continue;
+        };
+        let (branch, indent) = if children.as_slice().is_empty() {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        writeln!(out, "{prefix}{branch}{}", label(child))?;
+        if !child.children.is_empty() {
+            levels.push((child.children.iter(), prefix.len()));
+            prefix.push_str(indent);
+        }
+    }
+    Ok(())
+}
+
+/// The id followed by `(missing)` for ids outside the catalog, `(*)` for
+/// repeated ids, and `(cycle)` for ids closing a cycle.
+fn label(node: &TreeNode) -> String {
+    let mut label = node.id.clone();
+    if !node.resolved {
+        label.push_str(" (missing)");
+    }
+    match node.marker {
+        Some(TreeMarker::Repeated) => label.push_str(" (*)"),
+        Some(TreeMarker::Cycle) => label.push_str(" (cycle)"),
+        None => {},
+    }
+    label
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::domain::RelationKind;
+    use crate::format::OutputFormat;
+    use crate::tree::{DependencyTree, TreeMarker, TreeNode};
+
+    fn node(
+        id: &str,
+        marker: Option<TreeMarker>,
+        children: Vec<TreeNode>,
+    ) -> TreeNode {
+        TreeNode {
+            id: id.to_owned(),
+            resolved: id != "gone",
+            marker,
+            children,
+        }
+    }
+
+    #[test]
+    fn draws_branches_and_markers() {
+        let tree = DependencyTree {
+            command: RelationKind::Deps,
+            root: node(
+                "hub",
+                None,
+                vec![
+                    node(
+                        "api",
+                        None,
+                        vec![
+                            node("gone", None, Vec::new()),
+                            node("hub", Some(TreeMarker::Cycle), Vec::new()),
+                        ],
+                    ),
+                    node(
+                        "guide",
+                        None,
+                        vec![node("api", Some(TreeMarker::Repeated), Vec::new())],
+                    ),
+                ],
+            ),
+            max_depth: None,
+        };
+
+        let mut output = Vec::new();
+        write(&tree, OutputFormat::Text, &mut output).expect("write tree");
+
+        assert_eq!(
+            String::from_utf8(output).expect("valid utf-8"),
+            "hub\n├── api\n│   ├── gone (missing)\n│   └── hub (cycle)\n└── guide\n    └── api (*)\n"
+        );
+    }
+
+    #[test]
+    fn restores_the_prefix_after_a_nested_branch() {
+        let tree = DependencyTree {
+            command: RelationKind::Deps,
+            root: node(
+                "a",
+                None,
+                vec![
+                    node(
+                        "b",
+                        None,
+                        vec![node("c", None, vec![node("d", None, Vec::new())])],
+                    ),
+                    node("e", None, Vec::new()),
+                ],
+            ),
+            max_depth: None,
+        };
+
+        let mut output = Vec::new();
+        write(&tree, OutputFormat::Text, &mut output).expect("write tree");
+
+        assert_eq!(
+            String::from_utf8(output).expect("valid utf-8"),
+            "a\n├── b\n│   └── c\n│       └── d\n└── e\n"
+        );
+    }
+
+    #[test]
+    fn draws_trees_deeper_than_the_stack() {
+        const DEPTH: usize = 100_000;
+        let mut root = node("leaf", None, Vec::new());
+        for level in (0..DEPTH).rev() {
+            root = node(&format!("doc-{level}"), None, vec![root]);
+        }
+        let tree = DependencyTree {
+            command: RelationKind::Deps,
+            root,
+            max_depth: None,
+        };
+
+        // Lines are drawn without copying their prefix, so a sink keeps
+        // this linear in the depth.
+        write(&tree, OutputFormat::Text, &mut std::io::sink()).expect("write tree");
+    }
+}
@@ -1,6 +1,14 @@
-use crate::scan::Entry;
+use crate::config::{RuleOverride, TypeContract};
+use crate::git::{is_iso_date, parse_date, today};
+use crate::i18n::Locale;
+use crate::links::{markdown_links, normalize};
+use crate::progress::{NoProgress, ProgressEvent, ProgressSink, ValidationPhase};
+use crate::scan::{Entry, ScanDiagnostic, SkippedFile};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::fmt::{self, Display, Formatter};
+use std::hash::Hash;
+use std::path::Path;
 use thiserror::Error;
 
 #[derive(Debug, Clone)]
@@ -21,11 +29,188 @@ pub struct DependencyCycle {
     pub ids: Vec<String>,
 }
 
+/// A document no other document depends on.
+#[derive(Debug, Clone)]
+pub struct OrphanNode {
+    pub id: String,
+    pub path: String,
+}
+
+/// A dependency between documents of two domains the domain policy does
+/// not allow.
+#[derive(Debug, Clone)]
+pub struct DomainViolation {
+    pub from_id: String,
+    pub from_domain: String,
+    pub to_id: String,
+    pub to_domain: String,
+    pub path: String,
+}
+
+/// Why a body link is broken.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BrokenLinkReason {
+    /// Nothing exists at the link target.
+    Missing,
+    /// The target is a Markdown file without frontmatter, or outside the
+    /// scanned root.
+    NotInCatalog,
+}
+
+impl BrokenLinkReason {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            BrokenLinkReason::Missing => "missing",
+            BrokenLinkReason::NotInCatalog => "not_in_catalog",
+        }
+    }
+}
+
+/// A document whose frontmatter lacks fields its `type` requires.
+#[derive(Debug, Clone)]
+pub struct MissingFields {
+    pub id: String,
+    pub node_type: String,
+    pub path: String,
+    /// Required fields that are unset or empty, in contract order.
+    pub fields: Vec<String>,
+}
+
+/// A document whose `status` is not in the allowed vocabulary.
+#[derive(Debug, Clone)]
+pub struct UnknownStatus {
+    pub id: String,
+    pub path: String,
+    pub status: String,
+    /// Statuses allowed for this document.
+    pub allowed: Vec<String>,
+}
+
+/// A document whose `last_reviewed` date is older than
+/// [`ValidationRules::stale_after_days`] allows.
+#[derive(Debug, Clone)]
+pub struct LapsedReview {
+    pub id: String,
+    pub path: String,
+    pub last_reviewed: String,
+    /// Days since `last_reviewed`.
+    pub age_days: u64,
+}
+
+/// A relative link in a document body whose target is broken.
+#[derive(Debug, Clone)]
+pub struct BrokenLink {
+    pub id: String,
+    pub path: String,
+    /// 1-based line of the link in `path`.
+    pub line: usize,
+    pub target: String,
+    pub reason: BrokenLinkReason,
+}
+
+/// Opt-in validation rules, read from the `[validation]` table of
+/// `docata.toml`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ValidationRules {
+    /// Flag documents that no other document depends on.
+    pub orphans: bool,
+    /// Document types exempt from the orphan rule, e.g. `index`.
+    pub orphan_root_types: Vec<String>,
+    /// Domains each domain may depend on, e.g. `frontend = ["api"]`. When
+    /// set, every other dependency between documents of different domains is
+    /// flagged; documents without a domain are exempt.
+    pub allowed_domain_deps: BTreeMap<String, Vec<String>>,
+    /// Flag relative body links to files that do not exist or are not
+    /// catalog documents.
+    pub broken_links: bool,
+    /// Report Markdown files without frontmatter or without an `id` as
+    /// warnings instead of dropping or rejecting them.
+    pub report_skipped: bool,
+    /// Report documents that fail to scan, e.g. with malformed frontmatter,
+    /// as findings instead of aborting at the first one.
+    pub lenient: bool,
+    /// Contracts per document `type`, from the `[types]` tables of
+    /// `docata.toml` (see [`Config::validation_rules`](crate::Config::validation_rules)).
+    #[serde(skip)]
+    pub types: BTreeMap<String, TypeContract>,
+    /// Allowed `status` values; any status is accepted when empty.
+    pub statuses: Vec<String>,
+    /// Ids outside the docs, e.g. `service:auth` or `repo:infra`, that
+    /// `deps` may name without being reported as unresolved.
+    pub external_ids: Vec<String>,
+    /// Ids of retired documents; `check --fix` drops deps on them.
+    pub removed_ids: Vec<String>,
+    /// Flag documents whose `last_reviewed` date is more than this many days
+    /// old.
+    pub stale_after_days: Option<u64>,
+    /// Statuses [`Self::stale_after_days`] applies to, e.g. `published`;
+    /// every document when empty.
+    pub stale_statuses: Vec<String>,
+    /// Rules for documents of one domain or under one path, from the
+    /// `[[overrides]]` tables of `docata.toml`, applied in order over the
+    /// rules above.
+    #[serde(skip)]
+    pub overrides: Vec<RuleOverride>,
+}
+
+impl ValidationRules {
+    /// These rules with `rule_override` merged over them.
+    fn merged(
+        &self,
+        rule_override: &RuleOverride,
+    ) -> Self {
+        let mut rules = self.clone();
+        if let Some(orphans) = rule_override.orphans {
+            rules.orphans = orphans;
+        }
+        if let Some(orphan_root_types) = &rule_override.orphan_root_types {
+            rules.orphan_root_types.clone_from(orphan_root_types);
+        }
+        if let Some(broken_links) = rule_override.broken_links {
+            rules.broken_links = broken_links;
+        }
+        if let Some(statuses) = &rule_override.statuses {
+            rules.statuses.clone_from(statuses);
+        }
+        rules.types.extend(
+            rule_override
+                .types
+                .iter()
+                .map(|(node_type, contract)| (node_type.clone(), contract.clone())),
+        );
+        rules
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct ValidationReport {
     pub duplicate_ids: Vec<DuplicateId>,
     pub unresolved_dependencies: Vec<UnresolvedDependency>,
     pub dependency_cycles: Vec<DependencyCycle>,
+    /// Only populated when [`ValidationRules::orphans`] is enabled.
+    pub orphan_nodes: Vec<OrphanNode>,
+    /// Only populated when [`ValidationRules::allowed_domain_deps`] is set.
+    pub domain_violations: Vec<DomainViolation>,
+    /// Only populated when [`ValidationRules::broken_links`] is enabled.
+    pub broken_links: Vec<BrokenLink>,
+    /// Only populated when [`ValidationRules::types`] requires fields.
+    pub missing_fields: Vec<MissingFields>,
+    /// Only populated when [`ValidationRules::statuses`] is set.
+    pub unknown_statuses: Vec<UnknownStatus>,
+    /// Only populated when [`ValidationRules::stale_after_days`] is set.
+    pub lapsed_reviews: Vec<LapsedReview>,
+    /// Documents that failed to scan. Only populated when
+    /// [`ValidationRules::lenient`] is enabled.
+    pub invalid_documents: Vec<ScanDiagnostic>,
+    /// Findings hidden by a [`Baseline`](crate::Baseline); not counted as
+    /// findings.
+    pub suppressed_count: usize,
+    /// Files left out of the catalog; warnings, not findings. Only populated
+    /// when [`ValidationRules::report_skipped`] is enabled.
+    pub skipped: Vec<SkippedFile>,
 }
 
 impl ValidationReport {
@@ -34,6 +219,28 @@ impl ValidationReport {
         self.duplicate_ids.is_empty()
             && self.unresolved_dependencies.is_empty()
             && self.dependency_cycles.is_empty()
+            && self.orphan_nodes.is_empty()
+            && self.domain_violations.is_empty()
+            && self.broken_links.is_empty()
+            && self.missing_fields.is_empty()
+            && self.unknown_statuses.is_empty()
+            && self.lapsed_reviews.is_empty()
+            && self.invalid_documents.is_empty()
+    }
+
+    /// Total number of findings across all rules.
+    #[must_use]
+    pub fn finding_count(&self) -> usize {
+        self.duplicate_ids.len()
+            + self.unresolved_dependencies.len()
+            + self.dependency_cycles.len()
+            + self.orphan_nodes.len()
+            + self.domain_violations.len()
+            + self.broken_links.len()
+            + self.missing_fields.len()
+            + self.unknown_statuses.len()
+            + self.lapsed_reviews.len()
+            + self.invalid_documents.len()
     }
 }
 
@@ -42,37 +249,57 @@ impl Display for ValidationReport {
         &self,
         f: &mut Formatter<'_>,
     ) -> fmt::Result {
-        writeln!(f, "validation failed:")?;
+        self.write_localized(Locale::En, f)
+    }
+}
+
+impl ValidationReport {
+    /// Write the failure listing with labels in `locale`.
+    pub(crate) fn write_localized<W: fmt::Write>(
+        &self,
+        locale: Locale,
+        f: &mut W,
+    ) -> fmt::Result {
+        let labels = locale.labels();
+        writeln!(f, "{}", labels.validation_failed)?;
+
+        if !self.invalid_documents.is_empty() {
+            heading(f, labels.invalid_documents, self.invalid_documents.len())?;
+            for invalid in &self.invalid_documents {
+                writeln!(f, "  - {invalid}")?;
+            }
+        }
 
         if !self.duplicate_ids.is_empty() {
-            writeln!(f, "- duplicate ids: {}", self.duplicate_ids.len())?;
+            heading(f, labels.duplicate_ids, self.duplicate_ids.len())?;
             for duplicate in &self.duplicate_ids {
                 writeln!(
                     f,
-                    "  - `{}` appears in: {}",
+                    "  - `{}` {}: {}",
                     duplicate.id,
+                    labels.appears_in,
                     duplicate.paths.join(", ")
                 )?;
             }
         }
 
         if !self.unresolved_dependencies.is_empty() {
-            writeln!(
+            heading(
                 f,
-                "- unresolved dependencies: {}",
-                self.unresolved_dependencies.len()
+                labels.unresolved_dependencies,
+                self.unresolved_dependencies.len(),
             )?;
             for unresolved in &self.unresolved_dependencies {
                 writeln!(
                     f,
-                    "  - `{}` -> `{}` (from {})",
-                    unresolved.from_id, unresolved.to_id, unresolved.path
+                    "  - `{}` -> `{}` ({} {})",
+                    unresolved.from_id, unresolved.to_id, labels.from, unresolved.path
                 )?;
             }
         }
 
         if !self.dependency_cycles.is_empty() {
-            writeln!(f, "- dependency cycles: {}", self.dependency_cycles.len())?;
+            heading(f, labels.dependency_cycles, self.dependency_cycles.len())?;
             for cycle in &self.dependency_cycles {
                 if let Some(first) = cycle.ids.first() {
                     let mut path = cycle.ids.join(" -> ");
@@ -83,14 +310,141 @@ impl Display for ValidationReport {
             }
         }
 
+        if !self.orphan_nodes.is_empty() {
+            heading(f, labels.orphan_nodes, self.orphan_nodes.len())?;
+            for orphan in &self.orphan_nodes {
+                writeln!(f, "  - `{}` ({} {})", orphan.id, labels.from, orphan.path)?;
+            }
+        }
+
+        if !self.domain_violations.is_empty() {
+            heading(
+                f,
+                labels.forbidden_domain_dependencies,
+                self.domain_violations.len(),
+            )?;
+            for violation in &self.domain_violations {
+                writeln!(
+                    f,
+                    "  - `{}` ({}) -> `{}` ({}) ({} {})",
+                    violation.from_id,
+                    violation.from_domain,
+                    violation.to_id,
+                    violation.to_domain,
+                    labels.from,
+                    violation.path
+                )?;
+            }
+        }
+
+        self.write_document_findings(locale, f)?;
+
+        if self.suppressed_count > 0 {
+            writeln!(f, "{}", locale.suppressed(self.suppressed_count))?;
+        }
+
+        Ok(())
+    }
+
+    /// Write findings about the content of single documents.
+    fn write_document_findings<W: fmt::Write>(
+        &self,
+        locale: Locale,
+        f: &mut W,
+    ) -> fmt::Result {
+        let labels = locale.labels();
+
+        if !self.broken_links.is_empty() {
+            heading(f, labels.broken_links, self.broken_links.len())?;
+            for link in &self.broken_links {
+                writeln!(
+                    f,
+                    "  - {}:{}: {}",
+                    link.path,
+                    link.line,
+                    locale.broken_link(&link.target, link.reason)
+                )?;
+            }
+        }
+
+        if !self.missing_fields.is_empty() {
+            heading(f, labels.missing_fields, self.missing_fields.len())?;
+            for missing in &self.missing_fields {
+                writeln!(
+                    f,
+                    "  - `{}` ({}): {} ({} {})",
+                    missing.id,
+                    missing.node_type,
+                    missing.fields.join(", "),
+                    labels.from,
+                    missing.path
+                )?;
+            }
+        }
+
+        if !self.unknown_statuses.is_empty() {
+            heading(f, labels.unknown_statuses, self.unknown_statuses.len())?;
+            for unknown in &self.unknown_statuses {
+                writeln!(
+                    f,
+                    "  - `{}`: `{}` ({}: {}) ({} {})",
+                    unknown.id,
+                    unknown.status,
+                    labels.allowed,
+                    unknown.allowed.join(", "),
+                    labels.from,
+                    unknown.path
+                )?;
+            }
+        }
+
+        if !self.lapsed_reviews.is_empty() {
+            heading(f, labels.lapsed_reviews, self.lapsed_reviews.len())?;
+            for lapsed in &self.lapsed_reviews {
+                writeln!(
+                    f,
+                    "  - `{}`: {} ({}) ({} {})",
+                    lapsed.id,
+                    lapsed.last_reviewed,
+                    locale.days_ago(lapsed.age_days),
+                    labels.from,
+                    lapsed.path
+                )?;
+            }
+        }
+
         Ok(())
     }
 }
 
+fn heading<W: fmt::Write>(
+    f: &mut W,
+    label: &str,
+    count: usize,
+) -> fmt::Result {
+    writeln!(f, "- {label}: {count}")
+}
+
+/// Result of validating a document tree, returned whether or not findings
+/// exist.
+#[derive(Debug, Clone, Default)]
+pub struct ValidationOutcome {
+    pub report: ValidationReport,
+    /// Number of documents with frontmatter that were validated.
+    pub document_count: usize,
+}
+
+impl ValidationOutcome {
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.report.is_empty()
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("{report}")]
 pub struct ValidationError {
-    report: ValidationReport,
+    report: Box<ValidationReport>,
 }
 
 impl ValidationError {
@@ -100,28 +454,395 @@ impl ValidationError {
     }
 }
 
-/// Validate scanned entries.
+/// Validate scanned entries, applying `rules` on top of the default checks.
 ///
 /// # Errors
 ///
 /// Returns `ValidationError` if duplicate IDs, unresolved dependencies, or
 /// dependency cycles are detected.
-pub fn validate_entries(entries: &[Entry]) -> Result<(), ValidationError> {
-    let report = build_validation_report(entries);
+pub fn validate_entries(
+    entries: &[Entry],
+    rules: &ValidationRules,
+) -> Result<(), ValidationError> {
+    let report = build_validation_report_with_rules(entries, rules);
 
     if report.is_empty() {
         Ok(())
     } else {
-        Err(ValidationError { report })
+        Err(ValidationError {
+            report: Box::new(report),
+        })
     }
 }
 
-fn build_validation_report(entries: &[Entry]) -> ValidationReport {
-    ValidationReport {
-        duplicate_ids: find_duplicate_ids(entries),
-        unresolved_dependencies: find_unresolved_dependencies(entries),
-        dependency_cycles: find_dependency_cycles(entries),
+/// Build the validation report for scanned entries, even when it is empty.
+#[must_use]
+pub fn build_validation_report(entries: &[Entry]) -> ValidationReport {
+    build_validation_report_with_rules(entries, &ValidationRules::default())
+}
+
+/// Build a validation report for `entries`, applying the opt-in `rules` on
+/// top of the default checks.
+///
+/// Override paths are matched against entry paths as given; see
+/// [`build_validation_report_in`] to match them below a documents directory.
+#[must_use]
+pub fn build_validation_report_with_rules(
+    entries: &[Entry],
+    rules: &ValidationRules,
+) -> ValidationReport {
+    build_validation_report_in(Path::new(""), entries, rules)
+}
+
+/// Build a validation report for `entries` scanned from `root`, applying
+/// `rules` with their overrides matched against paths relative to `root`.
+#[must_use]
+pub(crate) fn build_validation_report_in(
+    root: &Path,
+    entries: &[Entry],
+    rules: &ValidationRules,
+) -> ValidationReport {
+    build_validation_report_for(root, entries, rules, |_| true, &NoProgress)
+}
+
+/// Build a validation report like [`build_validation_report_in`], keeping
+/// only findings about the entries `is_subject` selects; the other entries
+/// are the graph the subjects are checked against. Each check run is
+/// reported to `progress` as it starts.
+#[must_use]
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "validate", skip_all, fields(entries = entries.len()))
+)]
+pub(crate) fn build_validation_report_for(
+    root: &Path,
+    entries: &[Entry],
+    rules: &ValidationRules,
+    is_subject: impl Fn(&Entry) -> bool,
+    progress: &dyn ProgressSink,
+) -> ValidationReport {
+    let phase = |phase| progress.event(&ProgressEvent::ValidationStarted { phase });
+    let subjects = entries
+        .iter()
+        .filter(|entry| is_subject(entry))
+        .collect::<Vec<_>>();
+    let subject_ids = subjects
+        .iter()
+        .map(|entry| entry.id.as_str())
+        .collect::<HashSet<_>>();
+
+    phase(ValidationPhase::DuplicateIds);
+    let duplicate_ids = find_duplicate_ids(entries);
+    phase(ValidationPhase::UnresolvedDependencies);
+    let unresolved_dependencies = find_unresolved_dependencies(entries, &rules.external_ids);
+    phase(ValidationPhase::DependencyCycles);
+    let dependency_cycles = find_dependency_cycles(entries);
+    let mut report = ValidationReport {
+        duplicate_ids,
+        unresolved_dependencies,
+        dependency_cycles,
+        domain_violations: if rules.allowed_domain_deps.is_empty() {
+            Vec::new()
+        } else {
+            phase(ValidationPhase::DomainViolations);
+            find_domain_violations(entries, &rules.allowed_domain_deps)
+        },
+        lapsed_reviews: rules.stale_after_days.map_or_else(Vec::new, |days| {
+            phase(ValidationPhase::LapsedReviews);
+            find_lapsed_reviews(entries, days, &rules.stale_statuses, &today())
+        }),
+        ..ValidationReport::default()
+    };
+
+    report
+        .duplicate_ids
+        .retain(|duplicate| subject_ids.contains(duplicate.id.as_str()));
+    report
+        .unresolved_dependencies
+        .retain(|unresolved| subject_ids.contains(unresolved.from_id.as_str()));
+    report
+        .dependency_cycles
+        .retain(|cycle| cycle.ids.iter().any(|id| subject_ids.contains(id.as_str())));
+    report
+        .domain_violations
+        .retain(|violation| subject_ids.contains(violation.from_id.as_str()));
+    report
+        .lapsed_reviews
+        .retain(|lapsed| subject_ids.contains(lapsed.id.as_str()));
+
+    phase(ValidationPhase::DocumentRules);
+    for (scope_rules, scope) in scopes(root, &subjects, rules) {
+        if scope_rules.orphans {
+            report.orphan_nodes.extend(find_orphan_nodes(
+                entries,
+                &scope,
+                &scope_rules.orphan_root_types,
+            ));
+        }
+        if scope_rules.broken_links {
+            report
+                .broken_links
+                .extend(find_broken_links(entries, &scope));
+        }
+        report
+            .missing_fields
+            .extend(find_missing_fields(&scope, &scope_rules.types));
+        report
+            .unknown_statuses
+            .extend(find_unknown_statuses(&scope, &scope_rules.statuses));
+    }
+
+    report
+        .orphan_nodes
+        .sort_by(|left, right| left.id.cmp(&right.id).then(left.path.cmp(&right.path)));
+    report
+        .broken_links
+        .sort_by(|left, right| left.path.cmp(&right.path).then(left.line.cmp(&right.line)));
+    report
+        .missing_fields
+        .sort_by(|left, right| left.path.cmp(&right.path));
+    report
+        .unknown_statuses
+        .sort_by(|left, right| left.path.cmp(&right.path));
+    #[cfg(feature = "tracing")]
+    tracing::debug!(findings = report.finding_count(), "validated documents");
+
+    report
+}
+
+/// Split `entries` into groups matched by the same overrides, each with the
+/// rules that apply to it.
+fn scopes<'a>(
+    root: &Path,
+    entries: &[&'a Entry],
+    rules: &ValidationRules,
+) -> Vec<(ValidationRules, Vec<&'a Entry>)> {
+    let mut groups = BTreeMap::<Vec<usize>, Vec<&Entry>>::new();
+    for &entry in entries {
+        let matched = rules
+            .overrides
+            .iter()
+            .enumerate()
+            .filter(|(_, rule_override)| rule_override.matches(root, entry))
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        groups.entry(matched).or_default().push(entry);
+    }
+
+    groups
+        .into_iter()
+        .map(|(matched, scope)| {
+            let scope_rules = matched.iter().fold(rules.clone(), |merged, &index| {
+                merged.merged(&rules.overrides[index])
+            });
+            (scope_rules, scope)
+        })
+        .collect()
+}
+
+fn find_unknown_statuses(
+    scope: &[&Entry],
+    statuses: &[String],
+) -> Vec<UnknownStatus> {
+    if statuses.is_empty() {
+        return Vec::new();
+    }
+
+    scope
+        .iter()
+        .filter_map(|entry| {
+            let status = entry.status.as_ref()?;
+            (!statuses.contains(status)).then(|| UnknownStatus {
+                id: entry.id.clone(),
+                path: entry.path.to_string_lossy().to_string(),
+                status: status.clone(),
+                allowed: statuses.to_vec(),
+            })
+        })
+        .collect()
+}
+
+fn find_lapsed_reviews(
+    entries: &[Entry],
+    stale_after_days: u64,
+    statuses: &[String],
+    today: &str,
+) -> Vec<LapsedReview> {
+    let Some(today) = parse_date(today) else {
+        return Vec::new();
+    };
+    let mut lapsed = entries
+        .iter()
+        .filter(|entry| {
+            statuses.is_empty()
+                || entry
+                    .status
+                    .as_ref()
+                    .is_some_and(|status| statuses.contains(status))
+        })
+        .filter_map(|entry| {
+            let last_reviewed = entry
+                .last_reviewed
+                .as_ref()
+                .filter(|date| is_iso_date(date))?;
+            let age_days = today.saturating_sub(parse_date(last_reviewed)?);
+            (age_days > stale_after_days).then(|| LapsedReview {
+                id: entry.id.clone(),
+                path: entry.path.to_string_lossy().to_string(),
+                last_reviewed: last_reviewed.clone(),
+                age_days,
+            })
+        })
+        .collect::<Vec<_>>();
+    lapsed.sort_by(|left, right| left.path.cmp(&right.path));
+    lapsed
+}
+
+fn find_missing_fields(
+    scope: &[&Entry],
+    types: &BTreeMap<String, TypeContract>,
+) -> Vec<MissingFields> {
+    scope
+        .iter()
+        .filter_map(|entry| {
+            let node_type = entry.node_type.as_deref()?;
+            let contract = types.get(node_type)?;
+            let fields = contract
+                .required
+                .iter()
+                .filter(|field| entry.fields.binary_search(field).is_err())
+                .cloned()
+                .collect::<Vec<_>>();
+            (!fields.is_empty()).then(|| MissingFields {
+                id: entry.id.clone(),
+                node_type: node_type.to_owned(),
+                path: entry.path.to_string_lossy().to_string(),
+                fields,
+            })
+        })
+        .collect()
+}
+
+/// Read each document body in `scope` and check its relative links against
+/// all `entries`. Documents that can no longer be read are skipped; scanning
+/// already reported them.
+fn find_broken_links(
+    entries: &[Entry],
+    scope: &[&Entry],
+) -> Vec<BrokenLink> {
+    let documents = entries
+        .iter()
+        .map(|entry| normalize(&entry.path))
+        .collect::<HashSet<_>>();
+
+    let mut broken = Vec::new();
+    for entry in scope {
+        let Ok(content) = std::fs::read_to_string(&entry.path) else {
+            continue;
+        };
+        let base = entry.path.parent().unwrap_or(Path::new(""));
+
+        for link in markdown_links(&content) {
+            let resolved = normalize(&base.join(&link.target));
+            let reason = if !resolved.exists() {
+                BrokenLinkReason::Missing
+            } else if link.is_markdown() && !documents.contains(&resolved) {
+                BrokenLinkReason::NotInCatalog
+            } else {
+                continue;
+            };
+            broken.push(BrokenLink {
+                id: entry.id.clone(),
+                path: entry.path.to_string_lossy().to_string(),
+                line: link.line,
+                target: link.target,
+                reason,
+            });
+        }
+    }
+
+    broken
+}
+
+fn find_domain_violations(
+    entries: &[Entry],
+    allowed: &BTreeMap<String, Vec<String>>,
+) -> Vec<DomainViolation> {
+    let domains_by_id = entries
+        .iter()
+        .filter_map(|entry| Some((entry.id.as_str(), entry.domain.as_deref()?)))
+        .collect::<HashMap<_, _>>();
+
+    let mut violations = Vec::new();
+    for entry in entries {
+        let Some(from_domain) = entry.domain.as_deref() else {
+            continue;
+        };
+
+        let mut deps = entry.deps.iter().collect::<Vec<_>>();
+        deps.sort();
+        deps.dedup();
+
+        for dep in deps {
+            let Some(&to_domain) = domains_by_id.get(dep.as_str()) else {
+                continue;
+            };
+            let permitted = from_domain == to_domain
+                || allowed
+                    .get(from_domain)
+                    .is_some_and(|targets| targets.iter().any(|target| target == to_domain));
+            if !permitted {
+                violations.push(DomainViolation {
+                    from_id: entry.id.clone(),
+                    from_domain: from_domain.to_owned(),
+                    to_id: dep.clone(),
+                    to_domain: to_domain.to_owned(),
+                    path: entry.path.to_string_lossy().to_string(),
+                });
+            }
+        }
     }
+    violations.sort_by(|left, right| {
+        left.from_id
+            .cmp(&right.from_id)
+            .then(left.path.cmp(&right.path))
+            .then(left.to_id.cmp(&right.to_id))
+    });
+
+    violations
+}
+
+/// Documents in `scope` that no entry depends on.
+fn find_orphan_nodes(
+    entries: &[Entry],
+    scope: &[&Entry],
+    root_types: &[String],
+) -> Vec<OrphanNode> {
+    let referenced = entries
+        .iter()
+        .flat_map(|entry| {
+            entry
+                .deps
+                .iter()
+                .filter(move |dep| **dep != entry.id)
+                .map(String::as_str)
+        })
+        .collect::<HashSet<_>>();
+
+    scope
+        .iter()
+        .filter(|entry| !referenced.contains(entry.id.as_str()))
+        .filter(|entry| {
+            entry
+                .node_type
+                .as_ref()
+                .is_none_or(|node_type| !root_types.contains(node_type))
+        })
+        .map(|entry| OrphanNode {
+            id: entry.id.clone(),
+            path: entry.path.to_string_lossy().to_string(),
+        })
+        .collect()
 }
 
 fn find_duplicate_ids(entries: &[Entry]) -> Vec<DuplicateId> {
@@ -152,10 +873,14 @@ fn find_duplicate_ids(entries: &[Entry]) -> Vec<DuplicateId> {
         .collect()
 }
 
-fn find_unresolved_dependencies(entries: &[Entry]) -> Vec<UnresolvedDependency> {
+pub(crate) fn find_unresolved_dependencies(
+    entries: &[Entry],
+    external_ids: &[String],
+) -> Vec<UnresolvedDependency> {
     let known_ids = entries
         .iter()
         .map(|entry| entry.id.as_str())
+        .chain(external_ids.iter().map(String::as_str))
         .collect::<HashSet<_>>();
 
     let mut ordered_entries = entries.iter().collect::<Vec<_>>();
@@ -230,75 +955,70 @@ fn find_dependency_cycles(entries: &[Entry]) -> Vec<DependencyCycle> {
         .collect()
 }
 
-fn strongly_connected_components(
-    adjacency: &BTreeMap<String, BTreeSet<String>>
-) -> Vec<Vec<String>> {
-    struct TarjanState {
+/// Tarjan's algorithm. Components are returned in reverse topological order:
+/// every component comes after the components it depends on.
+///
+/// Depth-first search keeps its own stack of nodes being visited instead of
+/// recursing, so dependency chains of any length fit in the thread's stack.
+pub(crate) fn strongly_connected_components<K: Clone + Ord + Hash>(
+    adjacency: &BTreeMap<K, BTreeSet<K>>
+) -> Vec<Vec<K>> {
+    struct TarjanState<'a, K> {
         index: usize,
-        stack: Vec<String>,
-        on_stack: HashSet<String>,
-        indices: HashMap<String, usize>,
-        low_link: HashMap<String, usize>,
-        components: Vec<Vec<String>>,
+        stack: Vec<&'a K>,
+        on_stack: HashSet<&'a K>,
+        indices: HashMap<&'a K, usize>,
+        low_link: HashMap<&'a K, usize>,
+        components: Vec<Vec<K>>,
     }
 
-    fn strong_connect(
-        node: &str,
-        adjacency: &BTreeMap<String, BTreeSet<String>>,
-        state: &mut TarjanState,
-    ) {
-        let node_key = node.to_owned();
-
-        state.indices.insert(node_key.clone(), state.index);
-        state.low_link.insert(node_key.clone(), state.index);
-        state.index += 1;
-        state.stack.push(node_key.clone());
-        state.on_stack.insert(node_key.clone());
-
-        if let Some(neighbors) = adjacency.get(node) {
-            for neighbor in neighbors {
-                if !state.indices.contains_key(neighbor) {
-                    strong_connect(neighbor, adjacency, state);
-
-                    if let Some(&neighbor_low_link) = state.low_link.get(neighbor) {
-                        let node_low_link = state.low_link.get(node).copied().unwrap_or(usize::MAX);
-                        let next_low_link = node_low_link.min(neighbor_low_link);
-                        state.low_link.insert(node_key.clone(), next_low_link);
-                    }
-                } else if state.on_stack.contains(neighbor)
-                    && let Some(&neighbor_index) = state.indices.get(neighbor)
-                {
-                    let node_low_link = state.low_link.get(node).copied().unwrap_or(usize::MAX);
-                    let next_low_link = node_low_link.min(neighbor_index);
-                    state.low_link.insert(node_key.clone(), next_low_link);
-                }
-            }
+    impl<'a, K: Clone + Ord + Hash> TarjanState<'a, K> {
+        fn enter(
+            &mut self,
+            node: &'a K,
+        ) {
+            self.indices.insert(node, self.index);
+            self.low_link.insert(node, self.index);
+            self.index += 1;
+            self.stack.push(node);
+            self.on_stack.insert(node);
         }
 
-        let Some(&node_index) = state.indices.get(node) else {
-            return;
-        };
-        let Some(&node_low_link) = state.low_link.get(node) else {
-            return;
-        };
+        fn lower(
+            &mut self,
+            node: &'a K,
+            low_link: usize,
+        ) {
+            if let Some(node_low_link) = self.low_link.get_mut(node) {
+                *node_low_link = (*node_low_link).min(low_link);
+            }
+        }
 
-        if node_index == node_low_link {
+        /// Finish visiting `node`, popping its component when it is the
+        /// component's root.
+        fn leave(
+            &mut self,
+            node: &'a K,
+        ) {
+            if self.indices.get(node) != self.low_link.get(node) {
+                return;
+            }
             let mut component = Vec::new();
-
-            while let Some(candidate) = state.stack.pop() {
-                state.on_stack.remove(&candidate);
-                let done = candidate == node;
-                component.push(candidate);
-                if done {
+            while let Some(candidate) = self.stack.pop() {
+                self.on_stack.remove(candidate);
+                component.push(candidate.clone());
+                if candidate == node {
                     break;
                 }
             }
 
             component.sort();
-            state.components.push(component);
+            self.components.push(component);
         }
     }
 
+    let no_neighbors = BTreeSet::new();
+    let neighbors = |node: &K| adjacency.get(node).unwrap_or(&no_neighbors).iter();
     let mut state = TarjanState {
         index: 0,
         stack: Vec::new(),
@@ -307,10 +1027,41 @@ fn strongly_connected_components(
         low_link: HashMap::new(),
         components: Vec::new(),
     };
+    // The nodes being visited, innermost last, each with the neighbors it
+    // has yet to look at.
+    let mut visiting = Vec::new();
+
+    for root in adjacency.keys() {
+        if state.indices.contains_key(root) {
+            continue;
+        }
+        state.enter(root);
+        visiting.push((root, neighbors(root)));
 
-    for node in adjacency.keys() {
-        if !state.indices.contains_key(node) {
-            strong_connect(node, adjacency, &mut state);
+        while let Some((node, remaining)) = visiting.last_mut() {
+            let node = *node;
+            match remaining.next() {
+                Some(neighbor) if !state.indices.contains_key(neighbor) => {
+                    state.enter(neighbor);
+                    visiting.push((neighbor, neighbors(neighbor)));
+                },
+                Some(neighbor) => {
+                    if state.on_stack.contains(neighbor)
+                        && let Some(&neighbor_index) = state.indices.get(neighbor)
+                    {
+                        state.lower(node, neighbor_index);
+                    }
+                },
+                None => {
+                    visiting.pop();
+                    state.leave(node);
+                    if let Some(&(parent, _)) = visiting.last()
+                        && let Some(&node_low_link) = state.low_link.get(node)
+                    {
+                        state.lower(parent, node_low_link);
+                    }
+                },
+            }
         }
     }
 
@@ -319,9 +1070,14 @@ fn strongly_connected_components(
 
 #[cfg(test)]
 mod tests {
-    use super::validate_entries;
+    use super::{
+        ValidationRules, build_validation_report_in, build_validation_report_with_rules,
+        find_lapsed_reviews, strongly_connected_components, validate_entries,
+    };
+    use crate::config::{RuleOverride, TypeContract};
     use crate::scan::Entry;
-    use std::path::PathBuf;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::path::{Path, PathBuf};
 
     fn entry(
         id: &str,
@@ -331,11 +1087,21 @@ mod tests {
         Entry {
             id: id.to_owned(),
             deps: deps.iter().map(ToString::to_string).collect(),
+            dep_lines: Vec::new(),
             path: PathBuf::from(path),
+            title: None,
+            tags: Vec::new(),
             node_type: None,
             domain: None,
             status: None,
             source_of_truth: None,
+            last_reviewed: None,
+            owner: None,
+            fields: Vec::new(),
+            values: BTreeMap::new(),
+            content_hash: None,
+            history: None,
+            code_owners: Vec::new(),
         }
     }
 
@@ -347,7 +1113,8 @@ mod tests {
             entry("a", &[], "docs/a-duplicate.md"),
         ];
 
-        let error = validate_entries(&entries).expect_err("validation must fail");
+        let error = validate_entries(&entries, &ValidationRules::default())
+            .expect_err("validation must fail");
         let report = error.report();
 
         assert_eq!(report.duplicate_ids.len(), 1);
@@ -372,6 +1139,159 @@ mod tests {
             entry("c", &["b"], "docs/c.md"),
         ];
 
-        validate_entries(&entries).expect("validation must pass");
+        validate_entries(&entries, &ValidationRules::default()).expect("validation must pass");
+    }
+
+    #[test]
+    fn accepts_deps_on_known_external_ids() {
+        let entries = vec![entry(
+            "api",
+            &["service:auth", "service:gone"],
+            "docs/api.md",
+        )];
+        let rules = ValidationRules {
+            external_ids: vec!["service:auth".to_owned()],
+            ..ValidationRules::default()
+        };
+
+        let error = validate_entries(&entries, &rules).expect_err("validation must fail");
+
+        let unresolved = &error.report().unresolved_dependencies;
+        assert_eq!(unresolved.len(), 1);
+        assert_eq!(unresolved[0].to_id, "service:gone");
+    }
+
+    #[test]
+    fn flags_cross_domain_dependencies_the_policy_does_not_allow() {
+        let mut frontend = entry("web", &["api", "web-shell"], "docs/web.md");
+        frontend.domain = Some("frontend".to_owned());
+        let mut shell = entry("web-shell", &[], "docs/web-shell.md");
+        shell.domain = Some("frontend".to_owned());
+        let mut api = entry("api", &["web", "notes"], "docs/api.md");
+        api.domain = Some("api".to_owned());
+        let notes = entry("notes", &["web"], "docs/notes.md");
+        let entries = vec![frontend, shell, api, notes];
+
+        let rules = ValidationRules {
+            allowed_domain_deps: BTreeMap::from([("frontend".to_owned(), vec!["api".to_owned()])]),
+            ..ValidationRules::default()
+        };
+        let report = build_validation_report_with_rules(&entries, &rules);
+
+        assert_eq!(report.domain_violations.len(), 1);
+        let violation = &report.domain_violations[0];
+        assert_eq!(
+            (violation.from_id.as_str(), violation.to_id.as_str()),
+            ("api", "web")
+        );
+        assert_eq!(violation.path, "docs/api.md");
+        assert!(
+            build_validation_report_with_rules(&entries, &ValidationRules::default())
+                .domain_violations
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn flags_documents_missing_fields_their_type_requires() {
+        let mut runbook = entry("restart", &[], "docs/restart.md");
+        runbook.node_type = Some("runbook".to_owned());
+        runbook.fields = vec!["id".to_owned(), "service".to_owned(), "type".to_owned()];
+        let mut guide = entry("guide", &[], "docs/guide.md");
+        guide.node_type = Some("guide".to_owned());
+
+        let rules = ValidationRules {
+            types: BTreeMap::from([(
+                "runbook".to_owned(),
+                TypeContract {
+                    required: vec!["service".to_owned(), "oncall".to_owned()],
+                    template: None,
+                },
+            )]),
+            ..ValidationRules::default()
+        };
+        let report = build_validation_report_with_rules(&[runbook, guide], &rules);
+
+        assert_eq!(report.missing_fields.len(), 1);
+        assert_eq!(report.missing_fields[0].id, "restart");
+        assert_eq!(report.missing_fields[0].fields, ["oncall"]);
+    }
+
+    #[test]
+    fn flags_published_documents_whose_review_lapsed() {
+        let reviewed = |id: &str, status: &str, date: &str| Entry {
+            status: Some(status.to_owned()),
+            last_reviewed: Some(date.to_owned()),
+            ..entry(id, &[], &format!("{id}.md"))
+        };
+        let entries = [
+            reviewed("api", "published", "2025-01-10"),
+            reviewed("db", "published", "2025-09-01"),
+            reviewed("notes", "draft", "2020-01-01"),
+        ];
+
+        let lapsed = find_lapsed_reviews(&entries, 180, &["published".to_owned()], "2025-10-01");
+
+        assert_eq!(lapsed.len(), 1);
+        assert_eq!(lapsed[0].id, "api");
+        assert_eq!(lapsed[0].age_days, 264);
+    }
+
+    #[test]
+    fn applies_overrides_per_domain_and_path() {
+        let mut api = entry("api", &[], "docs/api/api.md");
+        api.domain = Some("api".to_owned());
+        api.status = Some("stable".to_owned());
+        let mut handbook = entry("leave", &[], "docs/hr/leave.md");
+        handbook.status = Some("approved".to_owned());
+
+        let rules = ValidationRules {
+            orphans: true,
+            statuses: vec!["draft".to_owned(), "stable".to_owned()],
+            overrides: vec![
+                RuleOverride {
+                    domain: Some("api".to_owned()),
+                    orphans: Some(false),
+                    ..RuleOverride::default()
+                },
+                RuleOverride {
+                    path: Some(PathBuf::from("hr")),
+                    statuses: Some(vec!["approved".to_owned()]),
+                    ..RuleOverride::default()
+                },
+            ],
+            ..ValidationRules::default()
+        };
+        let report = build_validation_report_in(Path::new("docs"), &[api, handbook], &rules);
+
+        assert_eq!(report.orphan_nodes.len(), 1);
+        assert_eq!(report.orphan_nodes[0].id, "leave");
+        assert!(report.unknown_statuses.is_empty());
+
+        let report = build_validation_report_in(
+            Path::new("docs"),
+            &[entry("x", &[], "docs/x.md")],
+            &ValidationRules {
+                statuses: vec!["draft".to_owned()],
+                ..ValidationRules::default()
+            },
+        );
+        assert!(report.unknown_statuses.is_empty());
+    }
+
+    #[test]
+    fn finds_components_in_chains_deeper_than_the_stack() {
+        const DEPTH: usize = 100_000;
+        let mut adjacency = (0..DEPTH)
+            .map(|node| (node, BTreeSet::from([node + 1])))
+            .collect::<BTreeMap<_, _>>();
+        adjacency.insert(DEPTH, BTreeSet::from([DEPTH / 2]));
+        adjacency.insert(DEPTH + 1, BTreeSet::from([0]));
+
+        let components = strongly_connected_components(&adjacency);
+        assert_eq!(components.len(), DEPTH / 2 + 2);
+        assert_eq!(components[0], (DEPTH / 2..=DEPTH).collect::<Vec<_>>());
+        assert_eq!(components[1], [DEPTH / 2 - 1]);
+        assert_eq!(components.last(), Some(&vec![DEPTH + 1]));
     }
 }
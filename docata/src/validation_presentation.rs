@@ -0,0 +1,918 @@
+use crate::format::OutputFormat;
+use crate::i18n::Locale;
+use crate::json;
+use crate::scan::{ScanDiagnostic, SkippedFile};
+use crate::validate::{
+    BrokenLink, BrokenLinkReason, DependencyCycle, DomainViolation, DuplicateId, LapsedReview,
+    MissingFields, OrphanNode, UnknownStatus, UnresolvedDependency, ValidationReport,
+};
+use crate::xml::escape;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::Write;
+use thiserror::Error;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const TOOL_URI: &str = "https://github.com/pranc1ngpegasus/docata";
+
+const RULE_DUPLICATE_ID: &str = "duplicate-id";
+const RULE_UNRESOLVED_DEPENDENCY: &str = "unresolved-dependency";
+const RULE_DEPENDENCY_CYCLE: &str = "dependency-cycle";
+const RULE_ORPHAN_NODE: &str = "orphan-node";
+const RULE_DOMAIN_VIOLATION: &str = "forbidden-domain-dependency";
+const RULE_BROKEN_LINK: &str = "broken-link";
+const RULE_MISSING_REQUIRED_FIELD: &str = "missing-required-field";
+const RULE_UNKNOWN_STATUS: &str = "unknown-status";
+const RULE_LAPSED_REVIEW: &str = "lapsed-review";
+const RULE_INVALID_DOCUMENT: &str = "invalid-document";
+const RULE_SKIPPED_FILE: &str = "skipped-file";
+
+#[derive(Debug, Serialize)]
+struct DuplicateIdJson<'a> {
+    id: &'a str,
+    paths: &'a [String],
+}
+
+impl<'a> From<&'a DuplicateId> for DuplicateIdJson<'a> {
+    fn from(duplicate: &'a DuplicateId) -> Self {
+        Self {
+            id: &duplicate.id,
+            paths: &duplicate.paths,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UnresolvedDependencyJson<'a> {
+    from_id: &'a str,
+    to_id: &'a str,
+    path: &'a str,
+}
+
+impl<'a> From<&'a UnresolvedDependency> for UnresolvedDependencyJson<'a> {
+    fn from(unresolved: &'a UnresolvedDependency) -> Self {
+        Self {
+            from_id: &unresolved.from_id,
+            to_id: &unresolved.to_id,
+            path: &unresolved.path,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DependencyCycleJson<'a> {
+    ids: &'a [String],
+}
+
+impl<'a> From<&'a DependencyCycle> for DependencyCycleJson<'a> {
+    fn from(cycle: &'a DependencyCycle) -> Self {
+        Self { ids: &cycle.ids }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct OrphanNodeJson<'a> {
+    id: &'a str,
+    path: &'a str,
+}
+
+impl<'a> From<&'a OrphanNode> for OrphanNodeJson<'a> {
+    fn from(orphan: &'a OrphanNode) -> Self {
+        Self {
+            id: &orphan.id,
+            path: &orphan.path,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct DomainViolationJson<'a> {
+    from_id: &'a str,
+    from_domain: &'a str,
+    to_id: &'a str,
+    to_domain: &'a str,
+    path: &'a str,
+}
+
+impl<'a> From<&'a DomainViolation> for DomainViolationJson<'a> {
+    fn from(violation: &'a DomainViolation) -> Self {
+        Self {
+            from_id: &violation.from_id,
+            from_domain: &violation.from_domain,
+            to_id: &violation.to_id,
+            to_domain: &violation.to_domain,
+            path: &violation.path,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct BrokenLinkJson<'a> {
+    id: &'a str,
+    path: &'a str,
+    line: usize,
+    target: &'a str,
+    reason: &'static str,
+}
+
+impl<'a> From<&'a BrokenLink> for BrokenLinkJson<'a> {
+    fn from(link: &'a BrokenLink) -> Self {
+        Self {
+            id: &link.id,
+            path: &link.path,
+            line: link.line,
+            target: &link.target,
+            reason: link.reason.as_str(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct MissingFieldsJson<'a> {
+    id: &'a str,
+    #[serde(rename = "type")]
+    node_type: &'a str,
+    path: &'a str,
+    fields: &'a [String],
+}
+
+impl<'a> From<&'a MissingFields> for MissingFieldsJson<'a> {
+    fn from(missing: &'a MissingFields) -> Self {
+        Self {
+            id: &missing.id,
+            node_type: &missing.node_type,
+            path: &missing.path,
+            fields: &missing.fields,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct UnknownStatusJson<'a> {
+    id: &'a str,
+    path: &'a str,
+    status: &'a str,
+    allowed: &'a [String],
+}
+
+impl<'a> From<&'a UnknownStatus> for UnknownStatusJson<'a> {
+    fn from(unknown: &'a UnknownStatus) -> Self {
+        Self {
+            id: &unknown.id,
+            path: &unknown.path,
+            status: &unknown.status,
+            allowed: &unknown.allowed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct LapsedReviewJson<'a> {
+    id: &'a str,
+    path: &'a str,
+    last_reviewed: &'a str,
+    age_days: u64,
+}
+
+impl<'a> From<&'a LapsedReview> for LapsedReviewJson<'a> {
+    fn from(lapsed: &'a LapsedReview) -> Self {
+        Self {
+            id: &lapsed.id,
+            path: &lapsed.path,
+            last_reviewed: &lapsed.last_reviewed,
+            age_days: lapsed.age_days,
+        }
+    }
+}
+
+/// A file that failed to scan, shared with build reports.
+#[derive(Debug, Serialize)]
+pub(crate) struct ScanDiagnosticJson<'a> {
+    path: String,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+}
+
+impl<'a> From<&'a ScanDiagnostic> for ScanDiagnosticJson<'a> {
+    fn from(diagnostic: &'a ScanDiagnostic) -> Self {
+        Self {
+            path: diagnostic.path.to_string_lossy().to_string(),
+            message: &diagnostic.message,
+            line: diagnostic.line,
+            column: diagnostic.column,
+        }
+    }
+}
+
+/// A file left out of the catalog, shared with build reports.
+#[derive(Debug, Serialize)]
+pub(crate) struct SkippedFileJson {
+    path: String,
+    reason: &'static str,
+}
+
+impl From<&SkippedFile> for SkippedFileJson {
+    fn from(skipped: &SkippedFile) -> Self {
+        Self {
+            path: skipped.path.to_string_lossy().to_string(),
+            reason: skipped.reason.as_str(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ValidationSummaryJson {
+    duplicate_ids: usize,
+    unresolved_dependencies: usize,
+    dependency_cycles: usize,
+    orphan_nodes: usize,
+    domain_violations: usize,
+    broken_links: usize,
+    missing_fields: usize,
+    unknown_statuses: usize,
+    lapsed_reviews: usize,
+    invalid_documents: usize,
+    suppressed: usize,
+}
+
+/// Findings of a validation report, shared by every report shape that embeds
+/// validation results.
+#[derive(Debug, Serialize)]
+pub(crate) struct ValidationFindingsJson<'a> {
+    summary: ValidationSummaryJson,
+    duplicate_ids: Vec<DuplicateIdJson<'a>>,
+    unresolved_dependencies: Vec<UnresolvedDependencyJson<'a>>,
+    dependency_cycles: Vec<DependencyCycleJson<'a>>,
+    orphan_nodes: Vec<OrphanNodeJson<'a>>,
+    domain_violations: Vec<DomainViolationJson<'a>>,
+    broken_links: Vec<BrokenLinkJson<'a>>,
+    missing_fields: Vec<MissingFieldsJson<'a>>,
+    unknown_statuses: Vec<UnknownStatusJson<'a>>,
+    lapsed_reviews: Vec<LapsedReviewJson<'a>>,
+    invalid_documents: Vec<ScanDiagnosticJson<'a>>,
+}
+
+impl<'a> From<&'a ValidationReport> for ValidationFindingsJson<'a> {
+    fn from(report: &'a ValidationReport) -> Self {
+        Self {
+            summary: ValidationSummaryJson {
+                duplicate_ids: report.duplicate_ids.len(),
+                unresolved_dependencies: report.unresolved_dependencies.len(),
+                dependency_cycles: report.dependency_cycles.len(),
+                orphan_nodes: report.orphan_nodes.len(),
+                domain_violations: report.domain_violations.len(),
+                broken_links: report.broken_links.len(),
+                missing_fields: report.missing_fields.len(),
+                unknown_statuses: report.unknown_statuses.len(),
+                lapsed_reviews: report.lapsed_reviews.len(),
+                invalid_documents: report.invalid_documents.len(),
+                suppressed: report.suppressed_count,
+            },
+            duplicate_ids: report.duplicate_ids.iter().map(Into::into).collect(),
+            unresolved_dependencies: report
+                .unresolved_dependencies
+                .iter()
+                .map(Into::into)
+                .collect(),
+            dependency_cycles: report.dependency_cycles.iter().map(Into::into).collect(),
+            orphan_nodes: report.orphan_nodes.iter().map(Into::into).collect(),
+            domain_violations: report.domain_violations.iter().map(Into::into).collect(),
+            broken_links: report.broken_links.iter().map(Into::into).collect(),
+            missing_fields: report.missing_fields.iter().map(Into::into).collect(),
+            unknown_statuses: report.unknown_statuses.iter().map(Into::into).collect(),
+            lapsed_reviews: report.lapsed_reviews.iter().map(Into::into).collect(),
+            invalid_documents: report.invalid_documents.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ValidationReportJson<'a> {
+    command: &'static str,
+    status: &'static str,
+    #[serde(flatten)]
+    findings: ValidationFindingsJson<'a>,
+    skipped: Vec<SkippedFileJson>,
+}
+
+impl<'a> From<&'a ValidationReport> for ValidationReportJson<'a> {
+    fn from(report: &'a ValidationReport) -> Self {
+        Self {
+            command: "check",
+            status: if report.is_empty() {
+                "passed"
+            } else {
+                "failed"
+            },
+            findings: report.into(),
+            skipped: report.skipped.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifRun {
+    tool: SarifTool,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    information_uri: &'static str,
+    version: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: &'static str,
+    short_description: SarifMessage,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: &'static str,
+    level: &'static str,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    region: Option<SarifRegion>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_column: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+impl SarifLocation {
+    fn file(path: &str) -> Self {
+        Self {
+            physical_location: SarifPhysicalLocation {
+                artifact_location: SarifArtifactLocation {
+                    uri: path.trim_start_matches("./").to_owned(),
+                },
+                region: None,
+            },
+        }
+    }
+
+    fn line(
+        path: &str,
+        line: usize,
+    ) -> Self {
+        let mut location = Self::file(path);
+        location.physical_location.region = Some(SarifRegion {
+            start_line: line,
+            start_column: None,
+        });
+        location
+    }
+
+    fn position(
+        path: &str,
+        line: Option<usize>,
+        column: Option<usize>,
+    ) -> Self {
+        let mut location = Self::file(path);
+        location.physical_location.region = line.map(|start_line| SarifRegion {
+            start_line,
+            start_column: column,
+        });
+        location
+    }
+}
+
+impl SarifResult {
+    fn error(
+        rule_id: &'static str,
+        text: String,
+        locations: Vec<SarifLocation>,
+    ) -> Self {
+        Self {
+            rule_id,
+            level: "error",
+            message: SarifMessage { text },
+            locations,
+        }
+    }
+
+    fn warning(
+        rule_id: &'static str,
+        text: String,
+        locations: Vec<SarifLocation>,
+    ) -> Self {
+        Self {
+            level: "warning",
+            ..Self::error(rule_id, text, locations)
+        }
+    }
+}
+
+fn sarif_rule(
+    id: &'static str,
+    description: &str,
+) -> SarifRule {
+    SarifRule {
+        id,
+        short_description: SarifMessage {
+            text: description.to_owned(),
+        },
+    }
+}
+
+fn sarif_rules() -> Vec<SarifRule> {
+    vec![
+        sarif_rule(RULE_DUPLICATE_ID, "Document ids must be unique"),
+        sarif_rule(
+            RULE_UNRESOLVED_DEPENDENCY,
+            "Dependencies must refer to known document ids",
+        ),
+        sarif_rule(RULE_DEPENDENCY_CYCLE, "Dependencies must not form cycles"),
+        sarif_rule(
+            RULE_ORPHAN_NODE,
+            "Documents must be referenced by another document",
+        ),
+        sarif_rule(
+            RULE_DOMAIN_VIOLATION,
+            "Dependencies between domains must be allowed by the domain policy",
+        ),
+        sarif_rule(
+            RULE_BROKEN_LINK,
+            "Relative links must point at catalog documents or existing files",
+        ),
+        sarif_rule(
+            RULE_MISSING_REQUIRED_FIELD,
+            "Documents must set the frontmatter fields their type requires",
+        ),
+        sarif_rule(
+            RULE_UNKNOWN_STATUS,
+            "Document statuses must come from the configured vocabulary",
+        ),
+        sarif_rule(
+            RULE_LAPSED_REVIEW,
+            "Documents must be reviewed within the configured age",
+        ),
+        sarif_rule(
+            RULE_INVALID_DOCUMENT,
+            "Document frontmatter must be readable YAML with an id",
+        ),
+        sarif_rule(
+            RULE_SKIPPED_FILE,
+            "Markdown files should have frontmatter with an id",
+        ),
+    ]
+}
+
+/// Results for findings about the content of a single document.
+fn document_results(report: &ValidationReport) -> Vec<SarifResult> {
+    let mut results = Vec::new();
+
+    for link in &report.broken_links {
+        let problem = match link.reason {
+            BrokenLinkReason::Missing => "does not exist",
+            BrokenLinkReason::NotInCatalog => "is not a catalog document",
+        };
+        results.push(SarifResult::error(
+            RULE_BROKEN_LINK,
+            format!("link target `{}` {problem}", link.target),
+            vec![SarifLocation::line(&link.path, link.line)],
+        ));
+    }
+
+    for missing in &report.missing_fields {
+        results.push(SarifResult::error(
+            RULE_MISSING_REQUIRED_FIELD,
+            format!(
+                "`{}` ({}) is missing required frontmatter field(s): {}",
+                missing.id,
+                missing.node_type,
+                missing.fields.join(", ")
+            ),
+            vec![SarifLocation::file(&missing.path)],
+        ));
+    }
+
+    for unknown in &report.unknown_statuses {
+        results.push(SarifResult::error(
+            RULE_UNKNOWN_STATUS,
+            format!(
+                "`{}` has status `{}`; allowed: {}",
+                unknown.id,
+                unknown.status,
+                unknown.allowed.join(", ")
+            ),
+            vec![SarifLocation::file(&unknown.path)],
+        ));
+    }
+
+    for lapsed in &report.lapsed_reviews {
+        results.push(SarifResult::error(
+            RULE_LAPSED_REVIEW,
+            format!(
+                "`{}` was last reviewed {}, {} days ago",
+                lapsed.id, lapsed.last_reviewed, lapsed.age_days
+            ),
+            vec![SarifLocation::file(&lapsed.path)],
+        ));
+    }
+
+    for invalid in &report.invalid_documents {
+        results.push(SarifResult::error(
+            RULE_INVALID_DOCUMENT,
+            invalid.message.clone(),
+            vec![SarifLocation::position(
+                &invalid.path.to_string_lossy(),
+                invalid.line,
+                invalid.column,
+            )],
+        ));
+    }
+
+    for skipped in &report.skipped {
+        results.push(SarifResult::warning(
+            RULE_SKIPPED_FILE,
+            format!("skipped: {}", skipped.reason),
+            vec![SarifLocation::file(&skipped.path.to_string_lossy())],
+        ));
+    }
+
+    results
+}
+
+impl From<&ValidationReport> for SarifLog {
+    fn from(report: &ValidationReport) -> Self {
+        let mut results = Vec::new();
+
+        for duplicate in &report.duplicate_ids {
+            for path in &duplicate.paths {
+                results.push(SarifResult::error(
+                    RULE_DUPLICATE_ID,
+                    format!(
+                        "id `{}` is declared by {} documents",
+                        duplicate.id,
+                        duplicate.paths.len()
+                    ),
+                    vec![SarifLocation::file(path)],
+                ));
+            }
+        }
+
+        for unresolved in &report.unresolved_dependencies {
+            results.push(SarifResult::error(
+                RULE_UNRESOLVED_DEPENDENCY,
+                format!(
+                    "`{}` depends on unknown id `{}`",
+                    unresolved.from_id, unresolved.to_id
+                ),
+                vec![SarifLocation::file(&unresolved.path)],
+            ));
+        }
+
+        for cycle in &report.dependency_cycles {
+            let mut path = cycle.ids.join(" -> ");
+            if let Some(first) = cycle.ids.first() {
+                path.push_str(" -> ");
+                path.push_str(first);
+            }
+            results.push(SarifResult::error(
+                RULE_DEPENDENCY_CYCLE,
+                format!("dependency cycle: {path}"),
+                Vec::new(),
+            ));
+        }
+
+        for orphan in &report.orphan_nodes {
+            results.push(SarifResult::error(
+                RULE_ORPHAN_NODE,
+                format!("no document depends on `{}`", orphan.id),
+                vec![SarifLocation::file(&orphan.path)],
+            ));
+        }
+
+        for violation in &report.domain_violations {
+            results.push(SarifResult::error(
+                RULE_DOMAIN_VIOLATION,
+                format!(
+                    "`{}` ({}) must not depend on `{}` ({})",
+                    violation.from_id, violation.from_domain, violation.to_id, violation.to_domain
+                ),
+                vec![SarifLocation::file(&violation.path)],
+            ));
+        }
+
+        results.extend(document_results(report));
+
+        Self {
+            schema: SARIF_SCHEMA,
+            version: SARIF_VERSION,
+            runs: vec![SarifRun {
+                tool: SarifTool {
+                    driver: SarifDriver {
+                        name: "docata",
+                        information_uri: TOOL_URI,
+                        version: env!("CARGO_PKG_VERSION"),
+                        rules: sarif_rules(),
+                    },
+                },
+                results,
+            }],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ValidationPresentationError {
+    #[error("io error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("json encoding error: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("yaml encoding error: {0}")]
+    Yaml(#[from] yaml_serde::Error),
+    #[error("unsupported output format: {0}")]
+    UnsupportedFormat(OutputFormat),
+}
+
+/// Write a validation report according to the selected output format.
+///
+/// Passing reports are written too, so every format has a success payload.
+/// Text output uses the labels of `locale`; structured formats are not
+/// translated.
+///
+/// # Errors
+///
+/// Returns `ValidationPresentationError` if serialization or writing fails.
+pub fn write<W: Write>(
+    report: &ValidationReport,
+    format: OutputFormat,
+    locale: Locale,
+    out: &mut W,
+) -> Result<(), ValidationPresentationError> {
+    match format {
+        OutputFormat::Text => write_text(report, locale, out),
+        OutputFormat::Json | OutputFormat::CompactJson => write_json(report, format, out),
+        OutputFormat::Yaml => write_yaml(report, out),
+        OutputFormat::Sarif => write_sarif(report, out),
+        OutputFormat::Junit => write_junit(report, out),
+        OutputFormat::Ndjson | OutputFormat::Dot => {
+            Err(ValidationPresentationError::UnsupportedFormat(format))
+        },
+    }
+}
+
+fn write_text<W: Write>(
+    report: &ValidationReport,
+    locale: Locale,
+    out: &mut W,
+) -> Result<(), ValidationPresentationError> {
+    let labels = locale.labels();
+    if report.is_empty() {
+        writeln!(out, "{}", labels.validation_passed)?;
+        if report.suppressed_count > 0 {
+            writeln!(out, "{}", locale.suppressed(report.suppressed_count))?;
+        }
+    } else {
+        let mut text = String::new();
+        report
+            .write_localized(locale, &mut text)
+            .map_err(|_| std::io::Error::other("failed to format validation report"))?;
+        write!(out, "{text}")?;
+    }
+    for skipped in &report.skipped {
+        writeln!(
+            out,
+            "{}: {}",
+            labels.warning,
+            locale.skipped(&skipped.path.to_string_lossy(), skipped.reason)
+        )?;
+    }
+
+    Ok(())
+}
+
+fn write_yaml<W: Write>(
+    report: &ValidationReport,
+    out: &mut W,
+) -> Result<(), ValidationPresentationError> {
+    yaml_serde::to_writer(out, &ValidationReportJson::from(report))?;
+    Ok(())
+}
+
+fn write_json<W: Write>(
+    report: &ValidationReport,
+    format: OutputFormat,
+    out: &mut W,
+) -> Result<(), ValidationPresentationError> {
+    json::write(out, &ValidationReportJson::from(report), format)?;
+    Ok(())
+}
+
+fn write_sarif<W: Write>(
+    report: &ValidationReport,
+    out: &mut W,
+) -> Result<(), ValidationPresentationError> {
+    json::write(out, &SarifLog::from(report), OutputFormat::Json)?;
+    Ok(())
+}
+
+/// Write findings as `JUnit` XML: a test suite per rule and, within it, a
+/// failing test case per file with findings, each finding a line of the
+/// failure. A rule without findings gets a single passing test case.
+fn write_junit<W: Write>(
+    report: &ValidationReport,
+    out: &mut W,
+) -> Result<(), ValidationPresentationError> {
+    let log = SarifLog::from(report);
+    let results = log.runs.iter().flat_map(|run| &run.results);
+    // Findings without a location, such as cycles, concern the whole catalog.
+    let mut by_rule = BTreeMap::<&str, BTreeMap<&str, Vec<&SarifResult>>>::new();
+    for result in results {
+        let file = result.locations.first().map_or("(catalog)", |location| {
+            location.physical_location.artifact_location.uri.as_str()
+        });
+        by_rule
+            .entry(result.rule_id)
+            .or_default()
+            .entry(file)
+            .or_default()
+            .push(result);
+    }
+    let rules = sarif_rules();
+    let tests = rules
+        .iter()
+        .map(|rule| by_rule.get(rule.id).map_or(1, BTreeMap::len))
+        .sum::<usize>();
+    let failures = by_rule.values().map(BTreeMap::len).sum::<usize>();
+
+    writeln!(out, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        out,
+        r#"<testsuites name="docata" tests="{tests}" failures="{failures}">"#
+    )?;
+    for rule in &rules {
+        let description = escape(&rule.short_description.text);
+        let Some(files) = by_rule.get(rule.id) else {
+            writeln!(
+                out,
+                r#"  <testsuite name="{}" tests="1" failures="0">"#,
+                rule.id
+            )?;
+            writeln!(
+                out,
+                r#"    <testcase classname="{}" name="{description}"/>"#,
+                rule.id
+            )?;
+            writeln!(out, "  </testsuite>")?;
+            continue;
+        };
+        writeln!(
+            out,
+            r#"  <testsuite name="{}" tests="{count}" failures="{count}">"#,
+            rule.id,
+            count = files.len()
+        )?;
+        for (file, results) in files {
+            writeln!(
+                out,
+                r#"    <testcase classname="{}" name="{}">"#,
+                rule.id,
+                escape(file)
+            )?;
+            let lines = results
+                .iter()
+                .map(|result| {
+                    let line = result
+                        .locations
+                        .first()
+                        .and_then(|location| location.physical_location.region.as_ref())
+                        .map(|region| format!("{file}:{}: ", region.start_line));
+                    format!("{}{}", line.unwrap_or_default(), result.message.text)
+                })
+                .collect::<Vec<_>>();
+            writeln!(
+                out,
+                r#"      <failure type="{}" message="{}">{}</failure>"#,
+                results[0].level,
+                escape(&results[0].message.text),
+                escape(&lines.join("\n"))
+            )?;
+            writeln!(out, "    </testcase>")?;
+        }
+        writeln!(out, "  </testsuite>")?;
+    }
+    writeln!(out, "</testsuites>")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::format::OutputFormat;
+    use crate::i18n::Locale;
+    use crate::validate::{UnresolvedDependency, ValidationReport};
+
+    fn render(
+        report: &ValidationReport,
+        format: OutputFormat,
+    ) -> String {
+        let mut output = Vec::new();
+        write(report, format, Locale::En, &mut output).expect("write report");
+        String::from_utf8(output).expect("valid utf-8")
+    }
+
+    #[test]
+    fn writes_success_payload_for_every_format() {
+        let report = ValidationReport::default();
+
+        assert_eq!(render(&report, OutputFormat::Text), "validation passed\n");
+        assert!(render(&report, OutputFormat::Json).contains("\"status\": \"passed\""));
+        assert!(render(&report, OutputFormat::Sarif).contains("\"results\": []"));
+    }
+
+    #[test]
+    fn writes_sarif_result_with_location() {
+        let report = ValidationReport {
+            unresolved_dependencies: vec![UnresolvedDependency {
+                from_id: "foo".to_owned(),
+                to_id: "missing".to_owned(),
+                path: "./docs/foo.md".to_owned(),
+            }],
+            ..ValidationReport::default()
+        };
+
+        let sarif = render(&report, OutputFormat::Sarif);
+        assert!(sarif.contains("\"ruleId\": \"unresolved-dependency\""));
+        assert!(sarif.contains("\"uri\": \"docs/foo.md\""));
+        assert!(render(&report, OutputFormat::Json).contains("\"status\": \"failed\""));
+    }
+
+    #[test]
+    fn writes_junit_test_case_per_rule_and_file() {
+        let unresolved = |to_id: &str| UnresolvedDependency {
+            from_id: "foo".to_owned(),
+            to_id: to_id.to_owned(),
+            path: "./docs/foo.md".to_owned(),
+        };
+        let report = ValidationReport {
+            unresolved_dependencies: vec![unresolved("a"), unresolved("b")],
+            ..ValidationReport::default()
+        };
+
+        let junit = render(&report, OutputFormat::Junit);
+
+        assert!(
+            junit.contains(r#"<testsuite name="unresolved-dependency" tests="1" failures="1">"#)
+        );
+        assert!(
+            junit.contains(r#"<testcase classname="unresolved-dependency" name="docs/foo.md">"#)
+        );
+        assert!(junit.contains("`foo` depends on unknown id `a`\n`foo` depends on unknown id `b`"));
+        assert!(junit.contains(r#"<testsuite name="duplicate-id" tests="1" failures="0">"#));
+        assert_eq!(junit.matches("<failure ").count(), 1);
+    }
+}
@@ -0,0 +1,87 @@
+//! Time and memory budgets for building and querying a generated corpus of
+//! 10k documents, to fail CI on a regression the benchmarks would only
+//! report. Budgets hold for release builds:
+//! `cargo test -p docata --release --features testing --test budgets`.
+
+use docata::testing::{CorpusOptions, TestWorkspace, generate_corpus};
+use docata::{
+    BuildOptions, OutputFormat, QueryOptions, RelationKind, build_catalog_into,
+    query_catalog_relation_with_options,
+};
+use std::fs;
+use std::io;
+use std::time::{Duration, Instant};
+
+const DOCUMENTS: usize = 10_000;
+const BUILD_TIME: Duration = Duration::from_secs(5);
+const QUERY_TIME: Duration = Duration::from_millis(250);
+/// Peak resident memory while building, in KiB.
+const BUILD_MEMORY: u64 = 128 * 1024;
+
+/// Peak resident memory of this process in KiB, or `None` off Linux.
+fn peak_memory() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+/// Restart [`peak_memory`] from the current resident memory; `false` off
+/// Linux.
+fn reset_peak_memory() -> bool {
+    fs::write("/proc/self/clear_refs", "5").is_ok()
+}
+
+#[test]
+fn builds_and_queries_ten_thousand_documents_within_budget() {
+    if cfg!(debug_assertions) {
+        eprintln!("budgets only hold for release builds; skipped");
+        return;
+    }
+    let workspace = TestWorkspace::new();
+    generate_corpus(0, &CorpusOptions::realistic(DOCUMENTS)).write(workspace.path());
+    let catalog = workspace.path().join("catalog.json");
+
+    let measured = reset_peak_memory();
+    let started = Instant::now();
+    build_catalog_into(workspace.path(), &catalog, BuildOptions::new()).expect("build catalog");
+    let build_time = started.elapsed();
+    assert!(
+        build_time < BUILD_TIME,
+        "build took {build_time:?}, over {BUILD_TIME:?}"
+    );
+    if measured && let Some(peak) = peak_memory() {
+        assert!(
+            peak < BUILD_MEMORY,
+            "build peaked at {peak} KiB, over {BUILD_MEMORY} KiB"
+        );
+    }
+
+    let last = format!("doc-{:04}", DOCUMENTS - 1);
+    for (relation_kind, id, transitive) in [
+        (RelationKind::Deps, last.as_str(), true),
+        (RelationKind::Refs, "doc-0000", false),
+    ] {
+        let started = Instant::now();
+        query_catalog_relation_with_options(
+            id,
+            &catalog,
+            relation_kind,
+            OutputFormat::Json,
+            &QueryOptions::new().transitive(transitive),
+            &mut io::sink(),
+        )
+        .expect("query catalog");
+        let query_time = started.elapsed();
+        assert!(
+            query_time < QUERY_TIME,
+            "{} {id} took {query_time:?}, over {QUERY_TIME:?}",
+            relation_kind.as_str()
+        );
+    }
+}